@@ -0,0 +1,98 @@
+/*
+ * C ABI for hosting frui user interfaces in other applications.
+ *
+ * The engine is created by a function exported by the application crate,
+ * which knows the root widget. See `frui_core::app::embed::ffi`.
+ *
+ * All functions have to be called on the thread the engine was created on.
+ */
+
+#ifndef FRUI_H
+#define FRUI_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct FruiEngine FruiEngine;
+
+#define FRUI_POINTER_DOWN 0
+#define FRUI_POINTER_UP 1
+#define FRUI_POINTER_MOVE 2
+#define FRUI_POINTER_WHEEL 3
+
+#define FRUI_BUTTON_LEFT 1
+#define FRUI_BUTTON_RIGHT 2
+#define FRUI_BUTTON_MIDDLE 4
+
+#define FRUI_MOD_SHIFT 1
+#define FRUI_MOD_CTRL 2
+#define FRUI_MOD_ALT 4
+#define FRUI_MOD_META 8
+
+typedef struct FruiPointerEvent {
+    /* One of FRUI_POINTER_*. */
+    uint32_t kind;
+    /* Position in display points. */
+    double x;
+    double y;
+    /* Button which was pressed or released, one of FRUI_BUTTON_*. */
+    uint32_t button;
+    /* All pressed buttons, made of FRUI_BUTTON_* flags. */
+    uint32_t buttons;
+    double wheel_x;
+    double wheel_y;
+    /* Made of FRUI_MOD_* flags. */
+    uint32_t mods;
+} FruiPointerEvent;
+
+/* Premultiplied RGBA pixels, valid until the next frame is rendered or the
+ * engine is destroyed. */
+typedef struct FruiFrame {
+    size_t width;
+    size_t height;
+    /* Length of a row in bytes. */
+    size_t stride;
+    const uint8_t *pixels;
+} FruiFrame;
+
+void frui_engine_destroy(FruiEngine *engine);
+
+/* Called with `user_data` when the engine needs a new frame. The callback
+ * should only schedule rendering. */
+void frui_engine_set_wake_callback(FruiEngine *engine,
+                                   void (*callback)(void *user_data),
+                                   void *user_data);
+
+/* Size in display points, scale in pixels per point. */
+void frui_engine_resize(FruiEngine *engine, double width, double height, double scale);
+
+void frui_engine_pointer_event(FruiEngine *engine, FruiPointerEvent event);
+
+/* `key` is a W3C key value, e.g. "a" or "Enter". Returns false if a key press
+ * wasn't handled and should be turned into text input. */
+bool frui_engine_key_event(FruiEngine *engine, bool down, const char *key, uint32_t mods,
+                           bool repeat);
+
+/* Replaces the selection of the focused text field. Returns false if no text
+ * field is focused. */
+bool frui_engine_insert_text(FruiEngine *engine, const char *text);
+
+void frui_engine_set_focused(FruiEngine *engine, bool focused);
+
+/* Fires due timers and delivers posted messages. Should be called on every
+ * display refresh. Returns whether a new frame should be rendered. */
+bool frui_engine_pump(FruiEngine *engine);
+
+/* Returns false if rendering failed. */
+bool frui_engine_render(FruiEngine *engine, FruiFrame *frame);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FRUI_H */
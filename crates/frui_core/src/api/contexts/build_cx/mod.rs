@@ -4,10 +4,15 @@ use std::{
     marker::PhantomData,
     ops::{Deref, DerefMut},
     sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 
 use crate::{
-    app::tree::{Node, NodeRef},
+    api::implementers::inherited::{InheritedValueOS, ValueKey},
+    app::{
+        timer::Timer,
+        tree::{Node, NodeRef, WeakNodeRef},
+    },
     prelude::InheritedWidget,
 };
 
@@ -73,6 +78,67 @@ impl<'a, T> _BuildCx<'a, T> {
         })
     }
 
+    /// Registers the widget of this [`BuildCx`] as a dependency of the closest
+    /// ancestor providing a value of type `V` (see
+    /// [`InheritedValue`](crate::prelude::InheritedValue)), so it
+    /// is rebuilt once that value changes. Returns [`None`] if there is no
+    /// such ancestor.
+    pub fn depend_on<V: 'static>(&self) -> Option<InheritedValueRef<V>> {
+        let node = self
+            .node_ref()
+            .depend_on_inherited_widget_of_key::<ValueKey<V>>()?;
+
+        Some(InheritedValueRef {
+            node,
+            _p: PhantomData,
+        })
+    }
+
+    /// Calls `callback` with the state of this widget once, after `duration`
+    /// elapses, and rebuilds this widget. The timer is cancelled when this
+    /// widget is removed from the tree.
+    pub fn timer_once<F>(&self, duration: Duration, callback: F) -> Timer
+    where
+        T: WidgetState,
+        F: FnOnce(&mut T::State) + 'static,
+    {
+        let node = self.node_ref().downgrade();
+        let mut callback = Some(callback);
+
+        Timer::schedule(duration, None, Some(node.clone()), move || {
+            if let Some(callback) = callback.take() {
+                update_state(&node, callback);
+            }
+        })
+    }
+
+    /// Calls `callback` with the state of this widget every `period` and
+    /// rebuilds this widget. The timer is cancelled when this widget is removed
+    /// from the tree.
+    pub fn timer_periodic<F>(&self, period: Duration, mut callback: F) -> Timer
+    where
+        T: WidgetState,
+        F: FnMut(&mut T::State) + 'static,
+    {
+        let node = self.node_ref().downgrade();
+
+        Timer::schedule(period, Some(period), Some(node.clone()), move || {
+            update_state(&node, &mut callback);
+        })
+    }
+
+    /// Returns a handle which can be used to update the state of this widget
+    /// from outside of the widget tree (e.g. from a listener of a service).
+    pub fn state_updater(&self) -> StateUpdater<T::State>
+    where
+        T: WidgetState,
+    {
+        StateUpdater {
+            node: self.node_ref().downgrade(),
+            _p: PhantomData,
+        }
+    }
+
     fn node_ref(&self) -> NodeRef {
         NodeRef {
             ptr: self.node.inner.borrow().is_alive.clone(),
@@ -80,6 +146,45 @@ impl<'a, T> _BuildCx<'a, T> {
     }
 }
 
+fn update_state<S: 'static>(node: &WeakNodeRef, callback: impl FnOnce(&mut S)) {
+    if let Some(node) = node.upgrade() {
+        callback(node.borrow_mut().state.deref_mut().downcast_mut().unwrap());
+        node.mark_dirty();
+
+        if node.is_inheritor() {
+            node.mark_dependent_widgets_as_dirty();
+        }
+    }
+}
+
+/// Handle to the state of a widget. See [`_BuildCx::state_updater`].
+pub struct StateUpdater<S: 'static> {
+    node: WeakNodeRef,
+    _p: PhantomData<S>,
+}
+
+impl<S: 'static> StateUpdater<S> {
+    /// Calls `callback` with the state and rebuilds the widget. Does nothing if
+    /// the widget was removed from the tree.
+    pub fn update(&self, callback: impl FnOnce(&mut S)) {
+        update_state(&self.node, callback);
+    }
+
+    /// Whether the widget is still in the tree.
+    pub fn is_alive(&self) -> bool {
+        self.node.is_alive()
+    }
+}
+
+impl<S: 'static> Clone for StateUpdater<S> {
+    fn clone(&self) -> Self {
+        StateUpdater {
+            node: self.node.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
 pub struct StateGuard<'a, T: 'static> {
     pub(crate) guard: Ref<'a, dyn Any>,
     pub(crate) _p: PhantomData<&'a T>,
@@ -169,3 +274,22 @@ impl<'a, T> DerefMut for InheritedStateRefMut<'a, T> {
         self.state.downcast_mut().unwrap()
     }
 }
+
+/// Value provided by an [`InheritedValue`](crate::prelude::InheritedValue)
+/// ancestor, returned by
+/// [`_BuildCx::depend_on`].
+pub struct InheritedValueRef<'a, V: 'static> {
+    pub(crate) node: NodeRef,
+    pub(crate) _p: PhantomData<&'a V>,
+}
+
+impl<'a, V: 'static> Deref for InheritedValueRef<'a, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        // Read from the current configuration of the ancestor, which may have
+        // changed since this reference was created.
+        let (_, value) = self.node.widget().inherited_value().unwrap();
+        value.downcast_ref().unwrap()
+    }
+}
@@ -0,0 +1,48 @@
+use frui_macros::sealed;
+
+use super::{LayoutCx, LayoutCxOS, RenderOSExt};
+
+/// Baseline of text painted by a render widget, which parents (e.g. rows
+/// aligning children of different fonts) can query once the widget is laid
+/// out.
+///
+/// Widgets not implementing this trait report the baseline of their child
+/// if they have exactly one, shifted by the [`RenderOSExt::offset`] of that
+/// child, and no baseline otherwise. Baselines of children can be queried
+/// with [`LayoutCxOS::distance_to_baseline`].
+pub trait Baseline: Sized {
+    /// Distance from the top of the widget to the baseline of its first line
+    /// of text, or `None` if it doesn't paint text.
+    fn distance_to_baseline(&self, cx: &LayoutCx<Self>) -> Option<f64> {
+        single_child(cx)
+    }
+}
+
+#[sealed(crate)]
+pub trait BaselineOS {
+    fn distance_to_baseline_os(&self, cx: LayoutCxOS) -> Option<f64>;
+}
+
+impl<T> BaselineOS for T {
+    default fn distance_to_baseline_os(&self, cx: LayoutCxOS) -> Option<f64> {
+        single_child(&cx)
+    }
+}
+
+impl<T: Baseline> BaselineOS for T {
+    fn distance_to_baseline_os(&self, cx: LayoutCxOS) -> Option<f64> {
+        T::distance_to_baseline(self, &LayoutCx::new(cx))
+    }
+}
+
+fn single_child(cx: &LayoutCxOS) -> Option<f64> {
+    let mut children = cx.children();
+
+    match children.len() {
+        1 => {
+            let child = children.next().unwrap();
+            Some(child.distance_to_baseline()? + child.offset().y)
+        }
+        _ => None,
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
     prelude::{Widget, WidgetState},
 };
 
-use super::{RenderState, Size};
+use super::{BoxLayoutData, Offset, RenderState, Size};
 
 pub trait RenderExt<W: Widget> {
     #[doc(hidden)]
@@ -68,6 +68,13 @@ pub trait RenderOSExt {
         self.node().borrow().render_data.size
     }
 
+    /// Offset from the parent stored in [`BoxLayoutData`] of this widget, or
+    /// zero if it has parent data of another type.
+    fn offset(&self) -> Offset {
+        self.try_parent_data::<BoxLayoutData>()
+            .map_or(Offset::default(), |data| data.offset)
+    }
+
     fn set_parent_data<T: 'static>(&self, data: T) {
         self.node().borrow_mut().render_data.parent_data = Box::new(data);
     }
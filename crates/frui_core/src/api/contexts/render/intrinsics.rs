@@ -0,0 +1,89 @@
+use frui_macros::sealed;
+
+use super::{LayoutCx, LayoutCxOS};
+
+/// Intrinsic dimensions of a render widget, which parents (e.g. ones sizing
+/// children to the widest of them) can query without laying it out.
+///
+/// Widgets not implementing this trait report the intrinsic dimensions of
+/// their child if they have exactly one, and zero otherwise. Intrinsic
+/// dimensions of children can be queried with the methods of the same name
+/// on [`LayoutCxOS`].
+pub trait Intrinsics: Sized {
+    /// Smallest width at which the widget paints its content correctly, if
+    /// given `height`.
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        single_child(cx, |child| child.min_intrinsic_width(height))
+    }
+
+    /// Width beyond which making the widget wider wouldn't make it shorter,
+    /// if given `height`.
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        single_child(cx, |child| child.max_intrinsic_width(height))
+    }
+
+    /// Smallest height at which the widget paints its content correctly, if
+    /// given `width`.
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        single_child(cx, |child| child.min_intrinsic_height(width))
+    }
+
+    /// Height beyond which making the widget taller wouldn't make it
+    /// narrower, if given `width`.
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        single_child(cx, |child| child.max_intrinsic_height(width))
+    }
+}
+
+#[sealed(crate)]
+pub trait IntrinsicsOS {
+    fn min_intrinsic_width_os(&self, cx: LayoutCxOS, height: f64) -> f64;
+    fn max_intrinsic_width_os(&self, cx: LayoutCxOS, height: f64) -> f64;
+    fn min_intrinsic_height_os(&self, cx: LayoutCxOS, width: f64) -> f64;
+    fn max_intrinsic_height_os(&self, cx: LayoutCxOS, width: f64) -> f64;
+}
+
+impl<T> IntrinsicsOS for T {
+    default fn min_intrinsic_width_os(&self, cx: LayoutCxOS, height: f64) -> f64 {
+        single_child(&cx, |child| child.min_intrinsic_width(height))
+    }
+
+    default fn max_intrinsic_width_os(&self, cx: LayoutCxOS, height: f64) -> f64 {
+        single_child(&cx, |child| child.max_intrinsic_width(height))
+    }
+
+    default fn min_intrinsic_height_os(&self, cx: LayoutCxOS, width: f64) -> f64 {
+        single_child(&cx, |child| child.min_intrinsic_height(width))
+    }
+
+    default fn max_intrinsic_height_os(&self, cx: LayoutCxOS, width: f64) -> f64 {
+        single_child(&cx, |child| child.max_intrinsic_height(width))
+    }
+}
+
+impl<T: Intrinsics> IntrinsicsOS for T {
+    fn min_intrinsic_width_os(&self, cx: LayoutCxOS, height: f64) -> f64 {
+        T::min_intrinsic_width(self, &LayoutCx::new(cx), height)
+    }
+
+    fn max_intrinsic_width_os(&self, cx: LayoutCxOS, height: f64) -> f64 {
+        T::max_intrinsic_width(self, &LayoutCx::new(cx), height)
+    }
+
+    fn min_intrinsic_height_os(&self, cx: LayoutCxOS, width: f64) -> f64 {
+        T::min_intrinsic_height(self, &LayoutCx::new(cx), width)
+    }
+
+    fn max_intrinsic_height_os(&self, cx: LayoutCxOS, width: f64) -> f64 {
+        T::max_intrinsic_height(self, &LayoutCx::new(cx), width)
+    }
+}
+
+fn single_child(cx: &LayoutCxOS, f: impl FnOnce(LayoutCxOS) -> f64) -> f64 {
+    let mut children = cx.children();
+
+    match children.len() {
+        1 => f(children.next().unwrap()),
+        _ => 0.,
+    }
+}
@@ -1,11 +1,15 @@
+mod baseline;
 mod ext;
+mod intrinsics;
 mod paint_cx;
 mod parent_data;
 mod render_cx;
 mod render_state;
 mod types;
 
+pub use baseline::*;
 pub use ext::*;
+pub use intrinsics::*;
 pub use paint_cx::*;
 pub use parent_data::*;
 pub use render_cx::*;
@@ -1,12 +1,53 @@
-use std::marker::PhantomData;
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+};
+
+use druid_shell::{
+    kurbo::{Affine, Point, Rect, Size, Vec2},
+    piet::RenderContext,
+};
+
+#[cfg(not(feature = "miri"))]
+use druid_shell::piet::{Device, Error as PietError, ImageBuf, ImageFormat, InterpolationMode};
 
+#[cfg(not(feature = "miri"))]
+use crate::app::metrics::WindowMetrics;
 use crate::{
-    app::{runner::Canvas, tree::NodeRef},
+    app::{instrumentation::Instrumentation, runner::Canvas, tree::NodeRef},
     prelude::Widget,
 };
 
 use super::{ext::RenderExt, Offset, RenderOSExt};
 
+thread_local! {
+    /// Stack of clips (in window coordinates) applied with
+    /// [`PaintCx::with_clip`]. Children completely outside of the innermost
+    /// clip aren't painted.
+    static PAINT_CLIP: RefCell<Vec<Rect>> = Default::default();
+
+    /// Position (in window coordinates) of the layer currently painted into,
+    /// see [`PaintCx::with_opacity`].
+    static LAYER_ORIGIN: Cell<Vec2> = Cell::new(Vec2::ZERO);
+
+    /// Device creating layers, reused between frames. Taken while a layer is
+    /// painted.
+    #[cfg(not(feature = "miri"))]
+    static LAYER_DEVICE: Cell<Option<Device>> = Cell::new(None);
+}
+
+/// Sets the area of the window being painted, replacing all clips.
+pub(crate) fn reset_paint_clip(window_area: Rect) {
+    PAINT_CLIP.with(|c| *c.borrow_mut() = vec![window_area]);
+    LAYER_ORIGIN.with(|o| o.set(Vec2::ZERO));
+}
+
+/// Transformation from current coordinates of `piet` to window coordinates,
+/// which differ from the ones of `piet` while painting into a layer.
+pub fn window_transform(piet: &Canvas) -> Affine {
+    Affine::translate(LAYER_ORIGIN.with(|o| o.get())) * piet.current_transform()
+}
+
 pub struct PaintCx<T> {
     cx: PaintCxOS,
     _p: PhantomData<T>,
@@ -19,6 +60,221 @@ impl<T> PaintCx<T> {
             _p: PhantomData,
         }
     }
+
+    /// Transformation from current coordinates of `piet` (in which the offset
+    /// passed to `paint` is given) to window coordinates, e.g. for reporting
+    /// positions to the platform.
+    pub fn window_transform(&self, piet: &Canvas) -> Affine {
+        window_transform(piet)
+    }
+
+    /// Clips painting done in `f` to `rect`. Children painted in `f` which
+    /// are completely outside of `rect` are skipped.
+    pub fn with_clip<R>(
+        &mut self,
+        piet: &mut Canvas,
+        rect: impl Into<Rect>,
+        f: impl FnOnce(&mut Self, &mut Canvas) -> R,
+    ) -> R {
+        let rect = rect.into();
+
+        if let Err(e) = piet.save() {
+            log::error!("saving render context failed: {:?}", e);
+        }
+
+        piet.clip(rect);
+
+        let window_rect = window_transform(piet).transform_rect_bbox(rect);
+        PAINT_CLIP.with(|c| {
+            let mut clips = c.borrow_mut();
+            let clip = match clips.last() {
+                Some(outer) => outer.intersect(window_rect),
+                None => window_rect,
+            };
+            clips.push(clip);
+        });
+
+        let r = f(self, piet);
+
+        PAINT_CLIP.with(|c| c.borrow_mut().pop());
+
+        if let Err(e) = piet.restore() {
+            log::error!("restoring render context failed: {:?}", e);
+        }
+
+        r
+    }
+
+    /// Paints whatever is painted in `f` with the given `opacity` (between 0
+    /// and 1), as if it was painted into a separate image which was then
+    /// blended into the canvas.
+    ///
+    /// Unless `opacity` is 0 or 1, painting is done into an offscreen layer
+    /// of the size of `bounds` (in current coordinates), so anything painted
+    /// outside of them is cut off. Layers cost an extra image per frame, so
+    /// they should be limited to small or short-lived parts of the window
+    /// (e.g. fading transitions).
+    ///
+    /// If the layer can't be created, painting is done at full opacity.
+    pub fn with_opacity(
+        &mut self,
+        piet: &mut Canvas,
+        opacity: f64,
+        bounds: impl Into<Rect>,
+        f: impl FnOnce(&mut Self, &mut Canvas),
+    ) {
+        if opacity <= 0. {
+            return;
+        }
+
+        let mut f = Some(f);
+
+        #[cfg(not(feature = "miri"))]
+        if opacity < 1. {
+            if let Err(e) = self.paint_layer(piet, opacity, bounds.into(), &mut f) {
+                log::error!("painting layer failed: {:?}", e);
+            }
+        }
+
+        #[cfg(feature = "miri")]
+        let _ = bounds;
+
+        // Painting wasn't done in a layer.
+        if let Some(f) = f {
+            f(self, piet);
+        }
+    }
+
+    /// Paints `f` into an image of `rect` (in current coordinates) at the
+    /// scale of the window, e.g. to draw it again on later frames without
+    /// painting `f`. Painting of `f` isn't limited by outer clips.
+    ///
+    /// The current transform is assumed to be a translation: positions
+    /// reported in `f` to the platform are approximate otherwise.
+    #[cfg(not(feature = "miri"))]
+    pub fn paint_to_image(
+        &mut self,
+        piet: &Canvas,
+        rect: Rect,
+        f: impl FnOnce(&mut Self, &mut Canvas),
+    ) -> Result<ImageBuf, PietError> {
+        let scale = WindowMetrics::current().scale;
+        let width = (rect.width() * scale).ceil() as usize;
+        let height = (rect.height() * scale).ceil() as usize;
+
+        if width == 0 || height == 0 {
+            return Ok(ImageBuf::empty());
+        }
+
+        let window_rect = window_transform(piet).transform_rect_bbox(rect);
+
+        with_layer_device(|device| {
+            let mut target = device.bitmap_target(width, height, scale)?;
+
+            {
+                let mut canvas = target.render_context();
+                canvas.transform(Affine::translate(-rect.origin().to_vec2()));
+
+                let origin = window_rect.origin() - rect.origin();
+                let outer_origin = LAYER_ORIGIN.with(|o| o.replace(origin));
+                PAINT_CLIP.with(|c| c.borrow_mut().push(window_rect));
+
+                f(self, &mut canvas);
+
+                PAINT_CLIP.with(|c| c.borrow_mut().pop());
+                LAYER_ORIGIN.with(|o| o.set(outer_origin));
+
+                canvas.finish()?;
+            }
+
+            target.to_image_buf(ImageFormat::RgbaPremul)
+        })
+    }
+
+    /// Paints `f` into a bitmap aligned with device pixels, which is then
+    /// drawn with its alpha multiplied by `opacity`. Takes `f` once the
+    /// layer was created.
+    #[cfg(not(feature = "miri"))]
+    fn paint_layer<F: FnOnce(&mut Self, &mut Canvas)>(
+        &mut self,
+        piet: &mut Canvas,
+        opacity: f64,
+        bounds: Rect,
+        f: &mut Option<F>,
+    ) -> Result<(), PietError> {
+        let scale = WindowMetrics::current().scale;
+        let transform = window_transform(piet);
+
+        let mut window_bounds = transform.transform_rect_bbox(bounds);
+        if let Some(clip) = PAINT_CLIP.with(|c| c.borrow().last().copied()) {
+            window_bounds = window_bounds.intersect(clip);
+        }
+
+        let window_bounds = window_bounds
+            .scale_from_origin(scale)
+            .expand()
+            .scale_from_origin(1. / scale);
+
+        let width = (window_bounds.width() * scale).round() as usize;
+        let height = (window_bounds.height() * scale).round() as usize;
+
+        if width == 0 || height == 0 {
+            // Nothing painted would be visible.
+            f.take();
+            return Ok(());
+        }
+
+        let origin = window_bounds.origin().to_vec2();
+
+        let layer = with_layer_device(|device| {
+            let mut target = device.bitmap_target(width, height, scale)?;
+
+            {
+                let mut canvas = target.render_context();
+                canvas.transform(Affine::translate(-origin) * transform);
+
+                let outer_origin = LAYER_ORIGIN.with(|o| o.replace(origin));
+                (f.take().unwrap())(self, &mut canvas);
+                LAYER_ORIGIN.with(|o| o.set(outer_origin));
+
+                canvas.finish()?;
+            }
+
+            target.to_image_buf(ImageFormat::RgbaPremul)
+        })?;
+
+        // Premultiplied colors are faded by scaling all of their channels.
+        let pixels = layer
+            .raw_pixels()
+            .iter()
+            .map(|c| (*c as f64 * opacity).round() as u8)
+            .collect::<Vec<_>>();
+
+        let image = ImageBuf::from_raw(pixels, ImageFormat::RgbaPremul, width, height);
+        let image = image.to_image(piet);
+
+        piet.save()?;
+        piet.transform(transform.inverse());
+        piet.draw_image(&image, window_bounds, InterpolationMode::NearestNeighbor);
+        piet.restore()
+    }
+}
+
+/// Calls `f` with the shared layer device, or with a new one if it's in use
+/// by an outer layer.
+#[cfg(not(feature = "miri"))]
+fn with_layer_device<R>(
+    f: impl FnOnce(&mut Device) -> Result<R, PietError>,
+) -> Result<R, PietError> {
+    let mut device = match LAYER_DEVICE.with(|d| d.take()) {
+        Some(device) => device,
+        None => Device::new()?,
+    };
+
+    let r = f(&mut device);
+    LAYER_DEVICE.with(|d| d.set(Some(device)));
+
+    r
 }
 
 impl<W: Widget> RenderExt<W> for PaintCx<W> {
@@ -76,11 +332,63 @@ impl PaintCxOS {
         // Used to calculate local offset of self (see Drop impl).
         self.offset = offset.clone();
 
-        // Update local offset of this node.
+        // Update local offset and transform of this node.
         let local_offset = *offset - self.parent_offset;
-        self.node.borrow_mut().render_data.local_offset = local_offset;
+        let paint_transform = window_transform(piet) * Affine::translate((offset.x, offset.y));
 
+        {
+            let render_data = &mut self.node.borrow_mut().render_data;
+            render_data.local_offset = local_offset;
+            render_data.paint_transform = Some(paint_transform);
+        }
+
+        if self.is_clipped_out(piet, offset) {
+            return;
+        }
+
+        Instrumentation::count(|s| s.painted += 1);
         self.node.widget().paint(self.clone(), piet, offset);
+
+        self.update_paint_bounds(paint_transform);
+    }
+
+    /// Whether everything this widget and its descendants painted during the
+    /// last paint would be completely outside of the current clip, in which
+    /// case it doesn't need to be painted. Widgets which weren't painted yet
+    /// are never culled.
+    ///
+    /// Widgets painting outside of their layout box without painting a child
+    /// there (e.g. shadows) can be culled even if part of what they paint
+    /// would be visible, which is why only the clips applied with
+    /// [`PaintCx::with_clip`] are considered.
+    fn is_clipped_out(&self, piet: &Canvas, offset: &Offset) -> bool {
+        let clip = match PAINT_CLIP.with(|c| c.borrow().last().copied()) {
+            Some(clip) => clip,
+            None => return false,
+        };
+
+        let bounds = match self.node.borrow().render_data.paint_bounds {
+            Some(bounds) => bounds,
+            None => return false,
+        };
+
+        let transform = window_transform(piet) * Affine::translate((offset.x, offset.y));
+        is_outside(transform.transform_rect_bbox(bounds), clip)
+    }
+
+    /// Records what this widget and its children painted, once it was painted
+    /// with `transform`.
+    fn update_paint_bounds(&self, transform: Affine) {
+        let size = self.node.borrow().render_data.size;
+
+        let children = self.node.children().into_iter().filter_map(|child| {
+            let child = child.borrow();
+            let data = &child.render_data;
+            Some((data.paint_transform?, data.paint_bounds?))
+        });
+        let bounds = paint_bounds(size, transform, children);
+
+        self.node.borrow_mut().render_data.paint_bounds = Some(bounds);
     }
 
     #[track_caller]
@@ -104,4 +412,96 @@ impl PaintCxOS {
             parent_offset: self.offset.clone(),
         })
     }
+
+    /// Paints children at their [`RenderOSExt::offset`] from `offset`.
+    pub fn paint_children(&mut self, canvas: &mut Canvas, offset: &Offset) {
+        for mut child in self.children() {
+            let child_offset = *offset + child.offset();
+            child.paint(canvas, &child_offset);
+        }
+    }
+}
+
+/// Bounds (in local coordinates of a widget of `size`, painted with
+/// `transform`) of its layout box and of what its children painted, given
+/// their paint transforms and paint bounds.
+fn paint_bounds(
+    size: Size,
+    transform: Affine,
+    children: impl IntoIterator<Item = (Affine, Rect)>,
+) -> Rect {
+    let bounds = Rect::from_origin_size(Point::ZERO, size);
+
+    // Nothing painted by a widget squashed into a line or a point is visible.
+    if transform.determinant() == 0. {
+        return bounds;
+    }
+
+    let inverse = transform.inverse();
+
+    children
+        .into_iter()
+        .fold(bounds, |bounds, (child, child_bounds)| {
+            bounds.union((inverse * child).transform_rect_bbox(child_bounds))
+        })
+}
+
+fn is_outside(bounds: Rect, clip: Rect) -> bool {
+    bounds.x1 < clip.x0 || bounds.x0 > clip.x1 || bounds.y1 < clip.y0 || bounds.y0 > clip.y1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paint_bounds_include_overflowing_children() {
+        let parent = Affine::translate((100., 0.));
+        let size = Size::new(50., 50.);
+
+        // Child painted below its parent, e.g. overflowing a column.
+        let child = (
+            parent * Affine::translate((0., 80.)),
+            Rect::new(0., 0., 50., 50.),
+        );
+        let bounds = paint_bounds(size, parent, [child]);
+        assert_eq!(bounds, Rect::new(0., 0., 50., 130.));
+
+        // Parent scrolled above the clip is painted, since its child isn't.
+        let clip = Rect::new(0., 0., 400., 300.);
+        let scrolled = Affine::translate((100., -100.));
+        assert!(!is_outside(scrolled.transform_rect_bbox(bounds), clip));
+        assert!(is_outside(
+            scrolled.transform_rect_bbox(Rect::from_origin_size(Point::ZERO, size)),
+            clip
+        ));
+    }
+
+    #[test]
+    fn paint_bounds_follow_transforms() {
+        let size = Size::new(10., 10.);
+
+        // Child scaled twice about its parent's origin.
+        let child = (Affine::scale(2.), Rect::new(0., 0., 10., 10.));
+        assert_eq!(
+            paint_bounds(size, Affine::IDENTITY, [child]),
+            Rect::new(0., 0., 20., 20.)
+        );
+
+        // Bounds are local to the parent, whatever its transform.
+        let parent = Affine::rotate(std::f64::consts::FRAC_PI_2);
+        let child = (
+            parent * Affine::translate((20., 0.)),
+            Rect::new(0., 0., 10., 10.),
+        );
+        let bounds = paint_bounds(size, parent, [child]);
+        assert!((bounds.x1 - 30.).abs() < 1e-9 && (bounds.y1 - 10.).abs() < 1e-9);
+
+        // Squashed parent keeps its layout box.
+        let squashed = Affine::scale_non_uniform(0., 1.);
+        assert_eq!(
+            paint_bounds(size, squashed, [child]),
+            Rect::new(0., 0., 10., 10.)
+        );
+    }
 }
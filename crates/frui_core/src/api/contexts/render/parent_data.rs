@@ -2,6 +2,14 @@ use std::any::Any;
 
 use frui_macros::sealed;
 
+use super::Offset;
+
+/// Data stored on a widget for its parent, e.g. the flex factor of a child
+/// of a flex layout.
+///
+/// The data is created when the widget is first built, and again whenever it
+/// is updated with a different configuration, replacing the data stored on it
+/// until then.
 pub trait ParentData {
     type Data: 'static;
 
@@ -11,16 +19,33 @@ pub trait ParentData {
 #[sealed(crate)]
 pub trait ParentDataOS {
     fn create_parent_data(&self) -> Box<dyn Any>;
+
+    /// Replaces `data` with parent data of this widget, if it provides any.
+    fn update_parent_data(&self, data: &mut Box<dyn Any>);
 }
 
 impl<T> ParentDataOS for T {
     default fn create_parent_data(&self) -> Box<dyn Any> {
         Box::new(())
     }
+
+    default fn update_parent_data(&self, _: &mut Box<dyn Any>) {}
 }
 
 impl<T: ParentData> ParentDataOS for T {
     fn create_parent_data(&self) -> Box<dyn Any> {
         Box::new(<T as ParentData>::create_data(&self))
     }
+
+    fn update_parent_data(&self, data: &mut Box<dyn Any>) {
+        *data = self.create_parent_data();
+    }
+}
+
+/// Parent data of widgets positioned at an offset from their parent, set with
+/// [`LayoutCxOS::set_offset`](super::LayoutCxOS::set_offset) and painted with
+/// [`PaintCxOS::paint_children`](super::PaintCxOS::paint_children).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BoxLayoutData {
+    pub offset: Offset,
 }
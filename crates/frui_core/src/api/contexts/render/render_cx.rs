@@ -1,15 +1,14 @@
 use std::marker::PhantomData;
 
-use druid_shell::IdleToken;
-
 use super::{
     ext::{RenderExt, RenderOSExt},
-    Constraints, Size,
+    BaselineOS, BoxLayoutData, Constraints, IntrinsicsOS, Offset, Size,
 };
 
 use crate::{
-    app::{runner::window_handler::APP_HANDLE, tree::NodeRef},
-    prelude::{InheritedState, InheritedWidget, Widget, WidgetState},
+    api::implementers::inherited::ValueKey,
+    app::{instrumentation::Instrumentation, runner::window_handler::schedule_idle, tree::NodeRef},
+    prelude::{InheritedState, InheritedValueRef, InheritedWidget, Widget, WidgetState},
 };
 
 pub struct LayoutCx<T> {
@@ -39,6 +38,18 @@ impl<T> LayoutCx<T> {
             _p: PhantomData,
         })
     }
+
+    /// See [`_BuildCx::depend_on`](crate::api::contexts::build_cx::_BuildCx::depend_on).
+    pub fn depend_on<V: 'static>(&self) -> Option<InheritedValueRef<V>> {
+        let node = self
+            .node
+            .depend_on_inherited_widget_of_key::<ValueKey<V>>()?;
+
+        Some(InheritedValueRef {
+            node,
+            _p: PhantomData,
+        })
+    }
 }
 
 impl<W: Widget> RenderExt<W> for LayoutCx<W> {
@@ -81,6 +92,7 @@ impl LayoutCxOS {
         let widget = self.node.widget();
 
         let size = widget.layout(self.clone(), constraints);
+        Instrumentation::count(|s| s.laid_out += 1);
 
         if cfg!(debug_assertions) {
             if size > constraints.biggest() {
@@ -111,6 +123,55 @@ impl LayoutCxOS {
         }
     }
 
+    pub fn min_intrinsic_width(&self, height: f64) -> f64 {
+        self.node
+            .widget()
+            .min_intrinsic_width_os(self.clone(), height)
+    }
+
+    pub fn max_intrinsic_width(&self, height: f64) -> f64 {
+        self.node
+            .widget()
+            .max_intrinsic_width_os(self.clone(), height)
+    }
+
+    pub fn min_intrinsic_height(&self, width: f64) -> f64 {
+        self.node
+            .widget()
+            .min_intrinsic_height_os(self.clone(), width)
+    }
+
+    pub fn max_intrinsic_height(&self, width: f64) -> f64 {
+        self.node
+            .widget()
+            .max_intrinsic_height_os(self.clone(), width)
+    }
+
+    /// Distance from the top of this widget to the baseline of its text, if
+    /// it has any. Only valid once the widget is laid out.
+    pub fn distance_to_baseline(&self) -> Option<f64> {
+        self.node.widget().distance_to_baseline_os(self.clone())
+    }
+
+    /// Sets parent data of children which don't have parent data of type `P`
+    /// yet to the one returned by `default`.
+    pub fn ensure_parent_data<P: 'static>(&self, default: impl Fn() -> P) {
+        for child in self.children() {
+            if child.try_parent_data::<P>().is_none() {
+                child.set_parent_data(default());
+            }
+        }
+    }
+
+    /// Positions this widget at `offset` from its parent, stored in its
+    /// [`BoxLayoutData`] (see [`RenderOSExt::offset`]).
+    pub fn set_offset(&self, offset: Offset) {
+        match self.try_parent_data_mut::<BoxLayoutData>() {
+            Some(mut data) => data.offset = offset,
+            None => self.set_parent_data(BoxLayoutData { offset }),
+        }
+    }
+
     fn try_child(&self, index: usize) -> Option<LayoutCxOS> {
         let child = self.node.child(index)?;
 
@@ -118,13 +179,7 @@ impl LayoutCxOS {
     }
 
     pub fn schedule_layout(&mut self) {
-        APP_HANDLE.with(|handle| {
-            handle
-                .borrow_mut()
-                .as_mut()
-                .expect("APP_HANDLE wasn't set")
-                .schedule_idle(IdleToken::new(0));
-        });
+        schedule_idle();
     }
 }
 
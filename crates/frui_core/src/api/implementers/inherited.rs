@@ -1,16 +1,52 @@
-use std::any::TypeId;
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData,
+};
+
+use frui_macros::sealed;
 
 use crate::{
     api::{IntoWidgetPtr, WidgetPtr},
     render::*,
 };
 
-use super::{InheritedWidgetOS, WidgetDerive};
+use super::{InheritedWidgetOS, RawWidget, WidgetDerive};
 
 pub trait InheritedWidget: WidgetDerive + Sized {
     fn build<'w>(&'w self) -> Self::Widget<'w>;
 }
 
+/// Value an [`InheritedWidget`] provides to its descendants, which read it
+/// with `depend_on::<Self::Value>()` (see
+/// [`_BuildCx::depend_on`](crate::api::contexts::build_cx::_BuildCx::depend_on)).
+///
+/// Descendants depend on the closest ancestor providing a value of that type,
+/// and are rebuilt once the ancestor is rebuilt with a different value.
+///
+/// ```ignore
+/// #[derive(InheritedWidget)]
+/// struct Theme<W: Widget> {
+///     data: ThemeData,
+///     child: W,
+/// }
+///
+/// impl<W: Widget> InheritedValue for Theme<W> {
+///     type Value = ThemeData;
+///
+///     fn value(&self) -> &ThemeData {
+///         &self.data
+///     }
+/// }
+///
+/// // In a descendant:
+/// let theme = cx.depend_on::<ThemeData>();
+/// ```
+pub trait InheritedValue: InheritedWidget {
+    type Value: PartialEq + 'static;
+
+    fn value(&self) -> &Self::Value;
+}
+
 impl<T: InheritedWidget> InheritedWidgetOS for T {
     fn build<'w>(&'w self, _: &'w crate::api::contexts::RawBuildCx) -> Vec<WidgetPtr<'w>> {
         vec![T::build(self).into_widget_ptr()]
@@ -28,3 +64,42 @@ impl<T: InheritedWidget> InheritedWidgetOS for T {
         Some(TypeId::of::<T::UniqueTypeId>())
     }
 }
+
+/// Key under which inherited widgets providing a value of type `T` are
+/// registered in the tree. Only used for its `TypeId`.
+#[allow(dead_code)]
+pub(crate) struct ValueKey<T>(PhantomData<T>);
+
+#[sealed(crate)]
+pub trait InheritedValueOS {
+    /// Key of the provided value and the value itself.
+    fn inherited_value(&self) -> Option<(TypeId, &dyn Any)>;
+
+    /// Whether the value provided by `old` configuration of this widget
+    /// differs from the current one.
+    fn inherited_value_changed(&self, old: &dyn RawWidget) -> bool;
+}
+
+impl<T> InheritedValueOS for T {
+    default fn inherited_value(&self) -> Option<(TypeId, &dyn Any)> {
+        None
+    }
+
+    default fn inherited_value_changed(&self, _: &dyn RawWidget) -> bool {
+        false
+    }
+}
+
+impl<T: InheritedValue> InheritedValueOS for T {
+    fn inherited_value(&self) -> Option<(TypeId, &dyn Any)> {
+        Some((TypeId::of::<ValueKey<T::Value>>(), T::value(self)))
+    }
+
+    fn inherited_value_changed(&self, old: &dyn RawWidget) -> bool {
+        let old = old
+            .inherited_value()
+            .and_then(|(_, value)| value.downcast_ref::<T::Value>());
+
+        old != Some(T::value(self))
+    }
+}
@@ -2,13 +2,15 @@ use std::any::TypeId;
 
 use frui_macros::copy_trait_as;
 
-use crate::render::*;
+use crate::{app::snapshots::SnapshotStateOS, render::*};
+
+use self::inherited::InheritedValueOS;
 
 use super::{
     any_ext::AnyExt,
     contexts::{
         build_cx::widget_state::WidgetStateOS,
-        render::{ParentDataOS, RenderStateOS},
+        render::{BaselineOS, IntrinsicsOS, ParentDataOS, RenderStateOS},
         RawBuildCx,
     },
     local_key::WidgetLocalKey,
@@ -57,7 +59,11 @@ pub trait OS:
     + WidgetUniqueType
     + WidgetDebug
     + HitTestOS
+    + IntrinsicsOS
+    + BaselineOS
+    + InheritedValueOS
     + StructuralEqOS
+    + SnapshotStateOS
     + AnyExt
 {
     fn build<'w>(&'w self, cx: &'w RawBuildCx) -> Vec<WidgetPtr<'w>>;
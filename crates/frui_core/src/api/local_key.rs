@@ -1,9 +1,29 @@
-use std::any::{Any, TypeId};
+use std::{
+    any::{Any, TypeId},
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use crate::prelude::{BuildCx, ViewWidget, Widget};
 
 /// LocalKey is a widget that allows you to annotate the key for a `child`
 /// widget.
+///
+/// Among children of the same widget, a child annotated with a key keeps its
+/// state (and the state of its subtree) when it moves, as long as its key is
+/// equal to the key it had before, e.g.:
+///
+/// ```ignore
+/// Column::builder().children(
+///     items.iter().map(|item| LocalKey::new(ValueKey(item.id), ItemTile::new(item))),
+/// )
+/// ```
+///
+/// Any `'static` value comparable with [`PartialEq`] can be used as a key,
+/// but keys which also implement [`Hash`] (like [`ValueKey`], [`ObjectKey`]
+/// and [`UniqueKey`]) are matched in constant time, which matters for long
+/// lists.
 #[derive(ViewWidget)]
 pub struct LocalKey<K: 'static + PartialEq, W: Widget> {
     pub key: K,
@@ -22,10 +42,58 @@ impl<K: 'static + PartialEq, W: Widget> ViewWidget for LocalKey<K, W> {
     }
 }
 
+/// Key of a value, e.g. of an id of an item. Keys of values of different
+/// types are never equal, so `ValueKey(1u32)` and `ValueKey(1u64)` can be
+/// keys of siblings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ValueKey<T>(pub T);
+
+/// Key of the identity of an object, equal only to keys of the same object
+/// regardless of its value.
+///
+/// Objects are identified by their address, so keys of objects which were
+/// dropped can be equal to keys of objects allocated after them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectKey(*const ());
+
+impl ObjectKey {
+    pub fn of<T: ?Sized>(object: &T) -> Self {
+        ObjectKey(object as *const T as *const ())
+    }
+}
+
+/// Key equal only to itself (and its copies).
+///
+/// A new key is created on every call to [`UniqueKey::new`], so keys created
+/// in `build` change on every rebuild. Keep them in a state instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UniqueKey(u64);
+
+impl UniqueKey {
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+
+        UniqueKey(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for UniqueKey {
+    fn default() -> Self {
+        UniqueKey::new()
+    }
+}
+
 pub struct LocalKeyAny<'a> {
     key: &'a dyn PartialEqAny,
 }
 
+impl LocalKeyAny<'_> {
+    /// Hash of the key (and of its type), if it implements [`Hash`].
+    pub(crate) fn hash(&self) -> Option<u64> {
+        self.key.key_hash()
+    }
+}
+
 impl PartialEq for LocalKeyAny<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.key.eq(other.key)
@@ -35,6 +103,7 @@ impl PartialEq for LocalKeyAny<'_> {
 trait PartialEqAny: Any {
     fn type_id(&self) -> TypeId;
     fn eq(&self, other: &dyn PartialEqAny) -> bool;
+    fn key_hash(&self) -> Option<u64>;
 }
 
 impl<T: 'static + PartialEq> PartialEqAny for T {
@@ -50,6 +119,29 @@ impl<T: 'static + PartialEq> PartialEqAny for T {
             false
         }
     }
+
+    fn key_hash(&self) -> Option<u64> {
+        KeyHash::key_hash(self)
+    }
+}
+
+trait KeyHash {
+    fn key_hash(&self) -> Option<u64>;
+}
+
+impl<T: 'static> KeyHash for T {
+    default fn key_hash(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<T: 'static + Hash> KeyHash for T {
+    fn key_hash(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        Hash::hash(&TypeId::of::<T>(), &mut hasher);
+        Hash::hash(self, &mut hasher);
+        Some(hasher.finish())
+    }
 }
 
 //
@@ -70,3 +162,30 @@ impl<K: 'static + PartialEq, W: Widget> WidgetLocalKey for LocalKey<K, W> {
         Some(LocalKeyAny { key: &self.key })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key<K: 'static + PartialEq>(key: &K) -> LocalKeyAny {
+        LocalKeyAny { key }
+    }
+
+    #[test]
+    fn keys_of_different_types_differ() {
+        assert!(key(&ValueKey(1u32)) == key(&ValueKey(1u32)));
+        assert!(key(&ValueKey(1u32)) != key(&ValueKey(1u64)));
+        assert_ne!(key(&ValueKey(1u32)).hash(), key(&ValueKey(1u64)).hash());
+
+        let unique = UniqueKey::new();
+        assert!(key(&unique) == key(&unique));
+        assert!(key(&unique) != key(&UniqueKey::new()));
+
+        let (a, b) = (String::from("a"), String::from("a"));
+        assert!(key(&ObjectKey::of(&a)) != key(&ObjectKey::of(&b)));
+
+        // Keys which aren't hashable are still compared.
+        assert!(key(&1.5f64) == key(&1.5f64));
+        assert_eq!(key(&1.5f64).hash(), None);
+    }
+}
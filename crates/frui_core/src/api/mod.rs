@@ -8,6 +8,7 @@ pub(crate) mod implementers;
 pub(crate) mod impls;
 pub(crate) mod local_key;
 pub(crate) mod pointer_events;
+pub(crate) mod shared_str;
 pub(crate) mod structural_eq;
 pub(crate) mod widget_ptr;
 
@@ -2,7 +2,9 @@ use std::marker::PhantomData;
 
 use druid_shell::kurbo::{Affine, Point};
 
-use crate::app::tree::pointer_handler::HitTestEntries;
+use crate::app::tree::pointer_handler::{
+    set_pointer_capture, HitTestEntries, PointerCapture, PointerLock, POINTER_CAPTURE,
+};
 use crate::app::tree::NodeRef;
 use crate::prelude::Widget;
 use crate::render::*;
@@ -53,6 +55,15 @@ impl HitTestCxOS {
         })
     }
 
+    /// Context for hit testing a widget which isn't a child of this one, but
+    /// which was painted by it, e.g. an entry of an overlay.
+    pub fn adopt(&self, widget: &PaintCxOS) -> HitTestCxOS {
+        HitTestCxOS {
+            node: widget.node().clone(),
+            ..self.clone()
+        }
+    }
+
     pub fn children<'a>(&'a mut self) -> ChildrenIter<'a> {
         self.node.children().into_iter().map(|child| HitTestCxOS {
             node: child,
@@ -72,9 +83,44 @@ impl HitTestCxOS {
         self.hit_test_with_transform(point, affine)
     }
 
-    // Todo: Add `hit_test_with_paint_transform` by defining our own
-    // `canvas.save()` which will check `canvas.current_transform()` and store
-    // it in the RenderState.
+    /// Hit tests this widget with `point` (in coordinates of its parent)
+    /// transformed the same way the canvas was transformed between painting
+    /// the parent and this widget. Unlike [`hit_test_with_paint_offset`] this
+    /// accounts for transformations applied by the parent while painting.
+    ///
+    /// Falls back to the paint offset if either widget wasn't painted yet.
+    ///
+    /// [`hit_test_with_paint_offset`]: Self::hit_test_with_paint_offset
+    pub fn hit_test_with_paint_transform(&mut self, point: Point, parent: &HitTestCxOS) -> bool {
+        let parent_transform = parent.node.borrow().render_data.paint_transform;
+        let transform = self.node.borrow().render_data.paint_transform;
+
+        match (parent_transform, transform) {
+            (Some(parent_transform), Some(transform)) => {
+                self.hit_test_with_transform(point, transform.inverse() * parent_transform)
+            }
+            _ => self.hit_test_with_paint_offset(point),
+        }
+    }
+
+    /// Hit tests children of this widget, starting from the last one (which
+    /// is usually painted on top), until one of them is hit.
+    pub fn hit_test_children(&mut self, point: Point) -> bool {
+        let parent = self.clone();
+
+        for mut child in self.children().rev() {
+            if child.hit_test_with_paint_transform(point, &parent) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Widgets hit so far, deepest first.
+    pub fn hit_test_result(&self) -> HitTestResult {
+        self.hit_entries.borrow().clone()
+    }
 
     /// Add comment.
     pub fn hit_test_with_transform(&mut self, point: Point, transform: Affine) -> bool {
@@ -86,11 +132,120 @@ impl HitTestCxOS {
         self.node.widget().hit_test_os(cx, point_after)
     }
 
+    /// Routes all following pointer events to this widget (and only to this
+    /// widget), until the pointer is released or [`release_pointer`] is
+    /// called. Useful for dragging, e.g. sliders.
+    ///
+    /// Events are still only received while the pointer is above the window,
+    /// since the windowing backend doesn't support capturing the pointer
+    /// outside of it.
+    ///
+    /// [`release_pointer`]: Self::release_pointer
+    pub fn capture_pointer(&self) {
+        set_pointer_capture(Some(PointerCapture {
+            node: self.node.downgrade(),
+            affine: None,
+            lock: None,
+        }));
+    }
+
+    /// Captures the pointer like [`capture_pointer`], hides the cursor and
+    /// holds the pointer in place. Pointer move events report positions as
+    /// if the pointer moved freely, without being stopped by edges of the
+    /// window or the screen. Useful for dragging values by pointer movement,
+    /// e.g. rotating a 3D view.
+    ///
+    /// Holding the pointer in place requires [`Window::warp_pointer`]. Where
+    /// the platform doesn't support it, the pointer is only hidden.
+    ///
+    /// [`capture_pointer`]: Self::capture_pointer
+    /// [`Window::warp_pointer`]: crate::app::window::Window::warp_pointer
+    pub fn lock_pointer(&self) {
+        set_pointer_capture(Some(PointerCapture {
+            node: self.node.downgrade(),
+            affine: None,
+            lock: Some(PointerLock::default()),
+        }));
+    }
+
+    /// Releases pointer captured or locked by this widget.
+    pub fn release_pointer(&self) {
+        if self.has_pointer_capture() {
+            set_pointer_capture(None);
+        }
+    }
+
+    pub fn has_pointer_capture(&self) -> bool {
+        POINTER_CAPTURE.with(|c| matches!(&*c.borrow(), Some(c) if c.node.is(&self.node)))
+    }
+
+    pub fn has_pointer_lock(&self) -> bool {
+        POINTER_CAPTURE
+            .with(|c| matches!(&*c.borrow(), Some(c) if c.node.is(&self.node) && c.lock.is_some()))
+    }
+
+    /// Transforms `point` from local coordinates of this widget to window
+    /// coordinates, as of the last paint.
+    pub fn local_to_global(&self, point: Point) -> Point {
+        match self.node.borrow().render_data.paint_transform {
+            Some(transform) => transform * point,
+            None => point,
+        }
+    }
+
     pub fn layout_box(&self) -> Size {
         self.node.borrow().render_data.size
     }
 }
 
+/// Path of widgets that were hit, from the deepest one to the root, together
+/// with transformations from window coordinates to their local coordinates.
+/// Pointer events are dispatched in that order.
+#[derive(Debug, Default, Clone)]
+pub struct HitTestResult {
+    path: Vec<(NodeRef, Affine)>,
+}
+
+impl HitTestResult {
+    pub(crate) fn add(&mut self, node: NodeRef, transform: Affine) {
+        if !self.contains(&node) {
+            self.path.push((node, transform));
+        }
+    }
+
+    pub(crate) fn contains(&self, node: &NodeRef) -> bool {
+        self.path.iter().any(|(n, _)| n == node)
+    }
+
+    pub(crate) fn transform(&self, node: &NodeRef) -> Option<Affine> {
+        self.path.iter().find(|(n, _)| n == node).map(|(_, t)| *t)
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &(NodeRef, Affine)> {
+        self.path.iter()
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<(NodeRef, Affine)> {
+        std::mem::take(&mut self.path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.path.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Debug names of widgets that were hit, deepest first.
+    pub fn widgets(&self) -> Vec<&'static str> {
+        self.path
+            .iter()
+            .map(|(n, _)| n.debug_name_short())
+            .collect()
+    }
+}
+
 type ChildrenIter<'a> =
     impl Iterator<Item = HitTestCxOS> + 'a + DoubleEndedIterator + ExactSizeIterator;
 
@@ -8,7 +8,7 @@ pub mod events;
 pub mod pointer_listener;
 pub mod pointer_region;
 
-pub use context::HitTestCx;
+pub use context::{HitTestCx, HitTestResult};
 pub use events::PointerEvent;
 pub use pointer_listener::PointerListener;
 pub use pointer_region::PointerRegion;
@@ -16,14 +16,7 @@ pub use pointer_region::PointerRegion;
 pub trait HitTest: Sized {
     fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
         if cx.layout_box().contains(point) {
-            for mut child in cx.children() {
-                if child.hit_test_with_paint_offset(point) {
-                    // Don't hit test other children if one already handled that
-                    // event.
-                    return true;
-                }
-            }
-
+            cx.hit_test_children(point);
             return true;
         }
 
@@ -43,14 +36,7 @@ pub trait HitTestOS {
 impl<T> HitTestOS for T {
     default fn hit_test_os(&self, mut cx: HitTestCxOS, point: Point) -> bool {
         if cx.layout_box().contains(point) {
-            for mut child in cx.children() {
-                if child.hit_test_with_paint_offset(point) {
-                    // Don't hit test other children if one already handled that
-                    // event.
-                    return true;
-                }
-            }
-
+            cx.hit_test_children(point);
             return true;
         }
 
@@ -65,10 +51,11 @@ impl<T: HitTest> HitTestOS for T {
         let cx = &mut <HitTestCx<T>>::new(cx);
 
         if T::hit_test(&self, cx, point) {
+            // Children are added before their parents.
             cx.inner
                 .hit_entries
                 .borrow_mut()
-                .insert(cx.inner.node.clone(), cx.inner.affine);
+                .add(cx.inner.node.clone(), cx.inner.affine);
 
             true
         } else {
@@ -1,4 +1,8 @@
-use crate::{prelude::*, render::*};
+use crate::{
+    app::window::{MouseCursor, Window},
+    prelude::*,
+    render::*,
+};
 
 use super::{events::*, HitTest, HitTestCx};
 
@@ -13,6 +17,7 @@ where
     on_enter: PEN,
     on_move: PMV,
     on_exit: PEX,
+    cursor: Option<MouseCursor>,
     child: CHILD,
 }
 
@@ -53,6 +58,12 @@ where
     fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
         match event {
             PointerEvent::PointerMove(e) => {
+                // Cursor is reset on every pointer move, so it is set again
+                // every time.
+                if let Some(cursor) = self.cursor.as_ref().and_then(|c| c.platform_cursor()) {
+                    Window::set_cursor(&cursor);
+                }
+
                 if *cx.render_state() {
                     self.on_move.call(&PointerMove(e.0.clone()));
                 } else {
@@ -108,6 +119,7 @@ impl PointerRegion<NOP, NOP, NOP, ()> {
             on_enter: NOP,
             on_move: NOP,
             on_exit: NOP,
+            cursor: None,
             child: (),
         }
     }
@@ -128,6 +140,7 @@ where
             on_enter: f,
             on_move: self.on_move,
             on_exit: self.on_exit,
+            cursor: self.cursor,
             child: self.child,
         }
     }
@@ -140,6 +153,7 @@ where
             on_enter: self.on_enter,
             on_move: f,
             on_exit: self.on_exit,
+            cursor: self.cursor,
             child: self.child,
         }
     }
@@ -152,15 +166,23 @@ where
             on_enter: self.on_enter,
             on_move: self.on_move,
             on_exit: f,
+            cursor: self.cursor,
             child: self.child,
         }
     }
 
+    /// Cursor displayed while the pointer hovers above this widget.
+    pub fn cursor(mut self, cursor: impl Into<MouseCursor>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
     pub fn child(self, child: impl Widget) -> PointerRegion<PEN, PMV, PEX, impl Widget> {
         PointerRegion {
             on_enter: self.on_enter,
             on_move: self.on_move,
             on_exit: self.on_exit,
+            cursor: self.cursor,
             child,
         }
     }
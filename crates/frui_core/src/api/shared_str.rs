@@ -0,0 +1,228 @@
+use std::{
+    borrow::Borrow,
+    cell::RefCell,
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::Arc,
+};
+
+use super::structural_eq::StructuralEqImpl;
+
+/// Strings of up to that many bytes are stored inline.
+const INLINE_CAPACITY: usize = 22;
+
+/// Immutable string which is cheap to clone, meant to be used for text passed
+/// to widgets (e.g. `Text::new(SharedStr::from(...))`).
+///
+/// Short strings are stored inline, longer ones are reference counted. Unlike
+/// [`String`], two `SharedStr`s are compared when rebuilding widgets, which
+/// allows widgets holding them to skip rebuilds if the text didn't change.
+#[derive(Clone)]
+pub struct SharedStr(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Static(&'static str),
+    Inline {
+        len: u8,
+        bytes: [u8; INLINE_CAPACITY],
+    },
+    Shared(Arc<str>),
+}
+
+impl SharedStr {
+    pub const fn new_static(s: &'static str) -> Self {
+        SharedStr(Repr::Static(s))
+    }
+
+    /// Returns a string sharing its allocation with every other string
+    /// interned with the same contents.
+    ///
+    /// Interned strings are never deallocated, so this should only be used
+    /// for a bounded set of strings (e.g. labels repeated across many widgets).
+    pub fn intern(s: &str) -> Self {
+        if let Some(inline) = Self::inline(s) {
+            return inline;
+        }
+
+        INTERNED.with(|interned| {
+            let mut interned = interned.borrow_mut();
+
+            match interned.get(s) {
+                Some(shared) => SharedStr(Repr::Shared(shared.clone())),
+                None => {
+                    let shared: Arc<str> = Arc::from(s);
+                    interned.insert(shared.clone());
+                    SharedStr(Repr::Shared(shared))
+                }
+            }
+        })
+    }
+
+    fn inline(s: &str) -> Option<Self> {
+        if s.len() > INLINE_CAPACITY {
+            return None;
+        }
+
+        let mut bytes = [0; INLINE_CAPACITY];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+
+        Some(SharedStr(Repr::Inline {
+            len: s.len() as u8,
+            bytes,
+        }))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Static(s) => s,
+            // Safety: `bytes` were copied from a valid `str` of length `len`.
+            Repr::Inline { len, bytes } => unsafe {
+                std::str::from_utf8_unchecked(&bytes[..*len as usize])
+            },
+            Repr::Shared(s) => s,
+        }
+    }
+
+    /// Whether both strings point to the same memory. Cheaper than `==`, but
+    /// may return `false` for equal strings.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Repr::Shared(a), Repr::Shared(b)) => Arc::ptr_eq(a, b),
+            (Repr::Static(a), Repr::Static(b)) => a.as_ptr() == b.as_ptr() && a.len() == b.len(),
+            _ => false,
+        }
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Default for SharedStr {
+    fn default() -> Self {
+        SharedStr::new_static("")
+    }
+}
+
+impl From<&'static str> for SharedStr {
+    fn from(s: &'static str) -> Self {
+        SharedStr::new_static(s)
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(s: String) -> Self {
+        Self::inline(&s).unwrap_or_else(|| SharedStr(Repr::Shared(Arc::from(s))))
+    }
+}
+
+impl From<&String> for SharedStr {
+    fn from(s: &String) -> Self {
+        Self::inline(s).unwrap_or_else(|| SharedStr(Repr::Shared(Arc::from(s.as_str()))))
+    }
+}
+
+impl From<Arc<str>> for SharedStr {
+    fn from(s: Arc<str>) -> Self {
+        SharedStr(Repr::Shared(s))
+    }
+}
+
+impl From<SharedStr> for String {
+    fn from(s: SharedStr) -> Self {
+        s.as_str().to_owned()
+    }
+}
+
+impl PartialEq for SharedStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SharedStr {}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for SharedStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+// Safety: `SharedStr` owns its contents (or points to 'static memory), so
+// comparing contents of two strings can't access deallocated memory.
+unsafe impl StructuralEqImpl for SharedStr {
+    const EQ_ENABLED: bool = true;
+
+    fn eq(&self, other: &Self) -> bool {
+        PartialEq::eq(self, other)
+    }
+}
+
+thread_local! {
+    static INTERNED: RefCell<HashSet<Arc<str>>> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shared_str() {
+        let short = SharedStr::from(String::from("short"));
+        let long = "a string which is too long to be stored inline";
+
+        assert!(matches!(short.0, Repr::Inline { .. }));
+        assert_eq!(short, "short");
+        assert_eq!(SharedStr::from(long.to_string()), SharedStr::from(long));
+
+        let a = SharedStr::intern(long);
+        let b = SharedStr::intern(&long.to_string());
+
+        assert!(a.ptr_eq(&b));
+        assert!(!a.ptr_eq(&SharedStr::from(long.to_string())));
+        assert!(StructuralEqImpl::eq(&short, &SharedStr::intern("short")));
+    }
+}
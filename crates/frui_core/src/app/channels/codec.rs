@@ -0,0 +1,73 @@
+use std::fmt;
+
+use super::JsonValue;
+
+/// Encoding of messages sent through a [`MessageChannel`](super::MessageChannel).
+/// Both ends of a channel must use the same codec.
+pub trait MessageCodec: 'static {
+    type Message;
+
+    fn encode(&self, message: &Self::Message) -> Vec<u8>;
+
+    fn decode(&self, data: &[u8]) -> Result<Self::Message, CodecError>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodecError(pub String);
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "decoding message failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// Passes messages through unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinaryCodec;
+
+impl MessageCodec for BinaryCodec {
+    type Message = Vec<u8>;
+
+    fn encode(&self, message: &Vec<u8>) -> Vec<u8> {
+        message.clone()
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>, CodecError> {
+        Ok(data.to_vec())
+    }
+}
+
+/// Encodes strings as UTF-8.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringCodec;
+
+impl MessageCodec for StringCodec {
+    type Message = String;
+
+    fn encode(&self, message: &String) -> Vec<u8> {
+        message.as_bytes().to_vec()
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<String, CodecError> {
+        String::from_utf8(data.to_vec()).map_err(|e| CodecError(e.to_string()))
+    }
+}
+
+/// Encodes [`JsonValue`]s as UTF-8 JSON text.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl MessageCodec for JsonCodec {
+    type Message = JsonValue;
+
+    fn encode(&self, message: &JsonValue) -> Vec<u8> {
+        message.to_string().into_bytes()
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<JsonValue, CodecError> {
+        let text = StringCodec.decode(data)?;
+        JsonValue::parse(&text).ok_or_else(|| CodecError("invalid JSON".into()))
+    }
+}
@@ -0,0 +1,391 @@
+use std::{collections::BTreeMap, fmt, fmt::Write};
+
+/// JSON value exchanged with [`JsonCodec`](super::JsonCodec).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum JsonValue {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+impl JsonValue {
+    /// Parses `data`, returning `None` if it isn't a single valid JSON value.
+    pub fn parse(data: &str) -> Option<JsonValue> {
+        let mut parser = Parser {
+            chars: data.chars().peekable(),
+        };
+
+        let value = parser.value()?;
+
+        match parser.peek() {
+            None => Some(value),
+            Some(_) => None,
+        }
+    }
+
+    /// Value of `key`, if this is an object containing it.
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(o) => o.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => f.write_str("null"),
+            JsonValue::Bool(v) => write!(f, "{}", v),
+            JsonValue::Number(v) if v.is_finite() => write!(f, "{}", v),
+            // Not representable in JSON.
+            JsonValue::Number(_) => f.write_str("null"),
+            JsonValue::String(v) => write_string(f, v),
+            JsonValue::Array(values) => {
+                f.write_char('[')?;
+
+                for (n, value) in values.iter().enumerate() {
+                    if n > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    write!(f, "{}", value)?;
+                }
+
+                f.write_char(']')
+            }
+            JsonValue::Object(values) => {
+                f.write_char('{')?;
+
+                for (n, (key, value)) in values.iter().enumerate() {
+                    if n > 0 {
+                        f.write_char(',')?;
+                    }
+
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl From<$t> for JsonValue {
+                fn from(v: $t) -> Self {
+                    JsonValue::$variant(v.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    bool => Bool,
+    f64 => Number,
+    f32 => Number,
+    i32 => Number,
+    u32 => Number,
+    String => String,
+    &str => String,
+    Vec<JsonValue> => Array,
+    BTreeMap<String, JsonValue> => Object
+);
+
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        self.peek()?;
+        self.chars.next()
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        match self.next()? == c {
+            true => Some(()),
+            false => None,
+        }
+    }
+
+    fn value(&mut self) -> Option<JsonValue> {
+        match self.peek()? {
+            '"' => Some(JsonValue::String(self.string()?)),
+            '[' => self.array(),
+            '{' => self.object(),
+            't' | 'f' | 'n' => match self.word().as_str() {
+                "true" => Some(JsonValue::Bool(true)),
+                "false" => Some(JsonValue::Bool(false)),
+                "null" => Some(JsonValue::Null),
+                _ => None,
+            },
+            _ => {
+                let word = self.word();
+
+                // Numbers too big for `f64` are parsed as infinity.
+                let number = word.parse().ok().filter(|v: &f64| v.is_finite());
+
+                match is_number(&word) {
+                    true => Some(JsonValue::Number(number?)),
+                    false => None,
+                }
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<JsonValue> {
+        self.expect('[')?;
+
+        let mut values = Vec::new();
+
+        if self.peek() == Some(']') {
+            self.chars.next();
+            return Some(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+
+            match self.next()? {
+                ',' => continue,
+                ']' => return Some(JsonValue::Array(values)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<JsonValue> {
+        self.expect('{')?;
+
+        let mut values = BTreeMap::new();
+
+        if self.peek() == Some('}') {
+            self.chars.next();
+            return Some(JsonValue::Object(values));
+        }
+
+        loop {
+            self.peek()?;
+            let key = self.string()?;
+            self.expect(':')?;
+            values.insert(key, self.value()?);
+
+            match self.next()? {
+                ',' => continue,
+                '}' => return Some(JsonValue::Object(values)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let mut r = String::new();
+
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_alphanumeric() || "+-.".contains(*c))
+        {
+            r.push(c);
+        }
+
+        r
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect('"')?;
+
+        let mut r = String::new();
+
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(r),
+                '\\' => match self.chars.next()? {
+                    'n' => r.push('\n'),
+                    'r' => r.push('\r'),
+                    't' => r.push('\t'),
+                    'b' => r.push('\u{8}'),
+                    'f' => r.push('\u{c}'),
+                    'u' => r.push(self.escaped_char()?),
+                    c @ ('"' | '\\' | '/') => r.push(c),
+                    _ => return None,
+                },
+                c if c < ' ' => return None,
+                c => r.push(c),
+            }
+        }
+    }
+
+    /// Character escaped as `\uXXXX`, after the `\u`. Characters outside of
+    /// the basic multilingual plane are escaped as a surrogate pair.
+    fn escaped_char(&mut self) -> Option<char> {
+        let code = match self.hex()? {
+            high @ 0xD800..=0xDBFF => {
+                self.chars.next_if_eq(&'\\')?;
+                self.chars.next_if_eq(&'u')?;
+
+                match self.hex()? {
+                    low @ 0xDC00..=0xDFFF => 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00),
+                    _ => return None,
+                }
+            }
+            code => code,
+        };
+
+        // Unpaired surrogates aren't characters.
+        char::from_u32(code)
+    }
+
+    fn hex(&mut self) -> Option<u32> {
+        (0..4).try_fold(0, |code, _| {
+            let digit = self.chars.next()?.to_digit(16)?;
+            Some(code * 16 + digit)
+        })
+    }
+}
+
+/// Whether `s` is a number as defined by JSON, which (unlike Rust) doesn't
+/// allow e.g. `NaN`, `inf`, `+1`, `.5` or `01`.
+fn is_number(s: &str) -> bool {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let (int, rest) = s.split_at(digits(s));
+
+    if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+        return false;
+    }
+
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => match digits(fraction) {
+            0 => return false,
+            n => &fraction[n..],
+        },
+        None => rest,
+    };
+
+    match rest.strip_prefix(|c| c == 'e' || c == 'E') {
+        Some(exponent) => {
+            let exponent = exponent
+                .strip_prefix(|c| c == '+' || c == '-')
+                .unwrap_or(exponent);
+
+            !exponent.is_empty() && digits(exponent) == exponent.len()
+        }
+        None => rest.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut object = BTreeMap::new();
+        object.insert("method".to_owned(), JsonValue::from("open\n\"file\""));
+        object.insert(
+            "args".to_owned(),
+            JsonValue::Array(vec![1.5.into(), true.into(), JsonValue::Null]),
+        );
+        object.insert("options".to_owned(), JsonValue::Object(BTreeMap::new()));
+        let value = JsonValue::Object(object);
+
+        assert_eq!(JsonValue::parse(&value.to_string()), Some(value.clone()));
+        assert_eq!(value.get("args").unwrap().as_array().unwrap().len(), 3);
+
+        assert_eq!(
+            JsonValue::parse(" [ 1 , \"\\u0041\" ] "),
+            Some(JsonValue::Array(vec![1.into(), "A".into()]))
+        );
+        assert_eq!(JsonValue::parse("[1,]"), None);
+        assert_eq!(JsonValue::parse("{} {}"), None);
+    }
+
+    #[test]
+    fn parses_surrogate_pairs() {
+        assert_eq!(
+            JsonValue::parse("\"\\ud83d\\ude00 \\u00e9\""),
+            Some("\u{1f600} \u{e9}".into())
+        );
+
+        // Unpaired surrogates.
+        assert_eq!(JsonValue::parse("\"\\ud83d\""), None);
+        assert_eq!(JsonValue::parse("\"\\ude00\""), None);
+        assert_eq!(JsonValue::parse("\"\\ud83d\\u0041\""), None);
+
+        assert_eq!(JsonValue::parse("\"\\x\""), None);
+        assert_eq!(JsonValue::parse("\"\\/\""), Some("/".into()));
+    }
+
+    #[test]
+    fn parses_only_json_numbers() {
+        for valid in ["0", "-0.5", "12e3", "1.5E+2", "-7e-1"] {
+            let expected = valid.parse::<f64>().unwrap();
+            assert_eq!(JsonValue::parse(valid), Some(expected.into()), "{}", valid);
+        }
+
+        for invalid in "NaN inf Infinity -Infinity 1e400 +1 .5 1. 01 1e".split(' ') {
+            assert_eq!(JsonValue::parse(invalid), None, "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Channels exchanging messages between the UI and native platform code or
+//! other processes, similar to platform channels of Flutter.
+//!
+//! Both ends of a [`MessageChannel`] are identified by its name and encode
+//! messages with the same [`MessageCodec`]. Messages sent from the UI with
+//! [`MessageChannel::send`] are passed to the [`BinaryMessenger`] of the
+//! application, which by default delivers them to handlers registered in this
+//! process with [`Channels::set_platform_handler`]. A different messenger,
+//! e.g. one forwarding messages to a helper process, can be set with
+//! [`Channels::set_messenger`].
+//!
+//! Messages in the other direction are posted with [`Channels::post`] (from
+//! any thread) and handled by the handler set with
+//! [`MessageChannel::set_handler`]. Both replies and posted messages are
+//! processed on the UI thread, after the current event is handled.
+//!
+//! ```ignore
+//! // Platform side, e.g. registered by a plugin:
+//! Channels::set_platform_handler("battery", |_, reply| {
+//!     let level = JsonValue::from(battery_level());
+//!     reply(Some(JsonCodec.encode(&level)));
+//! });
+//!
+//! // UI side:
+//! let channel = MessageChannel::new("battery", JsonCodec);
+//!
+//! channel.send(&JsonValue::Null, move |level| {
+//!     updater.update(|state| state.level = level.ok());
+//! });
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+    sync::Mutex,
+};
+
+use druid_shell::IdleToken;
+
+use super::{runner::IdleHandle, services::Services};
+
+pub use codec::*;
+pub use json::JsonValue;
+
+mod codec;
+mod json;
+
+/// Called with the response to a message, or `None` if the message wasn't
+/// handled. Can be called from any thread.
+pub type Reply = Box<dyn FnOnce(Option<Vec<u8>>) + Send>;
+
+/// Transport of messages sent from the UI, see [`Channels::set_messenger`].
+pub trait BinaryMessenger {
+    /// Sends `message` to the handler of `channel`, which responds to it by
+    /// calling `reply`.
+    fn send(&self, channel: &str, message: Vec<u8>, reply: Reply);
+}
+
+/// Messenger delivering messages to handlers set with
+/// [`Channels::set_platform_handler`].
+struct LocalMessenger;
+
+impl BinaryMessenger for LocalMessenger {
+    fn send(&self, channel: &str, message: Vec<u8>, reply: Reply) {
+        let handler = PLATFORM_HANDLERS.with(|h| h.borrow().get(channel).cloned());
+
+        match handler {
+            Some(handler) => handler(message, reply),
+            None => reply(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelError {
+    /// The other end of the channel had no handler of the message.
+    NotHandled,
+    Codec(CodecError),
+}
+
+impl fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelError::NotHandled => write!(f, "message wasn't handled"),
+            ChannelError::Codec(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ChannelError {}
+
+impl From<CodecError> for ChannelError {
+    fn from(e: CodecError) -> Self {
+        ChannelError::Codec(e)
+    }
+}
+
+/// Named channel of messages encoded with codec `C`.
+pub struct MessageChannel<C: MessageCodec> {
+    name: String,
+    codec: Rc<C>,
+}
+
+impl<C: MessageCodec> MessageChannel<C> {
+    pub fn new(name: impl Into<String>, codec: C) -> Self {
+        MessageChannel {
+            name: name.into(),
+            codec: Rc::new(codec),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Sends `message` to the other end of the channel. `on_reply` is called
+    /// on the UI thread with its response.
+    pub fn send(
+        &self,
+        message: &C::Message,
+        on_reply: impl FnOnce(Result<C::Message, ChannelError>) + 'static,
+    ) {
+        let codec = self.codec.clone();
+
+        let id = NEXT_REPLY_ID.with(|n| n.replace(n.get() + 1));
+        let on_reply = move |data: Option<Vec<u8>>| match data {
+            Some(data) => on_reply(codec.decode(&data).map_err(Into::into)),
+            None => on_reply(Err(ChannelError::NotHandled)),
+        };
+
+        REPLIES.with(|r| r.borrow_mut().insert(id, Box::new(on_reply)));
+
+        let reply: Reply = Box::new(move |data| push(Pending::Reply { id, data }));
+        let message = self.codec.encode(message);
+
+        match Services::get::<Box<dyn BinaryMessenger>>() {
+            Some(messenger) => messenger.send(&self.name, message, reply),
+            None => LocalMessenger.send(&self.name, message, reply),
+        }
+    }
+
+    /// Handles messages posted to this channel with [`Channels::post`],
+    /// replacing the previous handler. `handler` returns the response to the
+    /// message, if any.
+    pub fn set_handler(&self, handler: impl Fn(C::Message) -> Option<C::Message> + 'static) {
+        let codec = self.codec.clone();
+        let name = self.name.clone();
+
+        let handler = move |data: Vec<u8>| match codec.decode(&data) {
+            Ok(message) => handler(message).map(|response| codec.encode(&response)),
+            Err(e) => {
+                log::warn!("message of channel `{}` was dropped: {}", name, e);
+                None
+            }
+        };
+
+        HANDLERS.with(|h| h.borrow_mut().insert(self.name.clone(), Rc::new(handler)));
+    }
+
+    pub fn remove_handler(&self) {
+        HANDLERS.with(|h| h.borrow_mut().remove(&self.name));
+    }
+}
+
+pub struct Channels;
+
+impl Channels {
+    /// Sets the transport of messages sent from the UI, instead of handlers
+    /// set with [`set_platform_handler`](Self::set_platform_handler).
+    pub fn set_messenger(messenger: impl BinaryMessenger + 'static) {
+        Services::register::<Box<dyn BinaryMessenger>>(Box::new(messenger));
+    }
+
+    /// Handles messages sent from the UI to `channel`, replacing the previous
+    /// handler. `handler` is called on the UI thread, but may respond from
+    /// any thread.
+    pub fn set_platform_handler(channel: &str, handler: impl Fn(Vec<u8>, Reply) + 'static) {
+        PLATFORM_HANDLERS.with(|h| h.borrow_mut().insert(channel.into(), Rc::new(handler)));
+    }
+
+    pub fn remove_platform_handler(channel: &str) {
+        PLATFORM_HANDLERS.with(|h| h.borrow_mut().remove(channel));
+    }
+
+    /// Posts `message` to the handler of `channel` in the UI (see
+    /// [`MessageChannel::set_handler`]). Can be called from any thread.
+    pub fn post(
+        channel: &str,
+        message: Vec<u8>,
+        reply: impl FnOnce(Option<Vec<u8>>) + Send + 'static,
+    ) {
+        push(Pending::Message {
+            channel: channel.into(),
+            message,
+            reply: Box::new(reply),
+        });
+    }
+
+    pub(crate) fn set_waker(handle: IdleHandle) {
+        *WAKER.lock().unwrap() = Some(handle);
+    }
+
+    /// Delivers replies and messages received since the last call.
+    pub(crate) fn dispatch_pending() {
+        let pending = std::mem::take(&mut *PENDING.lock().unwrap());
+
+        for pending in pending {
+            match pending {
+                Pending::Reply { id, data } => {
+                    if let Some(on_reply) = REPLIES.with(|r| r.borrow_mut().remove(&id)) {
+                        on_reply(data);
+                    }
+                }
+                Pending::Message {
+                    channel,
+                    message,
+                    reply,
+                } => {
+                    let handler = HANDLERS.with(|h| h.borrow().get(&channel).cloned());
+                    reply(handler.and_then(|handler| handler(message)));
+                }
+            }
+        }
+    }
+}
+
+enum Pending {
+    Reply {
+        id: u64,
+        data: Option<Vec<u8>>,
+    },
+    Message {
+        channel: String,
+        message: Vec<u8>,
+        reply: Reply,
+    },
+}
+
+fn push(pending: Pending) {
+    PENDING.lock().unwrap().push(pending);
+
+    // Messages are dispatched when the UI thread is idle.
+    if let Some(handle) = WAKER.lock().unwrap().as_ref() {
+        handle.schedule_idle(IdleToken::new(0));
+    }
+}
+
+/// Replies and messages waiting to be dispatched on the UI thread.
+static PENDING: Mutex<Vec<Pending>> = Mutex::new(Vec::new());
+
+static WAKER: Mutex<Option<IdleHandle>> = Mutex::new(None);
+
+thread_local! {
+    static NEXT_REPLY_ID: Cell<u64> = Cell::new(0);
+
+    static REPLIES: RefCell<HashMap<u64, Box<dyn FnOnce(Option<Vec<u8>>)>>> =
+        Default::default();
+
+    /// Handlers of messages posted to the UI.
+    static HANDLERS: RefCell<HashMap<String, Rc<dyn Fn(Vec<u8>) -> Option<Vec<u8>>>>> =
+        Default::default();
+
+    /// Handlers of messages sent from the UI, used by [`LocalMessenger`].
+    static PLATFORM_HANDLERS: RefCell<HashMap<String, Rc<dyn Fn(Vec<u8>, Reply)>>> =
+        Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn send_and_post() {
+        Channels::set_platform_handler("echo", |message, reply| {
+            std::thread::spawn(move || reply(Some(message)));
+        });
+
+        let channel = MessageChannel::new("echo", JsonCodec);
+        let replies = Rc::new(RefCell::new(Vec::new()));
+
+        for message in [JsonValue::from("hello"), JsonValue::from(1.5)] {
+            let replies = replies.clone();
+            channel.send(&message, move |r| replies.borrow_mut().push(r));
+        }
+
+        let unhandled = MessageChannel::new("unhandled", StringCodec);
+        let not_handled = Rc::new(Cell::new(false));
+        unhandled.send(&"hello".into(), {
+            let not_handled = not_handled.clone();
+            move |r| not_handled.set(r == Err(ChannelError::NotHandled))
+        });
+
+        // Replies are only delivered once dispatched on this thread.
+        while replies.borrow().len() < 2 || !not_handled.get() {
+            Channels::dispatch_pending();
+            std::thread::yield_now();
+        }
+
+        let replies = replies.borrow();
+        assert!(replies.contains(&Ok(JsonValue::from("hello"))));
+        assert!(replies.contains(&Ok(JsonValue::from(1.5))));
+
+        // Posting to the UI.
+        let upper = MessageChannel::new("upper", StringCodec);
+        upper.set_handler(|s| Some(s.to_uppercase()));
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            Channels::post("upper", b"frui".to_vec(), move |r| sender.send(r).unwrap())
+        })
+        .join()
+        .unwrap();
+
+        Channels::dispatch_pending();
+        assert_eq!(receiver.recv().unwrap(), Some(b"FRUI".to_vec()));
+    }
+}
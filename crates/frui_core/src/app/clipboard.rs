@@ -0,0 +1,35 @@
+//! Access to the system clipboard.
+//!
+//! The platform clipboard can be replaced (e.g. in tests) by registering a
+//! `Box<dyn ClipboardService>` in [`Services`].
+
+use super::{runner::Application, services::Services};
+
+/// Backend of the [`Clipboard`].
+pub trait ClipboardService {
+    fn get_string(&self) -> Option<String>;
+
+    fn put_string(&self, text: &str);
+}
+
+pub struct Clipboard;
+
+impl Clipboard {
+    pub fn get_string() -> Option<String> {
+        match Services::get::<Box<dyn ClipboardService>>() {
+            Some(service) => service.get_string(),
+            None => Application::global().clipboard().get_string(),
+        }
+    }
+
+    pub fn put_string(text: &str) {
+        match Services::get::<Box<dyn ClipboardService>>() {
+            Some(service) => service.put_string(text),
+            None => Application::global().clipboard().put_string(text),
+        }
+    }
+
+    pub fn has_string() -> bool {
+        Self::get_string().map_or(false, |s| !s.is_empty())
+    }
+}
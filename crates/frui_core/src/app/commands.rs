@@ -0,0 +1,372 @@
+//! Application-wide registry of named commands.
+//!
+//! Commands are registered once and can be then consumed by menus, toolbars,
+//! keyboard shortcuts or the command palette. Whether a command is enabled is
+//! decided by its predicate. Since predicates usually depend on application
+//! state, they are evaluated again before every frame and after a command is
+//! executed, and listeners are notified if enablement of any command changed.
+
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use druid_shell::{keyboard_types::Key, KeyEvent};
+use slotmap::SlotMap;
+
+use super::leaks::{LeakDetector, Tracked};
+
+slotmap::new_key_type! { pub struct CommandListenerKey; }
+
+/// Key combination, e.g. `Ctrl+Shift+P`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    /// Character (e.g. `p`) or name of the key (e.g. `Enter`, `F5`).
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Shortcut {
+    /// Parses shortcuts like `Ctrl+S`, `ctrl+shift+p` or `Alt+F4`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut shortcut = Shortcut {
+            key: String::new(),
+            ctrl: false,
+            shift: false,
+            alt: false,
+            meta: false,
+        };
+
+        let mut parts = s.split('+').map(str::trim).peekable();
+
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                shortcut.key = part.to_owned();
+                break;
+            }
+
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => shortcut.ctrl = true,
+                "shift" => shortcut.shift = true,
+                "alt" | "option" => shortcut.alt = true,
+                "meta" | "cmd" | "super" | "win" => shortcut.meta = true,
+                _ => return None,
+            }
+        }
+
+        match shortcut.key.is_empty() {
+            true => None,
+            false => Some(shortcut),
+        }
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let key = match &event.key {
+            Key::Character(c) => c.clone(),
+            key => key.to_string(),
+        };
+
+        key.eq_ignore_ascii_case(&self.key)
+            && event.mods.ctrl() == self.ctrl
+            && event.mods.shift() == self.shift
+            && event.mods.alt() == self.alt
+            && event.mods.meta() == self.meta
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let modifiers = [
+            (self.ctrl, "Ctrl+"),
+            (self.shift, "Shift+"),
+            (self.alt, "Alt+"),
+            (self.meta, "Meta+"),
+        ];
+
+        for (_, name) in modifiers.iter().filter(|(enabled, _)| *enabled) {
+            write!(f, "{}", name)?;
+        }
+
+        match self.key.chars().count() {
+            1 => write!(f, "{}", self.key.to_uppercase()),
+            _ => write!(f, "{}", self.key),
+        }
+    }
+}
+
+/// Command which can be registered in [`Commands`].
+pub struct Command {
+    id: String,
+    label: String,
+    shortcut: Option<Shortcut>,
+    action: Rc<dyn Fn()>,
+    enabled: Rc<dyn Fn() -> bool>,
+}
+
+impl Command {
+    pub fn new(
+        id: impl Into<String>,
+        label: impl Into<String>,
+        action: impl Fn() + 'static,
+    ) -> Self {
+        Command {
+            id: id.into(),
+            label: label.into(),
+            shortcut: None,
+            action: Rc::new(action),
+            enabled: Rc::new(|| true),
+        }
+    }
+
+    /// Panics if `shortcut` can't be parsed by [`Shortcut::parse`].
+    #[track_caller]
+    pub fn shortcut(mut self, shortcut: &str) -> Self {
+        self.shortcut = Some(Shortcut::parse(shortcut).expect("invalid shortcut"));
+        self
+    }
+
+    /// Predicate deciding whether this command can be executed.
+    pub fn enabled_when(mut self, enabled: impl Fn() -> bool + 'static) -> Self {
+        self.enabled = Rc::new(enabled);
+        self
+    }
+}
+
+/// Snapshot of a registered command, used to display it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandInfo {
+    pub id: String,
+    pub label: String,
+    pub shortcut: Option<Shortcut>,
+    pub enabled: bool,
+}
+
+#[derive(Default)]
+pub struct Commands {
+    /// Commands in the order of registration.
+    commands: Vec<Command>,
+    listeners: SlotMap<CommandListenerKey, (Rc<dyn Fn()>, Tracked)>,
+    /// Enablement of commands (by id) when listeners were last notified.
+    enablement: Vec<(String, bool)>,
+}
+
+impl Commands {
+    /// Registers `command`, replacing previously registered command with the
+    /// same id.
+    pub fn register(command: Command) {
+        COMMANDS.with(|c| {
+            let mut c = c.borrow_mut();
+
+            match c.commands.iter_mut().find(|c| c.id == command.id) {
+                Some(existing) => *existing = command,
+                None => c.commands.push(command),
+            }
+        });
+
+        Self::refresh();
+    }
+
+    pub fn unregister(id: &str) {
+        COMMANDS.with(|c| c.borrow_mut().commands.retain(|c| c.id != id));
+
+        Self::refresh();
+    }
+
+    pub fn get(id: &str) -> Option<CommandInfo> {
+        Self::all().into_iter().find(|c| c.id == id)
+    }
+
+    /// All registered commands, in the order of registration.
+    pub fn all() -> Vec<CommandInfo> {
+        let commands = COMMANDS.with(|c| {
+            let c = c.borrow();
+
+            c.commands
+                .iter()
+                .map(|c| {
+                    (
+                        c.id.clone(),
+                        c.label.clone(),
+                        c.shortcut.clone(),
+                        c.enabled.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // Predicates are called outside of the borrow, since they could access
+        // the registry themselves.
+        commands
+            .into_iter()
+            .map(|(id, label, shortcut, enabled)| CommandInfo {
+                id,
+                label,
+                shortcut,
+                enabled: enabled(),
+            })
+            .collect()
+    }
+
+    pub fn is_enabled(id: &str) -> bool {
+        let enabled = COMMANDS.with(|c| {
+            let c = c.borrow();
+            Some(c.commands.iter().find(|c| c.id == id)?.enabled.clone())
+        });
+
+        enabled.map_or(false, |enabled| enabled())
+    }
+
+    /// Executes command of `id` if it is enabled. Returns whether the command
+    /// was executed.
+    pub fn execute(id: &str) -> bool {
+        let command = COMMANDS.with(|c| {
+            let c = c.borrow();
+            let command = c.commands.iter().find(|c| c.id == id)?;
+            Some((command.action.clone(), command.enabled.clone()))
+        });
+
+        match command {
+            Some((action, enabled)) if enabled() => {
+                action();
+                Self::update();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Executes enabled command whose shortcut matches `event`. Returns
+    /// whether any command was executed.
+    pub fn handle_shortcut(event: &KeyEvent) -> bool {
+        let candidates = COMMANDS.with(|c| {
+            let c = c.borrow();
+
+            c.commands
+                .iter()
+                .filter(|c| c.shortcut.as_ref().map_or(false, |s| s.matches(event)))
+                .map(|c| (c.action.clone(), c.enabled.clone()))
+                .collect::<Vec<_>>()
+        });
+
+        for (action, enabled) in candidates {
+            if enabled() {
+                action();
+                Self::update();
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Calls `callback` every time commands or their enablement may have
+    /// changed.
+    pub fn listen(callback: impl Fn() + 'static) -> CommandListenerKey {
+        let tracked = LeakDetector::track("Listener");
+        COMMANDS.with(|c| {
+            c.borrow_mut()
+                .listeners
+                .insert((Rc::new(callback), tracked))
+        })
+    }
+
+    pub fn unlisten(key: CommandListenerKey) {
+        COMMANDS.with(|c| c.borrow_mut().listeners.remove(key));
+    }
+
+    /// Notifies listeners that enablement of commands may have changed. Only
+    /// needed if it changed outside of event handlers, e.g. on a background
+    /// thread, since it's otherwise checked before every frame.
+    pub fn refresh() {
+        let listeners = COMMANDS.with(|c| {
+            let mut c = c.borrow_mut();
+            c.enablement.clear();
+            c.listeners
+                .values()
+                .map(|l| l.0.clone())
+                .collect::<Vec<_>>()
+        });
+
+        for listener in listeners {
+            listener();
+        }
+    }
+
+    /// Evaluates predicates of all commands and notifies listeners if any of
+    /// them changed since listeners were last notified.
+    pub(crate) fn update() {
+        if COMMANDS.with(|c| c.borrow().listeners.is_empty()) {
+            return;
+        }
+
+        let enablement = Self::all()
+            .into_iter()
+            .map(|c| (c.id, c.enabled))
+            .collect::<Vec<_>>();
+
+        let changed = COMMANDS.with(|c| c.borrow().enablement != enablement);
+
+        if changed {
+            Self::refresh();
+            COMMANDS.with(|c| c.borrow_mut().enablement = enablement);
+        }
+    }
+}
+
+thread_local! {
+    static COMMANDS: RefCell<Commands> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use druid_shell::Modifiers;
+
+    /// Key and modifiers (Ctrl, Shift, Alt, Meta) of the parsed shortcut.
+    fn parse(s: &str) -> Option<(String, [bool; 4])> {
+        Shortcut::parse(s).map(|s| (s.key, [s.ctrl, s.shift, s.alt, s.meta]))
+    }
+
+    #[test]
+    fn shortcuts_are_parsed() {
+        let (f, t) = (false, true);
+
+        assert_eq!(parse("Ctrl+S"), Some(("S".to_owned(), [t, f, f, f])));
+        assert_eq!(
+            parse("ctrl + shift+p"),
+            Some(("p".to_owned(), [t, t, f, f]))
+        );
+        assert_eq!(parse("Alt+F4"), Some(("F4".to_owned(), [f, f, t, f])));
+        assert_eq!(parse("Cmd+Option+I"), Some(("I".to_owned(), [f, f, t, t])));
+        assert_eq!(parse("Enter"), Some(("Enter".to_owned(), [f, f, f, f])));
+
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("Ctrl+"), None);
+        assert_eq!(parse("Hyper+K"), None);
+    }
+
+    #[test]
+    fn shortcuts_match_key_and_exact_modifiers() {
+        let event = |key: Key, mods: Modifiers| KeyEvent::for_test(mods, key);
+        let save = Shortcut::parse("Ctrl+S").unwrap();
+
+        assert!(save.matches(&event(Key::Character("s".into()), Modifiers::CONTROL)));
+        assert!(save.matches(&event(Key::Character("S".into()), Modifiers::CONTROL)));
+        assert!(!save.matches(&event(Key::Character("s".into()), Modifiers::empty())));
+        assert!(!save.matches(&event(
+            Key::Character("s".into()),
+            Modifiers::CONTROL | Modifiers::SHIFT
+        )));
+        assert!(!save.matches(&event(Key::Character("d".into()), Modifiers::CONTROL)));
+
+        let close = Shortcut::parse("Alt+F4").unwrap();
+        assert!(close.matches(&event(Key::F4, Modifiers::ALT)));
+        assert!(!close.matches(&event(Key::F5, Modifiers::ALT)));
+    }
+
+    #[test]
+    fn shortcuts_are_displayed() {
+        let shortcut = Shortcut::parse("shift+ctrl+p").unwrap();
+        assert_eq!(shortcut.to_string(), "Ctrl+Shift+P");
+    }
+}
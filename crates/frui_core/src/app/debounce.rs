@@ -0,0 +1,244 @@
+//! Helpers limiting how often event handlers run, e.g. for search-as-you-type
+//! or window resizing.
+//!
+//! Both helpers are meant to be stored in widget state. Calls which are still
+//! pending get cancelled once the helper is dropped.
+
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::prelude::{BuildCx, WidgetState};
+
+use super::timer::{self, Timer};
+
+/// Delays a call until no other call was made for the given duration.
+pub struct Debouncer {
+    delay: Duration,
+    pending: Cell<Option<Timer>>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Debouncer {
+            delay,
+            pending: Cell::new(None),
+        }
+    }
+
+    /// Schedules `callback`, cancelling the previously scheduled one.
+    pub fn call(&self, callback: impl FnOnce() + 'static) {
+        self.replace(Timer::once(self.delay, callback));
+    }
+
+    /// Schedules `callback` which receives the state of the widget of `cx`,
+    /// cancelling the previously scheduled one. Widget is rebuilt afterwards.
+    pub fn call_with_state<T, F>(&self, cx: BuildCx<T>, callback: F)
+    where
+        T: WidgetState,
+        F: FnOnce(&mut T::State) + 'static,
+    {
+        self.replace(cx.timer_once(self.delay, callback));
+    }
+
+    pub fn cancel(&self) {
+        if let Some(timer) = self.pending.take() {
+            timer.cancel();
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.get().map_or(false, |t| t.is_active())
+    }
+
+    fn replace(&self, timer: Timer) {
+        if let Some(previous) = self.pending.replace(Some(timer)) {
+            previous.cancel();
+        }
+    }
+}
+
+impl Drop for Debouncer {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Makes sure that a call is made at most once per the given interval.
+///
+/// Calls made within the interval are not dropped. Instead, the last one of
+/// them is made at the end of the interval.
+pub struct Throttler {
+    interval: Duration,
+    last_call: Rc<Cell<Option<Instant>>>,
+    trailing: Cell<Option<Timer>>,
+}
+
+impl Throttler {
+    pub fn new(interval: Duration) -> Self {
+        Throttler {
+            interval,
+            last_call: Rc::new(Cell::new(None)),
+            trailing: Cell::new(None),
+        }
+    }
+
+    /// Calls `callback` immediately if no call was made within the interval.
+    /// Otherwise schedules it at the end of the interval, replacing previously
+    /// scheduled call.
+    pub fn call(&self, callback: impl FnOnce() + 'static) {
+        let delay = self.delay();
+
+        if delay.is_zero() {
+            self.cancel();
+            self.last_call.set(Some(timer::now()));
+            callback();
+        } else {
+            let last_call = self.last_call.clone();
+
+            self.replace(Timer::once(delay, move || {
+                last_call.set(Some(timer::now()));
+                callback();
+            }));
+        }
+    }
+
+    /// Like [`call`](Self::call), but `callback` receives the state of the
+    /// widget of `cx`. Since that state may be borrowed at the moment of this
+    /// call, `callback` is always made from the scheduler, at the earliest on
+    /// its next tick.
+    pub fn call_with_state<T, F>(&self, cx: BuildCx<T>, callback: F)
+    where
+        T: WidgetState,
+        F: FnOnce(&mut T::State) + 'static,
+    {
+        let last_call = self.last_call.clone();
+
+        self.replace(cx.timer_once(self.delay(), move |state| {
+            last_call.set(Some(timer::now()));
+            callback(state);
+        }));
+    }
+
+    /// Cancels the scheduled call, if any.
+    pub fn cancel(&self) {
+        if let Some(timer) = self.trailing.take() {
+            timer.cancel();
+        }
+    }
+
+    /// Time until the next call can be made.
+    fn delay(&self) -> Duration {
+        match self.last_call.get() {
+            Some(last_call) => (last_call + self.interval).saturating_duration_since(timer::now()),
+            None => Duration::ZERO,
+        }
+    }
+
+    fn replace(&self, timer: Timer) {
+        if let Some(previous) = self.trailing.replace(Some(timer)) {
+            previous.cancel();
+        }
+    }
+}
+
+impl Drop for Throttler {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::timer::{skip_time, Timers};
+    use std::cell::RefCell;
+
+    /// Advances the clock of timers by `ms` milliseconds and fires due timers.
+    fn advance(ms: u64) {
+        skip_time(Duration::from_millis(ms));
+        Timers::fire_due();
+    }
+
+    fn recorder() -> (Rc<RefCell<Vec<u32>>>, impl Fn(u32) -> Box<dyn FnOnce()>) {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let calls_ = calls.clone();
+
+        let record = move |n| {
+            let calls = calls_.clone();
+            Box::new(move || calls.borrow_mut().push(n)) as Box<dyn FnOnce()>
+        };
+
+        (calls, record)
+    }
+
+    #[test]
+    fn debouncer_calls_last_callback_after_delay() {
+        let (calls, record) = recorder();
+        let debouncer = Debouncer::new(Duration::from_millis(100));
+
+        debouncer.call(record(1));
+        advance(60);
+        debouncer.call(record(2));
+        advance(60);
+
+        assert!(calls.borrow().is_empty());
+        assert!(debouncer.is_pending());
+
+        advance(40);
+
+        assert_eq!(*calls.borrow(), [2]);
+        assert!(!debouncer.is_pending());
+    }
+
+    #[test]
+    fn cancelled_debouncer_doesnt_call() {
+        let (calls, record) = recorder();
+        let debouncer = Debouncer::new(Duration::from_millis(100));
+
+        debouncer.call(record(1));
+        debouncer.cancel();
+        assert!(!debouncer.is_pending());
+
+        let dropped = Debouncer::new(Duration::from_millis(100));
+        dropped.call(record(2));
+        drop(dropped);
+
+        advance(200);
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn throttler_calls_at_most_once_per_interval() {
+        let (calls, record) = recorder();
+        let throttler = Throttler::new(Duration::from_millis(100));
+
+        throttler.call(record(1));
+        assert_eq!(*calls.borrow(), [1]);
+
+        // Only the last call within the interval is made, at its end.
+        advance(30);
+        throttler.call(record(2));
+        advance(30);
+        throttler.call(record(3));
+        assert_eq!(*calls.borrow(), [1]);
+
+        advance(40);
+        assert_eq!(*calls.borrow(), [1, 3]);
+
+        // Interval starts again with the trailing call.
+        advance(50);
+        throttler.call(record(4));
+        assert_eq!(*calls.borrow(), [1, 3]);
+
+        advance(50);
+        assert_eq!(*calls.borrow(), [1, 3, 4]);
+
+        // Calls after the interval are made immediately.
+        advance(150);
+        throttler.call(record(5));
+        assert_eq!(*calls.borrow(), [1, 3, 4, 5]);
+    }
+}
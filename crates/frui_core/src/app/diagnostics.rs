@@ -0,0 +1,182 @@
+//! Approximate memory retained by the widget tree and by framework caches,
+//! meant to help tracking down leaks in long-running applications.
+//!
+//! Sizes are shallow: heap allocations owned by widget states (e.g. contents
+//! of a `Vec`) aren't accounted for, unless reported by a cache through
+//! [`MemoryDiagnostics::report_cache`].
+
+use std::{cell::RefCell, collections::BTreeMap, fmt, mem::size_of_val};
+
+use super::tree::{Node, NodeRef, WeakNodeRef};
+
+pub struct MemoryDiagnostics;
+
+impl MemoryDiagnostics {
+    /// Memory retained by the widget tree of the current window, or `None` if
+    /// there is no widget tree yet.
+    pub fn report() -> Option<MemoryReport> {
+        Some(MemoryReport::of(&Self::root()?))
+    }
+
+    /// Records the current usage of a cache that isn't part of the widget
+    /// tree (e.g. shaped text layouts or decoded images). Caches should call
+    /// it every time their usage changes.
+    pub fn report_cache(name: &'static str, usage: CacheUsage) {
+        CACHES.with(|c| c.borrow_mut().insert(name, usage));
+    }
+
+    /// Last reported usage of every cache, sorted by name.
+    pub fn caches() -> Vec<(&'static str, CacheUsage)> {
+        CACHES.with(|c| c.borrow().iter().map(|(k, v)| (*k, *v)).collect())
+    }
+
+    /// Root node of the widget tree of the current window.
+    pub(crate) fn root() -> Option<NodeRef> {
+        ROOT.with(|r| r.borrow().as_ref().and_then(|r| r.upgrade()))
+    }
+
+    pub(crate) fn set_root(root: Option<&NodeRef>) {
+        ROOT.with(|r| *r.borrow_mut() = root.map(NodeRef::downgrade));
+    }
+
+    pub(crate) fn is_root(node: &NodeRef) -> bool {
+        ROOT.with(|r| r.borrow().as_ref().map_or(false, |root| root.is(node)))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub entries: usize,
+    /// Approximate number of bytes retained by the cache.
+    pub bytes: usize,
+}
+
+/// Memory retained by a widget and its descendants.
+#[derive(Debug, Clone)]
+pub struct MemoryReport {
+    pub widget: &'static str,
+    /// Number of nodes in this subtree, including the widget itself.
+    pub nodes: usize,
+    /// Approximate number of bytes retained by this subtree, including
+    /// states and render states of all of its widgets.
+    pub bytes: usize,
+    pub children: Vec<MemoryReport>,
+}
+
+impl MemoryReport {
+    fn of(node: &NodeRef) -> MemoryReport {
+        let children: Vec<_> = node.children().iter().map(MemoryReport::of).collect();
+
+        let widget = node.debug_name_short();
+        let inner = node.borrow();
+        let own_bytes = std::mem::size_of::<Node>()
+            + size_of_val(&*inner.state)
+            + size_of_val(&*inner.render_data.state)
+            + size_of_val(&*inner.render_data.parent_data)
+            + inner.children_capacity() * std::mem::size_of::<NodeRef>();
+
+        MemoryReport {
+            widget,
+            nodes: 1 + children.iter().map(|c| c.nodes).sum::<usize>(),
+            bytes: own_bytes + children.iter().map(|c| c.bytes).sum::<usize>(),
+            children,
+        }
+    }
+
+    /// First subtree (in depth-first order) whose root widget is named
+    /// `widget`, e.g. `"Column"`.
+    pub fn find(&self, widget: &str) -> Option<&MemoryReport> {
+        if self.widget == widget {
+            return Some(self);
+        }
+
+        self.children.iter().find_map(|c| c.find(widget))
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{} ({} nodes, {} B)",
+            "",
+            self.widget,
+            self.nodes,
+            self.bytes,
+            indent = depth * 2
+        )?;
+
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    /// Prints the subtree with one widget per line, indented by depth.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+thread_local! {
+    static ROOT: RefCell<Option<WeakNodeRef>> = RefCell::new(None);
+    static CACHES: RefCell<BTreeMap<&'static str, CacheUsage>> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tree() -> MemoryReport {
+        let leaf = |widget| MemoryReport {
+            widget,
+            nodes: 1,
+            bytes: 8,
+            children: Vec::new(),
+        };
+
+        MemoryReport {
+            widget: "Column",
+            nodes: 3,
+            bytes: 24,
+            children: vec![leaf("Text"), leaf("Image")],
+        }
+    }
+
+    #[test]
+    fn find_returns_first_matching_subtree() {
+        let tree = tree();
+
+        assert_eq!(tree.find("Column").unwrap().nodes, 3);
+        assert_eq!(tree.find("Image").unwrap().widget, "Image");
+        assert!(tree.find("Row").is_none());
+    }
+
+    #[test]
+    fn display_indents_children() {
+        assert_eq!(
+            tree().to_string(),
+            "Column (3 nodes, 24 B)\n  Text (1 nodes, 8 B)\n  Image (1 nodes, 8 B)\n"
+        );
+    }
+
+    #[test]
+    fn caches_are_sorted_by_name() {
+        let usage = CacheUsage {
+            entries: 1,
+            bytes: 16,
+        };
+        MemoryDiagnostics::report_cache("text layouts", usage);
+        MemoryDiagnostics::report_cache("decoded images", CacheUsage::default());
+        MemoryDiagnostics::report_cache("text layouts", usage);
+
+        assert_eq!(
+            MemoryDiagnostics::caches(),
+            [
+                ("decoded images", CacheUsage::default()),
+                ("text layouts", usage)
+            ]
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! C ABI of [`Engine`], declared in `include/frui.h`.
+//!
+//! Only the application knows its root widget, so the application crate
+//! (built as a `staticlib` or `cdylib`) exports the constructor itself:
+//!
+//! ```ignore
+//! #[no_mangle]
+//! pub extern "C" fn my_app_create(width: f64, height: f64, scale: f64) -> *mut Engine {
+//!     Engine::new(App, Size::new(width, height), scale)
+//!         .map_or(std::ptr::null_mut(), Engine::into_raw)
+//! }
+//! ```
+//!
+//! The rest of the functions are exported by frui. Each of them has to be
+//! called on the thread the engine was created on, with an engine returned
+//! by [`Engine::into_raw`] which wasn't destroyed yet. Null engines are
+//! ignored.
+
+use std::{
+    ffi::{c_char, c_void, CStr},
+    ptr,
+};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    kurbo::{Point, Vec2},
+    KeyEvent, Modifiers, MouseButton, MouseButtons, MouseEvent,
+};
+
+use crate::render::Size;
+
+use super::Engine;
+
+pub const FRUI_POINTER_DOWN: u32 = 0;
+pub const FRUI_POINTER_UP: u32 = 1;
+pub const FRUI_POINTER_MOVE: u32 = 2;
+pub const FRUI_POINTER_WHEEL: u32 = 3;
+
+pub const FRUI_BUTTON_LEFT: u32 = 1;
+pub const FRUI_BUTTON_RIGHT: u32 = 2;
+pub const FRUI_BUTTON_MIDDLE: u32 = 4;
+
+pub const FRUI_MOD_SHIFT: u32 = 1;
+pub const FRUI_MOD_CTRL: u32 = 2;
+pub const FRUI_MOD_ALT: u32 = 4;
+pub const FRUI_MOD_META: u32 = 8;
+
+/// Frame rendered by [`frui_engine_render`]. Pixels are premultiplied RGBA,
+/// row by row, and stay valid until the next frame is rendered or the engine
+/// is destroyed.
+#[repr(C)]
+pub struct FruiFrame {
+    pub width: usize,
+    pub height: usize,
+    /// Length of a row in bytes.
+    pub stride: usize,
+    pub pixels: *const u8,
+}
+
+/// # Safety
+///
+/// See the [module documentation](self). The engine mustn't be used after.
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_destroy(engine: *mut Engine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Sets the callback called with `user_data` when the engine needs a new
+/// frame. See [`Engine::set_wake_callback`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_set_wake_callback(
+    engine: *mut Engine,
+    callback: Option<extern "C" fn(*mut c_void)>,
+    user_data: *mut c_void,
+) {
+    if let (Some(engine), Some(callback)) = (engine.as_mut(), callback) {
+        engine.set_wake_callback(move || callback(user_data));
+    }
+}
+
+/// # Safety
+///
+/// See the [module documentation](self).
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_resize(
+    engine: *mut Engine,
+    width: f64,
+    height: f64,
+    scale: f64,
+) {
+    if let Some(engine) = engine.as_mut() {
+        engine.resize(Size::new(width, height), scale);
+    }
+}
+
+/// Pointer event passed to [`frui_engine_pointer_event`].
+#[repr(C)]
+pub struct FruiPointerEvent {
+    /// One of `FRUI_POINTER_*`.
+    pub kind: u32,
+    /// Position in display points.
+    pub x: f64,
+    pub y: f64,
+    /// Button which was pressed or released, one of `FRUI_BUTTON_*`.
+    pub button: u32,
+    /// All pressed buttons, made of `FRUI_BUTTON_*` flags.
+    pub buttons: u32,
+    pub wheel_x: f64,
+    pub wheel_y: f64,
+    /// Made of `FRUI_MOD_*` flags.
+    pub mods: u32,
+}
+
+/// # Safety
+///
+/// See the [module documentation](self).
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_pointer_event(engine: *mut Engine, event: FruiPointerEvent) {
+    let engine = match engine.as_mut() {
+        Some(engine) => engine,
+        None => return,
+    };
+
+    let mouse_event = MouseEvent {
+        pos: Point::new(event.x, event.y),
+        buttons: mouse_buttons(event.buttons),
+        mods: modifiers(event.mods),
+        count: (event.kind == FRUI_POINTER_DOWN) as u8,
+        focus: false,
+        button: mouse_button(event.button),
+        wheel_delta: Vec2::new(event.wheel_x, event.wheel_y),
+    };
+
+    match event.kind {
+        FRUI_POINTER_DOWN => engine.mouse_down(&mouse_event),
+        FRUI_POINTER_UP => engine.mouse_up(&mouse_event),
+        FRUI_POINTER_MOVE => engine.mouse_move(&mouse_event),
+        FRUI_POINTER_WHEEL => engine.wheel(&mouse_event),
+        kind => log::warn!("unknown pointer event kind: {}", kind),
+    }
+}
+
+/// Passes key event. `key` is a UTF-8 key value as defined by the W3C
+/// (e.g. `"a"` or `"Enter"`). Returns `false` if a key press wasn't handled
+/// and should be turned into text input by the host.
+///
+/// # Safety
+///
+/// See the [module documentation](self). `key` must be a null-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_key_event(
+    engine: *mut Engine,
+    down: bool,
+    key: *const c_char,
+    mods: u32,
+    repeat: bool,
+) -> bool {
+    let engine = match engine.as_mut() {
+        Some(engine) => engine,
+        None => return false,
+    };
+
+    let key = match str_arg(key) {
+        Some(key) => key.parse().unwrap_or(Key::Unidentified),
+        None => Key::Unidentified,
+    };
+
+    let event = KeyEvent {
+        state: match down {
+            true => KeyState::Down,
+            false => KeyState::Up,
+        },
+        key,
+        mods: modifiers(mods),
+        repeat,
+        ..Default::default()
+    };
+
+    match down {
+        true => engine.key_down(event),
+        false => {
+            engine.key_up(event);
+            true
+        }
+    }
+}
+
+/// See [`Engine::insert_text`].
+///
+/// # Safety
+///
+/// See the [module documentation](self). `text` must be a null-terminated
+/// UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_insert_text(engine: *mut Engine, text: *const c_char) -> bool {
+    match (engine.as_mut(), str_arg(text)) {
+        (Some(engine), Some(text)) => engine.insert_text(text),
+        _ => false,
+    }
+}
+
+/// # Safety
+///
+/// See the [module documentation](self).
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_set_focused(engine: *mut Engine, focused: bool) {
+    if let Some(engine) = engine.as_mut() {
+        engine.set_focused(focused);
+    }
+}
+
+/// See [`Engine::pump`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_pump(engine: *mut Engine) -> bool {
+    engine.as_mut().map_or(false, Engine::pump)
+}
+
+/// Renders the next frame into `frame`. Returns `false` if rendering failed.
+///
+/// # Safety
+///
+/// See the [module documentation](self). `frame` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn frui_engine_render(engine: *mut Engine, frame: *mut FruiFrame) -> bool {
+    let engine = match engine.as_mut() {
+        Some(engine) => engine,
+        None => return false,
+    };
+
+    match engine.render() {
+        Ok(image) => {
+            ptr::write(
+                frame,
+                FruiFrame {
+                    width: image.width(),
+                    height: image.height(),
+                    stride: image.width() * 4,
+                    pixels: image.raw_pixels().as_ptr(),
+                },
+            );
+
+            true
+        }
+        Err(e) => {
+            log::error!("{}", e);
+            false
+        }
+    }
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Option<&'a str> {
+    match s.is_null() {
+        true => None,
+        false => CStr::from_ptr(s).to_str().ok(),
+    }
+}
+
+fn mouse_button(button: u32) -> MouseButton {
+    match button {
+        FRUI_BUTTON_LEFT => MouseButton::Left,
+        FRUI_BUTTON_RIGHT => MouseButton::Right,
+        FRUI_BUTTON_MIDDLE => MouseButton::Middle,
+        _ => MouseButton::None,
+    }
+}
+
+fn mouse_buttons(buttons: u32) -> MouseButtons {
+    [FRUI_BUTTON_LEFT, FRUI_BUTTON_RIGHT, FRUI_BUTTON_MIDDLE]
+        .into_iter()
+        .filter(|b| buttons & b != 0)
+        .fold(MouseButtons::new(), |all, b| all.with(mouse_button(b)))
+}
+
+fn modifiers(mods: u32) -> Modifiers {
+    let mut modifiers = Modifiers::default();
+    modifiers.set(Modifiers::SHIFT, mods & FRUI_MOD_SHIFT != 0);
+    modifiers.set(Modifiers::CONTROL, mods & FRUI_MOD_CTRL != 0);
+    modifiers.set(Modifiers::ALT, mods & FRUI_MOD_ALT != 0);
+    modifiers.set(Modifiers::META, mods & FRUI_MOD_META != 0);
+    modifiers
+}
@@ -0,0 +1,272 @@
+//! Hosting frui inside applications written in other languages.
+//!
+//! An [`Engine`] runs a widget tree without a window of its own. The host
+//! forwards input events to it and asks it to render frames into pixel
+//! buffers, which it then shows e.g. as a texture. This way frui can be
+//! adopted gradually, one view of an existing application at a time. The
+//! engine is exposed to C, C++ and Swift through the functions of [`ffi`],
+//! declared in `include/frui.h`.
+//!
+//! Engines aren't thread-safe: an engine has to be used on the thread it was
+//! created on and only one engine can exist per thread.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+use druid_shell::{
+    kurbo::Rect,
+    piet::{Device, ImageBuf, ImageFormat, RenderContext},
+    IdleToken, KeyEvent, MouseEvent, Region, TimerToken,
+};
+
+use crate::{
+    app::{
+        instrumentation::Instrumentation,
+        listeners::lifecycle,
+        metrics::WindowMetrics,
+        plugins::Plugins,
+        runner::{
+            window_handler::{WindowHandler, WINDOW_HANDLE},
+            FruiWindowHandler, WindowHandle,
+        },
+        text_input,
+        ticker::Tickers,
+        TEXT_FACTORY,
+    },
+    prelude::Widget,
+    render::Size,
+};
+
+pub mod ffi;
+
+#[derive(Debug, Clone)]
+pub struct EmbedError(String);
+
+impl fmt::Display for EmbedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "embedded engine failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for EmbedError {}
+
+impl From<druid_shell::piet::Error> for EmbedError {
+    fn from(e: druid_shell::piet::Error) -> Self {
+        EmbedError(e.to_string())
+    }
+}
+
+/// Widget tree hosted by another application.
+///
+/// ```ignore
+/// let mut engine = Engine::new(App, Size::new(800., 600.), 2.)?;
+/// engine.set_wake_callback(|| host.schedule_frame());
+///
+/// // On every display refresh:
+/// if engine.pump() {
+///     let frame = engine.render()?;
+///     host.upload_texture(frame.raw_pixels(), frame.width(), frame.height());
+/// }
+/// ```
+pub struct Engine {
+    handler: WindowHandler,
+    device: Device,
+    /// Size of the hosting view in display points.
+    size: Size,
+    scale: f64,
+    /// Last rendered frame, kept alive for hosts reading it through [`ffi`].
+    frame: Option<ImageBuf>,
+}
+
+impl Engine {
+    /// Builds `widget` for a view of `size` (in display points) shown with
+    /// `scale` pixels per point.
+    pub fn new<W: Widget + 'static>(widget: W, size: Size, scale: f64) -> Result<Self, EmbedError> {
+        if ENGINE.with(|e| e.active.get()) {
+            return Err(EmbedError("an engine already exists on this thread".into()));
+        }
+
+        Instrumentation::start();
+
+        let mut device = Device::new()?;
+
+        // Same as offscreen rendering, text is laid out with the factory of
+        // a bitmap target.
+        let text = {
+            let mut target = device.bitmap_target(1, 1, 1.)?;
+            let mut canvas = target.render_context();
+            let text = canvas.text().clone();
+            canvas.finish()?;
+            text
+        };
+
+        ENGINE.with(|e| e.active.set(true));
+
+        let mut handler = WindowHandler::new(widget);
+        handler.connect(&WindowHandle::default());
+
+        TEXT_FACTORY.with(|f| f.set(text));
+
+        let mut engine = Engine {
+            handler,
+            device,
+            size,
+            scale,
+            frame: None,
+        };
+
+        engine.resize(size, scale);
+
+        Ok(engine)
+    }
+
+    /// Moves the engine to the heap, to be passed to the functions of
+    /// [`ffi`], which take ownership of it.
+    pub fn into_raw(self) -> *mut Engine {
+        Box::into_raw(Box::new(self))
+    }
+
+    /// Sets the callback called when the engine needs a new frame, e.g.
+    /// because state of a widget changed. The callback is called at most
+    /// once until the next [`render`](Self::render) and should only schedule
+    /// the rendering, since it's called while the widget tree is borrowed.
+    pub fn set_wake_callback(&mut self, callback: impl Fn() + 'static) {
+        ENGINE.with(|e| *e.on_wake.borrow_mut() = Some(Rc::new(callback)));
+    }
+
+    pub fn resize(&mut self, size: Size, scale: f64) {
+        self.size = size;
+        self.scale = scale;
+
+        self.handler.size(size.into());
+
+        // The window handler takes the scale from the window, which the
+        // engine doesn't have.
+        if size.width * size.height > 0. {
+            WindowMetrics::set_size(size, scale);
+        }
+
+        wake();
+    }
+
+    pub fn mouse_down(&mut self, event: &MouseEvent) {
+        self.handler.mouse_down(event);
+    }
+
+    pub fn mouse_up(&mut self, event: &MouseEvent) {
+        self.handler.mouse_up(event);
+    }
+
+    pub fn mouse_move(&mut self, event: &MouseEvent) {
+        self.handler.mouse_move(event);
+    }
+
+    pub fn wheel(&mut self, event: &MouseEvent) {
+        self.handler.wheel(event);
+    }
+
+    /// Returns `false` if the event wasn't handled, in which case the host
+    /// should turn it into text input passed to
+    /// [`insert_text`](Self::insert_text).
+    pub fn key_down(&mut self, event: KeyEvent) -> bool {
+        self.handler.key_down(event)
+    }
+
+    pub fn key_up(&mut self, event: KeyEvent) {
+        self.handler.key_up(event);
+    }
+
+    /// Replaces the selection of the focused text field with `text`.
+    /// Returns `false` if no text field is focused.
+    pub fn insert_text(&mut self, text: &str) -> bool {
+        text_input::insert_text(text)
+    }
+
+    /// Informs the engine whether the hosting view has keyboard focus.
+    pub fn set_focused(&mut self, focused: bool) {
+        match focused {
+            true => self.handler.got_focus(),
+            false => self.handler.lost_focus(),
+        }
+    }
+
+    /// Fires due timers and delivers messages posted to channels from other
+    /// threads. Should be called regularly, e.g. on every display refresh.
+    ///
+    /// Returns whether a new frame should be rendered.
+    pub fn pump(&mut self) -> bool {
+        self.handler.timer(TimerToken::next());
+        self.handler.idle(IdleToken::new(0));
+
+        self.needs_frame()
+    }
+
+    pub fn needs_frame(&self) -> bool {
+        ENGINE.with(|e| e.needs_frame.get())
+    }
+
+    /// Rebuilds, lays out and paints the widget tree into an image of the
+    /// size of the view in pixels. Pixels are in premultiplied RGBA format.
+    pub fn render(&mut self) -> Result<&ImageBuf, EmbedError> {
+        let width = (self.size.width * self.scale).ceil().max(1.) as usize;
+        let height = (self.size.height * self.scale).ceil().max(1.) as usize;
+
+        let mut target = self.device.bitmap_target(width, height, self.scale)?;
+
+        {
+            let mut canvas = target.render_context();
+
+            // Running animations need the next frame right away.
+            let animating = !lifecycle::is_suspended() && Tickers::tick();
+            ENGINE.with(|e| e.needs_frame.set(animating));
+
+            let mut region = Region::EMPTY;
+            region.add_rect(Rect::new(0., 0., self.size.width, self.size.height));
+
+            self.handler.paint(&mut canvas, &region);
+
+            canvas.finish()?;
+        }
+
+        let frame = target.to_image_buf(ImageFormat::RgbaPremul)?;
+        Ok(self.frame.insert(frame))
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        Plugins::exit();
+
+        WINDOW_HANDLE.with(|h| h.borrow_mut().take());
+        ENGINE.with(|e| {
+            e.active.set(false);
+            e.on_wake.borrow_mut().take();
+        });
+    }
+}
+
+/// Requests a new frame of the engine of this thread.
+pub(crate) fn wake() {
+    let callback = ENGINE.with(|e| match e.needs_frame.replace(true) {
+        true => None,
+        false => e.on_wake.borrow().clone(),
+    });
+
+    if let Some(callback) = callback {
+        callback();
+    }
+}
+
+#[derive(Default)]
+struct EngineShared {
+    active: Cell<bool>,
+    needs_frame: Cell<bool>,
+    on_wake: RefCell<Option<Rc<dyn Fn()>>>,
+}
+
+thread_local! {
+    static ENGINE: EngineShared = Default::default();
+}
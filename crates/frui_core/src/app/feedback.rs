@@ -0,0 +1,118 @@
+//! Sound and haptic feedback of user interactions.
+//!
+//! Widgets report interactions through [`Feedback::perform`], and the
+//! registered [`FeedbackService`] decides how (and if) to play them, e.g. by
+//! vibrating a phone or playing the system click sound. Without a registered
+//! service, feedback is ignored.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use super::services::Services;
+
+/// Kind of interaction feedback is performed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeedbackKind {
+    /// Button or menu item was activated.
+    Click,
+    /// Long-press gesture was recognized.
+    LongPress,
+    /// Value of a picker or selection changed.
+    SelectionChanged,
+    /// Light haptic impact, e.g. when a dragged item snaps into place.
+    LightImpact,
+    /// Heavy haptic impact, e.g. when reaching the end of a scrollable.
+    HeavyImpact,
+}
+
+/// Platform backend of [`Feedback`].
+pub trait FeedbackService {
+    fn perform(&self, kind: FeedbackKind);
+}
+
+pub struct Feedback;
+
+impl Feedback {
+    pub fn set_service(service: impl FeedbackService + 'static) {
+        Services::register::<Box<dyn FeedbackService>>(Box::new(service));
+    }
+
+    /// Plays feedback of the given kind, unless feedback was disabled.
+    pub fn perform(kind: FeedbackKind) {
+        if !Self::is_enabled() {
+            return;
+        }
+
+        if let Some(service) = Services::get::<Box<dyn FeedbackService>>() {
+            service.perform(kind);
+        }
+    }
+
+    /// Enables or disables feedback of the whole application, e.g. according
+    /// to the user's settings.
+    pub fn set_enabled(enabled: bool) {
+        ENABLED.with(|e| e.set(enabled));
+    }
+
+    pub fn is_enabled() -> bool {
+        ENABLED.with(|e| e.get())
+    }
+}
+
+thread_local! {
+    static ENABLED: Cell<bool> = Cell::new(true);
+}
+
+/// [`FeedbackService`] recording performed feedback instead of playing it,
+/// used in tests.
+///
+/// ```ignore
+/// let recording = RecordingFeedback::new();
+/// Feedback::set_service(recording.clone());
+/// // ...
+/// assert_eq!(recording.performed(), vec![FeedbackKind::Click]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RecordingFeedback {
+    performed: Rc<RefCell<Vec<FeedbackKind>>>,
+}
+
+impl RecordingFeedback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn performed(&self) -> Vec<FeedbackKind> {
+        self.performed.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.performed.borrow_mut().clear();
+    }
+}
+
+impl FeedbackService for RecordingFeedback {
+    fn perform(&self, kind: FeedbackKind) {
+        self.performed.borrow_mut().push(kind);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn disabled_feedback_is_not_performed() {
+        let recording = RecordingFeedback::new();
+        Feedback::set_service(recording.clone());
+
+        Feedback::perform(FeedbackKind::Click);
+        Feedback::set_enabled(false);
+        Feedback::perform(FeedbackKind::LongPress);
+        Feedback::set_enabled(true);
+
+        assert_eq!(recording.performed(), vec![FeedbackKind::Click]);
+    }
+}
@@ -0,0 +1,639 @@
+//! Keyboard focus: which widget receives key events, and traversal between
+//! focusable widgets with Tab and Shift+Tab.
+//!
+//! Each focusable widget owns a [`FocusNode`], which it attaches every time
+//! it's laid out (see [`FocusNode::attach`]). Nodes attached while laying out
+//! children of another node are its descendants, and the order in which they
+//! were attached is the order of traversal.
+//!
+//! Key events (both presses and releases) are first passed to the key
+//! handler of the focused node, then to handlers of its ancestors, until one
+//! of them handles the event. While nothing is focused, they are passed to
+//! handlers of nodes which can't be focused and which aren't attached within
+//! a node which can (e.g. of application-wide shortcuts), deepest first. Tab
+//! presses which weren't handled move focus to the next node within the
+//! closest [scope](FocusNode::scope) of the focused node.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    KeyEvent,
+};
+
+use super::leaks::{LeakDetector, Tracked};
+
+/// Node of the focus tree, which is owned by a focusable widget. Widgets can
+/// be passed a node to request focus for them from elsewhere.
+///
+/// Clones of a node refer to the same node.
+#[derive(Clone)]
+pub struct FocusNode {
+    inner: Rc<NodeInner>,
+}
+
+struct NodeInner {
+    is_scope: bool,
+    can_request_focus: Cell<bool>,
+    skip_traversal: Cell<bool>,
+    key_handler: Cell<Option<*const dyn Fn(&KeyEvent) -> bool>>,
+    /// Descendant of a scope which was focused most recently.
+    last_focused: RefCell<Weak<NodeInner>>,
+    listeners: RefCell<Vec<(FocusListenerKey, Rc<dyn Fn()>, Tracked)>>,
+    next_key: Cell<usize>,
+    _tracked: Tracked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusListenerKey(usize);
+
+impl FocusNode {
+    pub fn new() -> Self {
+        Self::with_scope(false)
+    }
+
+    /// Node of a scope, which limits traversal to its descendants while one
+    /// of them is focused (e.g. to fields of a dialog). Requesting focus for
+    /// a scope focuses its descendant which was focused last, or the first
+    /// one.
+    pub fn scope() -> Self {
+        Self::with_scope(true)
+    }
+
+    fn with_scope(is_scope: bool) -> Self {
+        FocusNode {
+            inner: Rc::new(NodeInner {
+                is_scope,
+                can_request_focus: Cell::new(true),
+                skip_traversal: Cell::new(false),
+                key_handler: Cell::new(None),
+                last_focused: RefCell::new(Weak::new()),
+                listeners: RefCell::new(Vec::new()),
+                next_key: Cell::new(0),
+                _tracked: LeakDetector::track("FocusNode"),
+            }),
+        }
+    }
+
+    pub fn is_scope(&self) -> bool {
+        self.inner.is_scope
+    }
+
+    /// Whether this node is the focused one.
+    pub fn is_focused(&self) -> bool {
+        FOCUS.with(|f| f.borrow().focused.as_ptr() == Rc::as_ptr(&self.inner))
+    }
+
+    /// Whether this node or one of its descendants is focused.
+    pub fn has_focus(&self) -> bool {
+        FOCUS.with(|f| {
+            let focus = f.borrow();
+            let focused = match focus.focused.upgrade() {
+                Some(focused) => focused,
+                None => return false,
+            };
+
+            focus
+                .ancestors(&focused)
+                .iter()
+                .any(|node| Rc::ptr_eq(node, &self.inner))
+        })
+    }
+
+    /// Focuses this node, unless it can't request focus.
+    pub fn request_focus(&self) {
+        if !self.inner.can_request_focus.get() {
+            return;
+        }
+
+        let target = match self.inner.is_scope {
+            true => FOCUS.with(|f| f.borrow().scope_target(&self.inner)),
+            false => Some(self.inner.clone()),
+        };
+
+        if let Some(target) = target {
+            set_focus(Some(target));
+        }
+    }
+
+    /// Removes focus from this node or its focused descendant.
+    pub fn unfocus(&self) {
+        if self.has_focus() {
+            set_focus(None);
+        }
+    }
+
+    pub fn can_request_focus(&self) -> bool {
+        self.inner.can_request_focus.get()
+    }
+
+    /// Disabled widgets can't be focused. Disabling the focused node removes
+    /// focus from it.
+    pub fn set_can_request_focus(&self, can_request_focus: bool) {
+        self.inner.can_request_focus.set(can_request_focus);
+
+        if !can_request_focus && self.is_focused() {
+            set_focus(None);
+        }
+    }
+
+    /// Nodes skipped by traversal can still be focused with
+    /// [`request_focus`](Self::request_focus), e.g. on click.
+    pub fn set_skip_traversal(&self, skip_traversal: bool) {
+        self.inner.skip_traversal.set(skip_traversal);
+    }
+
+    /// Sets the handler of key events received while this node or one of its
+    /// descendants is focused, returning whether the event was handled.
+    ///
+    /// ## Safety:
+    ///
+    /// Value `handler` points to must live until the handler is replaced or
+    /// removed with [`remove_key_handler`](Self::remove_key_handler).
+    pub unsafe fn set_key_handler<'a>(&self, handler: *const (dyn Fn(&KeyEvent) -> bool + 'a)) {
+        self.inner
+            .key_handler
+            .set(Some(std::mem::transmute(handler)));
+    }
+
+    pub fn remove_key_handler(&self) {
+        self.inner.key_handler.set(None);
+    }
+
+    /// Attaches this node to the focus tree for the duration of the current
+    /// layout. Must be called by the widget owning the node every time it's
+    /// laid out, with its children laid out in `layout_children`, so that
+    /// nodes of descendants are attached as its descendants.
+    pub fn attach<R>(&self, layout_children: impl FnOnce() -> R) -> R {
+        FOCUS.with(|f| {
+            let mut focus = f.borrow_mut();
+            let parent = focus.stack.last().copied();
+
+            focus.attached.push(Attached {
+                node: Rc::downgrade(&self.inner),
+                parent,
+            });
+
+            let index = focus.attached.len() - 1;
+            focus.stack.push(index);
+        });
+
+        let r = layout_children();
+
+        FOCUS.with(|f| f.borrow_mut().stack.pop());
+
+        r
+    }
+
+    /// Calls `callback` every time this node gains or loses focus, including
+    /// focus of its descendants.
+    pub fn listen(&self, callback: impl Fn() + 'static) -> FocusListenerKey {
+        let key = FocusListenerKey(self.inner.next_key.get());
+        self.inner.next_key.set(key.0 + 1);
+
+        let mut listeners = self.inner.listeners.borrow_mut();
+        listeners.push((key, Rc::new(callback), LeakDetector::track("Listener")));
+
+        key
+    }
+
+    pub fn unlisten(&self, key: FocusListenerKey) {
+        let mut listeners = self.inner.listeners.borrow_mut();
+        listeners.retain(|(k, ..)| *k != key);
+    }
+
+    /// Whether both handles refer to the same node.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// This node followed by its ancestors, from the closest one, as attached
+    /// during the last layout.
+    pub fn chain(&self) -> Vec<FocusNode> {
+        let chain = FOCUS.with(|f| f.borrow().ancestors(&self.inner));
+
+        chain.into_iter().map(|inner| FocusNode { inner }).collect()
+    }
+}
+
+impl Default for FocusNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for FocusNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl std::fmt::Debug for FocusNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FocusNode")
+            .field("is_scope", &self.inner.is_scope)
+            .field("is_focused", &self.is_focused())
+            .finish()
+    }
+}
+
+/// Access to the focused node of the window.
+pub struct FocusManager;
+
+impl FocusManager {
+    pub fn focused() -> Option<FocusNode> {
+        let inner = FOCUS.with(|f| f.borrow().focused.upgrade())?;
+        Some(FocusNode { inner })
+    }
+
+    /// Removes focus from the focused node.
+    pub fn unfocus() {
+        set_focus(None);
+    }
+
+    /// Focuses the next node in traversal order, returning whether focus
+    /// moved.
+    pub fn next() -> bool {
+        traverse(true)
+    }
+
+    /// Focuses the previous node in traversal order, returning whether focus
+    /// moved.
+    pub fn previous() -> bool {
+        traverse(false)
+    }
+
+    /// Should be called before the widget tree is laid out, which attaches
+    /// nodes again.
+    pub(crate) fn begin_layout() {
+        FOCUS.with(|f| {
+            let mut focus = f.borrow_mut();
+            focus.attached.clear();
+            focus.stack.clear();
+        });
+    }
+
+    /// Passes `event` to handlers of the focused node and its ancestors (or
+    /// of unfocusable nodes outside of focusable ones), and moves focus on
+    /// Tab if it wasn't handled. Returns whether the event was handled.
+    pub(crate) fn handle_key(event: &KeyEvent) -> bool {
+        let chain = FOCUS.with(|f| {
+            let focus = f.borrow();
+
+            match focus.focused.upgrade() {
+                Some(focused) => focus.ancestors(&focused),
+                None => focus.unfocused_chain(),
+            }
+        });
+
+        for node in chain {
+            if let Some(handler) = node.key_handler.get() {
+                // Safety: `handler` is valid as ensured by callers of
+                // `set_key_handler`.
+                if unsafe { (*handler)(event) } {
+                    return true;
+                }
+            }
+        }
+
+        let mods = event.mods;
+        if event.state == KeyState::Down
+            && event.key == Key::Tab
+            && !mods.ctrl()
+            && !mods.alt()
+            && !mods.meta()
+        {
+            return traverse(!mods.shift());
+        }
+
+        false
+    }
+}
+
+struct Attached {
+    node: Weak<NodeInner>,
+    /// Index of the parent in attached nodes.
+    parent: Option<usize>,
+}
+
+#[derive(Default)]
+struct Focus {
+    focused: Weak<NodeInner>,
+    /// Nodes attached during the last layout, in layout order.
+    attached: Vec<Attached>,
+    /// Nodes being laid out, as indices of attached nodes.
+    stack: Vec<usize>,
+}
+
+impl Focus {
+    fn index_of(&self, node: &Rc<NodeInner>) -> Option<usize> {
+        self.attached
+            .iter()
+            .position(|a| a.node.as_ptr() == Rc::as_ptr(node))
+    }
+
+    /// `node` followed by its attached ancestors, from the closest one.
+    fn ancestors(&self, node: &Rc<NodeInner>) -> Vec<Rc<NodeInner>> {
+        let mut ancestors = vec![node.clone()];
+        let mut index = self.index_of(node).and_then(|i| self.attached[i].parent);
+
+        while let Some(i) = index {
+            if let Some(ancestor) = self.attached[i].node.upgrade() {
+                ancestors.push(ancestor);
+            }
+
+            index = self.attached[i].parent;
+        }
+
+        ancestors
+    }
+
+    /// Nodes receiving key events while nothing is focused: nodes which
+    /// can't be focused themselves (scopes aside) and whose ancestors can't
+    /// either, deepest first.
+    fn unfocused_chain(&self) -> Vec<Rc<NodeInner>> {
+        let mut unfocusable = Vec::with_capacity(self.attached.len());
+
+        // Parents are attached before their descendants.
+        for attached in self.attached.iter() {
+            let node = attached.node.upgrade();
+            let is_unfocusable = node.map_or(true, |n| n.is_scope || !n.can_request_focus.get());
+            let in_unfocusable = attached.parent.map_or(true, |p| unfocusable[p]);
+
+            unfocusable.push(is_unfocusable && in_unfocusable);
+        }
+
+        self.attached
+            .iter()
+            .zip(unfocusable)
+            .rev()
+            .filter(|(_, unfocusable)| *unfocusable)
+            .filter_map(|(a, _)| a.node.upgrade())
+            .collect()
+    }
+
+    /// Whether the node attached at `index` is a descendant of the node
+    /// attached at `ancestor`.
+    fn is_descendant(&self, index: usize, ancestor: usize) -> bool {
+        let mut parent = self.attached[index].parent;
+
+        while let Some(i) = parent {
+            if i == ancestor {
+                return true;
+            }
+
+            parent = self.attached[i].parent;
+        }
+
+        false
+    }
+
+    /// Attached nodes which can be focused by traversal, within `scope` (an
+    /// index of an attached node) if there is one.
+    fn traversable(&self, scope: Option<usize>) -> Vec<Rc<NodeInner>> {
+        self.attached
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| scope.map_or(true, |scope| self.is_descendant(*i, scope)))
+            .filter_map(|(_, a)| a.node.upgrade())
+            .filter(|node| {
+                !node.is_scope && node.can_request_focus.get() && !node.skip_traversal.get()
+            })
+            .collect()
+    }
+
+    /// Node focused when focus is requested for `scope`.
+    fn scope_target(&self, scope: &Rc<NodeInner>) -> Option<Rc<NodeInner>> {
+        let index = self.index_of(scope);
+
+        let last = scope.last_focused.borrow().upgrade();
+        if let Some(last) = last.filter(|n| n.can_request_focus.get()) {
+            return Some(last);
+        }
+
+        match index {
+            Some(index) => self.traversable(Some(index)).into_iter().next(),
+            None => None,
+        }
+    }
+}
+
+thread_local! {
+    static FOCUS: RefCell<Focus> = Default::default();
+}
+
+fn set_focus(node: Option<Rc<NodeInner>>) {
+    let changed = FOCUS.with(|f| {
+        let mut focus = f.borrow_mut();
+
+        let previous = focus.focused.upgrade();
+        if previous.as_ref().map(Rc::as_ptr) == node.as_ref().map(Rc::as_ptr) {
+            return Vec::new();
+        }
+
+        focus.focused = node.as_ref().map_or(Weak::new(), Rc::downgrade);
+
+        let chain = |node: &Option<Rc<NodeInner>>| match node {
+            Some(node) => focus.ancestors(node),
+            None => Vec::new(),
+        };
+
+        let (new_chain, old_chain) = (chain(&node), chain(&previous));
+
+        if let Some(node) = &node {
+            for scope in new_chain.iter().filter(|n| n.is_scope) {
+                *scope.last_focused.borrow_mut() = Rc::downgrade(node);
+            }
+        }
+
+        // Nodes which gained or lost focus, either their own or of their
+        // descendants. Common ancestors keep theirs.
+        let contains = |chain: &[Rc<NodeInner>], n: &Rc<NodeInner>| {
+            chain.iter().any(|other| Rc::ptr_eq(other, n))
+        };
+
+        let mut changed: Vec<_> = new_chain
+            .iter()
+            .filter(|n| !contains(&old_chain, n))
+            .chain(old_chain.iter().filter(|n| !contains(&new_chain, n)))
+            .cloned()
+            .collect();
+
+        for primary in node.iter().chain(previous.iter()) {
+            if !contains(&changed, primary) {
+                changed.push(primary.clone());
+            }
+        }
+
+        changed
+    });
+
+    // Listeners are called outside of the borrow, since they may query or
+    // change focus.
+    for node in changed {
+        let listeners: Vec<_> = node
+            .listeners
+            .borrow()
+            .iter()
+            .map(|l| l.1.clone())
+            .collect();
+
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+fn traverse(forward: bool) -> bool {
+    let target = FOCUS.with(|f| {
+        let focus = f.borrow();
+        let focused = focus.focused.upgrade();
+
+        // Traversal is limited to the closest scope of the focused node.
+        let scope = focused.as_ref().and_then(|focused| {
+            let scope = focus
+                .ancestors(focused)
+                .into_iter()
+                .skip(1)
+                .find(|n| n.is_scope)?;
+            focus.index_of(&scope)
+        });
+
+        let nodes = focus.traversable(scope);
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let current =
+            focused.and_then(|focused| nodes.iter().position(|n| Rc::ptr_eq(n, &focused)));
+
+        let index = match (current, forward) {
+            (Some(i), true) => (i + 1) % nodes.len(),
+            (Some(i), false) => (i + nodes.len() - 1) % nodes.len(),
+            (None, true) => 0,
+            (None, false) => nodes.len() - 1,
+        };
+
+        Some(nodes[index].clone())
+    });
+
+    match target {
+        Some(target) => {
+            set_focus(Some(target));
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn traversal_and_scopes() {
+        FocusManager::begin_layout();
+
+        let (a, b, c) = (FocusNode::new(), FocusNode::new(), FocusNode::new());
+        let scope = FocusNode::scope();
+
+        let notified = Rc::new(Cell::new(0));
+        scope.listen({
+            let notified = notified.clone();
+            move || notified.set(notified.get() + 1)
+        });
+
+        a.attach(|| {});
+        scope.attach(|| {
+            b.attach(|| {});
+            c.attach(|| {});
+        });
+
+        a.request_focus();
+        assert!(a.is_focused());
+
+        assert!(FocusManager::next());
+        assert!(b.is_focused() && scope.has_focus() && !scope.is_focused());
+        assert_eq!(notified.get(), 1);
+
+        // Traversal wraps around within the scope.
+        assert!(FocusManager::next());
+        assert!(FocusManager::next());
+        assert!(b.is_focused());
+        assert!(FocusManager::previous());
+        assert!(c.is_focused());
+
+        a.request_focus();
+        assert_eq!(notified.get(), 2);
+
+        // Scope focuses its descendant focused last.
+        scope.request_focus();
+        assert!(c.is_focused());
+
+        c.set_can_request_focus(false);
+        assert!(FocusManager::focused().is_none());
+    }
+
+    #[test]
+    fn key_dispatch_without_focus() {
+        FocusManager::begin_layout();
+
+        let unfocusable = || {
+            let node = FocusNode::new();
+            node.set_can_request_focus(false);
+            node
+        };
+
+        // Shortcuts within actions, and a shortcut within a focusable field.
+        let (actions, shortcuts, field, nested) = (
+            unfocusable(),
+            unfocusable(),
+            FocusNode::new(),
+            unfocusable(),
+        );
+
+        actions.attach(|| {
+            shortcuts.attach(|| {
+                field.attach(|| nested.attach(|| {}));
+            });
+        });
+
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let handler = |name: &'static str| {
+            let received = received.clone();
+            move |_: &KeyEvent| {
+                received.borrow_mut().push(name);
+                false
+            }
+        };
+
+        let handlers = [
+            (&actions, handler("actions")),
+            (&shortcuts, handler("shortcuts")),
+            (&field, handler("field")),
+            (&nested, handler("nested")),
+        ];
+
+        for (node, handler) in handlers.iter() {
+            unsafe { node.set_key_handler(handler) };
+        }
+
+        let event = KeyEvent::for_test(Default::default(), Key::Character("s".into()));
+
+        FocusManager::handle_key(&event);
+        assert_eq!(*received.borrow(), ["shortcuts", "actions"]);
+
+        received.borrow_mut().clear();
+        field.request_focus();
+
+        FocusManager::handle_key(&event);
+        assert_eq!(*received.borrow(), ["field", "shortcuts", "actions"]);
+
+        for (node, _) in handlers.iter() {
+            node.remove_key_handler();
+        }
+    }
+}
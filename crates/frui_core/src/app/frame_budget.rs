@@ -0,0 +1,95 @@
+//! Time available for building widgets in a single frame.
+//!
+//! Widgets which build expensive subtrees that aren't needed right away (e.g.
+//! items of a lazy list entering its cache extent) can check the budget and
+//! postpone building once it is exhausted, spreading the work across several
+//! frames instead of delaying one of them:
+//!
+//! ```ignore
+//! if FrameBudget::is_exhausted() {
+//!     cx.timer_once(Duration::ZERO, |_| {});
+//!     return placeholder;
+//! }
+//! ```
+//!
+//! Building of widgets which need to be visible in the current frame should
+//! never be postponed.
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+pub struct FrameBudget;
+
+impl FrameBudget {
+    /// Default budget, about half of a frame at 60 Hz, which leaves the rest
+    /// of the frame to layout and painting.
+    pub const DEFAULT: Duration = Duration::from_millis(8);
+
+    /// Sets the time which can be spent building widgets in a frame before
+    /// postponable work is postponed. Defaults to [`DEFAULT`](Self::DEFAULT).
+    pub fn set(budget: Duration) {
+        BUDGET.with(|b| b.set(budget));
+    }
+
+    pub fn get() -> Duration {
+        BUDGET.with(|b| b.get())
+    }
+
+    /// Time elapsed since the current frame started building, or zero outside
+    /// of a frame.
+    pub fn elapsed() -> Duration {
+        FRAME_START.with(|s| s.get().map_or(Duration::ZERO, |start| start.elapsed()))
+    }
+
+    pub fn remaining() -> Duration {
+        Self::get().saturating_sub(Self::elapsed())
+    }
+
+    /// Whether the current frame ran out of its budget. Always `false`
+    /// outside of a frame (e.g. while the first widget tree is built).
+    pub fn is_exhausted() -> bool {
+        FRAME_START.with(|s| {
+            s.get()
+                .map_or(false, |start| start.elapsed() >= Self::get())
+        })
+    }
+
+    /// Marks the start of building of a frame.
+    pub(crate) fn frame_started() {
+        FRAME_START.with(|s| s.set(Some(Instant::now())));
+    }
+
+    /// Marks the end of building of a frame.
+    pub(crate) fn frame_built() {
+        FRAME_START.with(|s| s.set(None));
+    }
+}
+
+thread_local! {
+    static BUDGET: Cell<Duration> = Cell::new(FrameBudget::DEFAULT);
+
+    /// Start of building of the current frame, `None` outside of it.
+    static FRAME_START: Cell<Option<Instant>> = Cell::new(None);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_exhausted_only_within_frame() {
+        FrameBudget::set(Duration::ZERO);
+        assert!(!FrameBudget::is_exhausted());
+
+        FrameBudget::frame_started();
+        assert!(FrameBudget::is_exhausted());
+
+        FrameBudget::set(Duration::from_secs(60));
+        assert!(!FrameBudget::is_exhausted());
+
+        FrameBudget::frame_built();
+        assert_eq!(FrameBudget::remaining(), Duration::from_secs(60));
+    }
+}
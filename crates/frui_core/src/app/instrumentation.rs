@@ -0,0 +1,272 @@
+//! Timings and statistics of the framework, which applications can report
+//! (e.g. to their telemetry) to track performance regressions across releases.
+//!
+//! ```no_run
+//! # use frui_core::app::instrumentation::Instrumentation;
+//! Instrumentation::on_startup(|timings| {
+//!     println!("first frame after {:?}", timings.time_to_first_frame);
+//! });
+//!
+//! Instrumentation::on_frame(|summary| {
+//!     println!("rebuilt {} widgets", summary.rebuilt);
+//! });
+//! ```
+
+use std::{
+    cell::RefCell,
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Durations of the phases of application startup, up to the first frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StartupTimings {
+    /// From the start of the application (e.g. `run_app`) until its window
+    /// was created.
+    pub engine_init: Duration,
+    /// Loading of fonts before the first frame, including fonts loaded by
+    /// plugins.
+    pub font_load: Duration,
+    /// Building of the widget tree.
+    pub first_build: Duration,
+    pub first_layout: Duration,
+    /// Painting of the first frame.
+    pub first_paint: Duration,
+    /// From the start of the application until the first frame was painted.
+    pub time_to_first_frame: Duration,
+}
+
+impl fmt::Display for StartupTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "engine init {:?}, font load {:?}, first build {:?}, first layout {:?}, \
+             first paint {:?}, time to first frame {:?}",
+            self.engine_init,
+            self.font_load,
+            self.first_build,
+            self.first_layout,
+            self.first_paint,
+            self.time_to_first_frame
+        )
+    }
+}
+
+/// Work done in a single frame, which shows what a state change actually
+/// caused to be rebuilt.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameSummary {
+    /// Widgets marked dirty (e.g. through `state_mut`) which were rebuilt.
+    pub dirty: usize,
+    /// Widgets whose `build` ran, i.e. widgets marked dirty, descendants
+    /// whose configuration changed as a result, and newly created widgets.
+    pub rebuilt: usize,
+    /// Calls to `layout` of widgets. A widget can be laid out several times
+    /// in a frame, e.g. when its parent measures it first.
+    pub laid_out: usize,
+    /// Widgets painted, not counting widgets outside of the painted area.
+    pub painted: usize,
+}
+
+impl fmt::Display for FrameSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} dirty, {} rebuilt, {} laid out, {} painted",
+            self.dirty, self.rebuilt, self.laid_out, self.painted
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StartupPhase {
+    FontLoad,
+    FirstBuild,
+    FirstLayout,
+    FirstPaint,
+}
+
+pub struct Instrumentation;
+
+impl Instrumentation {
+    /// Timings of the startup, or `None` until the first frame is painted.
+    pub fn startup_timings() -> Option<StartupTimings> {
+        STARTUP.with(|s| {
+            let s = s.borrow();
+            s.finished.then_some(s.timings)
+        })
+    }
+
+    /// Calls `callback` with timings of the startup once the first frame is
+    /// painted, or right away if it already was.
+    pub fn on_startup(callback: impl FnOnce(&StartupTimings) + 'static) {
+        match Self::startup_timings() {
+            Some(timings) => callback(&timings),
+            None => STARTUP.with(|s| s.borrow_mut().callbacks.push(Box::new(callback))),
+        }
+    }
+
+    /// Marks the start of the application, from which startup is measured.
+    pub(crate) fn start() {
+        STARTUP.with(|s| s.borrow_mut().start());
+    }
+
+    /// Marks the end of [`StartupTimings::engine_init`].
+    pub(crate) fn engine_initialized() {
+        STARTUP.with(|s| {
+            let mut s = s.borrow_mut();
+            s.timings.engine_init = s.start().elapsed();
+        });
+    }
+
+    /// Runs `f`, adding its duration to `phase` if the first frame wasn't
+    /// painted yet.
+    pub(crate) fn measure<R>(phase: StartupPhase, f: impl FnOnce() -> R) -> R {
+        if STARTUP.with(|s| s.borrow().finished) {
+            return f();
+        }
+
+        let start = Instant::now();
+        let r = f();
+        let duration = start.elapsed();
+
+        STARTUP.with(|s| {
+            let mut s = s.borrow_mut();
+
+            if !s.finished {
+                *s.phase_mut(phase) += duration;
+            }
+        });
+
+        r
+    }
+
+    /// Marks the end of startup, calling [`on_startup`](Self::on_startup)
+    /// callbacks.
+    pub(crate) fn first_frame_painted() {
+        let (timings, callbacks) = STARTUP.with(|s| {
+            let mut s = s.borrow_mut();
+
+            if s.finished {
+                return (s.timings, Vec::new());
+            }
+
+            s.finished = true;
+            s.timings.time_to_first_frame = s.start().elapsed();
+            log::debug!("startup: {}", s.timings);
+
+            (s.timings, std::mem::take(&mut s.callbacks))
+        });
+
+        for callback in callbacks {
+            callback(&timings);
+        }
+    }
+
+    /// Summary of the last painted frame.
+    pub fn last_frame() -> Option<FrameSummary> {
+        FRAME.with(|f| f.borrow().last)
+    }
+
+    /// Calls `callback` with the summary of every frame once it is painted.
+    pub fn on_frame(callback: impl FnMut(&FrameSummary) + 'static) {
+        FRAME.with(|f| f.borrow_mut().callbacks.push(Box::new(callback)));
+    }
+
+    /// Counts work done in the current frame. Work done between frames (e.g.
+    /// building of the initial widget tree) is counted in the next one.
+    pub(crate) fn count(f: impl FnOnce(&mut FrameSummary)) {
+        FRAME.with(|frame| f(&mut frame.borrow_mut().current));
+    }
+
+    /// Marks the end of a frame, calling [`on_frame`](Self::on_frame)
+    /// callbacks.
+    pub(crate) fn frame_painted() {
+        let (summary, mut callbacks) = FRAME.with(|f| {
+            let mut f = f.borrow_mut();
+            let summary = std::mem::take(&mut f.current);
+            f.last = Some(summary);
+
+            (summary, std::mem::take(&mut f.callbacks))
+        });
+
+        log::trace!("frame: {}", summary);
+
+        for callback in &mut callbacks {
+            callback(&summary);
+        }
+
+        // Keep callbacks registered by the callbacks themselves.
+        FRAME.with(|f| {
+            let mut f = f.borrow_mut();
+            callbacks.append(&mut f.callbacks);
+            f.callbacks = callbacks;
+        });
+    }
+}
+
+#[derive(Default)]
+struct Startup {
+    start: Option<Instant>,
+    timings: StartupTimings,
+    /// Whether the first frame was painted.
+    finished: bool,
+    callbacks: Vec<Box<dyn FnOnce(&StartupTimings)>>,
+}
+
+impl Startup {
+    /// Start of the application, or the time its window was created if the
+    /// start wasn't marked (e.g. in tests).
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
+
+    fn phase_mut(&mut self, phase: StartupPhase) -> &mut Duration {
+        match phase {
+            StartupPhase::FontLoad => &mut self.timings.font_load,
+            StartupPhase::FirstBuild => &mut self.timings.first_build,
+            StartupPhase::FirstLayout => &mut self.timings.first_layout,
+            StartupPhase::FirstPaint => &mut self.timings.first_paint,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Frame {
+    current: FrameSummary,
+    last: Option<FrameSummary>,
+    callbacks: Vec<Box<dyn FnMut(&FrameSummary)>>,
+}
+
+thread_local! {
+    static STARTUP: RefCell<Startup> = Default::default();
+    static FRAME: RefCell<Frame> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn summarizes_frames() {
+        let summaries = Rc::new(RefCell::new(Vec::new()));
+        let summaries_ = summaries.clone();
+        Instrumentation::on_frame(move |s| summaries_.borrow_mut().push(*s));
+
+        Instrumentation::count(|s| s.rebuilt += 2);
+        Instrumentation::count(|s| s.painted += 1);
+        Instrumentation::frame_painted();
+        Instrumentation::frame_painted();
+
+        let first = FrameSummary {
+            rebuilt: 2,
+            painted: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(*summaries.borrow(), [first, FrameSummary::default()]);
+        assert_eq!(Instrumentation::last_frame(), Some(FrameSummary::default()));
+    }
+}
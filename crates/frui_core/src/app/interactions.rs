@@ -0,0 +1,72 @@
+//! Observing user interactions, e.g. to collect analytics.
+//!
+//! Built-in widgets report interactions themselves (taps are reported by
+//! gesture detectors, scrolling by scrollables), so an application only
+//! needs to observe them:
+//!
+//! ```no_run
+//! # use frui_core::app::interactions::{Interaction, Interactions};
+//! Interactions::observe(|interaction| match interaction {
+//!     Interaction::Tap { label: Some(label), .. } => println!("tapped {}", label),
+//!     _ => {}
+//! });
+//! ```
+
+use std::{cell::RefCell, rc::Rc};
+
+use slotmap::SlotMap;
+
+slotmap::new_key_type! { pub struct InteractionObserverKey; }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Interaction {
+    /// Widget was tapped (or clicked).
+    Tap {
+        /// Name of the tapped control (e.g. `"Button"`), if it's known.
+        widget: Option<&'static str>,
+        /// Label given to the tapped widget or to one of its ancestors.
+        label: Option<String>,
+    },
+    /// Application navigated to a different named route. Reported by
+    /// navigators, and by custom navigation through [`Interactions::report`].
+    RouteChanged { from: Option<String>, to: String },
+    /// Scrollable content was scrolled past another quarter of its length
+    /// for the first time. Reported with depths of 0.25, 0.5, 0.75 and 1.
+    ScrollDepth { label: Option<String>, depth: f64 },
+}
+
+pub struct Interactions;
+
+impl Interactions {
+    /// Registers `callback`, which will be called with every reported
+    /// interaction until [`unobserve`](Self::unobserve) is called.
+    pub fn observe(callback: impl Fn(&Interaction) + 'static) -> InteractionObserverKey {
+        OBSERVERS.with(|o| o.borrow_mut().insert(Rc::new(callback)))
+    }
+
+    pub fn unobserve(key: InteractionObserverKey) {
+        OBSERVERS.with(|o| o.borrow_mut().remove(key));
+    }
+
+    /// Whether there is any observer. Reporting can be skipped otherwise.
+    pub fn is_observed() -> bool {
+        OBSERVERS.with(|o| !o.borrow().is_empty())
+    }
+
+    /// Calls every observer with `interaction`. Widgets which aren't covered
+    /// by built-in reporting (e.g. custom navigation) can report their
+    /// interactions through it.
+    pub fn report(interaction: Interaction) {
+        // Observers may (un)register other observers while being called.
+        let observers = OBSERVERS.with(|o| o.borrow().values().cloned().collect::<Vec<_>>());
+
+        for observer in observers {
+            observer(&interaction);
+        }
+    }
+}
+
+thread_local! {
+    static OBSERVERS: RefCell<SlotMap<InteractionObserverKey, Rc<dyn Fn(&Interaction)>>> =
+        Default::default();
+}
@@ -0,0 +1,232 @@
+//! Detection of resources (timers, tickers, controllers and listeners) which
+//! were created by a widget, but outlived it.
+//!
+//! Resources are tracked with [`LeakDetector::track`] and are attributed to
+//! the widget whose state was being created, or which was being mounted or
+//! built at that time. Once that widget is removed from the tree and its
+//! state is dropped, resources which are still alive (e.g. a listener which
+//! wasn't removed in `unmount`) are logged together with the backtrace of
+//! where they were created (captured when `RUST_BACKTRACE` is set).
+//!
+//! Tracking is only enabled in debug builds.
+
+use std::{backtrace::Backtrace, cell::RefCell, collections::HashMap};
+
+use slotmap::SlotMap;
+
+use super::tree::{NodeId, NodeRef};
+
+slotmap::new_key_type! { struct LeakKey; }
+
+pub struct LeakDetector;
+
+impl LeakDetector {
+    /// Starts tracking a resource of the given `kind` (e.g. `"Timer"`). The
+    /// resource is considered disposed once the returned [`Tracked`] is
+    /// dropped or [disposed](Tracked::dispose).
+    pub fn track(kind: &'static str) -> Tracked {
+        if !cfg!(debug_assertions) {
+            return Tracked { key: None };
+        }
+
+        let key = LEAKS.with(|l| {
+            let mut leaks = l.borrow_mut();
+
+            let key = leaks.resources.insert(Resource {
+                kind,
+                owner: None,
+                backtrace: Backtrace::capture(),
+            });
+
+            if let Some(collecting) = leaks.collecting.last_mut() {
+                collecting.push(key);
+            }
+
+            key
+        });
+
+        Tracked { key: Some(key) }
+    }
+
+    /// Returns leaks detected since the last call, oldest first.
+    pub fn take_leaks() -> Vec<Leak> {
+        LEAKS.with(|l| std::mem::take(&mut l.borrow_mut().leaks))
+    }
+
+    /// Calls `f` and attributes resources tracked during that call to the
+    /// widget which is later passed to [`adopt`](Self::adopt).
+    pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, LeakKeys) {
+        LEAKS.with(|l| l.borrow_mut().collecting.push(Vec::new()));
+
+        let r = f();
+
+        let keys = LEAKS.with(|l| l.borrow_mut().collecting.pop().unwrap_or_default());
+
+        (r, LeakKeys(keys))
+    }
+
+    pub(crate) fn adopt(keys: LeakKeys, owner: &NodeRef) {
+        LEAKS.with(|l| {
+            let leaks = &mut *l.borrow_mut();
+            let owner = owner.id();
+
+            for key in keys.0 {
+                if let Some(resource) = leaks.resources.get_mut(key) {
+                    // Resources are owned by the first widget adopting them.
+                    if resource.owner.is_none() {
+                        resource.owner = Some(owner);
+                        leaks.owned.entry(owner).or_default().push(key);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Resources owned by `node`, which should be checked with
+    /// [`report_leaked`](Self::report_leaked) once the node and its state
+    /// are dropped.
+    pub(crate) fn owned_by(node: &NodeRef) -> OwnedResources {
+        let keys = match cfg!(debug_assertions) {
+            true => LEAKS.with(|l| l.borrow_mut().owned.remove(&node.id()).unwrap_or_default()),
+            false => Vec::new(),
+        };
+
+        OwnedResources {
+            owner: node.debug_name_short(),
+            keys,
+        }
+    }
+
+    /// Reports resources which are still alive.
+    pub(crate) fn report_leaked(owned: OwnedResources) {
+        LEAKS.with(|l| {
+            let mut leaks = l.borrow_mut();
+
+            for key in owned.keys {
+                let resource = match leaks.resources.remove(key) {
+                    Some(resource) => resource,
+                    None => continue,
+                };
+
+                let leak = Leak {
+                    kind: resource.kind,
+                    owner: owned.owner,
+                    backtrace: resource.backtrace.to_string(),
+                };
+
+                log::warn!(
+                    "{} created by {} outlived it after it was unmounted\n{}",
+                    leak.kind,
+                    leak.owner,
+                    leak.backtrace
+                );
+
+                leaks.leaks.push(leak);
+            }
+        });
+    }
+}
+
+/// Keys of resources tracked during [`LeakDetector::collect`].
+pub(crate) struct LeakKeys(Vec<LeakKey>);
+
+/// See [`LeakDetector::owned_by`].
+pub(crate) struct OwnedResources {
+    owner: &'static str,
+    keys: Vec<LeakKey>,
+}
+
+/// Handle to a tracked resource, see [`LeakDetector::track`].
+#[derive(Debug)]
+pub struct Tracked {
+    key: Option<LeakKey>,
+}
+
+impl Tracked {
+    pub fn dispose(self) {}
+}
+
+/// Handle which doesn't track any resource.
+impl Default for Tracked {
+    fn default() -> Self {
+        Tracked { key: None }
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            // Might be already removed if it was reported as leaked.
+            let _ = LEAKS.try_with(|l| l.borrow_mut().remove(key));
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Leak {
+    pub kind: &'static str,
+    /// Name of the widget which created the resource.
+    pub owner: &'static str,
+    pub backtrace: String,
+}
+
+struct Resource {
+    kind: &'static str,
+    owner: Option<NodeId>,
+    backtrace: Backtrace,
+}
+
+#[derive(Default)]
+struct Leaks {
+    resources: SlotMap<LeakKey, Resource>,
+    /// Keys of resources owned by each node, removed along with the node.
+    owned: HashMap<NodeId, Vec<LeakKey>>,
+    /// Keys of resources tracked during (possibly nested) `collect` calls.
+    collecting: Vec<Vec<LeakKey>>,
+    leaks: Vec<Leak>,
+}
+
+impl Leaks {
+    /// Stops tracking a disposed resource.
+    fn remove(&mut self, key: LeakKey) {
+        let owner = match self.resources.remove(key) {
+            Some(resource) => resource.owner,
+            None => return,
+        };
+
+        if let Some(owned) = owner.and_then(|owner| self.owned.get_mut(&owner)) {
+            owned.retain(|k| *k != key);
+        }
+    }
+}
+
+thread_local! {
+    static LEAKS: RefCell<Leaks> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reports_resources_which_werent_disposed() {
+        let ((a, b), keys) =
+            LeakDetector::collect(|| (LeakDetector::track("A"), LeakDetector::track("B")));
+
+        let owned = OwnedResources {
+            owner: "Owner",
+            keys: keys.0,
+        };
+
+        a.dispose();
+        LeakDetector::report_leaked(owned);
+
+        let leaks = LeakDetector::take_leaks();
+        assert_eq!(leaks.len(), 1);
+        assert_eq!((leaks[0].kind, leaks[0].owner), ("B", "Owner"));
+
+        // Leaked resources are reported only once.
+        drop(b);
+        assert!(LeakDetector::take_leaks().is_empty());
+    }
+}
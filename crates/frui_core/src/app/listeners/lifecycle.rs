@@ -0,0 +1,154 @@
+//! Listeners of application lifecycle events.
+
+use std::cell::{Cell, RefCell};
+
+use slotmap::SlotMap;
+
+use crate::app::runner::window_handler::WINDOW_HANDLE;
+
+slotmap::new_key_type! { pub struct LifecycleCallbackKey; }
+
+#[derive(Debug)]
+pub enum LifecycleEvent {
+    /// Application window gained keyboard focus.
+    Focused,
+    /// Application window lost keyboard focus.
+    Blurred,
+    /// Application window was minimized or became occluded.
+    Suspended,
+    /// Application window was restored or became visible again.
+    Resumed,
+    /// User requested application window to be closed (e.g. by clicking the
+    /// close button). See [`ExitRequest`].
+    ExitRequested(ExitRequest),
+}
+
+/// Allows listeners to cancel closing of the application window, e.g. to
+/// prompt about unsaved changes.
+///
+/// After cancelling, call [`exit`] to close the application.
+#[derive(Debug, Default)]
+pub struct ExitRequest {
+    cancelled: Cell<bool>,
+}
+
+impl ExitRequest {
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+/// Emits [`LifecycleEvent::ExitRequested`] and closes application window,
+/// unless the request was cancelled.
+pub fn request_exit() {
+    let event = LifecycleEvent::ExitRequested(ExitRequest::default());
+
+    LifecycleEventListeners::dispatch(&event);
+
+    if let LifecycleEvent::ExitRequested(request) = event {
+        if !request.is_cancelled() {
+            exit();
+        }
+    }
+}
+
+/// Whether the application window is minimized or occluded (e.g. cloaked on
+/// another virtual desktop). No frames are produced while suspended, so
+/// periodic work (e.g. polling timers) can be paused as well.
+pub fn is_suspended() -> bool {
+    MINIMIZED.with(|m| m.get()) || is_occluded()
+}
+
+pub(crate) fn set_minimized(minimized: bool) {
+    MINIMIZED.with(|m| m.set(minimized));
+}
+
+pub(crate) fn is_occluded() -> bool {
+    OCCLUDED.with(|o| o.get())
+}
+
+pub(crate) fn set_occluded(occluded: bool) {
+    OCCLUDED.with(|o| o.set(occluded));
+}
+
+/// Whether the device runs on battery. Animations are throttled to a lower
+/// frame rate while it does.
+///
+/// Always `false` if the platform doesn't report it.
+pub fn is_on_battery() -> bool {
+    ON_BATTERY.with(|b| b.get())
+}
+
+pub(crate) fn set_on_battery(on_battery: bool) {
+    ON_BATTERY.with(|b| b.set(on_battery));
+}
+
+/// Closes application window without emitting [`LifecycleEvent::ExitRequested`].
+pub fn exit() {
+    WINDOW_HANDLE.with(|handle| {
+        if let Some(handle) = &*handle.borrow() {
+            handle.close();
+        }
+    });
+}
+
+#[derive(Default)]
+pub struct LifecycleEventListeners {
+    callbacks: SlotMap<LifecycleCallbackKey, *const dyn Fn(&LifecycleEvent)>,
+}
+
+impl LifecycleEventListeners {
+    /// Registers a callback which will be called when a lifecycle event is
+    /// received.
+    ///
+    /// ## Safety:
+    ///
+    /// Value `callback` points to must live until [`unregister`] is called
+    /// with the returned [`LifecycleCallbackKey`].
+    ///
+    /// [`unregister`]: LifecycleEventListeners::unregister
+    pub unsafe fn register<'a>(
+        &mut self,
+        callback: *const (dyn Fn(&LifecycleEvent) + 'a),
+    ) -> LifecycleCallbackKey {
+        self.callbacks.insert(std::mem::transmute(callback))
+    }
+
+    pub fn unregister(&mut self, key: LifecycleCallbackKey) {
+        self.callbacks.remove(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.callbacks.len()
+    }
+
+    /// Calls every registered listener with `event`.
+    pub(crate) fn dispatch(event: &LifecycleEvent) {
+        // Keys are collected first, since a listener could (un)register other
+        // listeners while being called.
+        let keys = LIFECYCLE_EVENT_LISTENERS
+            .with(|listeners| listeners.borrow().callbacks.keys().collect::<Vec<_>>());
+
+        for key in keys {
+            let callback = LIFECYCLE_EVENT_LISTENERS
+                .with(|listeners| listeners.borrow().callbacks.get(key).copied());
+
+            if let Some(callback) = callback {
+                // Safety: `callback` is valid as ensured by registrars to `LifecycleEventListeners`.
+                unsafe { (*callback)(event) }
+            }
+        }
+    }
+}
+
+thread_local! {
+    pub static LIFECYCLE_EVENT_LISTENERS: RefCell<LifecycleEventListeners> = Default::default();
+
+    static MINIMIZED: Cell<bool> = Cell::new(false);
+    static OCCLUDED: Cell<bool> = Cell::new(false);
+    static ON_BATTERY: Cell<bool> = Cell::new(false);
+}
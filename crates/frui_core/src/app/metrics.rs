@@ -0,0 +1,136 @@
+//! Metrics of the application window: its size, scale and parts of it which
+//! are obscured by the operating system, as well as accessibility and
+//! appearance settings of the platform.
+//!
+//! Size and scale are updated by the framework, as are brightness and text
+//! scale factor on platforms where the framework can read them (currently
+//! Windows). Insets can't be queried from the windowing backend, so they are
+//! expected to be reported by platform integrations (e.g. when a soft keyboard
+//! is shown) through [`WindowMetrics::set_padding`] and
+//! [`WindowMetrics::set_view_insets`], and so are settings on other platforms,
+//! through [`WindowMetrics::set_text_scale_factor`] and
+//! [`WindowMetrics::set_platform_brightness`].
+
+use std::{cell::RefCell, rc::Rc};
+
+use druid_shell::kurbo::Insets;
+use slotmap::SlotMap;
+
+use crate::{
+    app::leaks::{LeakDetector, Tracked},
+    render::Size,
+};
+
+slotmap::new_key_type! { pub struct WindowMetricsListenerKey; }
+
+/// Whether the platform (or the user) prefers light or dark appearance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Brightness {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowMetrics {
+    /// Size of the window in display points.
+    pub size: Size,
+    /// Number of pixels per display point.
+    pub scale: f64,
+    /// Parts of the window permanently obscured by the system UI, e.g. notches
+    /// or taskbars of fullscreen windows.
+    pub padding: Insets,
+    /// Parts of the window temporarily obscured by the system UI, e.g. the
+    /// soft keyboard.
+    pub view_insets: Insets,
+    /// Number of font pixels per logical pixel preferred by the user.
+    pub text_scale_factor: f64,
+    pub platform_brightness: Brightness,
+}
+
+impl Default for WindowMetrics {
+    fn default() -> Self {
+        WindowMetrics {
+            size: Size::default(),
+            scale: 1.,
+            padding: Insets::ZERO,
+            view_insets: Insets::ZERO,
+            text_scale_factor: 1.,
+            platform_brightness: Brightness::Light,
+        }
+    }
+}
+
+impl WindowMetrics {
+    pub fn current() -> WindowMetrics {
+        METRICS.with(|m| m.borrow().metrics)
+    }
+
+    pub fn set_padding(padding: Insets) {
+        Self::update(|m| m.padding = padding);
+    }
+
+    pub fn set_view_insets(view_insets: Insets) {
+        Self::update(|m| m.view_insets = view_insets);
+    }
+
+    pub fn set_text_scale_factor(text_scale_factor: f64) {
+        Self::update(|m| m.text_scale_factor = text_scale_factor);
+    }
+
+    pub fn set_platform_brightness(brightness: Brightness) {
+        Self::update(|m| m.platform_brightness = brightness);
+    }
+
+    pub(crate) fn set_scale(scale: f64) {
+        Self::update(|m| m.scale = scale);
+    }
+
+    pub(crate) fn set_size(size: Size, scale: f64) {
+        Self::update(|m| {
+            m.size = size;
+            m.scale = scale;
+        });
+    }
+
+    /// Calls `callback` every time metrics change.
+    pub fn listen(callback: impl Fn() + 'static) -> WindowMetricsListenerKey {
+        let tracked = LeakDetector::track("Listener");
+        METRICS.with(|m| {
+            m.borrow_mut()
+                .listeners
+                .insert((Rc::new(callback), tracked))
+        })
+    }
+
+    pub fn unlisten(key: WindowMetricsListenerKey) {
+        METRICS.with(|m| m.borrow_mut().listeners.remove(key));
+    }
+
+    fn update(f: impl FnOnce(&mut WindowMetrics)) {
+        let listeners = METRICS.with(|m| {
+            let mut m = m.borrow_mut();
+            let previous = m.metrics;
+
+            f(&mut m.metrics);
+
+            match m.metrics != previous {
+                true => m.listeners.values().map(|l| l.0.clone()).collect(),
+                false => Vec::new(),
+            }
+        });
+
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+#[derive(Default)]
+struct MetricsRegistry {
+    metrics: WindowMetrics,
+    listeners: SlotMap<WindowMetricsListenerKey, (Rc<dyn Fn()>, Tracked)>,
+}
+
+thread_local! {
+    static METRICS: RefCell<MetricsRegistry> = Default::default();
+}
@@ -1,10 +1,41 @@
-use std::cell::{RefCell, RefMut};
+use std::{
+    cell::{RefCell, RefMut},
+    sync::{Arc, Mutex},
+};
 
-use druid_shell::piet::PietText;
+use druid_shell::piet::{self, FontFamily, PietText, Text};
 
+use self::instrumentation::{Instrumentation, StartupPhase};
+
+pub mod channels;
+pub mod clipboard;
+pub mod commands;
+pub mod debounce;
+pub mod diagnostics;
+#[cfg(not(feature = "miri"))]
+pub mod embed;
+pub mod feedback;
+pub mod focus;
+pub mod frame_budget;
+pub mod instrumentation;
+pub mod interactions;
+pub mod leaks;
 pub mod listeners;
+pub mod metrics;
+#[cfg(not(feature = "miri"))]
+pub mod offscreen;
+pub mod plugins;
+pub mod preferences;
+pub mod restoration;
 pub mod runner;
+pub mod services;
+pub mod snapshots;
+pub mod test_ids;
+pub mod text_input;
+pub mod ticker;
+pub mod timer;
 pub mod tree;
+pub mod window;
 
 pub struct TextFactory(RefCell<Option<PietText>>);
 
@@ -13,10 +44,27 @@ impl TextFactory {
         TextFactory(RefCell::new(None))
     }
 
-    pub(crate) fn set(&self, f: PietText) {
+    /// Sets the text factory of this thread, loading all fonts previously
+    /// loaded with [`load_font`](Self::load_font) (possibly on other threads).
+    pub(crate) fn set(&self, mut f: PietText) {
+        for data in LOADED_FONTS.lock().unwrap().iter() {
+            if let Err(e) = f.load_font(data) {
+                log::warn!("loading font failed: {}", e);
+            }
+        }
+
         *self.0.borrow_mut() = Some(f);
     }
 
+    /// Loads font from `data`. Fonts loaded that way are available to every
+    /// text factory, e.g. also to the one used for offscreen rendering.
+    pub fn load_font(&self, data: &[u8]) -> Result<FontFamily, piet::Error> {
+        let family =
+            Instrumentation::measure(StartupPhase::FontLoad, || self.get().load_font(data))?;
+        LOADED_FONTS.lock().unwrap().push(Arc::from(data));
+        Ok(family)
+    }
+
     pub fn get(&self) -> RefMut<PietText> {
         RefMut::map(self.0.borrow_mut(), |b| {
             b.as_mut().expect("TextFactory was not set")
@@ -27,3 +75,5 @@ impl TextFactory {
 thread_local! {
     pub static TEXT_FACTORY: TextFactory = TextFactory::new();
 }
+
+static LOADED_FONTS: Mutex<Vec<Arc<[u8]>>> = Mutex::new(Vec::new());
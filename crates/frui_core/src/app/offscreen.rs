@@ -0,0 +1,89 @@
+//! Rendering widgets into images on a background thread, e.g. thumbnails of
+//! documents, without touching the widget tree of the window.
+//!
+//! The widget is built, laid out and painted in a separate widget tree, which
+//! lives on the rendering thread. Services and other thread-local state of
+//! the UI thread aren't available there, with the exception of fonts loaded
+//! with [`TextFactory::load_font`](super::TextFactory::load_font).
+
+use std::{fmt, thread::JoinHandle};
+
+use druid_shell::{
+    kurbo::Rect,
+    piet::{Device, ImageBuf, ImageFormat, RenderContext},
+};
+
+use crate::{
+    api::{contexts::render::reset_paint_clip, WidgetPtr},
+    app::{tree::WidgetTree, TEXT_FACTORY},
+    prelude::Widget,
+    render::{Constraints, Size},
+};
+
+#[derive(Debug, Clone)]
+pub struct OffscreenError(String);
+
+impl fmt::Display for OffscreenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "offscreen rendering failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for OffscreenError {}
+
+impl From<druid_shell::piet::Error> for OffscreenError {
+    fn from(e: druid_shell::piet::Error) -> Self {
+        OffscreenError(e.to_string())
+    }
+}
+
+/// Renders the widget returned by `build` into an image of `size` (in display
+/// points) scaled by `scale`, on a new thread.
+///
+/// The widget is built on the rendering thread, which is why `build` is
+/// passed instead of the widget itself.
+pub fn render<W, F>(
+    size: Size,
+    scale: f64,
+    build: F,
+) -> JoinHandle<Result<ImageBuf, OffscreenError>>
+where
+    W: Widget + 'static,
+    F: FnOnce() -> W + Send + 'static,
+{
+    std::thread::spawn(move || render_blocking(size, scale, build()))
+}
+
+/// Must not be called on the UI thread, since the text factory of the window
+/// would be replaced.
+fn render_blocking<W: Widget + 'static>(
+    size: Size,
+    scale: f64,
+    widget: W,
+) -> Result<ImageBuf, OffscreenError> {
+    let mut device = Device::new()?;
+    let mut target = device.bitmap_target(
+        (size.width * scale).ceil() as usize,
+        (size.height * scale).ceil() as usize,
+        scale,
+    )?;
+
+    {
+        let mut canvas = target.render_context();
+        TEXT_FACTORY.with(|f| f.set(canvas.text().clone()));
+
+        let mut tree = WidgetTree::new(WidgetPtr::from_owned(Box::new(widget)));
+
+        tree.layout(Constraints::new_tight(size));
+
+        reset_paint_clip(Rect::new(0., 0., size.width, size.height));
+        tree.paint(&mut canvas);
+
+        // Unmount widgets before the canvas is gone.
+        drop(tree);
+
+        canvas.finish()?;
+    }
+
+    Ok(target.to_image_buf(ImageFormat::RgbaPremul)?)
+}
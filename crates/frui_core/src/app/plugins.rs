@@ -0,0 +1,226 @@
+//! Extensions of the application provided by other crates.
+//!
+//! A [`Plugin`] is added with [`Plugins::add`] before the application is run
+//! (see [`run_app`]), and initialized once the window is created, before the
+//! root widget is built. That's where it registers what it provides: services
+//! (see [`Services`]), fonts (see [`TextFactory::load_font`]), handlers of
+//! platform messages, etc.
+//!
+//! ```ignore
+//! struct SpellCheckPlugin;
+//!
+//! impl Plugin for SpellCheckPlugin {
+//!     fn init(&self) {
+//!         SpellCheck::set_service(HunspellService::new());
+//!     }
+//! }
+//!
+//! fn main() {
+//!     Plugins::add(SpellCheckPlugin);
+//!     run_app(App);
+//! }
+//! ```
+//!
+//! [`run_app`]: super::runner::native::run_app
+//! [`Services`]: super::services::Services
+//! [`TextFactory::load_font`]: super::TextFactory::load_font
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    rc::Rc,
+};
+
+pub trait Plugin: 'static {
+    /// Name of the plugin used in logs.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Registers what the plugin provides. Called once on the UI thread, when
+    /// the window is created (or right away, if it already was).
+    fn init(&self);
+
+    /// Called once the application window is closed, e.g. to release
+    /// resources of platform libraries.
+    fn exit(&self) {}
+}
+
+pub struct Plugins;
+
+impl Plugins {
+    /// Adds `plugin`, unless a plugin of the same type was already added.
+    pub fn add<P: Plugin>(plugin: P) {
+        let type_id = TypeId::of::<P>();
+
+        let initialized = PLUGINS.with(|p| {
+            let mut plugins = p.borrow_mut();
+
+            if plugins.entries.iter().any(|e| e.type_id == type_id) {
+                log::warn!("plugin `{}` was already added", plugin.name());
+                return None;
+            }
+
+            let plugin = Rc::new(plugin);
+            plugins.entries.push(Entry {
+                type_id,
+                any: plugin.clone(),
+                plugin,
+                init_called: false,
+            });
+
+            Some(plugins.initialized)
+        });
+
+        if initialized == Some(true) {
+            Self::init_pending();
+        }
+    }
+
+    pub fn contains<P: Plugin>() -> bool {
+        PLUGINS.with(|p| {
+            let type_id = TypeId::of::<P>();
+            p.borrow().entries.iter().any(|e| e.type_id == type_id)
+        })
+    }
+
+    /// Returns the added plugin of type `P`, e.g. to access its configuration.
+    pub fn get<P: Plugin>() -> Option<Rc<P>> {
+        PLUGINS.with(|p| {
+            let type_id = TypeId::of::<P>();
+            let plugins = p.borrow();
+            let entry = plugins.entries.iter().find(|e| e.type_id == type_id)?;
+
+            entry.any.clone().downcast().ok()
+        })
+    }
+
+    /// Initializes plugins added so far and every plugin added later.
+    pub(crate) fn init() {
+        PLUGINS.with(|p| p.borrow_mut().initialized = true);
+        Self::init_pending();
+    }
+
+    pub(crate) fn exit() {
+        let plugins = PLUGINS.with(|p| {
+            let mut plugins = p.borrow_mut();
+            plugins.initialized = false;
+
+            let mut initialized = Vec::new();
+
+            for entry in plugins.entries.iter_mut() {
+                if std::mem::take(&mut entry.init_called) {
+                    initialized.push(entry.plugin.clone());
+                }
+            }
+
+            initialized
+        });
+
+        // In reverse order, since plugins may use plugins added before them.
+        for plugin in plugins.iter().rev() {
+            plugin.exit();
+        }
+    }
+
+    fn init_pending() {
+        // Plugins are initialized outside of the borrow, since they may add
+        // other plugins.
+        loop {
+            let plugin = PLUGINS.with(|p| {
+                let mut plugins = p.borrow_mut();
+                let entry = plugins.entries.iter_mut().find(|e| !e.init_called)?;
+
+                entry.init_called = true;
+                Some(entry.plugin.clone())
+            });
+
+            match plugin {
+                Some(plugin) => {
+                    log::debug!("initializing plugin `{}`", plugin.name());
+                    plugin.init();
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+struct Entry {
+    type_id: TypeId,
+    plugin: Rc<dyn Plugin>,
+    /// Same as `plugin`, used to downcast it.
+    any: Rc<dyn Any>,
+    init_called: bool,
+}
+
+#[derive(Default)]
+struct PluginsInner {
+    entries: Vec<Entry>,
+    /// Whether the window was created, so that added plugins are
+    /// initialized right away.
+    initialized: bool,
+}
+
+thread_local! {
+    static PLUGINS: RefCell<PluginsInner> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    thread_local! {
+        static LOG: RefCell<Vec<&'static str>> = Default::default();
+    }
+
+    struct First(Cell<u32>);
+
+    impl Plugin for First {
+        fn init(&self) {
+            self.0.set(self.0.get() + 1);
+            LOG.with(|l| l.borrow_mut().push("init first"));
+
+            // Plugins may add plugins they depend on.
+            Plugins::add(Second);
+        }
+
+        fn exit(&self) {
+            LOG.with(|l| l.borrow_mut().push("exit first"));
+        }
+    }
+
+    struct Second;
+
+    impl Plugin for Second {
+        fn init(&self) {
+            LOG.with(|l| l.borrow_mut().push("init second"));
+        }
+
+        fn exit(&self) {
+            LOG.with(|l| l.borrow_mut().push("exit second"));
+        }
+    }
+
+    #[test]
+    fn init_and_exit() {
+        Plugins::add(First(Cell::new(0)));
+        Plugins::add(First(Cell::new(0)));
+        assert!(LOG.with(|l| l.borrow().is_empty()));
+
+        Plugins::init();
+        assert!(Plugins::contains::<Second>());
+        assert_eq!(Plugins::get::<First>().unwrap().0.get(), 1);
+
+        Plugins::exit();
+
+        LOG.with(|l| {
+            assert_eq!(
+                *l.borrow(),
+                ["init first", "init second", "exit second", "exit first"]
+            )
+        });
+    }
+}
@@ -0,0 +1,337 @@
+//! Persistent key-value storage of user preferences.
+//!
+//! Preferences are stored as a flat JSON object in the platform configuration
+//! directory (e.g. `~/.config/<app>/preferences.json` on Linux) and are written
+//! to disk every time a value changes.
+
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use slotmap::SlotMap;
+
+use super::{
+    channels::JsonValue,
+    leaks::{LeakDetector, Tracked},
+};
+
+slotmap::new_key_type! { pub struct PreferenceListenerKey; }
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreferenceValue {
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+/// Types which can be stored in [`Preferences`].
+pub trait Preference: Sized {
+    fn to_value(&self) -> PreferenceValue;
+
+    fn from_value(value: &PreferenceValue) -> Option<Self>;
+}
+
+impl Preference for bool {
+    fn to_value(&self) -> PreferenceValue {
+        PreferenceValue::Bool(*self)
+    }
+
+    fn from_value(value: &PreferenceValue) -> Option<Self> {
+        match value {
+            PreferenceValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl Preference for String {
+    fn to_value(&self) -> PreferenceValue {
+        PreferenceValue::String(self.clone())
+    }
+
+    fn from_value(value: &PreferenceValue) -> Option<Self> {
+        match value {
+            PreferenceValue::String(v) => Some(v.clone()),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_number_preference {
+    ($($t:ty),*) => {
+        $(
+            impl Preference for $t {
+                fn to_value(&self) -> PreferenceValue {
+                    PreferenceValue::Number(*self as f64)
+                }
+
+                fn from_value(value: &PreferenceValue) -> Option<Self> {
+                    match value {
+                        PreferenceValue::Number(v) => Some(*v as $t),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_number_preference!(f32, f64, i32, i64, u32, u64, usize);
+
+#[derive(Default)]
+pub struct Preferences {
+    path: Option<PathBuf>,
+    values: BTreeMap<String, PreferenceValue>,
+    listeners: SlotMap<PreferenceListenerKey, (String, Rc<dyn Fn()>, Tracked)>,
+}
+
+impl Preferences {
+    /// Loads preferences of application `app_name` from the platform
+    /// configuration directory.
+    pub fn load(app_name: &str) {
+        match config_dir() {
+            Some(dir) => Self::load_from(dir.join(app_name).join("preferences.json")),
+            None => log::warn!("configuration directory not found, preferences won't be saved"),
+        }
+    }
+
+    /// Loads preferences from the file at `path`. Changes are written back to
+    /// the same file.
+    ///
+    /// A malformed file is moved aside to `<path>.bak` (replacing an older
+    /// backup), so that it isn't overwritten by the next change. If it can't
+    /// be moved, changes aren't saved.
+    pub fn load_from(path: impl AsRef<Path>) {
+        let path = path.as_ref().to_owned();
+        let mut save = true;
+
+        let values = match std::fs::read_to_string(&path) {
+            Ok(data) => parse_json(&data).unwrap_or_else(|| {
+                let backup = backup_path(&path);
+
+                match std::fs::rename(&path, &backup) {
+                    Ok(()) => log::warn!(
+                        "preferences at {} are malformed, moved them to {}",
+                        path.display(),
+                        backup.display()
+                    ),
+                    Err(e) => {
+                        log::error!(
+                            "preferences at {} are malformed and backing them up failed, \
+                             changes won't be saved: {}",
+                            path.display(),
+                            e
+                        );
+                        save = false;
+                    }
+                }
+
+                BTreeMap::new()
+            }),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("reading preferences failed: {}", e);
+                }
+
+                BTreeMap::new()
+            }
+        };
+
+        PREFERENCES.with(|p| {
+            let mut p = p.borrow_mut();
+            p.path = save.then_some(path);
+            p.values = values;
+        });
+    }
+
+    pub fn get<T: Preference>(key: &str) -> Option<T> {
+        PREFERENCES.with(|p| T::from_value(p.borrow().values.get(key)?))
+    }
+
+    pub fn get_or<T: Preference>(key: &str, default: T) -> T {
+        Self::get(key).unwrap_or(default)
+    }
+
+    pub fn set<T: Preference>(key: &str, value: T) {
+        let value = value.to_value();
+
+        let changed = PREFERENCES.with(|p| {
+            let mut p = p.borrow_mut();
+            p.values.insert(key.to_owned(), value.clone()) != Some(value)
+        });
+
+        if changed {
+            Self::changed(key);
+        }
+    }
+
+    pub fn remove(key: &str) {
+        let removed = PREFERENCES.with(|p| p.borrow_mut().values.remove(key).is_some());
+
+        if removed {
+            Self::changed(key);
+        }
+    }
+
+    /// Calls `callback` every time value of `key` changes.
+    pub fn listen(key: &str, callback: impl Fn() + 'static) -> PreferenceListenerKey {
+        PREFERENCES.with(|p| {
+            let mut p = p.borrow_mut();
+            p.listeners.insert((
+                key.to_owned(),
+                Rc::new(callback),
+                LeakDetector::track("Listener"),
+            ))
+        })
+    }
+
+    pub fn unlisten(key: PreferenceListenerKey) {
+        PREFERENCES.with(|p| p.borrow_mut().listeners.remove(key));
+    }
+
+    fn changed(key: &str) {
+        let (path, data, listeners) = PREFERENCES.with(|p| {
+            let p = p.borrow();
+
+            let listeners = p
+                .listeners
+                .values()
+                .filter(|(k, ..)| k == key)
+                .map(|(_, callback, _)| callback.clone())
+                .collect::<Vec<_>>();
+
+            (p.path.clone(), to_json(&p.values), listeners)
+        });
+
+        if let Some(path) = path {
+            let result = match path.parent() {
+                Some(dir) => std::fs::create_dir_all(dir),
+                None => Ok(()),
+            };
+
+            if let Err(e) = result.and_then(|_| std::fs::write(&path, data)) {
+                log::error!("saving preferences failed: {}", e);
+            }
+        }
+
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+/// Platform directory for application configuration files.
+pub fn config_dir() -> Option<PathBuf> {
+    let env = |var| std::env::var_os(var).map(PathBuf::from);
+
+    if cfg!(target_os = "windows") {
+        env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        Some(env("HOME")?.join("Library/Application Support"))
+    } else {
+        env("XDG_CONFIG_HOME").or_else(|| Some(env("HOME")?.join(".config")))
+    }
+}
+
+/// Path malformed preferences at `path` are moved to.
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn to_json(values: &BTreeMap<String, PreferenceValue>) -> String {
+    let mut r = String::from("{\n");
+
+    for (n, (key, value)) in values.iter().enumerate() {
+        if n > 0 {
+            r.push_str(",\n");
+        }
+
+        write!(r, "  {}: ", JsonValue::from(key.as_str())).unwrap();
+
+        match value {
+            PreferenceValue::Bool(v) => write!(r, "{}", v).unwrap(),
+            PreferenceValue::Number(v) if v.is_finite() => write!(r, "{}", v).unwrap(),
+            PreferenceValue::Number(_) => r.push_str("null"),
+            PreferenceValue::String(v) => write!(r, "{}", JsonValue::from(v.as_str())).unwrap(),
+        }
+    }
+
+    r.push_str("\n}\n");
+    r
+}
+
+/// Parses flat JSON object of booleans, numbers and strings. Null values are
+/// skipped.
+fn parse_json(data: &str) -> Option<BTreeMap<String, PreferenceValue>> {
+    let object = match JsonValue::parse(data)? {
+        JsonValue::Object(object) => object,
+        _ => return None,
+    };
+
+    let mut values = BTreeMap::new();
+
+    for (key, value) in object {
+        let value = match value {
+            JsonValue::Null => continue,
+            JsonValue::Bool(v) => PreferenceValue::Bool(v),
+            JsonValue::Number(v) => PreferenceValue::Number(v),
+            JsonValue::String(v) => PreferenceValue::String(v),
+            JsonValue::Array(_) | JsonValue::Object(_) => return None,
+        };
+
+        values.insert(key, value);
+    }
+
+    Some(values)
+}
+
+thread_local! {
+    static PREFERENCES: RefCell<Preferences> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let mut values = BTreeMap::new();
+        values.insert(
+            "theme".to_owned(),
+            PreferenceValue::String("da\"rk\n".into()),
+        );
+        values.insert("font_size".to_owned(), PreferenceValue::Number(14.5));
+        values.insert("vsync".to_owned(), PreferenceValue::Bool(false));
+
+        assert_eq!(parse_json(&to_json(&values)), Some(values));
+        assert_eq!(parse_json("{}"), Some(BTreeMap::new()));
+        assert_eq!(parse_json("{\"a\": null}"), Some(BTreeMap::new()));
+        assert_eq!(parse_json("{\"a\": [1]}"), None);
+        assert_eq!(parse_json("[]"), None);
+    }
+
+    #[test]
+    fn malformed_preferences_are_backed_up() {
+        let dir = std::env::temp_dir().join(format!("frui-preferences-{}", std::process::id()));
+        let path = dir.join("preferences.json");
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "{\"theme\": \"dark\"").unwrap();
+
+        Preferences::load_from(&path);
+        Preferences::set("vsync", true);
+
+        let backup = std::fs::read_to_string(dir.join("preferences.json.bak"));
+        let saved = std::fs::read_to_string(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(backup.unwrap(), "{\"theme\": \"dark\"");
+        assert_eq!(parse_json(&saved.unwrap()).unwrap().len(), 1);
+    }
+}
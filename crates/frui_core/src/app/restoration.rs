@@ -0,0 +1,327 @@
+//! Persisting pieces of widget state between application launches.
+//!
+//! Restoration is disabled until [`Restoration::enable`] is called (usually
+//! right before `run_app`). After that, every [`Restorable`] value whose id
+//! was restored on the previous launch starts with its restored value, and
+//! current values are written to disk whenever the window is minimized or
+//! loses focus, and when the application exits.
+//!
+//! ```no_run
+//! # use frui_core::app::restoration::{Restoration, Restorable};
+//! Restoration::enable("app_state.txt");
+//!
+//! // In widget state:
+//! let mut query = Restorable::new("search_query", String::new());
+//! query.set("frui".to_owned());
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// Values which can be written to and read from restoration data.
+pub trait RestorableValue: Sized {
+    fn encode(&self) -> String;
+
+    fn decode(data: &str) -> Option<Self>;
+}
+
+macro_rules! impl_restorable_value {
+    ($($t:ty),*) => {
+        $(
+            impl RestorableValue for $t {
+                fn encode(&self) -> String {
+                    self.to_string()
+                }
+
+                fn decode(data: &str) -> Option<Self> {
+                    data.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_restorable_value!(
+    bool, char, String, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64
+);
+
+impl<T: RestorableValue> RestorableValue for Option<T> {
+    fn encode(&self) -> String {
+        match self {
+            Some(v) => format!("1{}", v.encode()),
+            None => "0".to_owned(),
+        }
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        match data.strip_prefix('1') {
+            Some(v) => Some(Some(T::decode(v)?)),
+            None if data == "0" => Some(None),
+            None => None,
+        }
+    }
+}
+
+/// Elements are stored with their length prefixed, e.g. `3:abc2:de`.
+impl<T: RestorableValue> RestorableValue for Vec<T> {
+    fn encode(&self) -> String {
+        let mut r = String::new();
+
+        for element in self {
+            let element = element.encode();
+            write!(r, "{}:{}", element.len(), element).unwrap();
+        }
+
+        r
+    }
+
+    fn decode(mut data: &str) -> Option<Self> {
+        let mut r = Vec::new();
+
+        while !data.is_empty() {
+            let (len, rest) = data.split_once(':')?;
+            let len = len.parse::<usize>().ok()?;
+
+            r.push(T::decode(rest.get(..len)?)?);
+            data = &rest[len..];
+        }
+
+        Some(r)
+    }
+}
+
+impl<A: RestorableValue, B: RestorableValue> RestorableValue for (A, B) {
+    fn encode(&self) -> String {
+        vec![self.0.encode(), self.1.encode()].encode()
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        match <Vec<String>>::decode(data)?.as_slice() {
+            [a, b] => Some((A::decode(a)?, B::decode(b)?)),
+            _ => None,
+        }
+    }
+}
+
+/// Value of widget state which is restored on the next launch of the
+/// application. Each value should have a unique restoration id.
+#[derive(Debug)]
+pub struct Restorable<T: RestorableValue> {
+    id: String,
+    value: T,
+}
+
+impl<T: RestorableValue> Restorable<T> {
+    /// Creates restorable value with the restored value of `id`, or `default`
+    /// if there was none.
+    pub fn new(id: impl Into<String>, default: T) -> Self {
+        let id = id.into();
+        let value = Restoration::restored(&id).unwrap_or(default);
+
+        let this = Restorable { id, value };
+        this.store();
+        this
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.store();
+    }
+
+    /// Modifies the value in place.
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value);
+        self.store();
+    }
+
+    fn store(&self) {
+        Restoration::store(&self.id, &self.value);
+    }
+}
+
+#[derive(Default)]
+pub struct Restoration {
+    /// File restoration data is read from and written to.
+    path: Option<PathBuf>,
+    /// Data read on launch.
+    restored: HashMap<String, String>,
+    /// Data written on exit.
+    current: HashMap<String, String>,
+}
+
+impl Restoration {
+    /// Enables restoration, reading data saved at `path` on previous launch.
+    pub fn enable(path: impl AsRef<Path>) {
+        let path = path.as_ref().to_owned();
+
+        let restored = match std::fs::read_to_string(&path) {
+            Ok(data) => parse(&data),
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("reading restoration data failed: {}", e);
+                }
+
+                HashMap::new()
+            }
+        };
+
+        RESTORATION.with(|r| {
+            let mut r = r.borrow_mut();
+            r.path = Some(path);
+            r.restored = restored;
+        });
+    }
+
+    pub fn is_enabled() -> bool {
+        RESTORATION.with(|r| r.borrow().path.is_some())
+    }
+
+    /// Restored value of `id`, if there is any.
+    pub fn restored<T: RestorableValue>(id: &str) -> Option<T> {
+        RESTORATION.with(|r| T::decode(r.borrow().restored.get(id)?))
+    }
+
+    /// Stores value of `id` which will be restored on next launch.
+    pub fn store<T: RestorableValue>(id: &str, value: &T) {
+        RESTORATION.with(|r| {
+            let mut r = r.borrow_mut();
+
+            if r.path.is_some() {
+                r.current.insert(id.to_owned(), value.encode());
+            }
+        });
+    }
+
+    /// Stops restoring value of `id` on next launch.
+    pub fn remove(id: &str) {
+        RESTORATION.with(|r| r.borrow_mut().current.remove(id));
+    }
+
+    /// Writes stored values to disk. Called automatically when the window is
+    /// minimized or loses focus, and when the application exits.
+    pub fn save() -> std::io::Result<()> {
+        RESTORATION.with(|r| {
+            let r = r.borrow();
+
+            match &r.path {
+                Some(path) => std::fs::write(path, serialize(&r.current)),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+/// Each entry is stored on a separate line as `id=value`.
+pub(crate) fn serialize(data: &HashMap<String, String>) -> String {
+    let mut entries = data.iter().collect::<Vec<_>>();
+    entries.sort();
+
+    let mut r = String::new();
+
+    for (id, value) in entries {
+        writeln!(r, "{}={}", escape(id), escape(value)).unwrap();
+    }
+
+    r
+}
+
+pub(crate) fn parse(data: &str) -> HashMap<String, String> {
+    data.lines()
+        .filter_map(|line| {
+            let (id, value) = split_unescaped(line)?;
+            Some((unescape(id), unescape(value)))
+        })
+        .collect()
+}
+
+fn escape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => r.push_str("\\\\"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            '=' => r.push_str("\\="),
+            c => r.push(c),
+        }
+    }
+
+    r
+}
+
+fn unescape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => r.push('\n'),
+                Some('r') => r.push('\r'),
+                Some(c) => r.push(c),
+                None => {}
+            },
+            c => r.push(c),
+        }
+    }
+
+    r
+}
+
+/// Splits `line` at the first `=` which isn't escaped.
+fn split_unescaped(line: &str) -> Option<(&str, &str)> {
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '=' if !escaped => return Some((&line[..i], &line[i + 1..])),
+            _ => escaped = false,
+        }
+    }
+
+    None
+}
+
+thread_local! {
+    static RESTORATION: RefCell<Restoration> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values_round_trip() {
+        let stack = vec!["/".to_owned(), "/settings".to_owned()];
+        assert_eq!(Vec::<String>::decode(&stack.encode()), Some(stack));
+
+        let offset = (12.5f64, -3f64);
+        assert_eq!(<(f64, f64)>::decode(&offset.encode()), Some(offset));
+
+        assert_eq!(Option::<u32>::decode(&None::<u32>.encode()), Some(None));
+        assert_eq!(Option::<u32>::decode(&Some(7u32).encode()), Some(Some(7)));
+    }
+
+    #[test]
+    fn data_round_trip() {
+        let mut data = HashMap::new();
+        data.insert("text=field".to_owned(), "line 1\nline 2 \\ =".to_owned());
+        data.insert("scroll".to_owned(), "120".to_owned());
+
+        assert_eq!(parse(&serialize(&data)), data);
+    }
+}
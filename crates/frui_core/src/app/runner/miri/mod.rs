@@ -1,12 +1,15 @@
 use druid_shell::{
-    kurbo::{Rect, Size},
-    KeyEvent, MouseEvent, Region,
+    kurbo::{Rect, Size, Vec2},
+    KeyEvent, Modifiers, MouseButton, MouseButtons, MouseEvent, Region, TimerToken,
 };
 use log::LevelFilter;
 use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
 
 use super::{window_handler::WindowHandler, FruiWindowHandler};
-use crate::prelude::Widget;
+use crate::{
+    app::{test_ids::TestIds, timer},
+    prelude::Widget,
+};
 
 mod substitutes;
 pub use substitutes::*;
@@ -50,6 +53,12 @@ impl MiriRunner {
     }
 
     pub fn update(&mut self, force_repaint: bool) {
+        // Timers that aren't due yet will be requested again.
+        let timers = std::mem::take(&mut *REQUEST_TIMER.lock().unwrap());
+        for token in timers {
+            self.handler.timer(token);
+        }
+
         for token in SCHEDULE_IDLE.lock().unwrap().drain(..) {
             self.handler.idle(token);
         }
@@ -63,6 +72,14 @@ impl MiriRunner {
         }
     }
 
+    /// Advances the clock of timers by `duration` without waiting, fires timers
+    /// that became due and updates the application.
+    pub fn advance_time(&mut self, duration: std::time::Duration) {
+        timer::skip_time(duration);
+        self.handler.timer(TimerToken::next());
+        self.update(false);
+    }
+
     //
     // Pass window events:
 
@@ -78,10 +95,49 @@ impl MiriRunner {
         self.handler.mouse_up(&event);
     }
 
+    /// Clicks the center of the widget with the given test identifier.
+    /// Returns `false` if no such widget was painted.
+    pub fn tap(&mut self, test_id: &str) -> bool {
+        let rect = match TestIds::find(test_id) {
+            Some(rect) => rect,
+            None => return false,
+        };
+
+        let mut event = MouseEvent {
+            pos: rect.center().into(),
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Modifiers::default(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+
+        self.mouse_move(&event);
+        self.mouse_down(&event);
+        event.buttons = MouseButtons::new();
+        self.mouse_up(&event);
+        self.update(false);
+
+        true
+    }
+
     pub fn key_down(&mut self, event: KeyEvent) {
         self.handler.key_down(event);
     }
 
+    pub fn got_focus(&mut self) {
+        self.handler.got_focus();
+    }
+
+    pub fn lost_focus(&mut self) {
+        self.handler.lost_focus();
+    }
+
+    pub fn request_close(&mut self) {
+        self.handler.request_close();
+    }
+
     pub fn size(&mut self, size: druid_shell::kurbo::Size) {
         self.handler.size(size);
         self.update(true);
@@ -1,14 +1,33 @@
-use std::{borrow::Cow, marker::PhantomData, sync::Mutex};
+use std::{borrow::Cow, marker::PhantomData, sync::Mutex, time::Duration};
 
 use druid_shell::{
-    piet::{CoreGraphicsImage, CoreGraphicsText, CoreGraphicsTextLayout, IntoBrush, PietText},
-    Cursor, IdleToken,
+    kurbo::{Affine, Point, Size},
+    piet::{
+        CoreGraphicsImage, CoreGraphicsText, CoreGraphicsTextLayout, ImageBuf, IntoBrush, PietText,
+    },
+    Cursor, CursorDesc, IdleToken, Scale, TextFieldToken, TimerToken, WindowState,
 };
 
+use crate::app::window::TaskbarProgress;
+
 pub static REQUEST_ANIM_FRAME: Mutex<bool> = Mutex::new(false);
 
 pub static SCHEDULE_IDLE: Mutex<Vec<IdleToken>> = Mutex::new(Vec::new());
 
+pub static REQUEST_TIMER: Mutex<Vec<TimerToken>> = Mutex::new(Vec::new());
+
+/// Whether the window skips taskbar, and progress shown on the taskbar.
+pub static TASKBAR: Mutex<(bool, TaskbarProgress)> = Mutex::new((false, TaskbarProgress::None));
+
+/// Last position the pointer was warped to.
+pub static POINTER: Mutex<Option<Point>> = Mutex::new(None);
+
+pub static CLIPBOARD: Mutex<Option<String>> = Mutex::new(None);
+
+/// Position, size and state of the window.
+pub static WINDOW: Mutex<(Point, Size, WindowState)> =
+    Mutex::new((Point::ORIGIN, Size::new(500., 400.), WindowState::Restored));
+
 /// Placeholder for [`IdleHandle`](druid_shell::IdleHandle) that allows us to test Frui in Miri.
 pub struct IdleHandle {}
 
@@ -27,6 +46,24 @@ impl Application {
     }
 
     pub fn quit(&self) {}
+
+    pub fn clipboard(&self) -> Clipboard {
+        Clipboard {}
+    }
+}
+
+/// Placeholder for [`Clipboard`](druid_shell::Clipboard) that allows us to test Frui in Miri.
+#[derive(Clone)]
+pub struct Clipboard {}
+
+impl Clipboard {
+    pub fn put_string(&mut self, s: impl AsRef<str>) {
+        *CLIPBOARD.lock().unwrap() = Some(s.as_ref().to_owned());
+    }
+
+    pub fn get_string(&self) -> Option<String> {
+        CLIPBOARD.lock().unwrap().clone()
+    }
 }
 
 /// Placeholder for [`WindowHandle`](druid_shell::WindowHandle) that allows us to test Frui in Miri.
@@ -44,10 +81,85 @@ impl WindowHandle {
 
     pub fn set_cursor(&self, _: &Cursor) {}
 
+    pub fn make_cursor(&self, _: &CursorDesc) -> Option<Cursor> {
+        None
+    }
+
+    pub fn request_timer(&self, _: Duration) -> TimerToken {
+        let token = TimerToken::next();
+        REQUEST_TIMER.lock().unwrap().push(token);
+        token
+    }
+
     pub fn invalidate(&self) {}
 
     pub fn close(&self) {}
 
+    pub fn set_window_state(&self, state: WindowState) {
+        WINDOW.lock().unwrap().2 = state;
+    }
+
+    pub fn get_window_state(&self) -> WindowState {
+        WINDOW.lock().unwrap().2
+    }
+
+    pub fn handle_titlebar(&self, _: bool) {}
+
+    pub fn get_position(&self) -> Point {
+        WINDOW.lock().unwrap().0
+    }
+
+    pub fn set_position(&self, position: Point) {
+        WINDOW.lock().unwrap().0 = position;
+    }
+
+    pub fn get_size(&self) -> Size {
+        WINDOW.lock().unwrap().1
+    }
+
+    pub fn set_size(&self, size: Size) {
+        WINDOW.lock().unwrap().1 = size;
+    }
+
+    pub fn set_title(&self, _: &str) {}
+
+    pub fn set_always_on_top(&self, _: bool) {}
+
+    pub fn set_skip_taskbar(&self, skip: bool) {
+        TASKBAR.lock().unwrap().0 = skip;
+    }
+
+    pub fn set_icon(&self, _: &ImageBuf) {}
+
+    pub fn set_taskbar_progress(&self, progress: TaskbarProgress) {
+        TASKBAR.lock().unwrap().1 = progress;
+    }
+
+    pub fn set_blur_behind(&self, _: bool) {}
+
+    pub fn warp_pointer(&self, position: Point) -> bool {
+        *POINTER.lock().unwrap() = Some(position);
+        true
+    }
+
+    pub fn is_occluded(&self) -> bool {
+        false
+    }
+
+    pub fn get_scale(&self) -> Result<Scale, druid_shell::Error> {
+        Ok(Scale::new(1., 1.))
+    }
+
+    pub fn add_text_field(&self) -> TextFieldToken {
+        TextFieldToken::next()
+    }
+
+    pub fn remove_text_field(&self, _: TextFieldToken) {}
+
+    pub fn set_focused_text_field(&self, _: Option<TextFieldToken>) {}
+
+    pub fn update_text_field(&self, _: TextFieldToken, _: druid_shell::text::Event) {}
+
     #[track_caller]
     pub fn text(&self) -> PietText {
         todo!()
@@ -57,6 +169,9 @@ impl WindowHandle {
 /// Placeholder for [`Piet`](druid_shell::piet::Piet) that allows us to test Frui in Miri.
 #[derive(Default)]
 pub struct Canvas<'a> {
+    /// Current transformation, and the saved ones.
+    transform: Affine,
+    saved: Vec<Affine>,
     _p: PhantomData<&'a ()>,
 }
 
@@ -137,20 +252,27 @@ impl druid_shell::piet::RenderContext for Canvas<'_> {
     }
 
     fn save(&mut self) -> Result<(), druid_shell::piet::Error> {
-        // Todo: This is incorrect, we should create a stack for this.
+        self.saved.push(self.transform);
         Ok(())
     }
 
     fn restore(&mut self) -> Result<(), druid_shell::piet::Error> {
-        // Todo: This is incorrect, we should create a stack for this.
-        Ok(())
+        match self.saved.pop() {
+            Some(transform) => {
+                self.transform = transform;
+                Ok(())
+            }
+            None => Err(druid_shell::piet::Error::StackUnbalance),
+        }
     }
 
     fn finish(&mut self) -> Result<(), druid_shell::piet::Error> {
         todo!()
     }
 
-    fn transform(&mut self, transform: druid_shell::kurbo::Affine) {}
+    fn transform(&mut self, transform: Affine) {
+        self.transform *= transform;
+    }
 
     fn make_image(
         &mut self,
@@ -197,8 +319,8 @@ impl druid_shell::piet::RenderContext for Canvas<'_> {
         todo!()
     }
 
-    fn current_transform(&self) -> druid_shell::kurbo::Affine {
-        todo!()
+    fn current_transform(&self) -> Affine {
+        self.transform
     }
 }
 
@@ -1,4 +1,4 @@
-use druid_shell::{IdleToken, KeyEvent, MouseEvent};
+use druid_shell::{IdleToken, KeyEvent, MouseEvent, TimerToken};
 
 pub mod window_handler;
 
@@ -37,8 +37,12 @@ pub trait FruiWindowHandler {
 
     fn size(&mut self, size: druid_shell::kurbo::Size);
 
+    fn scale(&mut self, scale: druid_shell::Scale);
+
     fn idle(&mut self, token: IdleToken);
 
+    fn timer(&mut self, token: TimerToken);
+
     fn destroy(&mut self);
 
     fn as_any(&mut self) -> &mut dyn std::any::Any;
@@ -55,5 +59,11 @@ pub trait FruiWindowHandler {
 
     fn key_down(&mut self, event: KeyEvent) -> bool;
 
+    fn key_up(&mut self, event: KeyEvent);
+
+    fn got_focus(&mut self);
+
+    fn lost_focus(&mut self);
+
     fn request_close(&mut self);
 }
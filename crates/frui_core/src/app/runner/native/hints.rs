@@ -0,0 +1,304 @@
+//! Window hints and pointer warping, which the windowing backend doesn't
+//! expose, implemented on top of the platform window handle.
+//!
+//! They are currently implemented on Windows only. On other platforms setting
+//! them logs a warning (once per hint) and does nothing.
+
+use druid_shell::{kurbo::Point, piet::ImageBuf, WindowHandle};
+
+use crate::app::window::TaskbarProgress;
+
+/// Extends [`WindowHandle`] with hints that [`Window`](crate::app::window::Window)
+/// exposes. Miri substitute of the handle implements the same methods.
+pub trait WindowHints {
+    fn set_skip_taskbar(&self, skip: bool);
+
+    fn set_icon(&self, icon: &ImageBuf);
+
+    fn set_taskbar_progress(&self, progress: TaskbarProgress);
+
+    /// Blurs what is visible through transparent parts of the window. Only
+    /// has an effect on transparent windows.
+    fn set_blur_behind(&self, blur: bool);
+
+    /// Moves the pointer to `position` in window coordinates. Returns `false`
+    /// if the platform doesn't support it.
+    fn warp_pointer(&self, position: Point) -> bool;
+
+    /// Whether the window is hidden from the user although it isn't minimized,
+    /// e.g. because it's on another virtual desktop. Always `false` if the
+    /// platform doesn't report it.
+    fn is_occluded(&self) -> bool;
+}
+
+#[cfg(windows)]
+impl WindowHints for WindowHandle {
+    fn set_skip_taskbar(&self, skip: bool) {
+        if let Some(hwnd) = win32::hwnd(self) {
+            win32::with_taskbar(|taskbar| unsafe {
+                match skip {
+                    true => taskbar.DeleteTab(hwnd),
+                    false => taskbar.AddTab(hwnd),
+                }
+            });
+        }
+    }
+
+    fn set_icon(&self, icon: &ImageBuf) {
+        if let Some(hwnd) = win32::hwnd(self) {
+            win32::set_icon(hwnd, icon);
+        }
+    }
+
+    fn set_taskbar_progress(&self, progress: TaskbarProgress) {
+        if let Some(hwnd) = win32::hwnd(self) {
+            win32::set_taskbar_progress(hwnd, progress);
+        }
+    }
+
+    fn set_blur_behind(&self, blur: bool) {
+        if let Some(hwnd) = win32::hwnd(self) {
+            win32::set_blur_behind(hwnd, blur);
+        }
+    }
+
+    fn warp_pointer(&self, position: Point) -> bool {
+        let scale = self.get_scale().map_or(1., |s| s.x());
+
+        match win32::hwnd(self) {
+            Some(hwnd) => win32::warp_pointer(hwnd, position, scale),
+            None => false,
+        }
+    }
+
+    fn is_occluded(&self) -> bool {
+        win32::hwnd(self).map_or(false, win32::is_cloaked)
+    }
+}
+
+#[cfg(not(windows))]
+impl WindowHints for WindowHandle {
+    fn set_skip_taskbar(&self, _: bool) {
+        unsupported(&SKIP_TASKBAR, "skipping taskbar");
+    }
+
+    fn set_icon(&self, _: &ImageBuf) {
+        unsupported(&ICON, "window icon");
+    }
+
+    fn set_taskbar_progress(&self, _: TaskbarProgress) {
+        unsupported(&TASKBAR_PROGRESS, "taskbar progress");
+    }
+
+    fn set_blur_behind(&self, _: bool) {
+        unsupported(&BLUR_BEHIND, "blur behind window");
+    }
+
+    fn warp_pointer(&self, _: Point) -> bool {
+        unsupported(&WARP_POINTER, "warping pointer");
+        false
+    }
+
+    fn is_occluded(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(not(windows))]
+static SKIP_TASKBAR: std::sync::Once = std::sync::Once::new();
+#[cfg(not(windows))]
+static ICON: std::sync::Once = std::sync::Once::new();
+#[cfg(not(windows))]
+static TASKBAR_PROGRESS: std::sync::Once = std::sync::Once::new();
+#[cfg(not(windows))]
+static BLUR_BEHIND: std::sync::Once = std::sync::Once::new();
+#[cfg(not(windows))]
+static WARP_POINTER: std::sync::Once = std::sync::Once::new();
+
+#[cfg(not(windows))]
+fn unsupported(warned: &std::sync::Once, hint: &str) {
+    warned.call_once(|| log::warn!("{} is not supported on this platform", hint));
+}
+
+#[cfg(windows)]
+mod win32 {
+    use druid_shell::{kurbo::Point, piet::ImageBuf, WindowHandle};
+    use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+    use windows::Win32::{
+        Foundation::{BOOL, HWND, LPARAM, POINT, WPARAM},
+        Graphics::{
+            Dwm::{
+                DwmEnableBlurBehindWindow, DwmGetWindowAttribute, DwmSetWindowAttribute,
+                DWMSBT_NONE, DWMSBT_TRANSIENTWINDOW, DWMWA_CLOAKED, DWMWA_SYSTEMBACKDROP_TYPE,
+                DWM_BB_ENABLE, DWM_BLURBEHIND,
+            },
+            Gdi::{ClientToScreen, HRGN},
+        },
+        System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER},
+        UI::{
+            Shell::{
+                ITaskbarList3, TaskbarList, TBPF_ERROR, TBPF_INDETERMINATE, TBPF_NOPROGRESS,
+                TBPF_NORMAL, TBPF_PAUSED,
+            },
+            WindowsAndMessaging::{
+                CreateIcon, DestroyIcon, SendMessageW, SetCursorPos, HICON, ICON_BIG, ICON_SMALL,
+                WM_SETICON,
+            },
+        },
+    };
+
+    use crate::app::window::TaskbarProgress;
+
+    /// Resolution of the progress reported to the taskbar.
+    const PROGRESS_STEPS: u64 = 10_000;
+
+    thread_local! {
+        /// Taskbar interface, created on first use.
+        static TASKBAR: Option<ITaskbarList3> = unsafe {
+            CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+                .and_then(|taskbar: ITaskbarList3| taskbar.HrInit().map(|_| taskbar))
+                .map_err(|e| log::warn!("creating taskbar interface failed: {}", e))
+                .ok()
+        };
+
+        /// Icon currently set on the window, destroyed when replaced.
+        static ICON: std::cell::Cell<Option<HICON>> = Default::default();
+    }
+
+    pub fn hwnd(handle: &WindowHandle) -> Option<HWND> {
+        match handle.raw_window_handle() {
+            RawWindowHandle::Win32(handle) => Some(HWND(handle.hwnd as isize)),
+            _ => None,
+        }
+    }
+
+    pub fn with_taskbar(f: impl FnOnce(&ITaskbarList3) -> windows::core::Result<()>) {
+        TASKBAR.with(|taskbar| {
+            if let Some(Err(e)) = taskbar.as_ref().map(f) {
+                log::warn!("updating taskbar failed: {}", e);
+            }
+        })
+    }
+
+    pub fn set_taskbar_progress(hwnd: HWND, progress: TaskbarProgress) {
+        let (state, value) = match progress {
+            TaskbarProgress::None => (TBPF_NOPROGRESS, None),
+            TaskbarProgress::Indeterminate => (TBPF_INDETERMINATE, None),
+            TaskbarProgress::Normal(v) => (TBPF_NORMAL, Some(v)),
+            TaskbarProgress::Paused(v) => (TBPF_PAUSED, Some(v)),
+            TaskbarProgress::Error(v) => (TBPF_ERROR, Some(v)),
+        };
+
+        with_taskbar(|taskbar| unsafe {
+            // Setting the value switches indeterminate progress to normal, so
+            // the state is set after it.
+            if let Some(value) = value {
+                let completed = (value.clamp(0., 1.) * PROGRESS_STEPS as f64) as u64;
+                taskbar.SetProgressValue(hwnd, completed, PROGRESS_STEPS)?;
+            }
+
+            taskbar.SetProgressState(hwnd, state)
+        });
+    }
+
+    pub fn set_icon(hwnd: HWND, icon: &ImageBuf) {
+        let (width, height) = (icon.width(), icon.height());
+
+        // Icons take BGRA pixels and a mask, which is ignored when the pixels
+        // have alpha. Icons are always created from RGBA pixels.
+        let pixels = icon
+            .raw_pixels()
+            .chunks_exact(4)
+            .flat_map(|p| [p[2], p[1], p[0], p[3]])
+            .collect::<Vec<_>>();
+        let mask = vec![0xff; (width + 7) / 8 * height];
+
+        let icon = unsafe {
+            CreateIcon(
+                None,
+                width as i32,
+                height as i32,
+                1,
+                32,
+                mask.as_ptr(),
+                pixels.as_ptr(),
+            )
+        };
+
+        match icon {
+            Ok(icon) => unsafe {
+                let lparam = LPARAM(icon.0);
+                SendMessageW(hwnd, WM_SETICON, WPARAM(ICON_BIG as usize), lparam);
+                SendMessageW(hwnd, WM_SETICON, WPARAM(ICON_SMALL as usize), lparam);
+
+                if let Some(previous) = ICON.with(|i| i.replace(Some(icon))) {
+                    DestroyIcon(previous);
+                }
+            },
+            Err(e) => log::warn!("creating window icon failed: {}", e),
+        }
+    }
+
+    /// Moves the pointer to `position` in logical window coordinates.
+    pub fn warp_pointer(hwnd: HWND, position: Point, scale: f64) -> bool {
+        let mut point = POINT {
+            x: (position.x * scale).round() as i32,
+            y: (position.y * scale).round() as i32,
+        };
+
+        unsafe {
+            ClientToScreen(hwnd, &mut point).as_bool() && SetCursorPos(point.x, point.y).as_bool()
+        }
+    }
+
+    /// Whether the window is cloaked by the compositor, which is the case for
+    /// windows on other virtual desktops.
+    pub fn is_cloaked(hwnd: HWND) -> bool {
+        let mut cloaked = 0u32;
+
+        let result = unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_CLOAKED,
+                &mut cloaked as *mut u32 as *mut _,
+                std::mem::size_of::<u32>() as u32,
+            )
+        };
+
+        result.is_ok() && cloaked != 0
+    }
+
+    /// Blurs the backdrop of the window. Uses the system backdrop (acrylic)
+    /// where available (Windows 11) and falls back to the blur of older
+    /// versions of Windows.
+    pub fn set_blur_behind(hwnd: HWND, blur: bool) {
+        let backdrop = match blur {
+            true => DWMSBT_TRANSIENTWINDOW,
+            false => DWMSBT_NONE,
+        };
+
+        let result = unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_SYSTEMBACKDROP_TYPE,
+                &backdrop as *const _ as *const _,
+                std::mem::size_of_val(&backdrop) as u32,
+            )
+        };
+
+        if result.is_ok() {
+            return;
+        }
+
+        let blur_behind = DWM_BLURBEHIND {
+            dwFlags: DWM_BB_ENABLE,
+            fEnable: BOOL::from(blur),
+            hRgnBlur: HRGN::default(),
+            fTransitionOnMaximized: BOOL::from(false),
+        };
+
+        if let Err(e) = unsafe { DwmEnableBlurBehindWindow(hwnd, &blur_behind) } {
+            log::warn!("enabling blur behind window failed: {}", e);
+        }
+    }
+}
@@ -1,19 +1,36 @@
 use druid_shell::{
-    kurbo::Size, piet::Piet, Application, IdleToken, KeyEvent, MouseEvent, Region, WinHandler,
-    WindowBuilder, WindowHandle,
+    kurbo::Size, piet::Piet, text::InputHandler, Application, IdleToken, KeyEvent, MouseEvent,
+    Region, Scale, TextFieldToken, TimerToken, WinHandler, WindowBuilder, WindowHandle,
+    WindowState,
 };
 use log::LevelFilter;
 use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
 
-use crate::prelude::Widget;
+use crate::{
+    app::{instrumentation::Instrumentation, text_input, window::WindowOptions},
+    prelude::Widget,
+};
 
 use super::{window_handler::WindowHandler, FruiWindowHandler};
 
+pub mod hints;
+pub(crate) mod power;
+pub(crate) mod settings;
+
+use self::hints::WindowHints;
+
 // Currently there is `'static` lifetime requirement for the root widget
 // because of the requirements of `WinHandle` from the druid_shell.
 //
 // In the future this requirement may be lifted.
 pub fn run_app<'a>(widget: impl Widget + 'static) {
+    run_app_with(widget, WindowOptions::default())
+}
+
+/// Same as [`run_app`], but creates the window according to `options`.
+pub fn run_app_with(widget: impl Widget + 'static, options: WindowOptions) {
+    Instrumentation::start();
+
     if cfg!(feature = "miri") {
         panic!(concat!(
             "feature `miri` is enabled which is not supported for `run_app`. ",
@@ -38,12 +55,55 @@ pub fn run_app<'a>(widget: impl Widget + 'static) {
     let app = Application::new().unwrap();
 
     let mut window = WindowBuilder::new(app.clone());
-    window.set_handler(Box::new(WindowHandler::new(widget)));
-    window.set_title("Frui App");
+    window.set_handler(Box::new(
+        WindowHandler::new(widget)
+            .with_background(options.background.clone())
+            .with_geometry_id(options.geometry_id.clone()),
+    ));
+    window.set_title(&options.title);
+    window.show_titlebar(options.decorations);
+    window.resizable(options.resizable);
+    window.set_transparent(options.transparent);
+    window.set_always_on_top(options.always_on_top);
+
+    if let Some(size) = options.size {
+        window.set_size(size.into());
+    }
+
+    if let Some(position) = options.position {
+        window.set_position(position);
+    }
+
+    if let Some(geometry) = options.restored_geometry() {
+        window.set_position(geometry.position);
+        window.set_size(geometry.size.into());
+
+        if geometry.maximized {
+            window.set_window_state(WindowState::Maximized);
+        }
+    }
+
+    if let Some(min_size) = options.min_size {
+        window.set_min_size(min_size.into());
+    }
 
     let window = window.build().unwrap();
 
+    if let Some(icon) = &options.icon {
+        window.set_icon(icon.image());
+    }
+
+    if options.blur_behind {
+        window.set_blur_behind(true);
+    }
+
     window.show();
+
+    // Taskbar adds the window when it is shown.
+    if options.skip_taskbar {
+        window.set_skip_taskbar(true);
+    }
+
     app.run(None);
 
     drop(window);
@@ -84,10 +144,18 @@ impl WinHandler for WindowHandler {
         FruiWindowHandler::size(self, size)
     }
 
+    fn scale(&mut self, scale: Scale) {
+        FruiWindowHandler::scale(self, scale)
+    }
+
     fn idle(&mut self, token: IdleToken) {
         FruiWindowHandler::idle(self, token)
     }
 
+    fn timer(&mut self, token: TimerToken) {
+        FruiWindowHandler::timer(self, token)
+    }
+
     fn destroy(&mut self) {
         FruiWindowHandler::destroy(self)
     }
@@ -112,6 +180,28 @@ impl WinHandler for WindowHandler {
         FruiWindowHandler::key_down(self, event)
     }
 
+    fn key_up(&mut self, event: KeyEvent) {
+        FruiWindowHandler::key_up(self, event)
+    }
+
+    fn got_focus(&mut self) {
+        FruiWindowHandler::got_focus(self)
+    }
+
+    fn acquire_input_lock(
+        &mut self,
+        token: TextFieldToken,
+        _mutable: bool,
+    ) -> Box<dyn InputHandler> {
+        text_input::input_handler(token)
+    }
+
+    fn release_input_lock(&mut self, _token: TextFieldToken) {}
+
+    fn lost_focus(&mut self) {
+        FruiWindowHandler::lost_focus(self)
+    }
+
     fn request_close(&mut self) {
         FruiWindowHandler::request_close(self)
     }
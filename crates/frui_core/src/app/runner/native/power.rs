@@ -0,0 +1,88 @@
+//! Power source of the device, which the windowing backend doesn't report.
+//!
+//! It is currently read on Windows and Linux only. On other platforms the
+//! device is assumed to be plugged in.
+
+/// Whether the device runs on battery. `None` if it can't be determined, e.g.
+/// on desktops without a battery.
+#[cfg(windows)]
+pub(crate) fn on_battery() -> Option<bool> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+
+    if !unsafe { GetSystemPowerStatus(&mut status) }.as_bool() {
+        return None;
+    }
+
+    match status.ACLineStatus {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn on_battery() -> Option<bool> {
+    on_battery_in(std::path::Path::new("/sys/class/power_supply"))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub(crate) fn on_battery() -> Option<bool> {
+    None
+}
+
+/// Reads power supplies listed in `dir` (in the format of sysfs). The device
+/// runs on battery if none of its mains supplies (AC adapters) is online.
+#[cfg(target_os = "linux")]
+fn on_battery_in(dir: &std::path::Path) -> Option<bool> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok();
+
+    let mut mains = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|supply| supply.path())
+        .filter(|supply| read(supply.join("type")).map_or(false, |t| t.trim() == "Mains"))
+        .peekable();
+
+    // Without mains supplies there's nothing to tell battery power apart from.
+    mains.peek()?;
+
+    Some(!mains.any(|supply| read(supply.join("online")).map_or(false, |o| o.trim() == "1")))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn battery_power_is_read_from_mains_supplies() {
+        let dir = std::env::temp_dir().join(format!("frui-power-{}", std::process::id()));
+
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+
+            for (file, content) in files {
+                std::fs::write(dir.join(name).join(file), content).unwrap();
+            }
+        };
+
+        supply(
+            "BAT0",
+            &[("type", "Battery\n"), ("status", "Discharging\n")],
+        );
+        let without_mains = on_battery_in(&dir);
+
+        supply("AC", &[("type", "Mains\n"), ("online", "0\n")]);
+        let unplugged = on_battery_in(&dir);
+
+        supply("AC", &[("online", "1\n")]);
+        let plugged_in = on_battery_in(&dir);
+
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(without_mains, None);
+        assert_eq!(unplugged, Some(true));
+        assert_eq!(plugged_in, Some(false));
+    }
+}
@@ -0,0 +1,86 @@
+//! Appearance and accessibility settings of the platform, which the windowing
+//! backend doesn't expose, reported through [`WindowMetrics`].
+//!
+//! They are currently read on Windows only. On other platforms metrics keep
+//! their defaults, unless platform integrations report them.
+
+use crate::app::metrics::{Brightness, WindowMetrics};
+
+/// Reads settings of the platform and updates window metrics with them. Called
+/// once the window connects and every time it gains focus, since settings are
+/// likely to be changed in another window.
+pub(crate) fn update_metrics() {
+    if let Some(brightness) = platform_brightness() {
+        WindowMetrics::set_platform_brightness(brightness);
+    }
+
+    if let Some(text_scale_factor) = text_scale_factor() {
+        WindowMetrics::set_text_scale_factor(text_scale_factor);
+    }
+}
+
+#[cfg(windows)]
+fn platform_brightness() -> Option<Brightness> {
+    let light = win32::read_dword(
+        windows::w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+        windows::w!("AppsUseLightTheme"),
+    )?;
+
+    Some(match light {
+        0 => Brightness::Dark,
+        _ => Brightness::Light,
+    })
+}
+
+/// Set in "Accessibility > Text size", in percents.
+#[cfg(windows)]
+fn text_scale_factor() -> Option<f64> {
+    let percents = win32::read_dword(
+        windows::w!("Software\\Microsoft\\Accessibility"),
+        windows::w!("TextScaleFactor"),
+    )?;
+
+    Some(percents as f64 / 100.)
+}
+
+#[cfg(not(windows))]
+fn platform_brightness() -> Option<Brightness> {
+    None
+}
+
+#[cfg(not(windows))]
+fn text_scale_factor() -> Option<f64> {
+    None
+}
+
+#[cfg(windows)]
+mod win32 {
+    use windows::{
+        core::PCWSTR,
+        Win32::{
+            Foundation::ERROR_SUCCESS,
+            System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+        },
+    };
+
+    /// Value of the current user's registry. `None` if it isn't set, which is
+    /// the case for settings which were never changed.
+    pub fn read_dword(key: PCWSTR, value: PCWSTR) -> Option<u32> {
+        let mut data = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key,
+                value,
+                RRF_RT_REG_DWORD,
+                None,
+                Some(&mut data as *mut u32 as *mut _),
+                Some(&mut size),
+            )
+        };
+
+        (result == ERROR_SUCCESS).then_some(data)
+    }
+}
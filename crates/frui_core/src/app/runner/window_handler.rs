@@ -1,28 +1,99 @@
-use std::{cell::RefCell, sync::Mutex};
+use std::{cell::RefCell, sync::Mutex, time::Duration};
 
 use druid_shell::{
     kurbo::Rect,
     piet::{Color, RenderContext},
-    Cursor, IdleToken, KeyEvent, MouseEvent,
+    Cursor, IdleToken, KeyEvent, MouseEvent, Scale, TimerToken,
 };
 
 use crate::{
-    api::{pointer_events::events::PointerEvent, WidgetPtr},
+    api::{contexts::render::reset_paint_clip, pointer_events::events::PointerEvent, WidgetPtr},
     app::{
-        listeners::keyboard::KEYBOARD_EVENT_LISTENERS,
+        channels::Channels,
+        commands::Commands,
+        focus::FocusManager,
+        frame_budget::FrameBudget,
+        instrumentation::{Instrumentation, StartupPhase},
+        listeners::{
+            keyboard::KEYBOARD_EVENT_LISTENERS,
+            lifecycle::{self, LifecycleEvent, LifecycleEventListeners},
+        },
+        metrics::WindowMetrics,
+        plugins::Plugins,
+        restoration::Restoration,
+        snapshots::Snapshots,
+        text_input,
+        ticker::Tickers,
+        timer::{Timer, Timers},
         tree::{NodeRef, WidgetTree},
+        window::Window,
         TEXT_FACTORY,
     },
     prelude::Widget,
     render::*,
 };
 
-use super::{Application, Canvas, FruiWindowHandler, IdleHandle, WindowHandle};
+use super::{
+    native::{power, settings},
+    Application, Canvas, FruiWindowHandler, IdleHandle, WindowHandle,
+};
+
+#[cfg(not(feature = "miri"))]
+use super::native::hints::WindowHints;
+
+/// How often state that the windowing backend doesn't report (occlusion of
+/// the window and power source) is polled.
+const POLL_PERIOD: Duration = Duration::from_secs(1);
+
+/// How often occlusion is polled while the window is occluded, so that frames
+/// resume soon after it becomes visible again.
+const OCCLUDED_POLL_PERIOD: Duration = Duration::from_millis(50);
+
+/// Time between frames of running animations while on battery.
+const BATTERY_FRAME_INTERVAL: Duration = Duration::from_millis(33);
 
 thread_local! {
     pub(crate) static APP_HANDLE: std::cell::RefCell<Option<IdleHandle>> = RefCell::new(None);
 }
 
+/// Reads occlusion of the window and power source of the device, which the
+/// windowing backend doesn't report.
+fn poll_platform() {
+    poll_occlusion();
+    lifecycle::set_on_battery(power::on_battery().unwrap_or(false));
+}
+
+fn poll_occlusion() {
+    #[cfg(not(feature = "miri"))]
+    if let Some(occluded) = WINDOW_HANDLE.with(|h| h.borrow().as_ref().map(|h| h.is_occluded())) {
+        lifecycle::set_occluded(occluded);
+    }
+}
+
+/// Schedules an idle callback of the window, in which pending updates are
+/// processed. An embedded [`Engine`](crate::app::embed::Engine), which has no
+/// window, is woken instead.
+pub(crate) fn schedule_idle() {
+    let scheduled = APP_HANDLE.with(|handle| match handle.borrow_mut().as_mut() {
+        Some(handle) => {
+            handle.schedule_idle(IdleToken::new(0));
+            true
+        }
+        None => false,
+    });
+
+    if scheduled {
+        return;
+    }
+
+    #[cfg(not(feature = "miri"))]
+    crate::app::embed::wake();
+}
+
+thread_local! {
+    pub(crate) static WINDOW_HANDLE: RefCell<Option<WindowHandle>> = RefCell::new(None);
+}
+
 thread_local! {
     pub(crate) static NEED_REBUILD: Mutex<Vec<NodeRef>>  = Mutex::new(Vec::with_capacity(100));
 }
@@ -30,12 +101,23 @@ thread_local! {
 pub struct WindowHandler {
     /// Current size of main window.
     window_size: Size,
+    /// Color the window is cleared with before painting.
+    background: Color,
+    /// Restoration id under which window geometry is saved on exit.
+    geometry_id: Option<String>,
     /// Clone of window handle received from `connect`.
     window_handle: WindowHandle,
 
     pending_update: bool,
     widget_tree: WidgetTree,
 
+    /// Timer polling platform state, see [`POLL_PERIOD`].
+    poll_timer: Option<Timer>,
+    /// Timer polling occlusion while occluded, see [`OCCLUDED_POLL_PERIOD`].
+    occlusion_timer: Option<Timer>,
+    /// Timer requesting the next animation frame while on battery.
+    frame_timer: Option<Timer>,
+
     /// Temporary field to store root widget before constructing the widget tree
     /// (which requires WindowHandle which can be obtained only after `connect`).
     root_temp: Option<WidgetPtr<'static>>,
@@ -45,15 +127,37 @@ impl WindowHandler {
     pub fn new<W: Widget + 'static>(widget: W) -> Self {
         Self {
             window_size: Size::default(),
+            background: Color::from_hex_str("#202324").unwrap(),
+            geometry_id: None,
             window_handle: WindowHandle::default(),
             pending_update: true,
             widget_tree: WidgetTree::default(),
+            poll_timer: None,
+            occlusion_timer: None,
+            frame_timer: None,
             root_temp: Some(WidgetPtr::from_owned(Box::new(widget))),
         }
     }
 
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_geometry_id(mut self, geometry_id: Option<String>) -> Self {
+        self.geometry_id = geometry_id;
+        self
+    }
+
     /// Will schedule an update for the next frame.
+    ///
+    /// While the window is suspended no frames are produced and the update is
+    /// scheduled once the window is resumed.
     pub fn schedule_update(&mut self) {
+        if lifecycle::is_suspended() {
+            return;
+        }
+
         if !self.pending_update {
             self.pending_update = true;
             self.window_handle.invalidate();
@@ -61,98 +165,230 @@ impl WindowHandler {
         }
     }
 
-    fn rebuild_dirty(&mut self) {
-        NEED_REBUILD.with(|need_rebuild| {
-            let mut idx = 0;
+    /// Writes restoration data to disk, so that it survives the application
+    /// being killed while it's in the background. Geometry of the window is
+    /// stored first if `store_geometry` is set.
+    fn save_restoration(&self, store_geometry: bool) {
+        if !Restoration::is_enabled() {
+            return;
+        }
 
-            loop {
-                let need_rebuild = need_rebuild.lock().unwrap();
+        if let (true, Some(id)) = (store_geometry, &self.geometry_id) {
+            if let Some(geometry) = Window::geometry() {
+                Restoration::store(id, &geometry);
+            }
+        }
 
-                // We use idx instead of iterator, since every call to `update_subtree` may add
-                // new widgets that need to be rebuilt.
-                if idx == need_rebuild.len() {
-                    break;
-                }
+        if let Err(e) = Restoration::save() {
+            log::error!("saving restoration data failed: {}", e);
+        }
+    }
+
+    /// Notifies listeners if the window was suspended or resumed since
+    /// `was_suspended` was read.
+    fn suspended_changed(&mut self, was_suspended: bool) {
+        let suspended = lifecycle::is_suspended();
 
-                // Todo: Sort widgets according to their depth, which will allow us to avoid
-                // rebuilding some widgets multiple times. Remember to sort those widgets every
-                // time you update a widget (don't sort if length of vec didn't change).
+        if suspended == was_suspended {
+            return;
+        }
 
-                // Acquire node reference.
-                let node = need_rebuild[idx].clone();
+        LifecycleEventListeners::dispatch(&match suspended {
+            true => LifecycleEvent::Suspended,
+            false => LifecycleEvent::Resumed,
+        });
 
-                // Drop the lock.
-                drop(need_rebuild);
+        if suspended {
+            // Geometry of a hidden window isn't worth restoring.
+            self.save_restoration(false);
+        } else {
+            // Catch up with updates skipped while suspended.
+            self.pending_update = false;
+            self.schedule_update();
+        }
+    }
 
-                if node.is_alive() {
-                    if node.borrow().dirty {
-                        node.update_subtree();
-                    }
-                }
+    /// Rebuilds nodes marked dirty, in batches of nodes marked while the
+    /// previous batch was rebuilt.
+    fn rebuild_dirty(&mut self) {
+        loop {
+            let mut batch = NEED_REBUILD.with(|n| std::mem::take(&mut *n.lock().unwrap()));
 
-                idx += 1;
+            if batch.is_empty() {
+                break;
             }
 
-            need_rebuild.lock().unwrap().clear();
-        });
+            // Ancestors are rebuilt first, so that dirty descendants rebuilt
+            // along with them aren't rebuilt again.
+            batch.retain(|node| node.is_alive());
+            batch.sort_by_cached_key(|node| node.depth());
+
+            for node in batch {
+                // Rebuilding of an ancestor could have dropped or rebuilt it.
+                if node.is_alive() && node.borrow().dirty {
+                    log::trace!("rebuilding dirty `{}`", node.debug_name_short());
+                    Instrumentation::count(|s| s.dirty += 1);
+                    node.update_subtree();
+                }
+            }
+        }
     }
 }
 
 impl FruiWindowHandler for WindowHandler {
     fn connect(&mut self, handle: &WindowHandle) {
-        APP_HANDLE.with(|r| *r.borrow_mut() = Some(handle.get_idle_handle().unwrap()));
+        // Handles of embedded engines have no idle handle, see `schedule_idle`.
+        if let Some(idle) = handle.get_idle_handle() {
+            APP_HANDLE.with(|r| *r.borrow_mut() = Some(idle.clone()));
+            Channels::set_waker(idle);
+        }
+        WINDOW_HANDLE.with(|r| *r.borrow_mut() = Some(handle.clone()));
+
+        // Timers created before the window connected (e.g. in `main`)
+        // couldn't be requested until now.
+        Timers::request_next();
+
+        Instrumentation::engine_initialized();
 
         if !cfg!(feature = "miri") {
-            TEXT_FACTORY.with(|f| f.set(self.window_handle.text()));
+            Instrumentation::measure(StartupPhase::FontLoad, || {
+                TEXT_FACTORY.with(|f| f.set(self.window_handle.text()))
+            });
+
+            settings::update_metrics();
+
+            poll_platform();
+            self.poll_timer = Some(Timer::periodic(POLL_PERIOD, poll_platform));
         }
 
+        // Plugins may register services and fonts used by widgets.
+        Plugins::init();
+
         let root_widget = std::mem::take(&mut self.root_temp);
-        self.widget_tree = WidgetTree::new(root_widget.unwrap());
+        self.widget_tree = Instrumentation::measure(StartupPhase::FirstBuild, || {
+            WidgetTree::new(root_widget.unwrap())
+        });
         self.window_handle = handle.clone();
 
-        self.window_handle.set_cursor(&Cursor::Arrow);
+        Window::set_cursor(&Cursor::Arrow);
     }
 
     fn prepare_paint(&mut self) {
+        if !lifecycle::is_suspended() && Tickers::tick() {
+            if !lifecycle::is_on_battery() {
+                self.window_handle.request_anim_frame();
+            } else if !self.frame_timer.map_or(false, |t| t.is_active()) {
+                // Animations run at a lower frame rate to save power.
+                self.frame_timer =
+                    Some(Timer::once(BATTERY_FRAME_INTERVAL, Tickers::request_frame));
+            }
+        }
+
         self.window_handle.invalidate();
     }
 
-    fn paint(&mut self, piet: &mut Canvas, _invalid: &druid_shell::Region) {
+    fn paint(&mut self, piet: &mut Canvas, invalid: &druid_shell::Region) {
+        if lifecycle::is_suspended() {
+            return;
+        }
+
         //
-        // Fill screen with one color (temp).
+        // Clear the window. Clearing (as opposed to filling) replaces pixels,
+        // which keeps transparent backgrounds transparent.
 
         let size = self.window_size;
         let rect = Rect::new(0., 0., size.width, size.height);
-        let brush = &piet.solid_brush(Color::from_hex_str("#202324").unwrap());
 
-        druid_shell::piet::RenderContext::fill(piet, rect, brush);
+        piet.clear(rect, self.background.clone());
 
         //
         // Rebuild widget tree.
 
         self.pending_update = false;
+
+        FrameBudget::frame_started();
+        // State changed since the last frame could change enablement of
+        // commands, so listeners can mark widgets dirty before the rebuild.
+        Commands::update();
         self.rebuild_dirty();
+        FrameBudget::frame_built();
 
         //
         // Layout & Paint
 
         // Todo: Optimize layout.
-        self.widget_tree
-            .layout(Constraints::new_tight(self.window_size));
+        Instrumentation::measure(StartupPhase::FirstLayout, || {
+            self.widget_tree
+                .layout(Constraints::new_tight(self.window_size))
+        });
+
+        // Widgets outside of the invalidated area aren't painted.
+        reset_paint_clip(invalid.bounding_box());
+        Instrumentation::measure(StartupPhase::FirstPaint, || self.widget_tree.paint(piet));
+        Instrumentation::first_frame_painted();
+        Instrumentation::frame_painted();
 
-        // Todo: Optimize paint.
-        self.widget_tree.paint(piet);
+        Snapshots::frame_painted();
     }
 
     fn size(&mut self, size: druid_shell::kurbo::Size) {
         self.window_size = size.into();
+
+        // Druid-shell doesn't report minimizing directly, but the window is
+        // resized to zero when it happens.
+        let minimized = size.area() == 0.;
+
+        if !minimized {
+            WindowMetrics::set_size(self.window_size, Window::scale().unwrap_or(1.));
+        }
+
+        let was_suspended = lifecycle::is_suspended();
+        lifecycle::set_minimized(minimized);
+        self.suspended_changed(was_suspended);
+    }
+
+    fn scale(&mut self, scale: Scale) {
+        // Moving the window to a monitor of different density doesn't
+        // necessarily resize it.
+        WindowMetrics::set_scale(scale.x());
+        self.schedule_update();
     }
 
     fn idle(&mut self, _token: IdleToken) {
+        Channels::dispatch_pending();
         self.schedule_update();
     }
 
+    fn timer(&mut self, _token: TimerToken) {
+        // Occlusion of the window is polled from a timer.
+        let was_suspended = lifecycle::is_suspended();
+        Timers::fire_due();
+        self.suspended_changed(was_suspended);
+
+        // Occluded windows aren't notified once they become visible again.
+        if self.poll_timer.is_some() {
+            match (lifecycle::is_occluded(), self.occlusion_timer.take()) {
+                (true, None) => {
+                    self.occlusion_timer =
+                        Some(Timer::periodic(OCCLUDED_POLL_PERIOD, poll_occlusion))
+                }
+                (false, Some(timer)) => timer.cancel(),
+                (_, timer) => self.occlusion_timer = timer,
+            }
+        }
+    }
+
     fn destroy(&mut self) {
+        self.save_restoration(true);
+
+        let timers = [self.poll_timer, self.occlusion_timer, self.frame_timer];
+
+        for timer in timers.into_iter().flatten() {
+            timer.cancel();
+        }
+
+        Plugins::exit();
+
         Application::global().quit()
     }
 
@@ -173,10 +409,12 @@ impl FruiWindowHandler for WindowHandler {
     }
 
     fn mouse_move(&mut self, event: &MouseEvent) {
+        // Reset cursor before dispatching the event, so that widgets can
+        // override it.
+        Window::set_cursor(&Cursor::Arrow);
+
         self.widget_tree
             .handle_pointer_event(PointerEvent::new(event, "move"));
-
-        self.window_handle.set_cursor(&Cursor::Arrow);
     }
 
     fn wheel(&mut self, event: &MouseEvent) {
@@ -185,16 +423,50 @@ impl FruiWindowHandler for WindowHandler {
     }
 
     fn key_down(&mut self, event: KeyEvent) -> bool {
+        // Focused widgets handle keys before commands, e.g. clipboard
+        // shortcuts of text fields.
+        if FocusManager::handle_key(&event) {
+            return true;
+        }
+
+        // Keys without command modifiers are typed into the focused text field.
+        let mods = event.mods;
+        let typed = text_input::is_active() && !mods.ctrl() && !mods.alt() && !mods.meta();
+
+        if !typed && Commands::handle_shortcut(&event) {
+            return true;
+        }
+
         KEYBOARD_EVENT_LISTENERS.with(|listeners| {
             for listener in listeners.borrow_mut().iter() {
                 listener(event.clone());
             }
         });
 
-        true
+        // Unhandled events are turned into text input of the focused text
+        // field by the platform.
+        !text_input::is_active()
+    }
+
+    fn key_up(&mut self, event: KeyEvent) {
+        // Releases are only passed to focused widgets.
+        FocusManager::handle_key(&event);
+    }
+
+    fn got_focus(&mut self) {
+        if !cfg!(feature = "miri") {
+            settings::update_metrics();
+        }
+
+        LifecycleEventListeners::dispatch(&LifecycleEvent::Focused);
+    }
+
+    fn lost_focus(&mut self) {
+        LifecycleEventListeners::dispatch(&LifecycleEvent::Blurred);
+        self.save_restoration(true);
     }
 
     fn request_close(&mut self) {
-        self.window_handle.close();
+        lifecycle::request_exit();
     }
 }
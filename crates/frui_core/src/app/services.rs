@@ -0,0 +1,50 @@
+//! Registry of application-wide services (spell checking, clipboard, etc.).
+//!
+//! Services are looked up by their type, so a pluggable backend is usually
+//! registered as a boxed trait object, e.g. `Box<dyn SpellCheckService>`.
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+#[derive(Default)]
+pub struct Services {
+    services: HashMap<TypeId, Rc<dyn Any>>,
+}
+
+impl Services {
+    /// Registers `service`, replacing previously registered service of the
+    /// same type.
+    pub fn register<T: 'static>(service: T) {
+        SERVICES.with(|s| {
+            s.borrow_mut()
+                .services
+                .insert(TypeId::of::<T>(), Rc::new(service))
+        });
+    }
+
+    pub fn get<T: 'static>() -> Option<Rc<T>> {
+        SERVICES.with(|s| {
+            let service = s.borrow().services.get(&TypeId::of::<T>())?.clone();
+            Some(service.downcast().unwrap())
+        })
+    }
+
+    pub fn contains<T: 'static>() -> bool {
+        SERVICES.with(|s| s.borrow().services.contains_key(&TypeId::of::<T>()))
+    }
+
+    pub fn remove<T: 'static>() -> Option<Rc<T>> {
+        SERVICES.with(|s| {
+            let service = s.borrow_mut().services.remove(&TypeId::of::<T>())?;
+            Some(service.downcast().unwrap())
+        })
+    }
+}
+
+thread_local! {
+    static SERVICES: RefCell<Services> = Default::default();
+}
@@ -0,0 +1,312 @@
+//! Snapshots of widget states, which can be recorded every frame and restored
+//! later, e.g. by a debugger stepping back through the frames which led to a
+//! bug.
+//!
+//! Only states of widgets implementing [`SnapshotState`] are captured. Each
+//! captured state is identified by the path of its widget in the tree, so a
+//! snapshot can be restored into the same widget tree it was captured from,
+//! or into a tree built the same way (e.g. after restarting the application
+//! to reproduce a bug).
+//!
+//! ```ignore
+//! Snapshots::record(600);
+//!
+//! // Later, in the debugger:
+//! Snapshots::stop();
+//! let history = Snapshots::history();
+//! Snapshots::restore(&history[history.len() - 10]);
+//! ```
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+
+use frui_macros::sealed;
+
+use crate::{api::contexts::build_cx::WidgetState, app::tree::NodeRef};
+
+use super::{
+    diagnostics::MemoryDiagnostics,
+    restoration::{self, RestorableValue},
+};
+
+/// Widget state which can be captured in a [`Snapshot`].
+///
+/// States holding values which can't be encoded (e.g. animation controllers)
+/// can capture only some of their fields:
+///
+/// ```ignore
+/// impl SnapshotState for Counter {
+///     type Snapshot = i32;
+///
+///     fn snapshot(&self, state: &Self::State) -> i32 {
+///         state.count
+///     }
+///
+///     fn restore(&self, state: &mut Self::State, count: i32) {
+///         state.count = count;
+///     }
+/// }
+/// ```
+pub trait SnapshotState: WidgetState {
+    type Snapshot: RestorableValue;
+
+    fn snapshot(&self, state: &Self::State) -> Self::Snapshot;
+
+    /// Replaces the captured part of `state` with `snapshot`.
+    fn restore(&self, state: &mut Self::State, snapshot: Self::Snapshot);
+}
+
+#[sealed(crate)]
+pub trait SnapshotStateOS {
+    fn snapshot_os(&self, state: &dyn Any) -> Option<String>;
+    fn restore_os(&self, state: &mut dyn Any, data: &str) -> bool;
+}
+
+impl<T> SnapshotStateOS for T {
+    default fn snapshot_os(&self, _: &dyn Any) -> Option<String> {
+        None
+    }
+
+    default fn restore_os(&self, _: &mut dyn Any, _: &str) -> bool {
+        false
+    }
+}
+
+impl<T: SnapshotState> SnapshotStateOS for T {
+    fn snapshot_os(&self, state: &dyn Any) -> Option<String> {
+        let state = state.downcast_ref::<T::State>()?;
+        Some(T::snapshot(self, state).encode())
+    }
+
+    fn restore_os(&self, state: &mut dyn Any, data: &str) -> bool {
+        match (state.downcast_mut::<T::State>(), T::Snapshot::decode(data)) {
+            (Some(state), Some(snapshot)) => {
+                T::restore(self, state, snapshot);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Captured states of all widgets implementing [`SnapshotState`], keyed by
+/// the paths of those widgets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    states: HashMap<String, String>,
+}
+
+impl Snapshot {
+    /// Number of captured states.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Encoded state of the widget at `path`, e.g. `"App/0:Column/1:Counter"`.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.states.get(path).map(String::as_str)
+    }
+
+    /// Paths of all widgets whose states were captured, sorted.
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths = self.states.keys().map(String::as_str).collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    /// Encodes the snapshot, e.g. to attach it to a bug report.
+    pub fn encode(&self) -> String {
+        restoration::serialize(&self.states)
+    }
+
+    pub fn decode(data: &str) -> Snapshot {
+        Snapshot {
+            states: restoration::parse(data),
+        }
+    }
+}
+
+pub struct Snapshots;
+
+impl Snapshots {
+    /// Starts capturing a snapshot after every frame, keeping the last
+    /// `capacity` of them. Frames in which no captured state changed aren't
+    /// recorded.
+    pub fn record(capacity: usize) {
+        SNAPSHOTS.with(|s| {
+            let mut s = s.borrow_mut();
+            s.capacity = capacity;
+
+            while s.history.len() > capacity {
+                s.history.pop_front();
+            }
+        });
+    }
+
+    /// Stops recording, keeping the recorded history. Recording should be
+    /// stopped before restoring a snapshot, otherwise restored states are
+    /// recorded as a new frame.
+    pub fn stop() {
+        SNAPSHOTS.with(|s| s.borrow_mut().capacity = 0);
+    }
+
+    pub fn is_recording() -> bool {
+        SNAPSHOTS.with(|s| s.borrow().capacity > 0)
+    }
+
+    /// Recorded snapshots, from the oldest to the newest.
+    pub fn history() -> Vec<Rc<Snapshot>> {
+        SNAPSHOTS.with(|s| s.borrow().history.iter().cloned().collect())
+    }
+
+    pub fn clear() {
+        SNAPSHOTS.with(|s| s.borrow_mut().history.clear());
+    }
+
+    /// Captures a snapshot of the current window, or returns `None` if there
+    /// is no widget tree yet.
+    pub fn capture() -> Option<Snapshot> {
+        let root = MemoryDiagnostics::root()?;
+
+        let mut states = HashMap::new();
+        let mut path = root.debug_name_short().to_owned();
+        capture_node(&root, &mut path, &mut states);
+
+        Some(Snapshot { states })
+    }
+
+    /// Restores states captured in `snapshot` and rebuilds their widgets.
+    /// Widgets which are no longer in the tree (or whose snapshot type
+    /// changed) are skipped.
+    ///
+    /// Returns the number of restored states.
+    pub fn restore(snapshot: &Snapshot) -> usize {
+        match MemoryDiagnostics::root() {
+            Some(root) => {
+                let mut path = root.debug_name_short().to_owned();
+                restore_node(&root, &mut path, snapshot)
+            }
+            None => 0,
+        }
+    }
+
+    /// Called by the window after every painted frame.
+    pub(crate) fn frame_painted() {
+        if !Self::is_recording() {
+            return;
+        }
+
+        if let Some(snapshot) = Self::capture() {
+            SNAPSHOTS.with(|s| s.borrow_mut().push(snapshot));
+        }
+    }
+}
+
+#[derive(Default)]
+struct History {
+    /// Maximum number of recorded snapshots, or 0 if recording is stopped.
+    capacity: usize,
+    history: VecDeque<Rc<Snapshot>>,
+}
+
+impl History {
+    fn push(&mut self, snapshot: Snapshot) {
+        if self.history.back().map_or(false, |last| **last == snapshot) {
+            return;
+        }
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(Rc::new(snapshot));
+    }
+}
+
+fn capture_node(node: &NodeRef, path: &mut String, states: &mut HashMap<String, String>) {
+    if let Some(data) = node.widget().snapshot_os(&*node.borrow().state) {
+        states.insert(path.clone(), data);
+    }
+
+    for_each_child(node, path, |child, path| capture_node(child, path, states));
+}
+
+fn restore_node(node: &NodeRef, path: &mut String, snapshot: &Snapshot) -> usize {
+    let mut restored = 0;
+
+    if let Some(data) = snapshot.get(path) {
+        let widget = node.widget();
+
+        if widget.restore_os(&mut *node.borrow_mut().state, data) {
+            node.mark_dirty();
+            restored += 1;
+        }
+    }
+
+    for_each_child(node, path, |child, path| {
+        restored += restore_node(child, path, snapshot);
+    });
+
+    restored
+}
+
+/// Calls `f` with every child of `node` and its path, which is the path of
+/// `node` followed by the index and the name of that child.
+fn for_each_child(node: &NodeRef, path: &mut String, mut f: impl FnMut(&NodeRef, &mut String)) {
+    let len = path.len();
+
+    for (n, child) in node.children().iter().enumerate() {
+        path.push_str(&format!("/{}:{}", n, child.debug_name_short()));
+        f(child, path);
+        path.truncate(len);
+    }
+}
+
+thread_local! {
+    static SNAPSHOTS: RefCell<History> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(count: &str) -> Snapshot {
+        let mut states = HashMap::new();
+        states.insert("App/0:Counter".to_owned(), count.to_owned());
+        Snapshot { states }
+    }
+
+    #[test]
+    fn history_skips_unchanged_frames() {
+        let mut history = History {
+            capacity: 2,
+            ..Default::default()
+        };
+
+        for count in ["1", "1", "2", "3"] {
+            history.push(snapshot(count));
+        }
+
+        let counts = history
+            .history
+            .iter()
+            .map(|s| s.get("App/0:Counter").unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(counts, ["2", "3"]);
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let snapshot = snapshot("3:a=b\n");
+        assert_eq!(Snapshot::decode(&snapshot.encode()), snapshot);
+    }
+}
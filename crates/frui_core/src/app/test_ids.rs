@@ -0,0 +1,45 @@
+//! Stable identifiers used by UI tests and automation tools to locate widgets.
+//!
+//! Widgets wrapped in `TestId` (see `frui_widgets`) register their bounds
+//! under the given identifier every time they are painted, which can be then
+//! looked up with [`TestIds::find`] (e.g. by `MiriRunner::tap`).
+//
+// Todo: Expose identifiers to accessibility-driven test runners, once there
+// is a semantics tree.
+
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::render::Rect;
+
+pub struct TestIds;
+
+impl TestIds {
+    /// Records bounds (in window coordinates) of the widget with identifier
+    /// `id`. Identifiers are expected to be unique.
+    pub fn register(id: &str, rect: Rect) {
+        TEST_IDS.with(|ids| ids.borrow_mut().insert(id.to_owned(), rect));
+    }
+
+    pub fn unregister(id: &str) {
+        TEST_IDS.with(|ids| ids.borrow_mut().remove(id));
+    }
+
+    /// Bounds of the widget with identifier `id` from the last paint.
+    pub fn find(id: &str) -> Option<Rect> {
+        TEST_IDS.with(|ids| ids.borrow().get(id).copied())
+    }
+
+    /// All registered identifiers with bounds of their widgets, sorted by
+    /// identifier.
+    pub fn all() -> Vec<(String, Rect)> {
+        let mut all: Vec<_> =
+            TEST_IDS.with(|ids| ids.borrow().iter().map(|(k, v)| (k.clone(), *v)).collect());
+
+        all.sort_by(|a, b| a.0.cmp(&b.0));
+        all
+    }
+}
+
+thread_local! {
+    static TEST_IDS: RefCell<HashMap<String, Rect>> = Default::default();
+}
@@ -0,0 +1,276 @@
+//! Routing of platform text input to the focused text field: typed
+//! characters, editing actions of platform key bindings (e.g. moving the
+//! caret by words) and composition of input methods (IME).
+//!
+//! Text fields implement [`InputHandler`] and register it through a
+//! [`TextInputConnection`], which they focus while being edited. Key events
+//! not consumed by shortcuts are then delivered to keyboard listeners and
+//! afterwards turned into calls of the focused handler.
+
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, ops::Range, rc::Rc};
+
+use druid_shell::{
+    kurbo::{Point, Rect},
+    piet::HitTestPoint,
+    TextFieldToken,
+};
+
+pub use druid_shell::text::{
+    Action as TextAction, Affinity, Direction, Event as TextInputEvent, InputHandler, Movement,
+    Selection, VerticalMovement,
+};
+
+use super::runner::window_handler::WINDOW_HANDLE;
+
+/// Registration of a text field with the platform. The field is unregistered
+/// once the connection is dropped.
+pub struct TextInputConnection {
+    token: TextFieldToken,
+}
+
+impl TextInputConnection {
+    pub fn new(handler: Rc<RefCell<dyn InputHandler>>) -> Self {
+        let token = WINDOW_HANDLE
+            .with(|h| h.borrow().as_ref().map(|h| h.add_text_field()))
+            .unwrap_or_else(TextFieldToken::next);
+
+        TEXT_INPUT.with(|t| t.borrow_mut().handlers.insert(token, handler));
+
+        TextInputConnection { token }
+    }
+
+    /// Routes text input to this connection, until [`blur`](Self::blur) is
+    /// called or another connection is focused, in which case `on_blur` is
+    /// called.
+    pub fn focus(&self, on_blur: impl FnOnce() + 'static) {
+        let previous = TEXT_INPUT.with(|t| {
+            let mut input = t.borrow_mut();
+            input
+                .focused
+                .replace((self.token, Box::new(on_blur)))
+                .filter(|(token, _)| *token != self.token)
+        });
+
+        set_focused_text_field(Some(self.token));
+
+        // Called outside of the borrow, since it will likely blur the
+        // previous connection.
+        if let Some((_, on_blur)) = previous {
+            on_blur();
+        }
+    }
+
+    /// Stops routing text input to this connection, without calling the
+    /// `on_blur` callback.
+    pub fn blur(&self) {
+        let was_focused = TEXT_INPUT.with(|t| {
+            let mut input = t.borrow_mut();
+
+            match &input.focused {
+                Some((token, _)) if *token == self.token => input.focused.take().is_some(),
+                _ => false,
+            }
+        });
+
+        if was_focused {
+            set_focused_text_field(None);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        TEXT_INPUT.with(|t| matches!(&t.borrow().focused, Some((token, _)) if *token == self.token))
+    }
+
+    /// Informs input methods that the text, selection or layout of the field
+    /// changed other than through its [`InputHandler`], e.g. because it was
+    /// set programmatically. Mustn't be called from within the handler.
+    pub fn notify(&self, event: TextInputEvent) {
+        if !self.is_focused() {
+            return;
+        }
+
+        WINDOW_HANDLE.with(|h| {
+            if let Some(handle) = h.borrow().as_ref() {
+                handle.update_text_field(self.token, event);
+            }
+        });
+    }
+}
+
+impl Drop for TextInputConnection {
+    fn drop(&mut self) {
+        self.blur();
+
+        TEXT_INPUT.with(|t| t.borrow_mut().handlers.remove(&self.token));
+        WINDOW_HANDLE.with(|h| {
+            if let Some(handle) = h.borrow().as_ref() {
+                handle.remove_text_field(self.token);
+            }
+        });
+    }
+}
+
+/// Whether a text field is focused, in which case key events not handled by
+/// shortcuts are passed to it.
+pub(crate) fn is_active() -> bool {
+    TEXT_INPUT.with(|t| t.borrow().focused.is_some())
+}
+
+/// Replaces the selection of the focused text field with `text`, for hosts
+/// which deliver committed text themselves. Returns `false` if no field is
+/// focused.
+pub(crate) fn insert_text(text: &str) -> bool {
+    let handler = TEXT_INPUT.with(|t| {
+        let input = t.borrow();
+        let (token, _) = input.focused.as_ref()?;
+        input.handlers.get(token).cloned()
+    });
+
+    let handler = match handler {
+        Some(handler) => handler,
+        None => return false,
+    };
+
+    let mut handler = handler.borrow_mut();
+    let range = handler.selection().range();
+    handler.replace_range(range.clone(), text);
+    handler.set_selection(Selection::caret(range.start + text.len()));
+
+    true
+}
+
+/// Handler of the text field registered under `token`, requested by the
+/// platform.
+pub(crate) fn input_handler(token: TextFieldToken) -> Box<dyn InputHandler> {
+    let handler = TEXT_INPUT.with(|t| t.borrow().handlers.get(&token).cloned());
+
+    match handler {
+        Some(handler) => Box::new(SharedInputHandler(handler)),
+        None => Box::new(SharedInputHandler(Rc::new(RefCell::new(EmptyInputHandler)))),
+    }
+}
+
+fn set_focused_text_field(token: Option<TextFieldToken>) {
+    WINDOW_HANDLE.with(|h| {
+        if let Some(handle) = h.borrow().as_ref() {
+            handle.set_focused_text_field(token);
+        }
+    });
+}
+
+#[derive(Default)]
+struct TextInput {
+    handlers: HashMap<TextFieldToken, Rc<RefCell<dyn InputHandler>>>,
+    focused: Option<(TextFieldToken, Box<dyn FnOnce()>)>,
+}
+
+thread_local! {
+    static TEXT_INPUT: RefCell<TextInput> = Default::default();
+}
+
+/// Handler borrowed by the platform, which can't hold onto a borrow of a
+/// `RefCell` for the duration of the input lock.
+struct SharedInputHandler(Rc<RefCell<dyn InputHandler>>);
+
+impl InputHandler for SharedInputHandler {
+    fn selection(&self) -> Selection {
+        self.0.borrow().selection()
+    }
+
+    fn set_selection(&mut self, selection: Selection) {
+        self.0.borrow_mut().set_selection(selection)
+    }
+
+    fn composition_range(&self) -> Option<Range<usize>> {
+        self.0.borrow().composition_range()
+    }
+
+    fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+        self.0.borrow_mut().set_composition_range(range)
+    }
+
+    fn is_char_boundary(&self, i: usize) -> bool {
+        self.0.borrow().is_char_boundary(i)
+    }
+
+    fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    fn slice(&self, range: Range<usize>) -> Cow<str> {
+        Cow::Owned(self.0.borrow().slice(range).into_owned())
+    }
+
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        self.0.borrow_mut().replace_range(range, text)
+    }
+
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        self.0.borrow().hit_test_point(point)
+    }
+
+    fn line_range(&self, index: usize, affinity: Affinity) -> Range<usize> {
+        self.0.borrow().line_range(index, affinity)
+    }
+
+    fn bounding_box(&self) -> Option<Rect> {
+        self.0.borrow().bounding_box()
+    }
+
+    fn slice_bounding_box(&self, range: Range<usize>) -> Option<Rect> {
+        self.0.borrow().slice_bounding_box(range)
+    }
+
+    fn handle_action(&mut self, action: TextAction) {
+        self.0.borrow_mut().handle_action(action)
+    }
+}
+
+/// Handler of a text field which was already unregistered.
+struct EmptyInputHandler;
+
+impl InputHandler for EmptyInputHandler {
+    fn selection(&self) -> Selection {
+        Selection::caret(0)
+    }
+
+    fn set_selection(&mut self, _: Selection) {}
+
+    fn composition_range(&self) -> Option<Range<usize>> {
+        None
+    }
+
+    fn set_composition_range(&mut self, _: Option<Range<usize>>) {}
+
+    fn is_char_boundary(&self, i: usize) -> bool {
+        i == 0
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn slice(&self, _: Range<usize>) -> Cow<str> {
+        Cow::Borrowed("")
+    }
+
+    fn replace_range(&mut self, _: Range<usize>, _: &str) {}
+
+    fn hit_test_point(&self, _: Point) -> HitTestPoint {
+        HitTestPoint::default()
+    }
+
+    fn line_range(&self, _: usize, _: Affinity) -> Range<usize> {
+        0..0
+    }
+
+    fn bounding_box(&self) -> Option<Rect> {
+        None
+    }
+
+    fn slice_bounding_box(&self, _: Range<usize>) -> Option<Rect> {
+        None
+    }
+
+    fn handle_action(&mut self, _: TextAction) {}
+}
@@ -0,0 +1,138 @@
+//! Callbacks called once per frame, in sync with the refresh rate of the
+//! display, which drive animations.
+//!
+//! While any ticker is active, frames are produced continuously. Tickers are
+//! ticked right before the frame is built, so widgets updated by a ticker are
+//! rebuilt and painted in the same frame.
+
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+use slotmap::SlotMap;
+
+use super::{
+    leaks::{LeakDetector, Tracked},
+    runner::window_handler::WINDOW_HANDLE,
+};
+
+slotmap::new_key_type! { struct TickerKey; }
+
+/// Calls its callback with the time elapsed since it was started, once per
+/// frame while it is active. The ticker is removed once it is dropped.
+#[derive(Debug)]
+pub struct Ticker {
+    key: TickerKey,
+    _tracked: Tracked,
+}
+
+impl Ticker {
+    /// Creates an inactive ticker.
+    pub fn new(callback: impl FnMut(Duration) + 'static) -> Ticker {
+        let key = TICKERS.with(|t| {
+            t.borrow_mut().insert(TickerEntry {
+                start: None,
+                callback: Some(Box::new(callback)),
+            })
+        });
+
+        Ticker {
+            key,
+            _tracked: LeakDetector::track("Ticker"),
+        }
+    }
+
+    /// Starts ticking from zero elapsed time, also if the ticker is already
+    /// active.
+    pub fn start(&self) {
+        TICKERS.with(|t| {
+            if let Some(entry) = t.borrow_mut().get_mut(self.key) {
+                entry.start = Some(Instant::now());
+            }
+        });
+
+        Tickers::request_frame();
+    }
+
+    pub fn stop(&self) {
+        TICKERS.with(|t| {
+            if let Some(entry) = t.borrow_mut().get_mut(self.key) {
+                entry.start = None;
+            }
+        });
+    }
+
+    pub fn is_active(&self) -> bool {
+        TICKERS.with(|t| {
+            t.borrow()
+                .get(self.key)
+                .map_or(false, |e| e.start.is_some())
+        })
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        let _ = TICKERS.try_with(|t| t.borrow_mut().remove(self.key));
+    }
+}
+
+struct TickerEntry {
+    /// Time at which the ticker was started, `None` if it isn't active.
+    start: Option<Instant>,
+    /// Taken out of the entry for the duration of the call.
+    callback: Option<Box<dyn FnMut(Duration)>>,
+}
+
+pub(crate) struct Tickers;
+
+impl Tickers {
+    /// Ticks all active tickers. Returns whether any of them is still active
+    /// afterwards, in which case another frame should be requested.
+    pub(crate) fn tick() -> bool {
+        let now = Instant::now();
+
+        let active = TICKERS.with(|t| {
+            t.borrow()
+                .iter()
+                .filter_map(|(key, e)| Some((key, e.start?)))
+                .collect::<Vec<_>>()
+        });
+
+        for (key, start) in active {
+            // Ticker could have been stopped or dropped by a previous callback.
+            let callback = TICKERS.with(|t| match t.borrow_mut().get_mut(key) {
+                Some(entry) if entry.start == Some(start) => entry.callback.take(),
+                _ => None,
+            });
+
+            if let Some(mut callback) = callback {
+                callback(now.saturating_duration_since(start));
+
+                TICKERS.with(|t| {
+                    if let Some(entry) = t.borrow_mut().get_mut(key) {
+                        entry.callback = Some(callback);
+                    }
+                });
+            }
+        }
+
+        TICKERS.with(|t| t.borrow().values().any(|e| e.start.is_some()))
+    }
+
+    pub(crate) fn request_frame() {
+        WINDOW_HANDLE.with(|handle| {
+            // Tickers started before the window was connected start ticking
+            // with the first frame.
+            if let Some(handle) = handle.borrow_mut().as_mut() {
+                handle.invalidate();
+                handle.request_anim_frame();
+            }
+        });
+    }
+}
+
+thread_local! {
+    static TICKERS: RefCell<SlotMap<TickerKey, TickerEntry>> = Default::default();
+}
@@ -0,0 +1,209 @@
+//! Timers firing on the UI thread.
+//!
+//! Timers are driven by the platform timer of the window, so their callbacks
+//! never race with the widget tree. Timers created through [`BuildCx`] are
+//! additionally owned by a widget and are cancelled once that widget is
+//! removed from the tree.
+//!
+//! [`BuildCx`]: crate::prelude::BuildCx
+
+use std::{
+    cell::{Cell, RefCell},
+    time::{Duration, Instant},
+};
+
+use slotmap::SlotMap;
+
+use super::{
+    leaks::{LeakDetector, Tracked},
+    runner::window_handler::WINDOW_HANDLE,
+    tree::{NodeRef, WeakNodeRef},
+};
+
+slotmap::new_key_type! { struct TimerKey; }
+
+/// Handle to a scheduled timer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timer {
+    key: TimerKey,
+}
+
+impl Timer {
+    /// Calls `callback` once, after `duration` elapses.
+    pub fn once(duration: Duration, callback: impl FnOnce() + 'static) -> Timer {
+        let mut callback = Some(callback);
+
+        Self::schedule(duration, None, None, move || {
+            if let Some(callback) = callback.take() {
+                callback()
+            }
+        })
+    }
+
+    /// Calls `callback` every `period` until the timer is cancelled.
+    pub fn periodic(period: Duration, callback: impl FnMut() + 'static) -> Timer {
+        Self::schedule(period, Some(period), None, callback)
+    }
+
+    pub(crate) fn schedule(
+        duration: Duration,
+        period: Option<Duration>,
+        owner: Option<WeakNodeRef>,
+        callback: impl FnMut() + 'static,
+    ) -> Timer {
+        let key = TIMERS.with(|timers| {
+            timers.borrow_mut().timers.insert(TimerEntry {
+                deadline: now() + duration,
+                period,
+                owner,
+                callback: Some(Box::new(callback)),
+                // Periodic timers run until cancelled, so forgetting to cancel
+                // them is reported as a leak.
+                _tracked: period.map(|_| LeakDetector::track("Timer")),
+            })
+        });
+
+        Timers::request_next();
+
+        Timer { key }
+    }
+
+    pub fn cancel(&self) {
+        TIMERS.with(|timers| timers.borrow_mut().timers.remove(self.key));
+    }
+
+    /// Whether the timer is still scheduled to fire.
+    pub fn is_active(&self) -> bool {
+        TIMERS.with(|timers| timers.borrow().timers.contains_key(self.key))
+    }
+}
+
+struct TimerEntry {
+    deadline: Instant,
+    period: Option<Duration>,
+    /// Widget which owns this timer.
+    owner: Option<WeakNodeRef>,
+    /// Taken out of the entry for the duration of the call.
+    callback: Option<Box<dyn FnMut()>>,
+    _tracked: Option<Tracked>,
+}
+
+#[derive(Default)]
+pub(crate) struct Timers {
+    timers: SlotMap<TimerKey, TimerEntry>,
+    /// Deadline for which the platform timer was last requested.
+    requested: Option<Instant>,
+}
+
+impl Timers {
+    /// Fires all timers whose deadline has passed and requests the platform
+    /// timer for the next deadline.
+    pub(crate) fn fire_due() {
+        let now = now();
+
+        let mut due = TIMERS.with(|timers| {
+            let mut timers = timers.borrow_mut();
+            timers.requested = None;
+
+            timers
+                .timers
+                .iter()
+                .filter(|(_, t)| t.deadline <= now)
+                .map(|(key, t)| (key, t.deadline))
+                .collect::<Vec<_>>()
+        });
+
+        due.sort_by_key(|(_, deadline)| *deadline);
+
+        for (key, _) in due {
+            // Timer could have been cancelled by a previous callback.
+            let callback = TIMERS.with(|timers| {
+                let mut timers = timers.borrow_mut();
+                let timer = timers.timers.get_mut(key)?;
+                let period = timer.period;
+
+                match period {
+                    Some(period) => {
+                        timer.deadline += period;
+
+                        // Skip missed periods instead of firing them in a burst.
+                        if timer.deadline <= now {
+                            timer.deadline = now + period;
+                        }
+
+                        timer.callback.take()
+                    }
+                    None => timers.timers.remove(key)?.callback,
+                }
+            });
+
+            if let Some(mut callback) = callback {
+                callback();
+
+                TIMERS.with(|timers| {
+                    if let Some(timer) = timers.borrow_mut().timers.get_mut(key) {
+                        timer.callback = Some(callback);
+                    }
+                });
+            }
+        }
+
+        Self::request_next();
+    }
+
+    /// Cancels all timers owned by `node`. Called when the node is dropped.
+    pub(crate) fn cancel_owned_by(node: &NodeRef) {
+        TIMERS.with(|timers| {
+            timers
+                .borrow_mut()
+                .timers
+                .retain(|_, t| !t.owner.as_ref().map_or(false, |owner| owner.is(node)))
+        });
+    }
+
+    pub(crate) fn request_next() {
+        WINDOW_HANDLE.with(|handle| {
+            let handle = handle.borrow();
+
+            // Timers scheduled before the window was connected are requested
+            // once it connects.
+            let handle = match &*handle {
+                Some(handle) => handle,
+                None => return,
+            };
+
+            TIMERS.with(|timers| {
+                let mut timers = timers.borrow_mut();
+
+                let next = match timers.timers.values().map(|t| t.deadline).min() {
+                    Some(next) => next,
+                    None => return,
+                };
+
+                if timers.requested.map_or(true, |requested| next < requested) {
+                    timers.requested = Some(next);
+                    handle.request_timer(next.saturating_duration_since(now()));
+                }
+            });
+        });
+    }
+}
+
+/// Current time as seen by timers.
+pub(crate) fn now() -> Instant {
+    Instant::now() + SKIPPED.with(Cell::get)
+}
+
+/// Advances the clock of timers by `duration`, so that tests can fire timers
+/// without waiting for them.
+#[cfg(any(test, feature = "miri"))]
+pub(crate) fn skip_time(duration: Duration) {
+    SKIPPED.with(|skipped| skipped.set(skipped.get() + duration));
+}
+
+thread_local! {
+    static TIMERS: RefCell<Timers> = Default::default();
+
+    /// Time skipped by [`skip_time`]. Always zero outside of tests.
+    static SKIPPED: Cell<Duration> = Cell::new(Duration::ZERO);
+}
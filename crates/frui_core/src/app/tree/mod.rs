@@ -1,20 +1,42 @@
+//! Widget tree.
+//!
+//! ## Ownership
+//!
+//! Every [`Node`] is owned by its parent (and the root node by the
+//! [`WidgetTree`]), and is deallocated together with its subtree when the
+//! parent drops it, regardless of other references to it. [`NodeRef`] doesn't
+//! own the node, but it keeps its liveness flag allocated. It should be only
+//! held by the tree itself and for the duration of a frame.
+//!
+//! References which cross the tree or outlive a frame (pointer capture, owners
+//! of timers, state updaters, etc.) should use [`WeakNodeRef`], which doesn't
+//! keep anything allocated once the node is dropped.
+
 use std::{
     any::{Any, TypeId},
     cell::{Cell, Ref, RefCell, RefMut},
     collections::{HashMap, HashSet},
     hash::Hash,
-    rc::Rc,
+    rc::{Rc, Weak},
 };
 
-use druid_shell::IdleToken;
+use druid_shell::kurbo::{Affine, Rect};
 
 use crate::{
     api::{
         contexts::{render::LayoutCxOS, RawBuildCx},
+        implementers::inherited::InheritedValueOS,
         pointer_events::events::PointerEvent,
         IntoWidgetPtr, WidgetPtr,
     },
-    app::runner::window_handler::{APP_HANDLE, NEED_REBUILD},
+    app::{
+        diagnostics::MemoryDiagnostics,
+        focus::FocusManager,
+        instrumentation::Instrumentation,
+        leaks::LeakDetector,
+        runner::window_handler::{schedule_idle, NEED_REBUILD},
+        timer::Timers,
+    },
     macro_exports::{PaintCxOS, RawWidget},
     render::{Canvas, Constraints, Offset, Size},
 };
@@ -42,6 +64,8 @@ impl WidgetTree {
 
         dummy_node.borrow_mut().children.push(root_node.clone());
 
+        MemoryDiagnostics::set_root(Some(&root_node));
+
         Self {
             root_node,
             dummy_node,
@@ -50,6 +74,8 @@ impl WidgetTree {
     }
 
     pub fn layout(&mut self, constraints: Constraints) {
+        // Focus nodes are attached again in layout order.
+        FocusManager::begin_layout();
         LayoutCxOS::new(self.root_node.clone()).layout(constraints);
     }
 
@@ -65,6 +91,10 @@ impl WidgetTree {
 
 impl Drop for WidgetTree {
     fn drop(&mut self) {
+        if MemoryDiagnostics::is_root(&self.root_node) {
+            MemoryDiagnostics::set_root(None);
+        }
+
         self.dummy_node.drop();
     }
 }
@@ -88,6 +118,12 @@ pub(crate) struct NodeInner {
     pub inheritance: Inheritance,
 }
 
+impl NodeInner {
+    pub(crate) fn children_capacity(&self) -> usize {
+        self.children.capacity()
+    }
+}
+
 pub(crate) struct Node {
     pub inner: RefCell<NodeInner>,
 }
@@ -98,6 +134,9 @@ impl Node {
         let widget_ptr =
             unsafe { std::mem::transmute::<WidgetPtr, WidgetPtr<'static>>(widget.clone()) };
 
+        // Resources created along with the state are owned by this widget.
+        let (state, tracked) = LeakDetector::collect(|| widget.raw().create_state());
+
         let node = Box::into_raw(Box::new(Node {
             inner: RefCell::new(NodeInner {
                 is_alive: Rc::new(Cell::new(std::ptr::null_mut())),
@@ -105,7 +144,7 @@ impl Node {
                 parent,
                 children: Vec::new(),
                 dirty: false,
-                state: widget.raw().create_state(),
+                state,
                 render_data: RenderData::new(widget.raw()),
                 inheritance: Inheritance::new(&widget, &inherited_ancestor),
             }),
@@ -119,6 +158,8 @@ impl Node {
             NodeRef { ptr: is_alive }
         };
 
+        LeakDetector::adopt(tracked, &node_ref);
+
         //
         // Insert this node to `active_inheritors`.
 
@@ -129,6 +170,10 @@ impl Node {
         {
             inherited_ancestor = node_ref.clone();
             active_inheritors.insert(widget.inherited_key(), node_ref.clone());
+
+            if let Some((key, _)) = widget.raw().inherited_value() {
+                active_inheritors.insert(key, node_ref.clone());
+            }
         }
 
         //
@@ -144,8 +189,12 @@ impl Node {
 
         let cx = unsafe { std::mem::transmute::<*mut Node, &RawBuildCx>(node) };
 
-        let children = widget
-            .build(cx)
+        Instrumentation::count(|s| s.rebuilt += 1);
+
+        let (children, tracked) = LeakDetector::collect(|| widget.build(cx));
+        LeakDetector::adopt(tracked, &node_ref);
+
+        let children = children
             .into_iter()
             .map(|child_widget_ptr| {
                 Node::new(
@@ -199,6 +248,19 @@ impl NodeRef {
         self.borrow().children.get(n).map(|v| v.clone())
     }
 
+    /// Number of ancestors of this node.
+    pub(crate) fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut parent = self.borrow().parent.clone();
+
+        while let Some(node) = parent {
+            depth += 1;
+            parent = node.borrow().parent.clone();
+        }
+
+        depth
+    }
+
     #[track_caller]
     pub(crate) fn children(&self) -> Vec<NodeRef> {
         assert!(self.is_alive());
@@ -231,13 +293,20 @@ impl NodeRef {
             .map(|c| Some(c))
             .collect::<Vec<_>>();
 
+        // Keyed children are matched by their keys regardless of their
+        // positions, e.g. to keep their state when a child is inserted before
+        // them.
+        let old_keys = KeyIndex::new(&old_children);
+
         let cx = unsafe { std::mem::transmute::<*mut Node, &RawBuildCx>(self.ptr.get()) };
-        let new_children_build = self.widget().build(cx);
+        Instrumentation::count(|s| s.rebuilt += 1);
+        let (new_children_build, tracked) = LeakDetector::collect(|| self.widget().build(cx));
+        LeakDetector::adopt(tracked, self);
         let mut new_children = Vec::with_capacity(new_children_build.len());
 
         for (n, new_child) in new_children_build.into_iter().enumerate() {
             if new_child.has_key() {
-                if let Some(n) = old_children.find_key(&new_child) {
+                if let Some(n) = old_keys.find(&old_children, &new_child) {
                     // Remove old_child from old_children.
                     let old_child = std::mem::take(old_children.get_mut(n).unwrap()).unwrap();
 
@@ -332,6 +401,8 @@ impl NodeRef {
 
                 return self.clone();
             } else {
+                let value_changed = new_widget.raw().inherited_value_changed(old_widget.raw());
+
                 // Unmount old widget.
                 self.unmount();
 
@@ -343,6 +414,11 @@ impl NodeRef {
                 let old_widget_ptr =
                     std::mem::replace(&mut self.borrow_mut().widget_ptr, new_widget);
 
+                // Parent data provided by the widget could have changed along
+                // with its configuration.
+                self.widget()
+                    .update_parent_data(&mut self.borrow_mut().render_data.parent_data);
+
                 // Update descendants of this node, stopping at equal widget configurations
                 // or a leaf node.
                 self.update_subtree();
@@ -356,6 +432,12 @@ impl NodeRef {
                 // Mount updated widget.
                 self.mount();
 
+                // Dependents may not have been rebuilt above, e.g. if they are
+                // built by a widget whose configuration didn't change.
+                if value_changed {
+                    self.mark_dependent_widgets_as_dirty();
+                }
+
                 return self.clone();
             }
         } else {
@@ -382,7 +464,8 @@ impl NodeRef {
         let widget = self.borrow().widget_ptr.clone();
         let context = unsafe { std::mem::transmute::<*mut Node, &RawBuildCx>(self.ptr.get()) };
 
-        widget.mount(context)
+        let ((), tracked) = LeakDetector::collect(|| widget.mount(context));
+        LeakDetector::adopt(tracked, self);
     }
 
     pub fn unmount(&self) {
@@ -403,6 +486,16 @@ impl NodeRef {
 
         self.unmount();
 
+        //
+        // Cancel timers owned by this widget.
+
+        Timers::cancel_owned_by(self);
+
+        //
+        // Resources created by this widget should be dropped along with it.
+
+        let owned = LeakDetector::owned_by(self);
+
         //
         // Deallocate children.
 
@@ -437,6 +530,11 @@ impl NodeRef {
             // From this point on `RawBuildCx` should not be accessed again.
         }
 
+        //
+        // Report resources created by this widget which outlived it.
+
+        LeakDetector::report_leaked(owned);
+
         //
         // Disable this widget.
 
@@ -447,16 +545,16 @@ impl NodeRef {
 /// Used by API.
 
 impl NodeRef {
+    /// Schedules this node to be rebuilt in the next frame.
+    ///
+    /// Only this node is rebuilt, along with descendants whose configuration
+    /// it changes. Siblings and other unrelated nodes are never rebuilt, see
+    /// [`FrameSummary`](crate::app::instrumentation::FrameSummary) for what a
+    /// frame rebuilt.
     pub fn mark_dirty(&self) {
         assert!(self.is_alive());
 
-        APP_HANDLE.with(|handle| {
-            handle
-                .borrow_mut()
-                .as_mut()
-                .expect("APP_HANDLE wasn't set")
-                .schedule_idle(IdleToken::new(0));
-        });
+        schedule_idle();
 
         if !self.borrow().dirty {
             self.borrow_mut().dirty = true;
@@ -467,6 +565,11 @@ impl NodeRef {
         }
     }
 
+    /// Whether this node holds an [`InheritedWidget`](crate::prelude::InheritedWidget).
+    pub(crate) fn is_inheritor(&self) -> bool {
+        matches!(self.borrow().inheritance, Inheritance::Inheritor { .. })
+    }
+
     pub fn mark_dependent_widgets_as_dirty(&self) {
         assert!(self.is_alive());
 
@@ -550,6 +653,67 @@ impl std::fmt::Debug for NodeRef {
     }
 }
 
+/// Identity of a node, e.g. for indexing data kept about it elsewhere. Ids of
+/// removed nodes may be reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct NodeId(usize);
+
+impl NodeRef {
+    pub(crate) fn id(&self) -> NodeId {
+        NodeId(Rc::as_ptr(&self.ptr) as usize)
+    }
+
+    pub fn downgrade(&self) -> WeakNodeRef {
+        WeakNodeRef {
+            ptr: Rc::downgrade(&self.ptr),
+        }
+    }
+}
+
+/// Non-owning reference to a widget [`Node`], see [module](self) docs.
+#[derive(Clone)]
+pub struct WeakNodeRef {
+    ptr: Weak<Cell<*mut Node>>,
+}
+
+impl WeakNodeRef {
+    /// Returns `None` if the node was removed from the tree.
+    pub fn upgrade(&self) -> Option<NodeRef> {
+        let ptr = self.ptr.upgrade()?;
+
+        match ptr.get().is_null() {
+            true => None,
+            false => Some(NodeRef { ptr }),
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.upgrade().is_some()
+    }
+
+    /// Whether this references the same node as `node`.
+    pub fn is(&self, node: &NodeRef) -> bool {
+        self.ptr.as_ptr() == Rc::as_ptr(&node.ptr)
+    }
+}
+
+impl PartialEq for WeakNodeRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr.ptr_eq(&other.ptr)
+    }
+}
+
+impl Eq for WeakNodeRef {}
+
+impl std::fmt::Debug for WeakNodeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.upgrade() {
+            Some(node) => write!(f, "WeakNodeRef ({})", node.debug_name_short()),
+            None => f.write_str("WeakNodeRef (removed)"),
+        }
+    }
+}
+
 pub(crate) struct RenderData {
     /// Render state used by this widget.
     pub state: Box<dyn Any>,
@@ -560,6 +724,12 @@ pub(crate) struct RenderData {
     pub size: Size,
     /// Offset received during last paint.
     pub local_offset: Offset,
+    /// Transformation from local coordinates of this widget to window
+    /// coordinates, during last paint.
+    pub paint_transform: Option<Affine>,
+    /// Area painted by this widget and its descendants during last paint, in
+    /// local coordinates of this widget.
+    pub paint_bounds: Option<Rect>,
     /// Incoming constraints received during last layout.
     pub constraints: Constraints,
 
@@ -575,6 +745,8 @@ impl RenderData {
             parent_data: widget.create_parent_data(),
             size: Size::default(),
             local_offset: Offset::default(),
+            paint_transform: None,
+            paint_bounds: None,
             constraints: Constraints::default(),
             laid_out: false,
         }
@@ -664,32 +836,56 @@ impl Default for Inheritance {
 //
 // Helpers.
 
-trait FindKey {
-    /// `key` must be valid as well as pointers in self`.
-    fn find_key(&mut self, key: &WidgetPtr) -> Option<usize>;
+/// Keyed children of a node being rebuilt, by hashes of their keys.
+struct KeyIndex {
+    hashed: HashMap<u64, Vec<usize>>,
+    /// Children whose keys aren't hashable.
+    unhashed: Vec<usize>,
 }
 
-impl FindKey for Vec<Option<NodeRef>> {
-    fn find_key(&mut self, key: &WidgetPtr) -> Option<usize> {
-        if let None = key.raw().local_key() {
-            return None;
-        }
+impl KeyIndex {
+    /// Pointers in `children` must be valid.
+    fn new(children: &[Option<NodeRef>]) -> Self {
+        let mut index = KeyIndex {
+            hashed: HashMap::new(),
+            unhashed: Vec::new(),
+        };
 
-        self.iter_mut()
-            .enumerate()
-            .find(|(_, w)| match w {
-                Some(w) => {
-                    let node = w.widget();
-                    let local_key_any = node.local_key();
+        for (n, child) in children.iter().enumerate() {
+            let child = match child {
+                Some(child) => child,
+                None => continue,
+            };
 
-                    match local_key_any {
-                        Some(k) => k == key.raw().local_key().unwrap(),
-                        None => false,
-                    }
+            if let Some(key) = child.widget().local_key() {
+                match key.hash() {
+                    Some(hash) => index.hashed.entry(hash).or_default().push(n),
+                    None => index.unhashed.push(n),
                 }
-                None => false,
-            })
-            .map(|(n, _)| n)
+            }
+        }
+
+        index
+    }
+
+    /// Position of a child in `children` with key equal to the key of
+    /// `widget`. Children which were taken out of `children` are skipped.
+    ///
+    /// `widget` must be valid as well as pointers in `children`.
+    fn find(&self, children: &[Option<NodeRef>], widget: &WidgetPtr) -> Option<usize> {
+        let key = widget.raw().local_key()?;
+
+        // Keys of different types are never equal, so hashable keys are only
+        // compared with hashable keys.
+        let candidates = match key.hash() {
+            Some(hash) => self.hashed.get(&hash)?,
+            None => &self.unhashed,
+        };
+
+        candidates.iter().copied().find(|&n| match &children[n] {
+            Some(child) => child.widget().local_key().map_or(false, |k| k == key),
+            None => false,
+        })
     }
 }
 
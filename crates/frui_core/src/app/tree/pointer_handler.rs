@@ -1,12 +1,19 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, rc::Rc};
 
-use druid_shell::kurbo::Affine;
+use druid_shell::kurbo::{Affine, Point};
 
-use crate::prelude::{context::HitTestCxOS, events::PointerExit, PointerEvent};
+use crate::{
+    app::window::Window,
+    prelude::{
+        context::{HitTestCxOS, HitTestResult},
+        events::PointerExit,
+        PointerEvent,
+    },
+};
 
-use super::NodeRef;
+use super::{NodeRef, WeakNodeRef};
 
-pub type HitTestEntries = Rc<RefCell<HashMap<NodeRef, Affine>>>;
+pub type HitTestEntries = Rc<RefCell<HitTestResult>>;
 
 #[derive(Default)]
 pub struct PointerHandler {
@@ -18,17 +25,47 @@ pub struct PointerHandler {
 
 impl PointerHandler {
     pub fn handle_pointer_event(&mut self, root: NodeRef, event: PointerEvent) {
+        let position = event.pos();
+
+        self.dispatch(root, event);
+
+        // Pointer is held at the position of the event during which it was
+        // locked.
+        PointerLock::anchor(position);
+    }
+
+    fn dispatch(&mut self, root: NodeRef, event: PointerEvent) {
+        if let Some((node, affine)) = self.pointer_capture() {
+            let event = match PointerLock::unlocked_position(&event) {
+                Some(position) => event.clone_at(position),
+                None => event,
+            };
+
+            self.handle_event(&node, event.transform(&affine));
+
+            if let PointerEvent::PointerUp(_) = event {
+                self.pointer_down_results.borrow_mut().take();
+                set_pointer_capture(None);
+            }
+
+            return;
+        }
+
         match event {
             PointerEvent::PointerDown(_) => {
                 self.hit_test(root, &self.pointer_down_results, &event);
 
-                for (node, affine) in self.pointer_down_results.borrow_mut().iter() {
+                let results = self.pointer_down_results.borrow().clone();
+
+                for (node, affine) in results.entries() {
                     self.handle_event(&node, event.transform(affine));
                 }
             }
             PointerEvent::PointerUp(_) => {
                 // Call all nodes that were hit during PointerDown.
-                for (node, affine) in self.pointer_down_results.borrow_mut().drain() {
+                let results = self.pointer_down_results.borrow_mut().take();
+
+                for (node, affine) in results {
                     self.handle_event(&node, event.transform(&affine));
                 }
             }
@@ -37,7 +74,7 @@ impl PointerHandler {
 
                 self.hit_test(root, &results, &event);
 
-                for (node, affine) in results.borrow_mut().iter() {
+                for (node, affine) in results.borrow().entries() {
                     self.handle_event(&node, event.transform(affine));
                 }
             }
@@ -47,7 +84,7 @@ impl PointerHandler {
                 self.hit_test(root, &new_results, &event);
 
                 // Dispatch to all widgets that got hit.
-                for (node, affine) in new_results.borrow_mut().iter() {
+                for (node, affine) in new_results.borrow().entries() {
                     self.handle_event(&node, event.transform(affine));
                 }
 
@@ -55,9 +92,9 @@ impl PointerHandler {
                 // Used to correctly dispatch PointerExit event.
                 for (node, affine) in self
                     .pointer_hover_results_last
-                    .borrow_mut()
-                    .iter()
-                    .filter(|(last, _)| !new_results.borrow_mut().contains_key(last))
+                    .borrow()
+                    .entries()
+                    .filter(|(last, _)| !new_results.borrow().contains(last))
                 {
                     let event = event.transform(affine).raw();
                     let event = PointerEvent::PointerExit(PointerExit(event));
@@ -70,6 +107,38 @@ impl PointerHandler {
         }
     }
 
+    /// Node which captured the pointer and the transformation of pointer
+    /// events dispatched to it.
+    fn pointer_capture(&self) -> Option<(NodeRef, Affine)> {
+        let node = POINTER_CAPTURE.with(|c| c.borrow().as_ref().map(|c| c.node.upgrade()))?;
+
+        let node = match node {
+            Some(node) => node,
+            None => {
+                set_pointer_capture(None);
+                return None;
+            }
+        };
+
+        POINTER_CAPTURE.with(|capture| {
+            let mut capture = capture.borrow_mut();
+            let affine = &mut capture.as_mut()?.affine;
+
+            // Capture is requested while handling an event, when the
+            // transformation of that node isn't known yet.
+            let affine = affine.get_or_insert_with(|| {
+                let down = self.pointer_down_results.borrow();
+                let hover = self.pointer_hover_results_last.borrow();
+
+                down.transform(&node)
+                    .or_else(|| hover.transform(&node))
+                    .unwrap_or_default()
+            });
+
+            Some((node, *affine))
+        })
+    }
+
     fn hit_test(&self, node: NodeRef, new_hit_entries: &HitTestEntries, event: &PointerEvent) {
         let cx = HitTestCxOS::new(&node, new_hit_entries.clone(), Affine::default());
         node.widget().hit_test_os(cx.clone(), event.pos());
@@ -80,3 +149,88 @@ impl PointerHandler {
         node.widget().handle_event_os(cx.clone(), &event);
     }
 }
+
+/// Node receiving all pointer events until it releases the capture or the
+/// pointer is released.
+pub(crate) struct PointerCapture {
+    pub node: WeakNodeRef,
+    /// Transformation of events dispatched to the node from window
+    /// coordinates.
+    pub affine: Option<Affine>,
+    pub lock: Option<PointerLock>,
+}
+
+/// Pointer held in place while its movement is reported as if it moved
+/// freely, without being stopped by edges of the window or the screen.
+#[derive(Default)]
+pub(crate) struct PointerLock {
+    /// Position the pointer is held at, in window coordinates. Known once the
+    /// event during which the pointer got locked is dispatched.
+    anchor: Option<Point>,
+    /// Last position of the pointer in window coordinates.
+    last: Point,
+    /// Position reported to the node which locked the pointer.
+    unlocked: Point,
+}
+
+impl PointerLock {
+    fn anchor(position: Point) {
+        POINTER_CAPTURE.with(|c| {
+            if let Some(lock) = c.borrow_mut().as_mut().and_then(|c| c.lock.as_mut()) {
+                if lock.anchor.is_none() {
+                    *lock = PointerLock {
+                        anchor: Some(position),
+                        last: position,
+                        unlocked: position,
+                    };
+                }
+            }
+        });
+    }
+
+    /// Position of the pointer as if it wasn't held in place, if it is
+    /// locked. Moves the pointer back to the anchor.
+    fn unlocked_position(event: &PointerEvent) -> Option<Point> {
+        let (anchor, unlocked) = POINTER_CAPTURE.with(|c| {
+            let mut capture = c.borrow_mut();
+            let lock = capture.as_mut()?.lock.as_mut()?;
+            let anchor = lock.anchor?;
+
+            lock.unlocked += event.pos() - lock.last;
+            lock.last = event.pos();
+
+            Some((anchor, lock.unlocked))
+        })?;
+
+        // Where the pointer can't be moved back, its movement is still
+        // reported, but it isn't held in place.
+        if event.pos() != anchor && Window::warp_pointer(anchor) {
+            POINTER_CAPTURE.with(|c| {
+                if let Some(lock) = c.borrow_mut().as_mut().and_then(|c| c.lock.as_mut()) {
+                    lock.last = anchor;
+                }
+            });
+        }
+
+        Some(unlocked)
+    }
+}
+
+/// Replaces the node capturing the pointer, showing the cursor again if the
+/// previous one locked the pointer.
+pub(crate) fn set_pointer_capture(capture: Option<PointerCapture>) {
+    let locked = capture.as_ref().map_or(false, |c| c.lock.is_some());
+    let previous = POINTER_CAPTURE.with(|c| std::mem::replace(&mut *c.borrow_mut(), capture));
+
+    if previous.map_or(false, |c| c.lock.is_some()) && !locked {
+        Window::set_cursor_visible(true);
+    }
+
+    if locked {
+        Window::set_cursor_visible(false);
+    }
+}
+
+thread_local! {
+    pub(crate) static POINTER_CAPTURE: RefCell<Option<PointerCapture>> = RefCell::new(None);
+}
@@ -0,0 +1,591 @@
+//! Configuration and control of the application window.
+//!
+//! Together with [`WindowOptions::decorations`] this allows applications to
+//! draw their own title bar, see e.g. `WindowDragArea` and `CaptionButton`
+//! widgets.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use druid_shell::{
+    kurbo::Point,
+    piet::{ImageBuf, ImageFormat},
+    CursorDesc,
+};
+
+pub use druid_shell::{Cursor, WindowState};
+
+use crate::render::{Color, Rect, Size};
+
+use super::{
+    listeners::lifecycle,
+    restoration::{RestorableValue, Restoration},
+    runner::window_handler::WINDOW_HANDLE,
+    runner::WindowHandle,
+};
+
+#[cfg(not(feature = "miri"))]
+use super::runner::native::hints::WindowHints;
+
+/// Options used to create the application window. See [`run_app_with`].
+///
+/// [`run_app_with`]: crate::app::runner::native::run_app_with
+#[derive(Debug, Clone)]
+pub struct WindowOptions {
+    pub title: String,
+    pub size: Option<Size>,
+    /// Position of the window in screen coordinates.
+    pub position: Option<Point>,
+    pub min_size: Option<Size>,
+    /// Whether the window has platform title bar and borders.
+    pub decorations: bool,
+    pub resizable: bool,
+    /// Whether the window stays above other windows.
+    pub always_on_top: bool,
+    /// Whether the window is left out of the taskbar.
+    pub skip_taskbar: bool,
+    /// Icon of the window shown in the title bar and the taskbar.
+    pub icon: Option<WindowIcon>,
+    /// Whether the window supports per-pixel transparency. Parts of the
+    /// window which aren't painted over (including the `background`, if it is
+    /// transparent) show what is behind the window.
+    pub transparent: bool,
+    /// Whether what is visible through transparent parts of the window is
+    /// blurred. Currently supported on Windows only.
+    pub blur_behind: bool,
+    /// Color the window is cleared with before painting widgets.
+    pub background: Color,
+    /// Restoration id of the window geometry. See
+    /// [`restore_geometry`](Self::restore_geometry).
+    pub geometry_id: Option<String>,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        WindowOptions {
+            title: "Frui App".into(),
+            size: None,
+            position: None,
+            min_size: None,
+            decorations: true,
+            resizable: true,
+            always_on_top: false,
+            skip_taskbar: false,
+            icon: None,
+            transparent: false,
+            blur_behind: false,
+            background: Color::from_hex_str("#202324").unwrap(),
+            geometry_id: None,
+        }
+    }
+}
+
+impl WindowOptions {
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn size(mut self, width: f64, height: f64) -> Self {
+        self.size = Some(Size::new(width, height));
+        self
+    }
+
+    pub fn position(mut self, x: f64, y: f64) -> Self {
+        self.position = Some(Point::new(x, y));
+        self
+    }
+
+    pub fn min_size(mut self, width: f64, height: f64) -> Self {
+        self.min_size = Some(Size::new(width, height));
+        self
+    }
+
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    pub fn skip_taskbar(mut self, skip_taskbar: bool) -> Self {
+        self.skip_taskbar = skip_taskbar;
+        self
+    }
+
+    pub fn icon(mut self, icon: WindowIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Makes the window transparent and clears it with a fully transparent
+    /// background. Usually combined with disabled `decorations`.
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+
+        if transparent {
+            self.background = Color::TRANSPARENT;
+        }
+
+        self
+    }
+
+    /// Blurs what is visible through transparent parts of the window. Only
+    /// has an effect together with [`transparent`](Self::transparent).
+    pub fn blur_behind(mut self, blur_behind: bool) -> Self {
+        self.blur_behind = blur_behind;
+        self
+    }
+
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Restores position, size and maximization of the window from the
+    /// previous session and saves them again on exit. Requires
+    /// [`Restoration`] to be enabled before the window is created.
+    pub fn restore_geometry(mut self, id: impl Into<String>) -> Self {
+        self.geometry_id = Some(id.into());
+        self
+    }
+
+    /// Geometry restored from the previous session, which overrides `size`
+    /// and `position` of these options.
+    pub fn restored_geometry(&self) -> Option<WindowGeometry> {
+        self.geometry_id
+            .as_ref()
+            .and_then(|id| Restoration::restored::<WindowGeometry>(id))
+    }
+}
+
+/// Controls the application window. Calls made before the window is created
+/// are ignored.
+pub struct Window;
+
+impl Window {
+    pub fn minimize() {
+        with_handle(|h| h.set_window_state(WindowState::Minimized));
+    }
+
+    pub fn maximize() {
+        with_handle(|h| h.set_window_state(WindowState::Maximized));
+    }
+
+    pub fn restore() {
+        with_handle(|h| h.set_window_state(WindowState::Restored));
+    }
+
+    pub fn toggle_maximized() {
+        match Self::state() {
+            Some(WindowState::Maximized) => Self::restore(),
+            _ => Self::maximize(),
+        }
+    }
+
+    pub fn state() -> Option<WindowState> {
+        with_handle(|h| h.get_window_state())
+    }
+
+    /// Requests the window to be closed, same as if the platform close button
+    /// was clicked. See [`LifecycleEvent::ExitRequested`].
+    ///
+    /// [`LifecycleEvent::ExitRequested`]: super::listeners::lifecycle::LifecycleEvent::ExitRequested
+    pub fn request_close() {
+        lifecycle::request_exit();
+    }
+
+    /// Paints the window again in the next frame, without rebuilding any
+    /// widgets. Used by widgets painting values which changed outside of
+    /// their state, e.g. the value of a running animation.
+    pub fn request_paint() {
+        with_handle(|h| h.invalidate());
+    }
+
+    /// Starts moving the window with the pointer. Should be called when
+    /// handling pointer down event.
+    pub fn begin_drag() {
+        with_handle(|h| h.handle_titlebar(true));
+    }
+
+    /// Position of the window in screen coordinates.
+    pub fn position() -> Option<Point> {
+        with_handle(|h| h.get_position())
+    }
+
+    pub fn set_position(position: Point) {
+        with_handle(|h| h.set_position(position));
+    }
+
+    pub fn size() -> Option<Size> {
+        with_handle(|h| h.get_size().into())
+    }
+
+    pub fn set_size(size: Size) {
+        with_handle(|h| h.set_size(size.into()));
+    }
+
+    /// Sets cursor shown above the window. Cursor is reset to the default one
+    /// every time the pointer moves, so this should be called on every pointer
+    /// move event.
+    ///
+    /// While the cursor is hidden (see [`set_cursor_visible`]), it stays
+    /// hidden.
+    ///
+    /// [`set_cursor_visible`]: Self::set_cursor_visible
+    pub fn set_cursor(cursor: &Cursor) {
+        match CURSOR_HIDDEN.with(|h| h.get()) {
+            true => {
+                let hidden = HIDDEN_CURSOR.with(|c| c.cursor());
+                with_handle(|h| h.set_cursor(hidden.as_ref().unwrap_or(cursor)));
+            }
+            false => {
+                with_handle(|h| h.set_cursor(cursor));
+            }
+        }
+    }
+
+    /// Hides the cursor while it is above the window, until it is shown
+    /// again. See also [`HitTestCxOS::lock_pointer`].
+    ///
+    /// [`HitTestCxOS::lock_pointer`]: crate::api::pointer_events::context::HitTestCxOS::lock_pointer
+    pub fn set_cursor_visible(visible: bool) {
+        CURSOR_HIDDEN.with(|h| h.set(!visible));
+        Self::set_cursor(&Cursor::Arrow);
+    }
+
+    pub fn is_cursor_visible() -> bool {
+        !CURSOR_HIDDEN.with(|h| h.get())
+    }
+
+    /// Moves the pointer to `position` in window coordinates. Returns `false`
+    /// if the window wasn't created yet or the platform doesn't support it.
+    pub fn warp_pointer(position: Point) -> bool {
+        with_handle(|h| h.warp_pointer(position)).unwrap_or(false)
+    }
+
+    /// Keeps the window above other windows.
+    pub fn set_always_on_top(always_on_top: bool) {
+        with_handle(|h| h.set_always_on_top(always_on_top));
+    }
+
+    /// Leaves the window out of the taskbar.
+    pub fn set_skip_taskbar(skip: bool) {
+        with_handle(|h| h.set_skip_taskbar(skip));
+    }
+
+    /// Blurs what is visible through transparent parts of the window.
+    pub fn set_blur_behind(blur: bool) {
+        with_handle(|h| h.set_blur_behind(blur));
+    }
+
+    pub fn set_icon(icon: &WindowIcon) {
+        with_handle(|h| h.set_icon(icon.image()));
+    }
+
+    /// Shows progress of a long running task (e.g. a download) on the taskbar
+    /// button of the window.
+    pub fn set_taskbar_progress(progress: TaskbarProgress) {
+        with_handle(|h| h.set_taskbar_progress(progress));
+    }
+
+    pub fn set_title(title: &str) {
+        with_handle(|h| h.set_title(title));
+    }
+
+    /// Scale factor of the monitor the window is currently on.
+    pub fn scale() -> Option<f64> {
+        with_handle(|h| h.get_scale().ok().map(|s| s.x())).flatten()
+    }
+
+    pub fn geometry() -> Option<WindowGeometry> {
+        Some(WindowGeometry {
+            position: Self::position()?,
+            size: Self::size()?,
+            maximized: Self::state()? == WindowState::Maximized,
+        })
+    }
+
+    pub fn set_geometry(geometry: &WindowGeometry) {
+        Self::set_position(geometry.position);
+        Self::set_size(geometry.size);
+
+        if geometry.maximized {
+            Self::maximize();
+        }
+    }
+
+    /// Monitor containing the center of the window.
+    pub fn monitor() -> Option<Monitor> {
+        let position = Self::position()?;
+        let size = Self::size()?;
+        let center = Point::new(position.x + size.width / 2., position.y + size.height / 2.);
+
+        Monitor::all().into_iter().find(|m| m.contains(center))
+    }
+
+    /// Centers the window in the work area of `monitor`.
+    pub fn center_on(monitor: &Monitor) {
+        if let Some(size) = Self::size() {
+            let area = monitor.work_area;
+            let center = area.center();
+
+            Self::set_position(Point::new(
+                center.x - size.width / 2.,
+                center.y - size.height / 2.,
+            ));
+        }
+    }
+}
+
+/// Progress shown on the taskbar button of the window. Values are fractions
+/// of the task completed, from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskbarProgress {
+    None,
+    /// Task is running, but its progress is unknown.
+    Indeterminate,
+    Normal(f64),
+    Paused(f64),
+    Error(f64),
+}
+
+/// Icon of the window. Cloning is cheap and clones share the pixels.
+#[derive(Clone)]
+pub struct WindowIcon {
+    image: ImageBuf,
+}
+
+impl WindowIcon {
+    /// Creates icon from `pixels` with 4 bytes (red, green, blue, alpha) per
+    /// pixel, row by row.
+    ///
+    /// Panics if length of `pixels` doesn't match the dimensions.
+    #[track_caller]
+    pub fn from_rgba(pixels: Vec<u8>, width: usize, height: usize) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height * 4,
+            "pixels don't match the image dimensions"
+        );
+
+        WindowIcon {
+            image: ImageBuf::from_raw(pixels, ImageFormat::RgbaSeparate, width, height),
+        }
+    }
+
+    /// Creates icon from PNG encoded `data`, usually an asset embedded with
+    /// `include_bytes!`.
+    pub fn from_png(data: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory_with_format(data, image::ImageFormat::Png)?;
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels = image.into_rgba8().into_raw();
+
+        Ok(Self::from_rgba(pixels, width, height))
+    }
+
+    pub(crate) fn image(&self) -> &ImageBuf {
+        &self.image
+    }
+}
+
+impl std::fmt::Debug for WindowIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowIcon")
+            .field("width", &self.image.width())
+            .field("height", &self.image.height())
+            .finish()
+    }
+}
+
+/// Position, size and maximization of the window, which can be persisted
+/// using [`Restoration`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub position: Point,
+    pub size: Size,
+    pub maximized: bool,
+}
+
+impl RestorableValue for WindowGeometry {
+    fn encode(&self) -> String {
+        (
+            (self.position.x, self.position.y),
+            ((self.size.width, self.size.height), self.maximized),
+        )
+            .encode()
+    }
+
+    fn decode(data: &str) -> Option<Self> {
+        let ((x, y), ((width, height), maximized)) = RestorableValue::decode(data)?;
+
+        Some(WindowGeometry {
+            position: Point::new(x, y),
+            size: Size::new(width, height),
+            maximized,
+        })
+    }
+}
+
+/// Display connected to the computer. All coordinates are in screen
+/// coordinates, same as [`Window::position`].
+///
+/// Scale factor of a monitor isn't exposed by the windowing backend; use
+/// [`Window::scale`] for the monitor the window is on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub bounds: Rect,
+    /// Bounds excluding taskbars, docks and similar.
+    pub work_area: Rect,
+    pub primary: bool,
+}
+
+impl Monitor {
+    pub fn all() -> Vec<Monitor> {
+        #[cfg(not(feature = "miri"))]
+        return druid_shell::Screen::get_monitors()
+            .into_iter()
+            .map(|m| Monitor {
+                bounds: m.virtual_rect().into(),
+                work_area: m.virtual_work_rect().into(),
+                primary: m.is_primary(),
+            })
+            .collect();
+
+        #[cfg(feature = "miri")]
+        return vec![Monitor {
+            bounds: Rect::from_ltwh(0., 0., 1920., 1080.),
+            work_area: Rect::from_ltwh(0., 0., 1920., 1040.),
+            primary: true,
+        }];
+    }
+
+    pub fn primary() -> Option<Monitor> {
+        Self::all().into_iter().find(|m| m.primary)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let b = &self.bounds;
+        point.x >= b.left && point.x < b.right && point.y >= b.top && point.y < b.bottom
+    }
+}
+
+thread_local! {
+    static CURSOR_HIDDEN: Cell<bool> = Cell::new(false);
+
+    /// Fully transparent cursor shown while the cursor is hidden.
+    static HIDDEN_CURSOR: CursorImage =
+        CursorImage::from_rgba(vec![0; 16 * 16 * 4], 16, 16, Point::ORIGIN);
+}
+
+fn with_handle<R>(f: impl FnOnce(&WindowHandle) -> R) -> Option<R> {
+    WINDOW_HANDLE.with(|handle| handle.borrow().as_ref().map(f))
+}
+
+/// Cursor displayed above a widget, see [`PointerRegion::cursor`].
+///
+/// [`PointerRegion::cursor`]: crate::api::pointer_events::PointerRegion::cursor
+#[derive(Debug, Clone)]
+pub enum MouseCursor {
+    System(Cursor),
+    Image(CursorImage),
+}
+
+impl MouseCursor {
+    /// Platform cursor, or `None` if it couldn't be created.
+    pub fn platform_cursor(&self) -> Option<Cursor> {
+        match self {
+            MouseCursor::System(cursor) => Some(cursor.clone()),
+            MouseCursor::Image(image) => image.cursor(),
+        }
+    }
+}
+
+impl From<Cursor> for MouseCursor {
+    fn from(cursor: Cursor) -> Self {
+        MouseCursor::System(cursor)
+    }
+}
+
+impl From<CursorImage> for MouseCursor {
+    fn from(image: CursorImage) -> Self {
+        MouseCursor::Image(image)
+    }
+}
+
+/// Custom cursor created from an RGBA image, e.g. a brush preview. Cloning is
+/// cheap and clones share the platform cursor.
+#[derive(Clone)]
+pub struct CursorImage {
+    inner: Rc<CursorImageInner>,
+}
+
+struct CursorImageInner {
+    image: ImageBuf,
+    hotspot: Point,
+    /// Platform cursor, created on first use.
+    cursor: RefCell<Option<Cursor>>,
+}
+
+impl CursorImage {
+    /// Creates cursor from `pixels` with 4 bytes (red, green, blue, alpha) per
+    /// pixel, row by row. `hotspot` is the point of the image (in pixels)
+    /// which points at the pointer position.
+    ///
+    /// Panics if length of `pixels` doesn't match the dimensions.
+    #[track_caller]
+    pub fn from_rgba(pixels: Vec<u8>, width: usize, height: usize, hotspot: Point) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height * 4,
+            "pixels don't match the image dimensions"
+        );
+
+        let image = ImageBuf::from_raw(pixels, ImageFormat::RgbaSeparate, width, height);
+
+        CursorImage {
+            inner: Rc::new(CursorImageInner {
+                image,
+                hotspot,
+                cursor: RefCell::new(None),
+            }),
+        }
+    }
+
+    pub fn hotspot(&self) -> Point {
+        self.inner.hotspot
+    }
+
+    /// Platform cursor, or `None` if the window wasn't created yet or the
+    /// platform doesn't support custom cursors.
+    pub fn cursor(&self) -> Option<Cursor> {
+        let mut cursor = self.inner.cursor.borrow_mut();
+
+        if cursor.is_none() {
+            let desc = CursorDesc::new(self.inner.image.clone(), self.inner.hotspot);
+            *cursor = with_handle(|h| h.make_cursor(&desc)).flatten();
+        }
+
+        cursor.clone()
+    }
+}
+
+impl std::fmt::Debug for CursorImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorImage")
+            .field("width", &self.inner.image.width())
+            .field("height", &self.inner.image.height())
+            .field("hotspot", &self.inner.hotspot)
+            .finish()
+    }
+}
@@ -12,14 +12,26 @@ pub mod prelude {
     pub use super::{
         api::{
             contexts::build_cx::{
-                BuildCx, InheritedState, InheritedStateRef, InheritedStateRefMut, WidgetState,
+                BuildCx, InheritedState, InheritedStateRef, InheritedStateRefMut,
+                InheritedValueRef, StateUpdater, WidgetState,
+            },
+            implementers::{
+                inherited::{InheritedValue, InheritedWidget},
+                view::ViewWidget,
             },
-            implementers::{inherited::InheritedWidget, view::ViewWidget},
             impls::BoxedWidget,
             pointer_events::*,
+            shared_str::SharedStr,
             Widget,
         },
-        app::runner::native::run_app,
+        app::{
+            debounce::{Debouncer, Throttler},
+            focus::{FocusManager, FocusNode},
+            runner::native::{run_app, run_app_with},
+            ticker::Ticker,
+            timer::Timer,
+            window::WindowOptions,
+        },
     };
 
     pub use crate::render::{Offset, Size};
@@ -30,11 +42,62 @@ pub mod prelude {
     pub use frui_macros::{Builder, InheritedWidget, RenderWidget, ViewWidget};
 
     // Core widgets exports.
-    pub use super::api::local_key::LocalKey;
+    pub use super::api::local_key::{LocalKey, ObjectKey, UniqueKey, ValueKey};
 }
 
+/// Everything needed to write render widgets, which lay out and paint their
+/// children themselves. Crates shipping their own render widgets only need
+/// this module and the [`prelude`].
+///
+/// A render widget implements [`RenderWidget`] (derived with
+/// `#[derive(RenderWidget)]`), and can opt into:
+///
+/// - [`Baseline`] to report the baseline of its text,
+/// - [`HitTest`] to receive pointer events,
+/// - [`Intrinsics`] to report its intrinsic dimensions,
+/// - [`ParentData`] to provide parent data its parent stores on it,
+/// - [`RenderState`] to keep state between layout and paint.
+///
+/// Widgets which don't implement these hit test and report intrinsic
+/// dimensions and baselines of their children, so single-child widgets only
+/// need to implement them to change that behavior.
+///
+/// Children are laid out with [`LayoutCxOS::layout`], after which
+/// multi-child widgets usually position them with
+/// [`LayoutCxOS::set_offset`] and paint them at those offsets with
+/// [`PaintCxOS::paint_children`]:
+///
+/// ```ignore
+/// #[derive(RenderWidget)]
+/// struct VerticalList<W: Widget> {
+///     children: Vec<W>,
+/// }
+///
+/// impl<W: Widget> RenderWidget for VerticalList<W> {
+///     fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+///         self.children.iter().collect()
+///     }
+///
+///     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+///         let mut height = 0.;
+///
+///         for child in cx.children() {
+///             let size = child.layout(constraints.loosen());
+///             child.set_offset(Offset::new(0., height));
+///             height += size.height;
+///         }
+///
+///         constraints.constrain(Size::new(constraints.max_width, height))
+///     }
+///
+///     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+///         cx.paint_children(canvas, offset)
+///     }
+/// }
+/// ```
 pub mod render {
     pub use crate::api::implementers::render::RenderWidget;
+    pub use crate::api::pointer_events::{HitTest, HitTestCx, PointerEvent};
 
     pub use crate::api::contexts::render::*;
     pub use crate::app::runner::Canvas;
@@ -16,6 +16,10 @@ pub fn impl_tuple_slice(tokens: TokenStream) -> TokenStream {
                 fn get(&self) -> Vec<&dyn Widget> {
                     vec![ #( &self.#bounds_3 ),* ]
                 }
+
+                fn len(&self) -> usize {
+                    #args_count
+                }
             }
         }
     });
@@ -0,0 +1,156 @@
+//! Helpers for layouts adapting to the size of the window.
+//!
+//! Widgets are built before they are laid out, so layouts are selected based
+//! on the window size reported by [`MediaQuery`], not on the constraints
+//! passed to the widget.
+
+use frui::{app::services::Services, prelude::*};
+
+use crate::*;
+
+/// Class of the window width, from the narrowest to the widest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WidthClass {
+    /// Phones in portrait.
+    Compact,
+    /// Tablets in portrait, phones in landscape.
+    Medium,
+    /// Tablets in landscape, small desktop windows.
+    Expanded,
+    /// Large desktop windows.
+    Large,
+}
+
+/// Minimal window widths of each [`WidthClass`].
+///
+/// Application-wide breakpoints can be registered with
+/// `Services::register(Breakpoints { .. })`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoints {
+    pub medium: f64,
+    pub expanded: f64,
+    pub large: f64,
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Breakpoints {
+            medium: 600.,
+            expanded: 840.,
+            large: 1200.,
+        }
+    }
+}
+
+impl Breakpoints {
+    /// Application-wide breakpoints, or the default ones if none were
+    /// registered.
+    pub fn current() -> Self {
+        match Services::get::<Breakpoints>() {
+            Some(breakpoints) => (*breakpoints).clone(),
+            None => Breakpoints::default(),
+        }
+    }
+
+    pub fn class(&self, width: f64) -> WidthClass {
+        if width >= self.large {
+            WidthClass::Large
+        } else if width >= self.expanded {
+            WidthClass::Expanded
+        } else if width >= self.medium {
+            WidthClass::Medium
+        } else {
+            WidthClass::Compact
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+impl MediaQueryData {
+    /// Window is in portrait if it is taller than it is wide.
+    pub fn orientation(&self) -> Orientation {
+        match self.size.height > self.size.width {
+            true => Orientation::Portrait,
+            false => Orientation::Landscape,
+        }
+    }
+}
+
+/// Builds its child with the [`WidthClass`] of the window.
+///
+/// ```ignore
+/// AdaptiveBuilder::new(|class| match class {
+///     WidthClass::Compact => BottomNavigation().boxed(),
+///     _ => SideNavigation().boxed(),
+/// })
+/// ```
+#[derive(ViewWidget)]
+pub struct AdaptiveBuilder<W: Widget, F: Fn(WidthClass) -> W> {
+    pub builder: F,
+    /// Uses [`Breakpoints::current`] if `None`.
+    pub breakpoints: Option<Breakpoints>,
+}
+
+impl<W: Widget, F: Fn(WidthClass) -> W> AdaptiveBuilder<W, F> {
+    pub fn new(builder: F) -> Self {
+        AdaptiveBuilder {
+            builder,
+            breakpoints: None,
+        }
+    }
+
+    pub fn breakpoints(mut self, breakpoints: Breakpoints) -> Self {
+        self.breakpoints = Some(breakpoints);
+        self
+    }
+}
+
+impl<W: Widget, F: Fn(WidthClass) -> W> ViewWidget for AdaptiveBuilder<W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let breakpoints = self
+            .breakpoints
+            .clone()
+            .unwrap_or_else(Breakpoints::current);
+
+        (self.builder)(breakpoints.class(MediaQuery::of(cx).size.width))
+    }
+}
+
+/// Builds its child with the [`Orientation`] of the window.
+#[derive(ViewWidget)]
+pub struct OrientationBuilder<W: Widget, F: Fn(Orientation) -> W> {
+    pub builder: F,
+}
+
+impl<W: Widget, F: Fn(Orientation) -> W> OrientationBuilder<W, F> {
+    pub fn new(builder: F) -> Self {
+        OrientationBuilder { builder }
+    }
+}
+
+impl<W: Widget, F: Fn(Orientation) -> W> ViewWidget for OrientationBuilder<W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        (self.builder)(MediaQuery::of(cx).orientation())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn width_class() {
+        let breakpoints = Breakpoints::default();
+
+        assert_eq!(breakpoints.class(0.), WidthClass::Compact);
+        assert_eq!(breakpoints.class(599.), WidthClass::Compact);
+        assert_eq!(breakpoints.class(600.), WidthClass::Medium);
+        assert_eq!(breakpoints.class(900.), WidthClass::Expanded);
+        assert_eq!(breakpoints.class(1920.), WidthClass::Large);
+    }
+}
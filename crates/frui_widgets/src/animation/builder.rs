@@ -0,0 +1,45 @@
+use frui::prelude::*;
+
+use crate::*;
+
+/// Builds the widget returned by `builder` from the current value of
+/// `animation`, and rebuilds it on every frame of the animation.
+///
+/// ```ignore
+/// AnimatedBuilder::new(controller.clone(), |value| {
+///     SizedBox::square(child, Tween::new(48., 96.).transform(value))
+/// })
+/// ```
+#[derive(ViewWidget)]
+pub struct AnimatedBuilder<W: Widget, F: Fn(f64) -> W> {
+    pub animation: AnimationController,
+    pub builder: F,
+}
+
+impl<W: Widget, F: Fn(f64) -> W> AnimatedBuilder<W, F> {
+    pub fn new(animation: AnimationController, builder: F) -> Self {
+        AnimatedBuilder { animation, builder }
+    }
+}
+
+impl<W: Widget, F: Fn(f64) -> W> WidgetState for AnimatedBuilder<W, F> {
+    type State = ControllerListener<AnimationController>;
+
+    fn create_state(&self) -> Self::State {
+        ControllerListener::default()
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().unlisten();
+    }
+}
+
+impl<W: Widget, F: Fn(f64) -> W> ViewWidget for AnimatedBuilder<W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let updater = cx.state_updater();
+        cx.state()
+            .listen(&self.animation, move || updater.update(|_| {}));
+
+        (self.builder)(self.animation.value())
+    }
+}
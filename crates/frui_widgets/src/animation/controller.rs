@@ -0,0 +1,378 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+use frui::{
+    app::leaks::{LeakDetector, Tracked},
+    prelude::*,
+};
+
+use crate::{Curve, Listenable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationStatus {
+    /// Stopped at the beginning.
+    Dismissed,
+    /// Running towards the end.
+    Forward,
+    /// Running towards the beginning.
+    Reverse,
+    /// Stopped at the end.
+    Completed,
+}
+
+/// Drives an animation, advancing its progress from 0 to 1 over `duration`
+/// while running forward (and back while running in reverse).
+///
+/// Clones of a controller refer to the same animation. The animation stops
+/// once all of them are dropped, so a controller is usually kept in the
+/// state of the widget which it animates.
+///
+/// ```ignore
+/// let controller = AnimationController::new(Duration::from_millis(200))
+///     .curve(Curve::EaseOut);
+///
+/// controller.forward();
+/// ```
+#[derive(Clone)]
+pub struct AnimationController {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    duration: Cell<Duration>,
+    curve: Cell<Curve>,
+    /// Linear progress of the animation, from 0 to 1.
+    progress: Cell<f64>,
+    status: Cell<AnimationStatus>,
+    run: Cell<Option<Run>>,
+    ticker: Ticker,
+    listeners: RefCell<Vec<(AnimationListenerKey, Rc<dyn Fn()>, Tracked)>>,
+    next_key: Cell<usize>,
+    _tracked: Tracked,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    from: f64,
+    to: f64,
+    repeat: Option<Repeat>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Repeat {
+    /// Jump back to the beginning once the end is reached.
+    Restart,
+    /// Change direction once either end is reached.
+    Reverse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimationListenerKey(usize);
+
+impl AnimationController {
+    pub fn new(duration: Duration) -> Self {
+        let inner = Rc::new_cyclic(|inner: &Weak<Inner>| {
+            let inner = inner.clone();
+
+            Inner {
+                duration: Cell::new(duration),
+                curve: Cell::new(Curve::Linear),
+                progress: Cell::new(0.),
+                status: Cell::new(AnimationStatus::Dismissed),
+                run: Cell::new(None),
+                ticker: Ticker::new(move |elapsed| {
+                    if let Some(inner) = inner.upgrade() {
+                        inner.tick(elapsed);
+                    }
+                }),
+                listeners: RefCell::new(Vec::new()),
+                next_key: Cell::new(0),
+                _tracked: LeakDetector::track("AnimationController"),
+            }
+        });
+
+        AnimationController { inner }
+    }
+
+    /// Sets the curve applied to the progress of the animation, see
+    /// [`value`](Self::value).
+    pub fn curve(self, curve: Curve) -> Self {
+        self.inner.curve.set(curve);
+        self
+    }
+
+    pub fn set_curve(&self, curve: Curve) {
+        self.inner.curve.set(curve);
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.inner.duration.get()
+    }
+
+    /// Takes effect from the next run of the animation.
+    pub fn set_duration(&self, duration: Duration) {
+        self.inner.duration.set(duration);
+    }
+
+    /// Progress of the animation mapped by its curve.
+    pub fn value(&self) -> f64 {
+        self.inner.curve.get().transform(self.progress())
+    }
+
+    /// Linear progress of the animation, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        self.inner.progress.get()
+    }
+
+    /// Stops the animation and sets its progress.
+    pub fn set_progress(&self, progress: f64) {
+        self.stop();
+
+        let progress = progress.clamp(0., 1.);
+        self.inner.progress.set(progress);
+        self.inner.status.set(match progress {
+            p if p == 1. => AnimationStatus::Completed,
+            _ => AnimationStatus::Dismissed,
+        });
+
+        self.inner.notify();
+    }
+
+    pub fn status(&self) -> AnimationStatus {
+        self.inner.status.get()
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.inner.ticker.is_active()
+    }
+
+    /// Runs the animation from its current progress to the end.
+    pub fn forward(&self) {
+        self.run(1., None);
+    }
+
+    /// Runs the animation from its current progress to the beginning.
+    pub fn reverse(&self) {
+        self.run(0., None);
+    }
+
+    /// Runs the animation until it is stopped, either from the beginning to
+    /// the end over and over, or back and forth if `reverse` is set.
+    pub fn repeat(&self, reverse: bool) {
+        let repeat = match reverse {
+            true => Repeat::Reverse,
+            false => Repeat::Restart,
+        };
+
+        self.run(1., Some(repeat));
+    }
+
+    /// Stops the animation at its current progress.
+    pub fn stop(&self) {
+        self.inner.ticker.stop();
+        self.inner.run.set(None);
+    }
+
+    /// Stops the animation and jumps to its beginning.
+    pub fn reset(&self) {
+        self.set_progress(0.);
+    }
+
+    /// Calls `callback` on every frame of the animation and every time it
+    /// changes its status.
+    pub fn listen(&self, callback: impl Fn() + 'static) -> AnimationListenerKey {
+        let key = AnimationListenerKey(self.inner.next_key.get());
+        self.inner.next_key.set(key.0 + 1);
+
+        let mut listeners = self.inner.listeners.borrow_mut();
+        listeners.push((key, Rc::new(callback), LeakDetector::track("Listener")));
+
+        key
+    }
+
+    pub fn unlisten(&self, key: AnimationListenerKey) {
+        let mut listeners = self.inner.listeners.borrow_mut();
+        listeners.retain(|(k, ..)| *k != key);
+    }
+
+    /// Whether both controllers refer to the same animation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn run(&self, to: f64, repeat: Option<Repeat>) {
+        let from = self.progress();
+
+        if from == to && repeat.is_none() {
+            self.stop();
+            self.inner.finish(to);
+            return;
+        }
+
+        self.inner.run.set(Some(Run { from, to, repeat }));
+        self.inner.status.set(match to > from {
+            true => AnimationStatus::Forward,
+            false => AnimationStatus::Reverse,
+        });
+
+        self.inner.ticker.start();
+        self.inner.notify();
+    }
+}
+
+impl Inner {
+    fn tick(&self, elapsed: Duration) {
+        let run = match self.run.get() {
+            Some(run) => run,
+            None => return,
+        };
+
+        // Partial runs (e.g. reversing a half-finished animation) take
+        // proportionally less time.
+        let duration = self.duration.get().mul_f64((run.to - run.from).abs());
+        let t = match duration.is_zero() {
+            true => 1.,
+            false => (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.),
+        };
+
+        self.progress.set(run.from + (run.to - run.from) * t);
+
+        if t < 1. {
+            return self.notify();
+        }
+
+        match run.repeat {
+            Some(repeat) => {
+                let (from, to) = match repeat {
+                    Repeat::Restart => (0., 1.),
+                    // The first run may start in the middle of the animation.
+                    Repeat::Reverse => (run.to, 1. - run.to),
+                };
+
+                self.progress.set(from);
+                self.run.set(Some(Run { from, to, ..run }));
+                self.status.set(match to > from {
+                    true => AnimationStatus::Forward,
+                    false => AnimationStatus::Reverse,
+                });
+
+                self.ticker.start();
+                self.notify();
+            }
+            None => {
+                self.ticker.stop();
+                self.run.set(None);
+                self.finish(run.to);
+            }
+        }
+    }
+
+    fn finish(&self, progress: f64) {
+        self.progress.set(progress);
+        self.status.set(match progress {
+            p if p == 1. => AnimationStatus::Completed,
+            _ => AnimationStatus::Dismissed,
+        });
+
+        self.notify();
+    }
+
+    fn notify(&self) {
+        // Listeners may add or remove listeners.
+        let listeners: Vec<_> = self
+            .listeners
+            .borrow()
+            .iter()
+            .map(|l| l.1.clone())
+            .collect();
+
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+impl Listenable for AnimationController {
+    type Key = AnimationListenerKey;
+
+    fn listen(&self, callback: impl Fn() + 'static) -> Self::Key {
+        AnimationController::listen(self, callback)
+    }
+
+    fn unlisten(&self, key: Self::Key) {
+        AnimationController::unlisten(self, key)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        AnimationController::ptr_eq(self, other)
+    }
+}
+
+impl PartialEq for AnimationController {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl std::fmt::Debug for AnimationController {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnimationController")
+            .field("progress", &self.progress())
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn animation_controller() {
+        let controller = AnimationController::new(Duration::from_millis(100));
+
+        controller.forward();
+        assert_eq!(controller.status(), AnimationStatus::Forward);
+        assert!(controller.is_animating());
+
+        controller.inner.tick(Duration::from_millis(50));
+        assert_eq!(controller.progress(), 0.5);
+
+        // Reversing from the middle takes half of the duration.
+        controller.reverse();
+        assert_eq!(controller.status(), AnimationStatus::Reverse);
+        controller.inner.tick(Duration::from_millis(50));
+        assert_eq!(controller.progress(), 0.);
+        assert_eq!(controller.status(), AnimationStatus::Dismissed);
+        assert!(!controller.is_animating());
+
+        controller.repeat(true);
+        controller.inner.tick(Duration::from_millis(100));
+        assert_eq!(controller.progress(), 1.);
+        assert_eq!(controller.status(), AnimationStatus::Reverse);
+
+        controller.stop();
+        assert!(!controller.is_animating());
+    }
+
+    #[test]
+    fn repeat_reverses_between_bounds() {
+        let controller = AnimationController::new(Duration::from_millis(100));
+        controller.set_progress(0.5);
+
+        controller.repeat(true);
+        controller.inner.tick(Duration::from_millis(50));
+        assert_eq!(controller.progress(), 1.);
+        assert_eq!(controller.status(), AnimationStatus::Reverse);
+
+        controller.inner.tick(Duration::from_millis(100));
+        assert_eq!(controller.progress(), 0.);
+        assert_eq!(controller.status(), AnimationStatus::Forward);
+
+        controller.inner.tick(Duration::from_millis(100));
+        assert_eq!(controller.progress(), 1.);
+    }
+}
@@ -0,0 +1,92 @@
+/// Easing of an animation, mapping its linear progress (from 0 to 1) to the
+/// animated value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Cubic Bézier curve from (0, 0) to (1, 1) with control points
+    /// `(x1, y1)` and `(x2, y2)`, same as `cubic-bezier` in CSS.
+    Cubic(f64, f64, f64, f64),
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Linear
+    }
+}
+
+impl Curve {
+    pub fn transform(&self, t: f64) -> f64 {
+        let t = t.clamp(0., 1.);
+
+        match *self {
+            Curve::Linear => t,
+            Curve::EaseIn => cubic(0.42, 0., 1., 1., t),
+            Curve::EaseOut => cubic(0., 0., 0.58, 1., t),
+            Curve::EaseInOut => cubic(0.42, 0., 0.58, 1., t),
+            Curve::Cubic(x1, y1, x2, y2) => cubic(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+/// Value of the curve at `x`, found by bisecting the curve parameter.
+fn cubic(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    if x == 0. || x == 1. {
+        return x;
+    }
+
+    let (mut start, mut end) = (0., 1.);
+
+    for _ in 0..64 {
+        let mid = (start + end) / 2.;
+        let estimate = bezier(x1, x2, mid);
+
+        if (x - estimate).abs() < 1e-7 {
+            return bezier(y1, y2, mid);
+        }
+
+        match estimate < x {
+            true => start = mid,
+            false => end = mid,
+        }
+    }
+
+    bezier(y1, y2, (start + end) / 2.)
+}
+
+/// One coordinate of a cubic Bézier curve starting at 0 and ending at 1.
+fn bezier(a: f64, b: f64, m: f64) -> f64 {
+    3. * a * (1. - m) * (1. - m) * m + 3. * b * (1. - m) * m * m + m * m * m
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn curves() {
+        for curve in [
+            Curve::Linear,
+            Curve::EaseIn,
+            Curve::EaseOut,
+            Curve::EaseInOut,
+        ] {
+            assert_eq!(curve.transform(0.), 0.);
+            assert_eq!(curve.transform(1.), 1.);
+            assert_eq!(curve.transform(2.), 1.);
+
+            let mut last = 0.;
+            for n in 1..=100 {
+                let value = curve.transform(n as f64 / 100.);
+                assert!(value >= last);
+                last = value;
+            }
+        }
+
+        assert!((Curve::EaseInOut.transform(0.5) - 0.5).abs() < 1e-6);
+        assert!(Curve::EaseIn.transform(0.25) < 0.25);
+        assert!(Curve::EaseOut.transform(0.25) > 0.25);
+    }
+}
@@ -0,0 +1,295 @@
+use std::{cell::RefCell, time::Duration};
+
+use frui::prelude::*;
+
+use crate::*;
+
+/// Duration of implicit animations unless set otherwise.
+const DEFAULT_DURATION: Duration = Duration::from_millis(200);
+
+/// State of implicitly animated widgets. Animates a value towards the value
+/// the widget was last built with.
+#[doc(hidden)]
+pub struct ImplicitAnimation<T: Lerp + PartialEq> {
+    controller: AnimationController,
+    /// `None` until the widget is built for the first time.
+    tween: RefCell<Option<Tween<T>>>,
+    listener: ControllerListener<AnimationController>,
+}
+
+impl<T: Lerp + PartialEq> Default for ImplicitAnimation<T> {
+    fn default() -> Self {
+        ImplicitAnimation {
+            controller: AnimationController::new(DEFAULT_DURATION),
+            tween: RefCell::new(None),
+            listener: ControllerListener::default(),
+        }
+    }
+}
+
+impl<T: Lerp + PartialEq> ImplicitAnimation<T> {
+    /// Starts animating towards `target` if it differs from the previous
+    /// target, and returns the current value. The widget is rebuilt through
+    /// `updater` on every frame of the animation.
+    pub(crate) fn animate<S: 'static>(
+        &self,
+        target: &T,
+        duration: Duration,
+        curve: Curve,
+        updater: StateUpdater<S>,
+    ) -> T {
+        let mut tween = self.tween.borrow_mut();
+
+        match &mut *tween {
+            None => *tween = Some(Tween::new(target.clone(), target.clone())),
+            Some(tween) if tween.end != *target => {
+                // Target changed mid-animation continues from the current
+                // value instead of jumping back to the previous target.
+                tween.begin = tween.transform(self.controller.value());
+                tween.end = target.clone();
+
+                // Restarting the controller notifies its listeners, which
+                // mustn't rebuild the widget while it is being built.
+                self.listener.unlisten();
+
+                self.controller.set_duration(duration);
+                self.controller.set_curve(curve);
+                self.controller.set_progress(0.);
+                self.controller.forward();
+            }
+            Some(_) => {}
+        }
+
+        self.listener
+            .listen(&self.controller, move || updater.update(|_| {}));
+
+        tween.as_ref().unwrap().transform(self.controller.value())
+    }
+
+    /// Should be called once the widget is unmounted.
+    pub(crate) fn dispose(&self) {
+        self.listener.unlisten();
+        self.controller.stop();
+    }
+}
+
+/// Version of [`Align`] which animates its child to the new `alignment`
+/// whenever it changes.
+///
+/// ```ignore
+/// AnimatedAlign::builder()
+///     .alignment(if selected { Alignment::TOP_LEFT } else { Alignment::BOTTOM_RIGHT })
+///     .curve(Curve::EaseInOut)
+///     .child(Marker)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct AnimatedAlign<W: Widget> {
+    pub child: W,
+    pub alignment: Alignment,
+    pub duration: Duration,
+    pub curve: Curve,
+}
+
+impl AnimatedAlign<()> {
+    pub fn builder() -> Self {
+        AnimatedAlign {
+            child: (),
+            alignment: Alignment::default(),
+            duration: DEFAULT_DURATION,
+            curve: Curve::Linear,
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for AnimatedAlign<W> {
+    type State = ImplicitAnimation<Alignment>;
+
+    fn create_state(&self) -> Self::State {
+        ImplicitAnimation::default()
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().dispose();
+    }
+}
+
+impl<W: Widget> ViewWidget for AnimatedAlign<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let alignment = cx.state().animate(
+            &self.alignment,
+            self.duration,
+            self.curve,
+            cx.state_updater(),
+        );
+
+        Align::builder().child(&self.child).alignment(alignment)
+    }
+}
+
+/// Version of [`Opacity`] which fades its child to the new `opacity` whenever
+/// it changes.
+///
+/// ```ignore
+/// AnimatedOpacity::builder()
+///     .opacity(if visible { 1. } else { 0. })
+///     .duration(Duration::from_millis(300))
+///     .child(Banner)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct AnimatedOpacity<W: Widget> {
+    pub child: W,
+    pub opacity: f64,
+    pub duration: Duration,
+    pub curve: Curve,
+}
+
+impl AnimatedOpacity<()> {
+    pub fn builder() -> Self {
+        AnimatedOpacity {
+            child: (),
+            opacity: 1.,
+            duration: DEFAULT_DURATION,
+            curve: Curve::Linear,
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for AnimatedOpacity<W> {
+    type State = ImplicitAnimation<f64>;
+
+    fn create_state(&self) -> Self::State {
+        ImplicitAnimation::default()
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().dispose();
+    }
+}
+
+impl<W: Widget> ViewWidget for AnimatedOpacity<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let opacity =
+            cx.state()
+                .animate(&self.opacity, self.duration, self.curve, cx.state_updater());
+
+        Opacity::builder()
+            .child(&self.child)
+            .opacity(opacity.clamp(0., 1.))
+    }
+}
+
+/// Version of [`Container`] which animates its size and color to the new
+/// values whenever they change.
+///
+/// Unset sizes and colors can't be animated; changing from or to one jumps
+/// halfway through the animation.
+#[derive(ViewWidget, Builder)]
+pub struct AnimatedContainer<W: Widget> {
+    pub child: W,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub color: Option<Color>,
+    pub duration: Duration,
+    pub curve: Curve,
+}
+
+impl AnimatedContainer<()> {
+    pub fn builder() -> Self {
+        AnimatedContainer {
+            child: (),
+            width: None,
+            height: None,
+            color: None,
+            duration: DEFAULT_DURATION,
+            curve: Curve::Linear,
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerValues {
+    width: Option<f64>,
+    height: Option<f64>,
+    color: Option<Color>,
+}
+
+impl Lerp for ContainerValues {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        ContainerValues {
+            width: self.width.lerp(&other.width, t),
+            height: self.height.lerp(&other.height, t),
+            color: self.color.lerp(&other.color, t),
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for AnimatedContainer<W> {
+    type State = ImplicitAnimation<ContainerValues>;
+
+    fn create_state(&self) -> Self::State {
+        ImplicitAnimation::default()
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().dispose();
+    }
+}
+
+impl<W: Widget> ViewWidget for AnimatedContainer<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let target = ContainerValues {
+            width: self.width,
+            height: self.height,
+            color: self.color.clone(),
+        };
+
+        let values = cx
+            .state()
+            .animate(&target, self.duration, self.curve, cx.state_updater());
+
+        let mut container = Container::builder().child(&self.child);
+
+        // Curves overshooting the target could make sizes negative.
+        if let Some(width) = values.width {
+            container = container.width(width.max(0.));
+        }
+
+        if let Some(height) = values.height {
+            container = container.height(height.max(0.));
+        }
+
+        if let Some(color) = values.color {
+            container = container.color(color);
+        }
+
+        container
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn container_values_jump_halfway_from_unset_ones() {
+        let begin = ContainerValues {
+            width: Some(100.),
+            height: None,
+            color: None,
+        };
+        let end = ContainerValues {
+            width: Some(200.),
+            height: Some(50.),
+            color: None,
+        };
+
+        let early = begin.lerp(&end, 0.25);
+        assert_eq!(early.width, Some(125.));
+        assert_eq!(early.height, None);
+
+        let late = begin.lerp(&end, 0.75);
+        assert_eq!(late.width, Some(175.));
+        assert_eq!(late.height, Some(50.));
+    }
+}
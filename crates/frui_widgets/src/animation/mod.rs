@@ -0,0 +1,23 @@
+//! Animations driven by a [`Ticker`](frui::prelude::Ticker).
+//!
+//! An [`AnimationController`] advances its value from 0 to 1 (or back) over
+//! its duration, one step per frame. The value is mapped by a [`Curve`] and
+//! then to the animated property by a [`Tween`]. Widgets built by an
+//! [`AnimatedBuilder`] are rebuilt (and so repainted) on every frame of the
+//! animation.
+//!
+//! Implicitly animated widgets, such as [`AnimatedAlign`], manage their own
+//! controller and animate to new values of their properties whenever they
+//! are rebuilt with them.
+
+pub use builder::*;
+pub use controller::*;
+pub use curve::*;
+pub use implicit::*;
+pub use tween::*;
+
+pub mod builder;
+pub mod controller;
+pub mod curve;
+pub mod implicit;
+pub mod tween;
@@ -0,0 +1,83 @@
+use frui::prelude::*;
+
+use super::AnimationController;
+
+/// Linear interpolation between two values of a type.
+pub trait Lerp: Clone {
+    /// Value between `self` (at `t == 0`) and `other` (at `t == 1`). Values
+    /// of `t` outside of that range extrapolate.
+    fn lerp(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Offset {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Offset::new(self.x.lerp(&other.x, t), self.y.lerp(&other.y, t))
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Size::new(
+            self.width.lerp(&other.width, t),
+            self.height.lerp(&other.height, t),
+        )
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let (r1, g1, b1, a1) = self.as_rgba();
+        let (r2, g2, b2, a2) = other.as_rgba();
+
+        Color::rgba(
+            r1.lerp(&r2, t).clamp(0., 1.),
+            g1.lerp(&g2, t).clamp(0., 1.),
+            b1.lerp(&b2, t).clamp(0., 1.),
+            a1.lerp(&a2, t).clamp(0., 1.),
+        )
+    }
+}
+
+/// Values which are `None` can't be interpolated, so the result switches from
+/// `self` to `other` halfway through instead.
+impl<T: Lerp> Lerp for Option<T> {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.lerp(b, t)),
+            _ if t < 0.5 => self.clone(),
+            _ => other.clone(),
+        }
+    }
+}
+
+/// Maps the value of an animation to a value between `begin` and `end`.
+///
+/// ```ignore
+/// let width = Tween::new(100., 300.).evaluate(&controller);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T: Lerp> {
+    pub begin: T,
+    pub end: T,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(begin: T, end: T) -> Self {
+        Tween { begin, end }
+    }
+
+    pub fn transform(&self, t: f64) -> T {
+        self.begin.lerp(&self.end, t)
+    }
+
+    /// Value at the current (curved) value of `animation`.
+    pub fn evaluate(&self, animation: &AnimationController) -> T {
+        self.transform(animation.value())
+    }
+}
@@ -1,9 +1,7 @@
-use std::ops::Deref;
-
 use frui::prelude::*;
 use frui::render::*;
 
-use crate::{Alignment, BoxLayoutData, Directional, EdgeInsets, TextDirection};
+use crate::{Alignment, Directional, EdgeInsets, TextDirection};
 
 pub trait ChildParentDataProvider<T: RenderWidget> {
     fn ensure_parent_data<F, P>(&self, cx: &LayoutCx<T>, default: F)
@@ -18,12 +16,7 @@ impl<T: RenderWidget> ChildParentDataProvider<T> for T {
         F: Fn() -> P,
         P: 'static,
     {
-        for child in cx.children() {
-            if child.try_parent_data::<P>().is_none() {
-                let data = default();
-                child.set_parent_data(data);
-            }
-        }
+        cx.ensure_parent_data(default);
     }
 }
 
@@ -33,11 +26,11 @@ pub struct Directionality<T: Widget> {
     pub child: T,
 }
 
-impl<T: Widget> WidgetState for Directionality<T> {
-    type State = TextDirection;
+impl<T: Widget> InheritedValue for Directionality<T> {
+    type Value = TextDirection;
 
-    fn create_state(&self) -> Self::State {
-        self.direction
+    fn value(&self) -> &Self::Value {
+        &self.direction
     }
 }
 
@@ -49,8 +42,7 @@ impl<T: Widget> InheritedWidget for Directionality<T> {
 
 impl Directionality<()> {
     pub fn of<T>(cx: &LayoutCx<T>) -> Option<TextDirection> {
-        let state = cx.depend_on_inherited_widget::<Self>();
-        state.map(|s| *s.as_ref().deref())
+        cx.depend_on::<TextDirection>().map(|d| *d)
     }
 
     pub fn of_or_default<T>(cx: &LayoutCx<T>) -> TextDirection {
@@ -65,11 +57,16 @@ impl Directionality<()> {
     }
 }
 
+/// Positions its `child` within itself at the `alignment`.
+///
+/// By default, it expands to fill its parent. If `width_factor` or
+/// `height_factor` is set, or if it's unconstrained in a direction, it sizes
+/// itself to the size of its child multiplied by the factor (or 1) instead.
 #[derive(RenderWidget, Builder)]
 pub struct Align<T: Widget, A: Directional<Output = Alignment>> {
     pub child: T,
     pub alignment: A,
-    pub widgh_factor: Option<f64>,
+    pub width_factor: Option<f64>,
     pub height_factor: Option<f64>,
     pub text_direction: Option<TextDirection>,
 }
@@ -79,7 +76,7 @@ impl Align<(), Alignment> {
         Self {
             child: (),
             alignment: Alignment::default(),
-            widgh_factor: None,
+            width_factor: None,
             height_factor: None,
             text_direction: None,
         }
@@ -96,45 +93,56 @@ where
     }
 
     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
-        self.ensure_parent_data(cx, || BoxLayoutData::default());
         let text_direction = self
             .text_direction
             .unwrap_or_else(|| Directionality::of_or_default(cx));
         let alignment = self.alignment.resolve(&text_direction);
-        let shrink_wrap_width =
-            self.widgh_factor.is_some() || constraints.max_width == f64::INFINITY;
-        let shrink_wrap_height =
-            self.height_factor.is_some() || constraints.max_height == f64::INFINITY;
 
         let child = cx.child(0);
         let child_size = child.layout(constraints.loosen());
-        let size = constraints.constrain(Size::new(
-            if shrink_wrap_width {
-                child_size.width * self.widgh_factor.unwrap_or(1.0)
-            } else {
-                f64::INFINITY
-            },
-            if shrink_wrap_height {
-                child_size.height * self.height_factor.unwrap_or(1.0)
-            } else {
-                f64::INFINITY
-            },
-        ));
-        let mut child_parent_data = child.try_parent_data_mut::<BoxLayoutData>().unwrap();
-        child_parent_data.offset = alignment.along(size - child_size);
+        let size = aligned_size(
+            constraints,
+            child_size,
+            self.width_factor,
+            self.height_factor,
+        );
+        child.set_offset(alignment.along(size - child_size));
         size
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
-        let child_offset = cx
-            .child(0)
-            .try_parent_data::<BoxLayoutData>()
-            .unwrap()
-            .offset;
-        cx.child(0).paint(canvas, &(child_offset + *offset))
+        cx.paint_children(canvas, offset)
     }
 }
 
+/// Size of a widget aligning a child of `child_size` within itself, sized by
+/// the given factors of `child_size`, or expanded where they're `None`.
+pub(crate) fn aligned_size(
+    constraints: Constraints,
+    child_size: Size,
+    width_factor: Option<f64>,
+    height_factor: Option<f64>,
+) -> Size {
+    assert!(
+        width_factor.map_or(true, |f| f >= 0.) && height_factor.map_or(true, |f| f >= 0.),
+        "size factors must not be negative",
+    );
+
+    let width = match width_factor {
+        Some(factor) => child_size.width * factor,
+        None if constraints.max_width == f64::INFINITY => child_size.width,
+        None => f64::INFINITY,
+    };
+
+    let height = match height_factor {
+        Some(factor) => child_size.height * factor,
+        None if constraints.max_height == f64::INFINITY => child_size.height,
+        None => f64::INFINITY,
+    };
+
+    constraints.constrain(Size::new(width, height))
+}
+
 #[derive(RenderWidget, Builder)]
 pub struct Padding<T: Widget, P: Directional<Output = EdgeInsets>> {
     pub child: T,
@@ -160,23 +168,65 @@ where
     }
 
     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
-        self.ensure_parent_data(cx, BoxLayoutData::default);
         let text_direction = Directionality::of_or_default(cx);
         let padding = self.padding.resolve(&text_direction);
         let child_constraints = padding.deflate_constraints(&constraints);
-        let child_size = cx.child(0).layout(child_constraints);
         let child = cx.child(0);
-        let mut child_parent_data = child.try_parent_data_mut::<BoxLayoutData>().unwrap();
-        child_parent_data.offset = padding.top_left();
+        let child_size = child.layout(child_constraints);
+        child.set_offset(padding.top_left());
         constraints.constrain(child_size + padding.collapsed_size())
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
-        let child_offset = cx
-            .child(0)
-            .try_parent_data::<BoxLayoutData>()
-            .unwrap()
-            .offset;
-        cx.child(0).paint(canvas, &(*offset + child_offset))
+        cx.paint_children(canvas, offset)
+    }
+}
+
+impl<T, P> Intrinsics for Padding<T, P>
+where
+    T: Widget,
+    P: Directional<Output = EdgeInsets>,
+{
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let padding = self.padding.resolve(&Directionality::of_or_default(cx));
+        let height = (height - padding.vertical()).max(0.);
+        cx.child(0).min_intrinsic_width(height) + padding.horizontal()
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let padding = self.padding.resolve(&Directionality::of_or_default(cx));
+        let height = (height - padding.vertical()).max(0.);
+        cx.child(0).max_intrinsic_width(height) + padding.horizontal()
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let padding = self.padding.resolve(&Directionality::of_or_default(cx));
+        let width = (width - padding.horizontal()).max(0.);
+        cx.child(0).min_intrinsic_height(width) + padding.vertical()
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let padding = self.padding.resolve(&Directionality::of_or_default(cx));
+        let width = (width - padding.horizontal()).max(0.);
+        cx.child(0).max_intrinsic_height(width) + padding.vertical()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sizes_to_child_by_factors() {
+        let child = Size::new(10., 20.);
+        let bounded = |w, h| aligned_size(Constraints::new(0., 100., 0., 100.), child, w, h);
+        let unbounded = |w, h| aligned_size(Constraints::default(), child, w, h);
+
+        assert_eq!(bounded(None, None), Size::new(100., 100.));
+        assert_eq!(bounded(Some(2.), None), Size::new(20., 100.));
+        assert_eq!(unbounded(None, Some(1.5)), Size::new(10., 30.));
+
+        // Sizes are still constrained.
+        assert_eq!(bounded(Some(20.), Some(0.)), Size::new(100., 0.));
     }
 }
@@ -1,7 +1,7 @@
 use frui::prelude::*;
 use frui::render::*;
 
-use crate::BoxLayoutData;
+use crate::*;
 
 #[derive(RenderWidget, Default, Builder)]
 pub struct ConstrainedBox<T: Widget> {
@@ -38,6 +38,32 @@ impl<T: Widget> RenderWidget for ConstrainedBox<T> {
     }
 }
 
+impl<T: Widget> Intrinsics for ConstrainedBox<T> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let height = self.constraints.constrain_height(height);
+        let width = cx.child(0).min_intrinsic_width(height);
+        self.constraints.constrain_width(width)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let height = self.constraints.constrain_height(height);
+        let width = cx.child(0).max_intrinsic_width(height);
+        self.constraints.constrain_width(width)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let width = self.constraints.constrain_width(width);
+        let height = cx.child(0).min_intrinsic_height(width);
+        self.constraints.constrain_height(height)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let width = self.constraints.constrain_width(width);
+        let height = cx.child(0).max_intrinsic_height(width);
+        self.constraints.constrain_height(height)
+    }
+}
+
 #[derive(RenderWidget, Builder)]
 pub struct UnconstrainedBox<T: Widget> {
     pub child: T,
@@ -176,3 +202,226 @@ impl<T: Widget> RenderWidget for LimitedBox<T> {
         cx.child(0).paint(canvas, offset)
     }
 }
+
+/// Sizes its `child` to a fraction of the space it is given, e.g. to make a
+/// dialog half as wide as the window, and positions it within that space at
+/// the `alignment`.
+///
+/// In a direction without a factor, or in which the space is unbounded, the
+/// child gets the constraints of this widget.
+#[derive(RenderWidget, Builder)]
+pub struct FractionallySizedBox<T: Widget, A: Directional<Output = Alignment>> {
+    pub child: T,
+    pub alignment: A,
+    pub width_factor: Option<f64>,
+    pub height_factor: Option<f64>,
+    pub text_direction: Option<TextDirection>,
+}
+
+impl FractionallySizedBox<(), Alignment> {
+    pub fn builder() -> Self {
+        Self {
+            child: (),
+            alignment: Alignment::CENTER,
+            width_factor: None,
+            height_factor: None,
+            text_direction: None,
+        }
+    }
+}
+
+impl<T, A> FractionallySizedBox<T, A>
+where
+    T: Widget,
+    A: Directional<Output = Alignment>,
+{
+    fn child_constraints(&self, constraints: Constraints) -> Constraints {
+        assert!(
+            self.width_factor.map_or(true, |f| f >= 0.)
+                && self.height_factor.map_or(true, |f| f >= 0.),
+            "size factors must not be negative",
+        );
+
+        let width = match self.width_factor {
+            Some(factor) if constraints.has_bounded_width() => Some(constraints.max_width * factor),
+            _ => None,
+        };
+
+        let height = match self.height_factor {
+            Some(factor) if constraints.has_bounded_height() => {
+                Some(constraints.max_height * factor)
+            }
+            _ => None,
+        };
+
+        Constraints::new(
+            width.unwrap_or(constraints.min_width),
+            width.unwrap_or(constraints.max_width),
+            height.unwrap_or(constraints.min_height),
+            height.unwrap_or(constraints.max_height),
+        )
+    }
+}
+
+impl<T, A> RenderWidget for FractionallySizedBox<T, A>
+where
+    T: Widget,
+    A: Directional<Output = Alignment>,
+{
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let text_direction = Directionality::unwrap_or_default(self.text_direction, cx);
+        let alignment = self.alignment.resolve(&text_direction);
+
+        let child = cx.child(0);
+        let child_size = child.layout(self.child_constraints(constraints));
+        let size = constraints.constrain(child_size);
+
+        child.set_offset(alignment.along(size - child_size));
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.paint_children(canvas, offset)
+    }
+}
+
+/// Sizes its `child` to the given `aspect_ratio` (width divided by height),
+/// e.g. to reserve space for a 16:9 video before it's loaded.
+///
+/// The child is as wide as possible, unless that would make it taller than
+/// allowed. In that case it's as tall as possible instead.
+#[derive(RenderWidget, Builder)]
+pub struct AspectRatio<T: Widget> {
+    pub child: T,
+    pub aspect_ratio: f64,
+}
+
+impl AspectRatio<()> {
+    pub fn builder() -> Self {
+        Self {
+            child: (),
+            aspect_ratio: 1.,
+        }
+    }
+}
+
+impl<T: Widget> RenderWidget for AspectRatio<T> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let size = aspect_ratio_size(constraints, self.aspect_ratio);
+        cx.child(0).layout(Constraints::new_tight(size));
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+impl<T: Widget> Intrinsics for AspectRatio<T> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        match height.is_finite() {
+            true => height * self.aspect_ratio,
+            false => cx.child(0).min_intrinsic_width(height),
+        }
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        match height.is_finite() {
+            true => height * self.aspect_ratio,
+            false => cx.child(0).max_intrinsic_width(height),
+        }
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        match width.is_finite() {
+            true => width / self.aspect_ratio,
+            false => cx.child(0).min_intrinsic_height(width),
+        }
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        match width.is_finite() {
+            true => width / self.aspect_ratio,
+            false => cx.child(0).max_intrinsic_height(width),
+        }
+    }
+}
+
+/// Biggest size of `aspect_ratio` satisfying `constraints`, preferring to
+/// fill the maximum width.
+fn aspect_ratio_size(constraints: Constraints, aspect_ratio: f64) -> Size {
+    assert!(
+        aspect_ratio.is_finite() && aspect_ratio > 0.,
+        "aspect ratio must be positive and finite",
+    );
+    assert!(
+        constraints.has_bounded_width() || constraints.has_bounded_height(),
+        "`AspectRatio` received unbounded constraints",
+    );
+
+    if constraints.is_tight() {
+        return constraints.smallest();
+    }
+
+    let (mut width, mut height) = match constraints.has_bounded_width() {
+        true => (constraints.max_width, constraints.max_width / aspect_ratio),
+        false => (
+            constraints.max_height * aspect_ratio,
+            constraints.max_height,
+        ),
+    };
+
+    if height > constraints.max_height {
+        height = constraints.max_height;
+        width = height * aspect_ratio;
+    }
+
+    if width < constraints.min_width {
+        width = constraints.min_width;
+        height = width / aspect_ratio;
+    }
+
+    if height < constraints.min_height {
+        height = constraints.min_height;
+        width = height * aspect_ratio;
+    }
+
+    constraints.constrain(Size::new(width, height))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sizes_to_aspect_ratio() {
+        let size = |constraints, ratio| {
+            let Size { width, height } = aspect_ratio_size(constraints, ratio);
+            (width, height)
+        };
+
+        // Fills the width.
+        let constraints = Constraints::new(0., 160., 0., 200.);
+        assert_eq!(size(constraints, 16. / 9.), (160., 90.));
+
+        // Fills the height if the width would make it too tall.
+        let constraints = Constraints::new(0., 400., 0., 100.);
+        assert_eq!(size(constraints, 2.), (200., 100.));
+
+        // Fills the height with unbounded width.
+        let constraints = Constraints::new(0., f64::INFINITY, 0., 50.);
+        assert_eq!(size(constraints, 2.), (100., 50.));
+
+        // Keeps the minimum height at the cost of the ratio.
+        let constraints = Constraints::new(0., 100., 80., 200.);
+        assert_eq!(size(constraints, 2.), (100., 80.));
+    }
+}
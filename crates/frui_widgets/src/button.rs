@@ -0,0 +1,648 @@
+//! Buttons: [`Button`], [`TextButton`] and [`IconButton`].
+//!
+//! All of them are drawn from the [`Theme`], highlight themselves while
+//! hovered, focused or pressed, and spread a ripple from the point where they
+//! were pressed. A button without `on_pressed` is disabled.
+
+use std::{cell::Cell, time::Duration};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    kurbo::{Circle, RoundedRect},
+    piet::RenderContext,
+    Cursor, KeyEvent,
+};
+use frui::{
+    app::feedback::{Feedback, FeedbackKind},
+    prelude::*,
+    render::*,
+};
+
+use crate::*;
+
+/// Overrides of the default appearance of a button. Unset properties are
+/// taken from the [`Theme`], depending on the kind of the button.
+///
+/// ```ignore
+/// ButtonStyle::new()
+///     .background(Color::rgb8(183, 28, 28))
+///     .radius(18.)
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ButtonStyle {
+    pub background: Option<Color>,
+    /// Color of the label.
+    pub foreground: Option<Color>,
+    /// Color of hover, focus and press highlights. Defaults to the
+    /// foreground color.
+    pub overlay: Option<Color>,
+    pub padding: Option<EdgeInsets>,
+    /// Radius of the corners. Infinite radius makes the button round.
+    pub radius: Option<f64>,
+    pub min_size: Option<Size>,
+}
+
+impl ButtonStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn foreground(mut self, color: Color) -> Self {
+        self.foreground = Some(color);
+        self
+    }
+
+    pub fn overlay(mut self, color: Color) -> Self {
+        self.overlay = Some(color);
+        self
+    }
+
+    pub fn padding(mut self, padding: EdgeInsets) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    pub fn min_size(mut self, min_size: Size) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    /// This style with properties set in `other` replaced by them.
+    pub fn merge(&self, other: &ButtonStyle) -> ButtonStyle {
+        ButtonStyle {
+            background: other.background.clone().or_else(|| self.background.clone()),
+            foreground: other.foreground.clone().or_else(|| self.foreground.clone()),
+            overlay: other.overlay.clone().or_else(|| self.overlay.clone()),
+            padding: other.padding.or(self.padding),
+            radius: other.radius.or(self.radius),
+            min_size: other.min_size.or(self.min_size),
+        }
+    }
+}
+
+/// Interaction states of a button, in which it's drawn differently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ButtonStates {
+    hovered: bool,
+    pressed: bool,
+    focused: bool,
+    disabled: bool,
+}
+
+impl ButtonStates {
+    /// Opacity of the overlay color painted over the button.
+    fn overlay_opacity(&self) -> f64 {
+        match self {
+            ButtonStates { disabled: true, .. } => 0.,
+            ButtonStates { pressed: true, .. } | ButtonStates { focused: true, .. } => 0.12,
+            ButtonStates { hovered: true, .. } => 0.08,
+            _ => 0.,
+        }
+    }
+}
+
+/// Button filled with the primary color of the theme, for the main action
+/// of a screen or dialog.
+///
+/// ```ignore
+/// Button::builder()
+///     .on_pressed(|| save())
+///     .child(Text::new("Save"))
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Button<W: Widget, F: Fn()> {
+    pub child: W,
+    /// If `None`, the button is disabled.
+    pub on_pressed: Option<F>,
+    pub style: ButtonStyle,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl Button<(), fn()> {
+    pub fn builder() -> Self {
+        Button {
+            child: (),
+            on_pressed: None,
+            style: ButtonStyle::default(),
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+impl<W: Widget, F: Fn()> ViewWidget for Button<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        RawButton {
+            kind: ButtonKind::Filled,
+            child: &self.child,
+            on_pressed: self.on_pressed.as_ref(),
+            style: self.style.clone(),
+            autofocus: self.autofocus,
+            focus_node: self.focus_node.clone(),
+        }
+    }
+}
+
+/// Button without a background, for less prominent actions, e.g. in
+/// toolbars and dialogs.
+#[derive(ViewWidget, Builder)]
+pub struct TextButton<W: Widget, F: Fn()> {
+    pub child: W,
+    /// If `None`, the button is disabled.
+    pub on_pressed: Option<F>,
+    pub style: ButtonStyle,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl TextButton<(), fn()> {
+    pub fn builder() -> Self {
+        TextButton {
+            child: (),
+            on_pressed: None,
+            style: ButtonStyle::default(),
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+impl<W: Widget, F: Fn()> ViewWidget for TextButton<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        RawButton {
+            kind: ButtonKind::Text,
+            child: &self.child,
+            on_pressed: self.on_pressed.as_ref(),
+            style: self.style.clone(),
+            autofocus: self.autofocus,
+            focus_node: self.focus_node.clone(),
+        }
+    }
+}
+
+/// Round button showing an icon. Its `child` is usually an icon, but any
+/// widget will do.
+#[derive(ViewWidget, Builder)]
+pub struct IconButton<W: Widget, F: Fn()> {
+    pub child: W,
+    /// If `None`, the button is disabled.
+    pub on_pressed: Option<F>,
+    pub style: ButtonStyle,
+    /// Shown when the button is hovered, since icons often aren't
+    /// self-explanatory.
+    pub tooltip: Option<String>,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl IconButton<(), fn()> {
+    pub fn builder() -> Self {
+        IconButton {
+            child: (),
+            on_pressed: None,
+            style: ButtonStyle::default(),
+            tooltip: None,
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+impl<W: Widget, F: Fn()> ViewWidget for IconButton<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let button = RawButton {
+            kind: ButtonKind::Icon,
+            child: &self.child,
+            on_pressed: self.on_pressed.as_ref(),
+            style: self.style.clone(),
+            autofocus: self.autofocus,
+            focus_node: self.focus_node.clone(),
+        };
+
+        match &self.tooltip {
+            Some(message) => Tooltip::builder()
+                .message(message.clone())
+                .child(button)
+                .boxed(),
+            None => button.boxed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ButtonKind {
+    Filled,
+    Text,
+    Icon,
+}
+
+impl ButtonKind {
+    /// Name of the public widget, under which its taps are reported.
+    fn name(&self) -> &'static str {
+        match self {
+            ButtonKind::Filled => "Button",
+            ButtonKind::Text => "TextButton",
+            ButtonKind::Icon => "IconButton",
+        }
+    }
+
+    fn default_style(&self, theme: &ThemeData) -> ButtonStyle {
+        let scheme = &theme.color_scheme;
+        let style = ButtonStyle::new().radius(4.).min_size(Size::new(64., 36.));
+
+        match self {
+            ButtonKind::Filled => style
+                .background(scheme.primary.clone())
+                .foreground(scheme.on_primary.clone())
+                .padding(theme.button_padding),
+            ButtonKind::Text => style
+                .background(Color::TRANSPARENT)
+                .foreground(scheme.primary.clone())
+                .padding(EdgeInsets::symmetric(8., 12.)),
+            ButtonKind::Icon => style
+                .background(Color::TRANSPARENT)
+                .foreground(scheme.on_background.clone())
+                .padding(EdgeInsets::all(8.))
+                .radius(f64::INFINITY)
+                .min_size(Size::new(40., 40.)),
+        }
+    }
+}
+
+/// Button of any kind, with its handlers and style borrowed from the public
+/// widget.
+#[derive(ViewWidget)]
+struct RawButton<W: Widget, F: Fn()> {
+    kind: ButtonKind,
+    child: W,
+    on_pressed: Option<F>,
+    style: ButtonStyle,
+    autofocus: bool,
+    focus_node: Option<FocusNode>,
+}
+
+pub struct ButtonState {
+    hovered: bool,
+    pressed: bool,
+    focused: bool,
+    ripple: AnimationController,
+    /// Fades the ripple out once the button is released.
+    fade: AnimationController,
+    /// Local position at which the button was pressed last.
+    ripple_origin: Cell<Point>,
+    listener: ControllerListener<AnimationController>,
+    fade_listener: ControllerListener<AnimationController>,
+}
+
+impl<W: Widget, F: Fn()> WidgetState for RawButton<W, F> {
+    type State = ButtonState;
+
+    fn create_state(&self) -> Self::State {
+        ButtonState {
+            hovered: false,
+            pressed: false,
+            focused: false,
+            ripple: AnimationController::new(RIPPLE_DURATION).curve(Curve::EaseOut),
+            fade: AnimationController::new(RIPPLE_FADE_DURATION),
+            ripple_origin: Cell::new(Point::ZERO),
+            listener: ControllerListener::default(),
+            fade_listener: ControllerListener::default(),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().listener.unlisten();
+        cx.state().fade_listener.unlisten();
+    }
+}
+
+const RIPPLE_DURATION: Duration = Duration::from_millis(300);
+const RIPPLE_FADE_DURATION: Duration = Duration::from_millis(200);
+const RIPPLE_OPACITY: f64 = 0.12;
+
+impl<W: Widget, F: Fn()> RawButton<W, F> {
+    fn activate(&self) {
+        if let Some(on_pressed) = &self.on_pressed {
+            Feedback::perform(FeedbackKind::Click);
+            on_pressed();
+        }
+    }
+
+    fn handle_key(&self, event: &KeyEvent) -> bool {
+        let activates = match &event.key {
+            Key::Enter => true,
+            Key::Character(c) => c == " ",
+            _ => false,
+        };
+
+        if activates && event.state == KeyState::Down && !event.repeat {
+            self.activate();
+        }
+
+        activates
+    }
+}
+
+impl<W: Widget, F: Fn()> ViewWidget for RawButton<W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let theme = Theme::of(cx);
+        let scheme = &theme.color_scheme;
+        let style = self.kind.default_style(&theme).merge(&self.style);
+
+        let state = cx.state();
+        let updater = cx.state_updater();
+        state.listener.listen(&state.ripple, {
+            let updater = updater.clone();
+            move || updater.update(|_| {})
+        });
+        state.fade_listener.listen(&state.fade, {
+            let updater = updater.clone();
+            move || updater.update(|_| {})
+        });
+
+        let states = ButtonStates {
+            hovered: state.hovered,
+            pressed: state.pressed,
+            focused: state.focused,
+            disabled: self.on_pressed.is_none(),
+        };
+
+        let (background, foreground) = match states.disabled {
+            true => (
+                match self.kind {
+                    ButtonKind::Filled => scheme.on_surface.with_alpha(0.12),
+                    _ => Color::TRANSPARENT,
+                },
+                scheme.on_surface.with_alpha(0.38),
+            ),
+            false => (style.background.unwrap(), style.foreground.unwrap()),
+        };
+
+        let overlay = style.overlay.unwrap_or_else(|| foreground.clone());
+
+        let icon_theme = IconThemeData {
+            color: Some(foreground.clone()),
+            size: None,
+        };
+
+        let opacity = ripple_opacity(&states, state.fade.is_animating(), state.fade.value());
+        let ripple = opacity.map(|opacity| Ripple {
+            origin: state.ripple_origin.get(),
+            progress: state.ripple.value(),
+            color: overlay.clone().with_alpha(opacity),
+        });
+
+        let surface = ButtonSurface {
+            background,
+            overlay: overlay.with_alpha(states.overlay_opacity()),
+            ripple,
+            radius: style.radius.unwrap(),
+            min_size: style.min_size.unwrap(),
+            child: Padding::builder().padding(style.padding.unwrap()).child(
+                DefaultTextStyle::builder()
+                    .style(theme.text_theme.label.clone().color(foreground.clone()))
+                    .max_lines(1)
+                    .child(IconTheme::builder().data(icon_theme).child(&self.child)),
+            ),
+        };
+
+        drop(state);
+
+        let press = move |pos: Point| {
+            let mut state = cx.state_mut();
+            state.pressed = true;
+            state.ripple_origin.set(pos);
+            state.fade.reset();
+            state.ripple.reset();
+            state.ripple.forward();
+        };
+
+        // The ripple keeps spreading while it fades, so that a quick tap
+        // still shows it.
+        let release = move || {
+            if cx.state().pressed {
+                let mut state = cx.state_mut();
+                state.pressed = false;
+                state.fade.forward();
+            }
+        };
+
+        let mut region = PointerRegion::builder()
+            .on_enter(move |_| cx.state_mut().hovered = true)
+            .on_exit(move |_| {
+                cx.state_mut().hovered = false;
+                release();
+            });
+
+        if !states.disabled {
+            region = region.cursor(Cursor::Pointer);
+        }
+
+        let mut focus = Focus::builder()
+            .autofocus(self.autofocus)
+            .can_request_focus(!states.disabled)
+            .on_key(move |event: &KeyEvent| self.handle_key(event))
+            .on_focus_change(move |focused| {
+                updater.update(move |state: &mut ButtonState| state.focused = focused)
+            });
+        focus.focus_node = self.focus_node.clone();
+
+        InteractionLabel::builder().widget(self.kind.name()).child(
+            focus.child(
+                region.child(
+                    PointerListener::builder()
+                        .on_pointer_up(move |_| release())
+                        .child(
+                            GestureDetector::builder()
+                                .on_tap_down(move |pos: Point| {
+                                    if !states.disabled {
+                                        press(pos);
+                                    }
+                                })
+                                .on_tap(move || self.activate())
+                                .keyboard_activation(false)
+                                .child(surface),
+                        ),
+                ),
+            ),
+        )
+    }
+}
+
+/// Opacity of the ripple, which is shown while the button is pressed and
+/// fades out by `fade` once it's released. `None` if there's no ripple.
+fn ripple_opacity(states: &ButtonStates, fading: bool, fade: f64) -> Option<f64> {
+    match !states.disabled && (states.pressed || fading) {
+        true => Some(RIPPLE_OPACITY * (1. - fade)),
+        false => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Ripple {
+    origin: Point,
+    /// From 0 (just pressed) to 1 (covering the whole button).
+    progress: f64,
+    color: Color,
+}
+
+/// Paints the background, highlight and ripple of a button below `child`,
+/// which is centered.
+#[derive(RenderWidget)]
+struct ButtonSurface<W: Widget> {
+    background: Color,
+    overlay: Color,
+    ripple: Option<Ripple>,
+    radius: f64,
+    min_size: Size,
+    child: W,
+}
+
+impl<W: Widget> RenderWidget for ButtonSurface<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let child = cx.child(0);
+        let child_size = child.layout(constraints.loosen());
+
+        let size = constraints.constrain(Size::new(
+            child_size.width.max(self.min_size.width),
+            child_size.height.max(self.min_size.height),
+        ));
+
+        child.set_offset(Offset::new(
+            (size.width - child_size.width) / 2.,
+            (size.height - child_size.height) / 2.,
+        ));
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let radius = self.radius.min(size.width.min(size.height) / 2.);
+        let shape = RoundedRect::new(
+            offset.x,
+            offset.y,
+            offset.x + size.width,
+            offset.y + size.height,
+            radius,
+        );
+
+        canvas.fill(shape, &self.background);
+        canvas.fill(shape, &self.overlay);
+
+        if let Some(ripple) = &self.ripple {
+            // Radius which covers the button from any origin.
+            let max_radius = size.width.hypot(size.height);
+            let circle = Circle::new(
+                (offset.x + ripple.origin.x, offset.y + ripple.origin.y),
+                max_radius * ripple.progress,
+            );
+
+            let r = canvas.with_save(|c| {
+                c.clip(shape);
+                c.fill(circle, &ripple.color);
+                Ok(())
+            });
+
+            if let Err(e) = r {
+                log::error!("painting button ripple failed: {}", e);
+            }
+        }
+
+        cx.paint_children(canvas, offset);
+    }
+}
+
+impl<W: Widget> Intrinsics for ButtonSurface<W> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let width = cx.child(0).min_intrinsic_width(height);
+        width.max(self.min_size.width)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let width = cx.child(0).max_intrinsic_width(height);
+        width.max(self.min_size.width)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let height = cx.child(0).min_intrinsic_height(width);
+        height.max(self.min_size.height)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let height = cx.child(0).max_intrinsic_height(width);
+        height.max(self.min_size.height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn overlay_opacity_follows_states() {
+        let hovered = ButtonStates {
+            hovered: true,
+            ..Default::default()
+        };
+        let pressed = ButtonStates {
+            pressed: true,
+            ..hovered
+        };
+        let disabled = ButtonStates {
+            disabled: true,
+            ..pressed
+        };
+
+        assert_eq!(ButtonStates::default().overlay_opacity(), 0.);
+        assert_eq!(hovered.overlay_opacity(), 0.08);
+        assert_eq!(pressed.overlay_opacity(), 0.12);
+        assert_eq!(disabled.overlay_opacity(), 0.);
+    }
+
+    #[test]
+    fn ripple_fades_out_after_release() {
+        let pressed = ButtonStates {
+            pressed: true,
+            ..Default::default()
+        };
+        let released = ButtonStates::default();
+
+        // Held long enough for the ripple to finish spreading.
+        assert_eq!(ripple_opacity(&pressed, false, 0.), Some(RIPPLE_OPACITY));
+        assert_eq!(ripple_opacity(&released, true, 0.), Some(RIPPLE_OPACITY));
+        assert_eq!(
+            ripple_opacity(&released, true, 0.5),
+            Some(RIPPLE_OPACITY / 2.)
+        );
+        assert_eq!(ripple_opacity(&released, false, 1.), None);
+    }
+
+    #[test]
+    fn merged_style_prefers_set_properties() {
+        let default = ButtonStyle::new().radius(4.).padding(EdgeInsets::all(8.));
+        let style = default.merge(&ButtonStyle::new().radius(18.));
+
+        assert_eq!(style.radius, Some(18.));
+        assert_eq!(style.padding, Some(EdgeInsets::all(8.)));
+        assert_eq!(style.background, None);
+    }
+}
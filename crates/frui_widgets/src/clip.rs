@@ -0,0 +1,156 @@
+use druid_shell::kurbo::{Ellipse, RoundedRect, RoundedRectRadii, Shape};
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::BorderRadius;
+
+/// Clips `child` to its layout box, cutting off whatever it paints outside
+/// of it (e.g. overflowing content). Children of `child` outside of the box
+/// don't receive pointer events.
+#[derive(RenderWidget, Builder)]
+pub struct ClipRect<W: Widget> {
+    pub child: W,
+}
+
+impl ClipRect<()> {
+    pub fn builder() -> Self {
+        ClipRect { child: () }
+    }
+}
+
+impl<W: Widget> RenderWidget for ClipRect<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let rect = Rect::from_origin_size(offset, cx.size());
+
+        cx.with_clip(canvas, rect, |cx, canvas| cx.child(0).paint(canvas, offset));
+    }
+}
+
+/// Clips `child` to its layout box with rounded corners.
+///
+/// Corners are rounded with circular arcs of the horizontal radius of
+/// `border_radius`.
+///
+/// ```ignore
+/// ClipRRect::builder()
+///     .border_radius(BorderRadius::circular(8.))
+///     .child(Image::new(avatar))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct ClipRRect<W: Widget> {
+    pub child: W,
+    pub border_radius: BorderRadius,
+}
+
+impl ClipRRect<()> {
+    pub fn builder() -> Self {
+        ClipRRect {
+            child: (),
+            border_radius: BorderRadius::ZERO,
+        }
+    }
+}
+
+impl<W: Widget> ClipRRect<W> {
+    fn shape(&self, rect: Rect) -> RoundedRect {
+        let r = &self.border_radius;
+        let radii = RoundedRectRadii::new(
+            r.top_left.x,
+            r.top_right.x,
+            r.bottom_right.x,
+            r.bottom_left.x,
+        );
+
+        RoundedRect::from_rect(rect.into(), radii)
+    }
+}
+
+impl<W: Widget> RenderWidget for ClipRRect<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let rect = Rect::from_origin_size(offset, cx.size());
+        paint_clipped(cx, canvas, offset, rect, self.shape(rect));
+    }
+}
+
+impl<W: Widget> HitTest for ClipRRect<W> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        let rect = Rect::from_origin_size(Offset::default(), cx.layout_box());
+        hit_test_clipped(cx, point, self.shape(rect))
+    }
+}
+
+/// Clips `child` to the ellipse inscribed in its layout box, e.g. to display
+/// round avatars. Square children are clipped to a circle.
+#[derive(RenderWidget, Builder)]
+pub struct ClipOval<W: Widget> {
+    pub child: W,
+}
+
+impl ClipOval<()> {
+    pub fn builder() -> Self {
+        ClipOval { child: () }
+    }
+}
+
+impl<W: Widget> RenderWidget for ClipOval<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let rect = Rect::from_origin_size(offset, cx.size());
+        paint_clipped(cx, canvas, offset, rect, Ellipse::from_rect(rect.into()));
+    }
+}
+
+impl<W: Widget> HitTest for ClipOval<W> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        let rect = Rect::from_origin_size(Offset::default(), cx.layout_box());
+        hit_test_clipped(cx, point, Ellipse::from_rect(rect.into()))
+    }
+}
+
+/// Paints the only child clipped to `shape`, which lies within `rect`.
+fn paint_clipped<W>(
+    cx: &mut PaintCx<W>,
+    canvas: &mut Canvas,
+    offset: &Offset,
+    rect: Rect,
+    shape: impl Shape,
+) {
+    // Clipping to the bounding rect first lets children outside of it be
+    // skipped entirely.
+    cx.with_clip(canvas, rect, |cx, canvas| {
+        canvas.clip(shape);
+        cx.child(0).paint(canvas, offset);
+    });
+}
+
+fn hit_test_clipped<W>(cx: &mut HitTestCx<W>, point: Point, shape: impl Shape) -> bool {
+    if shape.contains(point) {
+        cx.hit_test_children(point);
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,454 @@
+use std::{cell::RefCell, rc::Rc};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    KeyEvent,
+};
+use frui::{
+    app::{
+        commands::{CommandInfo, Commands},
+        focus::{FocusManager, FocusNode},
+    },
+    prelude::*,
+};
+
+use crate::*;
+
+/// Maximal number of remembered recently executed commands.
+const RECENT_CAPACITY: usize = 32;
+
+/// Relevance added to the most recently executed command, comparable to the
+/// score of a matched character at the start of a word. Less recently executed
+/// commands get proportionally less.
+const RECENCY_WEIGHT: i64 = 9000;
+
+/// Height of an entry of the list of commands.
+const ENTRY_EXTENT: f64 = 28.;
+
+/// Command which can be executed from the [`CommandPalette`].
+#[derive(Clone)]
+pub struct PaletteCommand {
+    pub id: String,
+    pub label: String,
+    /// Description of a shortcut of this command, e.g. `Ctrl+S`.
+    pub shortcut: Option<String>,
+    action: Rc<dyn Fn()>,
+}
+
+impl PaletteCommand {
+    pub fn new(
+        id: impl Into<String>,
+        label: impl Into<String>,
+        action: impl Fn() + 'static,
+    ) -> Self {
+        PaletteCommand {
+            id: id.into(),
+            label: label.into(),
+            shortcut: None,
+            action: Rc::new(action),
+        }
+    }
+
+    pub fn shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Command executing enabled command of [`Commands`] registry.
+    pub fn registered(info: CommandInfo) -> Self {
+        let id = info.id.clone();
+
+        PaletteCommand {
+            id: info.id,
+            label: info.label,
+            shortcut: info.shortcut.map(|s| s.to_string()),
+            action: Rc::new(move || {
+                Commands::execute(&id);
+            }),
+        }
+    }
+
+    /// Executes this command and marks it as recently used.
+    pub fn execute(&self) {
+        RECENT_COMMANDS.with(|recent| {
+            let mut recent = recent.borrow_mut();
+            recent.retain(|id| id != &self.id);
+            recent.insert(0, self.id.clone());
+            recent.truncate(RECENT_CAPACITY);
+        });
+
+        (self.action)();
+    }
+}
+
+/// Palette opened with `Ctrl+Shift+P` which fuzzy-searches `commands` and
+/// executes the selected one. It is shown in the closest [`Overlay`] ancestor.
+///
+/// Besides `commands`, enabled commands of the [`Commands`] registry are
+/// listed, unless `registered_commands` is disabled. Recently executed commands
+/// rank higher, the more recently they were executed.
+#[derive(ViewWidget, Builder)]
+pub struct CommandPalette<W: Widget> {
+    pub child: W,
+    pub commands: Vec<PaletteCommand>,
+    pub registered_commands: bool,
+    /// Number of commands visible at once, the rest can be scrolled to.
+    pub visible_results: usize,
+    pub width: f64,
+    pub color: Color,
+    pub highlight_color: Color,
+}
+
+impl CommandPalette<()> {
+    pub fn builder() -> Self {
+        CommandPalette {
+            child: (),
+            commands: Vec::new(),
+            registered_commands: true,
+            visible_results: 10,
+            width: 500.,
+            color: Color::rgb8(37, 37, 38),
+            highlight_color: Color::rgb8(4, 57, 94),
+        }
+    }
+}
+
+pub struct CommandPaletteState {
+    open: bool,
+    query: String,
+    selected: usize,
+    scroll: ScrollController,
+    /// Node focused before the palette was opened, focused again once it is
+    /// closed.
+    previous_focus: Option<FocusNode>,
+}
+
+impl<W: Widget> WidgetState for CommandPalette<W> {
+    type State = CommandPaletteState;
+
+    fn create_state(&self) -> Self::State {
+        CommandPaletteState {
+            open: false,
+            query: String::new(),
+            selected: 0,
+            scroll: ScrollController::new(),
+            previous_focus: None,
+        }
+    }
+}
+
+impl<W: Widget> CommandPalette<W> {
+    /// Commands matching the query, ordered by relevance.
+    fn matches(&self, query: &str) -> Vec<PaletteCommand> {
+        let recent = RECENT_COMMANDS.with(|recent| recent.borrow().clone());
+        let recency = |command: &PaletteCommand| recent.iter().position(|id| id == &command.id);
+
+        let mut commands = self.commands.clone();
+
+        if self.registered_commands {
+            commands.extend(
+                Commands::all()
+                    .into_iter()
+                    .filter(|c| c.enabled)
+                    .map(PaletteCommand::registered),
+            );
+        }
+
+        let mut matches = commands
+            .into_iter()
+            .filter_map(|c| Some((relevance(fuzzy_score(query, &c.label)?, recency(&c)), c)))
+            .collect::<Vec<_>>();
+
+        // Stable sort keeps order of `commands` for equally relevant ones.
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches.into_iter().map(|(_, c)| c).collect()
+    }
+
+    fn open(&self, cx: BuildCx<Self>) {
+        let mut state = cx.state_mut();
+        state.open = true;
+        state.previous_focus = FocusManager::focused();
+    }
+
+    fn close(&self, cx: BuildCx<Self>) {
+        let previous_focus = {
+            let mut state = cx.state_mut();
+            state.open = false;
+            state.query.clear();
+            state.selected = 0;
+            state.scroll.jump_to(0.);
+            state.previous_focus.take()
+        };
+
+        if let Some(node) = previous_focus {
+            node.request_focus();
+        }
+    }
+
+    fn execute_selected(&self, cx: BuildCx<Self>) {
+        let command = {
+            let state = cx.state();
+            self.matches(&state.query).into_iter().nth(state.selected)
+        };
+
+        self.close(cx);
+
+        if let Some(command) = command {
+            command.execute();
+        }
+    }
+
+    /// Selects the command `delta` entries away from the selected one and
+    /// scrolls it into view.
+    fn move_selection(&self, cx: BuildCx<Self>, delta: isize) {
+        let len = self.matches(&cx.state().query).len();
+
+        if len == 0 {
+            return;
+        }
+
+        let mut state = cx.state_mut();
+        state.selected = (state.selected as isize + delta).clamp(0, len as isize - 1) as usize;
+
+        let top = state.selected as f64 * ENTRY_EXTENT;
+        let visible = self.visible_results as f64 * ENTRY_EXTENT;
+        let offset = state.scroll.offset();
+
+        if top < offset {
+            state.scroll.jump_to(top);
+        } else if top + ENTRY_EXTENT > offset + visible {
+            state.scroll.jump_to(top + ENTRY_EXTENT - visible);
+        }
+    }
+
+    fn handle_toggle(&self, cx: BuildCx<Self>, event: KeyEvent) {
+        let is_toggle = event.state == KeyState::Down
+            && event.mods.ctrl()
+            && event.mods.shift()
+            && matches!(&event.key, Key::Character(c) if c.eq_ignore_ascii_case("p"));
+
+        match (is_toggle, cx.state().open) {
+            (false, _) => {}
+            (true, false) => self.open(cx),
+            (true, true) => self.close(cx),
+        }
+    }
+
+    /// Navigation keys of the query field, which aren't passed to the field
+    /// itself.
+    fn handle_key(&self, cx: BuildCx<Self>, event: &KeyEvent) -> bool {
+        if event.state != KeyState::Down {
+            return false;
+        }
+
+        match event.key {
+            Key::Escape => self.close(cx),
+            Key::ArrowUp => self.move_selection(cx, -1),
+            Key::ArrowDown => self.move_selection(cx, 1),
+            Key::PageUp => self.move_selection(cx, -(self.visible_results as isize)),
+            Key::PageDown => self.move_selection(cx, self.visible_results as isize),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn palette<'w>(&'w self, cx: BuildCx<'w, Self>) -> impl Widget + 'w {
+        let state = cx.state();
+        let matches = self.matches(&state.query);
+        let count = matches.len();
+        let selected = state.selected;
+
+        let results = ListView::builder(count, move |n| PaletteEntry {
+            command: matches[n].clone(),
+            color: match n == selected {
+                true => self.highlight_color.clone(),
+                false => self.color.clone(),
+            },
+            on_execute: move || self.close(cx),
+        })
+        .item_extent(ENTRY_EXTENT)
+        .controller(state.scroll.clone());
+
+        let height = count.min(self.visible_results) as f64 * ENTRY_EXTENT;
+
+        let query = TextField::builder()
+            .placeholder("Type a command")
+            .autofocus(true)
+            .on_changed(move |query: &str| {
+                let mut state = cx.state_mut();
+                state.query = query.to_owned();
+                state.selected = 0;
+                state.scroll.jump_to(0.);
+            })
+            .on_submitted(move |_: &str| self.execute_selected(cx));
+
+        Align::builder().alignment(Alignment::TOP_CENTER).child(
+            Padding::builder()
+                .padding(EdgeInsets::from_ltrb(0., 48., 0., 0.))
+                .child(SizedBox::new(
+                    ColoredBox::builder().color(self.color.clone()).child(
+                        Column::builder()
+                            .cross_axis_alignment(CrossAxisAlignment::Stretch)
+                            .children((
+                                Padding::builder().padding(EdgeInsets::all(8.)).child(
+                                    Focus::builder()
+                                        .can_request_focus(false)
+                                        .skip_traversal(true)
+                                        .on_key(move |event: &KeyEvent| self.handle_key(cx, event))
+                                        .child(query),
+                                ),
+                                SizedBox::new(results, None, Some(height)),
+                            )),
+                    ),
+                    Some(self.width),
+                    None,
+                )),
+        )
+    }
+}
+
+impl<W: Widget> ViewWidget for CommandPalette<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let open = cx.state().open;
+
+        let palette = match open {
+            true => self.palette(cx).boxed(),
+            false => ().boxed(),
+        };
+
+        KeyboardEventDetector {
+            on_event: |event| self.handle_toggle(cx, event),
+            child: OverlayPortal::builder()
+                .visible(open)
+                .child(&self.child)
+                .overlay_child(palette),
+        }
+    }
+}
+
+#[derive(ViewWidget)]
+struct PaletteEntry<F: Fn()> {
+    command: PaletteCommand,
+    color: Color,
+    /// Called before the command is executed.
+    on_execute: F,
+}
+
+impl<F: Fn()> ViewWidget for PaletteEntry<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let shortcut = self.command.shortcut.as_deref().unwrap_or("");
+
+        PointerListener::builder()
+            .on_pointer_up(|_| {
+                (self.on_execute)();
+                self.command.execute();
+            })
+            .child(
+                ColoredBox::builder().color(self.color.clone()).child(
+                    Padding::builder()
+                        .padding(EdgeInsets::symmetric(4., 8.))
+                        .child(
+                            Row::builder()
+                                .main_axis_alignment(MainAxisAlignment::SpaceBetween)
+                                .children((
+                                    Text::new(self.command.label.as_str()),
+                                    Text::new(shortcut).color(Color::grey(0.6)),
+                                )),
+                        ),
+                ),
+            )
+    }
+}
+
+/// Relevance of a command whose label matched the query with `score` (see
+/// [`fuzzy_score`]) and which was the `recency`-th most recently executed one.
+fn relevance(score: i64, recency: Option<usize>) -> i64 {
+    let recency = recency.map_or(0, |n| RECENT_CAPACITY.saturating_sub(n));
+
+    score + RECENCY_WEIGHT * recency as i64 / RECENT_CAPACITY as i64
+}
+
+/// Scores how well `query` matches `text`, or returns `None` if characters of
+/// `query` don't appear in `text` in the same order (ignoring case).
+///
+/// Consecutive matches and matches at the start of words score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    if query.trim().is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut text_chars = text.chars().enumerate();
+    let mut previous = ' ';
+    let mut last_match = None;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let q = q.to_lowercase().next()?;
+
+        loop {
+            let (n, c) = text_chars.next()?;
+            let is_word_start =
+                !previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase());
+            previous = c;
+
+            if c.to_lowercase().next() != Some(q) {
+                continue;
+            }
+
+            score += 1;
+
+            if is_word_start {
+                score += 8;
+            }
+
+            if last_match.map_or(false, |m| m + 1 == n) {
+                score += 4;
+            }
+
+            last_match = Some(n);
+            break;
+        }
+    }
+
+    // Prefer shorter texts among the otherwise equal matches.
+    Some(score * 1000 - text.chars().count() as i64)
+}
+
+thread_local! {
+    /// Ids of recently executed commands, most recent first.
+    static RECENT_COMMANDS: RefCell<Vec<String>> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fuzzy_score, relevance};
+
+    #[test]
+    fn fuzzy_matching() {
+        assert!(fuzzy_score("opf", "Open File").is_some());
+        assert!(fuzzy_score("fo", "Open File").is_none());
+
+        assert!(fuzzy_score("of", "Open File") > fuzzy_score("of", "Toggle Profiler"));
+        assert!(fuzzy_score("save", "Save") > fuzzy_score("save", "Save All"));
+    }
+
+    #[test]
+    fn recency_is_weighted_into_relevance() {
+        let save = fuzzy_score("save", "Save").unwrap();
+        let save_all = fuzzy_score("save", "Save All").unwrap();
+
+        // Recently executed command outranks a slightly better match.
+        assert!(relevance(save_all, Some(0)) > relevance(save, None));
+        assert!(relevance(save, Some(0)) > relevance(save, Some(5)));
+
+        // Without a query, commands are ordered by recency alone.
+        assert!(relevance(0, Some(1)) > relevance(0, Some(2)));
+        assert_eq!(relevance(0, None), 0);
+
+        // Much better match still wins.
+        let open_file = fuzzy_score("of", "Open File").unwrap();
+        let profiler = fuzzy_score("of", "Toggle Profiler").unwrap();
+        assert!(relevance(open_file, None) > relevance(profiler, Some(0)));
+    }
+}
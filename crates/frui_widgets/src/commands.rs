@@ -0,0 +1,47 @@
+use frui::{
+    app::commands::{CommandInfo, CommandListenerKey, Commands},
+    prelude::*,
+};
+
+/// Builds its child from the registered command of `id`, rebuilding it every
+/// time commands or their enablement change. Useful for menu items and toolbar
+/// buttons.
+#[derive(ViewWidget)]
+pub struct CommandBuilder<W: Widget, F: Fn(Option<CommandInfo>) -> W> {
+    /// Shouldn't change for the lifetime of this widget.
+    pub id: &'static str,
+    pub builder: F,
+}
+
+impl<W: Widget, F: Fn(Option<CommandInfo>) -> W> CommandBuilder<W, F> {
+    pub fn new(id: &'static str, builder: F) -> Self {
+        CommandBuilder { id, builder }
+    }
+}
+
+impl<W: Widget, F: Fn(Option<CommandInfo>) -> W> WidgetState for CommandBuilder<W, F> {
+    type State = Option<CommandListenerKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        let updater = cx.state_updater();
+        let key = Commands::listen(move || updater.update(|_| {}));
+
+        *cx.state_mut() = Some(key);
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        if let Some(key) = cx.state_mut().take() {
+            Commands::unlisten(key);
+        }
+    }
+}
+
+impl<W: Widget, F: Fn(Option<CommandInfo>) -> W> ViewWidget for CommandBuilder<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        (self.builder)(Commands::get(self.id))
+    }
+}
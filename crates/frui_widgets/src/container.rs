@@ -1,34 +1,56 @@
 use frui::prelude::*;
 use frui::render::*;
 
-use crate::{BoxDecoration, Decoration, DecorationPosition, DefaultBoxDecoration, TextDirection};
-
+use crate::{
+    BoxDecoration, Decoration, DecorationPosition, DefaultBoxDecoration, EdgeInsets, TextDirection,
+};
+
+/// Sizes its `child` to the given `width` and `height` (where they're set),
+/// and paints the `color` or the `decoration` behind it. The child is inset
+/// by the border widths of the decoration.
+///
+/// Only one of `color` and `decoration` can be set. To paint a color along
+/// with a decoration, set the color of the decoration instead.
+///
+/// ```ignore
+/// Container::builder()
+///     .decoration(
+///         BoxDecoration::builder()
+///             .color(Color::WHITE)
+///             .border_radius(BorderRadius::circular(8.))
+///             .box_shadow(vec![shadow]),
+///     )
+///     .child(content)
+/// ```
 #[derive(RenderWidget)]
-pub struct Container<W: Widget> {
+pub struct Container<W: Widget, D: Decoration> {
     child: W,
     width: Option<f64>,
     height: Option<f64>,
     color: Option<Color>,
+    decoration: Option<D>,
 }
 
-impl Container<()> {
-    pub fn builder() -> Container<()> {
+impl Container<(), DefaultBoxDecoration> {
+    pub fn builder() -> Container<(), DefaultBoxDecoration> {
         Container {
             child: (),
             width: None,
             height: None,
             color: None,
+            decoration: None,
         }
     }
 }
 
-impl<W: Widget> Container<W> {
-    pub fn child<C: Widget>(self, child: C) -> Container<C> {
+impl<W: Widget, D: Decoration> Container<W, D> {
+    pub fn child<C: Widget>(self, child: C) -> Container<C, D> {
         Container {
             child,
             width: self.width,
             height: self.height,
             color: self.color,
+            decoration: self.decoration,
         }
     }
 
@@ -46,21 +68,55 @@ impl<W: Widget> Container<W> {
         self
     }
 
+    #[track_caller]
     pub fn color(mut self, color: Color) -> Self {
+        assert!(
+            self.decoration.is_none(),
+            "color can't be set along with decoration, set the color of the decoration instead"
+        );
         self.color = Some(color);
         self
     }
+
+    #[track_caller]
+    pub fn decoration<D2: Decoration>(self, decoration: D2) -> Container<W, D2> {
+        assert!(
+            self.color.is_none(),
+            "decoration can't be set along with color, set the color of the decoration instead"
+        );
+        Container {
+            child: self.child,
+            width: self.width,
+            height: self.height,
+            color: self.color,
+            decoration: Some(decoration),
+        }
+    }
+}
+
+impl<W: Widget, D: Decoration> Container<W, D> {
+    /// Space between the edges of the container and its child.
+    fn padding(&self) -> EdgeInsets {
+        self.decoration
+            .as_ref()
+            .map_or(EdgeInsets::ZERO, |d| d.padding())
+    }
 }
 
-impl<W: Widget> RenderWidget for Container<W> {
+impl<W: Widget, D: Decoration> RenderWidget for Container<W, D> {
     fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
         vec![&self.child]
     }
 
     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
         let constraints = Constraints::new_tight_for(self.width, self.height).enforce(constraints);
+        let padding = self.padding();
 
-        cx.child(0).layout(constraints)
+        let child = cx.child(0);
+        let child_size = child.layout(padding.deflate_constraints(&constraints));
+        child.set_offset(padding.top_left());
+
+        constraints.constrain(child_size + padding.collapsed_size())
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
@@ -69,7 +125,42 @@ impl<W: Widget> RenderWidget for Container<W> {
             canvas.fill(DruidRect::from_origin_size(offset, cx.size()), brush);
         }
 
-        cx.child(0).paint(canvas, offset)
+        if let Some(decoration) = &self.decoration {
+            let rect = Rect::from_origin_size(offset, cx.size());
+            decoration.paint(canvas, rect, offset);
+        }
+
+        cx.paint_children(canvas, offset)
+    }
+}
+
+impl<W: Widget, D: Decoration> Intrinsics for Container<W, D> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let padding = self.padding();
+        let height = (self.height.unwrap_or(height) - padding.vertical()).max(0.);
+        self.width
+            .unwrap_or_else(|| cx.child(0).min_intrinsic_width(height) + padding.horizontal())
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        let padding = self.padding();
+        let height = (self.height.unwrap_or(height) - padding.vertical()).max(0.);
+        self.width
+            .unwrap_or_else(|| cx.child(0).max_intrinsic_width(height) + padding.horizontal())
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let padding = self.padding();
+        let width = (self.width.unwrap_or(width) - padding.horizontal()).max(0.);
+        self.height
+            .unwrap_or_else(|| cx.child(0).min_intrinsic_height(width) + padding.vertical())
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let padding = self.padding();
+        let width = (self.width.unwrap_or(width) - padding.horizontal()).max(0.);
+        self.height
+            .unwrap_or_else(|| cx.child(0).max_intrinsic_height(width) + padding.vertical())
     }
 }
 
@@ -0,0 +1,90 @@
+use std::{cell::Cell, time::Duration};
+
+use frui::{app::frame_budget::FrameBudget, prelude::*};
+
+/// Builds `placeholder` in the first frame and the (expensive to build)
+/// subtree returned by `builder` only after `delay` elapses, so that the
+/// frame in which this widget appears (e.g. after navigating to a heavy
+/// screen) isn't delayed by it.
+///
+/// With the default zero `delay`, the subtree is built as soon as the frame
+/// with the placeholder is done. If the [`FrameBudget`] of that frame is
+/// already exhausted, building is postponed to the next frame, so that many
+/// deferred builders becoming ready at once don't delay a single frame.
+#[derive(ViewWidget)]
+pub struct DeferredBuilder<P: Widget, W: Widget, F: Fn() -> W> {
+    pub placeholder: P,
+    pub builder: F,
+    pub delay: Duration,
+}
+
+impl<W: Widget, F: Fn() -> W> DeferredBuilder<(), W, F> {
+    pub fn new(builder: F) -> Self {
+        DeferredBuilder {
+            placeholder: (),
+            builder,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+impl<P: Widget, W: Widget, F: Fn() -> W> DeferredBuilder<P, W, F> {
+    pub fn placeholder<P2: Widget>(self, placeholder: P2) -> DeferredBuilder<P2, W, F> {
+        DeferredBuilder {
+            placeholder,
+            builder: self.builder,
+            delay: self.delay,
+        }
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+pub struct DeferredBuilderState {
+    /// Whether `delay` elapsed.
+    ready: bool,
+    /// Whether the subtree was built, after which it's kept regardless of
+    /// the frame budget.
+    built: Cell<bool>,
+}
+
+impl<P: Widget, W: Widget, F: Fn() -> W> WidgetState for DeferredBuilder<P, W, F> {
+    type State = DeferredBuilderState;
+
+    fn create_state(&self) -> Self::State {
+        DeferredBuilderState {
+            ready: false,
+            built: Cell::new(false),
+        }
+    }
+
+    fn mount<'a>(&'a self, cx: BuildCx<'a, Self>) {
+        if !cx.state().ready {
+            cx.timer_once(self.delay, |state| state.ready = true);
+        }
+    }
+}
+
+impl<P: Widget, W: Widget, F: Fn() -> W> ViewWidget for DeferredBuilder<P, W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        if !state.ready {
+            return (&self.placeholder).boxed();
+        }
+
+        if !state.built.get() && FrameBudget::is_exhausted() {
+            drop(state);
+            cx.timer_once(Duration::ZERO, |_| {});
+
+            return (&self.placeholder).boxed();
+        }
+
+        state.built.set(true);
+
+        (self.builder)().boxed()
+    }
+}
@@ -0,0 +1,241 @@
+use druid_shell::kurbo::Line;
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::{
+    Axis, BorderSide, BorderStyle, CanvasStrokeExt, PixelSnap, StrokeCap, StrokeJoin, Theme,
+};
+
+/// Horizontal line separating content, e.g. items of a list.
+///
+/// The line spans the available width (less the indents) and is centered
+/// within `height`. A zero `thickness` paints a hairline, one device pixel
+/// thick.
+///
+/// ```ignore
+/// Divider::builder()
+///     .indent(72.)
+///     .style(BorderStyle::Dash(vec![4., 4.], 0.))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct Divider {
+    pub height: f64,
+    pub thickness: f64,
+    /// Empty space before the start of the line.
+    pub indent: f64,
+    /// Empty space after the end of the line.
+    pub end_indent: f64,
+    /// If `None`, [`ThemeData::divider_color`] is used.
+    pub color: Option<Color>,
+    pub style: BorderStyle,
+    pub cap: StrokeCap,
+}
+
+impl Divider {
+    pub fn builder() -> Self {
+        Self {
+            height: 16.,
+            thickness: 0.,
+            indent: 0.,
+            end_indent: 0.,
+            color: None,
+            style: BorderStyle::Solid,
+            cap: StrokeCap::Butt,
+        }
+    }
+
+    fn side(&self, color: Color) -> BorderSide {
+        BorderSide {
+            color,
+            width: self.thickness,
+            style: self.style.clone(),
+            cap: self.cap,
+            join: StrokeJoin::default(),
+        }
+    }
+}
+
+impl RenderWidget for Divider {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let color = resolve_color(cx, &self.color);
+        *cx.render_state_mut() = color;
+
+        let width = match constraints.has_bounded_width() {
+            true => constraints.max_width,
+            false => constraints.min_width,
+        };
+
+        Size::new(width, constraints.constrain_height(self.height))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let side = self.side(cx.render_state().clone());
+
+        paint_line(
+            canvas,
+            &side,
+            Axis::Horizontal,
+            offset.x + self.indent,
+            offset.x + size.width - self.end_indent,
+            offset.y + size.height / 2.,
+        );
+    }
+}
+
+/// Vertical line separating content, e.g. actions of a toolbar. See
+/// [`Divider`].
+#[derive(RenderWidget, Builder)]
+pub struct VerticalDivider {
+    pub width: f64,
+    pub thickness: f64,
+    /// Empty space above the line.
+    pub indent: f64,
+    /// Empty space below the line.
+    pub end_indent: f64,
+    /// If `None`, [`ThemeData::divider_color`] is used.
+    pub color: Option<Color>,
+    pub style: BorderStyle,
+    pub cap: StrokeCap,
+}
+
+impl VerticalDivider {
+    pub fn builder() -> Self {
+        Self {
+            width: 16.,
+            thickness: 0.,
+            indent: 0.,
+            end_indent: 0.,
+            color: None,
+            style: BorderStyle::Solid,
+            cap: StrokeCap::Butt,
+        }
+    }
+
+    fn side(&self, color: Color) -> BorderSide {
+        BorderSide {
+            color,
+            width: self.thickness,
+            style: self.style.clone(),
+            cap: self.cap,
+            join: StrokeJoin::default(),
+        }
+    }
+}
+
+impl RenderWidget for VerticalDivider {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let color = resolve_color(cx, &self.color);
+        *cx.render_state_mut() = color;
+
+        let height = match constraints.has_bounded_height() {
+            true => constraints.max_height,
+            false => constraints.min_height,
+        };
+
+        Size::new(constraints.constrain_width(self.width), height)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let side = self.side(cx.render_state().clone());
+
+        paint_line(
+            canvas,
+            &side,
+            Axis::Vertical,
+            offset.y + self.indent,
+            offset.y + size.height - self.end_indent,
+            offset.x + size.width / 2.,
+        );
+    }
+}
+
+impl RenderState for Divider {
+    /// Color resolved during layout.
+    type State = Color;
+
+    fn create_state(&self) -> Self::State {
+        Color::TRANSPARENT
+    }
+}
+
+impl RenderState for VerticalDivider {
+    type State = Color;
+
+    fn create_state(&self) -> Self::State {
+        Color::TRANSPARENT
+    }
+}
+
+fn resolve_color<T>(cx: &LayoutCx<T>, color: &Option<Color>) -> Color {
+    match color {
+        Some(color) => color.clone(),
+        None => Theme::of_layout(cx).divider_color,
+    }
+}
+
+/// Paints a line of `side` from `start` to `end` along `axis`, centered on
+/// `center` across it.
+fn paint_line(
+    canvas: &mut Canvas,
+    side: &BorderSide,
+    axis: Axis,
+    start: f64,
+    end: f64,
+    center: f64,
+) {
+    // Zero width is a hairline, so `to_stroke_options` can't be used to
+    // check whether there is anything to paint.
+    if side.style == BorderStyle::None || end <= start {
+        return;
+    }
+
+    let snap = PixelSnap::for_canvas(canvas);
+    let width = snap.line_width(side.width);
+
+    let (center, start, end) = match axis {
+        Axis::Horizontal => (
+            snap.line_center_y(center, width),
+            snap.snap_x(start),
+            snap.snap_x(end),
+        ),
+        Axis::Vertical => (
+            snap.line_center_x(center, width),
+            snap.snap_y(start),
+            snap.snap_y(end),
+        ),
+    };
+
+    let point = |along: f64, across: f64| match axis {
+        Axis::Horizontal => Point::new(along, across),
+        Axis::Vertical => Point::new(across, along),
+    };
+
+    match side.copy_with(None, Some(width), None).to_stroke_options() {
+        // Plain lines are filled rather than stroked, so that their ends stay
+        // on pixel boundaries too.
+        Some(options) if options.dash.is_none() && options.cap == StrokeCap::Butt => {
+            let rect = DruidRect::from_points(
+                point(start, center - width / 2.),
+                point(end, center + width / 2.),
+            );
+
+            let brush = canvas.solid_brush(side.color.clone());
+            canvas.fill(rect, &brush);
+        }
+        Some(options) => {
+            let line = Line::new(point(start, center), point(end, center));
+            canvas.stroke_with(line, &side.color, &options);
+        }
+        None => {}
+    }
+}
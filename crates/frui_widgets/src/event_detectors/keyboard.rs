@@ -4,6 +4,11 @@ use frui::{
     prelude::*,
 };
 
+use crate::Focus;
+
+/// Calls `on_event` with key presses of the window which weren't handled by
+/// focused widgets. See [`KeyboardListener`] for events received by a
+/// focused widget.
 #[derive(ViewWidget)]
 pub struct KeyboardEventDetector<W: Widget, F: Fn(KeyEvent)> {
     pub on_event: F,
@@ -36,3 +41,48 @@ impl<W: Widget, F: Fn(KeyEvent)> ViewWidget for KeyboardEventDetector<W, F> {
         &self.child
     }
 }
+
+/// Calls `on_key_event` with key events (presses and releases) received while
+/// `child` (or one of its descendants) is focused. Events aren't handled, so
+/// they are passed on to focused ancestors and the platform afterwards.
+///
+/// ```ignore
+/// KeyboardListener::builder()
+///     .autofocus(true)
+///     .on_key_event(|event: &KeyEvent| log::info!("{:?}", event.key))
+///     .child(game)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct KeyboardListener<W: Widget, F: Fn(&KeyEvent)> {
+    /// If `None`, the listener has its own node.
+    pub focus_node: Option<FocusNode>,
+    pub autofocus: bool,
+    pub on_key_event: F,
+    pub child: W,
+}
+
+impl KeyboardListener<(), fn(&KeyEvent)> {
+    pub fn builder() -> Self {
+        KeyboardListener {
+            focus_node: None,
+            autofocus: false,
+            on_key_event: |_| {},
+            child: (),
+        }
+    }
+}
+
+impl<W: Widget, F: Fn(&KeyEvent)> ViewWidget for KeyboardListener<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let mut focus = Focus::builder()
+            .autofocus(self.autofocus)
+            .on_key(move |event: &KeyEvent| {
+                (self.on_key_event)(event);
+                false
+            })
+            .child(&self.child);
+        focus.focus_node = self.focus_node.clone();
+
+        focus
+    }
+}
@@ -0,0 +1,43 @@
+use frui::{
+    app::listeners::lifecycle::{LifecycleCallbackKey, LIFECYCLE_EVENT_LISTENERS},
+    prelude::*,
+};
+
+pub use frui::app::listeners::lifecycle::{ExitRequest, LifecycleEvent};
+
+/// Calls `on_event` for every application lifecycle event.
+///
+/// To prevent the window from closing, call [`ExitRequest::cancel`] on
+/// [`LifecycleEvent::ExitRequested`].
+#[derive(ViewWidget)]
+pub struct LifecycleEventDetector<W: Widget, F: Fn(&LifecycleEvent)> {
+    pub on_event: F,
+    pub child: W,
+}
+
+impl<W: Widget, F: Fn(&LifecycleEvent)> WidgetState for LifecycleEventDetector<W, F> {
+    type State = Option<LifecycleCallbackKey>;
+
+    fn create_state<'a>(&'a self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        *cx.state_mut() = Some(
+            LIFECYCLE_EVENT_LISTENERS
+                .with(|listeners| unsafe { listeners.borrow_mut().register(&self.on_event) }),
+        );
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        let mut key = cx.state_mut();
+        LIFECYCLE_EVENT_LISTENERS.with(|listeners| listeners.borrow_mut().unregister(key.unwrap()));
+        *key = None;
+    }
+}
+
+impl<W: Widget, F: Fn(&LifecycleEvent)> ViewWidget for LifecycleEventDetector<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+}
@@ -3,7 +3,7 @@ use frui::render::*;
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
-use crate::{Directional, TextDirection};
+use crate::{Directional, Lerp, TextDirection};
 
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Alignment {
@@ -20,6 +20,12 @@ impl Directional for Alignment {
 }
 
 impl Alignment {
+    /// Alignment with `x` and `y` between -1 (left and top) and 1 (right and
+    /// bottom), where 0 is the center.
+    pub const fn new(x: f64, y: f64) -> Self {
+        Alignment { x, y }
+    }
+
     pub fn along<T: Into<Size>>(&self, other: T) -> Offset {
         let size: Size = other.into();
         let center_x = size.width / 2.0;
@@ -30,6 +36,34 @@ impl Alignment {
         }
     }
 
+    /// Point within `rect` at this alignment.
+    pub fn within_rect(&self, rect: Rect) -> Offset {
+        let offset = self.along(rect.size());
+        Offset::new(rect.left + offset.x, rect.top + offset.y)
+    }
+
+    /// Rect of `size` placed within `rect` at this alignment.
+    pub fn inscribe(&self, size: Size, rect: Rect) -> Rect {
+        let offset = self.along(rect.size() - size);
+        Rect::from_origin_size(Offset::new(rect.left + offset.x, rect.top + offset.y), size)
+    }
+
+    /// Alignment mirrored along the horizontal axis (top becomes bottom).
+    pub fn flip_vertically(&self) -> Alignment {
+        Alignment {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+
+    /// Alignment mirrored along the vertical axis (left becomes right).
+    pub fn flip_horizontally(&self) -> Alignment {
+        Alignment {
+            x: -self.x,
+            y: self.y,
+        }
+    }
+
     pub const TOP_LEFT: Alignment = Alignment { x: -1.0, y: -1.0 };
     pub const TOP_CENTER: Alignment = Alignment { x: 0.0, y: -1.0 };
     pub const TOP_RIGHT: Alignment = Alignment { x: 1.0, y: -1.0 };
@@ -86,6 +120,37 @@ impl Neg for Alignment {
     }
 }
 
+impl Mul<f64> for Alignment {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Alignment {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+impl Div<f64> for Alignment {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Alignment {
+            x: self.x / rhs,
+            y: self.y / rhs,
+        }
+    }
+}
+
+impl Lerp for Alignment {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        Alignment {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+}
+
 impl Display for Alignment {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         for (alignment, name) in Alignment::PRELUDES {
@@ -123,6 +188,17 @@ impl AlignmentDirectional {
         Self { start, y }
     }
 
+    /// Point within `rect` at this alignment, resolved for `text_direction`.
+    pub fn within_rect(&self, rect: Rect, text_direction: &TextDirection) -> Offset {
+        self.resolve(text_direction).within_rect(rect)
+    }
+
+    /// Rect of `size` placed within `rect` at this alignment, resolved for
+    /// `text_direction`.
+    pub fn inscribe(&self, size: Size, rect: Rect, text_direction: &TextDirection) -> Rect {
+        self.resolve(text_direction).inscribe(size, rect)
+    }
+
     pub const TOP_START: AlignmentDirectional = Self::new(-1., -1.);
     pub const TOP_CENTER: AlignmentDirectional = Self::new(0., -1.);
     pub const TOP_END: AlignmentDirectional = Self::new(1., -1.);
@@ -222,6 +298,15 @@ impl Div<f64> for AlignmentDirectional {
     }
 }
 
+impl Lerp for AlignmentDirectional {
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        AlignmentDirectional::new(
+            self.start + (other.start - self.start) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+}
+
 impl Display for AlignmentDirectional {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         for (alignment, name) in AlignmentDirectional::PRELUDES {
@@ -232,3 +317,30 @@ impl Display for AlignmentDirectional {
         write!(f, "AlignmentDirectional({}, {})", &self.start, &self.y)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn alignment_within_rect() {
+        let rect = Rect::from_ltwh(10., 20., 100., 50.);
+
+        assert_eq!(
+            Alignment::BOTTOM_RIGHT.within_rect(rect),
+            Offset::new(110., 70.)
+        );
+        assert_eq!(
+            Alignment::CENTER.inscribe(Size::new(20., 10.), rect),
+            Rect::from_ltwh(50., 40., 20., 10.)
+        );
+        assert_eq!(
+            AlignmentDirectional::TOP_START.within_rect(rect, &TextDirection::Rtl),
+            Offset::new(110., 20.)
+        );
+
+        let halfway = Alignment::TOP_LEFT.lerp(&Alignment::BOTTOM_RIGHT, 0.5);
+        assert_eq!(halfway, Alignment::CENTER);
+        assert_eq!(Alignment::TOP_LEFT * 0.5 / 0.5, Alignment::TOP_LEFT);
+    }
+}
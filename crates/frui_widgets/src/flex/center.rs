@@ -1,14 +1,33 @@
 use frui::prelude::*;
 use frui::render::*;
 
+use crate::{aligned_size, Alignment};
+
+/// Centers its `child` within itself, sized like an [`Align`](crate::Align).
 #[derive(RenderWidget)]
 pub struct Center<W: Widget> {
     pub child: W,
+    pub width_factor: Option<f64>,
+    pub height_factor: Option<f64>,
 }
 
 impl<W: Widget> Center<W> {
     pub fn child(child: W) -> Self {
-        Center { child }
+        Center {
+            child,
+            width_factor: None,
+            height_factor: None,
+        }
+    }
+
+    pub fn width_factor(mut self, width_factor: f64) -> Self {
+        self.width_factor = Some(width_factor);
+        self
+    }
+
+    pub fn height_factor(mut self, height_factor: f64) -> Self {
+        self.height_factor = Some(height_factor);
+        self
     }
 }
 
@@ -18,28 +37,19 @@ impl<W: Widget> RenderWidget for Center<W> {
     }
 
     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
-        let child_size = cx.child(0).layout(constraints.loosen());
-
-        let mut size = constraints.biggest();
-
-        if constraints.max_height == f64::INFINITY {
-            size.height = child_size.height;
-        } else if constraints.max_width == f64::INFINITY {
-            size.width = child_size.width;
-        }
-
+        let child = cx.child(0);
+        let child_size = child.layout(constraints.loosen());
+        let size = aligned_size(
+            constraints,
+            child_size,
+            self.width_factor,
+            self.height_factor,
+        );
+        child.set_offset(Alignment::CENTER.along(size - child_size));
         size
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
-        let self_size = cx.size();
-        let child_size = cx.child(0).size();
-
-        let child_offset = Offset {
-            x: offset.x + (self_size.width - child_size.width) / 2.,
-            y: offset.y + (self_size.height - child_size.height) / 2.,
-        };
-
-        cx.child(0).paint(canvas, &child_offset);
+        cx.paint_children(canvas, offset)
     }
 }
@@ -145,10 +145,24 @@ impl<WL: WidgetList> RenderWidget for Flex<WL> {
             cross_size_min = cross_size_min.max(cross_size_min_flex);
         }
 
+        //
+        // Align children to a shared baseline.
+
+        let max_baseline = self.max_baseline(cx);
+
+        if let Some((max_baseline, max_below_baseline)) = max_baseline {
+            cross_size_min = cross_size_min.max(max_baseline + max_below_baseline);
+        }
+
         //
         // Compute size of this `Flex` widget.
 
-        let size = self.compute_size(flexible, main_size_min, cross_size_min, constraints);
+        let mut size = self.compute_size(flexible, main_size_min, cross_size_min, constraints);
+
+        // Children which don't fit are cut off, instead of overflowing the
+        // parent of this `Flex`.
+        let overflow = (size.main(self.direction) - main_size_max).max(0.);
+        *size.main_mut(self.direction) -= overflow;
 
         let main_size = size.main(self.direction);
         let cross_size = size.cross(self.direction);
@@ -172,29 +186,169 @@ impl<WL: WidgetList> RenderWidget for Flex<WL> {
                 main_offset += child_size.main(self.direction) + space_between;
             }
 
-            let cross_offset = self.compute_cross_offset(
-                child_size.cross(self.direction),
-                cross_size,
-                text_direction,
-            );
+            let cross_offset = match max_baseline {
+                Some((max_baseline, _)) => child
+                    .distance_to_baseline()
+                    .map_or(0., |baseline| max_baseline - baseline),
+                None => self.compute_cross_offset(
+                    child_size.cross(self.direction),
+                    cross_size,
+                    text_direction,
+                ),
+            };
 
             *child_offset.cross_mut(self.direction) = cross_offset;
         }
 
+        let overflow = FlexOverflow {
+            extent: overflow,
+            flipped: main_axis_flipped,
+        };
+
+        let previous = std::mem::replace(&mut *cx.render_state_mut(), overflow);
+
+        // Warned once when children start overflowing, not on every layout
+        // while they keep overflowing (e.g. while the window is resized).
+        if overflow.extent > 0. && previous.extent <= 0. {
+            log::warn!(
+                "children of `Flex` overflowed it by {:.1} pixels on the main axis",
+                overflow.extent
+            );
+        }
+
         size
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
-        for mut child in cx.children() {
-            let child_offset: Offset = child
-                .try_parent_data::<FlexData>()
-                .map_or(*offset, |d| (*offset + d.offset));
-            child.paint(canvas, &child_offset);
+        let overflow = *cx.render_state();
+
+        if overflow.extent <= 0. {
+            return Self::paint_children(cx, canvas, offset);
+        }
+
+        let rect = Rect::from_origin_size(*offset, cx.size());
+
+        cx.with_clip(canvas, rect, |cx, canvas| {
+            Self::paint_children(cx, canvas, offset)
+        });
+
+        if cfg!(debug_assertions) {
+            self.paint_overflow_indicator(canvas, rect, overflow.flipped);
+        }
+    }
+}
+
+impl<WL: WidgetList> RenderState for Flex<WL> {
+    type State = FlexOverflow;
+
+    fn create_state(&self) -> Self::State {
+        FlexOverflow::default()
+    }
+}
+
+impl<WL: WidgetList> Baseline for Flex<WL> {
+    /// Highest baseline of children of a row, or baseline of the first child
+    /// of a column which has one.
+    fn distance_to_baseline(&self, cx: &LayoutCx<Self>) -> Option<f64> {
+        let mut baselines = cx.children().filter_map(|child| {
+            let offset = child.try_parent_data::<FlexData>()?.offset;
+            Some(child.distance_to_baseline()? + offset.y)
+        });
+
+        match self.direction {
+            Axis::Horizontal => baselines.reduce(f64::min),
+            Axis::Vertical => baselines.next(),
         }
     }
 }
 
+impl<WL: WidgetList> Intrinsics for Flex<WL> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Horizontal, height, Intrinsic::Min)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Horizontal, height, Intrinsic::Max)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Vertical, width, Intrinsic::Min)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Vertical, width, Intrinsic::Max)
+    }
+}
+
 impl<WL: WidgetList> Flex<WL> {
+    /// Intrinsic extent of this widget along `axis`, given `extent` along the
+    /// other axis.
+    fn intrinsic_extent(
+        &self,
+        cx: &LayoutCx<Self>,
+        axis: Axis,
+        extent: f64,
+        intrinsic: Intrinsic,
+    ) -> f64 {
+        let child_count = cx.children().len();
+        let spacing = self.space_between * child_count.saturating_sub(1) as f64;
+        let mut total_flex = 0;
+
+        if axis == self.direction {
+            // Flexible children get space proportional to their flex, so
+            // the one needing the most space per flex decides how much space
+            // all of them get.
+            let mut inflexible_extent = 0.;
+            let mut extent_per_flex: f64 = 0.;
+
+            for child in cx.children() {
+                let child_extent = intrinsic.of(&child, axis, extent);
+
+                match get_flex(&child).unwrap_or(0) {
+                    0 => inflexible_extent += child_extent,
+                    flex => {
+                        total_flex += flex;
+                        extent_per_flex = extent_per_flex.max(child_extent / flex as f64);
+                    }
+                }
+            }
+
+            inflexible_extent + extent_per_flex * total_flex as f64 + spacing
+        } else {
+            // Inflexible children take their maximum intrinsic extent on the
+            // main axis, and flexible children share the rest of `extent`.
+            let mut allocated = 0.;
+            let mut cross_extent: f64 = 0.;
+
+            for child in cx.children() {
+                match get_flex(&child).unwrap_or(0) {
+                    0 => {
+                        let main = Intrinsic::Max.of(&child, self.direction, f64::INFINITY);
+                        allocated += main;
+                        cross_extent = cross_extent.max(intrinsic.of(&child, axis, main));
+                    }
+                    flex => total_flex += flex,
+                }
+            }
+
+            let extent_per_flex = match extent.is_finite() {
+                true => (extent - allocated - spacing).max(0.) / total_flex.max(1) as f64,
+                false => f64::INFINITY,
+            };
+
+            for child in cx.children() {
+                let flex = get_flex(&child).unwrap_or(0);
+
+                if flex > 0 {
+                    let main = extent_per_flex * flex as f64;
+                    cross_extent = cross_extent.max(intrinsic.of(&child, axis, main));
+                }
+            }
+
+            cross_extent
+        }
+    }
+
     fn layout_inflexible(&self, children: LayoutCxIter, constraints: Constraints) -> InflexResult {
         let mut flex_count = 0;
         let mut cross_size_min = 0.0;
@@ -247,10 +401,10 @@ impl<WL: WidgetList> Flex<WL> {
         use MainAxisAlignment::*;
 
         // Caller should enforce following requirements.
-        assert!(child_count >= 1);
         assert!(self.space_between >= 0.0);
 
         let child_count = child_count as f64;
+        let gaps = (child_count - 1.).max(0.);
         let total_space = match self.direction {
             Axis::Horizontal => constraints.max_width,
             Axis::Vertical => constraints.max_height,
@@ -259,7 +413,7 @@ impl<WL: WidgetList> Flex<WL> {
         // Space between computed based on available space.
         let space_between;
 
-        if !flexible && matches!(self.main_axis_size, MainAxisSize::Max) {
+        if !flexible && child_count > 0. && matches!(self.main_axis_size, MainAxisSize::Max) {
             let available = total_space - allocated_space;
 
             space_between = match self.main_axis_alignment {
@@ -268,7 +422,9 @@ impl<WL: WidgetList> Flex<WL> {
                 // End:          [--------[][XX]]
                 Start | Center | End => 0.0,
                 // SpaceBetween: [[]--------[XX]]
-                SpaceBetween => available / (child_count - 1.),
+                SpaceBetween if gaps > 0. => available / gaps,
+                // SpaceBetween: [[XX]----------]
+                SpaceBetween => 0.0,
                 // SpaceAround:  [--[]----[XX]--]
                 SpaceAround => available / child_count,
                 // SpaceEvenly:  [---[]---[XX]---]
@@ -283,7 +439,7 @@ impl<WL: WidgetList> Flex<WL> {
 
         // Space from first child to end of last child (including the space
         // between those children).
-        let back_to_back = space_between * (child_count - 1.) + allocated_space;
+        let back_to_back = space_between * gaps + allocated_space;
 
         // Space before the first child.
         let mut leading_space;
@@ -479,10 +635,68 @@ impl<WL: WidgetList> Flex<WL> {
             (Start, false) | (End, true) => available,
             (Center, _) => available / 2.,
             (Stretch, _) => 0.0,
-            (Baseline, _) => todo!("implement baseline alignment"),
+            // Baselines are only aligned in rows, see `max_baseline`.
+            (Baseline, true) => 0.0,
+            (Baseline, false) => available,
         }
     }
 
+    /// Largest distance from the top of a child to its baseline, and largest
+    /// distance from a baseline to the bottom of a child, if children of a
+    /// row are aligned to their baselines.
+    ///
+    /// Children without a baseline are placed at the top.
+    fn max_baseline(&self, cx: &LayoutCx<Self>) -> Option<(f64, f64)> {
+        if self.cross_axis_alignment != CrossAxisAlignment::Baseline
+            || self.direction != Axis::Horizontal
+        {
+            return None;
+        }
+
+        let mut max_baseline: f64 = 0.;
+        let mut max_below_baseline: f64 = 0.;
+
+        for child in cx.children() {
+            if let Some(baseline) = child.distance_to_baseline() {
+                max_baseline = max_baseline.max(baseline);
+                max_below_baseline = max_below_baseline.max(child.size().height - baseline);
+            }
+        }
+
+        Some((max_baseline, max_below_baseline))
+    }
+
+    fn paint_children(cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        for mut child in cx.children() {
+            let child_offset: Offset = child
+                .try_parent_data::<FlexData>()
+                .map_or(*offset, |d| (*offset + d.offset));
+            child.paint(canvas, &child_offset);
+        }
+    }
+
+    /// Marks the edge of `rect` past which children overflowed.
+    fn paint_overflow_indicator(&self, canvas: &mut Canvas, rect: Rect, flipped: bool) {
+        const WIDTH: f64 = 4.;
+
+        let strip = match (self.direction, flipped) {
+            (Axis::Horizontal, false) => {
+                Rect::from_ltrb(rect.right - WIDTH, rect.top, rect.right, rect.bottom)
+            }
+            (Axis::Horizontal, true) => {
+                Rect::from_ltrb(rect.left, rect.top, rect.left + WIDTH, rect.bottom)
+            }
+            (Axis::Vertical, false) => {
+                Rect::from_ltrb(rect.left, rect.bottom - WIDTH, rect.right, rect.bottom)
+            }
+            (Axis::Vertical, true) => {
+                Rect::from_ltrb(rect.left, rect.top, rect.right, rect.top + WIDTH)
+            }
+        };
+
+        canvas.fill(DruidRect::from(strip), &Color::rgb8(0xE5, 0x39, 0x35));
+    }
+
     fn start_is_top_left(&self, text_direction: TextDirection) -> bool {
         match (self.direction, text_direction, self.vertical_direction) {
             (Axis::Vertical, TextDirection::Ltr, _) => true,
@@ -500,6 +714,34 @@ impl<WL: WidgetList> Flex<WL> {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Intrinsic {
+    Min,
+    Max,
+}
+
+impl Intrinsic {
+    /// Intrinsic extent of `child` along `axis`, given `extent` along the
+    /// other axis.
+    pub(crate) fn of(self, child: &LayoutCxOS, axis: Axis, extent: f64) -> f64 {
+        match (self, axis) {
+            (Intrinsic::Min, Axis::Horizontal) => child.min_intrinsic_width(extent),
+            (Intrinsic::Max, Axis::Horizontal) => child.max_intrinsic_width(extent),
+            (Intrinsic::Min, Axis::Vertical) => child.min_intrinsic_height(extent),
+            (Intrinsic::Max, Axis::Vertical) => child.max_intrinsic_height(extent),
+        }
+    }
+}
+
+/// Overflow of children of a [`Flex`] on its main axis, found during layout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlexOverflow {
+    extent: f64,
+    /// Whether children overflowed past the start of the main axis (its left
+    /// or top edge) rather than its end.
+    flipped: bool,
+}
+
 #[derive(Debug)]
 struct InflexResult {
     flex_count: usize,
@@ -537,7 +779,7 @@ fn fit_tight(c: &LayoutCxOS) -> bool {
     get_fit(c).unwrap() == FlexFit::Tight
 }
 
-trait AxisExt {
+pub(crate) trait AxisExt {
     fn main(&self, axis: Axis) -> f64;
     fn main_mut(&mut self, axis: Axis) -> &mut f64;
 
@@ -604,3 +846,112 @@ impl AxisExt for Size {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(not(feature = "miri"))]
+    use {
+        frui::app::offscreen,
+        std::sync::{Arc, Mutex},
+    };
+
+    fn distribute(alignment: MainAxisAlignment, child_count: usize) -> (f64, f64) {
+        let flex = Flex::builder()
+            .children(Vec::<()>::new())
+            .main_axis_size(MainAxisSize::Max)
+            .main_axis_alignment(alignment);
+
+        let constraints = Constraints::new(0., 100., 0., 100.);
+        let sizes = flex.compute_main_size(false, child_count, constraints, 40.);
+
+        (sizes.leading_space, sizes.space_between)
+    }
+
+    #[test]
+    fn distributes_free_space() {
+        assert_eq!(distribute(MainAxisAlignment::Start, 3), (0., 0.));
+        assert_eq!(distribute(MainAxisAlignment::End, 3), (60., 0.));
+        assert_eq!(distribute(MainAxisAlignment::Center, 3), (30., 0.));
+        assert_eq!(distribute(MainAxisAlignment::SpaceBetween, 3), (0., 30.));
+        assert_eq!(distribute(MainAxisAlignment::SpaceAround, 4), (7.5, 15.));
+        assert_eq!(distribute(MainAxisAlignment::SpaceEvenly, 3), (15., 15.));
+
+        // Single child and no children.
+        assert_eq!(distribute(MainAxisAlignment::SpaceBetween, 1), (0., 0.));
+        assert_eq!(distribute(MainAxisAlignment::SpaceEvenly, 0), (0., 0.));
+    }
+
+    /// Records the position of the baseline of `child` relative to the
+    /// painted surface, together with its vertical offset.
+    #[cfg(not(feature = "miri"))]
+    #[derive(RenderWidget)]
+    struct BaselineProbe<W: Widget> {
+        baselines: Arc<Mutex<Vec<(f64, f64)>>>,
+        child: W,
+    }
+
+    #[cfg(not(feature = "miri"))]
+    impl<W: Widget> RenderState for BaselineProbe<W> {
+        type State = f64;
+
+        fn create_state(&self) -> Self::State {
+            0.
+        }
+    }
+
+    #[cfg(not(feature = "miri"))]
+    impl<W: Widget> RenderWidget for BaselineProbe<W> {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![&self.child]
+        }
+
+        fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+            let size = cx.child(0).layout(constraints);
+            *cx.render_state_mut() = cx.child(0).distance_to_baseline().unwrap();
+            size
+        }
+
+        fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+            let baseline = offset.y + *cx.render_state();
+            self.baselines.lock().unwrap().push((offset.y, baseline));
+
+            cx.child(0).paint(canvas, offset)
+        }
+    }
+
+    #[cfg(not(feature = "miri"))]
+    #[test]
+    fn baseline_aligns_text_of_mixed_sizes() {
+        let baselines = Arc::new(Mutex::new(Vec::new()));
+
+        let root = {
+            let baselines = baselines.clone();
+            let text = move |size: f64| BaselineProbe {
+                baselines: baselines.clone(),
+                child: Text::new("Ag").size(size),
+            };
+
+            move || {
+                Row::builder()
+                    .cross_axis_alignment(CrossAxisAlignment::Baseline)
+                    .children((text(12.), text(32.)))
+            }
+        };
+
+        offscreen::render(Size::new(200., 100.), 1., root)
+            .join()
+            .unwrap()
+            .unwrap();
+
+        let baselines = baselines.lock().unwrap();
+        let [(small_top, small), (large_top, large)] = baselines[..] else {
+            panic!("expected two painted texts, got {:?}", baselines);
+        };
+
+        // Smaller text is moved down so that both baselines line up.
+        assert!(small_top > large_top);
+        assert!((small - large).abs() < 1e-6);
+    }
+}
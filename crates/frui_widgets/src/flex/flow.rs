@@ -0,0 +1,277 @@
+use frui::app::window::Window;
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::*;
+
+/// Positions children of a [`Flow`] while it is painted.
+pub trait FlowDelegate {
+    /// Size of the flow, the biggest size allowed by `constraints` by default
+    /// (or the smallest one, if they are unbounded).
+    fn size(&self, constraints: Constraints) -> Size {
+        let biggest = constraints.biggest();
+
+        match biggest.width.is_finite() && biggest.height.is_finite() {
+            true => biggest,
+            false => constraints.smallest(),
+        }
+    }
+
+    /// Constraints of the child at `index`, given constraints of the flow.
+    fn child_constraints(&self, _index: usize, constraints: Constraints) -> Constraints {
+        constraints.loosen()
+    }
+
+    /// Paints children with [`FlowPaintCx::paint_child`], from the bottom
+    /// one to the top one. Children which aren't painted aren't visible and
+    /// can't be hit by the pointer.
+    fn paint_children(&self, cx: &mut FlowPaintCx);
+
+    /// Animation read by `paint_children`. The flow is painted again on every
+    /// change of its value, without being rebuilt.
+    fn repaint(&self) -> Option<AnimationController> {
+        None
+    }
+}
+
+/// Children of a [`Flow`] being painted by its delegate.
+pub struct FlowPaintCx<'a, 'c> {
+    canvas: &'a mut Canvas<'c>,
+    offset: Offset,
+    size: Size,
+    children: Vec<PaintCxOS>,
+    /// Indices of painted children, in the order they were painted.
+    painted: Vec<usize>,
+}
+
+impl<'a, 'c> FlowPaintCx<'a, 'c> {
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn child_size(&self, index: usize) -> Option<Size> {
+        self.children.get(index).map(|child| child.size())
+    }
+
+    /// Paints the child at `index` transformed by `transform`, in the
+    /// coordinates of the flow. Every child is painted at most once.
+    pub fn paint_child(&mut self, index: usize, transform: Affine) {
+        assert!(
+            !self.painted.contains(&index),
+            "child {} of `Flow` was already painted",
+            index
+        );
+
+        let offset = self.offset;
+        let child = &mut self.children[index];
+
+        let r = self.canvas.with_save(|cv| {
+            cv.transform(
+                Affine::translate((offset.x, offset.y))
+                    * transform
+                    * Affine::translate((-offset.x, -offset.y)),
+            );
+            child.paint(cv, &offset);
+
+            Ok(())
+        });
+
+        if let Err(e) = r {
+            log::error!("painting child of `Flow` failed: {}", e);
+        }
+
+        self.painted.push(index);
+    }
+}
+
+/// Lays out its children with constraints given by the `delegate`, which then
+/// positions them with transformations while they are painted.
+///
+/// Since moving children doesn't require building or laying them out again,
+/// this is suited for animating many children at once, e.g. buttons of a menu
+/// fanning out of a corner:
+///
+/// ```ignore
+/// struct FanOut(AnimationController);
+///
+/// impl FlowDelegate for FanOut {
+///     fn paint_children(&self, cx: &mut FlowPaintCx) {
+///         let radius = 120. * self.0.value();
+///
+///         for i in (0..cx.child_count()).rev() {
+///             let angle = FRAC_PI_2 * i as f64 / (cx.child_count() - 1) as f64;
+///             let (x, y) = (radius * angle.cos(), radius * angle.sin());
+///             cx.paint_child(i, Affine::translate((x, y)));
+///         }
+///     }
+///
+///     fn repaint(&self) -> Option<AnimationController> {
+///         Some(self.0.clone())
+///     }
+/// }
+///
+/// Flow::new(FanOut(controller.clone()), buttons)
+/// ```
+#[derive(RenderWidget)]
+pub struct Flow<D: FlowDelegate, WL: WidgetList> {
+    pub delegate: D,
+    pub children: WL,
+}
+
+impl<D: FlowDelegate, WL: WidgetList> Flow<D, WL> {
+    pub fn new(delegate: D, children: WL) -> Self {
+        Flow { delegate, children }
+    }
+}
+
+impl<D: FlowDelegate, WL: WidgetList> WidgetState for Flow<D, WL> {
+    type State = ControllerListener<AnimationController>;
+
+    fn create_state(&self) -> Self::State {
+        ControllerListener::default()
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().unlisten();
+    }
+}
+
+impl<D: FlowDelegate, WL: WidgetList> RenderState for Flow<D, WL> {
+    /// Indices of children painted in the last frame, in paint order.
+    type State = Vec<usize>;
+
+    fn create_state(&self) -> Self::State {
+        Vec::new()
+    }
+}
+
+impl<D: FlowDelegate, WL: WidgetList> RenderWidget for Flow<D, WL> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        match self.delegate.repaint() {
+            Some(animation) => cx.state().listen(&animation, Window::request_paint),
+            None => cx.state().unlisten(),
+        }
+
+        self.children.get()
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        for (index, child) in cx.children().enumerate() {
+            child.layout(self.delegate.child_constraints(index, constraints));
+        }
+
+        constraints.constrain(self.delegate.size(constraints))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let mut flow_cx = FlowPaintCx {
+            canvas,
+            offset: *offset,
+            size: cx.size(),
+            children: cx.children().collect(),
+            painted: Vec::new(),
+        };
+
+        self.delegate.paint_children(&mut flow_cx);
+
+        *cx.render_state_mut() = flow_cx.painted;
+    }
+}
+
+impl<D: FlowDelegate, WL: WidgetList> HitTest for Flow<D, WL> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        let painted = cx.render_state().clone();
+        let parent = (**cx).clone();
+
+        for index in painted.into_iter().rev() {
+            if let Some(mut child) = cx.child(index) {
+                if child.hit_test_with_paint_transform(point, &parent) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(all(test, not(feature = "miri")))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use frui::app::offscreen;
+
+    use super::*;
+
+    /// Records its index and where (in window coordinates) it was painted.
+    #[derive(RenderWidget)]
+    struct Probe {
+        index: usize,
+        painted: Arc<Mutex<Vec<(usize, Point)>>>,
+    }
+
+    impl RenderWidget for Probe {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![] as Vec<()>
+        }
+
+        fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+            constraints.constrain(Size::new(10., 10.))
+        }
+
+        fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+            let position = cx.window_transform(canvas) * Point::new(offset.x, offset.y);
+            self.painted.lock().unwrap().push((self.index, position));
+        }
+    }
+
+    /// Paints children listed in `order`, each moved right by 20 points more
+    /// than the previous one.
+    struct Spread(Vec<usize>);
+
+    impl FlowDelegate for Spread {
+        fn paint_children(&self, cx: &mut FlowPaintCx) {
+            for (n, index) in self.0.iter().enumerate() {
+                cx.paint_child(*index, Affine::translate((20. * n as f64, 0.)));
+            }
+        }
+    }
+
+    fn paint(order: Vec<usize>) -> std::thread::Result<Vec<(usize, Point)>> {
+        let painted = Arc::new(Mutex::new(Vec::new()));
+
+        let root = {
+            let painted = painted.clone();
+            let probe = move |index| Probe {
+                index,
+                painted: painted.clone(),
+            };
+
+            move || Flow::new(Spread(order), (probe(0), probe(1), probe(2)))
+        };
+
+        offscreen::render(Size::new(100., 100.), 1., root)
+            .join()?
+            .unwrap();
+
+        let painted = painted.lock().unwrap().clone();
+        Ok(painted)
+    }
+
+    #[test]
+    fn children_are_painted_in_delegate_order_with_transforms() {
+        let painted = paint(vec![2, 0]).unwrap();
+
+        // Child which wasn't painted by the delegate isn't visible.
+        assert_eq!(painted, [(2, Point::new(0., 0.)), (0, Point::new(20., 0.))]);
+    }
+
+    #[test]
+    fn painting_a_child_twice_panics() {
+        assert!(paint(vec![1, 1]).is_err());
+    }
+}
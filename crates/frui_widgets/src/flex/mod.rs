@@ -3,20 +3,21 @@ use std::ops::{Deref, DerefMut};
 use frui::prelude::*;
 use frui::render::*;
 
+pub use frui::render::BoxLayoutData;
+
 pub use alignment::*;
 pub use center::*;
 pub use flex::*;
+pub use flow::*;
 pub use stack::*;
+pub use wrap::*;
 
 pub mod alignment;
 pub mod center;
 pub mod flex;
+pub mod flow;
 pub mod stack;
-
-#[derive(Debug, Clone, Copy, Default)]
-pub struct BoxLayoutData {
-    pub offset: Offset,
-}
+pub mod wrap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MainAxisSize {
@@ -30,14 +31,23 @@ impl Default for MainAxisSize {
     }
 }
 
+/// How a [`Flex`] places its children on the main axis, if it's longer than
+/// them. That's only the case if its `main_axis_size` is
+/// [`Max`](MainAxisSize::Max) and none of its children is [`Flexible`]
+/// (which would take all the free space).
 #[derive(Debug, Clone, Copy)]
 pub enum MainAxisAlignment {
     Start,
     Center,
     End,
+    /// Free space is split evenly between children. A single child is placed
+    /// at the start.
     SpaceBetween,
-    /// Leading space will not work with `MainAxisAlignment::SpaceAround` and `MainAxisAlignment::SpaceEvenly`
+    /// Free space is split evenly between children, with half of that space
+    /// before the first child and after the last one.
     SpaceAround,
+    /// Free space is split evenly between children, before the first child
+    /// and after the last one.
     SpaceEvenly,
 }
 
@@ -170,3 +180,56 @@ impl Expanded {
         }
     }
 }
+
+/// Takes a share of the free space of a [`Flex`] proportional to its `flex`
+/// factor, e.g. to push children after it to the end of a [`Row`].
+///
+/// ```ignore
+/// Row::builder().children((Text::new("Title"), Spacer::new(), close_button))
+/// ```
+#[derive(RenderWidget)]
+pub struct Spacer {
+    pub flex: usize,
+}
+
+impl Spacer {
+    pub fn new() -> Self {
+        Spacer { flex: 1 }
+    }
+
+    pub fn flex(mut self, flex: usize) -> Self {
+        assert!(flex > 0, "flex factor of `Spacer` must be positive");
+        self.flex = flex;
+        self
+    }
+}
+
+impl Default for Spacer {
+    fn default() -> Self {
+        Spacer::new()
+    }
+}
+
+impl ParentData for Spacer {
+    type Data = FlexData;
+
+    fn create_data(&self) -> Self::Data {
+        FlexData {
+            flex_factor: self.flex,
+            fit: FlexFit::Tight,
+            box_data: BoxLayoutData::default(),
+        }
+    }
+}
+
+impl RenderWidget for Spacer {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.smallest()
+    }
+
+    fn paint(&self, _: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {}
+}
@@ -1,5 +1,5 @@
 use crate::alignment::{Alignment, AlignmentDirectional};
-use crate::{BoxLayoutData, Directional, TextDirection, WidgetList};
+use crate::{BoxLayoutData, Directional, Directionality, SpatialIndex, TextDirection, WidgetList};
 
 use frui::prelude::*;
 use frui::render::*;
@@ -16,7 +16,9 @@ pub struct Stack<WL: WidgetList, A: Directional<Output = Alignment>> {
     pub clip: bool,
     pub alignment: A,
     pub fit: StackFit,
-    pub text_direction: TextDirection,
+    /// Direction used to resolve `alignment`. If `None`, the value of the
+    /// closest [`Directionality`] ancestor is used.
+    pub text_direction: Option<TextDirection>,
 }
 
 /// RenderData which Stack's children should hold, if not the child widget
@@ -58,7 +60,7 @@ impl Stack<(), AlignmentDirectional> {
             children: (),
             fit: StackFit::Loose,
             alignment: AlignmentDirectional::TOP_START,
-            text_direction: TextDirection::Ltr,
+            text_direction: None,
         }
     }
 
@@ -138,13 +140,65 @@ impl<WL: WidgetList, A: Directional<Output = Alignment>> Stack<WL, A> {
     }
 }
 
+impl<WL: WidgetList, A: Directional<Output = Alignment>> Intrinsics for Stack<WL, A> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.intrinsic_extent(cx, |child| child.min_intrinsic_width(height))
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.intrinsic_extent(cx, |child| child.max_intrinsic_width(height))
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_extent(cx, |child| child.min_intrinsic_height(width))
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_extent(cx, |child| child.max_intrinsic_height(width))
+    }
+}
+
+impl<WL: WidgetList, A: Directional<Output = Alignment>> Stack<WL, A> {
+    /// Largest intrinsic extent of children sizing the stack. Positioned
+    /// children are sized by the stack instead.
+    fn intrinsic_extent(&self, cx: &LayoutCx<Self>, extent: impl Fn(&LayoutCxOS) -> f64) -> f64 {
+        cx.children()
+            .filter(|child| !Stack::is_positioned(child))
+            .map(|child| extent(&child))
+            .fold(0., f64::max)
+    }
+}
+
+/// Stacks with at least that many children keep a [`SpatialIndex`] of them,
+/// so that hit testing doesn't have to test every child.
+const INDEXED_CHILDREN: usize = 64;
+
+#[doc(hidden)]
+pub struct StackRenderState {
+    /// Alignment resolved during layout.
+    alignment: Alignment,
+    index: Option<SpatialIndex>,
+}
+
+impl<WL: WidgetList, A: Directional<Output = Alignment>> RenderState for Stack<WL, A> {
+    type State = StackRenderState;
+
+    fn create_state(&self) -> Self::State {
+        StackRenderState {
+            alignment: Alignment::default(),
+            index: None,
+        }
+    }
+}
+
 impl<WL: WidgetList, A: Directional<Output = Alignment>> RenderWidget for Stack<WL, A> {
     fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
         self.children.get()
     }
 
     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
-        let alignment = self.alignment.resolve(&self.text_direction);
+        let text_direction = Directionality::unwrap_or_default(self.text_direction, cx);
+        let alignment = self.alignment.resolve(&text_direction);
         let mut width = constraints.min_width;
         let mut height = constraints.min_height;
         let non_positioned_constraints = match self.fit {
@@ -194,27 +248,39 @@ impl<WL: WidgetList, A: Directional<Output = Alignment>> RenderWidget for Stack<
             }
         }
 
+        let index = match cx.children().len() >= INDEXED_CHILDREN {
+            true => Some(SpatialIndex::from_rects(
+                128.,
+                cx.children().map(|child| {
+                    let child_size = child.size();
+                    let offset = child
+                        .try_parent_data::<StackLayoutData>()
+                        .map_or_else(|| alignment.along(size - child_size), |d| d.base.offset);
+
+                    Rect::from_ltwh(offset.x, offset.y, child_size.width, child_size.height)
+                }),
+            )),
+            false => None,
+        };
+
+        *cx.render_state_mut() = StackRenderState { alignment, index };
+
         size
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
         let size = cx.size();
-        let alignment = self.alignment.resolve(&self.text_direction);
+        let alignment = cx.render_state().alignment;
 
         if self.clip {
-            let r = canvas.with_save(|cv| {
-                cv.clip(Into::<druid_shell::piet::kurbo::Rect>::into(
-                    Rect::from_origin_size(*offset, size),
-                ));
+            let rect = Rect::from_origin_size(*offset, size);
 
+            cx.with_clip(canvas, rect, |cx, canvas| {
                 for mut child in cx.children() {
                     let offset = *offset + self.get_layout_offset(&child, &alignment, size);
-                    child.paint(cv, &offset);
+                    child.paint(canvas, &offset);
                 }
-
-                Ok(())
             });
-            r.unwrap();
         } else {
             for mut child in cx.children() {
                 let offset = *offset + self.get_layout_offset(&child, &alignment, size);
@@ -227,6 +293,20 @@ impl<WL: WidgetList, A: Directional<Output = Alignment>> RenderWidget for Stack<
 impl<WL: WidgetList, A: Directional<Output = Alignment>> HitTest for Stack<WL, A> {
     fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
         if cx.layout_box().contains(point) {
+            let candidates = cx.render_state().index.as_ref().map(|i| i.query(point));
+
+            if let Some(candidates) = candidates {
+                for n in candidates.into_iter().rev() {
+                    if let Some(mut child) = cx.child(n) {
+                        if child.hit_test_with_paint_offset(point) {
+                            return true;
+                        }
+                    }
+                }
+
+                return true;
+            }
+
             for mut child in cx.children().rev() {
                 if child.hit_test_with_paint_offset(point) {
                     // If widget on top handled an event, it won't be passed to
@@ -0,0 +1,380 @@
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::*;
+
+use super::flex::{AxisExt, Intrinsic};
+
+/// How [`Wrap`] distributes free space between children of a run, or between
+/// its runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapAlignment {
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+}
+
+impl Default for WrapAlignment {
+    fn default() -> Self {
+        WrapAlignment::Start
+    }
+}
+
+impl WrapAlignment {
+    /// Space before the first of `count` items and between every two of
+    /// them.
+    fn spaces(self, free_space: f64, count: usize) -> (f64, f64) {
+        let free_space = free_space.max(0.);
+        let count = count as f64;
+
+        match self {
+            WrapAlignment::Start => (0., 0.),
+            WrapAlignment::End => (free_space, 0.),
+            WrapAlignment::Center => (free_space / 2., 0.),
+            WrapAlignment::SpaceBetween if count > 1. => (0., free_space / (count - 1.)),
+            WrapAlignment::SpaceBetween => (0., 0.),
+            WrapAlignment::SpaceAround => (free_space / count / 2., free_space / count),
+            WrapAlignment::SpaceEvenly => {
+                let space = free_space / (count + 1.);
+                (space, space)
+            }
+        }
+    }
+}
+
+/// How children of a run of [`Wrap`] are aligned on the cross axis, relative
+/// to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapCrossAlignment {
+    Start,
+    End,
+    Center,
+}
+
+impl Default for WrapCrossAlignment {
+    fn default() -> Self {
+        WrapCrossAlignment::Start
+    }
+}
+
+/// Lays out children one after another along the `direction`, like a [`Row`]
+/// or a [`Column`], but starts a new run of children whenever the next child
+/// doesn't fit into the current one.
+///
+/// ```ignore
+/// Wrap::builder()
+///     .spacing(8.)
+///     .run_spacing(4.)
+///     .children(tags.iter().map(|tag| Chip::new(tag)))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct Wrap<WL: WidgetList> {
+    pub children: WL,
+
+    /// Whether runs are horizontal (and are placed below each other) or
+    /// vertical (and are placed next to each other).
+    pub direction: Axis,
+
+    /// How children are placed within a run on the main axis.
+    pub alignment: WrapAlignment,
+
+    /// Space between children of a run.
+    pub spacing: f64,
+
+    /// How runs are placed on the cross axis.
+    pub run_alignment: WrapAlignment,
+
+    /// Space between runs.
+    pub run_spacing: f64,
+
+    pub cross_axis_alignment: WrapCrossAlignment,
+
+    /// If `direction` is [`Horizontal`](Axis::Horizontal), whether children
+    /// of a run start on the left or on the right. Otherwise, whether runs
+    /// start on the left or on the right.
+    ///
+    /// If `None`, the value of the closest [`Directionality`] ancestor is
+    /// used.
+    pub text_direction: Option<TextDirection>,
+
+    /// If `direction` is [`Vertical`](Axis::Vertical), whether children of a
+    /// run start at the top or at the bottom. Otherwise, whether runs start at
+    /// the top or at the bottom.
+    pub vertical_direction: VerticalDirection,
+}
+
+impl Wrap<()> {
+    pub fn builder() -> Self {
+        Wrap {
+            children: (),
+            direction: Axis::Horizontal,
+            alignment: WrapAlignment::Start,
+            spacing: 0.,
+            run_alignment: WrapAlignment::Start,
+            run_spacing: 0.,
+            cross_axis_alignment: WrapCrossAlignment::Start,
+            text_direction: None,
+            vertical_direction: VerticalDirection::Down,
+        }
+    }
+}
+
+/// Children placed next to each other on the main axis.
+#[derive(Debug, PartialEq)]
+struct Run {
+    main_extent: f64,
+    cross_extent: f64,
+    count: usize,
+}
+
+impl Run {
+    /// Splits children with the given main and cross extents into runs no
+    /// longer than `main_limit`, unless a single child is longer.
+    fn split(extents: impl Iterator<Item = (f64, f64)>, spacing: f64, main_limit: f64) -> Vec<Run> {
+        let mut runs: Vec<Run> = Vec::new();
+
+        for (main, cross) in extents {
+            match runs.last_mut() {
+                Some(run) if run.main_extent + spacing + main <= main_limit => {
+                    run.main_extent += spacing + main;
+                    run.cross_extent = run.cross_extent.max(cross);
+                    run.count += 1;
+                }
+                _ => runs.push(Run {
+                    main_extent: main,
+                    cross_extent: cross,
+                    count: 1,
+                }),
+            }
+        }
+
+        runs
+    }
+
+    /// Cross extent of all `runs` placed next to each other.
+    fn cross_extent(runs: &[Run], run_spacing: f64) -> f64 {
+        runs.iter().map(|r| r.cross_extent).sum::<f64>()
+            + run_spacing * runs.len().saturating_sub(1) as f64
+    }
+}
+
+impl<WL: WidgetList> RenderWidget for Wrap<WL> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let text_direction = Directionality::unwrap_or_default(self.text_direction, cx);
+
+        let (main_limit, child_constraints) = match self.direction {
+            Axis::Horizontal => (
+                constraints.max_width,
+                Constraints::new(0., constraints.max_width, 0., f64::INFINITY),
+            ),
+            Axis::Vertical => (
+                constraints.max_height,
+                Constraints::new(0., f64::INFINITY, 0., constraints.max_height),
+            ),
+        };
+
+        //
+        // Lay out children and split them into runs.
+
+        let extents = cx.children().map(|child| {
+            let child_size = child.layout(child_constraints);
+            (
+                child_size.main(self.direction),
+                child_size.cross(self.direction),
+            )
+        });
+
+        let runs = Run::split(extents, self.spacing, main_limit);
+
+        let main_extent = runs.iter().map(|r| r.main_extent).fold(0., f64::max);
+        let cross_extent = Run::cross_extent(&runs, self.run_spacing);
+
+        let size = constraints.constrain(match self.direction {
+            Axis::Horizontal => Size::new(main_extent, cross_extent),
+            Axis::Vertical => Size::new(cross_extent, main_extent),
+        });
+
+        let (main_size, cross_size) = (size.main(self.direction), size.cross(self.direction));
+
+        //
+        // Position children.
+
+        let (flip_main, flip_cross) = match self.direction {
+            Axis::Horizontal => (
+                text_direction == TextDirection::Rtl,
+                self.vertical_direction == VerticalDirection::Up,
+            ),
+            Axis::Vertical => (
+                self.vertical_direction == VerticalDirection::Up,
+                text_direction == TextDirection::Rtl,
+            ),
+        };
+
+        let (run_leading, run_between) = self
+            .run_alignment
+            .spaces(cross_size - cross_extent, runs.len());
+        let run_between = run_between + self.run_spacing;
+
+        let mut cross_offset = match flip_cross {
+            true => cross_size - run_leading,
+            false => run_leading,
+        };
+
+        let mut children = cx.children();
+
+        for run in runs {
+            let (leading, between) = self
+                .alignment
+                .spaces(main_size - run.main_extent, run.count);
+            let between = between + self.spacing;
+
+            let mut main_offset = match flip_main {
+                true => main_size - leading,
+                false => leading,
+            };
+
+            if flip_cross {
+                cross_offset -= run.cross_extent;
+            }
+
+            for child in children.by_ref().take(run.count) {
+                let child_size = child.size();
+                let child_main = child_size.main(self.direction);
+
+                if flip_main {
+                    main_offset -= child_main;
+                }
+
+                let free_cross = run.cross_extent - child_size.cross(self.direction);
+                let child_cross = match (self.cross_axis_alignment, flip_cross) {
+                    (WrapCrossAlignment::Start, false) | (WrapCrossAlignment::End, true) => 0.,
+                    (WrapCrossAlignment::Start, true) | (WrapCrossAlignment::End, false) => {
+                        free_cross
+                    }
+                    (WrapCrossAlignment::Center, _) => free_cross / 2.,
+                };
+
+                let mut child_offset = Offset::default();
+                *child_offset.main_mut(self.direction) = main_offset;
+                *child_offset.cross_mut(self.direction) = cross_offset + child_cross;
+                child.set_offset(child_offset);
+
+                match flip_main {
+                    true => main_offset -= between,
+                    false => main_offset += child_main + between,
+                }
+            }
+
+            match flip_cross {
+                true => cross_offset -= run_between,
+                false => cross_offset += run.cross_extent + run_between,
+            }
+        }
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.paint_children(canvas, offset)
+    }
+}
+
+impl<WL: WidgetList> Intrinsics for Wrap<WL> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Horizontal, height, Intrinsic::Min)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Horizontal, height, Intrinsic::Max)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Vertical, width, Intrinsic::Min)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_extent(cx, Axis::Vertical, width, Intrinsic::Max)
+    }
+}
+
+impl<WL: WidgetList> Wrap<WL> {
+    /// Intrinsic extent of this widget along `axis`, given `extent` along the
+    /// other axis.
+    fn intrinsic_extent(
+        &self,
+        cx: &LayoutCx<Self>,
+        axis: Axis,
+        extent: f64,
+        intrinsic: Intrinsic,
+    ) -> f64 {
+        if axis == self.direction {
+            // At its narrowest, every child is in its own run. At its widest,
+            // all of them are in one.
+            let extents = cx.children().map(|c| intrinsic.of(&c, axis, f64::INFINITY));
+
+            match intrinsic {
+                Intrinsic::Min => extents.fold(0., f64::max),
+                Intrinsic::Max => {
+                    let count = cx.children().len();
+                    extents.sum::<f64>() + self.spacing * count.saturating_sub(1) as f64
+                }
+            }
+        } else {
+            // Runs are split the same way they are during layout.
+            let extents = cx.children().map(|child| {
+                let main = Intrinsic::Max
+                    .of(&child, self.direction, f64::INFINITY)
+                    .min(extent);
+                (main, Intrinsic::Max.of(&child, axis, main))
+            });
+
+            let runs = Run::split(extents, self.spacing, extent);
+            Run::cross_extent(&runs, self.run_spacing)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distributes_free_space() {
+        assert_eq!(WrapAlignment::Start.spaces(30., 3), (0., 0.));
+        assert_eq!(WrapAlignment::End.spaces(30., 3), (30., 0.));
+        assert_eq!(WrapAlignment::Center.spaces(30., 3), (15., 0.));
+        assert_eq!(WrapAlignment::SpaceBetween.spaces(30., 3), (0., 15.));
+        assert_eq!(WrapAlignment::SpaceBetween.spaces(30., 1), (0., 0.));
+        assert_eq!(WrapAlignment::SpaceAround.spaces(30., 3), (5., 10.));
+        assert_eq!(WrapAlignment::SpaceEvenly.spaces(30., 2), (10., 10.));
+
+        // Overflowing runs aren't shifted.
+        assert_eq!(WrapAlignment::End.spaces(-10., 3), (0., 0.));
+    }
+
+    #[test]
+    fn splits_children_into_runs() {
+        let extents = [(40., 10.), (40., 20.), (40., 5.), (120., 15.)];
+        let runs = Run::split(extents.into_iter(), 10., 100.);
+
+        let run = |main_extent, cross_extent, count| Run {
+            main_extent,
+            cross_extent,
+            count,
+        };
+
+        // A child longer than the limit gets a run of its own.
+        assert_eq!(runs, [run(90., 20., 2), run(40., 5., 1), run(120., 15., 1)]);
+        assert_eq!(Run::cross_extent(&runs, 4.), 48.);
+
+        assert_eq!(Run::split(extents.into_iter(), 10., f64::INFINITY).len(), 1);
+        assert_eq!(Run::cross_extent(&[], 4.), 0.);
+    }
+}
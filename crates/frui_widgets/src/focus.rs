@@ -0,0 +1,235 @@
+use std::cell::Cell;
+
+use druid_shell::KeyEvent;
+use frui::{
+    app::focus::{FocusListenerKey, FocusNode},
+    prelude::*,
+    render::*,
+};
+
+use crate::*;
+
+impl Listenable for FocusNode {
+    type Key = FocusListenerKey;
+
+    fn listen(&self, callback: impl Fn() + 'static) -> Self::Key {
+        FocusNode::listen(self, callback)
+    }
+
+    fn unlisten(&self, key: Self::Key) {
+        FocusNode::unlisten(self, key)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        FocusNode::ptr_eq(self, other)
+    }
+}
+
+/// Makes `child` focusable, receiving key events while it (or one of its
+/// descendants) is focused. See [`frui::app::focus`].
+///
+/// ```ignore
+/// Focus::builder()
+///     .on_key(|event: &KeyEvent| {
+///         if event.key == Key::Enter {
+///             activate();
+///             return true;
+///         }
+///         false
+///     })
+///     .child(button)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Focus<W: Widget, K: Fn(&KeyEvent) -> bool, C: Fn(bool)> {
+    /// Node of this widget, which can be passed to request focus for it from
+    /// elsewhere. If `None`, the widget has its own node.
+    pub focus_node: Option<FocusNode>,
+    /// Whether the widget is focused once it's inserted into the tree.
+    pub autofocus: bool,
+    pub can_request_focus: bool,
+    /// Whether the widget is skipped when moving focus with Tab.
+    pub skip_traversal: bool,
+    /// Called with key events (presses and releases) received while focused,
+    /// returning whether the event was handled. Unhandled events are passed
+    /// to focused ancestors.
+    pub on_key: K,
+    /// Called with whether the widget (or one of its descendants) is focused,
+    /// every time it changes.
+    pub on_focus_change: C,
+    pub child: W,
+}
+
+impl Focus<(), fn(&KeyEvent) -> bool, fn(bool)> {
+    pub fn builder() -> Self {
+        Focus {
+            focus_node: None,
+            autofocus: false,
+            can_request_focus: true,
+            skip_traversal: false,
+            on_key: |_| false,
+            on_focus_change: |_| {},
+            child: (),
+        }
+    }
+}
+
+impl<W: Widget, K: Fn(&KeyEvent) -> bool, C: Fn(bool)> Focus<W, K, C> {
+    fn node(&self, state: &FocusState) -> FocusNode {
+        match &self.focus_node {
+            Some(node) => node.clone(),
+            None => state.fallback.clone(),
+        }
+    }
+}
+
+pub struct FocusState {
+    /// Node used if none was passed.
+    fallback: FocusNode,
+    listener: ControllerListener<FocusNode>,
+    /// Node the key handler was set for.
+    registered: Option<FocusNode>,
+    had_focus: Cell<bool>,
+    autofocused: Cell<bool>,
+}
+
+impl<W: Widget, K: Fn(&KeyEvent) -> bool, C: Fn(bool)> WidgetState for Focus<W, K, C> {
+    type State = FocusState;
+
+    fn create_state(&self) -> Self::State {
+        FocusState {
+            fallback: FocusNode::new(),
+            listener: ControllerListener::default(),
+            registered: None,
+            had_focus: Cell::new(false),
+            autofocused: Cell::new(false),
+        }
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        let mut state = cx.state_mut();
+        let node = self.node(&state);
+
+        // Safety: handler is removed once this widget is unmounted, which
+        // happens before it's dropped or its configuration changes.
+        unsafe { node.set_key_handler(&self.on_key) };
+        state.registered = Some(node);
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        let mut state = cx.state_mut();
+
+        if let Some(node) = state.registered.take() {
+            node.remove_key_handler();
+        }
+
+        state.listener.unlisten();
+    }
+}
+
+impl<W: Widget, K: Fn(&KeyEvent) -> bool, C: Fn(bool)> ViewWidget for Focus<W, K, C> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let node = {
+            let state = cx.state();
+            let node = self.node(&state);
+
+            let autofocus = self.autofocus && !state.autofocused.replace(true);
+            let disabled = !self.can_request_focus && node.is_focused();
+
+            // Focus changed while building mustn't rebuild this widget.
+            if autofocus || disabled {
+                state.listener.unlisten();
+            }
+
+            node.set_can_request_focus(self.can_request_focus);
+            node.set_skip_traversal(self.skip_traversal);
+
+            if autofocus {
+                node.request_focus();
+            }
+
+            let updater = cx.state_updater();
+            state.listener.listen(&node, move || updater.update(|_| {}));
+
+            node
+        };
+
+        let has_focus = node.has_focus();
+        if cx.state().had_focus.replace(has_focus) != has_focus {
+            (self.on_focus_change)(has_focus);
+        }
+
+        FocusAttach {
+            node,
+            child: &self.child,
+        }
+    }
+}
+
+/// Scope of focus traversal, which keeps Tab and Shift+Tab within focusable
+/// descendants of `child` while one of them is focused. Requesting focus of
+/// its node focuses the descendant which was focused last.
+#[derive(ViewWidget, Builder)]
+pub struct FocusScope<W: Widget> {
+    /// Node of the scope, created with [`FocusNode::scope`]. If `None`, the
+    /// scope has its own node.
+    pub focus_node: Option<FocusNode>,
+    pub child: W,
+}
+
+impl FocusScope<()> {
+    pub fn builder() -> Self {
+        FocusScope {
+            focus_node: None,
+            child: (),
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for FocusScope<W> {
+    /// Node used if none was passed.
+    type State = FocusNode;
+
+    fn create_state(&self) -> Self::State {
+        FocusNode::scope()
+    }
+}
+
+impl<W: Widget> ViewWidget for FocusScope<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        debug_assert!(
+            self.focus_node.as_ref().map_or(true, |n| n.is_scope()),
+            "FocusScope requires a node created with FocusNode::scope"
+        );
+
+        let node = match &self.focus_node {
+            Some(node) => node.clone(),
+            None => cx.state().clone(),
+        };
+
+        FocusAttach {
+            node,
+            child: &self.child,
+        }
+    }
+}
+
+/// Attaches `node` to the focus tree every time it's laid out.
+#[derive(RenderWidget)]
+pub(crate) struct FocusAttach<W: Widget> {
+    pub node: FocusNode,
+    pub child: W,
+}
+
+impl<W: Widget> RenderWidget for FocusAttach<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        self.node.attach(|| cx.child(0).layout(constraints))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
@@ -0,0 +1,242 @@
+//! Positioning widgets relative to other widgets painted elsewhere in the tree,
+//! e.g. dropdowns or tooltips painted in an overlay above their anchor.
+
+use std::{cell::Cell, rc::Rc};
+
+use frui::{prelude::*, render::*};
+
+use crate::*;
+
+/// Connects a [`FollowerTarget`] with any number of [`Follower`]s.
+#[derive(Debug, Clone, Default)]
+pub struct LayerLink {
+    /// Bounds of the target in window coordinates, from the last paint.
+    target: Rc<Cell<Option<Rect>>>,
+}
+
+impl LayerLink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bounds of the target in window coordinates, or `None` if the target
+    /// wasn't painted yet.
+    pub fn target_rect(&self) -> Option<Rect> {
+        self.target.get()
+    }
+}
+
+impl PartialEq for LayerLink {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.target, &other.target)
+    }
+}
+
+/// Records position of `child`, so that [`Follower`]s using the same `link`
+/// can be positioned relative to it.
+#[derive(RenderWidget, Builder)]
+pub struct FollowerTarget<W: Widget> {
+    pub link: LayerLink,
+    pub child: W,
+}
+
+impl FollowerTarget<()> {
+    pub fn builder() -> Self {
+        FollowerTarget {
+            link: LayerLink::new(),
+            child: (),
+        }
+    }
+}
+
+impl<W: Widget> RenderWidget for FollowerTarget<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let origin = canvas.current_transform() * Point::from(*offset);
+        let rect = Rect::from_origin_size(origin, cx.size());
+        self.link.target.set(Some(rect));
+
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+/// Paints `child` next to the [`FollowerTarget`] of the same `link`, following
+/// it as it moves or scrolls.
+///
+/// Follower takes all the space it is given and keeps `child` inside of it,
+/// so it should be placed in a layer covering the window, e.g. a [`Stack`]
+/// above the rest of the application. The target has to be painted before the
+/// follower.
+///
+/// `target_anchor` point of the target is aligned with `follower_anchor` point
+/// of `child` (moved by `offset`). By default `child` is placed below the
+/// target, aligned to its left edge.
+#[derive(RenderWidget, Builder)]
+pub struct Follower<W: Widget> {
+    pub link: LayerLink,
+    pub child: W,
+    pub target_anchor: Alignment,
+    pub follower_anchor: Alignment,
+    pub offset: Offset,
+    /// Whether `child` is flipped to the other side of the target if it
+    /// doesn't fit on the preferred side.
+    pub flip: bool,
+    /// Whether `child` is moved to stay within the bounds of this widget.
+    pub keep_in_bounds: bool,
+}
+
+impl Follower<()> {
+    pub fn builder() -> Self {
+        Follower {
+            link: LayerLink::new(),
+            child: (),
+            target_anchor: Alignment::BOTTOM_LEFT,
+            follower_anchor: Alignment::TOP_LEFT,
+            offset: Offset::default(),
+            flip: true,
+            keep_in_bounds: true,
+        }
+    }
+}
+
+impl<W: Widget> Follower<W> {
+    /// Position of the follower in the same coordinates as `target` and
+    /// `bounds`.
+    fn position(&self, target: Rect, size: Size, bounds: Rect) -> Offset {
+        let place = |target_anchor: Alignment, follower_anchor: Alignment, offset: Offset| {
+            target.top_left() + target_anchor.along(target.size()) - follower_anchor.along(size)
+                + offset
+        };
+
+        let mut position = place(self.target_anchor, self.follower_anchor, self.offset);
+
+        if self.flip {
+            let overflows_y = position.y < bounds.top || position.y + size.height > bounds.bottom;
+            let overflows_x = position.x < bounds.left || position.x + size.width > bounds.right;
+
+            if overflows_y {
+                let flipped = place(
+                    self.target_anchor.flip_vertically(),
+                    self.follower_anchor.flip_vertically(),
+                    Offset::new(self.offset.x, -self.offset.y),
+                );
+
+                if flipped.y >= bounds.top && flipped.y + size.height <= bounds.bottom {
+                    position.y = flipped.y;
+                }
+            }
+
+            if overflows_x {
+                let flipped = place(
+                    self.target_anchor.flip_horizontally(),
+                    self.follower_anchor.flip_horizontally(),
+                    Offset::new(-self.offset.x, self.offset.y),
+                );
+
+                if flipped.x >= bounds.left && flipped.x + size.width <= bounds.right {
+                    position.x = flipped.x;
+                }
+            }
+        }
+
+        if self.keep_in_bounds {
+            // Prefer showing the top left corner if child doesn't fit at all.
+            position.x = position.x.min(bounds.right - size.width).max(bounds.left);
+            position.y = position.y.min(bounds.bottom - size.height).max(bounds.top);
+        }
+
+        position
+    }
+}
+
+impl<W: Widget> RenderWidget for Follower<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let child_size = cx.child(0).layout(constraints.loosen());
+
+        match constraints.biggest() {
+            size if size.width.is_finite() && size.height.is_finite() => size,
+            _ => constraints.constrain(child_size),
+        }
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let target = match self.link.target_rect() {
+            Some(target) => target,
+            None => return,
+        };
+
+        let origin = canvas.current_transform() * Point::from(*offset);
+        let bounds = Rect::from_origin_size(origin, cx.size());
+
+        let mut child = cx.child(0);
+        let position = self.position(target, child.size(), bounds);
+        let local = Offset::new(position.x - origin.x, position.y - origin.y);
+
+        child.paint(canvas, &(*offset + local));
+    }
+}
+
+impl<W: Widget> HitTest for Follower<W> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        // Only the child is hit, since the follower itself usually covers the
+        // whole window.
+        for mut child in cx.children() {
+            if child.hit_test_with_paint_offset(point) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn follower_position() {
+        let follower = Follower::builder();
+        let bounds = Rect::from_ltwh(0., 0., 400., 300.);
+        let size = Size::new(100., 50.);
+
+        // Below the target.
+        let target = Rect::from_ltwh(20., 20., 80., 20.);
+        assert_eq!(
+            follower.position(target, size, bounds),
+            Offset::new(20., 40.)
+        );
+
+        // Flipped above the target near the bottom edge.
+        let target = Rect::from_ltwh(20., 260., 80., 20.);
+        assert_eq!(
+            follower.position(target, size, bounds),
+            Offset::new(20., 210.)
+        );
+
+        // Aligned to the right edge of the target near the right edge.
+        let target = Rect::from_ltwh(350., 20., 40., 20.);
+        assert_eq!(
+            follower.position(target, size, bounds),
+            Offset::new(290., 40.)
+        );
+
+        // Moved inside of the bounds if flipping doesn't help.
+        let target = Rect::from_ltwh(380., 20., 100., 20.);
+        assert_eq!(
+            follower.position(target, size, bounds),
+            Offset::new(300., 40.)
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Resolution of conflicts between gesture recognizers of nested widgets.
+//!
+//! Every recognizer interested in a pointer joins the arena when the pointer
+//! is pressed. Recognizers join in the order in which pointer events are
+//! dispatched, that is from the deepest widget to the root. The arena is then
+//! resolved in one of two ways:
+//!
+//! - A member [accepts](GestureArena::accept) the gesture (e.g. a long-press
+//!   timer fires, or a drag exceeds its slop) and wins, if nobody won before.
+//! - The pointer is released and the first (deepest) member which is still
+//!   competing for taps wins the tap, see [`GestureArena::wins_tap`].
+//!
+//! Either way the winner is kept until every member left the arena, and all
+//! other members are rejected, so that exactly one of them recognizes the
+//! gesture.
+
+use std::cell::{Cell, RefCell};
+
+/// Identifier of a recognizer in the [`GestureArena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaMember(u64);
+
+impl ArenaMember {
+    pub fn new() -> Self {
+        NEXT_MEMBER.with(|next| {
+            let id = next.get();
+            next.set(id + 1);
+            ArenaMember(id)
+        })
+    }
+}
+
+impl Default for ArenaMember {
+    fn default() -> Self {
+        ArenaMember::new()
+    }
+}
+
+pub struct GestureArena;
+
+impl GestureArena {
+    /// Joins the arena of the currently pressed pointer. Arena is reset when
+    /// the first member joins after all members of the previous gesture left.
+    pub fn add(member: ArenaMember, wants_tap: bool) {
+        ARENA.with(|a| {
+            let mut arena = a.borrow_mut();
+
+            // Member which is already in the arena missed the release of the
+            // previous pointer (e.g. it was removed from the tree), in which
+            // case the previous gesture is abandoned.
+            if arena.members.is_empty() || arena.members.iter().any(|m| m.id == member) {
+                arena.members.clear();
+                arena.winner = None;
+            }
+
+            arena.members.push(Member {
+                id: member,
+                wants_tap,
+                rejected: false,
+            });
+        })
+    }
+
+    /// Claims the gesture. Returns `true` if `member` won, which is the case
+    /// if nobody won before.
+    pub fn accept(member: ArenaMember) -> bool {
+        ARENA.with(|a| {
+            let mut arena = a.borrow_mut();
+
+            if !arena.members.iter().any(|m| m.id == member && !m.rejected) {
+                return false;
+            }
+
+            arena.resolve(member);
+            arena.winner == Some(member)
+        })
+    }
+
+    /// Stops competing for the gesture, e.g. after the pointer moved too far
+    /// for a tap.
+    pub fn reject(member: ArenaMember) {
+        ARENA.with(|a| {
+            if let Some(m) = a.borrow_mut().members.iter_mut().find(|m| m.id == member) {
+                m.rejected = true;
+            }
+        })
+    }
+
+    /// Whether `member` won the gesture. If nobody won yet, the deepest member
+    /// still competing for taps wins it.
+    pub fn wins_tap(member: ArenaMember) -> bool {
+        ARENA.with(|a| {
+            let mut arena = a.borrow_mut();
+
+            if arena.winner.is_none() {
+                let first = arena.members.iter().find(|m| m.wants_tap && !m.rejected);
+
+                if let Some(first) = first.map(|m| m.id) {
+                    arena.resolve(first);
+                }
+            }
+
+            arena.winner == Some(member)
+        })
+    }
+
+    /// Leaves the arena once the pointer was released.
+    pub fn leave(member: ArenaMember) {
+        ARENA.with(|a| a.borrow_mut().members.retain(|m| m.id != member))
+    }
+}
+
+struct Member {
+    id: ArenaMember,
+    wants_tap: bool,
+    rejected: bool,
+}
+
+#[derive(Default)]
+struct Arena {
+    /// Members in the order they joined, deepest first.
+    members: Vec<Member>,
+    /// Member which won the gesture of the current pointer. It's kept after
+    /// it leaves, so that members leaving after it can't win too.
+    winner: Option<ArenaMember>,
+}
+
+impl Arena {
+    /// Declares `member` the winner (unless somebody won before) and rejects
+    /// all other members.
+    fn resolve(&mut self, member: ArenaMember) {
+        let winner = *self.winner.get_or_insert(member);
+
+        for m in self.members.iter_mut() {
+            m.rejected = m.id != winner;
+        }
+    }
+}
+
+thread_local! {
+    static ARENA: RefCell<Arena> = Default::default();
+    static NEXT_MEMBER: Cell<u64> = Cell::new(0);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_gesture_arena() {
+        let (inner, outer) = (ArenaMember::new(), ArenaMember::new());
+
+        // Deepest member wins the tap.
+        GestureArena::add(inner, true);
+        GestureArena::add(outer, true);
+        assert!(GestureArena::wins_tap(inner));
+        assert!(!GestureArena::wins_tap(outer));
+        GestureArena::leave(inner);
+        GestureArena::leave(outer);
+
+        // Members not interested in taps are skipped.
+        GestureArena::add(inner, false);
+        GestureArena::add(outer, true);
+        assert!(GestureArena::wins_tap(outer));
+        GestureArena::leave(inner);
+        GestureArena::leave(outer);
+
+        // Accepting member wins regardless of depth.
+        GestureArena::add(inner, true);
+        GestureArena::add(outer, true);
+        assert!(GestureArena::accept(outer));
+        assert!(!GestureArena::accept(inner));
+        assert!(!GestureArena::wins_tap(inner));
+        assert!(GestureArena::wins_tap(outer));
+        GestureArena::leave(inner);
+        GestureArena::leave(outer);
+
+        // Once the deepest member won the tap and left, members leaving after
+        // it don't win it too.
+        GestureArena::add(inner, true);
+        GestureArena::add(outer, true);
+        assert!(GestureArena::wins_tap(inner));
+        GestureArena::leave(inner);
+        assert!(!GestureArena::wins_tap(outer));
+        assert!(!GestureArena::accept(outer));
+        GestureArena::leave(outer);
+
+        // Arena is reset for the next gesture.
+        GestureArena::add(inner, true);
+        assert!(GestureArena::wins_tap(inner));
+        GestureArena::leave(inner);
+    }
+}
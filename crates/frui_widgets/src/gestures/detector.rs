@@ -0,0 +1,446 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::Instant,
+};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    KeyEvent, MouseButton,
+};
+use frui::{
+    app::interactions::{Interaction, Interactions},
+    prelude::*,
+    render::*,
+};
+
+use super::*;
+use crate::{Focus, InteractionLabel};
+
+/// Recognizes taps, double taps, long presses and drags of its child.
+///
+/// Nested detectors compete in the [`GestureArena`]: a tap is recognized by
+/// the deepest detector with a tap handler, while a long press or a drag is
+/// recognized by the first detector whose long-press timeout elapses or whose
+/// pan slop is exceeded.
+///
+/// If `on_double_tap` is set, `on_tap` is delayed by [`DOUBLE_TAP_TIMEOUT`],
+/// since until then it isn't known whether the tap is the first half of a
+/// double tap.
+///
+/// A detector with `on_tap` and a label given by an [`InteractionLabel`]
+/// can also be focused with Tab and activated with Enter or Space, so that
+/// custom controls aren't mouse-only:
+///
+/// ```ignore
+/// GestureDetector::builder()
+///     .on_tap(|| open_profile())
+///     .child(avatar)
+///     .interaction_label("Profile")
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct GestureDetector<W, T, DT, LP, TD, TU, PS, PU, PE>
+where
+    W: Widget,
+    T: Fn(),
+    DT: Fn(),
+    LP: Fn(),
+    TD: Fn(Point),
+    TU: Fn(Point),
+    PS: Fn(DragStartDetails),
+    PU: Fn(DragUpdateDetails),
+    PE: Fn(DragEndDetails),
+{
+    pub child: W,
+    pub on_tap: Option<T>,
+    pub on_double_tap: Option<DT>,
+    pub on_long_press: Option<LP>,
+    /// Called with the local position of the pointer once it is pressed,
+    /// before it is known which gesture it starts.
+    pub on_tap_down: Option<TD>,
+    /// Called with the local position of the pointer once it is released, if
+    /// this detector recognized a tap.
+    pub on_tap_up: Option<TU>,
+    /// Called once the pointer moved by [`PAN_SLOP`] while pressed, if this
+    /// detector won the gesture.
+    pub on_pan_start: Option<PS>,
+    pub on_pan_update: Option<PU>,
+    pub on_pan_end: Option<PE>,
+    /// Whether the detector is focusable and activated by the keyboard, if
+    /// it has `on_tap` and a label. Controls which handle the keyboard
+    /// themselves turn it off.
+    pub keyboard_activation: bool,
+}
+
+impl
+    GestureDetector<
+        (),
+        fn(),
+        fn(),
+        fn(),
+        fn(Point),
+        fn(Point),
+        fn(DragStartDetails),
+        fn(DragUpdateDetails),
+        fn(DragEndDetails),
+    >
+{
+    pub fn builder() -> Self {
+        GestureDetector {
+            child: (),
+            on_tap: None,
+            on_double_tap: None,
+            on_long_press: None,
+            on_tap_down: None,
+            on_tap_up: None,
+            on_pan_start: None,
+            on_pan_update: None,
+            on_pan_end: None,
+            keyboard_activation: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Gesture {
+    Tap,
+    LongPress,
+}
+
+pub struct GestureDetectorState {
+    member: ArenaMember,
+    /// Position at which the pointer was pressed.
+    down: Cell<Option<Point>>,
+    long_press_timer: Cell<Option<Timer>>,
+    /// Whether a long press was recognized since the pointer was pressed.
+    long_pressed: Cell<bool>,
+    /// Tap waiting for a possible second tap.
+    pending_tap: Cell<Option<Timer>>,
+    /// Gesture recognized by a timer, to be reported once this widget is
+    /// rebuilt (callbacks can't be called from timers, which outlive them).
+    recognized: Cell<Option<Gesture>>,
+    /// Drags compete in the arena separately from taps and long presses.
+    drag_member: ArenaMember,
+    drag: RefCell<Drag>,
+}
+
+#[derive(Default)]
+struct Drag {
+    /// Position at which the pointer was pressed, if it can still start a
+    /// drag.
+    start: Option<Point>,
+    last: Point,
+    dragging: bool,
+    tracker: VelocityTracker,
+}
+
+impl<W, T, DT, LP, TD, TU, PS, PU, PE> WidgetState
+    for GestureDetector<W, T, DT, LP, TD, TU, PS, PU, PE>
+where
+    W: Widget,
+    T: Fn(),
+    DT: Fn(),
+    LP: Fn(),
+    TD: Fn(Point),
+    TU: Fn(Point),
+    PS: Fn(DragStartDetails),
+    PU: Fn(DragUpdateDetails),
+    PE: Fn(DragEndDetails),
+{
+    type State = GestureDetectorState;
+
+    fn create_state(&self) -> Self::State {
+        GestureDetectorState {
+            member: ArenaMember::new(),
+            down: Cell::new(None),
+            long_press_timer: Cell::new(None),
+            long_pressed: Cell::new(false),
+            pending_tap: Cell::new(None),
+            recognized: Cell::new(None),
+            drag_member: ArenaMember::new(),
+            drag: RefCell::new(Drag::default()),
+        }
+    }
+}
+
+impl<W, T, DT, LP, TD, TU, PS, PU, PE> GestureDetector<W, T, DT, LP, TD, TU, PS, PU, PE>
+where
+    W: Widget,
+    T: Fn(),
+    DT: Fn(),
+    LP: Fn(),
+    TD: Fn(Point),
+    TU: Fn(Point),
+    PS: Fn(DragStartDetails),
+    PU: Fn(DragUpdateDetails),
+    PE: Fn(DragEndDetails),
+{
+    fn wants_tap(&self) -> bool {
+        self.on_tap.is_some() || self.on_double_tap.is_some() || self.on_tap_up.is_some()
+    }
+
+    fn wants_drag(&self) -> bool {
+        self.on_pan_start.is_some() || self.on_pan_update.is_some() || self.on_pan_end.is_some()
+    }
+
+    fn pointer_down(&self, cx: BuildCx<Self>, pos: Point) {
+        let member = cx.state().member;
+
+        GestureArena::add(member, self.wants_tap());
+
+        let state = cx.state();
+        state.down.set(Some(pos));
+        state.long_pressed.set(false);
+        drop(state);
+
+        if self.on_long_press.is_some() {
+            let timer = cx.timer_once(LONG_PRESS_TIMEOUT, move |state: &mut Self::State| {
+                state.long_press_timer.set(None);
+
+                if GestureArena::accept(member) {
+                    state.long_pressed.set(true);
+                    state.recognized.set(Some(Gesture::LongPress));
+                }
+            });
+
+            if let Some(previous) = cx.state().long_press_timer.replace(Some(timer)) {
+                previous.cancel();
+            }
+        }
+
+        if let Some(on_tap_down) = &self.on_tap_down {
+            on_tap_down(pos);
+        }
+    }
+
+    /// Calls `on_tap` once Enter or Space is pressed while focused.
+    fn handle_key(&self, cx: BuildCx<Self>, event: &KeyEvent) -> bool {
+        let activates = match &event.key {
+            Key::Enter => true,
+            Key::Character(c) => c == " ",
+            _ => false,
+        };
+
+        if let (true, Some(on_tap)) = (activates, &self.on_tap) {
+            if event.state == KeyState::Down && !event.repeat {
+                report_tap(cx);
+                on_tap();
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    fn pointer_up(&self, cx: BuildCx<Self>, pos: Point) {
+        let state = cx.state();
+        let member = state.member;
+
+        if let Some(timer) = state.long_press_timer.take() {
+            timer.cancel();
+        }
+
+        let down = match state.down.take() {
+            Some(down) => down,
+            None => return,
+        };
+
+        if down.distance(pos) > TAP_SLOP {
+            GestureArena::reject(member);
+        }
+
+        let is_tap = !state.long_pressed.get() && GestureArena::wins_tap(member);
+        GestureArena::leave(member);
+
+        if !is_tap {
+            return;
+        }
+
+        let pending_tap = state.pending_tap.take();
+        drop(state);
+
+        report_tap(cx);
+
+        if let Some(on_tap_up) = &self.on_tap_up {
+            on_tap_up(pos);
+        }
+
+        match (&self.on_double_tap, pending_tap) {
+            (Some(on_double_tap), Some(pending_tap)) => {
+                pending_tap.cancel();
+                on_double_tap();
+            }
+            (Some(_), None) => {
+                let timer = cx.timer_once(DOUBLE_TAP_TIMEOUT, |state: &mut Self::State| {
+                    state.pending_tap.set(None);
+                    state.recognized.set(Some(Gesture::Tap));
+                });
+
+                cx.state().pending_tap.set(Some(timer));
+            }
+            (None, _) => {
+                if let Some(on_tap) = &self.on_tap {
+                    on_tap();
+                }
+            }
+        }
+    }
+}
+
+fn report_tap<T>(cx: BuildCx<T>) {
+    if Interactions::is_observed() {
+        let target = InteractionLabel::of(cx);
+
+        Interactions::report(Interaction::Tap {
+            widget: target.widget,
+            label: target.label,
+        });
+    }
+}
+
+impl<W, T, DT, LP, TD, TU, PS, PU, PE> ViewWidget
+    for GestureDetector<W, T, DT, LP, TD, TU, PS, PU, PE>
+where
+    W: Widget,
+    T: Fn(),
+    DT: Fn(),
+    LP: Fn(),
+    TD: Fn(Point),
+    TU: Fn(Point),
+    PS: Fn(DragStartDetails),
+    PU: Fn(DragUpdateDetails),
+    PE: Fn(DragEndDetails),
+{
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let recognized = cx.state().recognized.take();
+
+        let callback: Option<&dyn Fn()> = match recognized {
+            Some(Gesture::Tap) => self.on_tap.as_ref().map(|f| f as _),
+            Some(Gesture::LongPress) => self.on_long_press.as_ref().map(|f| f as _),
+            None => None,
+        };
+
+        if let Some(callback) = callback {
+            callback();
+        }
+
+        let listener = PointerListener::builder()
+            .on_pointer_down(move |e| {
+                if e.0.button == MouseButton::Left {
+                    self.pointer_down(cx, e.0.pos);
+                }
+            })
+            .on_pointer_up(move |e| {
+                if e.0.button == MouseButton::Left {
+                    self.pointer_up(cx, e.0.pos);
+                }
+            })
+            .child(&self.child);
+
+        let activatable = self.keyboard_activation
+            && self.on_tap.is_some()
+            && InteractionLabel::of(cx).label.is_some();
+
+        match activatable {
+            true => Focus::builder()
+                .on_key(move |event: &KeyEvent| self.handle_key(cx, event))
+                .child(listener)
+                .boxed(),
+            false => listener.boxed(),
+        }
+    }
+}
+
+impl<W, T, DT, LP, TD, TU, PS, PU, PE> HitTest for GestureDetector<W, T, DT, LP, TD, TU, PS, PU, PE>
+where
+    W: Widget,
+    T: Fn(),
+    DT: Fn(),
+    LP: Fn(),
+    TD: Fn(Point),
+    TU: Fn(Point),
+    PS: Fn(DragStartDetails),
+    PU: Fn(DragUpdateDetails),
+    PE: Fn(DragEndDetails),
+{
+    // Taps are recognized by the `PointerListener` built by this widget, since
+    // they need timers. Drags need pointer move events (also outside of this
+    // widget once a drag starts), which are only received here.
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        if !self.wants_drag() {
+            return;
+        }
+
+        let state = cx.widget_state();
+        let member = state.drag_member;
+        let mut drag = state.drag.borrow_mut();
+
+        match event {
+            PointerEvent::PointerDown(e) if e.0.button == MouseButton::Left => {
+                GestureArena::add(member, false);
+
+                *drag = Drag {
+                    start: Some(e.0.pos),
+                    last: e.0.pos,
+                    ..Drag::default()
+                };
+                drag.tracker.add(Instant::now(), e.0.pos);
+            }
+            PointerEvent::PointerMove(e) => {
+                let pos = e.0.pos;
+                let start = match drag.start {
+                    Some(start) => start,
+                    None => return,
+                };
+
+                drag.tracker.add(Instant::now(), pos);
+
+                if !drag.dragging {
+                    if start.distance(pos) <= PAN_SLOP {
+                        return;
+                    }
+
+                    if !GestureArena::accept(member) {
+                        drag.start = None;
+                        return;
+                    }
+
+                    drag.dragging = true;
+
+                    // Keep receiving events if the pointer leaves this widget.
+                    cx.capture_pointer();
+
+                    if let Some(on_pan_start) = &self.on_pan_start {
+                        on_pan_start(DragStartDetails {
+                            local_position: start,
+                            global_position: cx.local_to_global(start),
+                        });
+                    }
+                }
+
+                let delta = pos - drag.last;
+                drag.last = pos;
+
+                if let Some(on_pan_update) = &self.on_pan_update {
+                    on_pan_update(DragUpdateDetails {
+                        delta,
+                        local_position: pos,
+                        global_position: cx.local_to_global(pos),
+                    });
+                }
+            }
+            PointerEvent::PointerUp(_) => {
+                let dragging = drag.dragging;
+                let velocity = drag.tracker.velocity();
+                *drag = Drag::default();
+
+                GestureArena::leave(member);
+
+                if let (true, Some(on_pan_end)) = (dragging, &self.on_pan_end) {
+                    on_pan_end(DragEndDetails { velocity });
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use frui::render::*;
+
+/// Positions are in local coordinates of the widget recognizing the gesture
+/// and in window coordinates respectively.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragStartDetails {
+    pub local_position: Point,
+    pub global_position: Point,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragUpdateDetails {
+    /// Distance the pointer moved since the previous update.
+    pub delta: Vec2,
+    pub local_position: Point,
+    pub global_position: Point,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragEndDetails {
+    /// Velocity of the pointer when it was released, in points per second.
+    pub velocity: Vec2,
+}
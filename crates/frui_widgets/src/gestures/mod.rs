@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+pub use arena::*;
+pub use detector::*;
+pub use drag::*;
+pub use velocity::*;
+
+pub mod arena;
+pub mod detector;
+pub mod drag;
+pub mod velocity;
+
+/// How long the pointer has to be pressed to be recognized as a long press.
+pub const LONG_PRESS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Maximal delay between taps recognized as a double tap.
+pub const DOUBLE_TAP_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Distance the pointer can move while pressed and still be recognized as a
+/// tap.
+pub const TAP_SLOP: f64 = 18.;
+
+/// Distance the pointer has to move while pressed to start a drag.
+pub const PAN_SLOP: f64 = 18.;
@@ -0,0 +1,85 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use frui::render::*;
+
+/// Samples older than that (relative to the newest one) don't affect the
+/// velocity.
+const HORIZON: Duration = Duration::from_millis(100);
+
+/// Maximal number of samples kept.
+const MAX_SAMPLES: usize = 20;
+
+/// Estimates velocity of the pointer from its recent positions, e.g. to fling
+/// a scroll view once the pointer is released.
+#[derive(Debug, Clone, Default)]
+pub struct VelocityTracker {
+    samples: VecDeque<(Instant, Point)>,
+}
+
+impl VelocityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, time: Instant, position: Point) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back((time, position));
+    }
+
+    /// Velocity in points per second, or zero if there are not enough recent
+    /// samples.
+    pub fn velocity(&self) -> Vec2 {
+        let (newest_time, newest) = match self.samples.back() {
+            Some(newest) => *newest,
+            None => return Vec2::ZERO,
+        };
+
+        let oldest = self
+            .samples
+            .iter()
+            .find(|(time, _)| newest_time.duration_since(*time) <= HORIZON);
+
+        match oldest {
+            Some((time, position)) if *time < newest_time => {
+                (newest - *position) / newest_time.duration_since(*time).as_secs_f64()
+            }
+            _ => Vec2::ZERO,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn track_velocity() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        let mut tracker = VelocityTracker::new();
+        assert_eq!(tracker.velocity(), Vec2::ZERO);
+
+        // Samples outside of the horizon are ignored.
+        tracker.add(at(0), Point::new(0., 0.));
+        tracker.add(at(200), Point::new(500., 0.));
+        tracker.add(at(250), Point::new(510., 0.));
+        tracker.add(at(300), Point::new(520., 0.));
+
+        assert_eq!(tracker.velocity(), Vec2::new(200., 0.));
+
+        tracker.reset();
+        tracker.add(at(400), Point::new(0., 0.));
+        assert_eq!(tracker.velocity(), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,401 @@
+//! Golden tests, which render a widget offscreen and compare it with a
+//! reference image (golden) checked into the repository.
+//!
+//! Goldens are stored as `<name>.png` in the `goldens` directory of the
+//! tested crate. Fonts are rendered differently on each platform, so a
+//! variant of the current platform (`<name>.<os>.png`, e.g.
+//! `button.macos.png`) is used instead if it exists.
+//!
+//! Running tests with `FRUI_UPDATE_GOLDENS=1` writes rendered images as the
+//! goldens instead of comparing them, updating the platform variant if there
+//! is one. `FRUI_UPDATE_GOLDENS=platform` always writes the variant of the
+//! current platform.
+//!
+//! When an image differs from its golden, the rendered image, the golden and
+//! an image highlighting differing pixels are written to
+//! `goldens/failures/<name>/` to be inspected.
+//!
+//! Available with the `testing` feature, usually enabled only for
+//! `dev-dependencies`.
+
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use druid_shell::piet::{ImageBuf, ImageFormat};
+use frui::{
+    app::offscreen::{self, OffscreenError},
+    prelude::*,
+};
+use image::{Rgba, RgbaImage};
+
+/// Environment variable which makes golden tests update goldens instead of
+/// comparing with them.
+pub const UPDATE_GOLDENS_VAR: &str = "FRUI_UPDATE_GOLDENS";
+
+/// Golden test of a widget rendered into an image.
+///
+/// ```ignore
+/// #[test]
+/// fn counter() {
+///     Golden::new("counter")
+///         .size(Size::new(300., 200.))
+///         .expect(|| Counter);
+/// }
+/// ```
+pub struct Golden {
+    name: String,
+    size: Size,
+    scale: f64,
+    tolerance: f64,
+    dir: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoldenError(String);
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "golden test failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl From<OffscreenError> for GoldenError {
+    fn from(e: OffscreenError) -> Self {
+        GoldenError(e.to_string())
+    }
+}
+
+impl From<image::ImageError> for GoldenError {
+    fn from(e: image::ImageError) -> Self {
+        GoldenError(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for GoldenError {
+    fn from(e: std::io::Error) -> Self {
+        GoldenError(e.to_string())
+    }
+}
+
+impl Golden {
+    pub fn new(name: impl Into<String>) -> Self {
+        // Set by cargo for tests, which otherwise run in the directory of the
+        // tested crate.
+        let root = env::var_os("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+
+        Golden {
+            name: name.into(),
+            size: Size::new(400., 300.),
+            scale: 1.,
+            tolerance: 0.,
+            dir: root.join("goldens"),
+        }
+    }
+
+    /// Size of the rendered widget in display points.
+    pub fn size(mut self, size: Size) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Fraction of pixels (between 0 and 1) which may differ from the golden,
+    /// e.g. because of differences in antialiasing.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Directory of goldens, instead of `goldens` of the tested crate.
+    pub fn dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Compares the widget returned by `build` with the golden, panicking if
+    /// they differ. See [`check`](Self::check).
+    #[track_caller]
+    pub fn expect<W, F>(&self, build: F)
+    where
+        W: Widget + 'static,
+        F: FnOnce() -> W + Send + 'static,
+    {
+        if let Err(e) = self.check(build) {
+            panic!("{}", e);
+        }
+    }
+
+    /// Renders the widget returned by `build` and compares it with the
+    /// golden, or updates the golden if [`UPDATE_GOLDENS_VAR`] is set.
+    pub fn check<W, F>(&self, build: F) -> Result<(), GoldenError>
+    where
+        W: Widget + 'static,
+        F: FnOnce() -> W + Send + 'static,
+    {
+        let rendered = offscreen::render(self.size, self.scale, build)
+            .join()
+            .map_err(|_| GoldenError(format!("rendering of `{}` panicked", self.name)))??;
+        let rendered = to_rgba_image(&rendered);
+
+        // Artifacts of previous failures would be misleading.
+        let failures = self.failures_dir();
+        if failures.exists() {
+            fs::remove_dir_all(&failures)?;
+        }
+
+        if let Some(mode) = UpdateMode::from_env() {
+            let path = match mode {
+                UpdateMode::Existing => self.golden_path().unwrap_or_else(|| self.shared_path()),
+                UpdateMode::Platform => self.platform_path(),
+            };
+
+            return save(&rendered, &path);
+        }
+
+        let path = self.golden_path().ok_or_else(|| {
+            GoldenError(format!(
+                "golden `{}` doesn't exist, run tests with {}=1 to create it",
+                self.shared_path().display(),
+                UPDATE_GOLDENS_VAR,
+            ))
+        })?;
+
+        let golden = image::open(&path)?.into_rgba8();
+
+        let mismatch = match compare(&golden, &rendered, self.tolerance) {
+            Ok(()) => return Ok(()),
+            Err(mismatch) => mismatch,
+        };
+
+        save(&golden, &failures.join("golden.png"))?;
+        save(&rendered, &failures.join("rendered.png"))?;
+
+        if let Mismatch::Pixels { diff, .. } = &mismatch {
+            save(diff, &failures.join("diff.png"))?;
+        }
+
+        Err(GoldenError(format!(
+            "`{}` differs from golden `{}`: {} (see {}, run tests with {}=1 to update the golden)",
+            self.name,
+            path.display(),
+            mismatch,
+            failures.display(),
+            UPDATE_GOLDENS_VAR,
+        )))
+    }
+
+    fn shared_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.png", self.name))
+    }
+
+    fn platform_path(&self) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}.png", self.name, env::consts::OS))
+    }
+
+    /// Golden of the current platform if there is one, or the shared one.
+    fn golden_path(&self) -> Option<PathBuf> {
+        [self.platform_path(), self.shared_path()]
+            .into_iter()
+            .find(|path| path.exists())
+    }
+
+    fn failures_dir(&self) -> PathBuf {
+        self.dir.join("failures").join(&self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UpdateMode {
+    /// Update the golden compared with, or create the shared one.
+    Existing,
+    Platform,
+}
+
+impl UpdateMode {
+    fn from_env() -> Option<Self> {
+        Self::parse(&env::var(UPDATE_GOLDENS_VAR).ok()?)
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "" | "0" | "false" => None,
+            "platform" => Some(UpdateMode::Platform),
+            _ => Some(UpdateMode::Existing),
+        }
+    }
+}
+
+enum Mismatch {
+    Size {
+        golden: (u32, u32),
+        rendered: (u32, u32),
+    },
+    Pixels {
+        differing: usize,
+        total: usize,
+        /// Golden faded out, with differing pixels in red.
+        diff: RgbaImage,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Size { golden, rendered } => write!(
+                f,
+                "size {}x{} differs from {}x{}",
+                rendered.0, rendered.1, golden.0, golden.1
+            ),
+            Mismatch::Pixels {
+                differing, total, ..
+            } => write!(f, "{} of {} pixels differ", differing, total),
+        }
+    }
+}
+
+fn compare(golden: &RgbaImage, rendered: &RgbaImage, tolerance: f64) -> Result<(), Mismatch> {
+    if golden.dimensions() != rendered.dimensions() {
+        return Err(Mismatch::Size {
+            golden: golden.dimensions(),
+            rendered: rendered.dimensions(),
+        });
+    }
+
+    let mut differing = 0;
+
+    let diff = RgbaImage::from_fn(golden.width(), golden.height(), |x, y| {
+        let expected = golden.get_pixel(x, y);
+
+        if expected == rendered.get_pixel(x, y) {
+            let [r, g, b, a] = expected.0;
+            let luma = (r as u32 * 3 + g as u32 * 6 + b as u32) / 10;
+            Rgba([luma as u8, luma as u8, luma as u8, a / 4])
+        } else {
+            differing += 1;
+            Rgba([255, 0, 0, 255])
+        }
+    });
+
+    let total = (golden.width() * golden.height()) as usize;
+
+    match differing as f64 <= tolerance * total as f64 {
+        true => Ok(()),
+        false => Err(Mismatch::Pixels {
+            differing,
+            total,
+            diff,
+        }),
+    }
+}
+
+fn save(image: &RgbaImage, path: &Path) -> Result<(), GoldenError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    Ok(image.save(path)?)
+}
+
+/// Converts pixels to non-premultiplied RGBA, which is stored in PNGs.
+pub(crate) fn to_rgba_image(image: &ImageBuf) -> RgbaImage {
+    let pixels = image.raw_pixels();
+
+    let rgba = match image.format() {
+        ImageFormat::RgbaSeparate => pixels.to_vec(),
+        ImageFormat::RgbaPremul => pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let a = p[3] as u32;
+                let c = |c: u8| match a {
+                    0 => 0,
+                    a => ((c as u32 * 255 + a / 2) / a).min(255) as u8,
+                };
+
+                [c(p[0]), c(p[1]), c(p[2]), p[3]]
+            })
+            .collect(),
+        ImageFormat::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        _ => pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+    };
+
+    RgbaImage::from_raw(image.width() as u32, image.height() as u32, rgba).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColoredBox;
+
+    #[test]
+    fn compare_images() {
+        let golden = RgbaImage::from_pixel(2, 2, Rgba([10, 20, 30, 255]));
+
+        let mut rendered = golden.clone();
+        assert!(compare(&golden, &rendered, 0.).is_ok());
+
+        rendered.put_pixel(1, 0, Rgba([10, 20, 31, 255]));
+
+        match compare(&golden, &rendered, 0.) {
+            Err(Mismatch::Pixels {
+                differing, diff, ..
+            }) => {
+                assert_eq!(differing, 1);
+                assert_eq!(*diff.get_pixel(1, 0), Rgba([255, 0, 0, 255]));
+                assert_eq!(diff.get_pixel(0, 0).0[3], 255 / 4);
+            }
+            _ => panic!("expected differing pixels"),
+        }
+
+        assert!(compare(&golden, &rendered, 0.25).is_ok());
+
+        let smaller = RgbaImage::new(2, 1);
+        assert!(matches!(
+            compare(&golden, &smaller, 1.),
+            Err(Mismatch::Size { .. })
+        ));
+    }
+
+    #[test]
+    fn update_mode() {
+        assert_eq!(UpdateMode::parse("0"), None);
+        assert_eq!(UpdateMode::parse(""), None);
+        assert_eq!(UpdateMode::parse("1"), Some(UpdateMode::Existing));
+        assert_eq!(UpdateMode::parse("platform"), Some(UpdateMode::Platform));
+    }
+
+    #[test]
+    fn golden_paths() {
+        let golden = Golden::new("button").dir("goldens");
+
+        assert_eq!(golden.shared_path(), Path::new("goldens/button.png"));
+        assert_eq!(
+            golden.platform_path(),
+            Path::new("goldens").join(format!("button.{}.png", env::consts::OS))
+        );
+        assert_eq!(golden.failures_dir(), Path::new("goldens/failures/button"));
+    }
+
+    #[test]
+    fn colored_box_matches_golden() {
+        Golden::new("colored_box")
+            .size(Size::new(8., 8.))
+            .expect(|| ColoredBox {
+                color: Color::rgb8(0x28, 0xC6, 0xA8),
+                child: (),
+            });
+    }
+}
@@ -0,0 +1,244 @@
+//! Hover intent: deciding whether the user actually means to hover over a
+//! widget, as opposed to just moving the pointer across it.
+
+use std::{cell::Cell, time::Duration};
+
+use frui::{app::services::Services, prelude::*, render::*};
+
+use crate::*;
+
+/// Timing of hover-triggered popups (tooltips, menus, hover cards).
+///
+/// Application-wide configuration can be registered with
+/// `Services::register(HoverIntentConfig { .. })`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoverIntentConfig {
+    /// How long the pointer has to rest above the widget.
+    pub enter_delay: Duration,
+    /// How long hover is kept after the pointer leaves the widget. Entering the
+    /// widget again within that time keeps the popup open without flicker.
+    pub exit_delay: Duration,
+    /// Distance the pointer can move while still being considered at rest.
+    pub move_tolerance: f64,
+}
+
+impl Default for HoverIntentConfig {
+    fn default() -> Self {
+        HoverIntentConfig {
+            enter_delay: Duration::from_millis(500),
+            exit_delay: Duration::from_millis(100),
+            move_tolerance: 4.,
+        }
+    }
+}
+
+impl HoverIntentConfig {
+    /// Application-wide configuration, or the default one if none was
+    /// registered.
+    pub fn current() -> Self {
+        match Services::get::<HoverIntentConfig>() {
+            Some(config) => (*config).clone(),
+            None => HoverIntentConfig::default(),
+        }
+    }
+}
+
+/// Builds its child with the hover intent state, rebuilding it when the
+/// pointer rests above the widget for `enter_delay` and after it leaves the
+/// widget for `exit_delay`.
+#[derive(ViewWidget)]
+pub struct HoverIntent<W: Widget, F: Fn(bool) -> W> {
+    pub builder: F,
+    /// Uses [`HoverIntentConfig::current`] if `None`.
+    pub config: Option<HoverIntentConfig>,
+}
+
+impl<W: Widget, F: Fn(bool) -> W> HoverIntent<W, F> {
+    pub fn new(builder: F) -> Self {
+        HoverIntent {
+            builder,
+            config: None,
+        }
+    }
+
+    pub fn config(mut self, config: HoverIntentConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct HoverIntentState {
+    hovered: bool,
+    /// Position at which the pointer started resting.
+    anchor: Cell<Option<Point>>,
+    /// Pending change of `hovered`.
+    timer: Cell<Option<Timer>>,
+}
+
+impl HoverIntentState {
+    fn set_timer(&self, timer: Option<Timer>) {
+        if let Some(previous) = self.timer.replace(timer) {
+            previous.cancel();
+        }
+    }
+}
+
+impl<W: Widget, F: Fn(bool) -> W> WidgetState for HoverIntent<W, F> {
+    type State = HoverIntentState;
+
+    fn create_state(&self) -> Self::State {
+        HoverIntentState::default()
+    }
+}
+
+impl<W: Widget, F: Fn(bool) -> W> ViewWidget for HoverIntent<W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let config = self
+            .config
+            .clone()
+            .unwrap_or_else(HoverIntentConfig::current);
+        let (enter_delay, exit_delay) = (config.enter_delay, config.exit_delay);
+
+        let start_resting = move |pos: Point| {
+            let timer = cx.timer_once(enter_delay, |state: &mut HoverIntentState| {
+                state.hovered = true;
+                state.timer.set(None);
+            });
+
+            let state = cx.state();
+            state.anchor.set(Some(pos));
+            state.set_timer(Some(timer));
+        };
+
+        PointerRegion::builder()
+            .on_enter(move |e| {
+                if cx.state().hovered {
+                    // Re-entered before the exit delay elapsed.
+                    cx.state().set_timer(None);
+                } else {
+                    start_resting(e.0.pos);
+                }
+            })
+            .on_move(move |e| {
+                let state = cx.state();
+
+                if state.hovered {
+                    return;
+                }
+
+                let moved = match state.anchor.get() {
+                    Some(anchor) => anchor.distance(e.0.pos) > config.move_tolerance,
+                    None => true,
+                };
+
+                if moved {
+                    drop(state);
+                    start_resting(e.0.pos);
+                }
+            })
+            .on_exit(move |_| {
+                let state = cx.state();
+                state.anchor.set(None);
+
+                if state.hovered {
+                    drop(state);
+
+                    let timer = cx.timer_once(exit_delay, |state: &mut HoverIntentState| {
+                        state.hovered = false;
+                        state.timer.set(None);
+                    });
+
+                    cx.state().set_timer(Some(timer));
+                } else {
+                    state.set_timer(None);
+                }
+            })
+            .child((self.builder)(cx.state().hovered))
+    }
+}
+
+/// Shows `message` below `child` when the user hovers over it.
+#[derive(ViewWidget, Builder)]
+pub struct Tooltip<W: Widget> {
+    pub child: W,
+    pub message: String,
+    pub color: Color,
+    pub text_color: Color,
+    /// Uses [`HoverIntentConfig::current`] if `None`.
+    pub config: Option<HoverIntentConfig>,
+}
+
+impl Tooltip<()> {
+    pub fn builder() -> Self {
+        Tooltip {
+            child: (),
+            message: String::new(),
+            color: Color::rgba8(97, 97, 97, 230),
+            text_color: Color::WHITE,
+            config: None,
+        }
+    }
+}
+
+impl<W: Widget> ViewWidget for Tooltip<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        HoverIntent {
+            config: self.config.clone(),
+            builder: move |hovered| {
+                let tooltip = match hovered && !self.message.is_empty() {
+                    true => DecoratedBox::builder()
+                        .position(DecorationPosition::Background)
+                        .decoration(
+                            BoxDecoration::builder()
+                                .color(self.color.clone())
+                                .border_radius(BorderRadius::circular(4.)),
+                        )
+                        .child(
+                            Padding::builder()
+                                .padding(EdgeInsets::symmetric(4., 8.))
+                                .child(
+                                    Text::new(self.message.as_str())
+                                        .size(12.)
+                                        .color(self.text_color.clone()),
+                                ),
+                        )
+                        .boxed(),
+                    false => ().boxed(),
+                };
+
+                TooltipLayout {
+                    child: &self.child,
+                    tooltip,
+                }
+            },
+        }
+    }
+}
+
+/// Sizes itself to `child` and paints `tooltip` below it.
+#[derive(RenderWidget)]
+struct TooltipLayout<W: Widget, T: Widget> {
+    child: W,
+    tooltip: T,
+}
+
+impl<W: Widget, T: Widget> RenderWidget for TooltipLayout<W, T> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![(&self.child).boxed(), (&self.tooltip).boxed()]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let size = cx.child(0).layout(constraints);
+        cx.child(1).layout(Constraints::default());
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let height = cx.size().height;
+
+        cx.child(0).paint(canvas, offset);
+        cx.child(1)
+            .paint(canvas, &(*offset + Offset::new(0., height + 4.)));
+    }
+}
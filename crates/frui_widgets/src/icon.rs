@@ -0,0 +1,446 @@
+//! [`Icon`]s drawn from SVG path data or glyphs of an icon font, so that
+//! standard UI icons don't need to be shipped as raster images.
+//!
+//! Common icons are available in [`Icons`]. Icons of other icon fonts are
+//! created through an [`IconFont`]:
+//!
+//! ```ignore
+//! let symbols = IconFont::load(include_bytes!("symbols.ttf"))?;
+//!
+//! Icon::new(symbols.icon('\u{e88a}')).size(32.)
+//! ```
+
+use druid_shell::{
+    kurbo::{Affine, BezPath},
+    piet::{self, FontFamily, RenderContext, Text as _, TextLayout, TextLayoutBuilder},
+};
+use frui::{prelude::*, render::*};
+
+use crate::*;
+
+/// Side of the square view box of [`IconData::Path`]s.
+const PATH_VIEW_BOX: f64 = 24.;
+
+const DEFAULT_ICON_SIZE: f64 = 24.;
+
+/// Shape of an icon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IconData {
+    /// SVG path data within a 24 by 24 view box, filled with the color of
+    /// the icon.
+    Path(&'static str),
+    /// Glyph of an icon font, see [`IconFont`].
+    Glyph {
+        code_point: char,
+        font_family: FontFamily,
+    },
+}
+
+impl IconData {
+    fn path(&self) -> Option<BezPath> {
+        match self {
+            IconData::Path(data) => parse_path(data),
+            IconData::Glyph { .. } => None,
+        }
+    }
+}
+
+fn parse_path(data: &str) -> Option<BezPath> {
+    match BezPath::from_svg(data) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            log::warn!("invalid icon path `{}`: {}", data, e);
+            None
+        }
+    }
+}
+
+/// Path of an [`IconData::Path`], parsed once per path data.
+#[derive(Default)]
+struct PathCache {
+    /// Data of the path and the path parsed from it.
+    parsed: Option<(&'static str, Option<BezPath>)>,
+}
+
+impl PathCache {
+    fn get(&mut self, data: &'static str) -> Option<&BezPath> {
+        if !matches!(&self.parsed, Some((parsed, _)) if *parsed == data) {
+            self.parsed = Some((data, parse_path(data)));
+        }
+
+        self.parsed.as_ref().and_then(|(_, path)| path.as_ref())
+    }
+}
+
+/// Font whose glyphs are icons, e.g. Material Symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconFont {
+    pub family: FontFamily,
+}
+
+impl IconFont {
+    /// Loads an icon font from `data` (e.g. bundled with `include_bytes!`).
+    pub fn load(data: &[u8]) -> Result<Self, piet::Error> {
+        let family = TextLayoutCache::load_font(data)?;
+        Ok(IconFont { family })
+    }
+
+    pub fn icon(&self, code_point: char) -> IconData {
+        IconData::Glyph {
+            code_point,
+            font_family: self.family.clone(),
+        }
+    }
+}
+
+/// Icons drawn from SVG paths, which don't require any font. Shapes are
+/// those of the Material Design icons (Apache License 2.0).
+pub struct Icons;
+
+impl Icons {
+    pub const ADD: IconData = IconData::Path("M19 13h-6v6h-2v-6H5v-2h6V5h2v6h6v2z");
+    pub const REMOVE: IconData = IconData::Path("M19 13H5v-2h14v2z");
+    pub const CLOSE: IconData = IconData::Path(
+        "M19 6.41L17.59 5 12 10.59 6.41 5 5 6.41 10.59 12 5 17.59 6.41 19 12 13.41 17.59 19 \
+         19 17.59 13.41 12z",
+    );
+    pub const CHECK: IconData = IconData::Path("M9 16.17L4.83 12l-1.42 1.41L9 19 21 7l-1.41-1.41z");
+    pub const MENU: IconData = IconData::Path("M3 18h18v-2H3v2zm0-5h18v-2H3v2zm0-7v2h18V6H3z");
+    pub const MORE_VERT: IconData = IconData::Path(
+        "M12 8c1.1 0 2-.9 2-2s-.9-2-2-2-2 .9-2 2 .9 2 2 2zm0 2c-1.1 0-2 .9-2 2s.9 2 2 2 2-.9 \
+         2-2-.9-2-2-2zm0 6c-1.1 0-2 .9-2 2s.9 2 2 2 2-.9 2-2-.9-2-2-2z",
+    );
+    pub const ARROW_BACK: IconData =
+        IconData::Path("M20 11H7.83l5.59-5.59L12 4l-8 8 8 8 1.41-1.41L7.83 13H20v-2z");
+    pub const ARROW_FORWARD: IconData =
+        IconData::Path("M12 4l-1.41 1.41L16.17 11H4v2h12.17l-5.58 5.59L12 20l8-8z");
+    pub const CHEVRON_LEFT: IconData =
+        IconData::Path("M15.41 7.41L14 6l-6 6 6 6 1.41-1.41L10.83 12z");
+    pub const CHEVRON_RIGHT: IconData =
+        IconData::Path("M10 6L8.59 7.41 13.17 12l-4.58 4.59L10 18l6-6z");
+    pub const EXPAND_MORE: IconData =
+        IconData::Path("M16.59 8.59L12 13.17 7.41 8.59 6 10l6 6 6-6z");
+    pub const EXPAND_LESS: IconData =
+        IconData::Path("M12 8l-6 6 1.41 1.41L12 10.83l4.59 4.58L18 14z");
+    pub const SEARCH: IconData = IconData::Path(
+        "M15.5 14h-.79l-.28-.27C15.41 12.59 16 11.11 16 9.5 16 5.91 13.09 3 9.5 3S3 5.91 3 9.5 \
+         5.91 16 9.5 16c1.61 0 3.09-.59 4.23-1.57l.27.28v.79l5 4.99L20.49 19l-4.99-5zm-6 0C7.01 \
+         14 5 11.99 5 9.5S7.01 5 9.5 5 14 7.01 14 9.5 11.99 14 9.5 14z",
+    );
+    pub const HOME: IconData = IconData::Path("M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z");
+    pub const EDIT: IconData = IconData::Path(
+        "M3 17.25V21h3.75L17.81 9.94l-3.75-3.75L3 17.25zM20.71 7.04c.39-.39.39-1.02 \
+         0-1.41l-2.34-2.34c-.39-.39-1.02-.39-1.41 0l-1.83 1.83 3.75 3.75 1.83-1.83z",
+    );
+    pub const DELETE: IconData = IconData::Path(
+        "M6 19c0 1.1.9 2 2 2h8c1.1 0 2-.9 2-2V7H6v12zM19 4h-3.5l-1-1h-5l-1 1H5v2h14V4z",
+    );
+    pub const REFRESH: IconData = IconData::Path(
+        "M17.65 6.35C16.2 4.9 14.21 4 12 4c-4.42 0-7.99 3.58-7.99 8s3.57 8 7.99 8c3.73 0 \
+         6.84-2.55 7.73-6h-2.08c-.82 2.33-3.04 4-5.65 4-3.31 0-6-2.69-6-6s2.69-6 6-6c1.66 0 \
+         3.14.69 4.22 1.78L13 11h7V4l-2.35 2.35z",
+    );
+    pub const INFO: IconData = IconData::Path(
+        "M12 2C6.48 2 2 6.48 2 12s4.48 10 10 10 10-4.48 10-10S17.52 2 12 \
+         2zm1 15h-2v-6h2v6zm0-8h-2V7h2v2z",
+    );
+    pub const WARNING: IconData =
+        IconData::Path("M1 21h22L12 2 1 21zm12-3h-2v-2h2v2zm0-4h-2v-4h2v4z");
+    pub const ERROR: IconData = IconData::Path(
+        "M12 2C6.48 2 2 6.48 2 12s4.48 10 10 10 10-4.48 10-10S17.52 2 12 \
+         2zm1 15h-2v-2h2v2zm0-4h-2V7h2v6z",
+    );
+}
+
+/// Size and color of icons. Properties which aren't set are taken from the
+/// enclosing [`IconTheme`], and eventually from [`ThemeData::icon_theme`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IconThemeData {
+    pub color: Option<Color>,
+    pub size: Option<f64>,
+}
+
+impl IconThemeData {
+    /// Properties of `self`, with those which aren't set taken from `other`.
+    pub fn merge(&self, other: &IconThemeData) -> IconThemeData {
+        IconThemeData {
+            color: self.color.clone().or_else(|| other.color.clone()),
+            size: self.size.or(other.size),
+        }
+    }
+}
+
+/// Sets the size and color of [`Icon`]s in its subtree, e.g. to match the
+/// foreground color of a button.
+#[derive(ViewWidget, Builder)]
+pub struct IconTheme<W: Widget> {
+    pub data: IconThemeData,
+    pub child: W,
+}
+
+impl IconTheme<()> {
+    pub fn builder() -> Self {
+        IconTheme {
+            data: IconThemeData::default(),
+            child: (),
+        }
+    }
+
+    /// Data of the closest [`IconTheme`] ancestor, on top of the icon theme
+    /// of the [`Theme`].
+    pub fn of<T>(cx: BuildCx<T>) -> IconThemeData {
+        let theme = Theme::of(cx).icon_theme;
+
+        cx.depend_on::<IconThemeData>()
+            .map_or(theme.clone(), |data| data.merge(&theme))
+    }
+
+    /// See [`IconTheme::of`].
+    pub fn of_layout<T>(cx: &LayoutCx<T>) -> IconThemeData {
+        let theme = Theme::of_layout(cx).icon_theme;
+
+        cx.depend_on::<IconThemeData>()
+            .map_or(theme.clone(), |data| data.merge(&theme))
+    }
+}
+
+impl<W: Widget> ViewWidget for IconTheme<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let data = match cx.depend_on::<IconThemeData>() {
+            Some(outer) => self.data.merge(&outer),
+            None => self.data.clone(),
+        };
+
+        IconThemeScope {
+            data,
+            child: &self.child,
+        }
+    }
+}
+
+#[derive(InheritedWidget)]
+struct IconThemeScope<W: Widget> {
+    data: IconThemeData,
+    child: W,
+}
+
+impl<W: Widget> InheritedWidget for IconThemeScope<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl<W: Widget> InheritedValue for IconThemeScope<W> {
+    type Value = IconThemeData;
+
+    fn value(&self) -> &Self::Value {
+        &self.data
+    }
+}
+
+/// Square icon of `size`, painted with `color`. Properties which aren't set
+/// are taken from the closest [`IconTheme`].
+///
+/// Directional icons, like [`Icons::ARROW_BACK`], should set
+/// `match_text_direction` to be mirrored in right-to-left text direction,
+/// same as by [`MatchTextDirection`].
+///
+/// ```ignore
+/// IconButton::builder()
+///     .on_pressed(|| close())
+///     .child(Icon::new(Icons::CLOSE))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct Icon {
+    pub icon: IconData,
+    pub size: Option<f64>,
+    pub color: Option<Color>,
+    pub match_text_direction: bool,
+}
+
+impl Icon {
+    pub fn new(icon: IconData) -> Self {
+        Icon {
+            icon,
+            size: None,
+            color: None,
+            match_text_direction: false,
+        }
+    }
+
+    fn side(&self, cx: &LayoutCx<Self>) -> f64 {
+        let theme = IconTheme::of_layout(cx);
+        self.size.or(theme.size).unwrap_or(DEFAULT_ICON_SIZE)
+    }
+}
+
+/// Properties of an [`Icon`] resolved during layout.
+pub struct IconStyle {
+    color: Color,
+    mirrored: bool,
+    path: PathCache,
+}
+
+impl RenderState for Icon {
+    type State = IconStyle;
+
+    fn create_state(&self) -> Self::State {
+        IconStyle {
+            color: Color::TRANSPARENT,
+            mirrored: false,
+            path: PathCache::default(),
+        }
+    }
+}
+
+impl RenderWidget for Icon {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let theme = IconTheme::of_layout(cx);
+        let size = self.size.or(theme.size).unwrap_or(DEFAULT_ICON_SIZE);
+
+        let mut style = cx.render_state_mut();
+        style.color = self.color.clone().or(theme.color).unwrap_or(Color::BLACK);
+        style.mirrored = self.match_text_direction && MatchTextDirection::is_mirrored(cx, None);
+        drop(style);
+
+        constraints.constrain(Size::new(size, size))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let side = size.width.min(size.height);
+        let color = cx.render_state().color.clone();
+
+        let mirror = match cx.render_state().mirrored {
+            true => MatchTextDirection::mirror_at(offset, size),
+            false => Affine::IDENTITY,
+        };
+
+        // Icons are centered if constraints didn't allow them to be square.
+        let origin = Point::new(
+            offset.x + (size.width - side) / 2.,
+            offset.y + (size.height - side) / 2.,
+        );
+
+        match &self.icon {
+            IconData::Path(data) => {
+                let mut style = cx.render_state_mut();
+                let path = match style.path.get(data) {
+                    Some(path) => path,
+                    None => return,
+                };
+
+                let transform =
+                    Affine::translate(origin.to_vec2()) * Affine::scale(side / PATH_VIEW_BOX);
+
+                let r = canvas.with_save(|cv| {
+                    cv.transform(mirror * transform);
+                    cv.fill(path, &color);
+                    Ok(())
+                });
+
+                if let Err(e) = r {
+                    log::error!("painting icon failed: {}", e);
+                }
+            }
+            IconData::Glyph {
+                code_point,
+                font_family,
+            } => {
+                let layout = TEXT_FACTORY.with(|f| {
+                    f.get()
+                        .new_text_layout(code_point.to_string())
+                        .font(font_family.clone(), side)
+                        .text_color(color)
+                        .build()
+                        .unwrap()
+                });
+
+                let glyph = layout.size();
+                let position = Point::new(
+                    origin.x + (side - glyph.width) / 2.,
+                    origin.y + (side - glyph.height) / 2.,
+                );
+
+                let r = canvas.with_save(|cv| {
+                    cv.transform(mirror);
+                    cv.draw_text(&layout, position);
+                    Ok(())
+                });
+
+                if let Err(e) = r {
+                    log::error!("painting icon failed: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl Intrinsics for Icon {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        self.side(cx)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        self.side(cx)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        self.side(cx)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        self.side(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn icon_paths_are_valid() {
+        let icons = [
+            Icons::ADD,
+            Icons::REMOVE,
+            Icons::CLOSE,
+            Icons::CHECK,
+            Icons::MENU,
+            Icons::MORE_VERT,
+            Icons::ARROW_BACK,
+            Icons::ARROW_FORWARD,
+            Icons::CHEVRON_LEFT,
+            Icons::CHEVRON_RIGHT,
+            Icons::EXPAND_MORE,
+            Icons::EXPAND_LESS,
+            Icons::SEARCH,
+            Icons::HOME,
+            Icons::EDIT,
+            Icons::DELETE,
+            Icons::REFRESH,
+            Icons::INFO,
+            Icons::WARNING,
+            Icons::ERROR,
+        ];
+
+        for icon in icons {
+            assert!(icon.path().is_some(), "{:?}", icon);
+        }
+    }
+
+    #[test]
+    fn paths_are_parsed_again_only_once_data_changes() {
+        let mut cache = PathCache::default();
+
+        let add = "M19 13h-6v6h-2v-6H5v-2h6V5h2v6h6v2z";
+        let elements = cache.get(add).unwrap().elements().as_ptr();
+        assert_eq!(cache.get(add).unwrap().elements().as_ptr(), elements);
+
+        assert!(cache.get("invalid").is_none());
+        assert!(cache.get("M19 13H5v-2h14v2z").is_some());
+    }
+}
@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+
+use frui::app::diagnostics::{CacheUsage, MemoryDiagnostics};
+
+use super::{ImageData, ImageSource};
+
+/// Images decoded from [`ImageSource`]s, shared by all [`Image`](super::Image)
+/// widgets displaying the same source.
+///
+/// Once the cache grows over its limits, least recently used images are
+/// evicted. Images which are still displayed stay in memory until their
+/// widgets are removed, but are decoded again once displayed anew.
+pub struct ImageCache;
+
+impl ImageCache {
+    pub const DEFAULT_MAX_BYTES: usize = 100 * 1024 * 1024;
+    pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+    /// Sets the maximum number of bytes of decoded frames retained by the
+    /// cache, evicting images over it. Images larger than that aren't cached
+    /// at all.
+    pub fn set_max_bytes(max_bytes: usize) {
+        Self::with(|c| {
+            c.max_bytes = max_bytes;
+            c.evict();
+        });
+    }
+
+    pub fn set_max_entries(max_entries: usize) {
+        Self::with(|c| {
+            c.max_entries = max_entries;
+            c.evict();
+        });
+    }
+
+    /// Image decoded from `source`, if it's cached.
+    pub fn get(source: &ImageSource) -> Option<ImageData> {
+        CACHE.with(|c| {
+            let mut c = c.borrow_mut();
+            let index = c.entries.iter().position(|(s, _)| s == source)?;
+
+            // Keep the entries ordered from the least recently used.
+            let entry = c.entries.remove(index);
+            let image = entry.1.clone();
+            c.entries.push(entry);
+
+            Some(image)
+        })
+    }
+
+    /// Caches `image` decoded from `source`, e.g. to have it displayed right
+    /// away once it is needed. Images larger than the maximum number of bytes
+    /// aren't cached, and don't evict other images.
+    pub fn insert(source: ImageSource, image: ImageData) {
+        Self::with(|c| {
+            c.remove(&source);
+
+            if image.decoded_bytes() <= c.max_bytes {
+                c.bytes += image.decoded_bytes();
+                c.entries.push((source, image));
+                c.evict();
+            }
+        });
+    }
+
+    pub fn remove(source: &ImageSource) -> bool {
+        Self::with(|c| c.remove(source))
+    }
+
+    pub fn clear() {
+        Self::with(|c| {
+            c.entries.clear();
+            c.bytes = 0;
+        });
+    }
+
+    pub fn usage() -> CacheUsage {
+        CACHE.with(|c| c.borrow().usage())
+    }
+
+    /// Changes the cache, reporting its usage afterwards if it changed.
+    fn with<R>(f: impl FnOnce(&mut Cache) -> R) -> R {
+        let (r, usage) = CACHE.with(|c| {
+            let mut c = c.borrow_mut();
+            let before = c.usage();
+            let r = f(&mut c);
+
+            (r, Some(c.usage()).filter(|usage| *usage != before))
+        });
+
+        if let Some(usage) = usage {
+            MemoryDiagnostics::report_cache("image cache", usage);
+        }
+
+        r
+    }
+}
+
+struct Cache {
+    /// Ordered from the least recently used.
+    entries: Vec<(ImageSource, ImageData)>,
+    /// Decoded bytes of all entries.
+    bytes: usize,
+    max_bytes: usize,
+    max_entries: usize,
+}
+
+impl Cache {
+    fn usage(&self) -> CacheUsage {
+        CacheUsage {
+            entries: self.entries.len(),
+            bytes: self.bytes,
+        }
+    }
+
+    fn remove(&mut self, source: &ImageSource) -> bool {
+        match self.entries.iter().position(|(s, _)| s == source) {
+            Some(index) => {
+                let (_, image) = self.entries.remove(index);
+                self.bytes -= image.decoded_bytes();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn evict(&mut self) {
+        let mut evicted = 0;
+
+        for (_, image) in &self.entries {
+            if self.bytes <= self.max_bytes && self.entries.len() - evicted <= self.max_entries {
+                break;
+            }
+
+            self.bytes -= image.decoded_bytes();
+            evicted += 1;
+        }
+
+        self.entries.drain(..evicted);
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<Cache> = RefCell::new(Cache {
+        entries: Vec::new(),
+        bytes: 0,
+        max_bytes: ImageCache::DEFAULT_MAX_BYTES,
+        max_entries: ImageCache::DEFAULT_MAX_ENTRIES,
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use druid_shell::piet::{ImageBuf, ImageFormat};
+
+    use super::*;
+
+    fn image(width: usize) -> ImageData {
+        let pixels = vec![0u8; width * 4];
+        ImageData::new(ImageBuf::from_raw(
+            pixels,
+            ImageFormat::RgbaSeparate,
+            width,
+            1,
+        ))
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let source = |name: &str| ImageSource::File(name.into());
+
+        ImageCache::set_max_bytes(100);
+        ImageCache::insert(source("a"), image(10));
+        ImageCache::insert(source("b"), image(10));
+
+        // Using `a` makes `b` the least recently used image.
+        assert!(ImageCache::get(&source("a")).is_some());
+        ImageCache::insert(source("c"), image(10));
+
+        assert!(ImageCache::get(&source("b")).is_none());
+        assert_eq!(ImageCache::usage().entries, 2);
+
+        // Images over the limit aren't cached, and don't evict other images.
+        ImageCache::insert(source("d"), image(30));
+        assert!(ImageCache::get(&source("d")).is_none());
+        assert_eq!(ImageCache::usage().entries, 2);
+        assert_eq!(ImageCache::usage().bytes, 80);
+
+        ImageCache::set_max_entries(1);
+        assert!(ImageCache::get(&source("a")).is_none());
+        assert_eq!(ImageCache::usage().bytes, 40);
+    }
+
+    #[test]
+    fn memory_sources_are_compared_by_content() {
+        let bytes = vec![1u8, 2, 3];
+
+        assert_eq!(
+            ImageSource::memory(bytes.clone()),
+            ImageSource::memory(bytes)
+        );
+        assert_ne!(
+            ImageSource::memory(vec![1u8, 2, 3]),
+            ImageSource::memory(vec![1u8, 2])
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    io::Cursor,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use druid_shell::piet::{ImageBuf, ImageFormat as PixelFormat};
+use frui::{
+    app::diagnostics::{CacheUsage, MemoryDiagnostics},
+    prelude::*,
+};
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    AnimationDecoder, Frames, ImageFormat,
+};
+
+/// Animations whose decoded frames would take more memory than this are
+/// decoded frame by frame while they play, instead of all at once.
+const DECODED_FRAMES_BUDGET: usize = 32 * 1024 * 1024;
+
+/// Decoded image, either a still image or an animation (GIF or APNG).
+/// Cloning it is cheap.
+#[derive(Clone)]
+pub struct ImageData {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    width: usize,
+    height: usize,
+    /// How long each frame is shown. Empty for still images.
+    delays: Vec<Duration>,
+    frames: FrameStorage,
+}
+
+enum FrameStorage {
+    Decoded(Vec<ImageBuf>),
+    /// Frames decoded on demand, keeping only the last one.
+    Streamed(RefCell<FrameStream>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageError(pub(super) String);
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "image decoding failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<image::ImageError> for ImageError {
+    fn from(e: image::ImageError) -> Self {
+        ImageError(e.to_string())
+    }
+}
+
+/// Image decoded on a loading thread, which is turned into [`ImageData`]
+/// once it's sent to the UI thread.
+pub(super) struct DecodedImage {
+    width: usize,
+    height: usize,
+    delays: Vec<Duration>,
+    frames: DecodedFrames,
+}
+
+enum DecodedFrames {
+    All(Vec<ImageBuf>),
+    /// Animation too large to keep all of its frames decoded.
+    Streamed(Arc<[u8]>, AnimationFormat),
+}
+
+impl DecodedImage {
+    /// Decodes an image in any supported format. All frames of GIF and APNG
+    /// animations are decoded, unless they would take too much memory.
+    pub(super) fn decode(bytes: Arc<[u8]>) -> Result<Self, ImageError> {
+        match image::guess_format(&bytes)? {
+            ImageFormat::Gif => Self::decode_animation(bytes, AnimationFormat::Gif),
+            ImageFormat::Png if PngDecoder::new(Cursor::new(&*bytes))?.is_apng() => {
+                Self::decode_animation(bytes, AnimationFormat::Apng)
+            }
+            _ => {
+                let image = rgba_image(image::load_from_memory(&bytes)?.into_rgba8());
+
+                Ok(DecodedImage {
+                    width: image.width(),
+                    height: image.height(),
+                    delays: Vec::new(),
+                    frames: DecodedFrames::All(vec![image]),
+                })
+            }
+        }
+    }
+
+    fn decode_animation(bytes: Arc<[u8]>, format: AnimationFormat) -> Result<Self, ImageError> {
+        let (mut width, mut height) = (0, 0);
+        let mut delays = Vec::new();
+        let mut decoded = Some(Vec::new());
+
+        for frame in format.frames(&bytes)? {
+            let frame = frame?;
+            delays.push(frame_delay(&frame));
+
+            let image = frame.into_buffer();
+            (width, height) = (image.width() as usize, image.height() as usize);
+
+            if let Some(frames) = &mut decoded {
+                if (frames.len() + 1) * width * height * 4 > DECODED_FRAMES_BUDGET {
+                    decoded = None;
+                } else {
+                    frames.push(rgba_image(image));
+                }
+            }
+        }
+
+        if delays.is_empty() {
+            return Err(ImageError("animation has no frames".into()));
+        }
+
+        Ok(DecodedImage {
+            width,
+            height,
+            delays,
+            frames: match decoded {
+                Some(frames) => DecodedFrames::All(frames),
+                None => DecodedFrames::Streamed(bytes, format),
+            },
+        })
+    }
+}
+
+impl ImageData {
+    pub fn new(image: ImageBuf) -> Self {
+        Self::from_frames(image.width(), image.height(), Vec::new(), vec![image])
+    }
+
+    /// Decodes an image in any supported format. All frames of GIF and APNG
+    /// animations are decoded, unless they would take too much memory.
+    pub fn decode(bytes: impl Into<Arc<[u8]>>) -> Result<Self, ImageError> {
+        DecodedImage::decode(bytes.into()).map(Self::from_decoded)
+    }
+
+    pub(super) fn from_decoded(image: DecodedImage) -> Self {
+        let DecodedImage {
+            width,
+            height,
+            delays,
+            frames,
+        } = image;
+
+        let frames = match frames {
+            DecodedFrames::All(frames) => FrameStorage::Decoded(frames),
+            DecodedFrames::Streamed(bytes, format) => {
+                FrameStorage::Streamed(RefCell::new(FrameStream {
+                    bytes,
+                    format,
+                    frames: None,
+                    current: None,
+                }))
+            }
+        };
+
+        Self::from_inner(Inner {
+            width,
+            height,
+            delays,
+            frames,
+        })
+    }
+
+    fn from_frames(
+        width: usize,
+        height: usize,
+        delays: Vec<Duration>,
+        frames: Vec<ImageBuf>,
+    ) -> Self {
+        Self::from_inner(Inner {
+            width,
+            height,
+            delays,
+            frames: FrameStorage::Decoded(frames),
+        })
+    }
+
+    fn from_inner(inner: Inner) -> Self {
+        DECODED_BYTES.with(|b| b.set(b.get() + inner.decoded_bytes()));
+        LIVE_IMAGES.with(|i| i.set(i.get() + 1));
+        report_usage();
+
+        ImageData {
+            inner: Rc::new(inner),
+        }
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> usize {
+        self.inner.width
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> usize {
+        self.inner.height
+    }
+
+    /// Size in pixels.
+    pub fn size(&self) -> Size {
+        Size::new(self.inner.width as f64, self.inner.height as f64)
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.inner.delays.len() > 1
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.inner.delays.len().max(1)
+    }
+
+    /// Duration of one loop of the animation, zero for still images.
+    pub fn duration(&self) -> Duration {
+        self.inner.delays.iter().sum()
+    }
+
+    /// Index of the frame shown `elapsed` into a loop of the animation.
+    pub fn frame_index_at(&self, elapsed: Duration) -> usize {
+        let mut end = Duration::ZERO;
+
+        for (index, delay) in self.inner.delays.iter().enumerate() {
+            end += *delay;
+
+            if elapsed < end {
+                return index;
+            }
+        }
+
+        self.frame_count() - 1
+    }
+
+    /// Frame at `index`, or `None` if there is no such frame or it couldn't
+    /// be decoded.
+    pub fn frame(&self, index: usize) -> Option<ImageBuf> {
+        match &self.inner.frames {
+            FrameStorage::Decoded(frames) => frames.get(index).cloned(),
+            FrameStorage::Streamed(stream) => stream.borrow_mut().frame(index),
+        }
+    }
+
+    /// Bytes of frames kept decoded for the whole life of the image.
+    pub(super) fn decoded_bytes(&self) -> usize {
+        self.inner.decoded_bytes()
+    }
+
+    /// Whether both refer to the same decoded image.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Inner {
+    /// Bytes of frames kept decoded for the whole life of the image.
+    fn decoded_bytes(&self) -> usize {
+        match &self.frames {
+            FrameStorage::Decoded(frames) => frames.len() * self.width * self.height * 4,
+            FrameStorage::Streamed(_) => self.width * self.height * 4,
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let bytes = self.decoded_bytes();
+
+        // Images may outlive the counters when dropped while the thread exits.
+        let counted = DECODED_BYTES.try_with(|b| b.set(b.get() - bytes)).is_ok()
+            && LIVE_IMAGES.try_with(|i| i.set(i.get() - 1)).is_ok();
+
+        if counted {
+            report_usage();
+        }
+    }
+}
+
+impl PartialEq for ImageData {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl fmt::Debug for ImageData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImageData")
+            .field("width", &self.inner.width)
+            .field("height", &self.inner.height)
+            .field("frames", &self.frame_count())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AnimationFormat {
+    Gif,
+    Apng,
+}
+
+impl AnimationFormat {
+    fn frames(self, bytes: &Arc<[u8]>) -> Result<Frames<'static>, ImageError> {
+        let reader = Cursor::new(bytes.clone());
+
+        Ok(match self {
+            AnimationFormat::Gif => GifDecoder::new(reader)?.into_frames(),
+            AnimationFormat::Apng => PngDecoder::new(reader)?.apng().into_frames(),
+        })
+    }
+}
+
+/// Decodes frames of an animation one after another, starting over once an
+/// earlier frame is requested (e.g. when the animation loops).
+struct FrameStream {
+    bytes: Arc<[u8]>,
+    format: AnimationFormat,
+    /// Remaining frames, and the index of the next one.
+    frames: Option<(Frames<'static>, usize)>,
+    current: Option<(usize, ImageBuf)>,
+}
+
+impl FrameStream {
+    fn frame(&mut self, index: usize) -> Option<ImageBuf> {
+        if let Some((current, image)) = &self.current {
+            if *current == index {
+                return Some(image.clone());
+            }
+        }
+
+        if !matches!(&self.frames, Some((_, next)) if *next <= index) {
+            self.frames = Some((self.format.frames(&self.bytes).ok()?, 0));
+        }
+
+        let (frames, next) = self.frames.as_mut()?;
+
+        while *next <= index {
+            let frame = frames.next()?.ok()?;
+            *next += 1;
+
+            if *next > index {
+                let image = rgba_image(frame.into_buffer());
+                self.current = Some((index, image.clone()));
+                return Some(image);
+            }
+        }
+
+        None
+    }
+}
+
+/// Browsers show frames with delays of up to 10 ms for 100 ms, since many
+/// GIFs rely on that.
+fn frame_delay(frame: &image::Frame) -> Duration {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    let delay = Duration::from_secs_f64(numer as f64 / denom.max(1) as f64 / 1000.);
+
+    match delay <= Duration::from_millis(10) {
+        true => Duration::from_millis(100),
+        false => delay,
+    }
+}
+
+fn rgba_image(image: image::RgbaImage) -> ImageBuf {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    ImageBuf::from_raw(image.into_raw(), PixelFormat::RgbaSeparate, width, height)
+}
+
+fn report_usage() {
+    let usage = CacheUsage {
+        entries: LIVE_IMAGES.with(|i| i.get()),
+        bytes: DECODED_BYTES.with(|b| b.get()),
+    };
+
+    MemoryDiagnostics::report_cache("decoded images", usage);
+}
+
+thread_local! {
+    /// Bytes of frames retained by all live images.
+    static DECODED_BYTES: Cell<usize> = Default::default();
+    static LIVE_IMAGES: Cell<usize> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frame_index_at() {
+        let ms = Duration::from_millis;
+        let image = ImageData::from_frames(1, 1, vec![ms(100), ms(50), ms(100)], Vec::new());
+
+        assert_eq!(image.duration(), ms(250));
+        assert_eq!(image.frame_index_at(ms(0)), 0);
+        assert_eq!(image.frame_index_at(ms(120)), 1);
+        assert_eq!(image.frame_index_at(ms(150)), 2);
+        assert_eq!(image.frame_index_at(ms(400)), 2);
+    }
+}
@@ -0,0 +1,20 @@
+//! Loading, decoding and caching of raster images, and the [`Image`] widget
+//! displaying them.
+//!
+//! Animated GIF and APNG images are decoded into all of their frames, unless
+//! those would take too much memory. Frames of such large animations are
+//! decoded one by one as the animation plays.
+//!
+//! BlurHash and ThumbHash previews can be decoded as well, to be shown while
+//! the actual image loads.
+
+pub use cache::*;
+pub use data::*;
+pub use source::*;
+pub use widget::*;
+
+mod cache;
+mod data;
+mod placeholder;
+mod source;
+mod widget;
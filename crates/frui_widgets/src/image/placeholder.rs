@@ -0,0 +1,293 @@
+//! Decoding of BlurHash and ThumbHash strings: compact, blurry previews of
+//! images, which are usually sent along with the URL of the image and shown
+//! while it loads.
+
+use std::f64::consts::PI;
+
+use druid_shell::piet::{ImageBuf, ImageFormat};
+
+use super::{ImageData, ImageError};
+
+const BASE83: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+impl ImageData {
+    /// Decodes a [BlurHash](https://blurha.sh) into an image of `width` by
+    /// `height` pixels. Since the preview is blurry anyway, a small size
+    /// (e.g. 32 by 32) stretched over the final size looks the same and is
+    /// much cheaper to decode.
+    pub fn from_blurhash(hash: &str, width: usize, height: usize) -> Result<Self, ImageError> {
+        let hash = hash.as_bytes();
+
+        if hash.len() < 6 {
+            return Err(ImageError("BlurHash is too short".into()));
+        }
+
+        let size_flag = decode_base83(&hash[0..1])?;
+        let (components_x, components_y) = (size_flag % 9 + 1, size_flag / 9 + 1);
+
+        if hash.len() != 4 + 2 * components_x * components_y {
+            return Err(ImageError("BlurHash has invalid length".into()));
+        }
+
+        let max_ac = (decode_base83(&hash[1..2])? + 1) as f64 / 166.;
+
+        let mut colors = Vec::with_capacity(components_x * components_y);
+
+        let dc = decode_base83(&hash[2..6])?;
+        colors.push([dc >> 16, (dc >> 8) & 255, dc & 255].map(|c| srgb_to_linear(c as u8)));
+
+        for chunk in hash[6..].chunks(2) {
+            let ac = decode_base83(chunk)?;
+            let quantized = [ac / (19 * 19), (ac / 19) % 19, ac % 19];
+
+            colors.push(quantized.map(|q| {
+                let v = (q as f64 - 9.) / 9.;
+                v.signum() * v * v * max_ac
+            }));
+        }
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut pixel = [0.; 3];
+
+                for j in 0..components_y {
+                    for i in 0..components_x {
+                        let basis = (PI * x as f64 * i as f64 / width as f64).cos()
+                            * (PI * y as f64 * j as f64 / height as f64).cos();
+
+                        let color = colors[i + j * components_x];
+                        for (p, c) in pixel.iter_mut().zip(color) {
+                            *p += c * basis;
+                        }
+                    }
+                }
+
+                pixels.extend(pixel.map(linear_to_srgb));
+                pixels.push(255);
+            }
+        }
+
+        Ok(rgba_image(pixels, width, height))
+    }
+
+    /// Decodes a [ThumbHash](https://evanw.github.io/thumbhash/). Unlike
+    /// BlurHash, it encodes the aspect ratio and transparency of the image.
+    /// The decoded image is at most 32 pixels wide and high.
+    pub fn from_thumbhash(hash: &[u8]) -> Result<Self, ImageError> {
+        if hash.len() < 5 {
+            return Err(ImageError("ThumbHash is too short".into()));
+        }
+
+        let header24 = hash[0] as u32 | (hash[1] as u32) << 8 | (hash[2] as u32) << 16;
+        let header16 = hash[3] as u32 | (hash[4] as u32) << 8;
+
+        let l_dc = (header24 & 63) as f64 / 63.;
+        let p_dc = ((header24 >> 6) & 63) as f64 / 31.5 - 1.;
+        let q_dc = ((header24 >> 12) & 63) as f64 / 31.5 - 1.;
+        let l_scale = ((header24 >> 18) & 31) as f64 / 31.;
+        let has_alpha = header24 >> 23 != 0;
+        let p_scale = ((header16 >> 3) & 63) as f64 / 63.;
+        let q_scale = ((header16 >> 9) & 63) as f64 / 63.;
+        let is_landscape = header16 >> 15 != 0;
+
+        let (lx, ly) = thumbhash_components(header16, has_alpha, is_landscape);
+        let (lx, ly) = (lx.max(3), ly.max(3));
+
+        let (a_dc, a_scale) = match has_alpha {
+            true => {
+                let byte = *hash
+                    .get(5)
+                    .ok_or_else(|| ImageError("ThumbHash is too short".into()))?;
+                ((byte & 15) as f64 / 15., (byte >> 4) as f64 / 15.)
+            }
+            false => (1., 0.),
+        };
+
+        let mut reader = NibbleReader {
+            hash,
+            start: if has_alpha { 6 } else { 5 },
+            index: 0,
+        };
+
+        // Saturation is boosted to compensate for quantization.
+        let l_ac = reader.channel(lx, ly, l_scale)?;
+        let p_ac = reader.channel(3, 3, p_scale * 1.25)?;
+        let q_ac = reader.channel(3, 3, q_scale * 1.25)?;
+        let a_ac = match has_alpha {
+            true => reader.channel(5, 5, a_scale)?,
+            false => Vec::new(),
+        };
+
+        let (ratio_x, ratio_y) = thumbhash_components(header16, has_alpha, is_landscape);
+        let ratio = ratio_x as f64 / ratio_y as f64;
+
+        let (width, height) = match ratio > 1. {
+            true => (32, (32. / ratio).round() as usize),
+            false => ((32. * ratio).round() as usize, 32),
+        };
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        let (mut fx, mut fy) = ([0.; 7], [0.; 7]);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (mut l, mut p, mut q, mut a) = (l_dc, p_dc, q_dc, a_dc);
+
+                let n = lx.max(if has_alpha { 5 } else { 3 });
+                for (cx, f) in fx.iter_mut().enumerate().take(n) {
+                    *f = (PI / width as f64 * (x as f64 + 0.5) * cx as f64).cos();
+                }
+
+                let n = ly.max(if has_alpha { 5 } else { 3 });
+                for (cy, f) in fy.iter_mut().enumerate().take(n) {
+                    *f = (PI / height as f64 * (y as f64 + 0.5) * cy as f64).cos();
+                }
+
+                for (j, (cx, cy)) in triangle(lx, ly).enumerate() {
+                    l += l_ac[j] * fx[cx] * fy[cy] * 2.;
+                }
+
+                for (j, (cx, cy)) in triangle(3, 3).enumerate() {
+                    let f = fx[cx] * fy[cy] * 2.;
+                    p += p_ac[j] * f;
+                    q += q_ac[j] * f;
+                }
+
+                if has_alpha {
+                    for (j, (cx, cy)) in triangle(5, 5).enumerate() {
+                        a += a_ac[j] * fx[cx] * fy[cy] * 2.;
+                    }
+                }
+
+                let b = l - 2. / 3. * p;
+                let r = (3. * l - b + q) / 2.;
+                let g = r - q;
+
+                pixels.extend([r, g, b, a].map(|c| (255. * c.clamp(0., 1.)) as u8));
+            }
+        }
+
+        Ok(rgba_image(pixels, width, height))
+    }
+}
+
+/// Number of luminance components along both axes, before limiting them to
+/// at least 3. Their ratio is the aspect ratio of the image.
+fn thumbhash_components(header16: u32, has_alpha: bool, is_landscape: bool) -> (usize, usize) {
+    let encoded = (header16 & 7) as usize;
+    let other = if has_alpha { 5 } else { 7 };
+
+    match is_landscape {
+        true => (other, encoded),
+        false => (encoded, other),
+    }
+}
+
+/// Positions of AC components of a channel with `nx` by `ny` components, in
+/// the order they are encoded. Only the upper-left triangle is encoded,
+/// without the DC component.
+fn triangle(nx: usize, ny: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..ny).flat_map(move |cy| {
+        let start = if cy == 0 { 1 } else { 0 };
+        (start..nx)
+            .take_while(move |cx| cx * ny < nx * (ny - cy))
+            .map(move |cx| (cx, cy))
+    })
+}
+
+/// Reads 4-bit AC components of a ThumbHash, low bits of each byte first.
+struct NibbleReader<'a> {
+    hash: &'a [u8],
+    start: usize,
+    index: usize,
+}
+
+impl NibbleReader<'_> {
+    fn channel(&mut self, nx: usize, ny: usize, scale: f64) -> Result<Vec<f64>, ImageError> {
+        triangle(nx, ny)
+            .map(|_| {
+                let byte = self
+                    .hash
+                    .get(self.start + self.index / 2)
+                    .ok_or_else(|| ImageError("ThumbHash is too short".into()))?;
+                let nibble = (byte >> ((self.index & 1) * 4)) & 15;
+                self.index += 1;
+
+                Ok((nibble as f64 / 7.5 - 1.) * scale)
+            })
+            .collect()
+    }
+}
+
+fn decode_base83(chars: &[u8]) -> Result<usize, ImageError> {
+    chars
+        .iter()
+        .try_fold(0, |value, c| match BASE83.iter().position(|b| b == c) {
+            Some(digit) => Ok(value * 83 + digit),
+            None => Err(ImageError("BlurHash contains invalid characters".into())),
+        })
+}
+
+fn srgb_to_linear(value: u8) -> f64 {
+    let v = value as f64 / 255.;
+
+    match v <= 0.04045 {
+        true => v / 12.92,
+        false => ((v + 0.055) / 1.055).powf(2.4),
+    }
+}
+
+fn linear_to_srgb(value: f64) -> u8 {
+    let v = value.clamp(0., 1.);
+
+    let srgb = match v <= 0.0031308 {
+        true => v * 12.92,
+        false => 1.055 * v.powf(1. / 2.4) - 0.055,
+    };
+
+    (srgb * 255. + 0.5) as u8
+}
+
+fn rgba_image(pixels: Vec<u8>, width: usize, height: usize) -> ImageData {
+    ImageData::new(ImageBuf::from_raw(
+        pixels,
+        ImageFormat::RgbaSeparate,
+        width,
+        height,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn blurhash() {
+        let image = ImageData::from_blurhash("LEHV6nWB2yk8pyo0adR*.7kCMdnj", 32, 24).unwrap();
+        assert_eq!((image.width(), image.height()), (32, 24));
+
+        assert!(ImageData::from_blurhash("LEHV6nWB2yk8", 32, 32).is_err());
+        assert!(ImageData::from_blurhash("LEHV6nWB2yk8pyo0adR*.7kCMdn\"", 32, 32).is_err());
+    }
+
+    #[test]
+    fn thumbhash_without_ac_is_uniform() {
+        // Opaque, white, 7 by 7 components (square) and all scales zero.
+        let mut hash = vec![63, 8, 2, 7, 0];
+        hash.resize(24, 0);
+
+        let image = ImageData::from_thumbhash(&hash).unwrap();
+        assert_eq!((image.width(), image.height()), (32, 32));
+
+        let frame = image.frame(0).unwrap();
+        let pixels = frame.raw_pixels();
+        assert!(pixels.chunks(4).all(|p| p == &pixels[0..4]));
+        assert_eq!(pixels[3], 255);
+
+        hash.truncate(20);
+        assert!(ImageData::from_thumbhash(&hash).is_err());
+    }
+}
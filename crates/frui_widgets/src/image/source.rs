@@ -0,0 +1,310 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use frui::app::channels::{BinaryCodec, Channels, MessageChannel};
+
+use super::{DecodedImage, ImageCache, ImageData, ImageError};
+
+/// Channel through which finished loads wake the UI thread.
+const LOADS_CHANNEL: &str = "frui/image_loads";
+
+/// Where an [`Image`](super::Image) comes from.
+///
+/// Images of sources other than [`ImageSource::Decoded`] are loaded and
+/// decoded asynchronously, and are kept in the [`ImageCache`] afterwards.
+#[derive(Clone)]
+pub enum ImageSource {
+    Decoded(ImageData),
+    /// Encoded image (PNG, JPEG or GIF) in memory. Sources are equal if
+    /// their bytes are.
+    Memory(ImageBytes),
+    File(PathBuf),
+    /// URL of an image, fetched with an HTTP GET request. Requires the
+    /// `network-images` feature.
+    Network(String),
+}
+
+impl ImageSource {
+    /// Image of this source if it doesn't need to be loaded, i.e. it's either
+    /// decoded already or it's in the [`ImageCache`].
+    pub fn cached(&self) -> Option<ImageData> {
+        match self {
+            ImageSource::Decoded(image) => Some(image.clone()),
+            _ => ImageCache::get(self),
+        }
+    }
+
+    /// Source of an encoded image (PNG, JPEG or GIF) in memory.
+    pub fn memory(bytes: impl Into<Arc<[u8]>>) -> Self {
+        ImageSource::Memory(ImageBytes::new(bytes))
+    }
+
+    /// Loads and decodes the image in the background, calling `on_loaded` on
+    /// the UI thread once it's done (but never before this function returns).
+    ///
+    /// Loading the same source again while it's loading doesn't start another
+    /// load.
+    pub fn load(&self, on_loaded: impl FnOnce(Result<ImageData, ImageError>) + 'static) {
+        let on_loaded = Box::new(on_loaded);
+
+        let fetch = match self {
+            ImageSource::Decoded(_) => Fetch::Decoded,
+            ImageSource::Memory(bytes) => Fetch::Memory(bytes.bytes.clone()),
+            ImageSource::File(path) => Fetch::File(path.clone()),
+            ImageSource::Network(url) => Fetch::Network(url.clone()),
+        };
+
+        let id = LOADS.with(|loads| {
+            let mut loads = loads.borrow_mut();
+
+            if let Some((_, load)) = loads.iter_mut().find(|(_, l)| l.source == *self) {
+                load.callbacks.push(on_loaded);
+                return None;
+            }
+
+            let id = NEXT_LOAD_ID.fetch_add(1, Ordering::Relaxed);
+            let load = Load {
+                source: self.clone(),
+                callbacks: vec![on_loaded],
+            };
+            loads.insert(id, load);
+
+            Some(id)
+        });
+
+        if let Some(id) = id {
+            listen_for_loads();
+
+            std::thread::spawn(move || {
+                let image = fetch.run();
+
+                FETCHED.lock().unwrap().push((id, image));
+                Channels::post(LOADS_CHANNEL, Vec::new(), |_| {});
+            });
+        }
+    }
+}
+
+impl From<ImageData> for ImageSource {
+    fn from(image: ImageData) -> Self {
+        ImageSource::Decoded(image)
+    }
+}
+
+impl PartialEq for ImageSource {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ImageSource::Decoded(a), ImageSource::Decoded(b)) => a == b,
+            (ImageSource::Memory(a), ImageSource::Memory(b)) => a == b,
+            (ImageSource::File(a), ImageSource::File(b)) => a == b,
+            (ImageSource::Network(a), ImageSource::Network(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for ImageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageSource::Decoded(image) => f.debug_tuple("Decoded").field(image).finish(),
+            ImageSource::Memory(bytes) => write!(f, "Memory({} bytes)", bytes.bytes.len()),
+            ImageSource::File(path) => f.debug_tuple("File").field(path).finish(),
+            ImageSource::Network(url) => f.debug_tuple("Network").field(url).finish(),
+        }
+    }
+}
+
+/// Encoded image in memory, compared by its content. Its hash is computed
+/// once, so that comparing different images is cheap.
+#[derive(Clone)]
+pub struct ImageBytes {
+    bytes: Arc<[u8]>,
+    hash: u64,
+}
+
+impl ImageBytes {
+    pub fn new(bytes: impl Into<Arc<[u8]>>) -> Self {
+        let bytes = bytes.into();
+
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+
+        ImageBytes {
+            hash: hasher.finish(),
+            bytes,
+        }
+    }
+
+    pub fn bytes(&self) -> &Arc<[u8]> {
+        &self.bytes
+    }
+}
+
+impl PartialEq for ImageBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && (Arc::ptr_eq(&self.bytes, &other.bytes) || self.bytes == other.bytes)
+    }
+}
+
+/// Part of an [`ImageSource`] which can be sent to the loading thread.
+enum Fetch {
+    Decoded,
+    Memory(Arc<[u8]>),
+    File(PathBuf),
+    Network(String),
+}
+
+impl Fetch {
+    /// Fetches and decodes the image. `None` for decoded images, which aren't
+    /// loaded.
+    fn run(self) -> Result<Option<DecodedImage>, ImageError> {
+        let bytes: Arc<[u8]> = match self {
+            Fetch::Decoded => return Ok(None),
+            Fetch::Memory(bytes) => bytes,
+            Fetch::File(path) => match std::fs::read(&path) {
+                Ok(bytes) => bytes.into(),
+                Err(e) => {
+                    let e = format!("reading {} failed: {}", path.display(), e);
+                    return Err(ImageError(e));
+                }
+            },
+            Fetch::Network(url) => fetch_url(&url).map_err(ImageError)?.into(),
+        };
+
+        DecodedImage::decode(bytes).map(Some)
+    }
+}
+
+#[cfg(feature = "network-images")]
+fn fetch_url(url: &str) -> Result<Vec<u8>, String> {
+    use std::io::Read;
+
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("fetching {} failed: {}", url, e))?;
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("fetching {} failed: {}", url, e))?;
+
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "network-images"))]
+fn fetch_url(url: &str) -> Result<Vec<u8>, String> {
+    Err(format!(
+        "fetching {} failed: loading images from the network requires the \
+         `network-images` feature",
+        url
+    ))
+}
+
+struct Load {
+    source: ImageSource,
+    callbacks: Vec<Box<dyn FnOnce(Result<ImageData, ImageError>)>>,
+}
+
+/// Handles messages posted by loading threads, once per thread.
+fn listen_for_loads() {
+    if LISTENING.with(|l| l.replace(true)) {
+        return;
+    }
+
+    MessageChannel::new(LOADS_CHANNEL, BinaryCodec).set_handler(|_| {
+        finish_loads();
+        None
+    });
+}
+
+/// Caches images decoded by loading threads and calls callbacks of their
+/// loads.
+fn finish_loads() {
+    // Other UI threads (e.g. of offscreen rendering) finish their own loads.
+    let fetched = {
+        let mut fetched = FETCHED.lock().unwrap();
+        let (ours, others) = std::mem::take(&mut *fetched)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(id, _)| LOADS.with(|l| l.borrow().contains_key(id)));
+
+        *fetched = others;
+        ours
+    };
+
+    for (id, image) in fetched {
+        let load = match LOADS.with(|l| l.borrow_mut().remove(&id)) {
+            Some(load) => load,
+            None => continue,
+        };
+
+        let result = match image {
+            Ok(Some(image)) => Ok(ImageData::from_decoded(image)),
+            Ok(None) => Ok(load.source.cached().unwrap()),
+            Err(e) => Err(e),
+        };
+
+        match &result {
+            Ok(_) if matches!(load.source, ImageSource::Decoded(_)) => {}
+            Ok(image) => ImageCache::insert(load.source.clone(), image.clone()),
+            Err(e) => log::warn!("loading image {:?} failed: {}", load.source, e),
+        }
+
+        for callback in load.callbacks {
+            callback(result.clone());
+        }
+    }
+}
+
+/// Images decoded by loading threads, waiting to be picked up by the UI
+/// thread which started their loads.
+static FETCHED: Mutex<Vec<(u64, Result<Option<DecodedImage>, ImageError>)>> =
+    Mutex::new(Vec::new());
+
+static NEXT_LOAD_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static LOADS: RefCell<HashMap<u64, Load>> = Default::default();
+    static LISTENING: Cell<bool> = Cell::new(false);
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+            .write_to(&mut bytes, ImageOutputFormat::Png)
+            .unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn decodes_on_loading_thread() {
+        let fetch = Fetch::Memory(png(3, 2).into());
+        let decoded = std::thread::spawn(move || fetch.run()).join().unwrap();
+
+        let image = ImageData::from_decoded(decoded.unwrap().unwrap());
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert!(!image.is_animated());
+
+        assert!(Fetch::Memory(vec![1u8, 2, 3].into()).run().is_err());
+        assert!(Fetch::File("missing.png".into()).run().is_err());
+        assert!(matches!(Fetch::Decoded.run(), Ok(None)));
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    cell::{Cell, RefCell},
+    path::PathBuf,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use druid_shell::piet::{ImageBuf, InterpolationMode};
+use frui::{prelude::*, render::*};
+
+use crate::*;
+
+/// Displays an image from an [`ImageSource`], inscribed into the widget
+/// according to `fit` and `alignment`.
+///
+/// Unless `width` or `height` is set, the widget has the size of the image
+/// (one pixel per display point). If only one of them is set, the other one
+/// keeps the aspect ratio of the image.
+///
+/// Images which aren't decoded yet are loaded in the background. Until they
+/// are, the `placeholder` is shown (if loading fails, the `error` widget
+/// is), sized to `width` and `height` where they're set. Loaded images are
+/// kept in the [`ImageCache`], so other widgets displaying the same source
+/// show them right away.
+///
+/// ```ignore
+/// Image::network("https://example.com/avatar.jpg")
+///     .width(48.)
+///     .height(48.)
+///     .fit(BoxFit::Cover)
+///     .placeholder(Image::new(ImageData::from_blurhash(hash, 8, 8)?).fit(BoxFit::Cover))
+///     .error(Icon::new(Icons::ERROR))
+/// ```
+///
+/// Animated images loop with the frame timing from their file. A
+/// `controller` can be passed to pause, resume or seek the animation; its
+/// duration is set to the duration of the image, and its progress maps to
+/// the time within one loop of it.
+///
+/// Without a `controller`, the animation is paused while the image isn't
+/// painted (e.g. while it's scrolled out of view) and resumes once it is.
+///
+/// ```ignore
+/// let controller = AnimationController::new(Duration::ZERO);
+/// controller.repeat(false);
+///
+/// Image::new(spinner.clone()).width(48.).controller(controller.clone())
+///
+/// // Later:
+/// controller.stop();
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Image<A: Directional<Output = Alignment>, P: Widget, E: Widget> {
+    pub source: ImageSource,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    /// Defaults to [`BoxFit::Fill`], which stretches the image to the size
+    /// of the widget.
+    pub fit: BoxFit,
+    /// Alignment of the image within the widget, if it doesn't fill it.
+    pub alignment: A,
+    pub controller: Option<AnimationController>,
+    /// Shown while the image is loading, e.g. a preview decoded with
+    /// [`ImageData::from_blurhash`].
+    pub placeholder: P,
+    /// Shown if the image couldn't be loaded.
+    pub error: E,
+}
+
+impl Image<Alignment, (), ()> {
+    pub fn new(image: ImageData) -> Self {
+        Self::from_source(ImageSource::Decoded(image))
+    }
+
+    /// Image decoded from encoded `bytes` (PNG, JPEG or GIF).
+    pub fn memory(bytes: impl Into<Arc<[u8]>>) -> Self {
+        Self::from_source(ImageSource::memory(bytes))
+    }
+
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::from_source(ImageSource::File(path.into()))
+    }
+
+    /// Image fetched from `url`. Requires the `network-images` feature.
+    pub fn network(url: impl Into<String>) -> Self {
+        Self::from_source(ImageSource::Network(url.into()))
+    }
+
+    pub fn from_source(source: ImageSource) -> Self {
+        Image {
+            source,
+            width: None,
+            height: None,
+            fit: BoxFit::Fill,
+            alignment: Alignment::CENTER,
+            controller: None,
+            placeholder: (),
+            error: (),
+        }
+    }
+}
+
+pub struct ImageState {
+    /// Controller looping animated images if none was passed.
+    controller: AnimationController,
+    listener: ControllerListener<AnimationController>,
+    /// Image from the last build, the animation restarts once it changes.
+    image: RefCell<Option<ImageData>>,
+    /// Source loaded by this widget, and its image once it's loaded (or the
+    /// reason it couldn't be).
+    loaded: RefCell<Option<(ImageSource, Option<Result<ImageData, ImageError>>)>>,
+    playback: Rc<Playback>,
+    cache: Rc<CanvasImageCache>,
+}
+
+/// Whether an animated image was painted since the last frame of its
+/// animation. Images which weren't are culled, so their animation is paused
+/// until they're painted again.
+#[derive(Default)]
+struct Playback {
+    painted: Cell<bool>,
+    paused: Cell<bool>,
+}
+
+impl<A: Directional<Output = Alignment>, P: Widget, E: Widget> WidgetState for Image<A, P, E> {
+    type State = ImageState;
+
+    fn create_state(&self) -> Self::State {
+        let duration = self
+            .source
+            .cached()
+            .map_or(Duration::ZERO, |i| i.duration());
+
+        ImageState {
+            controller: AnimationController::new(duration),
+            listener: ControllerListener::default(),
+            image: RefCell::new(None),
+            loaded: RefCell::new(None),
+            playback: Rc::new(Playback::default()),
+            cache: Rc::new(CanvasImageCache::default()),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        let state = cx.state();
+        state.listener.unlisten();
+        state.controller.stop();
+    }
+}
+
+impl<A: Directional<Output = Alignment>, P: Widget, E: Widget> Image<A, P, E> {
+    /// Image of the source, or `None` until it's loaded.
+    fn image(&self, cx: BuildCx<Self>) -> Option<Result<ImageData, ImageError>> {
+        if let Some(image) = self.source.cached() {
+            return Some(Ok(image));
+        }
+
+        let state = cx.state();
+        let mut loaded = state.loaded.borrow_mut();
+
+        if let Some((source, image)) = &*loaded {
+            if *source == self.source {
+                return image.clone();
+            }
+        }
+
+        *loaded = Some((self.source.clone(), None));
+
+        let updater = cx.state_updater();
+        let source = self.source.clone();
+
+        self.source.load(move |result| {
+            updater.update(move |state: &mut ImageState| match state.loaded.get_mut() {
+                Some((loaded, slot)) if *loaded == source => *slot = Some(result),
+                _ => {}
+            });
+        });
+
+        None
+    }
+}
+
+impl<A: Directional<Output = Alignment>, P: Widget, E: Widget> ViewWidget for Image<A, P, E> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let image = match self.image(cx) {
+            Some(Ok(image)) => image,
+            result => {
+                cx.state().listener.unlisten();
+
+                let child = match result {
+                    Some(_) => (&self.error).boxed(),
+                    None => (&self.placeholder).boxed(),
+                };
+
+                return SizedBox::new(child, self.width, self.height).boxed();
+            }
+        };
+
+        let state = cx.state();
+
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.controller.clone(),
+        };
+
+        let changed = match state.image.replace(Some(image.clone())) {
+            Some(previous) => previous != image,
+            None => true,
+        };
+
+        if image.is_animated() {
+            if controller.duration() != image.duration() {
+                controller.set_duration(image.duration());
+            }
+
+            if changed && self.controller.is_none() {
+                // Starting the animation notifies listeners, which mustn't
+                // rebuild the widget while it is being built.
+                state.listener.unlisten();
+                state.playback.paused.set(false);
+                controller.set_progress(0.);
+                controller.repeat(false);
+            }
+
+            let updater = cx.state_updater();
+            let playback = state.playback.clone();
+            let pause = self.controller.is_none();
+
+            state.listener.listen(&controller, move || {
+                if playback.painted.replace(false) {
+                    updater.update(|_| {});
+                } else if pause {
+                    playback.paused.set(true);
+                    updater.update(|state: &mut ImageState| {
+                        state.controller.stop();
+                        state.cache.clear();
+                    });
+                }
+            });
+        } else {
+            state.listener.unlisten();
+        }
+
+        let elapsed = image.duration().mul_f64(controller.progress());
+        let frame = image.frame(image.frame_index_at(elapsed));
+
+        let playback = match image.is_animated() && self.controller.is_none() {
+            true => Some((state.playback.clone(), controller)),
+            false => None,
+        };
+
+        RawImage {
+            frame,
+            size: image.size(),
+            width: self.width,
+            height: self.height,
+            fit: self.fit,
+            alignment: &self.alignment,
+            playback,
+            cache: state.cache.clone(),
+        }
+        .boxed()
+    }
+}
+
+#[derive(RenderWidget)]
+struct RawImage<A: Directional<Output = Alignment>> {
+    /// `None` if the frame couldn't be decoded.
+    frame: Option<ImageBuf>,
+    /// Size of the image in pixels.
+    size: Size,
+    width: Option<f64>,
+    height: Option<f64>,
+    fit: BoxFit,
+    alignment: A,
+    /// Playback of an animation paused while the image isn't painted.
+    playback: Option<(Rc<Playback>, AnimationController)>,
+    cache: Rc<CanvasImageCache>,
+}
+
+impl<A: Directional<Output = Alignment>> RenderState for RawImage<A> {
+    /// Alignment resolved during layout.
+    type State = Alignment;
+
+    fn create_state(&self) -> Self::State {
+        Alignment::CENTER
+    }
+}
+
+impl<A: Directional<Output = Alignment>> RawImage<A> {
+    /// Size of the widget before it's constrained: `width` and `height`, or
+    /// the size of the image keeping its aspect ratio.
+    fn preferred_size(&self) -> Size {
+        if self.size == Size::ZERO {
+            return Size::new(self.width.unwrap_or(0.), self.height.unwrap_or(0.));
+        }
+
+        let Size { width, height } = self.size;
+        let aspect_ratio = if height > 0. { width / height } else { 1. };
+
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => Size::new(width, height),
+            (Some(width), None) => Size::new(width, width / aspect_ratio),
+            (None, Some(height)) => Size::new(height * aspect_ratio, height),
+            (None, None) => self.size,
+        }
+    }
+}
+
+impl<A: Directional<Output = Alignment>> RenderWidget for RawImage<A> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        *cx.render_state_mut() = self.alignment.resolve(&Directionality::of_or_default(cx));
+
+        constraints.constrain(self.preferred_size())
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        if let Some((playback, controller)) = &self.playback {
+            playback.painted.set(true);
+
+            if playback.paused.replace(false) {
+                // Resuming notifies the listener, which rebuilds the image
+                // since it was just painted.
+                controller.repeat(false);
+                playback.painted.set(true);
+            }
+        }
+
+        if let Some(frame) = &self.frame {
+            let alignment = *cx.render_state();
+            let fitted = self.fit.apply(self.size, cx.size());
+
+            let image_rect = Rect::from_origin_size(Offset::default(), self.size);
+            let widget_rect = Rect::from_origin_size(*offset, cx.size());
+
+            let src = alignment.inscribe(fitted.source, image_rect);
+            let dst = alignment.inscribe(fitted.destination, widget_rect);
+
+            self.cache.with(canvas, frame, |canvas, image| {
+                canvas.draw_image_area(
+                    image,
+                    DruidRect::from(src),
+                    DruidRect::from(dst),
+                    InterpolationMode::Bilinear,
+                );
+            });
+        }
+    }
+}
+
+impl<A: Directional<Output = Alignment>> Intrinsics for RawImage<A> {
+    fn min_intrinsic_width(&self, _: &LayoutCx<Self>, _: f64) -> f64 {
+        self.preferred_size().width
+    }
+
+    fn max_intrinsic_width(&self, _: &LayoutCx<Self>, _: f64) -> f64 {
+        self.preferred_size().width
+    }
+
+    fn min_intrinsic_height(&self, _: &LayoutCx<Self>, _: f64) -> f64 {
+        self.preferred_size().height
+    }
+
+    fn max_intrinsic_height(&self, _: &LayoutCx<Self>, _: f64) -> f64 {
+        self.preferred_size().height
+    }
+}
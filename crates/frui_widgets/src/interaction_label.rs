@@ -0,0 +1,91 @@
+use frui::prelude::*;
+
+/// What interactions with widgets below an [`InteractionLabel`] are reported
+/// as to [`Interactions`](frui::app::interactions::Interactions) observers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InteractionTarget {
+    pub widget: Option<&'static str>,
+    pub label: Option<String>,
+}
+
+pub trait InteractionLabelExt: Widget + Sized {
+    /// Wraps this widget in an [`InteractionLabel`] with `label`.
+    fn interaction_label(self, label: impl Into<String>) -> InteractionLabel<Self> {
+        InteractionLabel {
+            label: Some(label.into()),
+            widget: None,
+            child: self,
+        }
+    }
+}
+
+impl<T: Widget> InteractionLabelExt for T {}
+
+/// Labels interactions with `child` and its descendants, which are reported
+/// to [`Interactions`](frui::app::interactions::Interactions) observers.
+/// Properties which aren't set are taken from the enclosing label.
+///
+/// ```ignore
+/// Button::builder()
+///     .on_pressed(|| checkout())
+///     .child(Text::new("Buy"))
+///     .interaction_label("checkout")
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct InteractionLabel<W: Widget> {
+    pub label: Option<String>,
+    /// Name of the control, e.g. `"Checkbox"`. Set by built-in controls.
+    pub widget: Option<&'static str>,
+    pub child: W,
+}
+
+impl InteractionLabel<()> {
+    pub fn builder() -> Self {
+        InteractionLabel {
+            label: None,
+            widget: None,
+            child: (),
+        }
+    }
+
+    /// Target of the closest [`InteractionLabel`] ancestor, or an empty one
+    /// if there is none.
+    pub fn of<T>(cx: BuildCx<T>) -> InteractionTarget {
+        cx.depend_on::<InteractionTarget>()
+            .map_or_else(InteractionTarget::default, |target| (*target).clone())
+    }
+}
+
+impl<W: Widget> ViewWidget for InteractionLabel<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let outer = InteractionLabel::of(cx);
+
+        InteractionScope {
+            target: InteractionTarget {
+                widget: self.widget.or(outer.widget),
+                label: self.label.clone().or(outer.label),
+            },
+            child: &self.child,
+        }
+    }
+}
+
+#[derive(InheritedWidget)]
+struct InteractionScope<W: Widget> {
+    target: InteractionTarget,
+    child: W,
+}
+
+impl<W: Widget> InheritedWidget for InteractionScope<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl<W: Widget> InheritedValue for InteractionScope<W> {
+    type Value = InteractionTarget;
+
+    fn value(&self) -> &Self::Value {
+        &self.target
+    }
+}
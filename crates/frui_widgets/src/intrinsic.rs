@@ -0,0 +1,157 @@
+use frui::prelude::*;
+use frui::render::*;
+
+/// Sizes its `child` to the child's maximum intrinsic width, e.g. to make
+/// buttons of a [`Column`](crate::Column) as wide as the widest of them,
+/// instead of as wide as the column.
+///
+/// Querying intrinsic dimensions walks the whole subtree of the child, so
+/// this widget is relatively expensive.
+#[derive(RenderWidget, Builder)]
+pub struct IntrinsicWidth<W: Widget> {
+    pub child: W,
+    /// If set, the width is rounded up to a multiple of it.
+    pub step_width: Option<f64>,
+    /// If set, the height is the child's maximum intrinsic height, rounded up
+    /// to a multiple of it.
+    pub step_height: Option<f64>,
+}
+
+impl IntrinsicWidth<()> {
+    pub fn builder() -> Self {
+        IntrinsicWidth {
+            child: (),
+            step_width: None,
+            step_height: None,
+        }
+    }
+}
+
+impl<W: Widget> IntrinsicWidth<W> {
+    fn width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        step(cx.child(0).max_intrinsic_width(height), self.step_width)
+    }
+
+    /// Height of the child, if it's sized by `step_height`.
+    fn stepped_height(&self, cx: &LayoutCx<Self>, width: f64) -> Option<f64> {
+        self.step_height?;
+
+        let height = cx.child(0).max_intrinsic_height(width);
+        Some(step(height, self.step_height))
+    }
+}
+
+impl<W: Widget> RenderWidget for IntrinsicWidth<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let mut child_constraints = constraints;
+
+        if !constraints.has_tight_width() {
+            let width = self.width(cx, constraints.max_height);
+            child_constraints = child_constraints.tighten(Some(width), None);
+        }
+
+        if let Some(height) = self.stepped_height(cx, child_constraints.max_width) {
+            child_constraints = child_constraints.tighten(None, Some(height));
+        }
+
+        constraints.constrain(cx.child(0).layout(child_constraints))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+impl<W: Widget> Intrinsics for IntrinsicWidth<W> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.max_intrinsic_width(cx, height)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        self.width(cx, height)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let width = self.width(cx, f64::INFINITY).min(width);
+        self.stepped_height(cx, width)
+            .unwrap_or_else(|| cx.child(0).min_intrinsic_height(width))
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let width = self.width(cx, f64::INFINITY).min(width);
+        self.stepped_height(cx, width)
+            .unwrap_or_else(|| cx.child(0).max_intrinsic_height(width))
+    }
+}
+
+/// Sizes its `child` to the child's minimum intrinsic height, e.g. to make
+/// children of a [`Row`](crate::Row) as tall as the tallest of them, instead
+/// of as tall as the row.
+///
+/// Like [`IntrinsicWidth`], this widget is relatively expensive.
+#[derive(RenderWidget, Builder)]
+pub struct IntrinsicHeight<W: Widget> {
+    pub child: W,
+}
+
+impl IntrinsicHeight<()> {
+    pub fn builder() -> Self {
+        IntrinsicHeight { child: () }
+    }
+}
+
+impl<W: Widget> RenderWidget for IntrinsicHeight<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let mut child_constraints = constraints;
+
+        if !constraints.has_tight_height() {
+            let height = cx.child(0).min_intrinsic_height(constraints.max_width);
+            child_constraints = child_constraints.tighten(None, Some(height));
+        }
+
+        constraints.constrain(cx.child(0).layout(child_constraints))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+impl<W: Widget> Intrinsics for IntrinsicHeight<W> {
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.max_intrinsic_height(cx, width)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        cx.child(0).min_intrinsic_height(width)
+    }
+}
+
+/// `extent` rounded up to a multiple of `step`, if any.
+fn step(extent: f64, step: Option<f64>) -> f64 {
+    match step {
+        Some(step) if step > 0. => (extent / step).ceil() * step,
+        _ => extent,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rounds_up_to_steps() {
+        assert_eq!(step(42., None), 42.);
+        assert_eq!(step(42., Some(10.)), 50.);
+        assert_eq!(step(40., Some(10.)), 40.);
+        assert_eq!(step(42., Some(0.)), 42.);
+    }
+}
@@ -0,0 +1,511 @@
+//! Property testing of layout with [`proptest`]. [`LayoutStrategy`]
+//! generates constraints, alignments, edge insets and shapes of widget trees,
+//! and [`LayoutCheck`] verifies invariants every layout should uphold:
+//!
+//! - the checked widget and every [`LayoutProbe`] in its subtree returns a
+//!   size satisfying its constraints,
+//! - nothing is painted outside of the bounds of the checked widget.
+//!
+//! ```ignore
+//! proptest! {
+//!     #[test]
+//!     fn card_layout(
+//!         constraints in LayoutStrategy::constraints(),
+//!         child in LayoutStrategy::tree(),
+//!     ) {
+//!         // Generated rows and columns may overflow, painting outside of
+//!         // their bounds, so only sizes are checked.
+//!         LayoutCheck::new(constraints)
+//!             .paint_bounds(false)
+//!             .check(move || Card::new(child.build()))?;
+//!     }
+//! }
+//! ```
+//!
+//! Requires the `proptest` feature.
+
+use std::{
+    any::type_name,
+    cell::RefCell,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use frui::{
+    app::offscreen::{self, OffscreenError},
+    prelude::*,
+    render::*,
+};
+use proptest::{collection::vec, prelude::*, sample::select};
+
+use crate::*;
+
+/// Space around the checked widget in which nothing should be painted.
+const MARGIN: f64 = 32.;
+
+/// Sizes are compared with a tolerance, since layout accumulates rounding
+/// errors (e.g. when insets are subtracted and added back).
+const EPSILON: f64 = 1e-6;
+
+const LEAF_COLOR: Color = Color::rgb8(33, 150, 243);
+
+/// Strategies generating values used in layout.
+pub struct LayoutStrategy;
+
+impl LayoutStrategy {
+    /// Sizes of up to 400x400.
+    pub fn size() -> impl Strategy<Value = Size> {
+        (0f64..=400., 0f64..=400.).prop_map(|(width, height)| Size::new(width, height))
+    }
+
+    /// Tight, loose and bounded constraints, some of them unbounded along
+    /// one or both axes.
+    pub fn constraints() -> impl Strategy<Value = Constraints> {
+        (axis(), axis()).prop_map(|((min_width, max_width), (min_height, max_height))| {
+            Constraints::new(min_width, max_width, min_height, max_height)
+        })
+    }
+
+    /// Named alignments (e.g. [`Alignment::TOP_LEFT`]) and arbitrary ones
+    /// within the bounds of the child.
+    pub fn alignment() -> impl Strategy<Value = Alignment> {
+        let named = select(vec![
+            Alignment::TOP_LEFT,
+            Alignment::TOP_CENTER,
+            Alignment::TOP_RIGHT,
+            Alignment::CENTER_LEFT,
+            Alignment::CENTER,
+            Alignment::CENTER_RIGHT,
+            Alignment::BOTTOM_LEFT,
+            Alignment::BOTTOM_CENTER,
+            Alignment::BOTTOM_RIGHT,
+        ]);
+
+        prop_oneof![
+            named,
+            (-1f64..=1., -1f64..=1.).prop_map(|(x, y)| Alignment::new(x, y)),
+        ]
+    }
+
+    /// Non-negative insets of up to 50 on each side.
+    pub fn edge_insets() -> impl Strategy<Value = EdgeInsets> {
+        let inset = || prop_oneof![Just(0.), 0f64..=50.];
+
+        (inset(), inset(), inset(), inset())
+            .prop_map(|(left, top, right, bottom)| EdgeInsets::from_ltrb(left, top, right, bottom))
+    }
+
+    /// Trees of up to 4 levels of paddings, aligns, rows, columns and stacks,
+    /// with colored boxes as leaves.
+    pub fn tree() -> impl Strategy<Value = TreeShape> {
+        let leaf = (0f64..=120., 0f64..=120.).prop_map(|(w, h)| TreeShape::Leaf(Size::new(w, h)));
+
+        leaf.prop_recursive(4, 32, 3, |inner| {
+            prop_oneof![
+                (LayoutStrategy::edge_insets(), inner.clone())
+                    .prop_map(|(padding, child)| TreeShape::Padding(padding, Box::new(child))),
+                (LayoutStrategy::alignment(), inner.clone())
+                    .prop_map(|(alignment, child)| TreeShape::Align(alignment, Box::new(child))),
+                vec(inner.clone(), 0..=3).prop_map(TreeShape::Column),
+                vec(inner.clone(), 0..=3).prop_map(TreeShape::Row),
+                vec(inner, 0..=3).prop_map(TreeShape::Stack),
+            ]
+        })
+    }
+}
+
+/// Minimum and maximum extent of constraints along one axis.
+fn axis() -> impl Strategy<Value = (f64, f64)> {
+    prop_oneof![
+        2 => (0f64..=400.).prop_map(|extent| (extent, extent)),
+        3 => (0f64..=400., 0f64..=400.).prop_map(|(a, b)| (a.min(b), a.max(b))),
+        1 => (0f64..=400.).prop_map(|min| (min, f64::INFINITY)),
+    ]
+}
+
+/// Shape of a widget tree generated by [`LayoutStrategy::tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeShape {
+    /// Colored box which is as close to the given size as its constraints
+    /// allow.
+    Leaf(Size),
+    Padding(EdgeInsets, Box<TreeShape>),
+    Align(Alignment, Box<TreeShape>),
+    Column(Vec<TreeShape>),
+    Row(Vec<TreeShape>),
+    Stack(Vec<TreeShape>),
+}
+
+impl TreeShape {
+    /// Builds the tree, with every widget wrapped in a [`LayoutProbe`].
+    ///
+    /// Rows and columns whose children don't fit overflow, painting outside
+    /// of their bounds.
+    pub fn build(&self) -> Box<dyn Widget> {
+        let children = |shapes: &[TreeShape]| shapes.iter().map(|s| s.build()).collect::<Vec<_>>();
+
+        match self {
+            TreeShape::Leaf(size) => LayoutProbe::new(
+                "Leaf",
+                ColoredBox {
+                    child: SizedBox::from_size((), *size),
+                    color: LEAF_COLOR,
+                },
+            )
+            .boxed(),
+            TreeShape::Padding(padding, child) => LayoutProbe::new(
+                "Padding",
+                Padding::builder().padding(*padding).child(child.build()),
+            )
+            .boxed(),
+            TreeShape::Align(alignment, child) => LayoutProbe::new(
+                "Align",
+                Align::builder().alignment(*alignment).child(child.build()),
+            )
+            .boxed(),
+            TreeShape::Column(shapes) => {
+                LayoutProbe::new("Column", Column::builder().children(children(shapes))).boxed()
+            }
+            TreeShape::Row(shapes) => {
+                LayoutProbe::new("Row", Row::builder().children(children(shapes))).boxed()
+            }
+            TreeShape::Stack(shapes) => {
+                LayoutProbe::new("Stack", Stack::builder().children(children(shapes))).boxed()
+            }
+        }
+    }
+}
+
+/// Constraints passed to a widget during layout and the size it returned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutRecord {
+    pub widget: &'static str,
+    pub constraints: Constraints,
+    pub size: Size,
+}
+
+impl LayoutRecord {
+    /// Whether the size is finite and satisfies the constraints.
+    pub fn is_valid(&self) -> bool {
+        let (c, size) = (&self.constraints, self.size);
+
+        size.width.is_finite()
+            && size.height.is_finite()
+            && size.width >= c.min_width - EPSILON
+            && size.width <= c.max_width + EPSILON
+            && size.height >= c.min_height - EPSILON
+            && size.height <= c.max_height + EPSILON
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutViolation(String);
+
+impl fmt::Display for LayoutViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "layout invariant violated: {}", self.0)
+    }
+}
+
+impl std::error::Error for LayoutViolation {}
+
+impl From<OffscreenError> for LayoutViolation {
+    fn from(e: OffscreenError) -> Self {
+        LayoutViolation(e.to_string())
+    }
+}
+
+/// Lays out and paints a widget offscreen with the given constraints,
+/// verifying that:
+///
+/// - the checked widget and every [`LayoutProbe`] in its subtree return sizes
+///   satisfying their constraints,
+/// - nothing is painted outside of the bounds of the checked widget.
+pub struct LayoutCheck {
+    constraints: Constraints,
+    surface: Size,
+    paint_bounds: bool,
+}
+
+impl LayoutCheck {
+    pub fn new(constraints: Constraints) -> Self {
+        LayoutCheck {
+            constraints,
+            surface: Size::new(512., 512.),
+            paint_bounds: true,
+        }
+    }
+
+    /// Size of the image the widget is painted into, with the widget placed
+    /// at a margin from its top-left corner. Painting beyond the image isn't
+    /// detected.
+    pub fn surface(mut self, surface: Size) -> Self {
+        self.surface = surface;
+        self
+    }
+
+    /// Whether to verify that nothing is painted outside of the bounds of the
+    /// checked widget, which is the case for e.g. shadows.
+    pub fn paint_bounds(mut self, paint_bounds: bool) -> Self {
+        self.paint_bounds = paint_bounds;
+        self
+    }
+
+    /// Verifies layout invariants of the widget returned by `build`. Returns
+    /// layout records of every [`LayoutProbe`] in the order their layout
+    /// finished (children before parents), ending with the checked widget.
+    pub fn check<W, F>(&self, build: F) -> Result<Vec<LayoutRecord>, LayoutViolation>
+    where
+        W: Widget + 'static,
+        F: FnOnce() -> W + Send + 'static,
+    {
+        let records = Arc::new(Mutex::new(Vec::new()));
+
+        let root = {
+            let constraints = self.constraints;
+            let records = records.clone();
+
+            move || CheckRoot {
+                constraints,
+                records,
+                child: build(),
+            }
+        };
+
+        let image = offscreen::render(self.surface, 1., root)
+            .join()
+            .map_err(|_| LayoutViolation("layout or painting panicked".into()))??;
+
+        let records = std::mem::take(&mut *records.lock().unwrap());
+
+        if let Some(record) = records.iter().find(|r| !r.is_valid()) {
+            return Err(LayoutViolation(format!(
+                "`{}` returned size {:?}, which doesn't satisfy {:?}",
+                record.widget, record.size, record.constraints
+            )));
+        }
+
+        let checked = records.last().expect("checked widget wasn't laid out");
+
+        if self.paint_bounds {
+            let bounds = Rect::from_ltrb(
+                MARGIN,
+                MARGIN,
+                (MARGIN + checked.size.width).ceil(),
+                (MARGIN + checked.size.height).ceil(),
+            );
+
+            let image = to_rgba_image(&image);
+            let outside = image
+                .enumerate_pixels()
+                .filter(|(x, y, pixel)| {
+                    let (x, y) = (*x as f64, *y as f64);
+                    let inside = x >= bounds.left
+                        && x < bounds.right
+                        && y >= bounds.top
+                        && y < bounds.bottom;
+
+                    !inside && pixel.0[3] > 0
+                })
+                .map(|(x, y, _)| (x, y))
+                .collect::<Vec<_>>();
+
+            if let Some(&(x, y)) = outside.first() {
+                return Err(LayoutViolation(format!(
+                    "`{}` of size {:?} painted {} pixels outside of its bounds, first at ({}, {})",
+                    checked.widget,
+                    checked.size,
+                    outside.len(),
+                    x as f64 - MARGIN,
+                    y as f64 - MARGIN,
+                )));
+            }
+        }
+
+        Ok(records)
+    }
+}
+
+thread_local! {
+    /// Records of probes laid out by the current check.
+    static RECORDS: RefCell<Vec<LayoutRecord>> = RefCell::new(Vec::new());
+}
+
+/// Records constraints and size of `child` during a [`LayoutCheck`], which
+/// verifies them. Can be placed around children of a widget to check that
+/// its children are laid out correctly too.
+#[derive(RenderWidget)]
+pub struct LayoutProbe<W: Widget> {
+    /// Name of the widget in reports.
+    pub name: &'static str,
+    pub child: W,
+}
+
+impl<W: Widget> LayoutProbe<W> {
+    pub fn new(name: &'static str, child: W) -> Self {
+        LayoutProbe { name, child }
+    }
+}
+
+impl<W: Widget> RenderWidget for LayoutProbe<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let size = cx.child(0).layout(constraints);
+
+        RECORDS.with(|r| {
+            r.borrow_mut().push(LayoutRecord {
+                widget: self.name,
+                constraints,
+                size,
+            })
+        });
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+/// Root of the offscreen tree, laying out the checked widget with the checked
+/// constraints and painting it at a margin.
+#[derive(RenderWidget)]
+struct CheckRoot<W: Widget> {
+    constraints: Constraints,
+    records: Arc<Mutex<Vec<LayoutRecord>>>,
+    child: W,
+}
+
+impl<W: Widget> RenderWidget for CheckRoot<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        RECORDS.with(|r| r.borrow_mut().clear());
+
+        let size = cx.child(0).layout(self.constraints);
+
+        let mut records = RECORDS.with(|r| std::mem::take(&mut *r.borrow_mut()));
+        records.push(LayoutRecord {
+            widget: type_name::<W>(),
+            constraints: self.constraints,
+            size,
+        });
+
+        *self.records.lock().unwrap() = records;
+
+        constraints.biggest()
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0)
+            .paint(canvas, &(*offset + Offset::new(MARGIN, MARGIN)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use druid_shell::kurbo::Rect as DruidRect;
+    use proptest::test_runner::TestRunner;
+
+    use super::*;
+
+    /// Returns `size` regardless of constraints and paints a 40x40 square.
+    #[derive(RenderWidget)]
+    struct Overpaint {
+        size: Size,
+    }
+
+    impl RenderWidget for Overpaint {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![] as Vec<()>
+        }
+
+        fn layout(&self, _: &LayoutCx<Self>, _: Constraints) -> Size {
+            self.size
+        }
+
+        fn paint(&self, _: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+            canvas.fill(
+                DruidRect::from_origin_size(*offset, (40., 40.)),
+                &LEAF_COLOR,
+            );
+        }
+    }
+
+    #[test]
+    fn generated_constraints_are_normalized() {
+        TestRunner::default()
+            .run(&LayoutStrategy::constraints(), |c| {
+                prop_assert!(c.min_width <= c.max_width);
+                prop_assert!(c.min_height <= c.max_height);
+                prop_assert!(c.min_width.is_finite() && c.min_height.is_finite());
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn record_validity() {
+        let record = |size: Size| LayoutRecord {
+            widget: "Leaf",
+            constraints: Constraints::new(10., 20., 0., f64::INFINITY),
+            size,
+        };
+
+        assert!(record(Size::new(20. + EPSILON / 2., 100.)).is_valid());
+        assert!(!record(Size::new(9., 100.)).is_valid());
+        assert!(!record(Size::new(15., f64::INFINITY)).is_valid());
+    }
+
+    #[test]
+    fn check_records_probes() {
+        let records = LayoutCheck::new(Constraints::new_loose(Size::new(100., 100.)))
+            .check(|| {
+                LayoutProbe::new(
+                    "Leaf",
+                    ColoredBox {
+                        child: SizedBox::from_size((), Size::new(40., 40.)),
+                        color: LEAF_COLOR,
+                    },
+                )
+            })
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].widget, "Leaf");
+        assert_eq!(records[0].size, Size::new(40., 40.));
+        assert_eq!(records[1].size, Size::new(40., 40.));
+    }
+
+    #[test]
+    fn check_reports_unsatisfied_constraints() {
+        let check = LayoutCheck::new(Constraints::new_tight(Size::new(20., 20.)));
+        let result = check.check(|| Overpaint {
+            size: Size::new(40., 40.),
+        });
+
+        assert!(result.unwrap_err().to_string().contains("doesn't satisfy"));
+    }
+
+    #[test]
+    fn check_reports_painting_outside_of_bounds() {
+        let check = LayoutCheck::new(Constraints::new_loose(Size::new(100., 100.)));
+        let build = || Overpaint {
+            size: Size::new(10., 10.),
+        };
+
+        let result = check.check(build);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("outside of its bounds"));
+
+        assert!(check.paint_bounds(false).check(build).is_ok());
+    }
+}
@@ -1,28 +1,109 @@
 #![feature(type_alias_impl_trait)]
 
+mod adaptive;
+mod animation;
 mod basic;
 mod boxes;
+mod button;
+mod clip;
+mod command_palette;
+mod commands;
 mod container;
+mod deferred;
+mod divider;
 mod event_detectors;
 mod flex;
+mod focus;
+mod follower;
+mod gestures;
+#[cfg(all(any(test, feature = "testing"), not(feature = "miri")))]
+mod golden;
+mod hover;
+mod icon;
+mod image;
+mod interaction_label;
+mod intrinsic;
+#[cfg(all(feature = "proptest", not(feature = "miri")))]
+mod layout_testing;
+mod listenable;
+mod localization;
+#[cfg(feature = "lottie")]
+mod lottie;
+mod media_query;
+mod memory_inspector;
+mod navigator;
+mod opacity;
+mod overlay;
 mod painting;
+mod platform;
+mod preferences;
 mod scroll;
+mod shortcuts;
+mod slider;
+mod soft_keyboard;
+mod spatial_index;
+mod table;
+mod test_id;
 mod testing;
 mod text;
+mod theme;
+mod toggle;
 mod transform;
 mod widget_list;
+mod window_chrome;
 
+pub use self::adaptive::*;
+pub use self::animation::*;
 pub use self::basic::*;
 pub use self::boxes::*;
+pub use self::button::*;
+pub use self::clip::*;
+pub use self::command_palette::*;
+pub use self::commands::*;
 pub use self::container::*;
+pub use self::deferred::*;
+pub use self::divider::*;
 pub use self::event_detectors::keyboard::*;
+pub use self::event_detectors::lifecycle::*;
 pub use self::flex::*;
+pub use self::focus::*;
+pub use self::follower::*;
+pub use self::gestures::*;
+#[cfg(all(any(test, feature = "testing"), not(feature = "miri")))]
+pub use self::golden::*;
+pub use self::hover::*;
+pub use self::icon::*;
+pub use self::image::*;
+pub use self::interaction_label::*;
+pub use self::intrinsic::*;
+#[cfg(all(feature = "proptest", not(feature = "miri")))]
+pub use self::layout_testing::*;
+pub use self::listenable::*;
+pub use self::localization::*;
+#[cfg(feature = "lottie")]
+pub use self::lottie::*;
+pub use self::media_query::*;
+pub use self::memory_inspector::*;
+pub use self::navigator::*;
+pub use self::opacity::*;
+pub use self::overlay::*;
 pub use self::painting::*;
+pub use self::platform::*;
+pub use self::preferences::*;
 pub use self::scroll::*;
+pub use self::shortcuts::*;
+pub use self::slider::*;
+pub use self::soft_keyboard::*;
+pub use self::spatial_index::*;
+pub use self::table::*;
+pub use self::test_id::*;
 pub use self::testing::*;
 pub use self::text::*;
+pub use self::theme::*;
+pub use self::toggle::*;
 pub use self::transform::*;
 pub use self::widget_list::*;
+pub use self::window_chrome::*;
 
 #[doc(hidden)]
 pub use frui::macro_exports;
@@ -0,0 +1,58 @@
+//! Objects which notify listeners about their changes, such as
+//! [`ScrollController`](crate::ScrollController) or
+//! [`AnimationController`](crate::AnimationController).
+
+use std::cell::RefCell;
+
+/// Shared handle (clones refer to the same object) notifying listeners about
+/// its changes.
+pub trait Listenable: Clone + 'static {
+    type Key: Copy;
+
+    fn listen(&self, callback: impl Fn() + 'static) -> Self::Key;
+
+    fn unlisten(&self, key: Self::Key);
+
+    /// Whether both handles refer to the same object.
+    fn ptr_eq(&self, other: &Self) -> bool;
+}
+
+/// Listener of at most one object at a time, kept in the state of widgets
+/// which are passed a controller that may change between rebuilds.
+#[doc(hidden)]
+pub struct ControllerListener<L: Listenable> {
+    listening: RefCell<Option<(L, L::Key)>>,
+}
+
+impl<L: Listenable> Default for ControllerListener<L> {
+    fn default() -> Self {
+        ControllerListener {
+            listening: RefCell::new(None),
+        }
+    }
+}
+
+impl<L: Listenable> ControllerListener<L> {
+    /// Starts listening to `controller`, unless it already listens to it.
+    pub(crate) fn listen(&self, controller: &L, callback: impl Fn() + 'static) {
+        let mut listening = self.listening.borrow_mut();
+
+        if matches!(&*listening, Some((c, _)) if c.ptr_eq(controller)) {
+            return;
+        }
+
+        if let Some((previous, key)) = listening.take() {
+            previous.unlisten(key);
+        }
+
+        let key = controller.listen(callback);
+        *listening = Some((controller.clone(), key));
+    }
+
+    /// Should be called once the widget is unmounted.
+    pub(crate) fn unlisten(&self) {
+        if let Some((controller, key)) = self.listening.take() {
+            controller.unlisten(key);
+        }
+    }
+}
@@ -0,0 +1,471 @@
+//! Formatting of dates and times using a subset of the CLDR date patterns:
+//!
+//! - `y` is the year and `yy` its last two digits,
+//! - `M` and `MM` are the month number, `MMM` and `MMMM` its abbreviated and
+//!   full name,
+//! - `d` and `dd` are the day of the month,
+//! - `H` and `HH` are the hour (0-23), `h` and `hh` the hour (1-12),
+//! - `mm` and `ss` are minutes and seconds, `a` is AM or PM,
+//! - `'de'` quotes literal text and `''` is an apostrophe.
+//!
+//! Doubled letters pad numbers with zeros. Other characters are copied as
+//! they are.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Locale;
+
+/// Calendar date and time of day, without a time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    pub year: i32,
+    /// Month from 1 to 12.
+    pub month: u32,
+    /// Day of the month from 1.
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    /// Midnight of the given date.
+    #[track_caller]
+    pub fn date(year: i32, month: u32, day: u32) -> Self {
+        assert!((1..=12).contains(&month), "month must be between 1 and 12");
+        assert!(
+            (1..=days_in_month(year, month)).contains(&day),
+            "day must be within the month"
+        );
+
+        DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }
+    }
+
+    #[track_caller]
+    pub fn time(mut self, hour: u32, minute: u32, second: u32) -> Self {
+        assert!(hour < 24 && minute < 60 && second < 60, "invalid time");
+
+        self.hour = hour;
+        self.minute = minute;
+        self.second = second;
+        self
+    }
+
+    /// Date and time in UTC, `secs` seconds after the Unix epoch.
+    pub fn from_unix_seconds(secs: i64) -> Self {
+        let (days, secs) = (secs.div_euclid(86400), secs.rem_euclid(86400) as u32);
+        let (year, month, day) = civil_from_days(days);
+
+        DateTime {
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs / 60 % 60,
+            second: secs % 60,
+        }
+    }
+
+    /// Date and time in UTC. Time zones aren't known to the framework, so
+    /// local times should be offset by the application.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs_f64().ceil() as i64),
+        };
+
+        Self::from_unix_seconds(secs)
+    }
+}
+
+/// Length of a localized date, e.g. `1/15/2024`, `Jan 15, 2024` and
+/// `January 15, 2024` in `en-US`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    Short,
+    Medium,
+    Long,
+}
+
+/// Locale specific names and patterns used when formatting dates.
+#[derive(Debug)]
+struct DateSymbols {
+    /// Names of the months, separated by spaces.
+    months: &'static str,
+    short_months: &'static str,
+    /// Patterns of [`DateStyle::Short`], `Medium` and `Long` dates.
+    dates: [&'static str; 3],
+    /// Pattern of the hours and minutes.
+    time: &'static str,
+    am_pm: [&'static str; 2],
+}
+
+const EN_MONTHS: &str =
+    "January February March April May June July August September October November December";
+
+const EN_SHORT_MONTHS: &str = "Jan Feb Mar Apr May Jun Jul Aug Sep Oct Nov Dec";
+
+const NUMERIC_MONTHS: &str = "1 2 3 4 5 6 7 8 9 10 11 12";
+
+impl DateSymbols {
+    fn of(locale: &Locale) -> Self {
+        let en = DateSymbols {
+            months: EN_MONTHS,
+            short_months: EN_SHORT_MONTHS,
+            dates: ["dd/MM/y", "d MMM y", "d MMMM y"],
+            time: "HH:mm",
+            am_pm: ["AM", "PM"],
+        };
+
+        let cjk = DateSymbols {
+            months: NUMERIC_MONTHS,
+            short_months: NUMERIC_MONTHS,
+            ..en
+        };
+
+        match (locale.language.as_str(), locale.country.as_deref()) {
+            ("en", Some("US") | None) => DateSymbols {
+                dates: ["M/d/y", "MMM d, y", "MMMM d, y"],
+                time: "h:mm a",
+                ..en
+            },
+            ("de", _) => DateSymbols {
+                months: "Januar Februar März April Mai Juni Juli August September Oktober November Dezember",
+                short_months: "Jan. Feb. März Apr. Mai Juni Juli Aug. Sept. Okt. Nov. Dez.",
+                dates: ["dd.MM.y", "dd.MM.y", "d. MMMM y"],
+                ..en
+            },
+            ("fr", _) => DateSymbols {
+                months: "janvier février mars avril mai juin juillet août septembre octobre novembre décembre",
+                short_months: "janv. févr. mars avr. mai juin juil. août sept. oct. nov. déc.",
+                ..en
+            },
+            ("es", _) => DateSymbols {
+                months: "enero febrero marzo abril mayo junio julio agosto septiembre octubre noviembre diciembre",
+                short_months: "ene feb mar abr may jun jul ago sept oct nov dic",
+                dates: ["d/M/y", "d MMM y", "d 'de' MMMM 'de' y"],
+                time: "H:mm",
+                ..en
+            },
+            ("it", _) => DateSymbols {
+                months: "gennaio febbraio marzo aprile maggio giugno luglio agosto settembre ottobre novembre dicembre",
+                short_months: "gen feb mar apr mag giu lug ago set ott nov dic",
+                ..en
+            },
+            ("nl", _) => DateSymbols {
+                months: "januari februari maart april mei juni juli augustus september oktober november december",
+                short_months: "jan feb mrt apr mei jun jul aug sep okt nov dec",
+                dates: ["dd-MM-y", "d MMM y", "d MMMM y"],
+                ..en
+            },
+            ("pt", _) => DateSymbols {
+                months: "janeiro fevereiro março abril maio junho julho agosto setembro outubro novembro dezembro",
+                short_months: "jan. fev. mar. abr. mai. jun. jul. ago. set. out. nov. dez.",
+                dates: ["dd/MM/y", "d 'de' MMM 'de' y", "d 'de' MMMM 'de' y"],
+                ..en
+            },
+            // Months in the genitive case, as they follow the day.
+            ("pl", _) => DateSymbols {
+                months: "stycznia lutego marca kwietnia maja czerwca lipca sierpnia września października listopada grudnia",
+                short_months: "sty lut mar kwi maj cze lip sie wrz paź lis gru",
+                dates: ["d.MM.y", "d MMM y", "d MMMM y"],
+                ..en
+            },
+            ("sv", _) => DateSymbols {
+                months: "januari februari mars april maj juni juli augusti september oktober november december",
+                short_months: "jan. feb. mars apr. maj juni juli aug. sep. okt. nov. dec.",
+                dates: ["y-MM-dd", "d MMM y", "d MMMM y"],
+                ..en
+            },
+            ("ja", _) => DateSymbols {
+                dates: ["y/MM/dd", "y/MM/dd", "y年M月d日"],
+                time: "H:mm",
+                ..cjk
+            },
+            ("zh", _) => DateSymbols {
+                dates: ["y/M/d", "y年M月d日", "y年M月d日"],
+                ..cjk
+            },
+            ("ko", _) => DateSymbols {
+                dates: ["y. M. d.", "y. M. d.", "y년 M월 d일"],
+                time: "a h:mm",
+                am_pm: ["오전", "오후"],
+                ..cjk
+            },
+            _ => en,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Field {
+    Text(String),
+    Year { digits: usize },
+    Month { width: usize },
+    Day { width: usize },
+    Hour24 { width: usize },
+    Hour12 { width: usize },
+    Minute { width: usize },
+    Second { width: usize },
+    DayPeriod,
+}
+
+/// Formats dates and times according to a [`Locale`].
+///
+/// ```
+/// # use frui_widgets::{DateFormat, DateStyle, DateTime, Locale};
+/// let date = DateTime::date(2024, 1, 15).time(15, 5, 0);
+///
+/// let format = DateFormat::date(&Locale::new("de", None), DateStyle::Long);
+/// assert_eq!(format.format(&date), "15. Januar 2024");
+///
+/// let format = DateFormat::time(&Locale::new("en", Some("US")));
+/// assert_eq!(format.format(&date), "3:05 PM");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateFormat {
+    fields: Vec<Field>,
+    months: &'static str,
+    short_months: &'static str,
+    am_pm: [&'static str; 2],
+}
+
+impl DateFormat {
+    pub fn date(locale: &Locale, style: DateStyle) -> Self {
+        let symbols = DateSymbols::of(locale);
+
+        let pattern = match style {
+            DateStyle::Short => symbols.dates[0],
+            DateStyle::Medium => symbols.dates[1],
+            DateStyle::Long => symbols.dates[2],
+        };
+
+        Self::with_symbols(pattern, symbols)
+    }
+
+    /// Formats hours and minutes, in a 12 or 24-hour clock depending on the
+    /// locale.
+    pub fn time(locale: &Locale) -> Self {
+        let symbols = DateSymbols::of(locale);
+        Self::with_symbols(symbols.time, symbols)
+    }
+
+    /// Formats dates using a custom pattern (see the [module
+    /// documentation](self)) and the month names of the locale.
+    pub fn pattern(locale: &Locale, pattern: &str) -> Self {
+        Self::with_symbols(pattern, DateSymbols::of(locale))
+    }
+
+    fn with_symbols(pattern: &str, symbols: DateSymbols) -> Self {
+        DateFormat {
+            fields: parse_pattern(pattern),
+            months: symbols.months,
+            short_months: symbols.short_months,
+            am_pm: symbols.am_pm,
+        }
+    }
+
+    pub fn format(&self, date: &DateTime) -> String {
+        let mut r = String::new();
+
+        let number = |r: &mut String, n: u32, width: usize| {
+            r.push_str(&format!("{:0width$}", n, width = width.min(2)));
+        };
+
+        for field in &self.fields {
+            match *field {
+                Field::Text(ref text) => r.push_str(text),
+                Field::Year { digits: 2 } => {
+                    r.push_str(&format!("{:02}", date.year.rem_euclid(100)))
+                }
+                Field::Year { .. } => r.push_str(&date.year.to_string()),
+                Field::Month { width: 3 } => r.push_str(month_name(self.short_months, date)),
+                Field::Month { width: 4.. } => r.push_str(month_name(self.months, date)),
+                Field::Month { width } => number(&mut r, date.month, width),
+                Field::Day { width } => number(&mut r, date.day, width),
+                Field::Hour24 { width } => number(&mut r, date.hour, width),
+                Field::Hour12 { width } => match date.hour % 12 {
+                    0 => number(&mut r, 12, width),
+                    hour => number(&mut r, hour, width),
+                },
+                Field::Minute { width } => number(&mut r, date.minute, width),
+                Field::Second { width } => number(&mut r, date.second, width),
+                Field::DayPeriod => r.push_str(self.am_pm[(date.hour >= 12) as usize]),
+            }
+        }
+
+        r
+    }
+}
+
+fn month_name(names: &'static str, date: &DateTime) -> &'static str {
+    let month = (date.month as usize).clamp(1, 12);
+    names.split(' ').nth(month - 1).unwrap_or_default()
+}
+
+fn parse_pattern(pattern: &str) -> Vec<Field> {
+    let mut fields = Vec::new();
+    let mut text = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let width = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let mut width = 1;
+
+            while chars.next_if_eq(&c).is_some() {
+                width += 1;
+            }
+
+            width
+        };
+
+        let field = match c {
+            'y' => Field::Year {
+                digits: width(&mut chars),
+            },
+            'M' => Field::Month {
+                width: width(&mut chars),
+            },
+            'd' => Field::Day {
+                width: width(&mut chars),
+            },
+            'H' => Field::Hour24 {
+                width: width(&mut chars),
+            },
+            'h' => Field::Hour12 {
+                width: width(&mut chars),
+            },
+            'm' => Field::Minute {
+                width: width(&mut chars),
+            },
+            's' => Field::Second {
+                width: width(&mut chars),
+            },
+            'a' => {
+                width(&mut chars);
+                Field::DayPeriod
+            }
+            '\'' if chars.next_if_eq(&'\'').is_some() => {
+                text.push('\'');
+                continue;
+            }
+            '\'' => {
+                // Unterminated quotes extend to the end of the pattern.
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\'' if chars.next_if_eq(&'\'').is_some() => text.push('\''),
+                        '\'' => break,
+                        c => text.push(c),
+                    }
+                }
+
+                continue;
+            }
+            c => {
+                text.push(c);
+                continue;
+            }
+        };
+
+        if !text.is_empty() {
+            fields.push(Field::Text(std::mem::take(&mut text)));
+        }
+
+        fields.push(field);
+    }
+
+    if !text.is_empty() {
+        fields.push(Field::Text(text));
+    }
+
+    fields
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Year, month and day of the `days`-th day after the Unix epoch, in the
+/// proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    // Days since 0000-03-01, so that leap days end the year.
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn date_styles() {
+        let date = DateTime::date(2024, 1, 5);
+
+        let format = |language, country, style| {
+            DateFormat::date(&Locale::new(language, country), style).format(&date)
+        };
+
+        assert_eq!(format("en", Some("US"), DateStyle::Short), "1/5/2024");
+        assert_eq!(format("en", Some("US"), DateStyle::Medium), "Jan 5, 2024");
+        assert_eq!(format("en", Some("GB"), DateStyle::Short), "05/01/2024");
+        assert_eq!(format("de", None, DateStyle::Short), "05.01.2024");
+        assert_eq!(format("es", None, DateStyle::Long), "5 de enero de 2024");
+        assert_eq!(format("pl", None, DateStyle::Long), "5 stycznia 2024");
+        assert_eq!(format("ja", None, DateStyle::Long), "2024年1月5日");
+    }
+
+    #[test]
+    fn times_and_patterns() {
+        let date = DateTime::date(1999, 12, 31).time(0, 7, 9);
+        let en = Locale::new("en", Some("US"));
+
+        assert_eq!(DateFormat::time(&en).format(&date), "12:07 AM");
+        assert_eq!(
+            DateFormat::time(&Locale::new("ko", None)).format(&date),
+            "오전 12:07"
+        );
+        assert_eq!(
+            DateFormat::pattern(&en, "'Q''s' dd-MM-yy HH:mm:ss").format(&date),
+            "Q's 31-12-99 00:07:09"
+        );
+    }
+
+    #[test]
+    fn unix_time() {
+        assert_eq!(DateTime::from_unix_seconds(0), DateTime::date(1970, 1, 1));
+        assert_eq!(
+            DateTime::from_unix_seconds(1_709_210_096),
+            DateTime::date(2024, 2, 29).time(12, 34, 56)
+        );
+        assert_eq!(
+            DateTime::from_unix_seconds(-1),
+            DateTime::date(1969, 12, 31).time(23, 59, 59)
+        );
+    }
+}
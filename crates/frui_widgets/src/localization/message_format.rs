@@ -0,0 +1,528 @@
+//! Formatting of translated messages using a subset of the ICU MessageFormat
+//! syntax:
+//!
+//! - `{0}` is replaced by the first argument,
+//! - `{0, plural, =0 {no files} one {# file} other {# files}}` selects a
+//!   message by the plural category of the argument in the given locale (`#`
+//!   is replaced by the number),
+//! - `{1, select, female {her} male {his} other {their}}` selects a message
+//!   by the value of the argument,
+//! - `'{'` escapes special characters and `''` is an apostrophe.
+
+use std::fmt::Display;
+
+use super::{Locale, NumberFormat};
+
+/// Plural category of a number, as defined by the CLDR plural rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// Cardinal plural category of `n` in the language of `locale`.
+    pub fn of(locale: &Locale, n: f64) -> Self {
+        use PluralCategory::*;
+
+        let n = n.abs();
+        // Integer digits and whether there are any visible fraction digits.
+        let i = n.trunc() as u64;
+        let v = n.fract() != 0.;
+        let (i10, i100) = (i % 10, i % 100);
+
+        match locale.language.as_str() {
+            "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" => Other,
+            "fr" | "hi" if i == 0 || i == 1 => One,
+            "fr" | "hi" => Other,
+            "ru" | "uk" if v => Other,
+            "ru" | "uk" => match (i10, i100) {
+                (1, i100) if i100 != 11 => One,
+                (2..=4, i100) if !(12..=14).contains(&i100) => Few,
+                _ => Many,
+            },
+            "pl" if v => Other,
+            "pl" => match (i, i10, i100) {
+                (1, _, _) => One,
+                (_, 2..=4, i100) if !(12..=14).contains(&i100) => Few,
+                _ => Many,
+            },
+            "cs" | "sk" if v => Many,
+            "cs" | "sk" => match i {
+                1 => One,
+                2..=4 => Few,
+                _ => Other,
+            },
+            "ar" if v => Other,
+            "ar" => match (i, i100) {
+                (0, _) => Zero,
+                (1, _) => One,
+                (2, _) => Two,
+                (_, 3..=10) => Few,
+                (_, 11..=99) => Many,
+                _ => Other,
+            },
+            _ if i == 1 && !v => One,
+            _ => Other,
+        }
+    }
+
+    fn parse(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "zero" => PluralCategory::Zero,
+            "one" => PluralCategory::One,
+            "two" => PluralCategory::Two,
+            "few" => PluralCategory::Few,
+            "many" => PluralCategory::Many,
+            "other" => PluralCategory::Other,
+            _ => return None,
+        })
+    }
+}
+
+/// Argument of a [`MessageFormat`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageArg {
+    Number(f64),
+    Text(String),
+}
+
+impl From<f64> for MessageArg {
+    fn from(n: f64) -> Self {
+        MessageArg::Number(n)
+    }
+}
+
+impl From<i64> for MessageArg {
+    fn from(n: i64) -> Self {
+        MessageArg::Number(n as f64)
+    }
+}
+
+impl From<i32> for MessageArg {
+    fn from(n: i32) -> Self {
+        MessageArg::Number(n as f64)
+    }
+}
+
+impl From<usize> for MessageArg {
+    fn from(n: usize) -> Self {
+        MessageArg::Number(n as f64)
+    }
+}
+
+impl From<&str> for MessageArg {
+    fn from(s: &str) -> Self {
+        MessageArg::Text(s.to_owned())
+    }
+}
+
+impl From<String> for MessageArg {
+    fn from(s: String) -> Self {
+        MessageArg::Text(s)
+    }
+}
+
+/// Error in the syntax of a [`MessageFormat`] pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageFormatError {
+    /// Character offset in the pattern at which the error was found.
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl Display for MessageFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for MessageFormatError {}
+
+/// Parsed message pattern. See the [module documentation](self) for the
+/// syntax.
+///
+/// ```ignore
+/// let message = MessageFormat::parse("{0, plural, one {# day} other {# days}} left")?;
+/// assert_eq!(message.format(&Locale::default(), &[3.into()]), "3 days left");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageFormat {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Arg(usize),
+    Plural {
+        arg: usize,
+        cases: Vec<(PluralSelector, Vec<Part>)>,
+    },
+    Select {
+        arg: usize,
+        cases: Vec<(String, Vec<Part>)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PluralSelector {
+    Exact(f64),
+    Category(PluralCategory),
+}
+
+impl MessageFormat {
+    pub fn parse(pattern: &str) -> Result<Self, MessageFormatError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+
+        let parts = parser.message(None)?;
+
+        match parser.peek() {
+            Some(_) => Err(parser.error("unmatched '}'")),
+            None => Ok(MessageFormat { parts }),
+        }
+    }
+
+    /// Formats the message with `args` in the given locale. Missing arguments
+    /// are formatted as empty strings.
+    pub fn format(&self, locale: &Locale, args: &[MessageArg]) -> String {
+        let mut output = String::new();
+        let numbers = NumberFormat::decimal(locale);
+
+        format_parts(&self.parts, locale, &numbers, args, &mut output);
+
+        output
+    }
+}
+
+fn format_parts(
+    parts: &[Part],
+    locale: &Locale,
+    numbers: &NumberFormat,
+    args: &[MessageArg],
+    output: &mut String,
+) {
+    for part in parts {
+        match part {
+            Part::Text(text) => output.push_str(text),
+            Part::Arg(arg) => match args.get(*arg) {
+                Some(MessageArg::Number(n)) => output.push_str(&numbers.format(*n)),
+                Some(MessageArg::Text(text)) => output.push_str(text),
+                None => {}
+            },
+            Part::Plural { arg, cases } => {
+                let n = match args.get(*arg) {
+                    Some(MessageArg::Number(n)) => *n,
+                    Some(MessageArg::Text(text)) => text.parse().unwrap_or(0.),
+                    None => 0.,
+                };
+
+                let category = PluralCategory::of(locale, n);
+
+                let case = cases
+                    .iter()
+                    .find(|(selector, _)| *selector == PluralSelector::Exact(n))
+                    .or_else(|| {
+                        cases
+                            .iter()
+                            .find(|(selector, _)| *selector == PluralSelector::Category(category))
+                    })
+                    .or_else(|| {
+                        cases.iter().find(|(selector, _)| {
+                            *selector == PluralSelector::Category(PluralCategory::Other)
+                        })
+                    });
+
+                if let Some((_, parts)) = case {
+                    format_parts(parts, locale, numbers, args, output);
+                }
+            }
+            Part::Select { arg, cases } => {
+                let value = match args.get(*arg) {
+                    Some(MessageArg::Text(text)) => text.clone(),
+                    Some(MessageArg::Number(n)) => n.to_string(),
+                    None => String::new(),
+                };
+
+                let case = cases
+                    .iter()
+                    .find(|(key, _)| *key == value)
+                    .or_else(|| cases.iter().find(|(key, _)| key == "other"));
+
+                if let Some((_, parts)) = case {
+                    format_parts(parts, locale, numbers, args, output);
+                }
+            }
+        }
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn error(&self, message: &'static str) -> MessageFormatError {
+        MessageFormatError {
+            position: self.position,
+            message,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, c: char, message: &'static str) -> Result<(), MessageFormatError> {
+        self.skip_whitespace();
+
+        match self.peek() == Some(c) {
+            true => {
+                self.position += 1;
+                Ok(())
+            }
+            false => Err(self.error(message)),
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let start = self.position;
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || c == '{' || c == '}' || c == ',' {
+                break;
+            }
+
+            self.position += 1;
+        }
+
+        self.chars[start..self.position].iter().collect()
+    }
+
+    /// Parses text up to the closing `}` of the enclosing argument (which is
+    /// not consumed) or the end of the pattern. `plural_arg` is the argument
+    /// `#` stands for.
+    fn message(&mut self, plural_arg: Option<usize>) -> Result<Vec<Part>, MessageFormatError> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+
+        while let Some(c) = self.peek() {
+            match c {
+                '}' => break,
+                '{' => {
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+
+                    parts.push(self.argument(plural_arg)?);
+                }
+                '#' => {
+                    if let Some(arg) = plural_arg {
+                        if !text.is_empty() {
+                            parts.push(Part::Text(std::mem::take(&mut text)));
+                        }
+
+                        parts.push(Part::Arg(arg));
+                    } else {
+                        text.push('#');
+                    }
+
+                    self.position += 1;
+                }
+                '\'' => self.quoted(&mut text),
+                c => {
+                    text.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(parts)
+    }
+
+    fn quoted(&mut self, text: &mut String) {
+        self.position += 1;
+
+        match self.peek() {
+            Some('\'') => {
+                text.push('\'');
+                self.position += 1;
+            }
+            Some('{' | '}' | '#') => {
+                while let Some(c) = self.peek() {
+                    self.position += 1;
+
+                    match c {
+                        '\'' if self.peek() == Some('\'') => {
+                            text.push('\'');
+                            self.position += 1;
+                        }
+                        '\'' => break,
+                        c => text.push(c),
+                    }
+                }
+            }
+            _ => text.push('\''),
+        }
+    }
+
+    fn argument(&mut self, plural_arg: Option<usize>) -> Result<Part, MessageFormatError> {
+        // Skip '{'.
+        self.position += 1;
+        self.skip_whitespace();
+
+        let arg = self
+            .word()
+            .parse::<usize>()
+            .map_err(|_| self.error("expected argument index"))?;
+
+        self.skip_whitespace();
+
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Part::Arg(arg));
+        }
+
+        self.expect(',', "expected ',' or '}'")?;
+        self.skip_whitespace();
+
+        let kind = self.word();
+        self.expect(',', "expected ','")?;
+
+        let part = match kind.as_str() {
+            "plural" => {
+                let mut cases = Vec::new();
+
+                while let Some(key) = self.case_key()? {
+                    let selector = match key.strip_prefix('=') {
+                        Some(n) => n.parse().ok().map(PluralSelector::Exact),
+                        None => PluralCategory::parse(&key).map(PluralSelector::Category),
+                    }
+                    .ok_or_else(|| self.error("unknown plural category"))?;
+
+                    cases.push((selector, self.case_message(Some(arg))?));
+                }
+
+                let has_other = cases
+                    .iter()
+                    .any(|(s, _)| *s == PluralSelector::Category(PluralCategory::Other));
+
+                if !has_other {
+                    return Err(self.error("missing 'other' case"));
+                }
+
+                Part::Plural { arg, cases }
+            }
+            "select" => {
+                let mut cases = Vec::new();
+
+                while let Some(key) = self.case_key()? {
+                    cases.push((key, self.case_message(plural_arg)?));
+                }
+
+                if !cases.iter().any(|(key, _)| key == "other") {
+                    return Err(self.error("missing 'other' case"));
+                }
+
+                Part::Select { arg, cases }
+            }
+            _ => return Err(self.error("expected 'plural' or 'select'")),
+        };
+
+        // Skip '}'.
+        self.position += 1;
+
+        Ok(part)
+    }
+
+    /// Key of the next case, or `None` if the argument ends.
+    fn case_key(&mut self) -> Result<Option<String>, MessageFormatError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('}') => Ok(None),
+            Some(_) => match self.word() {
+                key if key.is_empty() => Err(self.error("expected case key")),
+                key => Ok(Some(key)),
+            },
+            None => Err(self.error("unterminated argument")),
+        }
+    }
+
+    fn case_message(&mut self, plural_arg: Option<usize>) -> Result<Vec<Part>, MessageFormatError> {
+        self.expect('{', "expected '{'")?;
+        let parts = self.message(plural_arg)?;
+        self.expect('}', "unterminated case")?;
+
+        Ok(parts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn format(locale: &str, pattern: &str, args: &[MessageArg]) -> String {
+        let locale = Locale::parse(locale).unwrap();
+        MessageFormat::parse(pattern).unwrap().format(&locale, args)
+    }
+
+    #[test]
+    fn plural_categories() {
+        let ru = Locale::new("ru", None);
+        let categories: Vec<_> = [1., 3., 5., 11., 21., 22.]
+            .iter()
+            .map(|n| PluralCategory::of(&ru, *n))
+            .collect();
+
+        use PluralCategory::*;
+        assert_eq!(categories, [One, Few, Many, Many, One, Few]);
+
+        let en = Locale::new("en", None);
+        assert_eq!(PluralCategory::of(&en, 1.), One);
+        assert_eq!(PluralCategory::of(&en, 1.5), Other);
+        assert_eq!(PluralCategory::of(&Locale::new("fr", None), 0.), One);
+    }
+
+    #[test]
+    fn message_format() {
+        let files = "{0, plural, =0 {No files} one {# file} other {# files}} in {1}";
+        let args = |n: i32| [n.into(), "Documents".into()];
+
+        assert_eq!(format("en", files, &args(0)), "No files in Documents");
+        assert_eq!(format("en", files, &args(1)), "1 file in Documents");
+        assert_eq!(format("en", files, &args(1200)), "1,200 files in Documents");
+
+        let gender = "{0, select, female {She} male {He} other {They}} replied";
+        assert_eq!(format("en", gender, &["female".into()]), "She replied");
+        assert_eq!(format("en", gender, &["x".into()]), "They replied");
+
+        assert_eq!(format("en", "It''s '{0}'", &[]), "It's {0}");
+    }
+
+    #[test]
+    fn message_format_errors() {
+        assert!(MessageFormat::parse("{0, plural, one {#}}").is_err());
+        assert!(MessageFormat::parse("{0, number}").is_err());
+        assert!(MessageFormat::parse("{0").is_err());
+        assert!(MessageFormat::parse("}").is_err());
+    }
+}
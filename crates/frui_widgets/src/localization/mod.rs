@@ -0,0 +1,119 @@
+use std::fmt::Display;
+
+use frui::prelude::*;
+
+pub use date_format::*;
+pub use message_format::*;
+pub use number_format::*;
+
+pub mod date_format;
+pub mod message_format;
+pub mod number_format;
+
+/// Identifies a language and optionally a country, e.g. `en-US` or `de`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale {
+    /// Lowercase ISO 639 language code.
+    pub language: String,
+    /// Uppercase ISO 3166 country code.
+    pub country: Option<String>,
+}
+
+impl Locale {
+    pub fn new(language: &str, country: Option<&str>) -> Self {
+        Locale {
+            language: language.to_lowercase(),
+            country: country.map(|c| c.to_uppercase()),
+        }
+    }
+
+    /// Parses locale identifiers like `en`, `en-US`, `en_US` or `en_US.UTF-8`.
+    pub fn parse(identifier: &str) -> Option<Self> {
+        let identifier = identifier.split('.').next()?;
+        let mut parts = identifier.split(|c| c == '-' || c == '_');
+
+        let language = parts.next().filter(|l| !l.is_empty())?;
+        let country = parts.next().filter(|c| !c.is_empty());
+
+        Some(Locale::new(language, country))
+    }
+
+    /// Locale of the system, read from the `LANG` family of environment
+    /// variables.
+    pub fn system() -> Option<Self> {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .filter(|v| v != "C" && v != "POSIX")
+            .find_map(|v| Locale::parse(&v))
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::new("en", Some("US"))
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.country {
+            Some(country) => write!(f, "{}-{}", self.language, country),
+            None => write!(f, "{}", self.language),
+        }
+    }
+}
+
+/// Provides [`Locale`] used by its descendants to format values.
+#[derive(InheritedWidget, Builder)]
+pub struct Localizations<T: Widget> {
+    pub locale: Locale,
+    pub child: T,
+}
+
+impl<T: Widget> InheritedValue for Localizations<T> {
+    type Value = Locale;
+
+    fn value(&self) -> &Self::Value {
+        &self.locale
+    }
+}
+
+impl<T: Widget> InheritedWidget for Localizations<T> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl Localizations<()> {
+    pub fn of<T>(cx: BuildCx<T>) -> Option<Locale> {
+        cx.depend_on::<Locale>().map(|l| (*l).clone())
+    }
+
+    pub fn of_or_default<T>(cx: BuildCx<T>) -> Locale {
+        Self::of(cx).unwrap_or_default()
+    }
+
+    /// Formats `pattern` (see [`MessageFormat`]) in the locale of the closest
+    /// [`Localizations`] ancestor. Invalid patterns are returned unformatted.
+    pub fn message<T>(cx: BuildCx<T>, pattern: &str, args: &[MessageArg]) -> String {
+        match MessageFormat::parse(pattern) {
+            Ok(message) => message.format(&Self::of_or_default(cx), args),
+            Err(e) => {
+                log::warn!("invalid message pattern {:?}: {}", pattern, e);
+                pattern.to_owned()
+            }
+        }
+    }
+
+    /// Decimal [`NumberFormat`] of the closest [`Localizations`] ancestor.
+    pub fn number_format<T>(cx: BuildCx<T>) -> NumberFormat {
+        NumberFormat::decimal(&Self::of_or_default(cx))
+    }
+
+    /// [`DateFormat`] of the given style of the closest [`Localizations`]
+    /// ancestor.
+    pub fn date_format<T>(cx: BuildCx<T>, style: DateStyle) -> DateFormat {
+        DateFormat::date(&Self::of_or_default(cx), style)
+    }
+}
@@ -0,0 +1,332 @@
+use super::Locale;
+
+/// Narrow no-break space, used as a grouping separator by some locales.
+const NNBSP: char = '\u{202F}';
+const NBSP: char = '\u{00A0}';
+
+/// Locale specific symbols used when formatting numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberSymbols {
+    pub decimal_separator: char,
+    pub grouping_separator: char,
+    /// Size of the group closest to the decimal separator.
+    pub primary_grouping: usize,
+    /// Size of the remaining groups, e.g. `2` for `12,34,567` in Hindi.
+    pub secondary_grouping: usize,
+    pub minus_sign: char,
+    /// Whether currency symbol is placed after the number.
+    pub currency_after: bool,
+    /// Whether currency symbol is separated from the number by a space.
+    pub currency_spacing: bool,
+    /// Whether percent sign is separated from the number by a space.
+    pub percent_spacing: bool,
+}
+
+impl NumberSymbols {
+    pub fn of(locale: &Locale) -> Self {
+        let en = NumberSymbols {
+            decimal_separator: '.',
+            grouping_separator: ',',
+            primary_grouping: 3,
+            secondary_grouping: 3,
+            minus_sign: '-',
+            currency_after: false,
+            currency_spacing: false,
+            percent_spacing: false,
+        };
+
+        let continental = NumberSymbols {
+            decimal_separator: ',',
+            grouping_separator: '.',
+            currency_after: true,
+            currency_spacing: true,
+            percent_spacing: true,
+            ..en.clone()
+        };
+
+        match (locale.language.as_str(), locale.country.as_deref()) {
+            ("de", Some("CH")) => NumberSymbols {
+                decimal_separator: '.',
+                grouping_separator: '’',
+                currency_after: false,
+                ..continental
+            },
+            ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da", _) => continental,
+            ("fr" | "pl" | "cs" | "sk" | "ru" | "uk" | "sv" | "nb" | "fi", _) => NumberSymbols {
+                grouping_separator: NNBSP,
+                ..continental
+            },
+            ("hi", _) | ("en", Some("IN")) => NumberSymbols {
+                secondary_grouping: 2,
+                ..en
+            },
+            _ => en,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum NumberStyle {
+    Decimal,
+    Percent,
+    Currency { symbol: String },
+}
+
+/// Formats numbers according to a [`Locale`].
+///
+/// ```
+/// # use frui_widgets::{Locale, NumberFormat};
+/// let format = NumberFormat::decimal(&Locale::new("de", None));
+/// assert_eq!(format.format(1234567.891), "1.234.567,891");
+///
+/// let format = NumberFormat::currency(&Locale::new("en", Some("US")), "USD");
+/// assert_eq!(format.format(-1234.5), "-$1,234.50");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    symbols: NumberSymbols,
+    style: NumberStyle,
+    min_fraction_digits: usize,
+    max_fraction_digits: usize,
+    grouping: bool,
+}
+
+impl NumberFormat {
+    pub fn decimal(locale: &Locale) -> Self {
+        NumberFormat {
+            symbols: NumberSymbols::of(locale),
+            style: NumberStyle::Decimal,
+            min_fraction_digits: 0,
+            max_fraction_digits: 3,
+            grouping: true,
+        }
+    }
+
+    /// Formats fraction `0.25` as `25%`.
+    pub fn percent(locale: &Locale) -> Self {
+        NumberFormat {
+            style: NumberStyle::Percent,
+            max_fraction_digits: 0,
+            ..Self::decimal(locale)
+        }
+    }
+
+    /// Formats amounts of the currency with the given ISO 4217 code.
+    pub fn currency(locale: &Locale, code: &str) -> Self {
+        let (symbol, digits) = currency_info(code);
+
+        NumberFormat {
+            style: NumberStyle::Currency {
+                symbol: symbol.to_owned(),
+            },
+            min_fraction_digits: digits,
+            max_fraction_digits: digits,
+            ..Self::decimal(locale)
+        }
+    }
+
+    /// Formats amounts using a custom currency symbol.
+    pub fn currency_with_symbol(locale: &Locale, symbol: &str, fraction_digits: usize) -> Self {
+        NumberFormat {
+            style: NumberStyle::Currency {
+                symbol: symbol.to_owned(),
+            },
+            min_fraction_digits: fraction_digits,
+            max_fraction_digits: fraction_digits,
+            ..Self::decimal(locale)
+        }
+    }
+
+    #[track_caller]
+    pub fn fraction_digits(mut self, min: usize, max: usize) -> Self {
+        assert!(min <= max, "min must be <= max");
+        self.min_fraction_digits = min;
+        self.max_fraction_digits = max;
+        self
+    }
+
+    pub fn grouping(mut self, grouping: bool) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    pub fn symbols(&self) -> &NumberSymbols {
+        &self.symbols
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        let value = match self.style {
+            NumberStyle::Percent => value * 100.,
+            _ => value,
+        };
+
+        if value.is_nan() {
+            return "NaN".into();
+        }
+
+        let number = if value.is_infinite() {
+            "∞".to_owned()
+        } else {
+            self.format_finite(value.abs())
+        };
+
+        // Don't display minus sign for values that were rounded to zero.
+        let is_zero = value.is_finite() && number.chars().all(|c| !c.is_ascii_digit() || c == '0');
+        let sign = if value.is_sign_negative() && !is_zero {
+            Some(self.symbols.minus_sign)
+        } else {
+            None
+        };
+
+        let mut r = String::new();
+
+        match &self.style {
+            NumberStyle::Decimal => {
+                r.extend(sign);
+                r.push_str(&number);
+            }
+            NumberStyle::Percent => {
+                r.extend(sign);
+                r.push_str(&number);
+
+                if self.symbols.percent_spacing {
+                    r.push(NBSP);
+                }
+
+                r.push('%');
+            }
+            NumberStyle::Currency { symbol } => {
+                r.extend(sign);
+
+                if self.symbols.currency_after {
+                    r.push_str(&number);
+                    r.push(NBSP);
+                    r.push_str(symbol);
+                } else {
+                    r.push_str(symbol);
+
+                    if self.symbols.currency_spacing {
+                        r.push(NBSP);
+                    }
+
+                    r.push_str(&number);
+                }
+            }
+        }
+
+        r
+    }
+
+    fn format_finite(&self, value: f64) -> String {
+        let formatted = format!("{:.*}", self.max_fraction_digits, value);
+
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (formatted.as_str(), ""),
+        };
+
+        // Remove trailing zeros above the minimum number of fraction digits.
+        let mut fraction = fraction.trim_end_matches('0');
+        if fraction.len() < self.min_fraction_digits {
+            fraction = &formatted[integer.len() + 1..][..self.min_fraction_digits];
+        }
+
+        let mut r = if self.grouping {
+            self.group(integer)
+        } else {
+            integer.to_owned()
+        };
+
+        if !fraction.is_empty() {
+            r.push(self.symbols.decimal_separator);
+            r.push_str(fraction);
+        }
+
+        r
+    }
+
+    fn group(&self, integer: &str) -> String {
+        let NumberSymbols {
+            primary_grouping,
+            secondary_grouping,
+            grouping_separator,
+            ..
+        } = self.symbols;
+
+        if integer.len() <= primary_grouping {
+            return integer.to_owned();
+        }
+
+        let (head, primary) = integer.split_at(integer.len() - primary_grouping);
+
+        let mut groups = vec![primary];
+        let mut head = head;
+
+        while head.len() > secondary_grouping {
+            let (rest, group) = head.split_at(head.len() - secondary_grouping);
+            groups.push(group);
+            head = rest;
+        }
+
+        groups.push(head);
+        groups.reverse();
+
+        groups.join(&grouping_separator.to_string())
+    }
+}
+
+/// Symbol and number of fraction digits of a currency.
+fn currency_info(code: &str) -> (&str, usize) {
+    match code {
+        "USD" => ("$", 2),
+        "EUR" => ("€", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "CNY" => ("¥", 2),
+        "KRW" => ("₩", 0),
+        "INR" => ("₹", 2),
+        "RUB" => ("₽", 2),
+        "PLN" => ("zł", 2),
+        "CHF" => ("CHF", 2),
+        "BRL" => ("R$", 2),
+        _ => (code, 2),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decimal() {
+        let en = NumberFormat::decimal(&Locale::new("en", Some("US")));
+        assert_eq!(en.format(1234567.891), "1,234,567.891");
+        assert_eq!(en.format(-0.0001), "0");
+        assert_eq!(en.format(f64::INFINITY), "∞");
+        assert_eq!(en.format(f64::NEG_INFINITY), "-∞");
+        assert_eq!(en.format(12.5), "12.5");
+
+        let de = NumberFormat::decimal(&Locale::new("de", None));
+        assert_eq!(de.format(-1234.5), "-1.234,5");
+
+        let hi = NumberFormat::decimal(&Locale::new("hi", None));
+        assert_eq!(hi.format(1234567.), "12,34,567");
+    }
+
+    #[test]
+    fn currency_and_percent() {
+        let en = Locale::new("en", Some("US"));
+        assert_eq!(
+            NumberFormat::currency(&en, "USD").format(-1234.5),
+            "-$1,234.50"
+        );
+        assert_eq!(NumberFormat::currency(&en, "JPY").format(1234.4), "¥1,234");
+        assert_eq!(NumberFormat::percent(&en).format(0.256), "26%");
+
+        let fr = Locale::new("fr", None);
+        assert_eq!(
+            NumberFormat::currency(&fr, "EUR").format(1234.5),
+            "1\u{202F}234,50\u{00A0}€"
+        );
+    }
+}
@@ -0,0 +1,473 @@
+//! Playback of Lottie animations (JSON exported with Bodymovin), rendered
+//! with rlottie. Requires the `lottie` feature, which links the rlottie C++
+//! library.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    path::Path,
+    rc::Rc,
+    time::Duration,
+};
+
+use druid_shell::piet::{ImageBuf, ImageFormat, InterpolationMode};
+use frui::{app::metrics::WindowMetrics, prelude::*, render::*};
+
+use crate::*;
+
+/// Parsed Lottie animation. Cloning it is cheap.
+#[derive(Clone)]
+pub struct LottieComposition {
+    animation: Rc<RefCell<rlottie::Animation>>,
+    size: Size,
+    frame_count: usize,
+    frame_rate: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LottieError(String);
+
+impl fmt::Display for LottieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "loading Lottie animation failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for LottieError {}
+
+impl LottieComposition {
+    pub fn from_json(json: impl Into<Vec<u8>>) -> Result<Self, LottieError> {
+        // rlottie keeps animations with a non-empty cache key alive forever.
+        match rlottie::Animation::from_data(json, "", "") {
+            Some(animation) => Ok(Self::new(animation)),
+            None => Err(LottieError("invalid animation data".into())),
+        }
+    }
+
+    /// Loads an animation from a file, resolving images it refers to
+    /// relative to its directory.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LottieError> {
+        let path = path.as_ref();
+
+        match rlottie::Animation::from_file(path) {
+            Some(animation) => Ok(Self::new(animation)),
+            None => Err(LottieError(format!("couldn't load {}", path.display()))),
+        }
+    }
+
+    fn new(animation: rlottie::Animation) -> Self {
+        let size = animation.size();
+
+        LottieComposition {
+            size: Size::new(size.width as f64, size.height as f64),
+            frame_count: animation.totalframe(),
+            frame_rate: animation.framerate(),
+            animation: Rc::new(RefCell::new(animation)),
+        }
+    }
+
+    /// Size the animation was designed for, in display points.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    /// Frames per second.
+    pub fn frame_rate(&self) -> f64 {
+        self.frame_rate
+    }
+
+    pub fn duration(&self) -> Duration {
+        frames_duration(self.frame_count, self.frame_rate)
+    }
+
+    /// Whether both refer to the same parsed animation.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.animation, &other.animation)
+    }
+
+    /// Renders `frame` scaled to `width` by `height` pixels.
+    fn render(&self, frame: usize, width: usize, height: usize) -> ImageBuf {
+        let mut surface = rlottie::Surface::new(rlottie::Size::new(width, height));
+        self.animation.borrow_mut().render(frame, &mut surface);
+
+        // Pixels are rendered as premultiplied BGRA.
+        let mut pixels = surface.data_as_bytes().to_vec();
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+
+        ImageBuf::from_raw(pixels, ImageFormat::RgbaPremul, width, height)
+    }
+}
+
+impl PartialEq for LottieComposition {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl fmt::Debug for LottieComposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LottieComposition")
+            .field("size", &self.size)
+            .field("frame_count", &self.frame_count)
+            .field("frame_rate", &self.frame_rate)
+            .finish()
+    }
+}
+
+/// Controls playback of a [`Lottie`] animation: pausing, seeking, looping
+/// and playing only a segment of its frames (e.g. a single transition of an
+/// animated icon).
+///
+/// Like [`AnimationController`], it should be kept in the state of the
+/// widget using it, and clones of it control the same playback.
+///
+/// ```ignore
+/// let controller = LottieController::new(&checkmark);
+/// controller.set_looping(false);
+///
+/// // On tap:
+/// match checked {
+///     true => controller.play_segment(0, 30),
+///     false => controller.play_segment(30, 60),
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LottieController {
+    animation: AnimationController,
+    frame_count: usize,
+    frame_rate: f64,
+    /// Played frames, the end is exclusive.
+    segment: Rc<Cell<(usize, usize)>>,
+    looping: Rc<Cell<bool>>,
+}
+
+impl LottieController {
+    /// Creates a paused controller of all frames of `composition`, which
+    /// loops once played.
+    pub fn new(composition: &LottieComposition) -> Self {
+        LottieController {
+            animation: AnimationController::new(composition.duration()),
+            frame_count: composition.frame_count,
+            frame_rate: composition.frame_rate,
+            segment: Rc::new(Cell::new((0, composition.frame_count))),
+            looping: Rc::new(Cell::new(true)),
+        }
+    }
+
+    /// Plays from the current frame. Unless looping, playing again once the
+    /// end of the segment was reached starts from its beginning.
+    pub fn play(&self) {
+        if self.looping.get() {
+            self.animation.repeat(false);
+        } else {
+            if self.animation.status() == AnimationStatus::Completed {
+                self.animation.set_progress(0.);
+            }
+
+            self.animation.forward();
+        }
+    }
+
+    pub fn pause(&self) {
+        self.animation.stop();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.animation.is_animating()
+    }
+
+    /// Plays frames from `start` up to (but not including) `end`, from the
+    /// beginning.
+    pub fn play_segment(&self, start: usize, end: usize) {
+        self.set_segment(start, end);
+        self.animation.set_progress(0.);
+        self.play();
+    }
+
+    /// Limits playback to frames from `start` up to (but not including)
+    /// `end`, keeping the progress within the segment. Takes effect from the
+    /// next call to [`play`](Self::play).
+    pub fn set_segment(&self, start: usize, end: usize) {
+        let end = end.min(self.frame_count);
+        let start = start.min(end);
+
+        self.segment.set((start, end));
+        self.animation
+            .set_duration(frames_duration(end - start, self.frame_rate));
+    }
+
+    pub fn segment(&self) -> (usize, usize) {
+        self.segment.get()
+    }
+
+    /// Whether the segment plays over and over. Takes effect from the next
+    /// call to [`play`](Self::play).
+    pub fn set_looping(&self, looping: bool) {
+        self.looping.set(looping);
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping.get()
+    }
+
+    /// Pauses playback and jumps to `progress` (from 0 to 1) of the segment.
+    pub fn seek(&self, progress: f64) {
+        self.animation.set_progress(progress);
+    }
+
+    /// Progress within the segment, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        self.animation.progress()
+    }
+
+    /// Frame shown at the current progress.
+    pub fn frame(&self) -> usize {
+        let (start, end) = self.segment.get();
+        let frame = start + ((end - start) as f64 * self.progress()) as usize;
+
+        frame.min(end.saturating_sub(1)).max(start)
+    }
+}
+
+impl Listenable for LottieController {
+    type Key = AnimationListenerKey;
+
+    fn listen(&self, callback: impl Fn() + 'static) -> Self::Key {
+        self.animation.listen(callback)
+    }
+
+    fn unlisten(&self, key: Self::Key) {
+        self.animation.unlisten(key)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        self.animation.ptr_eq(&other.animation)
+    }
+}
+
+/// Plays a [`LottieComposition`], stretched to the size of the widget.
+///
+/// Unless `width` or `height` is set, the widget has the size the animation
+/// was designed for. If only one of them is set, the other one keeps its
+/// aspect ratio.
+///
+/// Without a `controller` the animation plays in a loop.
+///
+/// ```ignore
+/// Lottie::new(loading.clone()).width(64.)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Lottie {
+    pub composition: LottieComposition,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    pub controller: Option<LottieController>,
+}
+
+impl Lottie {
+    pub fn new(composition: LottieComposition) -> Self {
+        Lottie {
+            composition,
+            width: None,
+            height: None,
+            controller: None,
+        }
+    }
+}
+
+pub struct LottieState {
+    /// Controller looping the animation if none was passed, together with
+    /// the composition it was created for.
+    fallback: RefCell<Option<(LottieComposition, LottieController)>>,
+    listener: ControllerListener<LottieController>,
+}
+
+impl WidgetState for Lottie {
+    type State = LottieState;
+
+    fn create_state(&self) -> Self::State {
+        LottieState {
+            fallback: RefCell::new(None),
+            listener: ControllerListener::default(),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().listener.unlisten();
+    }
+}
+
+impl ViewWidget for Lottie {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => {
+                let mut fallback = state.fallback.borrow_mut();
+
+                match &*fallback {
+                    Some((composition, controller)) if *composition == self.composition => {
+                        controller.clone()
+                    }
+                    _ => {
+                        // Starting playback notifies listeners, which mustn't
+                        // rebuild the widget while it is being built.
+                        state.listener.unlisten();
+
+                        let controller = LottieController::new(&self.composition);
+                        controller.play();
+
+                        *fallback = Some((self.composition.clone(), controller.clone()));
+                        controller
+                    }
+                }
+            }
+        };
+
+        let updater = cx.state_updater();
+        state
+            .listener
+            .listen(&controller, move || updater.update(|_| {}));
+
+        RawLottie {
+            composition: self.composition.clone(),
+            frame: controller.frame(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[derive(RenderWidget)]
+struct RawLottie {
+    composition: LottieComposition,
+    frame: usize,
+    width: Option<f64>,
+    height: Option<f64>,
+}
+
+#[doc(hidden)]
+#[derive(Default)]
+pub struct RawLottieState {
+    /// Last rendered frame, its size in pixels and the image of it.
+    rendered: Option<(usize, (usize, usize), ImageBuf)>,
+    cache: CanvasImageCache,
+}
+
+impl RenderState for RawLottie {
+    type State = RawLottieState;
+
+    fn create_state(&self) -> Self::State {
+        RawLottieState::default()
+    }
+}
+
+impl RenderWidget for RawLottie {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let Size { width, height } = self.composition.size();
+        let aspect_ratio = if height > 0. { width / height } else { 1. };
+
+        let size = match (self.width, self.height) {
+            (Some(width), Some(height)) => Size::new(width, height),
+            (Some(width), None) => Size::new(width, width / aspect_ratio),
+            (None, Some(height)) => Size::new(height * aspect_ratio, height),
+            (None, None) => self.composition.size(),
+        };
+
+        constraints.constrain(size)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let scale = WindowMetrics::current().scale;
+        let pixels = (
+            (size.width * scale).round() as usize,
+            (size.height * scale).round() as usize,
+        );
+
+        if pixels.0 == 0 || pixels.1 == 0 {
+            return;
+        }
+
+        let mut state = cx.render_state_mut();
+
+        // Paints happen more often than frames change, e.g. while scrolling.
+        let frame = match &state.rendered {
+            Some((frame, size, image)) if *frame == self.frame && *size == pixels => image.clone(),
+            _ => {
+                let image = self.composition.render(self.frame, pixels.0, pixels.1);
+                state.rendered = Some((self.frame, pixels, image.clone()));
+                image
+            }
+        };
+
+        let rect = DruidRect::from_origin_size(*offset, size);
+
+        state.cache.with(canvas, &frame, |canvas, image| {
+            canvas.draw_image(image, rect, InterpolationMode::Bilinear);
+        });
+    }
+}
+
+fn frames_duration(frames: usize, frame_rate: f64) -> Duration {
+    match frame_rate > 0. {
+        true => Duration::from_secs_f64(frames as f64 / frame_rate),
+        false => Duration::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Composition without layers, 60 frames long at 30 frames per second.
+    const EMPTY: &str = r#"{"v":"5.7.4","fr":30,"ip":0,"op":60,"w":100,"h":50,"layers":[]}"#;
+
+    #[test]
+    fn reads_composition() {
+        let composition = LottieComposition::from_json(EMPTY).unwrap();
+
+        assert_eq!(composition.size(), Size::new(100., 50.));
+        assert_eq!(composition.frame_count(), 60);
+        assert_eq!(composition.duration(), Duration::from_secs(2));
+
+        assert!(LottieComposition::from_json("{").is_err());
+    }
+
+    #[test]
+    fn maps_progress_to_frames_of_segment() {
+        let composition = LottieComposition::from_json(EMPTY).unwrap();
+        let controller = LottieController::new(&composition);
+
+        controller.seek(0.5);
+        assert_eq!(controller.frame(), 30);
+
+        // The end of a segment is exclusive.
+        controller.set_segment(10, 20);
+        controller.seek(1.);
+        assert_eq!(controller.frame(), 19);
+        controller.seek(0.);
+        assert_eq!(controller.frame(), 10);
+
+        // Segments are clamped to frames of the composition.
+        controller.set_segment(50, 100);
+        assert_eq!(controller.segment(), (50, 60));
+    }
+
+    #[test]
+    fn computes_duration_of_frames() {
+        assert_eq!(frames_duration(45, 30.), Duration::from_millis(1500));
+        assert_eq!(frames_duration(45, 0.), Duration::ZERO);
+    }
+}
@@ -0,0 +1,170 @@
+use frui::{
+    app::metrics::{WindowMetrics, WindowMetricsListenerKey},
+    prelude::*,
+    render::*,
+};
+
+use crate::*;
+
+/// Window metrics and platform settings provided by [`MediaQuery`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaQueryData {
+    /// Size of the window in display points.
+    pub size: Size,
+    /// Number of pixels per display point (device pixel ratio).
+    pub scale: f64,
+    /// Number of font pixels per display point preferred by the user.
+    pub text_scale_factor: f64,
+    pub platform_brightness: Brightness,
+    /// Parts of the window permanently obscured by the system UI.
+    pub padding: EdgeInsets,
+    /// Parts of the window temporarily obscured by the system UI, e.g. the
+    /// soft keyboard.
+    pub view_insets: EdgeInsets,
+}
+
+impl From<WindowMetrics> for MediaQueryData {
+    fn from(metrics: WindowMetrics) -> Self {
+        let insets = |i: druid_shell::kurbo::Insets| EdgeInsets::from_ltrb(i.x0, i.y0, i.x1, i.y1);
+
+        MediaQueryData {
+            size: metrics.size,
+            scale: metrics.scale,
+            text_scale_factor: metrics.text_scale_factor,
+            platform_brightness: metrics.platform_brightness,
+            padding: insets(metrics.padding),
+            view_insets: insets(metrics.view_insets),
+        }
+    }
+}
+
+/// Provides [`MediaQueryData`] of the application window to its descendants,
+/// rebuilding those which depend on it when the window is resized, moved to a
+/// monitor of different density or when platform settings change. Usually
+/// placed at the root of the application.
+///
+/// ```ignore
+/// let data = MediaQuery::of(cx);
+///
+/// Theme::builder()
+///     .data(match data.platform_brightness {
+///         Brightness::Light => ThemeData::light(),
+///         Brightness::Dark => ThemeData::dark(),
+///     })
+///     .child(Home)
+/// ```
+#[derive(InheritedWidget, Builder)]
+pub struct MediaQuery<W: Widget> {
+    pub child: W,
+}
+
+impl MediaQuery<()> {
+    pub fn builder() -> Self {
+        MediaQuery { child: () }
+    }
+
+    /// Data of the closest [`MediaQuery`] ancestor. If there is none, current
+    /// window metrics are returned, but the widget won't be rebuilt when they
+    /// change.
+    pub fn of<T>(cx: BuildCx<T>) -> MediaQueryData {
+        match cx.depend_on_inherited_widget::<Self>() {
+            Some(state) => state.as_ref().data,
+            None => WindowMetrics::current().into(),
+        }
+    }
+
+    /// Same as [`MediaQuery::of`], but relays out instead of rebuilding the
+    /// widget when the data changes.
+    pub fn of_layout<T>(cx: &LayoutCx<T>) -> MediaQueryData {
+        match cx.depend_on_inherited_widget::<Self>() {
+            Some(state) => state.as_ref().data,
+            None => WindowMetrics::current().into(),
+        }
+    }
+}
+
+pub struct MediaQueryState {
+    data: MediaQueryData,
+    listener: Option<WindowMetricsListenerKey>,
+}
+
+impl<W: Widget> WidgetState for MediaQuery<W> {
+    type State = MediaQueryState;
+
+    fn create_state(&self) -> Self::State {
+        MediaQueryState {
+            data: WindowMetrics::current().into(),
+            listener: None,
+        }
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        let updater = cx.state_updater();
+        let key = WindowMetrics::listen(move || {
+            updater.update(|state: &mut MediaQueryState| {
+                state.data = WindowMetrics::current().into();
+            })
+        });
+
+        cx.state_mut().listener = Some(key);
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        if let Some(key) = cx.state_mut().listener.take() {
+            WindowMetrics::unlisten(key);
+        }
+    }
+}
+
+impl<W: Widget> InheritedWidget for MediaQuery<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+/// Pads `child` away from parts of the window obscured by the system UI,
+/// including the soft keyboard, as reported by [`MediaQuery`].
+#[derive(ViewWidget, Builder)]
+pub struct SafeArea<W: Widget> {
+    pub child: W,
+    pub left: bool,
+    pub top: bool,
+    pub right: bool,
+    pub bottom: bool,
+    /// Padding applied even if the window isn't obscured.
+    pub minimum: EdgeInsets,
+}
+
+impl SafeArea<()> {
+    pub fn builder() -> Self {
+        SafeArea {
+            child: (),
+            left: true,
+            top: true,
+            right: true,
+            bottom: true,
+            minimum: EdgeInsets::ZERO,
+        }
+    }
+}
+
+impl<W: Widget> ViewWidget for SafeArea<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let data = MediaQuery::of(cx);
+        let (padding, insets, min) = (data.padding, data.view_insets, self.minimum);
+
+        let side = |enabled: bool, padding: f64, inset: f64, min: f64| match enabled {
+            true => padding.max(inset).max(min),
+            false => min,
+        };
+
+        Padding::builder()
+            .padding(EdgeInsets::from_ltrb(
+                side(self.left, padding.left, insets.left, min.left),
+                side(self.top, padding.top, insets.top, min.top),
+                side(self.right, padding.right, insets.right, min.right),
+                side(self.bottom, padding.bottom, insets.bottom, min.bottom),
+            ))
+            .child(&self.child)
+    }
+}
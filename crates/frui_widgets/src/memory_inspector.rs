@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use frui::{
+    app::diagnostics::{CacheUsage, MemoryDiagnostics, MemoryReport},
+    prelude::*,
+};
+
+/// Shows memory retained by the widget tree and by framework caches (see
+/// [`MemoryDiagnostics`]) in a panel drawn over `child`.
+///
+/// Subtrees are listed up to `depth` levels below the root, largest first.
+/// The report is refreshed every `refresh_period`.
+///
+/// ```ignore
+/// MemoryInspector::builder()
+///     .enabled(cfg!(debug_assertions))
+///     .child(App)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct MemoryInspector<W: Widget> {
+    pub child: W,
+    /// If `false`, only `child` is shown and no reports are made.
+    pub enabled: bool,
+    pub depth: usize,
+    pub refresh_period: Duration,
+}
+
+impl MemoryInspector<()> {
+    pub fn builder() -> Self {
+        MemoryInspector {
+            child: (),
+            enabled: true,
+            depth: 2,
+            refresh_period: Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryInspectorState {
+    lines: Vec<String>,
+}
+
+impl<W: Widget> WidgetState for MemoryInspector<W> {
+    type State = MemoryInspectorState;
+
+    fn create_state(&self) -> Self::State {
+        MemoryInspectorState::default()
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        if !self.enabled {
+            return;
+        }
+
+        // Reports are made from timers, since the tree can't be borrowed
+        // while it's being built.
+        let depth = self.depth;
+        let refresh = move |state: &mut MemoryInspectorState| {
+            state.lines = match MemoryDiagnostics::report() {
+                Some(report) => summary(&report, &MemoryDiagnostics::caches(), depth),
+                None => Vec::new(),
+            };
+        };
+
+        cx.timer_once(Duration::ZERO, refresh);
+        cx.timer_periodic(self.refresh_period, refresh);
+    }
+}
+
+impl<W: Widget> ViewWidget for MemoryInspector<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        if !self.enabled {
+            return (&self.child).boxed();
+        }
+
+        let lines = cx
+            .state()
+            .lines
+            .iter()
+            .map(|line| Text::new(line.clone()).size(12.).color(Color::WHITE))
+            .collect::<Vec<_>>();
+
+        Stack::builder()
+            .fit(StackFit::Passthrough)
+            .children((
+                &self.child,
+                Positioned::builder().top(8.).right(8.).child(
+                    ColoredBox::builder()
+                        .color(Color::rgba(0., 0., 0., 0.75))
+                        .child(
+                            Padding::builder().padding(EdgeInsets::all(8.)).child(
+                                Column::builder()
+                                    .cross_axis_alignment(CrossAxisAlignment::Start)
+                                    .children(lines),
+                            ),
+                        ),
+                ),
+            ))
+            .boxed()
+    }
+}
+
+/// Lines shown by [`MemoryInspector`]: subtrees of `report` up to `depth`
+/// levels deep, largest first, followed by caches.
+fn summary(
+    report: &MemoryReport,
+    caches: &[(&'static str, CacheUsage)],
+    depth: usize,
+) -> Vec<String> {
+    fn subtrees(report: &MemoryReport, depth: usize, max_depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!(
+            "{:indent$}{}: {} nodes, {}",
+            "",
+            report.widget,
+            report.nodes,
+            format_bytes(report.bytes),
+            indent = depth * 2
+        ));
+
+        if depth < max_depth {
+            let mut children = report.children.iter().collect::<Vec<_>>();
+            children.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+
+            for child in children {
+                subtrees(child, depth + 1, max_depth, lines);
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    subtrees(report, 0, depth, &mut lines);
+
+    for (name, usage) in caches {
+        lines.push(format!(
+            "{}: {} entries, {}",
+            name,
+            usage.entries,
+            format_bytes(usage.bytes)
+        ));
+    }
+
+    lines
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1048575 => format!("{:.1} KiB", bytes as f64 / 1024.),
+        _ => format!("{:.1} MiB", bytes as f64 / 1048576.),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn report(widget: &'static str, bytes: usize, children: Vec<MemoryReport>) -> MemoryReport {
+        MemoryReport {
+            widget,
+            nodes: 1 + children.iter().map(|c| c.nodes).sum::<usize>(),
+            bytes: bytes + children.iter().map(|c| c.bytes).sum::<usize>(),
+            children,
+        }
+    }
+
+    #[test]
+    fn summary_lists_largest_subtrees_first_up_to_depth() {
+        let tree = report(
+            "Root",
+            100,
+            vec![
+                report("Small", 10, vec![]),
+                report("Large", 2000, vec![report("Leaf", 10, vec![])]),
+            ],
+        );
+
+        let caches = [(
+            "image cache",
+            CacheUsage {
+                entries: 2,
+                bytes: 3 * 1048576,
+            },
+        )];
+
+        assert_eq!(
+            summary(&tree, &caches, 1),
+            [
+                "Root: 4 nodes, 2.1 KiB",
+                "  Large: 2 nodes, 2.0 KiB",
+                "  Small: 1 nodes, 10 B",
+                "image cache: 2 entries, 3.0 MiB",
+            ]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use frui::prelude::*;
+
+use super::{Navigator, NavigatorHandle, NavigatorInner, Route, RouteId};
+
+/// Dispatches back requests (e.g. of the system back button on mobile, or of
+/// the browser on the web) to navigators. Navigators dispatch Escape key
+/// presses themselves.
+pub struct BackDispatcher;
+
+impl BackDispatcher {
+    /// Pops the topmost route of the innermost shown navigator, unless a
+    /// [`WillPopScope`] in it vetoes, see [`NavigatorHandle::handle_back`].
+    ///
+    /// Returns `false` if there is no route to pop, in which case the
+    /// application is usually closed.
+    pub fn dispatch() -> bool {
+        let root = mounted().into_iter().rev().find(|n| n.parent().is_none());
+
+        root.map_or(false, |root| root.handle_back())
+    }
+}
+
+impl NavigatorHandle {
+    /// Pops the topmost route, unless a [`WillPopScope`] in it vetoes.
+    ///
+    /// Returns `false` if there is no route to pop (only the initial route is
+    /// left), and `true` if the route was popped or the pop was vetoed.
+    pub fn maybe_pop(&self) -> bool {
+        if !self.can_pop() {
+            return false;
+        }
+
+        let route = self.current().id();
+        let callbacks = self
+            .0
+            .will_pop
+            .borrow()
+            .iter()
+            .filter(|w| w.route == route)
+            .map(|w| w.callback)
+            .collect::<Vec<_>>();
+
+        // Safety: Callbacks are unregistered before their scopes are unmounted.
+        if callbacks
+            .into_iter()
+            .all(|callback| unsafe { (*callback)() })
+        {
+            self.pop();
+        }
+
+        true
+    }
+
+    /// Passes a back request to the innermost navigator nested in the topmost
+    /// route of this navigator, or pops the topmost route of this navigator
+    /// (see [`maybe_pop`](Self::maybe_pop)) if none of them has a route to pop.
+    ///
+    /// Returns `false` if there is no route to pop.
+    pub fn handle_back(&self) -> bool {
+        let current = self.current().id();
+
+        for nested in mounted().into_iter().rev() {
+            if nested.parent().as_ref() == Some(self)
+                && nested.0.host.get() == Some(current)
+                && nested.handle_back()
+            {
+                return true;
+            }
+        }
+
+        self.maybe_pop()
+    }
+
+    /// ## Safety:
+    ///
+    /// `callback` must live until [`remove_will_pop`](Self::remove_will_pop)
+    /// is called with the returned key.
+    unsafe fn add_will_pop<'a>(
+        &self,
+        route: RouteId,
+        callback: *const (dyn Fn() -> bool + 'a),
+    ) -> WillPopKey {
+        let key = WillPopKey::next();
+
+        self.0.will_pop.borrow_mut().push(WillPop {
+            route,
+            key,
+            callback: std::mem::transmute(callback),
+        });
+
+        key
+    }
+
+    fn remove_will_pop(&self, key: WillPopKey) {
+        self.0.will_pop.borrow_mut().retain(|w| w.key != key);
+    }
+}
+
+/// Callback of a [`WillPopScope`] in `route`.
+pub(super) struct WillPop {
+    route: RouteId,
+    key: WillPopKey,
+    callback: *const dyn Fn() -> bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct WillPopKey(u64);
+
+impl WillPopKey {
+    fn next() -> Self {
+        thread_local! {
+            static NEXT: Cell<u64> = Cell::new(0);
+        }
+
+        WillPopKey(NEXT.with(|next| next.replace(next.get() + 1)))
+    }
+}
+
+/// Calls `on_will_pop` before the route it's in is popped by a back request
+/// (e.g. by pressing Escape) or by tapping the barrier of the route. The route
+/// is kept if it returns `false`, e.g. to ask whether to discard unsaved
+/// changes first:
+///
+/// ```ignore
+/// WillPopScope {
+///     on_will_pop: || {
+///         if has_unsaved_changes {
+///             navigator.push(Route::modal(|| DiscardChangesDialog));
+///         }
+///
+///         !has_unsaved_changes
+///     },
+///     child: editor,
+/// }
+/// ```
+///
+/// Routes can still be popped directly, through [`NavigatorHandle::pop`].
+#[derive(ViewWidget)]
+pub struct WillPopScope<W: Widget, F: Fn() -> bool> {
+    pub on_will_pop: F,
+    pub child: W,
+}
+
+impl<W: Widget, F: Fn() -> bool> WidgetState for WillPopScope<W, F> {
+    type State = Option<(NavigatorHandle, WillPopKey)>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        if let (Some(navigator), Some(route)) = (Navigator::of(cx), Route::of(cx)) {
+            let on_will_pop: &dyn Fn() -> bool = &self.on_will_pop;
+            let key = unsafe { navigator.add_will_pop(route, on_will_pop) };
+
+            *cx.state_mut() = Some((navigator, key));
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        if let Some((navigator, key)) = cx.state_mut().take() {
+            navigator.remove_will_pop(key);
+        }
+    }
+}
+
+impl<W: Widget, F: Fn() -> bool> ViewWidget for WillPopScope<W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+/// Registers `navigator`, once it's shown for the first time.
+pub(super) fn register(navigator: &NavigatorHandle) {
+    NAVIGATORS.with(|n| n.borrow_mut().push(Rc::downgrade(&navigator.0)));
+}
+
+/// Navigators in the tree, in the order in which they were shown for the first
+/// time (so nested navigators are after their parents).
+fn mounted() -> Vec<NavigatorHandle> {
+    NAVIGATORS.with(|n| {
+        let mut navigators = n.borrow_mut();
+        navigators.retain(|n| n.strong_count() > 0);
+
+        navigators
+            .iter()
+            .filter_map(|n| n.upgrade().map(NavigatorHandle))
+            .filter(|n| n.is_mounted())
+            .collect()
+    })
+}
+
+thread_local! {
+    static NAVIGATORS: RefCell<Vec<Weak<NavigatorInner>>> = Default::default();
+}
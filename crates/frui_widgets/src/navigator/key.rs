@@ -0,0 +1,66 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::NavigatorHandle;
+
+/// Key of a [`Navigator`](super::Navigator), which gives access to its routes
+/// from outside of the widget tree (e.g. from services), and keeps them while
+/// the navigator isn't in the tree.
+///
+/// Navigator given a key which was given to a navigator before shows the
+/// routes of that navigator, instead of its initial route. That way tabs with
+/// their own navigators keep their history when switching between them:
+///
+/// ```ignore
+/// Navigator::new(Route::page(|| Feed)).key(NavigatorKey::named("feed"))
+///
+/// // Somewhere else, e.g. after receiving a notification.
+/// if let Some(navigator) = NavigatorKey::named("feed").current() {
+///     navigator.push(Route::page(|| Post));
+/// }
+/// ```
+///
+/// A key shouldn't be given to more than one navigator in the tree at once.
+#[derive(Clone, Default)]
+pub struct NavigatorKey(Rc<RefCell<Option<NavigatorHandle>>>);
+
+impl PartialEq for NavigatorKey {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl NavigatorKey {
+    pub fn new() -> Self {
+        NavigatorKey::default()
+    }
+
+    /// Returns the key registered under `name`, which is the same key every
+    /// time it is called with the same `name` on this thread.
+    pub fn named(name: &str) -> Self {
+        NAMED_KEYS.with(|keys| {
+            keys.borrow_mut()
+                .entry(name.to_owned())
+                .or_default()
+                .clone()
+        })
+    }
+
+    /// Returns routes of the navigator this key was given to, or `None` if
+    /// it wasn't given to any navigator yet.
+    pub fn current(&self) -> Option<NavigatorHandle> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns routes of the navigator this key was given to, or the routes
+    /// returned by `init` if there is no such navigator yet.
+    pub(super) fn current_or_insert(
+        &self,
+        init: impl FnOnce() -> NavigatorHandle,
+    ) -> NavigatorHandle {
+        self.0.borrow_mut().get_or_insert_with(init).clone()
+    }
+}
+
+thread_local! {
+    static NAMED_KEYS: RefCell<HashMap<String, NavigatorKey>> = Default::default();
+}
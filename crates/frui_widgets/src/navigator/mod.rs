@@ -0,0 +1,348 @@
+//! Stacks of routes, e.g. screens of an application and dialogs shown above
+//! them.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    KeyEvent,
+};
+use frui::{
+    app::interactions::{Interaction, Interactions},
+    prelude::*,
+};
+
+use crate::*;
+
+mod back;
+mod key;
+mod observer;
+mod route;
+
+pub use self::back::*;
+pub use self::key::*;
+pub use self::observer::*;
+pub use self::route::*;
+
+struct NavigatorInner {
+    /// Pushed routes, the topmost one is the last. There is always at least
+    /// one route.
+    routes: RefCell<Vec<Route>>,
+    observers: RefCell<Vec<Rc<dyn NavigatorObserver>>>,
+    /// Rebuilds the navigator once its routes change. It's `None` while the
+    /// navigator isn't in the tree.
+    rebuild: RefCell<Option<StateUpdater<NavigatorState>>>,
+    /// Closest navigator above this navigator.
+    parent: RefCell<Option<NavigatorHandle>>,
+    /// Route of the parent navigator which this navigator is shown in.
+    host: Cell<Option<RouteId>>,
+    /// Callbacks of [`WillPopScope`]s in the routes.
+    will_pop: RefCell<Vec<WillPop>>,
+    /// Whether observers were notified about the initial route.
+    started: Cell<bool>,
+}
+
+/// Handle to the routes of a [`Navigator`], see [`Navigator::of`].
+#[derive(Clone)]
+pub struct NavigatorHandle(Rc<NavigatorInner>);
+
+impl PartialEq for NavigatorHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl NavigatorHandle {
+    fn new(initial_route: Route) -> Self {
+        NavigatorHandle(Rc::new(NavigatorInner {
+            routes: RefCell::new(vec![initial_route]),
+            observers: RefCell::new(Vec::new()),
+            rebuild: RefCell::new(None),
+            parent: RefCell::new(None),
+            host: Cell::new(None),
+            will_pop: RefCell::new(Vec::new()),
+            started: Cell::new(false),
+        }))
+    }
+
+    /// Pushes `route` above the topmost route.
+    pub fn push(&self, route: Route) {
+        let previous = self.current();
+        self.0.routes.borrow_mut().push(route.clone());
+        self.changed(Some(&previous), &route);
+
+        for observer in self.observers() {
+            observer.did_push(&route, Some(&previous));
+        }
+    }
+
+    /// Pops the topmost route, unless it's the only one. Returns whether the
+    /// route was popped.
+    pub fn pop(&self) -> bool {
+        let route = {
+            let mut routes = self.0.routes.borrow_mut();
+
+            match routes.len() > 1 {
+                true => routes.pop().unwrap(),
+                false => return false,
+            }
+        };
+
+        let previous = self.current();
+        self.changed(Some(&route), &previous);
+
+        for observer in self.observers() {
+            observer.did_pop(&route, Some(&previous));
+        }
+
+        true
+    }
+
+    /// Replaces the topmost route with `route`.
+    pub fn replace(&self, route: Route) {
+        let old = std::mem::replace(
+            self.0.routes.borrow_mut().last_mut().unwrap(),
+            route.clone(),
+        );
+
+        self.changed(Some(&old), &route);
+
+        for observer in self.observers() {
+            observer.did_replace(&route, &old);
+        }
+    }
+
+    pub fn can_pop(&self) -> bool {
+        self.0.routes.borrow().len() > 1
+    }
+
+    /// Topmost route.
+    pub fn current(&self) -> Route {
+        self.0.routes.borrow().last().unwrap().clone()
+    }
+
+    /// Closest navigator above this navigator, if it's nested in a route of
+    /// another navigator.
+    pub fn parent(&self) -> Option<NavigatorHandle> {
+        self.0.parent.borrow().clone()
+    }
+
+    /// Outermost navigator above this navigator, or this navigator if it
+    /// isn't nested.
+    pub fn root(&self) -> NavigatorHandle {
+        match self.parent() {
+            Some(parent) => parent.root(),
+            None => self.clone(),
+        }
+    }
+
+    /// Whether the navigator is in the tree.
+    fn is_mounted(&self) -> bool {
+        self.0
+            .rebuild
+            .borrow()
+            .as_ref()
+            .map_or(false, |r| r.is_alive())
+    }
+
+    fn observers(&self) -> Vec<Rc<dyn NavigatorObserver>> {
+        // Observers may push or pop routes while being notified.
+        self.0.observers.borrow().clone()
+    }
+
+    fn changed(&self, from: Option<&Route>, to: &Route) {
+        let rebuild = self.0.rebuild.borrow().clone();
+
+        if let Some(rebuild) = rebuild {
+            rebuild.update(|_| {});
+        }
+
+        if let Some(name) = to.name() {
+            Interactions::report(Interaction::RouteChanged {
+                from: from.and_then(|r| r.name()).map(String::from),
+                to: name.to_owned(),
+            });
+        }
+    }
+}
+
+/// Shows a stack of [`Route`]s, starting with `initial_route`. Routes are
+/// pushed and popped through the [`NavigatorHandle`] returned by
+/// [`Navigator::of`]:
+///
+/// ```ignore
+/// Navigator::new(Route::page(|| Home).named("/"))
+///     .observer(RouteObserver::new())
+/// ```
+///
+/// Observers are notified about every change of the routes, and named routes
+/// are reported to [`Interactions`] as well.
+///
+/// Pressing Escape pops the topmost route, see [`BackDispatcher`].
+///
+/// Navigators can be nested in routes of other navigators, e.g. to give every
+/// tab its own routes. [`Navigator::of`] returns the closest one, and outer
+/// navigators can be reached through [`NavigatorHandle::parent`] (e.g. to
+/// show a dialog above all tabs). Routes of a navigator with a
+/// [`NavigatorKey`] are kept in the key, so they outlive the navigator.
+#[derive(ViewWidget)]
+pub struct Navigator {
+    pub initial_route: Route,
+    pub observers: Vec<Rc<dyn NavigatorObserver>>,
+    pub key: Option<NavigatorKey>,
+}
+
+impl Navigator {
+    pub fn new(initial_route: Route) -> Self {
+        Navigator {
+            initial_route,
+            observers: Vec::new(),
+            key: None,
+        }
+    }
+
+    pub fn key(mut self, key: NavigatorKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn observer(mut self, observer: impl NavigatorObserver + 'static) -> Self {
+        self.observers.push(Rc::new(observer));
+        self
+    }
+
+    /// Returns the closest navigator above the widget of `cx`.
+    pub fn of<T>(cx: BuildCx<T>) -> Option<NavigatorHandle> {
+        cx.depend_on::<NavigatorHandle>()
+            .map(|handle| (*handle).clone())
+    }
+
+    /// Routes of this navigator, which are kept in its key if it has one.
+    fn handle(&self, cx: BuildCx<Self>) -> NavigatorHandle {
+        match &self.key {
+            Some(key) => key.current_or_insert(|| NavigatorHandle::new(self.initial_route.clone())),
+            None => cx.state().routes.clone(),
+        }
+    }
+
+    /// Makes `handle` the routes shown by this navigator.
+    fn attach(&self, cx: BuildCx<Self>, handle: &NavigatorHandle) {
+        let previous = cx.state().attached.replace(Some(handle.clone()));
+
+        // Navigator was given a different key.
+        if let Some(previous) = previous.filter(|previous| previous != handle) {
+            previous.0.rebuild.take();
+        }
+
+        *handle.0.observers.borrow_mut() = self.observers.clone();
+        *handle.0.rebuild.borrow_mut() = Some(cx.state_updater());
+        *handle.0.parent.borrow_mut() = Navigator::of(cx);
+        handle.0.host.set(Route::of(cx));
+
+        if !handle.0.started.replace(true) {
+            back::register(handle);
+
+            for observer in handle.observers() {
+                observer.did_push(&handle.current(), None);
+            }
+        }
+    }
+}
+
+pub struct NavigatorState {
+    /// Routes of a navigator without a key.
+    routes: NavigatorHandle,
+    /// Routes shown by the navigator.
+    attached: RefCell<Option<NavigatorHandle>>,
+}
+
+impl WidgetState for Navigator {
+    type State = NavigatorState;
+
+    fn create_state(&self) -> Self::State {
+        NavigatorState {
+            routes: NavigatorHandle::new(self.initial_route.clone()),
+            attached: RefCell::new(None),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        let attached = cx.state().attached.take();
+
+        if let Some(handle) = attached {
+            handle.0.rebuild.take();
+        }
+    }
+}
+
+impl ViewWidget for Navigator {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let handle = self.handle(cx);
+        self.attach(cx, &handle);
+
+        let routes = handle.0.routes.borrow().clone();
+
+        // Routes below the topmost opaque route are covered by it.
+        let covered = routes.iter().rposition(|r| r.is_opaque()).unwrap_or(0);
+
+        let routes = routes
+            .into_iter()
+            .enumerate()
+            .map(|(n, route)| {
+                let handle = handle.clone();
+
+                LocalKey::new(
+                    ValueKey(route.id()),
+                    RouteView {
+                        route,
+                        offstage: n < covered,
+                        on_dismiss: move || {
+                            handle.maybe_pop();
+                        },
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let back = handle.clone();
+
+        KeyboardEventDetector {
+            // Nested navigators are reached through the outermost one.
+            on_event: move |event: KeyEvent| {
+                if event.state == KeyState::Down
+                    && event.key == Key::Escape
+                    && back.parent().is_none()
+                {
+                    back.handle_back();
+                }
+            },
+            child: NavigatorScope {
+                handle,
+                child: Stack::builder().fit(StackFit::Expand).children(routes),
+            },
+        }
+    }
+}
+
+#[derive(InheritedWidget)]
+struct NavigatorScope<W: Widget> {
+    handle: NavigatorHandle,
+    child: W,
+}
+
+impl<W: Widget> InheritedWidget for NavigatorScope<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl<W: Widget> InheritedValue for NavigatorScope<W> {
+    type Value = NavigatorHandle;
+
+    fn value(&self) -> &Self::Value {
+        &self.handle
+    }
+}
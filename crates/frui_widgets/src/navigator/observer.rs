@@ -0,0 +1,115 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+use super::{Route, RouteId};
+
+/// Observes routes pushed to and popped from a [`Navigator`](super::Navigator),
+/// e.g. to collect analytics.
+///
+/// `previous` is the route below the pushed (or popped) route, which is the
+/// topmost route once it's popped.
+#[allow(unused_variables)]
+pub trait NavigatorObserver {
+    fn did_push(&self, route: &Route, previous: Option<&Route>) {}
+
+    fn did_pop(&self, route: &Route, previous: Option<&Route>) {}
+
+    fn did_replace(&self, new: &Route, old: &Route) {}
+}
+
+/// Widget (or its state) notified when the route it's shown in is covered or
+/// uncovered, see [`RouteObserver`].
+pub trait RouteAware {
+    /// Route was pushed.
+    fn did_push(&self) {}
+
+    /// Route was popped (or replaced).
+    fn did_pop(&self) {}
+
+    /// Another route was pushed above this route.
+    fn did_push_next(&self) {}
+
+    /// Route above this route was popped, so this route is the topmost one.
+    fn did_pop_next(&self) {}
+}
+
+/// [`NavigatorObserver`] which notifies [`RouteAware`] subscribers about
+/// changes of the route they subscribed to, e.g. to pause a video once its
+/// route is covered:
+///
+/// ```ignore
+/// // Given to the navigator with `Navigator::new(home).observer(observer)`.
+/// let observer = RouteObserver::new();
+///
+/// // In the state of the video player.
+/// let aware: Rc<dyn RouteAware> = Rc::new(PauseOnCover(player.clone()));
+/// observer.subscribe(Route::of(cx).unwrap(), &aware);
+/// ```
+///
+/// Subscribers are held weakly, so they are unsubscribed once dropped.
+#[derive(Clone, Default)]
+pub struct RouteObserver {
+    subscribers: Rc<RefCell<HashMap<RouteId, Vec<Weak<dyn RouteAware>>>>>,
+}
+
+impl RouteObserver {
+    pub fn new() -> Self {
+        RouteObserver::default()
+    }
+
+    pub fn subscribe(&self, route: RouteId, aware: &Rc<dyn RouteAware>) {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let subscribers = subscribers.entry(route).or_default();
+
+        subscribers.retain(|s| s.strong_count() > 0);
+        subscribers.push(Rc::downgrade(aware));
+    }
+
+    pub fn unsubscribe(&self, aware: &Rc<dyn RouteAware>) {
+        for subscribers in self.subscribers.borrow_mut().values_mut() {
+            subscribers.retain(|s| s.strong_count() > 0 && !s.ptr_eq(&Rc::downgrade(aware)));
+        }
+    }
+
+    /// Calls `f` with every subscriber of `route`.
+    fn notify(&self, route: &Route, f: impl Fn(&dyn RouteAware)) {
+        // Subscribers may (un)subscribe while being notified.
+        let subscribers = match self.subscribers.borrow().get(&route.id()) {
+            Some(subscribers) => subscribers.clone(),
+            None => return,
+        };
+
+        for aware in subscribers.iter().filter_map(|s| s.upgrade()) {
+            f(&*aware);
+        }
+    }
+}
+
+impl NavigatorObserver for RouteObserver {
+    fn did_push(&self, route: &Route, previous: Option<&Route>) {
+        self.notify(route, |aware| aware.did_push());
+
+        if let Some(previous) = previous {
+            self.notify(previous, |aware| aware.did_push_next());
+        }
+    }
+
+    fn did_pop(&self, route: &Route, previous: Option<&Route>) {
+        self.notify(route, |aware| aware.did_pop());
+        self.subscribers.borrow_mut().remove(&route.id());
+
+        if let Some(previous) = previous {
+            self.notify(previous, |aware| aware.did_pop_next());
+        }
+    }
+
+    fn did_replace(&self, new: &Route, old: &Route) {
+        self.notify(old, |aware| aware.did_pop());
+        self.subscribers.borrow_mut().remove(&old.id());
+
+        self.notify(new, |aware| aware.did_push());
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    rc::Rc,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::*;
+
+/// Identity of a [`Route`], shared by its clones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RouteId(u64);
+
+impl RouteId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+
+        RouteId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Screen or dialog which can be pushed to a [`Navigator`].
+///
+/// Routes below an opaque route aren't painted, but keep their state until
+/// they are popped. Modal routes aren't opaque and are shown above a
+/// [`Barrier`], which can be customized:
+///
+/// ```ignore
+/// let dialog = Route::modal(|| ConfirmDialog)
+///     .named("/confirm")
+///     .barrier_color(Color::BLACK.with_alpha(0.3))
+///     .barrier_dismissible(false);
+///
+/// Navigator::of(cx).unwrap().push(dialog);
+/// ```
+#[derive(Clone)]
+pub struct Route {
+    id: RouteId,
+    name: Option<String>,
+    builder: Rc<dyn Fn() -> Box<dyn Widget>>,
+    opaque: bool,
+    barrier: Option<Barrier>,
+}
+
+impl Route {
+    /// Opaque route covering the routes below it.
+    pub fn page<W: Widget + 'static>(builder: impl Fn() -> W + 'static) -> Self {
+        Route {
+            id: RouteId::next(),
+            name: None,
+            builder: Rc::new(move || builder().boxed()),
+            opaque: true,
+            barrier: None,
+        }
+    }
+
+    /// Route shown above the routes below it, which are separated by the
+    /// default [`Barrier`].
+    pub fn modal<W: Widget + 'static>(builder: impl Fn() -> W + 'static) -> Self {
+        Route {
+            opaque: false,
+            barrier: Some(Barrier::default()),
+            ..Route::page(builder)
+        }
+    }
+
+    /// Sets the name reported to [`NavigatorObserver`]s and as
+    /// [`Interaction::RouteChanged`](frui::app::interactions::Interaction).
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn barrier_color(mut self, color: Color) -> Self {
+        self.barrier_mut().color = color;
+        self
+    }
+
+    /// Whether tapping the barrier pops this route.
+    pub fn barrier_dismissible(mut self, dismissible: bool) -> Self {
+        self.barrier_mut().dismissible = dismissible;
+        self
+    }
+
+    /// Duration of the fade in of the barrier color once the route is pushed.
+    pub fn barrier_duration(mut self, duration: Duration) -> Self {
+        self.barrier_mut().duration = duration;
+        self
+    }
+
+    pub fn barrier_curve(mut self, curve: Curve) -> Self {
+        self.barrier_mut().curve = curve;
+        self
+    }
+
+    pub fn id(&self) -> RouteId {
+        self.id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.opaque
+    }
+
+    pub fn barrier(&self) -> Option<&Barrier> {
+        self.barrier.as_ref()
+    }
+
+    /// Returns the route which the widget of `cx` is built in.
+    pub fn of<T>(cx: BuildCx<T>) -> Option<RouteId> {
+        cx.depend_on::<RouteId>().map(|id| *id)
+    }
+
+    fn barrier_mut(&mut self) -> &mut Barrier {
+        self.barrier.get_or_insert_with(Barrier::default)
+    }
+}
+
+/// Layer between a [`Route`] and the routes below it, which absorbs pointer
+/// events and fades in its color once the route is pushed.
+#[derive(Debug, Clone)]
+pub struct Barrier {
+    pub color: Color,
+    /// Whether tapping the barrier pops the route.
+    pub dismissible: bool,
+    pub duration: Duration,
+    pub curve: Curve,
+}
+
+impl Default for Barrier {
+    fn default() -> Self {
+        Barrier {
+            color: Color::BLACK.with_alpha(0.54),
+            dismissible: true,
+            duration: Duration::from_millis(200),
+            curve: Curve::EaseOut,
+        }
+    }
+}
+
+/// Shows the content of `route` above its barrier.
+#[derive(ViewWidget)]
+pub(super) struct RouteView<F: Fn()> {
+    pub route: Route,
+    /// Routes covered by an opaque route are laid out, but aren't painted
+    /// nor hit tested.
+    pub offstage: bool,
+    pub on_dismiss: F,
+}
+
+impl<F: Fn()> ViewWidget for RouteView<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        // Keeps the same shape of the subtree whether or not the route has a
+        // barrier, so that the content keeps its state.
+        let barrier = match &self.route.barrier {
+            Some(barrier) => ModalBarrier {
+                barrier: barrier.clone(),
+                on_dismiss: &self.on_dismiss,
+            }
+            .boxed(),
+            None => ().boxed(),
+        };
+
+        RouteScope {
+            id: self.route.id,
+            child: RouteLayout {
+                offstage: self.offstage,
+                opaque: self.route.opaque,
+                barrier,
+                content: (self.route.builder)(),
+            },
+        }
+    }
+}
+
+#[derive(InheritedWidget)]
+struct RouteScope<W: Widget> {
+    id: RouteId,
+    child: W,
+}
+
+impl<W: Widget> InheritedWidget for RouteScope<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl<W: Widget> InheritedValue for RouteScope<W> {
+    type Value = RouteId;
+
+    fn value(&self) -> &Self::Value {
+        &self.id
+    }
+}
+
+/// Fills the space it is given with the color of `barrier`, fading it in once
+/// mounted. Calls `on_dismiss` once tapped, if the barrier is dismissible.
+#[derive(ViewWidget)]
+pub struct ModalBarrier<F: Fn()> {
+    pub barrier: Barrier,
+    pub on_dismiss: F,
+}
+
+impl<F: Fn()> WidgetState for ModalBarrier<F> {
+    type State = AnimationController;
+
+    fn create_state(&self) -> Self::State {
+        AnimationController::new(self.barrier.duration).curve(self.barrier.curve)
+    }
+
+    fn mount<'a>(&'a self, cx: BuildCx<'a, Self>) {
+        cx.state().forward();
+    }
+}
+
+impl<F: Fn()> ViewWidget for ModalBarrier<F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let color = &self.barrier.color;
+        let alpha = color.as_rgba().3;
+
+        PointerListener::builder()
+            .on_pointer_up(move |_| {
+                if self.barrier.dismissible {
+                    (self.on_dismiss)();
+                }
+            })
+            .child(AnimatedBuilder::new(cx.state().clone(), move |t| {
+                ColoredBox {
+                    color: color.clone().with_alpha(alpha * t),
+                    child: (),
+                }
+            }))
+    }
+}
+
+/// Stretches `barrier` over the space it is given, and lays out `content`
+/// above it. Content of opaque routes is stretched as well, content of other
+/// routes (e.g. dialogs) is centered, so that the barrier is hit around it.
+#[derive(RenderWidget)]
+struct RouteLayout<B: Widget, C: Widget> {
+    offstage: bool,
+    opaque: bool,
+    barrier: B,
+    content: C,
+}
+
+impl<B: Widget, C: Widget> RenderWidget for RouteLayout<B, C> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![(&self.barrier).boxed(), (&self.content).boxed()]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let size = constraints.biggest();
+        cx.child(0).layout(Constraints::new_tight(size));
+
+        let content = cx.child(1);
+        let content_size = match self.opaque {
+            true => content.layout(Constraints::new_tight(size)),
+            false => content.layout(constraints.loosen()),
+        };
+        content.set_offset(Alignment::CENTER.along(size - content_size));
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        if !self.offstage {
+            cx.paint_children(canvas, offset);
+        }
+    }
+}
+
+impl<B: Widget, C: Widget> HitTest for RouteLayout<B, C> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        if self.offstage || !cx.layout_box().contains(point) {
+            return false;
+        }
+
+        // Content is hit first, the barrier is hit anywhere around it.
+        for mut child in cx.children().rev() {
+            if child.hit_test_with_paint_offset(point) {
+                break;
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,49 @@
+use frui::prelude::*;
+use frui::render::*;
+
+/// Paints `child` partially transparent, as if it was painted into a separate
+/// image which was then blended with `opacity` (from 0 to 1).
+///
+/// Opacities other than 0 and 1 paint `child` into an offscreen layer on
+/// every frame, which makes them much more expensive. Anything `child` paints
+/// outside of its layout box is cut off in that case.
+///
+/// Transparent children are not painted, but still receive pointer events.
+///
+/// ```ignore
+/// Opacity::builder()
+///     .opacity(if enabled { 1. } else { 0.38 })
+///     .child(Text::new("Save"))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct Opacity<W: Widget> {
+    pub child: W,
+    pub opacity: f64,
+}
+
+impl Opacity<()> {
+    pub fn builder() -> Self {
+        Opacity {
+            child: (),
+            opacity: 1.,
+        }
+    }
+}
+
+impl<W: Widget> RenderWidget for Opacity<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let rect = Rect::from_origin_size(offset, cx.size());
+
+        cx.with_opacity(canvas, self.opacity, rect, |cx, canvas| {
+            cx.child(0).paint(canvas, offset)
+        });
+    }
+}
@@ -0,0 +1,362 @@
+//! Painting widgets above the rest of the application, e.g. dropdowns or
+//! tooltips opened by the widget they are anchored to.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use frui::prelude::*;
+use frui::render::*;
+
+/// Overlay children which [`OverlayPortal`]s below an [`Overlay`] hand over
+/// to it while they are painted.
+#[derive(Default)]
+struct OverlayEntries {
+    /// Space available to overlay children, known before children of the
+    /// overlay are laid out.
+    size: Cell<Size>,
+    /// Overlay children waiting to be painted in the current frame.
+    queued: RefCell<Vec<PaintCxOS>>,
+    /// Overlay children painted in the last frame, in paint order.
+    painted: RefCell<Vec<PaintCxOS>>,
+}
+
+#[derive(Clone, Default)]
+pub struct OverlayHandle(Rc<OverlayEntries>);
+
+impl PartialEq for OverlayHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Layer above `child`, in which [`OverlayPortal`]s below it show their
+/// overlay children. It's usually placed around the whole application, so
+/// that overlay children can use the whole window.
+///
+/// Overlay fills the space it is given (if it's bounded).
+#[derive(RenderWidget, Builder)]
+pub struct Overlay<W: Widget> {
+    pub child: W,
+}
+
+impl Overlay<()> {
+    pub fn builder() -> Self {
+        Overlay { child: () }
+    }
+}
+
+impl<W: Widget> WidgetState for Overlay<W> {
+    type State = OverlayHandle;
+
+    fn create_state(&self) -> Self::State {
+        OverlayHandle::default()
+    }
+}
+
+impl<W: Widget> RenderWidget for Overlay<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![OverlayScope {
+            handle: cx.state().clone(),
+            child: &self.child,
+        }]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let biggest = constraints.biggest();
+        cx.widget_state().0.size.set(biggest);
+
+        let child_size = cx.child(0).layout(constraints);
+
+        match biggest.width.is_finite() && biggest.height.is_finite() {
+            true => biggest,
+            false => child_size,
+        }
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let entries = cx.widget_state().0.clone();
+        entries.queued.borrow_mut().clear();
+
+        cx.child(0).paint(canvas, offset);
+
+        let mut painted = Vec::new();
+
+        // Overlay children can contain portals too, which queue their own
+        // overlay children above them.
+        loop {
+            let queued = entries.queued.take();
+
+            if queued.is_empty() {
+                break;
+            }
+
+            for mut child in queued {
+                child.paint(canvas, offset);
+                painted.push(child);
+            }
+        }
+
+        *entries.painted.borrow_mut() = painted;
+    }
+}
+
+impl<W: Widget> HitTest for Overlay<W> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        let painted = cx.widget_state().0.painted.borrow().clone();
+        let parent = (**cx).clone();
+
+        for child in painted.iter().rev() {
+            if parent
+                .adopt(child)
+                .hit_test_with_paint_transform(point, &parent)
+            {
+                return true;
+            }
+        }
+
+        if cx.layout_box().contains(point) {
+            cx.hit_test_children(point);
+            return true;
+        }
+
+        false
+    }
+}
+
+#[derive(InheritedWidget)]
+struct OverlayScope<W: Widget> {
+    handle: OverlayHandle,
+    child: W,
+}
+
+impl<W: Widget> InheritedWidget for OverlayScope<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl<W: Widget> InheritedValue for OverlayScope<W> {
+    type Value = OverlayHandle;
+
+    fn value(&self) -> &Self::Value {
+        &self.handle
+    }
+}
+
+/// Shows `overlay_child` in the closest [`Overlay`] while `visible`, above
+/// the `child` of that overlay.
+///
+/// Overlay child is a part of the portal, so it is built together with
+/// `child`, inherits from the same ancestors and is removed along with the
+/// portal. It is given the space of the whole overlay, which makes it a good
+/// fit for a [`Follower`](crate::Follower) of `child`:
+///
+/// ```ignore
+/// OverlayPortal::builder()
+///     .visible(state.open)
+///     .child(FollowerTarget::builder().link(link.clone()).child(button))
+///     .overlay_child(Follower::builder().link(link.clone()).child(menu))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct OverlayPortal<W: Widget, O: Widget> {
+    pub visible: bool,
+    pub child: W,
+    /// Built only while `visible`.
+    pub overlay_child: O,
+}
+
+impl OverlayPortal<(), ()> {
+    pub fn builder() -> Self {
+        OverlayPortal {
+            visible: false,
+            child: (),
+            overlay_child: (),
+        }
+    }
+}
+
+impl<W: Widget, O: Widget> RenderState for OverlayPortal<W, O> {
+    /// Overlay the overlay child was laid out in.
+    type State = Option<OverlayHandle>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl<W: Widget, O: Widget> RenderWidget for OverlayPortal<W, O> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        match self.visible {
+            true => vec![(&self.child).boxed(), (&self.overlay_child).boxed()],
+            false => vec![(&self.child).boxed()],
+        }
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let size = cx.child(0).layout(constraints);
+
+        *cx.render_state_mut() = match self.visible {
+            true => {
+                let handle = cx
+                    .depend_on::<OverlayHandle>()
+                    .map(|handle| (*handle).clone())
+                    .expect("`OverlayPortal` has no `Overlay` ancestor");
+
+                let overlay = handle.0.size.get();
+                cx.child(1)
+                    .layout(Constraints::new(0., overlay.width, 0., overlay.height));
+
+                Some(handle)
+            }
+            false => None,
+        };
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset);
+
+        let handle = cx.render_state().clone();
+
+        if let Some(handle) = handle {
+            handle.0.queued.borrow_mut().push(cx.child(1));
+        }
+    }
+}
+
+impl<W: Widget, O: Widget> HitTest for OverlayPortal<W, O> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        // Overlay child is hit tested by the overlay.
+        if cx.layout_box().contains(point) {
+            let parent = (**cx).clone();
+
+            if let Some(mut child) = cx.child(0) {
+                child.hit_test_with_paint_transform(point, &parent);
+            }
+
+            return true;
+        }
+
+        false
+    }
+}
+
+impl<W: Widget, O: Widget> Intrinsics for OverlayPortal<W, O> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        cx.child(0).min_intrinsic_width(height)
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, height: f64) -> f64 {
+        cx.child(0).max_intrinsic_width(height)
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        cx.child(0).min_intrinsic_height(width)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        cx.child(0).max_intrinsic_height(width)
+    }
+}
+
+impl<W: Widget, O: Widget> Baseline for OverlayPortal<W, O> {
+    fn distance_to_baseline(&self, cx: &LayoutCx<Self>) -> Option<f64> {
+        cx.child(0).distance_to_baseline()
+    }
+}
+
+#[cfg(all(test, not(feature = "miri")))]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use frui::app::offscreen;
+
+    use crate::Column;
+
+    use super::*;
+
+    /// Records its name and the biggest size it was allowed once painted.
+    #[derive(RenderWidget)]
+    struct Probe {
+        name: &'static str,
+        painted: Arc<Mutex<Vec<(&'static str, Size)>>>,
+    }
+
+    impl RenderState for Probe {
+        type State = Size;
+
+        fn create_state(&self) -> Self::State {
+            Size::default()
+        }
+    }
+
+    impl RenderWidget for Probe {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![] as Vec<()>
+        }
+
+        fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+            *cx.render_state_mut() = constraints.biggest();
+            constraints.constrain(Size::new(10., 10.))
+        }
+
+        fn paint(&self, cx: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {
+            let biggest = *cx.render_state();
+            self.painted.lock().unwrap().push((self.name, biggest));
+        }
+    }
+
+    fn paint(visible: bool) -> Vec<(&'static str, Size)> {
+        let painted = Arc::new(Mutex::new(Vec::new()));
+
+        let root = {
+            let painted = painted.clone();
+            let probe = move |name| Probe {
+                name,
+                painted: painted.clone(),
+            };
+
+            move || {
+                Overlay::builder().child(
+                    Column::builder().children((
+                        OverlayPortal::builder()
+                            .visible(visible)
+                            .child(probe("anchor"))
+                            .overlay_child(probe("overlay")),
+                        probe("sibling"),
+                    )),
+                )
+            }
+        };
+
+        offscreen::render(Size::new(100., 50.), 1., root)
+            .join()
+            .unwrap()
+            .unwrap();
+
+        let painted = painted.lock().unwrap();
+        painted.clone()
+    }
+
+    #[test]
+    fn overlay_child_is_painted_above_the_overlay() {
+        let painted = paint(true);
+        let names = painted.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+
+        assert_eq!(names, ["anchor", "sibling", "overlay"]);
+
+        // Overlay child is given the space of the whole overlay.
+        assert_eq!(painted[2].1, Size::new(100., 50.));
+    }
+
+    #[test]
+    fn hidden_overlay_child_is_not_painted() {
+        let painted = paint(false);
+        let names = painted.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+
+        assert_eq!(names, ["anchor", "sibling"]);
+    }
+}
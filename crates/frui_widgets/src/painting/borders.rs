@@ -4,7 +4,7 @@ use druid_shell::{kurbo::BezPath, piet::StrokeStyle};
 use frui::prelude::*;
 use frui::render::Rect;
 
-use crate::EdgeInsets;
+use crate::{DashPattern, EdgeInsets, StrokeCap, StrokeJoin, StrokeOptions};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BorderStyle {
@@ -20,6 +20,8 @@ pub enum BorderStyle {
     /// ```
     /// You can use offset to change the start position of the line and make an animation.
     Dash(Vec<f64>, f64),
+    /// Round dots as wide as the side, separated by gaps of the same width.
+    Dotted,
 }
 
 /// A side of a border of a box.
@@ -28,6 +30,9 @@ pub struct BorderSide {
     pub color: Color,
     pub width: f64,
     pub style: BorderStyle,
+    /// Ignored by [`BorderStyle::Dotted`], which always uses round caps.
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
 }
 
 impl BorderSide {
@@ -35,8 +40,44 @@ impl BorderSide {
         color: Color::BLACK,
         width: 0.0,
         style: BorderStyle::None,
+        cap: StrokeCap::Butt,
+        join: StrokeJoin::miter(),
     };
 
+    pub fn solid(color: Color, width: f64) -> Self {
+        BorderSide {
+            color,
+            width,
+            style: BorderStyle::Solid,
+            cap: StrokeCap::default(),
+            join: StrokeJoin::default(),
+        }
+    }
+
+    /// Dashes `dash` long separated by `gap`.
+    pub fn dashed(color: Color, width: f64, dash: f64, gap: f64) -> Self {
+        Self::solid(color, width).style(BorderStyle::Dash(vec![dash, gap], 0.))
+    }
+
+    pub fn dotted(color: Color, width: f64) -> Self {
+        Self::solid(color, width).style(BorderStyle::Dotted)
+    }
+
+    pub fn style(mut self, style: BorderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: StrokeJoin) -> Self {
+        self.join = join;
+        self
+    }
+
     pub fn merge(a: &BorderSide, b: &BorderSide) -> Self {
         assert!(BorderSide::can_merge(a, b));
         let a_is_none = a.style == BorderStyle::None && a.width == 0.0;
@@ -49,9 +90,8 @@ impl BorderSide {
             a.clone()
         } else {
             BorderSide {
-                color: a.color.clone(),
                 width: a.width + b.width,
-                style: a.style.clone(),
+                ..a.clone()
             }
         }
     }
@@ -76,18 +116,34 @@ impl BorderSide {
             color: color.unwrap_or(self.color.clone()),
             width: width.unwrap_or(self.width),
             style: style.unwrap_or(self.style.clone()),
+            ..self.clone()
         }
     }
 
-    pub fn to_stroke_style(&self) -> Option<StrokeStyle> {
-        if let BorderStyle::Dash(ref dash, offset) = self.style {
-            let mut stroke = StrokeStyle::new();
-            stroke.set_dash_pattern(dash.clone());
-            stroke.set_dash_offset(offset);
-            Some(stroke)
-        } else {
-            None
+    /// How this side is stroked, or `None` if it isn't painted at all.
+    pub fn to_stroke_options(&self) -> Option<StrokeOptions> {
+        if self.width <= 0.0 {
+            return None;
         }
+
+        let options = StrokeOptions::new(self.width).cap(self.cap).join(self.join);
+
+        match self.style {
+            BorderStyle::None => None,
+            BorderStyle::Solid => Some(options),
+            BorderStyle::Dash(ref dash, offset) => {
+                Some(options.dash(DashPattern::new(dash.clone()).offset(offset)))
+            }
+            BorderStyle::Dotted => Some(
+                options
+                    .cap(StrokeCap::Round)
+                    .dash(DashPattern::dotted(self.width, self.width)),
+            ),
+        }
+    }
+
+    pub fn to_stroke_style(&self) -> Option<StrokeStyle> {
+        self.to_stroke_options().map(|o| o.to_stroke_style())
     }
 }
 
@@ -96,13 +152,13 @@ impl Mul<f64> for BorderSide {
 
     fn mul(self, rhs: f64) -> Self::Output {
         BorderSide {
-            color: self.color,
             width: (self.width * rhs).max(0.0),
             style: if rhs <= 0.0 {
                 BorderStyle::None
             } else {
                 self.style
             },
+            ..self
         }
     }
 }
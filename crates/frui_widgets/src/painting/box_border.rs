@@ -1,7 +1,7 @@
 use std::ops::Add;
 
 use druid_shell::{
-    kurbo::{BezPath, Circle, RoundedRect, Shape},
+    kurbo::{BezPath, Circle, Line, RoundedRect, Shape},
     piet::RenderContext,
 };
 use frui::{
@@ -10,8 +10,8 @@ use frui::{
 };
 
 use crate::{
-    borders::BorderSide, BorderRadius, BorderStyle, Directional, EdgeInsets, ShapeBorder,
-    TextDirection, EPSILON,
+    borders::BorderSide, BorderRadius, BorderStyle, CanvasStrokeExt, Directional, EdgeInsets,
+    PixelSnap, ShapeBorder, TextDirection, EPSILON,
 };
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -20,6 +20,14 @@ pub enum BoxShape {
     Rectangle,
 }
 
+/// Border of a box, with each side styled separately. Sides which are
+/// [`BorderSide::NONE`] aren't painted.
+///
+/// ```ignore
+/// BoxBorder::default()
+///     .bottom(BorderSide::solid(Color::grey(0.5), 1.))
+///     .left(BorderSide::dashed(Color::RED, 2., 4., 2.))
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct BoxBorder {
     pub top: BorderSide,
@@ -28,6 +36,8 @@ pub struct BoxBorder {
     pub left: BorderSide,
 }
 
+pub type Border = BoxBorder;
+
 impl Directional for BoxBorder {
     type Output = BoxBorder;
 
@@ -85,12 +95,7 @@ impl ShapeBorder for BoxBorder {
 
 impl BoxBorder {
     pub fn all(color: Color, width: f64, stroke_style: BorderStyle) -> Self {
-        let side = BorderSide {
-            color,
-            width,
-            style: stroke_style,
-        };
-        Self::from_border_side(side)
+        Self::from_border_side(BorderSide::solid(color, width).style(stroke_style))
     }
 
     pub fn from_border_side(side: BorderSide) -> Self {
@@ -102,6 +107,36 @@ impl BoxBorder {
         }
     }
 
+    /// `vertical` on the left and right, `horizontal` on the top and bottom.
+    pub fn symmetric(vertical: BorderSide, horizontal: BorderSide) -> Self {
+        Self {
+            top: horizontal.clone(),
+            right: vertical.clone(),
+            bottom: horizontal,
+            left: vertical,
+        }
+    }
+
+    pub fn top(mut self, side: BorderSide) -> Self {
+        self.top = side;
+        self
+    }
+
+    pub fn right(mut self, side: BorderSide) -> Self {
+        self.right = side;
+        self
+    }
+
+    pub fn bottom(mut self, side: BorderSide) -> Self {
+        self.bottom = side;
+        self
+    }
+
+    pub fn left(mut self, side: BorderSide) -> Self {
+        self.left = side;
+        self
+    }
+
     pub fn merge(a: &BoxBorder, b: &BoxBorder) -> Self {
         Self {
             top: BorderSide::merge(&a.top, &b.top),
@@ -128,11 +163,24 @@ impl BoxBorder {
     }
 
     fn style_is_uniform(&self) -> bool {
-        self.top.style == self.right.style
-            && self.right.style == self.bottom.style
-            && self.bottom.style == self.left.style
+        let same = |a: &BorderSide, b: &BorderSide| {
+            a.style == b.style && a.cap == b.cap && a.join == b.join
+        };
+
+        same(&self.top, &self.right)
+            && same(&self.right, &self.bottom)
+            && same(&self.bottom, &self.left)
     }
 
+    /// Paints the border inside of `rect`.
+    ///
+    /// Rounded corners and circles are only supported by uniform borders.
+    /// Edges and line widths are snapped to device pixels.
+    ///
+    /// Uniform borders used to be stroked centered on the edges of `rect`,
+    /// half of their width outside of it. Like borders with different sides,
+    /// they are now painted inside of `rect`, so they don't overlap the
+    /// surroundings of the box and line up with the insets of its child.
     pub fn paint(
         &self,
         canvas: &mut Canvas,
@@ -140,34 +188,178 @@ impl BoxBorder {
         shape: Option<BoxShape>,
         border_radius: BorderRadius,
     ) {
-        assert!(
-            self.is_uniform(),
-            "BoxBorder::paint() can only paint uniform borders"
-        );
-        if self.top.width == 0.0 {
-            return;
+        let snap = PixelSnap::for_canvas(canvas);
+        let rect = snap.snap_rect(rect);
+
+        if self.is_uniform() {
+            return self.paint_uniform(canvas, rect, shape, border_radius, snap);
+        }
+
+        if shape == Some(BoxShape::Circle) || border_radius != BorderRadius::ZERO {
+            log::warn!("Borders with different sides are painted without rounded corners (yet)");
         }
 
-        let path = if let Some(shape) = shape {
-            match shape {
-                BoxShape::Circle => {
-                    let center = rect.center();
-                    let radius = rect.width().min(rect.height()) / 2.0;
-                    Circle::new(center, radius).into_path(EPSILON)
+        self.paint_sides(canvas, rect, snap);
+    }
+
+    fn paint_uniform(
+        &self,
+        canvas: &mut Canvas,
+        rect: Rect,
+        shape: Option<BoxShape>,
+        border_radius: BorderRadius,
+        snap: PixelSnap,
+    ) {
+        let mut options = match self.top.to_stroke_options() {
+            Some(options) => options,
+            None => return,
+        };
+        options.width = snap.line_width(options.width);
+
+        // Strokes are centered on the path, so it is inset by half of the
+        // width to keep the border within its dimensions.
+        let inset = options.width / 2.0;
+
+        let path = match shape.unwrap_or(BoxShape::Rectangle) {
+            BoxShape::Circle => {
+                let center = rect.center();
+                let radius = rect.width().min(rect.height()) / 2.0 - inset;
+                Circle::new(center, radius.max(0.0)).into_path(EPSILON)
+            }
+            BoxShape::Rectangle if border_radius == BorderRadius::ZERO => {
+                druid_shell::piet::kurbo::Rect::from(rect.deflate(inset)).into_path(EPSILON)
+            }
+            BoxShape::Rectangle => {
+                let mut rrect = border_radius.to_rrect(&rect).deflate(inset);
+
+                for radius in [
+                    &mut rrect.tl_radius_x,
+                    &mut rrect.tl_radius_y,
+                    &mut rrect.tr_radius_x,
+                    &mut rrect.tr_radius_y,
+                    &mut rrect.bl_radius_x,
+                    &mut rrect.bl_radius_y,
+                    &mut rrect.br_radius_x,
+                    &mut rrect.br_radius_y,
+                ] {
+                    *radius = radius.max(0.0);
                 }
-                BoxShape::Rectangle => RoundedRect::try_from(border_radius.to_rrect(&rect))
-                    .unwrap()
-                    .into_path(EPSILON),
+
+                RoundedRect::try_from(rrect).unwrap().into_path(EPSILON)
             }
-        } else {
-            druid_shell::piet::kurbo::Rect::from(rect).into_path(EPSILON)
         };
 
-        let brush = canvas.solid_brush(self.top.color.clone());
-        if let Some(stroke_style) = self.top.to_stroke_style() {
-            canvas.stroke_styled(path, &brush, self.top.width, &stroke_style);
-        } else {
-            canvas.stroke(path, &brush, self.top.width);
+        canvas.stroke_with(path, &self.top.color, &options);
+    }
+
+    fn paint_sides(&self, canvas: &mut Canvas, rect: Rect, snap: PixelSnap) {
+        let width = |side: &BorderSide| match side.to_stroke_options() {
+            Some(options) => snap.line_width(options.width),
+            None => 0.0,
+        };
+
+        let (top, right, bottom, left) = (
+            width(&self.top),
+            width(&self.right),
+            width(&self.bottom),
+            width(&self.left),
+        );
+
+        let outer = rect;
+        let inner = Rect::from_ltrb(
+            rect.left + left,
+            rect.top + top,
+            rect.right - right,
+            rect.bottom - bottom,
+        );
+
+        // Solid sides are filled as trapezoids between the outer and the inner
+        // corners, meeting each other diagonally. Other sides are stroked along
+        // their center lines.
+        let sides = [
+            (
+                &self.top,
+                top,
+                [
+                    outer.top_left(),
+                    outer.top_right(),
+                    inner.top_right(),
+                    inner.top_left(),
+                ],
+                (
+                    outer.top_left(),
+                    outer.top_right(),
+                    Offset::new(0.0, top / 2.0),
+                ),
+            ),
+            (
+                &self.right,
+                right,
+                [
+                    outer.top_right(),
+                    outer.bottom_right(),
+                    inner.bottom_right(),
+                    inner.top_right(),
+                ],
+                (
+                    outer.top_right(),
+                    outer.bottom_right(),
+                    Offset::new(-right / 2.0, 0.0),
+                ),
+            ),
+            (
+                &self.bottom,
+                bottom,
+                [
+                    outer.bottom_right(),
+                    outer.bottom_left(),
+                    inner.bottom_left(),
+                    inner.bottom_right(),
+                ],
+                (
+                    outer.bottom_left(),
+                    outer.bottom_right(),
+                    Offset::new(0.0, -bottom / 2.0),
+                ),
+            ),
+            (
+                &self.left,
+                left,
+                [
+                    outer.bottom_left(),
+                    outer.top_left(),
+                    inner.top_left(),
+                    inner.bottom_left(),
+                ],
+                (
+                    outer.top_left(),
+                    outer.bottom_left(),
+                    Offset::new(left / 2.0, 0.0),
+                ),
+            ),
+        ];
+
+        for (side, width, corners, (start, end, inset)) in sides {
+            if width == 0.0 {
+                continue;
+            }
+
+            if side.style == BorderStyle::Solid {
+                let mut path = BezPath::new();
+                path.move_to(corners[0]);
+                for corner in &corners[1..] {
+                    path.line_to(*corner);
+                }
+                path.close_path();
+
+                let brush = canvas.solid_brush(side.color.clone());
+                canvas.fill(path, &brush);
+            } else if let Some(mut options) = side.to_stroke_options() {
+                options.width = width;
+
+                let line = Line::new(start + inset, end + inset);
+                canvas.stroke_with(line, &side.color, &options);
+            }
         }
     }
 }
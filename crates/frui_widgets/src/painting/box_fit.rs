@@ -0,0 +1,93 @@
+use frui::prelude::*;
+
+/// How a box of some size (e.g. an image) is inscribed into another box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxFit {
+    /// Stretched to fill the target box, distorting the aspect ratio.
+    Fill,
+    /// As large as possible while still fitting entirely within the target
+    /// box.
+    Contain,
+    /// As small as possible while still covering the entire target box,
+    /// cropping what doesn't fit.
+    Cover,
+    /// Not scaled, cropping what doesn't fit.
+    None,
+}
+
+impl Default for BoxFit {
+    fn default() -> Self {
+        BoxFit::Fill
+    }
+}
+
+/// Part of the source box which is visible and the size it's painted with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FittedSizes {
+    pub source: Size,
+    pub destination: Size,
+}
+
+impl BoxFit {
+    /// Sizes of the part of a box of `input` size which is visible when
+    /// fitted into a box of `output` size, and of the area it's painted to.
+    /// Both are to be aligned within their boxes.
+    pub fn apply(self, input: Size, output: Size) -> FittedSizes {
+        if input.width <= 0. || input.height <= 0. || output.width <= 0. || output.height <= 0. {
+            return FittedSizes {
+                source: Size::ZERO,
+                destination: Size::ZERO,
+            };
+        }
+
+        let input_ratio = input.width / input.height;
+        let output_ratio = output.width / output.height;
+
+        let (source, destination) = match self {
+            BoxFit::Fill => (input, output),
+            BoxFit::Contain => match output_ratio > input_ratio {
+                true => (input, Size::new(output.height * input_ratio, output.height)),
+                false => (input, Size::new(output.width, output.width / input_ratio)),
+            },
+            BoxFit::Cover => match output_ratio > input_ratio {
+                true => (Size::new(input.width, input.width / output_ratio), output),
+                false => (Size::new(input.height * output_ratio, input.height), output),
+            },
+            BoxFit::None => {
+                let size = Size::new(
+                    input.width.min(output.width),
+                    input.height.min(output.height),
+                );
+
+                (size, size)
+            }
+        };
+
+        FittedSizes {
+            source,
+            destination,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fits_wide_image_into_square() {
+        let (input, output) = (Size::new(200., 100.), Size::new(50., 50.));
+
+        let contain = BoxFit::Contain.apply(input, output);
+        assert_eq!(contain.source, input);
+        assert_eq!(contain.destination, Size::new(50., 25.));
+
+        let cover = BoxFit::Cover.apply(input, output);
+        assert_eq!(cover.source, Size::new(100., 100.));
+        assert_eq!(cover.destination, output);
+
+        let none = BoxFit::None.apply(input, output);
+        assert_eq!(none.source, output);
+        assert_eq!(none.destination, output);
+    }
+}
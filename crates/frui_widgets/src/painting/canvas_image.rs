@@ -0,0 +1,46 @@
+use std::cell::RefCell;
+
+use druid_shell::piet::{ImageBuf, RenderContext};
+use frui::render::Canvas;
+
+/// Image created by the canvas, e.g. a texture uploaded to the GPU.
+pub type CanvasImage = <Canvas<'static> as RenderContext>::Image;
+
+/// Keeps the [`CanvasImage`] created from an [`ImageBuf`] between paints,
+/// so that its pixels aren't uploaded again every frame. The image is
+/// created anew once it's asked for a different buffer.
+#[derive(Default)]
+pub struct CanvasImageCache {
+    cached: RefCell<Option<(ImageBuf, CanvasImage)>>,
+}
+
+impl CanvasImageCache {
+    /// Calls `f` with the image created from `buf`.
+    pub fn with<R>(
+        &self,
+        canvas: &mut Canvas,
+        buf: &ImageBuf,
+        f: impl FnOnce(&mut Canvas, &CanvasImage) -> R,
+    ) -> R {
+        let mut cached = self.cached.borrow_mut();
+
+        if !matches!(&*cached, Some((cached, _)) if same_pixels(cached, buf)) {
+            *cached = Some((buf.clone(), buf.to_image(canvas)));
+        }
+
+        f(canvas, &cached.as_ref().unwrap().1)
+    }
+
+    /// Drops the created image, e.g. once it's no longer painted.
+    pub fn clear(&self) {
+        self.cached.borrow_mut().take();
+    }
+}
+
+/// Whether both buffers share their pixels. The cached buffer keeps its
+/// pixels alive, so they can't be reused by a different buffer.
+fn same_pixels(a: &ImageBuf, b: &ImageBuf) -> bool {
+    a.raw_pixels().as_ptr() == b.raw_pixels().as_ptr()
+        && a.width() == b.width()
+        && a.height() == b.height()
+}
@@ -8,8 +8,8 @@ use frui::{
 };
 
 use crate::{
-    border_radius::BorderRadius, box_border::BoxShape, BoxBorder, BoxShadow, Directional,
-    EdgeInsets, ShapeBorder, TextDirection, EPSILON,
+    border_radius::BorderRadius, box_border::BoxShape, BoxBorder, BoxShadow, DecorationImage,
+    Directional, EdgeInsets, Gradient, ShapeBorder, TextDirection, EPSILON,
 };
 
 pub trait BoxPainter {
@@ -37,12 +37,11 @@ where
 {
     pub color: Option<Color>,
     pub box_shadow: Vec<BoxShadow>,
-    // TODO: image background
-    // pub image: Option<Image>,
+    pub image: Option<DecorationImage>,
     pub border: Option<B>,
     pub border_radius: Option<BR>,
-    // FIXME: implement gradient or merge it with color
-    // pub gradient: Option<G>,
+    /// Painted above `color`.
+    pub gradient: Option<Gradient>,
     pub shape: BoxShape,
     pub text_direction: TextDirection,
 }
@@ -53,7 +52,9 @@ impl BoxDecoration<BoxBorder, BorderRadius> {
     pub fn builder() -> DefaultBoxDecoration {
         Self {
             color: None,
+            gradient: None,
             box_shadow: Vec::new(),
+            image: None,
             border: None,
             border_radius: None,
             shape: BoxShape::Rectangle,
@@ -72,19 +73,31 @@ where
         self
     }
 
+    pub fn gradient(mut self, gradient: impl Into<Gradient>) -> Self {
+        self.gradient = Some(gradient.into());
+        self
+    }
+
     pub fn box_shadow(mut self, box_shadow: Vec<BoxShadow>) -> Self {
         self.box_shadow.clear();
         self.box_shadow.extend(box_shadow);
         self
     }
 
+    pub fn image(mut self, image: DecorationImage) -> Self {
+        self.image = Some(image);
+        self
+    }
+
     pub fn border<BORDER>(self, border: BORDER) -> BoxDecoration<BORDER, BR>
     where
         BORDER: Directional<Output = BoxBorder>,
     {
         BoxDecoration::<BORDER, BR> {
             color: self.color,
+            gradient: self.gradient,
             box_shadow: self.box_shadow,
+            image: self.image,
             border: Some(border),
             border_radius: self.border_radius,
             shape: self.shape,
@@ -98,7 +111,9 @@ where
     {
         BoxDecoration::<B, RADIUS> {
             color: self.color,
+            gradient: self.gradient,
             box_shadow: self.box_shadow,
+            image: self.image,
             border: self.border,
             border_radius: Some(border_radius),
             shape: self.shape,
@@ -162,11 +177,25 @@ where
         if let Some(color) = &self.color {
             canvas.fill(path.clone(), color);
         }
-        // or draw gradient
-        // if let Some(gradient) = &self.gradient {
-        //     canvas.fill(rect, gradient);
-        // }
-        // FIXME: draw background image
+        // draw gradient
+        if let Some(gradient) = &self.gradient {
+            match canvas.gradient(gradient.to_fixed(rect)) {
+                Ok(brush) => canvas.fill(path.clone(), &brush),
+                Err(e) => log::error!("creating gradient failed: {:?}", e),
+            }
+        }
+        // draw background image
+        if let Some(image) = &self.image {
+            let r = canvas.with_save(|c| {
+                c.clip(path.clone());
+                image.paint(c, rect);
+                Ok(())
+            });
+
+            if let Err(e) = r {
+                log::error!("painting decoration image failed: {}", e);
+            }
+        }
 
         // draw border
         if let Some(border) = &self.border {
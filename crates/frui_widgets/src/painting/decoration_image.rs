@@ -0,0 +1,195 @@
+use std::rc::Rc;
+
+use druid_shell::piet::{ImageBuf, InterpolationMode, RenderContext};
+use frui::{
+    prelude::*,
+    render::{Canvas, DruidRect, Rect},
+};
+
+use crate::CanvasImageCache;
+
+/// Image painted by a [`BoxDecoration`](crate::BoxDecoration) above its
+/// background color, stretched to fill the decorated box.
+///
+/// With a `center_slice` the image is scaled as a nine-slice: its corners
+/// keep their size, its edges are stretched along one axis and only the
+/// center slice is stretched along both. This way bitmap frames or speech
+/// bubbles can be scaled without distorting their corners.
+///
+/// ```ignore
+/// BoxDecoration::builder().image(
+///     DecorationImage::new(bubble).center_slice(Rect::from_ltwh(12., 12., 8., 8.)),
+/// )
+/// ```
+#[derive(Clone)]
+pub struct DecorationImage {
+    pub image: ImageBuf,
+    /// Part of the image (in its pixels) stretched in both directions.
+    pub center_slice: Option<Rect>,
+    /// Number of image pixels per display point, e.g. 2 for images made for
+    /// high density displays. Only affects the size of nine-slice corners.
+    pub scale: f64,
+    /// Shared by clones, which are painted from the same image.
+    cache: Rc<CanvasImageCache>,
+}
+
+impl DecorationImage {
+    pub fn new(image: ImageBuf) -> Self {
+        DecorationImage {
+            image,
+            center_slice: None,
+            scale: 1.,
+            cache: Default::default(),
+        }
+    }
+
+    pub fn center_slice(mut self, center_slice: Rect) -> Self {
+        self.center_slice = Some(center_slice);
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn paint(&self, canvas: &mut Canvas, rect: Rect) {
+        if self.image.width() == 0 || self.image.height() == 0 || rect.is_empty() {
+            return;
+        }
+
+        let image_size = Size::new(self.image.width() as f64, self.image.height() as f64);
+
+        let slices = match self.center_slice {
+            Some(center) => nine_slices(image_size, center, rect, self.scale),
+            None => vec![(
+                Rect::from_ltwh(0., 0., image_size.width, image_size.height),
+                rect,
+            )],
+        };
+
+        self.cache.with(canvas, &self.image, |canvas, image| {
+            for (src, dst) in slices {
+                canvas.draw_image_area(
+                    image,
+                    DruidRect::from(src),
+                    DruidRect::from(dst),
+                    InterpolationMode::Bilinear,
+                );
+            }
+        });
+    }
+}
+
+/// Pairs of source (in the image) and destination rects of a nine-slice
+/// scaled image. Slices which would be empty are left out.
+///
+/// If `dst` is too small to fit the corners, they are shrunk proportionally.
+fn nine_slices(image_size: Size, center: Rect, dst: Rect, scale: f64) -> Vec<(Rect, Rect)> {
+    // Center slice outside of the image (or inverted) is clamped to an empty
+    // slice at its edge.
+    let (left, top) = (
+        center.left.max(0.).min(image_size.width),
+        center.top.max(0.).min(image_size.height),
+    );
+    let center = Rect::from_ltrb(
+        left,
+        top,
+        center.right.max(left).min(image_size.width),
+        center.bottom.max(top).min(image_size.height),
+    );
+
+    let xs = edges(
+        [0., center.left, center.right, image_size.width],
+        dst.left,
+        dst.right,
+        scale,
+    );
+    let ys = edges(
+        [0., center.top, center.bottom, image_size.height],
+        dst.top,
+        dst.bottom,
+        scale,
+    );
+
+    let mut slices = Vec::with_capacity(9);
+
+    for row in 0..3 {
+        for column in 0..3 {
+            let (x0, x1) = (xs[column], xs[column + 1]);
+            let (y0, y1) = (ys[row], ys[row + 1]);
+
+            let src = Rect::from_ltrb(x0.0, y0.0, x1.0, y1.0);
+            let dst = Rect::from_ltrb(x0.1, y0.1, x1.1, y1.1);
+
+            if src.width() > 0. && src.height() > 0. && dst.width() > 0. && dst.height() > 0. {
+                slices.push((src, dst));
+            }
+        }
+    }
+
+    slices
+}
+
+/// Source and destination positions of the edges of slices along one axis.
+fn edges(src: [f64; 4], start: f64, end: f64, scale: f64) -> [(f64, f64); 4] {
+    let (mut before, mut after) = (src[1] / scale, (src[3] - src[2]) / scale);
+
+    let available = end - start;
+    if before + after > available {
+        let shrink = available / (before + after);
+        before *= shrink;
+        after *= shrink;
+    }
+
+    [
+        (src[0], start),
+        (src[1], start + before),
+        (src[2], end - after),
+        (src[3], end),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nine_slice_keeps_corners() {
+        let image = Size::new(30., 30.);
+        let center = Rect::from_ltrb(10., 10., 20., 20.);
+
+        let slices = nine_slices(image, center, Rect::from_ltwh(0., 0., 100., 50.), 1.);
+        assert_eq!(slices.len(), 9);
+
+        // Top left corner, top edge and center.
+        assert_eq!(slices[0].1, Rect::from_ltrb(0., 0., 10., 10.));
+        assert_eq!(slices[1].1, Rect::from_ltrb(10., 0., 90., 10.));
+        assert_eq!(slices[4], (center, Rect::from_ltrb(10., 10., 90., 40.)));
+
+        // Corners are shrunk once they don't fit, and the center disappears.
+        let slices = nine_slices(image, center, Rect::from_ltwh(0., 0., 10., 40.), 1.);
+        assert_eq!(slices.len(), 6);
+        assert_eq!(slices[0].1, Rect::from_ltrb(0., 0., 5., 10.));
+    }
+
+    #[test]
+    fn nine_slice_clamps_center_to_image() {
+        let image = Size::new(30., 30.);
+        let dst = Rect::from_ltwh(0., 0., 60., 60.);
+
+        // Center past the image, and an inverted one.
+        for center in [
+            Rect::from_ltrb(40., 40., 50., 50.),
+            Rect::from_ltrb(20., 20., 10., 10.),
+        ] {
+            let slices = nine_slices(image, center, dst, 1.);
+
+            let area: f64 = slices
+                .iter()
+                .map(|(src, _)| src.width() * src.height())
+                .sum();
+            assert_eq!(area, 900.);
+        }
+    }
+}
@@ -131,6 +131,15 @@ impl EdgeInsets {
         )
     }
 
+    pub fn inflate_rect(&self, rect: Rect) -> Rect {
+        Rect::from_ltrb(
+            rect.left - self.left,
+            rect.top - self.top,
+            rect.right + self.right,
+            rect.bottom + self.bottom,
+        )
+    }
+
     pub fn deflate_rect(&self, rect: Rect) -> Rect {
         Rect::from_ltrb(
             rect.left + self.left,
@@ -246,7 +255,9 @@ impl Display for EdgeInsets {
     }
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Like [`EdgeInsets`], but with `start` and `end` offsets, which are
+/// resolved to left and right ones according to the [`TextDirection`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Builder)]
 pub struct EdgeInsetsDirectional {
     pub start: f64,
     pub top: f64,
@@ -255,6 +266,13 @@ pub struct EdgeInsetsDirectional {
 }
 
 impl EdgeInsetsDirectional {
+    pub const ZERO: EdgeInsetsDirectional = EdgeInsetsDirectional {
+        start: 0.0,
+        top: 0.0,
+        end: 0.0,
+        bottom: 0.0,
+    };
+
     pub fn from_steb(start: f64, top: f64, end: f64, bottom: f64) -> EdgeInsetsDirectional {
         EdgeInsetsDirectional {
             start,
@@ -263,6 +281,119 @@ impl EdgeInsetsDirectional {
             bottom,
         }
     }
+
+    pub fn all(value: f64) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: value,
+            top: value,
+            end: value,
+            bottom: value,
+        }
+    }
+
+    pub fn symmetric(vertical: f64, horizontal: f64) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: horizontal,
+            top: vertical,
+            end: horizontal,
+            bottom: vertical,
+        }
+    }
+
+    pub fn is_non_negative(&self) -> bool {
+        self.start >= 0.0 && self.top >= 0.0 && self.end >= 0.0 && self.bottom >= 0.0
+    }
+
+    /// The total offset in the horizontal direction.
+    pub fn horizontal(&self) -> f64 {
+        self.start + self.end
+    }
+
+    /// The total offset in the vertical direction.
+    pub fn vertical(&self) -> f64 {
+        self.top + self.bottom
+    }
+
+    /// The size that this [EdgeInsetsDirectional] would occupy with an empty
+    /// interior.
+    pub fn collapsed_size(&self) -> Size {
+        Size::new(self.horizontal(), self.vertical())
+    }
+
+    pub fn flipped(&self) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: self.end,
+            top: self.bottom,
+            end: self.start,
+            bottom: self.top,
+        }
+    }
+
+    pub fn lerp(&self, other: EdgeInsetsDirectional, t: f64) -> EdgeInsetsDirectional {
+        *self + (other - *self) * t
+    }
+}
+
+impl Neg for EdgeInsetsDirectional {
+    type Output = EdgeInsetsDirectional;
+
+    fn neg(self) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: -self.start,
+            top: -self.top,
+            end: -self.end,
+            bottom: -self.bottom,
+        }
+    }
+}
+
+impl Sub for EdgeInsetsDirectional {
+    type Output = EdgeInsetsDirectional;
+
+    fn sub(self, rhs: EdgeInsetsDirectional) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: self.start - rhs.start,
+            top: self.top - rhs.top,
+            end: self.end - rhs.end,
+            bottom: self.bottom - rhs.bottom,
+        }
+    }
+}
+
+impl Add for EdgeInsetsDirectional {
+    type Output = EdgeInsetsDirectional;
+
+    fn add(self, rhs: EdgeInsetsDirectional) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: self.start + rhs.start,
+            top: self.top + rhs.top,
+            end: self.end + rhs.end,
+            bottom: self.bottom + rhs.bottom,
+        }
+    }
+}
+
+impl Mul<f64> for EdgeInsetsDirectional {
+    type Output = EdgeInsetsDirectional;
+
+    fn mul(self, rhs: f64) -> EdgeInsetsDirectional {
+        EdgeInsetsDirectional {
+            start: self.start * rhs,
+            top: self.top * rhs,
+            end: self.end * rhs,
+            bottom: self.bottom * rhs,
+        }
+    }
+}
+
+impl Display for EdgeInsetsDirectional {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EdgeInsetsDirectional({}, {}, {}, {})",
+            self.start, self.top, self.end, self.bottom
+        )
+    }
 }
 
 impl Directional for EdgeInsetsDirectional {
@@ -285,3 +416,31 @@ impl Directional for EdgeInsetsDirectional {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolves_directional_insets() {
+        let insets = EdgeInsetsDirectional::from_steb(1., 2., 3., 4.);
+
+        assert_eq!(
+            insets.resolve(&TextDirection::Ltr),
+            EdgeInsets::from_ltrb(1., 2., 3., 4.)
+        );
+        assert_eq!(
+            insets.resolve(&TextDirection::Rtl),
+            EdgeInsets::from_ltrb(3., 2., 1., 4.)
+        );
+    }
+
+    #[test]
+    fn inflates_and_deflates_rects() {
+        let insets = EdgeInsets::from_ltrb(1., 2., 3., 4.);
+        let rect = Rect::from_ltwh(10., 10., 20., 20.);
+
+        assert_eq!(insets.inflate_rect(rect), Rect::from_ltrb(9., 8., 33., 34.));
+        assert_eq!(insets.deflate_rect(insets.inflate_rect(rect)), rect);
+    }
+}
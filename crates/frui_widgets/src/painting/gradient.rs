@@ -0,0 +1,213 @@
+use druid_shell::piet::{FixedGradient, FixedLinearGradient, FixedRadialGradient, GradientStop};
+use frui::{
+    prelude::*,
+    render::{Point, Rect, Vec2},
+};
+
+use crate::Alignment;
+
+/// Gradient filling a box, positioned relative to the box, so that it
+/// stretches along with it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gradient {
+    Linear(LinearGradient),
+    Radial(RadialGradient),
+}
+
+impl Gradient {
+    /// Gradient in the coordinates of `rect`.
+    pub fn to_fixed(&self, rect: Rect) -> FixedGradient {
+        let point = |alignment: Alignment| {
+            let offset = alignment.within_rect(rect);
+            Point::new(offset.x, offset.y)
+        };
+
+        match self {
+            Gradient::Linear(gradient) => FixedGradient::Linear(FixedLinearGradient {
+                start: point(gradient.begin),
+                end: point(gradient.end),
+                stops: gradient_stops(&gradient.colors, gradient.stops.as_deref()),
+            }),
+            Gradient::Radial(gradient) => FixedGradient::Radial(FixedRadialGradient {
+                center: point(gradient.center),
+                origin_offset: Vec2::ZERO,
+                radius: gradient.radius * rect.shortest_side(),
+                stops: gradient_stops(&gradient.colors, gradient.stops.as_deref()),
+            }),
+        }
+    }
+}
+
+impl From<LinearGradient> for Gradient {
+    fn from(gradient: LinearGradient) -> Self {
+        Gradient::Linear(gradient)
+    }
+}
+
+impl From<RadialGradient> for Gradient {
+    fn from(gradient: RadialGradient) -> Self {
+        Gradient::Radial(gradient)
+    }
+}
+
+/// Transition between `colors` along the line from `begin` to `end`.
+///
+/// ```ignore
+/// LinearGradient::new(vec![Color::rgb8(255, 94, 98), Color::rgb8(255, 153, 102)])
+///     .begin(Alignment::TOP_LEFT)
+///     .end(Alignment::BOTTOM_RIGHT)
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearGradient {
+    pub begin: Alignment,
+    pub end: Alignment,
+    pub colors: Vec<Color>,
+    /// Positions of `colors` between `begin` (0) and `end` (1). If `None`,
+    /// colors are spread evenly.
+    pub stops: Option<Vec<f64>>,
+}
+
+impl LinearGradient {
+    pub fn new(colors: Vec<Color>) -> Self {
+        LinearGradient {
+            begin: Alignment::CENTER_LEFT,
+            end: Alignment::CENTER_RIGHT,
+            colors,
+            stops: None,
+        }
+    }
+
+    pub fn begin(mut self, begin: Alignment) -> Self {
+        self.begin = begin;
+        self
+    }
+
+    pub fn end(mut self, end: Alignment) -> Self {
+        self.end = end;
+        self
+    }
+
+    pub fn stops(mut self, stops: Vec<f64>) -> Self {
+        self.stops = Some(stops);
+        self
+    }
+}
+
+/// Transition between `colors` in circles around the `center`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialGradient {
+    pub center: Alignment,
+    /// Radius of the outermost circle, as a fraction of the shortest side of
+    /// the box.
+    pub radius: f64,
+    pub colors: Vec<Color>,
+    /// Positions of `colors` between the `center` (0) and the outermost
+    /// circle (1). If `None`, colors are spread evenly.
+    pub stops: Option<Vec<f64>>,
+}
+
+impl RadialGradient {
+    pub fn new(colors: Vec<Color>) -> Self {
+        RadialGradient {
+            center: Alignment::CENTER,
+            radius: 0.5,
+            colors,
+            stops: None,
+        }
+    }
+
+    pub fn center(mut self, center: Alignment) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn stops(mut self, stops: Vec<f64>) -> Self {
+        self.stops = Some(stops);
+        self
+    }
+}
+
+/// Stops of a gradient between `colors` at the `stops` positions. Invalid
+/// gradients are logged and painted with their colors spread evenly (or with
+/// a single color, if there's only one).
+fn gradient_stops(colors: &[Color], stops: Option<&[f64]>) -> Vec<GradientStop> {
+    let stop = |pos: f64, color: &Color| GradientStop {
+        pos: pos.clamp(0., 1.) as f32,
+        color: color.clone(),
+    };
+
+    if colors.len() < 2 {
+        log::warn!("gradient needs at least two colors, got {}", colors.len());
+
+        let color = colors.first().unwrap_or(&Color::TRANSPARENT);
+        return vec![stop(0., color), stop(1., color)];
+    }
+
+    match stops {
+        Some(stops) if stops.len() != colors.len() => log::warn!(
+            "gradient needs as many stops as colors, got {} stops for {} colors",
+            stops.len(),
+            colors.len()
+        ),
+        // Also rejects NaN stops.
+        Some(stops) if !stops.windows(2).all(|w| w[0] <= w[1]) => {
+            log::warn!("gradient stops must be in ascending order, got {:?}", stops)
+        }
+        Some(stops) => return colors.iter().zip(stops).map(|(c, s)| stop(*s, c)).collect(),
+        None => {}
+    }
+
+    let last = (colors.len() - 1) as f64;
+
+    colors
+        .iter()
+        .enumerate()
+        .map(|(n, color)| stop(n as f64 / last, color))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spreads_stops_evenly() {
+        let colors = [Color::BLACK, Color::WHITE, Color::BLACK];
+
+        let positions = |stops: Option<&[f64]>| {
+            gradient_stops(&colors, stops)
+                .into_iter()
+                .map(|stop| stop.pos)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(positions(None), [0., 0.5, 1.]);
+        assert_eq!(positions(Some(&[0., 0.2, 1.5][..])), [0., 0.2, 1.]);
+    }
+
+    #[test]
+    fn falls_back_to_even_stops() {
+        let colors = [Color::BLACK, Color::WHITE, Color::BLACK];
+
+        let positions = |stops: &[f64]| {
+            gradient_stops(&colors, Some(stops))
+                .into_iter()
+                .map(|stop| stop.pos)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(positions(&[0., 1.]), [0., 0.5, 1.]);
+        assert_eq!(positions(&[0., 0.8, 0.2]), [0., 0.5, 1.]);
+        assert_eq!(positions(&[0., f64::NAN, 1.]), [0., 0.5, 1.]);
+
+        let single = gradient_stops(&[Color::WHITE], None);
+        assert_eq!(single.len(), 2);
+        assert_eq!(single[1].color, Color::WHITE);
+        assert_eq!(gradient_stops(&[], None).len(), 2);
+    }
+}
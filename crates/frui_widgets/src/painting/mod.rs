@@ -1,15 +1,29 @@
 pub use border_radius::*;
 pub use borders::*;
 pub use box_border::*;
+pub use box_fit::*;
+pub use canvas_image::*;
 pub use decoration::*;
+pub use decoration_image::*;
 pub use edge_insets::*;
+pub use gradient::*;
+pub use picture::*;
+pub use pixel_snap::*;
 pub use shadow::*;
+pub use stroke::*;
 
 pub mod border_radius;
 pub mod borders;
 pub mod box_border;
+pub mod box_fit;
+pub mod canvas_image;
 pub mod decoration;
+pub mod decoration_image;
 pub mod edge_insets;
+pub mod gradient;
+pub mod picture;
+pub mod pixel_snap;
 pub mod shadow;
+pub mod stroke;
 
 pub const EPSILON: f64 = 1E-9;
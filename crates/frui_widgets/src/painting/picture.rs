@@ -0,0 +1,443 @@
+//! Recording of paint commands, which can be replayed cheaply on subsequent
+//! frames instead of computing the same paths again.
+
+use std::rc::Rc;
+
+use druid_shell::{
+    kurbo::{Affine, BezPath, Shape},
+    piet::{RenderContext, StrokeStyle},
+};
+use frui::{prelude::*, render::*};
+
+use crate::StrokeOptions;
+
+#[cfg(not(feature = "miri"))]
+use druid_shell::piet::{ImageBuf, InterpolationMode, PietTextLayout};
+
+#[cfg(not(feature = "miri"))]
+use crate::CanvasImageCache;
+
+/// Accuracy of converting shapes to paths.
+const TOLERANCE: f64 = 0.1;
+
+#[derive(Clone)]
+enum Command {
+    Save,
+    Restore,
+    Transform(Affine),
+    Clip(BezPath),
+    Fill(BezPath, Color),
+    FillEvenOdd(BezPath, Color),
+    Stroke(BezPath, Color, f64, StrokeStyle),
+    #[cfg(not(feature = "miri"))]
+    DrawText(PietTextLayout, Point),
+    /// The cache keeps the image uploaded between replays.
+    #[cfg(not(feature = "miri"))]
+    DrawImage(ImageBuf, DruidRect, Rc<CanvasImageCache>),
+}
+
+/// Records paint commands into a [`Picture`]. Mirrors a subset of the
+/// [`RenderContext`] methods, with solid colors as brushes.
+#[derive(Default)]
+pub struct PictureRecorder {
+    commands: Vec<Command>,
+    /// Number of `save` calls without matching `restore`.
+    depth: usize,
+}
+
+impl PictureRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn save(&mut self) {
+        self.depth += 1;
+        self.commands.push(Command::Save);
+    }
+
+    /// Ignored if there is no matching [`save`](Self::save).
+    pub fn restore(&mut self) {
+        if self.depth > 0 {
+            self.depth -= 1;
+            self.commands.push(Command::Restore);
+        }
+    }
+
+    pub fn transform(&mut self, transform: Affine) {
+        self.commands.push(Command::Transform(transform));
+    }
+
+    pub fn clip(&mut self, shape: impl Shape) {
+        self.commands
+            .push(Command::Clip(shape.into_path(TOLERANCE)));
+    }
+
+    pub fn fill(&mut self, shape: impl Shape, color: &Color) {
+        self.commands
+            .push(Command::Fill(shape.into_path(TOLERANCE), color.clone()));
+    }
+
+    pub fn fill_even_odd(&mut self, shape: impl Shape, color: &Color) {
+        self.commands.push(Command::FillEvenOdd(
+            shape.into_path(TOLERANCE),
+            color.clone(),
+        ));
+    }
+
+    pub fn stroke(&mut self, shape: impl Shape, color: &Color, width: f64) {
+        self.stroke_styled(shape, color, width, &StrokeStyle::new());
+    }
+
+    pub fn stroke_styled(
+        &mut self,
+        shape: impl Shape,
+        color: &Color,
+        width: f64,
+        style: &StrokeStyle,
+    ) {
+        self.commands.push(Command::Stroke(
+            shape.into_path(TOLERANCE),
+            color.clone(),
+            width,
+            style.clone(),
+        ));
+    }
+
+    pub fn stroke_with(&mut self, shape: impl Shape, color: &Color, options: &StrokeOptions) {
+        self.stroke_styled(shape, color, options.width, &options.to_stroke_style());
+    }
+
+    #[cfg(not(feature = "miri"))]
+    pub fn draw_text(&mut self, layout: &PietTextLayout, pos: impl Into<Point>) {
+        self.commands
+            .push(Command::DrawText(layout.clone(), pos.into()));
+    }
+
+    #[cfg(not(feature = "miri"))]
+    pub fn draw_paragraph(&mut self, paragraph: &crate::Paragraph, pos: impl Into<Point>) {
+        let origin = paragraph.layout_origin(pos);
+        self.draw_text(paragraph.layout(), origin);
+    }
+
+    /// Draws `image` stretched over `rect`.
+    #[cfg(not(feature = "miri"))]
+    pub fn draw_image(&mut self, image: &ImageBuf, rect: Rect) {
+        self.commands.push(Command::DrawImage(
+            image.clone(),
+            rect.into(),
+            Rc::new(CanvasImageCache::default()),
+        ));
+    }
+
+    pub fn finish(mut self) -> Picture {
+        for _ in 0..self.depth {
+            self.commands.push(Command::Restore);
+        }
+
+        Picture {
+            commands: Rc::new(self.commands),
+        }
+    }
+}
+
+/// Recorded paint commands. Cloning a picture is cheap.
+#[derive(Clone, Default)]
+pub struct Picture {
+    commands: Rc<Vec<Command>>,
+}
+
+impl Picture {
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Replays recorded commands, translated by `offset`.
+    pub fn paint(&self, canvas: &mut Canvas, offset: &Offset) {
+        canvas.save().unwrap();
+        canvas.transform(Affine::translate((offset.x, offset.y)));
+
+        for command in self.commands.iter() {
+            match command {
+                Command::Save => canvas.save().unwrap(),
+                Command::Restore => canvas.restore().unwrap(),
+                Command::Transform(transform) => canvas.transform(*transform),
+                Command::Clip(path) => canvas.clip(path),
+                Command::Fill(path, color) => canvas.fill(path, color),
+                Command::FillEvenOdd(path, color) => canvas.fill_even_odd(path, color),
+                Command::Stroke(path, color, width, style) => {
+                    canvas.stroke_styled(path, color, *width, style)
+                }
+                #[cfg(not(feature = "miri"))]
+                Command::DrawText(layout, pos) => canvas.draw_text(layout, *pos),
+                #[cfg(not(feature = "miri"))]
+                Command::DrawImage(image, rect, cache) => {
+                    cache.with(canvas, image, |canvas, image| {
+                        canvas.draw_image(image, *rect, InterpolationMode::Bilinear)
+                    })
+                }
+            }
+        }
+
+        canvas.restore().unwrap();
+    }
+}
+
+/// Paints the picture recorded by `painter`, recording it again only when
+/// `version` or the size of this widget changes.
+///
+/// ```ignore
+/// CachedPicture::builder()
+///     .version(chart_data.revision())
+///     .painter(|recorder, size| chart_data.record(recorder, size))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct CachedPicture<F: Fn(&mut PictureRecorder, Size)> {
+    pub painter: F,
+    /// Bump to invalidate the recorded picture.
+    pub version: u64,
+    /// Preferred size, constrained by the incoming constraints.
+    pub size: Size,
+}
+
+impl CachedPicture<fn(&mut PictureRecorder, Size)> {
+    pub fn builder() -> Self {
+        CachedPicture {
+            painter: |_, _| {},
+            version: 0,
+            size: Size::new(f64::INFINITY, f64::INFINITY),
+        }
+    }
+}
+
+impl<F: Fn(&mut PictureRecorder, Size)> RenderState for CachedPicture<F> {
+    /// Version and size the picture was recorded for.
+    type State = Option<(u64, Size, Picture)>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl<F: Fn(&mut PictureRecorder, Size)> RenderWidget for CachedPicture<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(self.size)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let mut state = cx.render_state_mut();
+
+        let picture = match &*state {
+            Some((version, recorded_size, picture))
+                if *version == self.version && *recorded_size == size =>
+            {
+                picture.clone()
+            }
+            _ => {
+                let mut recorder = PictureRecorder::new();
+                (self.painter)(&mut recorder, size);
+
+                let picture = recorder.finish();
+                *state = Some((self.version, size, picture.clone()));
+                picture
+            }
+        };
+
+        drop(state);
+        picture.paint(canvas, offset);
+    }
+}
+
+/// Records painting of `child` into a [`Picture`] once and replays it on
+/// subsequent frames, until `version` or the size of `child` changes. Meant
+/// for static subtrees which are expensive to paint, e.g. a complex chart
+/// shown under an animated cursor.
+///
+/// Changes to the painting of `child` aren't shown until `version` is bumped.
+/// `child` is recorded into an image at the scale of the window, so anything
+/// it paints outside of its layout box is cut off. Under miri, `child` is
+/// painted every frame.
+///
+/// ```ignore
+/// PictureBoundary::builder()
+///     .version(chart_data.revision())
+///     .child(Chart::new(&chart_data))
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct PictureBoundary<W: Widget> {
+    pub child: W,
+    /// Bump to record `child` again.
+    pub version: u64,
+}
+
+impl PictureBoundary<()> {
+    pub fn builder() -> Self {
+        PictureBoundary {
+            child: (),
+            version: 0,
+        }
+    }
+}
+
+impl<W: Widget> RenderState for PictureBoundary<W> {
+    /// Version and size the picture was recorded for.
+    type State = Option<(u64, Size, Picture)>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl<W: Widget> RenderWidget for PictureBoundary<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    #[cfg(not(feature = "miri"))]
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+
+        let recorded = match &*cx.render_state() {
+            Some((version, recorded_size, picture))
+                if *version == self.version && *recorded_size == size =>
+            {
+                Some(picture.clone())
+            }
+            _ => None,
+        };
+
+        let picture = match recorded {
+            Some(picture) => picture,
+            None => {
+                let bounds = Rect::from_origin_size(offset, size);
+                let image = cx.paint_to_image(canvas, bounds.into(), |cx, canvas| {
+                    cx.child(0).paint(canvas, offset)
+                });
+
+                match image {
+                    Ok(image) => {
+                        // Pictures are replayed relative to the offset.
+                        let mut recorder = PictureRecorder::new();
+                        if image.width() > 0 {
+                            recorder.draw_image(&image, Rect::from_origin_size(Point::ZERO, size));
+                        }
+
+                        let picture = recorder.finish();
+                        *cx.render_state_mut() = Some((self.version, size, picture.clone()));
+                        picture
+                    }
+                    Err(e) => {
+                        log::error!("recording picture failed: {:?}", e);
+                        return cx.child(0).paint(canvas, offset);
+                    }
+                }
+            }
+        };
+
+        picture.paint(canvas, offset);
+    }
+
+    #[cfg(feature = "miri")]
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unmatched_saves_are_restored() {
+        let mut recorder = PictureRecorder::new();
+        recorder.restore();
+        recorder.save();
+        recorder.save();
+        recorder.restore();
+
+        let commands = recorder.finish().commands;
+        let commands = commands
+            .iter()
+            .map(|c| match c {
+                Command::Save => "save",
+                Command::Restore => "restore",
+                _ => "other",
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(commands, ["save", "save", "restore", "restore"]);
+    }
+
+    #[test]
+    fn pictures_share_commands() {
+        let mut recorder = PictureRecorder::new();
+        assert!(recorder.commands.is_empty());
+
+        recorder.fill(DruidRect::new(0., 0., 10., 10.), &Color::BLACK);
+        let picture = recorder.finish();
+
+        assert!(!picture.is_empty());
+        assert!(Rc::ptr_eq(&picture.commands, &picture.clone().commands));
+    }
+
+    #[cfg(not(feature = "miri"))]
+    mod boundary {
+        use std::cell::Cell;
+
+        use frui::app::embed::Engine;
+
+        use super::*;
+
+        #[derive(RenderWidget)]
+        struct PaintCounter {
+            paints: Rc<Cell<usize>>,
+        }
+
+        impl RenderWidget for PaintCounter {
+            fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+                vec![] as Vec<()>
+            }
+
+            fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+                constraints.biggest()
+            }
+
+            fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+                self.paints.set(self.paints.get() + 1);
+
+                let rect = Rect::from_origin_size(offset, cx.size());
+                canvas.fill(DruidRect::from(rect), &Color::rgb8(255, 0, 0));
+            }
+        }
+
+        #[test]
+        fn static_subtree_is_recorded_once_per_size() {
+            let paints = Rc::new(Cell::new(0));
+
+            let boundary = PictureBoundary::builder().child(PaintCounter {
+                paints: paints.clone(),
+            });
+            let mut engine = Engine::new(boundary, Size::new(20., 10.), 1.).unwrap();
+
+            engine.render().unwrap();
+            let frame = engine.render().unwrap();
+
+            // Replayed picture paints the recorded child.
+            assert_eq!(paints.get(), 1);
+            assert_eq!(&frame.raw_pixels()[..4], [255, 0, 0, 255]);
+
+            engine.resize(Size::new(30., 10.), 1.);
+            engine.render().unwrap();
+
+            assert_eq!(paints.get(), 2);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use frui::{
+    app::metrics::WindowMetrics,
+    render::{window_transform, Affine, Canvas, Point, Rect},
+};
+
+/// Rounds coordinates and line widths to whole device pixels, so that edges
+/// of thin lines aren't blurred by antialiasing.
+///
+/// Coordinates are snapped in device space, so that painting offset by a
+/// fraction of a pixel (e.g. within a scrolled list) is snapped too.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelSnap {
+    /// Number of device pixels per unit of the snapped coordinates. Nothing
+    /// is snapped if it's zero.
+    pub scale: f64,
+    /// Position of the origin of the snapped coordinates in device pixels.
+    pub origin: Point,
+}
+
+impl PixelSnap {
+    /// Doesn't change any coordinates.
+    pub const NONE: PixelSnap = PixelSnap {
+        scale: 0.,
+        origin: Point::ORIGIN,
+    };
+
+    /// Snaps coordinates whose origin is at the origin of the device.
+    pub fn new(scale: f64) -> Self {
+        PixelSnap {
+            scale,
+            origin: Point::ORIGIN,
+        }
+    }
+
+    /// Snaps current coordinates of `canvas` to the pixels of the window.
+    ///
+    /// Only coordinates which are translated and uniformly scaled relative to
+    /// the window can be snapped, others (e.g. rotated ones) aren't snapped.
+    pub fn for_canvas(canvas: &Canvas) -> Self {
+        let scale = WindowMetrics::current().scale;
+        let transform = Affine::scale(scale) * window_transform(canvas);
+
+        match transform.as_coeffs() {
+            [a, b, c, d, x, y] if b == 0. && c == 0. && a == d && a > 0. => PixelSnap {
+                scale: a,
+                origin: Point::new(x, y),
+            },
+            _ => Self::NONE,
+        }
+    }
+
+    fn snap_to(&self, value: f64, origin: f64) -> f64 {
+        match self.scale > 0. {
+            true => ((value * self.scale + origin).round() - origin) / self.scale,
+            false => value,
+        }
+    }
+
+    pub fn snap_x(&self, x: f64) -> f64 {
+        self.snap_to(x, self.origin.x)
+    }
+
+    pub fn snap_y(&self, y: f64) -> f64 {
+        self.snap_to(y, self.origin.y)
+    }
+
+    pub fn snap_rect(&self, rect: Rect) -> Rect {
+        Rect::from_ltrb(
+            self.snap_x(rect.left),
+            self.snap_y(rect.top),
+            self.snap_x(rect.right),
+            self.snap_y(rect.bottom),
+        )
+    }
+
+    /// Rounds `width` to whole device pixels, but to no less than one pixel.
+    /// A zero `width` is therefore a hairline.
+    pub fn line_width(&self, width: f64) -> f64 {
+        match self.scale > 0. {
+            true => (width * self.scale).round().max(1.) / self.scale,
+            false => width,
+        }
+    }
+
+    /// Position of the center of a vertical line `width` thick (as returned
+    /// by [`line_width`](Self::line_width)) which is centered around `x`,
+    /// such that both edges of the line fall on pixel boundaries.
+    pub fn line_center_x(&self, x: f64, width: f64) -> f64 {
+        self.snap_x(x - width / 2.) + width / 2.
+    }
+
+    /// Horizontal counterpart of [`line_center_x`](Self::line_center_x).
+    pub fn line_center_y(&self, y: f64, width: f64) -> f64 {
+        self.snap_y(y - width / 2.) + width / 2.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snaps_to_device_pixels() {
+        let snap = PixelSnap::new(2.);
+
+        assert_eq!(snap.snap_x(10.3), 10.5);
+        assert_eq!(snap.line_width(0.), 0.5);
+        assert_eq!(snap.line_width(1.2), 1.);
+
+        // A hairline centered at 8 covers the pixel starting at 8.
+        assert_eq!(snap.line_center_y(8., 0.5), 8.25);
+        assert_eq!(snap.line_center_y(8., 1.), 8.);
+    }
+
+    #[test]
+    fn snaps_in_device_space() {
+        // Origin of the coordinates is half a pixel right of and one pixel
+        // below the origin of the device.
+        let snap = PixelSnap {
+            scale: 2.,
+            origin: Point::new(0.5, 1.),
+        };
+
+        // 10.3 is 21.1 device pixels in, which is rounded to 21.
+        assert_eq!(snap.snap_x(10.3), 10.25);
+        // 10.3 is 21.6 device pixels down, which is rounded to 22.
+        assert_eq!(snap.snap_y(10.3), 10.5);
+
+        assert_eq!(PixelSnap::NONE.snap_x(10.3), 10.3);
+        assert_eq!(PixelSnap::NONE.line_width(1.2), 1.2);
+    }
+}
@@ -0,0 +1,204 @@
+use druid_shell::{
+    kurbo::Shape,
+    piet::{LineCap, LineJoin, RenderContext, StrokeStyle},
+};
+use frui::{prelude::*, render::Canvas};
+
+/// Shape of the ends of stroked lines (and of dashes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeCap {
+    /// Ends exactly at the end of the line.
+    #[default]
+    Butt,
+    /// Extends past the end of the line by a half circle.
+    Round,
+    /// Extends past the end of the line by half of the stroke width.
+    Square,
+}
+
+/// Shape of the corners where segments of stroked paths meet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrokeJoin {
+    /// Sharp corner. Corners longer than `limit` times the stroke width are
+    /// beveled instead.
+    Miter {
+        limit: f64,
+    },
+    Round,
+    Bevel,
+}
+
+impl StrokeJoin {
+    pub const DEFAULT_MITER_LIMIT: f64 = 10.;
+
+    pub const fn miter() -> Self {
+        StrokeJoin::Miter {
+            limit: Self::DEFAULT_MITER_LIMIT,
+        }
+    }
+}
+
+impl Default for StrokeJoin {
+    fn default() -> Self {
+        StrokeJoin::miter()
+    }
+}
+
+/// Lengths of alternating dashes and gaps, starting with a dash, repeated
+/// along the stroked line.
+///
+/// ```ignore
+/// // Dashes 6 points long separated by 3 point gaps.
+/// DashPattern::dashed(6., 3.)
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DashPattern {
+    pub dashes: Vec<f64>,
+    /// Distance into the pattern at which the line starts. Changing it over
+    /// time makes the dashes "march" along the line.
+    pub offset: f64,
+}
+
+impl DashPattern {
+    pub fn new(dashes: impl Into<Vec<f64>>) -> Self {
+        DashPattern {
+            dashes: dashes.into(),
+            offset: 0.,
+        }
+    }
+
+    pub fn dashed(dash: f64, gap: f64) -> Self {
+        Self::new([dash, gap])
+    }
+
+    /// Dots separated by `gap`, measured between their edges. Dots are
+    /// zero-length dashes, so they need a [`StrokeCap::Round`] (or
+    /// [`StrokeCap::Square`]) cap to be visible, and their diameter is
+    /// the stroke width.
+    pub fn dotted(width: f64, gap: f64) -> Self {
+        Self::new([0., width + gap])
+    }
+
+    pub fn offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Length of one repetition of the pattern.
+    pub fn period(&self) -> f64 {
+        // Odd patterns are repeated twice to alternate between dashes and
+        // gaps, like in PostScript.
+        let sum: f64 = self.dashes.iter().sum();
+
+        match self.dashes.len() % 2 {
+            0 => sum,
+            _ => sum * 2.,
+        }
+    }
+}
+
+/// How shapes are stroked: the width of the line, its caps and joins, and
+/// an optional dash pattern.
+///
+/// ```ignore
+/// let options = StrokeOptions::new(2.)
+///     .cap(StrokeCap::Round)
+///     .dash(DashPattern::dashed(8., 4.));
+///
+/// canvas.stroke_with(path, &Color::BLACK, &options);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeOptions {
+    pub width: f64,
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
+    /// Solid line if `None`.
+    pub dash: Option<DashPattern>,
+}
+
+impl StrokeOptions {
+    pub fn new(width: f64) -> Self {
+        StrokeOptions {
+            width,
+            cap: StrokeCap::default(),
+            join: StrokeJoin::default(),
+            dash: None,
+        }
+    }
+
+    pub fn cap(mut self, cap: StrokeCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn join(mut self, join: StrokeJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn miter_limit(mut self, limit: f64) -> Self {
+        self.join = StrokeJoin::Miter { limit };
+        self
+    }
+
+    pub fn dash(mut self, dash: DashPattern) -> Self {
+        self.dash = Some(dash);
+        self
+    }
+
+    pub fn to_stroke_style(&self) -> StrokeStyle {
+        let mut style = StrokeStyle::new();
+
+        style.set_line_cap(match self.cap {
+            StrokeCap::Butt => LineCap::Butt,
+            StrokeCap::Round => LineCap::Round,
+            StrokeCap::Square => LineCap::Square,
+        });
+
+        style.set_line_join(match self.join {
+            StrokeJoin::Miter { limit } => LineJoin::Miter { limit },
+            StrokeJoin::Round => LineJoin::Round,
+            StrokeJoin::Bevel => LineJoin::Bevel,
+        });
+
+        if let Some(dash) = &self.dash {
+            // Backends differ in how they handle empty and all-zero patterns,
+            // both of which mean a solid line.
+            if dash.period() > 0. {
+                style.set_dash_pattern(dash.dashes.clone());
+                style.set_dash_offset(dash.offset);
+            }
+        }
+
+        style
+    }
+}
+
+pub trait CanvasStrokeExt {
+    /// Strokes `shape` with `color` as described by `options`.
+    fn stroke_with(&mut self, shape: impl Shape, color: &Color, options: &StrokeOptions);
+}
+
+impl CanvasStrokeExt for Canvas<'_> {
+    fn stroke_with(&mut self, shape: impl Shape, color: &Color, options: &StrokeOptions) {
+        if options.width <= 0. {
+            return;
+        }
+
+        let brush = self.solid_brush(color.clone());
+        self.stroke_styled(shape, &brush, options.width, &options.to_stroke_style());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dash_pattern_period() {
+        assert_eq!(DashPattern::dashed(6., 3.).period(), 9.);
+        assert_eq!(DashPattern::dotted(2., 4.).period(), 6.);
+        assert_eq!(DashPattern::new([5.]).period(), 10.);
+        assert_eq!(DashPattern::default().period(), 0.);
+    }
+}
@@ -0,0 +1,102 @@
+//! Platform-appropriate defaults of controls.
+//!
+//! Controls query [`Platform::of`] for the [`TargetPlatform`] they should
+//! resemble, instead of checking the operating system they run on. This way
+//! e.g. iOS look and feel can be previewed and tested on a desktop.
+
+use frui::prelude::*;
+
+/// Platform which controls should resemble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetPlatform {
+    Android,
+    Ios,
+    Linux,
+    MacOs,
+    Windows,
+}
+
+impl Default for TargetPlatform {
+    fn default() -> Self {
+        Self::host()
+    }
+}
+
+impl TargetPlatform {
+    /// Platform the application runs on.
+    pub fn host() -> Self {
+        if cfg!(target_os = "android") {
+            TargetPlatform::Android
+        } else if cfg!(target_os = "ios") {
+            TargetPlatform::Ios
+        } else if cfg!(target_os = "macos") {
+            TargetPlatform::MacOs
+        } else if cfg!(target_os = "windows") {
+            TargetPlatform::Windows
+        } else {
+            TargetPlatform::Linux
+        }
+    }
+
+    pub fn is_mobile(&self) -> bool {
+        matches!(self, TargetPlatform::Android | TargetPlatform::Ios)
+    }
+
+    pub fn is_desktop(&self) -> bool {
+        !self.is_mobile()
+    }
+
+    fn is_apple(&self) -> bool {
+        matches!(self, TargetPlatform::Ios | TargetPlatform::MacOs)
+    }
+
+    pub fn switch_style(&self) -> SwitchStyle {
+        match self.is_apple() {
+            true => SwitchStyle::Cupertino,
+            false => SwitchStyle::Material,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwitchStyle {
+    Material,
+    Cupertino,
+}
+
+/// Overrides [`TargetPlatform`] of its descendants.
+#[derive(InheritedWidget, Builder)]
+pub struct Platform<T: Widget> {
+    pub platform: TargetPlatform,
+    pub child: T,
+}
+
+impl Platform<()> {
+    pub fn builder() -> Self {
+        Platform {
+            platform: TargetPlatform::host(),
+            child: (),
+        }
+    }
+
+    /// Platform of the closest [`Platform`] ancestor, or the host platform if
+    /// there is none.
+    pub fn of<T>(cx: BuildCx<T>) -> TargetPlatform {
+        cx.depend_on::<TargetPlatform>()
+            .map_or_else(TargetPlatform::host, |p| *p)
+    }
+}
+
+impl<T: Widget> InheritedValue for Platform<T> {
+    type Value = TargetPlatform;
+
+    fn value(&self) -> &Self::Value {
+        &self.platform
+    }
+}
+
+impl<T: Widget> InheritedWidget for Platform<T> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
@@ -0,0 +1,53 @@
+use std::marker::PhantomData;
+
+use frui::{
+    app::preferences::{Preference, PreferenceListenerKey, Preferences},
+    prelude::*,
+};
+
+/// Builds its child from the value of preference `key`, rebuilding it every
+/// time that value changes.
+#[derive(ViewWidget)]
+pub struct PreferenceBuilder<T: Preference, W: Widget, F: Fn(Option<T>) -> W> {
+    /// Shouldn't change for the lifetime of this widget.
+    pub key: &'static str,
+    pub builder: F,
+    _p: PhantomData<T>,
+}
+
+impl<T: Preference, W: Widget, F: Fn(Option<T>) -> W> PreferenceBuilder<T, W, F> {
+    pub fn new(key: &'static str, builder: F) -> Self {
+        PreferenceBuilder {
+            key,
+            builder,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T: Preference, W: Widget, F: Fn(Option<T>) -> W> WidgetState for PreferenceBuilder<T, W, F> {
+    type State = Option<PreferenceListenerKey>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        let updater = cx.state_updater();
+        let key = Preferences::listen(self.key, move || updater.update(|_| {}));
+
+        *cx.state_mut() = Some(key);
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        if let Some(key) = cx.state_mut().take() {
+            Preferences::unlisten(key);
+        }
+    }
+}
+
+impl<T: Preference, W: Widget, F: Fn(Option<T>) -> W> ViewWidget for PreferenceBuilder<T, W, F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        (self.builder)(Preferences::get(self.key))
+    }
+}
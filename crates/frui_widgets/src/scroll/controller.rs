@@ -0,0 +1,266 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use frui::{
+    app::leaks::{LeakDetector, Tracked},
+    prelude::*,
+};
+
+use crate::Listenable;
+
+/// Scroll offset shared by a [`Scrollable`](crate::Scrollable) and its
+/// [`Viewport`](crate::Viewport), which can also be read and changed by other
+/// widgets, e.g. a "scroll to top" button.
+///
+/// Clones of a controller refer to the same scroll offset.
+#[derive(Clone)]
+pub struct ScrollController {
+    position: Rc<ScrollPosition>,
+}
+
+#[derive(Default)]
+struct ScrollPosition {
+    offset: Cell<f64>,
+    extents: Cell<ScrollExtents>,
+    animation: RefCell<Option<Ticker>>,
+    listeners: RefCell<Vec<(ScrollListenerKey, Rc<dyn Fn()>, Tracked)>>,
+    next_key: Cell<usize>,
+    _tracked: Tracked,
+}
+
+/// Range in which the scroll offset can change, reported by the viewport
+/// during its layout.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScrollExtents {
+    pub min_scroll_extent: f64,
+    pub max_scroll_extent: f64,
+    /// Size of the viewport along the scroll axis.
+    pub viewport_extent: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollListenerKey(usize);
+
+impl Default for ScrollController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScrollController {
+    pub fn new() -> Self {
+        let position = ScrollPosition {
+            _tracked: LeakDetector::track("ScrollController"),
+            ..Default::default()
+        };
+
+        ScrollController {
+            position: Rc::new(position),
+        }
+    }
+
+    pub fn with_initial_offset(offset: f64) -> Self {
+        let controller = Self::new();
+        controller.position.offset.set(offset);
+        controller
+    }
+
+    pub fn offset(&self) -> f64 {
+        self.position.offset.get()
+    }
+
+    /// Extents as of the last layout of the viewport, or zeros if it wasn't
+    /// laid out yet.
+    pub fn extents(&self) -> ScrollExtents {
+        self.position.extents.get()
+    }
+
+    pub fn max_scroll_extent(&self) -> f64 {
+        self.extents().max_scroll_extent
+    }
+
+    /// Changes the offset immediately, cancelling any running animation.
+    pub fn jump_to(&self, offset: f64) {
+        self.cancel_animation();
+        self.position.set_offset(offset);
+    }
+
+    /// Moves the offset by `delta`, cancelling any running animation.
+    pub fn scroll_by(&self, delta: f64) {
+        self.jump_to(self.offset() + delta);
+    }
+
+    /// Animates the offset to `offset` over `duration`, decelerating towards
+    /// the end.
+    pub fn animate_to(&self, offset: f64, duration: Duration) {
+        if duration.is_zero() {
+            return self.jump_to(offset);
+        }
+
+        self.cancel_animation();
+
+        let position = Rc::downgrade(&self.position);
+        let (from, to) = (self.offset(), self.position.clamp(offset));
+
+        let ticker = Ticker::new(move |elapsed| {
+            let position = match position.upgrade() {
+                Some(position) => position,
+                None => return,
+            };
+
+            let t = (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.);
+            let eased = 1. - (1. - t).powi(3);
+
+            position.set_offset(from + (to - from) * eased);
+
+            if t >= 1. {
+                position.animation.borrow_mut().take();
+            }
+        });
+
+        ticker.start();
+        *self.position.animation.borrow_mut() = Some(ticker);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        let animation = self.position.animation.borrow();
+        animation.as_ref().map_or(false, Ticker::is_active)
+    }
+
+    /// Calls `callback` every time the offset or the extents change.
+    pub fn listen(&self, callback: impl Fn() + 'static) -> ScrollListenerKey {
+        let key = ScrollListenerKey(self.position.next_key.get());
+        self.position.next_key.set(key.0 + 1);
+
+        let mut listeners = self.position.listeners.borrow_mut();
+        listeners.push((key, Rc::new(callback), LeakDetector::track("Listener")));
+
+        key
+    }
+
+    pub fn unlisten(&self, key: ScrollListenerKey) {
+        let mut listeners = self.position.listeners.borrow_mut();
+        listeners.retain(|(k, ..)| *k != key);
+    }
+
+    /// Whether both controllers refer to the same scroll offset.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.position, &other.position)
+    }
+
+    /// Called by the viewport once it is laid out. The offset is clamped to
+    /// the new extents.
+    pub(crate) fn set_extents(&self, extents: ScrollExtents) {
+        if self.position.extents.replace(extents) == extents {
+            return;
+        }
+
+        let offset = self.position.clamp(self.offset());
+        self.position.offset.set(offset);
+
+        self.position.notify();
+    }
+
+    fn cancel_animation(&self) {
+        self.position.animation.borrow_mut().take();
+    }
+}
+
+impl ScrollPosition {
+    fn clamp(&self, offset: f64) -> f64 {
+        let extents = self.extents.get();
+        offset.clamp(extents.min_scroll_extent, extents.max_scroll_extent)
+    }
+
+    fn set_offset(&self, offset: f64) {
+        let offset = self.clamp(offset);
+
+        if offset == self.offset.get() {
+            return;
+        }
+
+        self.offset.set(offset);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        // Listeners may add or remove listeners.
+        let listeners: Vec<_> = self
+            .listeners
+            .borrow()
+            .iter()
+            .map(|l| l.1.clone())
+            .collect();
+
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+impl Listenable for ScrollController {
+    type Key = ScrollListenerKey;
+
+    fn listen(&self, callback: impl Fn() + 'static) -> Self::Key {
+        ScrollController::listen(self, callback)
+    }
+
+    fn unlisten(&self, key: Self::Key) {
+        ScrollController::unlisten(self, key)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        ScrollController::ptr_eq(self, other)
+    }
+}
+
+impl PartialEq for ScrollController {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl std::fmt::Debug for ScrollController {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScrollController")
+            .field("offset", &self.offset())
+            .field("extents", &self.extents())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn offset_is_clamped_to_extents() {
+        let controller = ScrollController::with_initial_offset(500.);
+        let changes = Rc::new(Cell::new(0));
+
+        let key = controller.listen({
+            let changes = changes.clone();
+            move || changes.set(changes.get() + 1)
+        });
+
+        controller.set_extents(ScrollExtents {
+            min_scroll_extent: 0.,
+            max_scroll_extent: 200.,
+            viewport_extent: 100.,
+        });
+        assert_eq!(controller.offset(), 200.);
+
+        controller.scroll_by(-50.);
+        controller.jump_to(-10.);
+        controller.jump_to(0.);
+        assert_eq!(controller.offset(), 0.);
+        assert_eq!(changes.get(), 3);
+
+        controller.unlisten(key);
+        controller.jump_to(100.);
+        assert_eq!(changes.get(), 3);
+    }
+}
@@ -0,0 +1,512 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeSet,
+    ops::Range,
+    rc::Rc,
+};
+
+use frui::{app::metrics::WindowMetrics, prelude::*, render::*};
+
+use super::list_view::LazyItem;
+use crate::*;
+
+/// Sizes of tiles of a [`GridView`] and the distances between them. Tiles
+/// are placed in rows across the scroll axis, filled from the start.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridTiling {
+    /// Number of tiles in a row.
+    pub cross_axis_count: usize,
+    /// Distance between starts of two consecutive rows.
+    pub main_axis_stride: f64,
+    /// Distance between starts of two consecutive tiles of a row.
+    pub cross_axis_stride: f64,
+    pub child_main_extent: f64,
+    pub child_cross_extent: f64,
+}
+
+impl GridTiling {
+    /// Tiling of `cross_axis_count` tiles filling `cross_extent`, with
+    /// `cross_axis_spacing` between them.
+    pub fn fill(
+        cross_extent: f64,
+        cross_axis_count: usize,
+        cross_axis_spacing: f64,
+        main_axis_spacing: f64,
+        child_main_extent: impl FnOnce(f64) -> f64,
+    ) -> Self {
+        let cross_axis_count = cross_axis_count.max(1);
+        let spacing = cross_axis_spacing * (cross_axis_count - 1) as f64;
+        let child_cross_extent = ((cross_extent - spacing) / cross_axis_count as f64).max(0.);
+        let child_main_extent = child_main_extent(child_cross_extent);
+
+        GridTiling {
+            cross_axis_count,
+            main_axis_stride: child_main_extent + main_axis_spacing,
+            cross_axis_stride: child_cross_extent + cross_axis_spacing,
+            child_main_extent,
+            child_cross_extent,
+        }
+    }
+
+    /// Extent of `item_count` tiles along the scroll axis.
+    pub fn main_extent(&self, item_count: usize) -> f64 {
+        let rows = (item_count + self.cross_axis_count - 1) / self.cross_axis_count;
+        let spacing = self.main_axis_stride - self.child_main_extent;
+
+        (rows as f64 * self.main_axis_stride - spacing).max(0.)
+    }
+
+    /// Items whose rows intersect `start..end` along the scroll axis.
+    fn items_between(&self, start: f64, end: f64, item_count: usize) -> Range<usize> {
+        if self.main_axis_stride <= 0. {
+            return 0..item_count;
+        }
+
+        let first_row = (start.max(0.) / self.main_axis_stride) as usize;
+        let last_row = (end.max(0.) / self.main_axis_stride).ceil() as usize;
+
+        let first = (first_row * self.cross_axis_count).min(item_count);
+        let last = (last_row * self.cross_axis_count).min(item_count);
+
+        first..last.max(first)
+    }
+}
+
+/// Decides how tiles of a [`GridView`] are sized, given the extent of the
+/// grid across its scroll axis.
+pub trait GridDelegate {
+    fn tiling(&self, cross_extent: f64) -> GridTiling;
+}
+
+impl<T: GridDelegate> GridDelegate for &T {
+    fn tiling(&self, cross_extent: f64) -> GridTiling {
+        T::tiling(self, cross_extent)
+    }
+}
+
+/// Grid with `cross_axis_count` tiles in every row, stretched to fill it.
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+pub struct GridFixedCrossAxisCount {
+    pub cross_axis_count: usize,
+    pub main_axis_spacing: f64,
+    pub cross_axis_spacing: f64,
+    /// Ratio of the cross axis extent of tiles to their main axis extent.
+    pub child_aspect_ratio: f64,
+    /// Extent of tiles along the scroll axis, overriding
+    /// `child_aspect_ratio`.
+    pub main_axis_extent: Option<f64>,
+}
+
+impl GridFixedCrossAxisCount {
+    pub fn new(cross_axis_count: usize) -> Self {
+        GridFixedCrossAxisCount {
+            cross_axis_count,
+            main_axis_spacing: 0.,
+            cross_axis_spacing: 0.,
+            child_aspect_ratio: 1.,
+            main_axis_extent: None,
+        }
+    }
+}
+
+impl GridDelegate for GridFixedCrossAxisCount {
+    fn tiling(&self, cross_extent: f64) -> GridTiling {
+        GridTiling::fill(
+            cross_extent,
+            self.cross_axis_count,
+            self.cross_axis_spacing,
+            self.main_axis_spacing,
+            |child_cross| {
+                self.main_axis_extent
+                    .unwrap_or(child_cross / self.child_aspect_ratio)
+            },
+        )
+    }
+}
+
+/// Grid with as few tiles in a row as possible without any of them being
+/// wider than `max_cross_axis_extent`, e.g. so that a photo grid shows more
+/// photos in a wider window.
+#[derive(Debug, Clone, Copy, PartialEq, Builder)]
+pub struct GridMaxCrossAxisExtent {
+    pub max_cross_axis_extent: f64,
+    pub main_axis_spacing: f64,
+    pub cross_axis_spacing: f64,
+    /// Ratio of the cross axis extent of tiles to their main axis extent.
+    pub child_aspect_ratio: f64,
+    /// Extent of tiles along the scroll axis, overriding
+    /// `child_aspect_ratio`.
+    pub main_axis_extent: Option<f64>,
+}
+
+impl GridMaxCrossAxisExtent {
+    pub fn new(max_cross_axis_extent: f64) -> Self {
+        GridMaxCrossAxisExtent {
+            max_cross_axis_extent,
+            main_axis_spacing: 0.,
+            cross_axis_spacing: 0.,
+            child_aspect_ratio: 1.,
+            main_axis_extent: None,
+        }
+    }
+}
+
+impl GridDelegate for GridMaxCrossAxisExtent {
+    fn tiling(&self, cross_extent: f64) -> GridTiling {
+        let count = (cross_extent / (self.max_cross_axis_extent + self.cross_axis_spacing)).ceil();
+
+        GridTiling::fill(
+            cross_extent,
+            count as usize,
+            self.cross_axis_spacing,
+            self.main_axis_spacing,
+            |child_cross| {
+                self.main_axis_extent
+                    .unwrap_or(child_cross / self.child_aspect_ratio)
+            },
+        )
+    }
+}
+
+/// Scrollable grid of `item_count` widgets returned by `item_builder`, tiled
+/// by a [`GridDelegate`].
+///
+/// Like in a [`ListView`], only items in the visible part of the grid
+/// (extended by `cache_extent` on both sides) are built, unless `lazy` is
+/// turned off, and building of items which aren't visible is postponed once
+/// the [`FrameBudget`](frui::app::frame_budget::FrameBudget) of a frame is
+/// exhausted. Grids with few items can build all of them, so that they keep
+/// their state while scrolled out of view.
+///
+/// ```ignore
+/// GridView::builder(
+///     photos.len(),
+///     GridMaxCrossAxisExtent::new(200.).cross_axis_spacing(4.),
+///     |i| PhotoTile::new(&photos[i]),
+/// )
+/// ```
+#[derive(ViewWidget)]
+pub struct GridView<D: GridDelegate, W: Widget, F: Fn(usize) -> W> {
+    pub item_count: usize,
+    pub delegate: D,
+    pub item_builder: F,
+    pub controller: Option<ScrollController>,
+    pub scroll_direction: ScrollDirection,
+    pub cache_extent: f64,
+    pub lazy: bool,
+}
+
+impl<D: GridDelegate, W: Widget, F: Fn(usize) -> W> GridView<D, W, F> {
+    pub fn builder(item_count: usize, delegate: D, item_builder: F) -> Self {
+        GridView {
+            item_count,
+            delegate,
+            item_builder,
+            controller: None,
+            scroll_direction: ScrollDirection::Vertical,
+            cache_extent: 250.,
+            lazy: true,
+        }
+    }
+
+    pub fn controller(mut self, controller: ScrollController) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    pub fn scroll_direction(mut self, scroll_direction: ScrollDirection) -> Self {
+        self.scroll_direction = scroll_direction;
+        self
+    }
+
+    pub fn cache_extent(mut self, cache_extent: f64) -> Self {
+        self.cache_extent = cache_extent;
+        self
+    }
+
+    pub fn lazy(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+}
+
+impl<W: Widget, F: Fn(usize) -> W> GridView<GridFixedCrossAxisCount, W, F> {
+    /// Grid with `cross_axis_count` square tiles in every row.
+    pub fn count(item_count: usize, cross_axis_count: usize, item_builder: F) -> Self {
+        let delegate = GridFixedCrossAxisCount::new(cross_axis_count);
+        GridView::builder(item_count, delegate, item_builder)
+    }
+}
+
+impl<W: Widget, F: Fn(usize) -> W> GridView<GridMaxCrossAxisExtent, W, F> {
+    /// Grid of square tiles no wider than `max_cross_axis_extent`.
+    pub fn extent(item_count: usize, max_cross_axis_extent: f64, item_builder: F) -> Self {
+        let delegate = GridMaxCrossAxisExtent::new(max_cross_axis_extent);
+        GridView::builder(item_count, delegate, item_builder)
+    }
+}
+
+pub struct GridViewState {
+    /// Controller used if none was passed.
+    controller: ScrollController,
+    layout: Rc<GridLayout>,
+    /// Rebuilds the grid once scrolling reveals items which weren't built.
+    listener: ControllerListener<ScrollController>,
+}
+
+impl<D: GridDelegate, W: Widget, F: Fn(usize) -> W> WidgetState for GridView<D, W, F> {
+    type State = GridViewState;
+
+    fn create_state(&self) -> Self::State {
+        GridViewState {
+            controller: ScrollController::new(),
+            layout: Rc::default(),
+            listener: ControllerListener::default(),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().listener.unlisten();
+    }
+}
+
+impl<D: GridDelegate, W: Widget, F: Fn(usize) -> W> ViewWidget for GridView<D, W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.controller.clone(),
+        };
+
+        let layout = state.layout.clone();
+        layout.item_count.set(self.item_count);
+        layout.cache_extent.set(self.cache_extent);
+        layout.lazy.set(self.lazy);
+
+        // Until the grid is laid out, it's assumed to fill the window, so
+        // that the first frame isn't blank. Once laid out, it's rebuilt if
+        // the assumption was wrong.
+        let window = WindowMetrics::current().size;
+        let (window_main, window_cross) = match self.scroll_direction {
+            ScrollDirection::Vertical => (window.height, window.width),
+            ScrollDirection::Horizontal => (window.width, window.height),
+        };
+
+        let cross_extent = layout.cross_extent.get().unwrap_or(window_cross);
+        layout.tiling.set(Some(self.delegate.tiling(cross_extent)));
+        layout.window_extent.set(window_main);
+
+        // Laying the grid out with a different extent across the scroll axis
+        // changes its scroll extents too, which rebuilds it if needed.
+        let updater = cx.state_updater();
+        state.listener.listen(&controller, {
+            let (controller, layout) = (controller.clone(), layout.clone());
+
+            move || {
+                if layout.range_to_build(&controller) != *layout.built.borrow() {
+                    updater.update(|_| {});
+                }
+            }
+        });
+
+        let range = layout.range_to_build(&controller);
+        let visible = layout.range(&controller, 0.);
+        *layout.built.borrow_mut() = range.clone();
+
+        Scrollable {
+            child: LazyGrid {
+                first: range.start,
+                children: range
+                    .map(|i| {
+                        let item = LazyItem {
+                            index: i,
+                            child: (self.item_builder)(i),
+                            postponable: !visible.contains(&i),
+                            pending: layout.pending.clone(),
+                        };
+
+                        LocalKey::new(i, item)
+                    })
+                    .collect(),
+                delegate: &self.delegate,
+                layout,
+                direction: self.scroll_direction,
+            },
+            controller,
+            scroll_direction: self.scroll_direction,
+            reverse: false,
+        }
+    }
+}
+
+/// Lays out built items of a [`GridView`] in rows of its tiling.
+#[derive(RenderWidget)]
+struct LazyGrid<W: Widget, D: GridDelegate> {
+    /// Index of the first built item.
+    first: usize,
+    children: Vec<LocalKey<usize, W>>,
+    delegate: D,
+    layout: Rc<GridLayout>,
+    direction: ScrollDirection,
+}
+
+impl<W: Widget, D: GridDelegate> RenderWidget for LazyGrid<W, D> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.iter().collect()
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let cross_extent = match self.direction {
+            ScrollDirection::Vertical => constraints.max_width,
+            ScrollDirection::Horizontal => constraints.max_height,
+        };
+
+        assert!(
+            cross_extent.is_finite(),
+            "grid received unbounded constraints across its scroll axis"
+        );
+
+        let tiling = self.delegate.tiling(cross_extent);
+
+        self.layout.cross_extent.set(Some(cross_extent));
+        self.layout.tiling.set(Some(tiling));
+        let main_extent = tiling.main_extent(self.layout.item_count.get());
+
+        // Rows of vertical grids start on the right in right-to-left text.
+        let rtl = self.direction == ScrollDirection::Vertical
+            && Directionality::of_or_default(cx) == TextDirection::Rtl;
+
+        for (n, child) in cx.children().enumerate() {
+            let index = self.first + n;
+            let row = (index / tiling.cross_axis_count) as f64;
+            let column = (index % tiling.cross_axis_count) as f64;
+
+            let main = row * tiling.main_axis_stride;
+            let cross = match rtl {
+                true => {
+                    cross_extent - column * tiling.cross_axis_stride - tiling.child_cross_extent
+                }
+                false => column * tiling.cross_axis_stride,
+            };
+
+            let (offset, size) = match self.direction {
+                ScrollDirection::Vertical => (
+                    Offset::new(cross, main),
+                    Size::new(tiling.child_cross_extent, tiling.child_main_extent),
+                ),
+                ScrollDirection::Horizontal => (
+                    Offset::new(main, cross),
+                    Size::new(tiling.child_main_extent, tiling.child_cross_extent),
+                ),
+            };
+
+            child.layout(Constraints::new_tight(size));
+            child.set_offset(offset);
+        }
+
+        match self.direction {
+            ScrollDirection::Vertical => Size::new(cross_extent, main_extent),
+            ScrollDirection::Horizontal => Size::new(main_extent, cross_extent),
+        }
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.paint_children(canvas, offset)
+    }
+}
+
+/// State of a [`GridView`] shared with its render widget.
+#[derive(Default)]
+struct GridLayout {
+    item_count: Cell<usize>,
+    cache_extent: Cell<f64>,
+    lazy: Cell<bool>,
+    /// Extent across the scroll axis the grid was last laid out with.
+    cross_extent: Cell<Option<f64>>,
+    /// Tiling of the grid, `None` until it is built.
+    tiling: Cell<Option<GridTiling>>,
+    /// Extent of the window along the scroll axis, which the viewport is
+    /// assumed to have until the grid is laid out.
+    window_extent: Cell<f64>,
+    /// Items built in the last build.
+    built: RefCell<Range<usize>>,
+    /// Built items which postponed building of their content.
+    pending: Rc<RefCell<BTreeSet<usize>>>,
+}
+
+impl GridLayout {
+    /// Items in the visible part of the grid, extended by the cache extent.
+    fn range_to_build(&self, controller: &ScrollController) -> Range<usize> {
+        match self.lazy.get() {
+            true => self.range(controller, self.cache_extent.get()),
+            false => 0..self.item_count.get(),
+        }
+    }
+
+    /// Items in the visible part of the grid, extended by `cache` on both
+    /// sides.
+    fn range(&self, controller: &ScrollController, cache: f64) -> Range<usize> {
+        let tiling = match self.tiling.get() {
+            Some(tiling) => tiling,
+            None => return 0..0,
+        };
+
+        let viewport_extent = match self.cross_extent.get() {
+            Some(_) => controller.extents().viewport_extent,
+            None => self.window_extent.get(),
+        };
+
+        let start = controller.offset() - cache;
+        let end = controller.offset() + viewport_extent + cache;
+
+        tiling.items_between(start, end, self.item_count.get())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tiles_rows() {
+        let tiling = GridFixedCrossAxisCount::new(3)
+            .cross_axis_spacing(10.)
+            .main_axis_spacing(5.)
+            .tiling(320.);
+
+        assert_eq!(tiling.child_cross_extent, 100.);
+        assert_eq!(tiling.child_main_extent, 100.);
+        assert_eq!(tiling.main_axis_stride, 105.);
+        assert_eq!(tiling.cross_axis_stride, 110.);
+
+        // 3 rows, without spacing after the last one.
+        assert_eq!(tiling.main_extent(7), 310.);
+        assert_eq!(tiling.main_extent(0), 0.);
+
+        assert_eq!(tiling.items_between(0., 100., 7), 0..3);
+        assert_eq!(tiling.items_between(110., 220., 7), 3..7);
+
+        let tiling = GridMaxCrossAxisExtent::new(100.).tiling(250.);
+        assert_eq!(tiling.cross_axis_count, 3);
+    }
+
+    #[test]
+    fn builds_items_filling_the_window_before_layout() {
+        let layout = GridLayout::default();
+        layout.item_count.set(100);
+        layout.lazy.set(true);
+        layout
+            .tiling
+            .set(Some(GridFixedCrossAxisCount::new(3).tiling(300.)));
+        layout.window_extent.set(250.);
+
+        let controller = ScrollController::new();
+
+        // 3 rows of 100 points intersect the window.
+        assert_eq!(layout.range_to_build(&controller), 0..9);
+
+        layout.lazy.set(false);
+        assert_eq!(layout.range_to_build(&controller), 0..100);
+    }
+}
@@ -0,0 +1,521 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+    rc::Rc,
+    time::Duration,
+};
+
+use frui::{app::frame_budget::FrameBudget, prelude::*, render::*};
+
+use crate::*;
+
+/// Extent assumed for items which weren't laid out yet, if nothing was laid
+/// out yet either.
+const DEFAULT_ITEM_EXTENT: f64 = 50.;
+
+/// Scrollable list of `item_count` widgets returned by `item_builder`.
+///
+/// Only items in the visible part of the list (extended by `cache_extent` on
+/// both sides) are built and laid out. Items keep their state while they stay
+/// in that region, and are dropped once they are scrolled out of it.
+///
+/// Building of items entering the cache extent is postponed to later frames
+/// once the [`FrameBudget`] of a frame is exhausted, so that scrolling many
+/// items into it at once doesn't delay a frame. Visible items are always
+/// built right away.
+///
+/// Positions of items which weren't laid out yet are estimated from the
+/// average extent of those which were, so the scroll position may shift
+/// slightly as new items are measured. Setting `item_extent` avoids that and
+/// makes jumping far into the list cheaper.
+///
+/// With `recycle_items`, widget nodes of items scrolled out of the cache
+/// extent are reused by items scrolled into it, which are then updated
+/// instead of built from scratch. Widgets keep their state when reused, so
+/// state of items has to be either derived from the item, or kept under a
+/// [`LocalKey`] of the item.
+///
+/// ```ignore
+/// ListView::builder(contacts.len(), |i| ContactTile::new(&contacts[i]))
+///     .item_extent(56.)
+/// ```
+#[derive(ViewWidget)]
+pub struct ListView<W: Widget, F: Fn(usize) -> W> {
+    pub item_count: usize,
+    pub item_builder: F,
+    pub controller: Option<ScrollController>,
+    pub scroll_direction: ScrollDirection,
+    /// Extent of every item along `scroll_direction`. If `None`, items are
+    /// measured as they are laid out.
+    pub item_extent: Option<f64>,
+    pub cache_extent: f64,
+    pub recycle_items: bool,
+}
+
+impl<W: Widget, F: Fn(usize) -> W> ListView<W, F> {
+    pub fn builder(item_count: usize, item_builder: F) -> Self {
+        ListView {
+            item_count,
+            item_builder,
+            controller: None,
+            scroll_direction: ScrollDirection::Vertical,
+            item_extent: None,
+            cache_extent: 250.,
+            recycle_items: false,
+        }
+    }
+
+    pub fn controller(mut self, controller: ScrollController) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    pub fn scroll_direction(mut self, scroll_direction: ScrollDirection) -> Self {
+        self.scroll_direction = scroll_direction;
+        self
+    }
+
+    pub fn item_extent(mut self, item_extent: f64) -> Self {
+        self.item_extent = Some(item_extent);
+        self
+    }
+
+    pub fn cache_extent(mut self, cache_extent: f64) -> Self {
+        self.cache_extent = cache_extent;
+        self
+    }
+
+    pub fn recycle_items(mut self, recycle_items: bool) -> Self {
+        self.recycle_items = recycle_items;
+        self
+    }
+}
+
+pub struct ListViewState {
+    /// Controller used if none was passed.
+    controller: ScrollController,
+    layout: Rc<ListLayout>,
+    /// Rebuilds the list once scrolling reveals items which weren't built.
+    listener: ControllerListener<ScrollController>,
+}
+
+impl<W: Widget, F: Fn(usize) -> W> WidgetState for ListView<W, F> {
+    type State = ListViewState;
+
+    fn create_state(&self) -> Self::State {
+        ListViewState {
+            controller: ScrollController::new(),
+            layout: Rc::default(),
+            listener: ControllerListener::default(),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().listener.unlisten();
+    }
+}
+
+impl<W: Widget, F: Fn(usize) -> W> ViewWidget for ListView<W, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.controller.clone(),
+        };
+
+        let layout = state.layout.clone();
+        layout.configure(self.item_count, self.item_extent, self.cache_extent);
+
+        let updater = cx.state_updater();
+        state.listener.listen(&controller, {
+            let (controller, layout) = (controller.clone(), layout.clone());
+
+            move || {
+                if layout.range_to_build(&controller) != layout.built.borrow().clone() {
+                    updater.update(|_| {});
+                }
+            }
+        });
+
+        let range = layout.range_to_build(&controller);
+        let visible = layout.range(&controller, 0.);
+        *layout.built.borrow_mut() = range.clone();
+
+        let slots = layout.assign_slots(range.clone());
+
+        Scrollable {
+            child: LazyList {
+                first: range.start,
+                children: range
+                    .zip(slots)
+                    .map(|(i, slot)| {
+                        let item = LazyItem {
+                            index: i,
+                            child: (self.item_builder)(i),
+                            postponable: !visible.contains(&i),
+                            pending: layout.pending.clone(),
+                        };
+
+                        // Nodes are matched by keys, so items taking over the
+                        // slot of another item reuse its node.
+                        match self.recycle_items {
+                            true => LocalKey::new(slot, item),
+                            false => LocalKey::new(i, item),
+                        }
+                    })
+                    .collect(),
+                layout,
+                direction: self.scroll_direction,
+            },
+            controller,
+            scroll_direction: self.scroll_direction,
+            reverse: false,
+        }
+    }
+}
+
+/// Item of a [`ListView`] or a [`GridView`], which postpones building of
+/// `child` to the next frame if the budget of the current one is exhausted.
+#[derive(ViewWidget)]
+pub(crate) struct LazyItem<W: Widget> {
+    pub index: usize,
+    pub child: W,
+    /// Whether building can be postponed, i.e. the item isn't visible.
+    pub postponable: bool,
+    /// Items which postponed building, shared by all items of the view.
+    pub pending: Rc<RefCell<BTreeSet<usize>>>,
+}
+
+impl<W: Widget> WidgetState for LazyItem<W> {
+    /// Index of the item whose `child` was built, after which it's kept
+    /// regardless of the frame budget. Recycled nodes change their index.
+    type State = Cell<Option<usize>>;
+
+    fn create_state(&self) -> Self::State {
+        Cell::new(None)
+    }
+
+    fn unmount(&self, _: BuildCx<Self>) {
+        self.pending.borrow_mut().remove(&self.index);
+    }
+}
+
+impl<W: Widget> ViewWidget for LazyItem<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let built = cx.state().get() == Some(self.index);
+
+        if !built && self.postponable && FrameBudget::is_exhausted() {
+            self.pending.borrow_mut().insert(self.index);
+            cx.timer_once(Duration::ZERO, |_| {});
+
+            return ().boxed();
+        }
+
+        self.pending.borrow_mut().remove(&self.index);
+        cx.state().set(Some(self.index));
+
+        (&self.child).boxed()
+    }
+}
+
+/// Lays out built items of a [`ListView`] one after another, at positions
+/// matching their indices.
+#[derive(RenderWidget)]
+struct LazyList<W: Widget> {
+    /// Index of the first built item.
+    first: usize,
+    children: Vec<LocalKey<usize, W>>,
+    layout: Rc<ListLayout>,
+    direction: ScrollDirection,
+}
+
+impl<W: Widget> RenderWidget for LazyList<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.iter().collect()
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let fixed = self.layout.item_extent.get();
+
+        // Items fill the list across the scroll axis, if it is bounded.
+        let fill = |min: f64, max: f64| if max.is_finite() { max } else { min };
+
+        let (child_constraints, cross_extent) = match self.direction {
+            ScrollDirection::Vertical => (
+                Constraints::new(
+                    fill(constraints.min_width, constraints.max_width),
+                    constraints.max_width,
+                    fixed.unwrap_or(0.),
+                    fixed.unwrap_or(f64::INFINITY),
+                ),
+                constraints.max_width,
+            ),
+            ScrollDirection::Horizontal => (
+                Constraints::new(
+                    fixed.unwrap_or(0.),
+                    fixed.unwrap_or(f64::INFINITY),
+                    fill(constraints.min_height, constraints.max_height),
+                    constraints.max_height,
+                ),
+                constraints.max_height,
+            ),
+        };
+
+        self.layout.set_cross_extent(cross_extent);
+
+        let mut max_cross: f64 = 0.;
+
+        for (n, child) in cx.children().enumerate() {
+            let index = self.first + n;
+            let size = child.layout(child_constraints);
+
+            // Items which weren't built yet keep their estimated extent.
+            if !self.layout.pending.borrow().contains(&index) {
+                self.layout.measure(index, self.direction.extent(size));
+            }
+
+            max_cross = max_cross.max(match self.direction {
+                ScrollDirection::Vertical => size.width,
+                ScrollDirection::Horizontal => size.height,
+            });
+        }
+
+        let main = self.layout.total_extent();
+
+        match self.direction {
+            ScrollDirection::Vertical => Size::new(constraints.constrain_width(max_cross), main),
+            ScrollDirection::Horizontal => Size::new(main, constraints.constrain_height(max_cross)),
+        }
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let mut position = self.layout.offset_of(self.first);
+
+        for (n, mut child) in cx.children().enumerate() {
+            let child_offset = match self.direction {
+                ScrollDirection::Vertical => Offset::new(0., position),
+                ScrollDirection::Horizontal => Offset::new(position, 0.),
+            };
+
+            child.paint(canvas, &(*offset + child_offset));
+
+            position += self.layout.extent(self.first + n);
+        }
+    }
+}
+
+/// Extents of items of a [`ListView`] along its scroll axis, shared between
+/// the list and its render widget.
+#[derive(Default)]
+struct ListLayout {
+    item_count: Cell<usize>,
+    item_extent: Cell<Option<f64>>,
+    cache_extent: Cell<f64>,
+    /// Extents of items which were laid out.
+    measured: RefCell<BTreeMap<usize, f64>>,
+    /// Cross axis extent the items were measured with.
+    cross_extent: Cell<f64>,
+    /// Items built in the last build.
+    built: RefCell<Range<usize>>,
+    /// Built items which postponed building of their content.
+    pending: Rc<RefCell<BTreeSet<usize>>>,
+    /// Slots of built items, which key their nodes if items are recycled.
+    slots: RefCell<BTreeMap<usize, usize>>,
+}
+
+impl ListLayout {
+    fn configure(&self, item_count: usize, item_extent: Option<f64>, cache_extent: f64) {
+        if item_count < self.item_count.get() {
+            self.measured.borrow_mut().split_off(&item_count);
+        }
+
+        self.item_count.set(item_count);
+        self.item_extent.set(item_extent);
+        self.cache_extent.set(cache_extent);
+    }
+
+    /// Slots of items in `range`. Items which were built before keep their
+    /// slots, and new ones take over slots of items which aren't built
+    /// anymore.
+    fn assign_slots(&self, range: Range<usize>) -> Vec<usize> {
+        let mut slots = self.slots.borrow_mut();
+        let mut free = Vec::new();
+
+        slots.retain(|index, slot| {
+            let keep = range.contains(index);
+
+            if !keep {
+                free.push(*slot);
+            }
+
+            keep
+        });
+
+        let mut next = slots.values().chain(&free).max().map_or(0, |s| s + 1);
+
+        // Slots of items closest to the range are reused first.
+        free.reverse();
+
+        range
+            .map(|index| {
+                *slots.entry(index).or_insert_with(|| {
+                    free.pop().unwrap_or_else(|| {
+                        next += 1;
+                        next - 1
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Items get remeasured once the extent across the scroll axis changes,
+    /// since their extents along it likely changed too (e.g. wrapped text).
+    fn set_cross_extent(&self, cross_extent: f64) {
+        if self.cross_extent.replace(cross_extent) != cross_extent {
+            self.measured.borrow_mut().clear();
+        }
+    }
+
+    fn measure(&self, index: usize, extent: f64) {
+        self.measured.borrow_mut().insert(index, extent);
+    }
+
+    /// Extent of items which weren't laid out yet.
+    fn estimated_extent(&self) -> f64 {
+        if let Some(extent) = self.item_extent.get() {
+            return extent;
+        }
+
+        let measured = self.measured.borrow();
+
+        match measured.len() {
+            0 => DEFAULT_ITEM_EXTENT,
+            len => measured.values().sum::<f64>() / len as f64,
+        }
+    }
+
+    fn extent(&self, index: usize) -> f64 {
+        match self.item_extent.get() {
+            Some(extent) => extent,
+            None => match self.measured.borrow().get(&index) {
+                Some(extent) => *extent,
+                None => self.estimated_extent(),
+            },
+        }
+    }
+
+    /// Position of the start of the item at `index`.
+    fn offset_of(&self, index: usize) -> f64 {
+        let estimated = self.estimated_extent();
+
+        if self.item_extent.get().is_some() {
+            return index as f64 * estimated;
+        }
+
+        let measured = self.measured.borrow();
+        let (count, sum) = measured
+            .range(..index)
+            .fold((0, 0.), |(count, sum), (_, extent)| {
+                (count + 1, sum + extent)
+            });
+
+        sum + (index - count) as f64 * estimated
+    }
+
+    fn total_extent(&self) -> f64 {
+        self.offset_of(self.item_count.get())
+    }
+
+    /// Index of the item at `offset`, which might be past the last item.
+    fn index_at(&self, offset: f64) -> usize {
+        let estimated = self.estimated_extent();
+        let unmeasured =
+            |from: usize, distance: f64| from + (distance / estimated).max(0.) as usize;
+
+        if self.item_extent.get().is_some() {
+            return unmeasured(0, offset);
+        }
+
+        let (mut position, mut next) = (0., 0);
+
+        for (&index, &extent) in self.measured.borrow().iter() {
+            let gap = (index - next) as f64 * estimated;
+
+            if position + gap > offset {
+                return unmeasured(next, offset - position);
+            }
+
+            position += gap;
+
+            if position + extent > offset {
+                return index;
+            }
+
+            position += extent;
+            next = index + 1;
+        }
+
+        unmeasured(next, offset - position)
+    }
+
+    /// Items in the visible part of the list, extended by the cache extent.
+    fn range_to_build(&self, controller: &ScrollController) -> Range<usize> {
+        self.range(controller, self.cache_extent.get())
+    }
+
+    /// Items in the visible part of the list, extended by `cache` on both
+    /// sides.
+    fn range(&self, controller: &ScrollController, cache: f64) -> Range<usize> {
+        let count = self.item_count.get();
+        let start = controller.offset() - cache;
+        let end = controller.offset() + controller.extents().viewport_extent + cache;
+
+        let first = self.index_at(start.max(0.)).min(count);
+        let last = (self.index_at(end) + 1).min(count);
+
+        first..last
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn estimates_extents_of_unmeasured_items() {
+        let layout = ListLayout::default();
+        layout.configure(100, None, 0.);
+
+        assert_eq!(layout.total_extent(), 100. * DEFAULT_ITEM_EXTENT);
+
+        layout.measure(0, 10.);
+        layout.measure(1, 30.);
+        layout.measure(5, 20.);
+
+        // Unmeasured items are assumed to be 20 points tall.
+        assert_eq!(layout.offset_of(2), 40.);
+        assert_eq!(layout.offset_of(6), 120.);
+        assert_eq!(layout.total_extent(), 2000.);
+
+        assert_eq!(layout.index_at(5.), 0);
+        assert_eq!(layout.index_at(39.), 1);
+        assert_eq!(layout.index_at(61.), 3);
+        assert_eq!(layout.index_at(105.), 5);
+        assert_eq!(layout.index_at(125.), 6);
+
+        layout.configure(4, None, 0.);
+        assert_eq!(layout.measured.borrow().len(), 2);
+    }
+
+    #[test]
+    fn items_entering_the_range_reuse_slots() {
+        let layout = ListLayout::default();
+
+        assert_eq!(layout.assign_slots(0..4), vec![0, 1, 2, 3]);
+        // Items 0 and 1 left, so items 4 and 5 take over their slots.
+        assert_eq!(layout.assign_slots(2..6), vec![2, 3, 0, 1]);
+        assert_eq!(layout.assign_slots(3..8), vec![3, 0, 1, 2, 4]);
+    }
+}
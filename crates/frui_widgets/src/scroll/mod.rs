@@ -0,0 +1,249 @@
+//! Scrolling of widgets larger than the space they are given.
+//!
+//! [`SingleChildScrollView`] covers the common case. It is built from a
+//! [`Scrollable`] which turns drags and mouse wheel into scroll offset of a
+//! [`ScrollController`], and a [`Viewport`] which paints the visible part of
+//! its child at that offset.
+//!
+//! Both reveal the caret of the focused text field when it's moved or when
+//! the soft keyboard appears (see [`Caret::reveal`]). [`Scroll`] is an older
+//! prototype of the viewport.
+
+use std::cell::Cell;
+
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::soft_keyboard::Caret;
+
+mod controller;
+mod grid_view;
+mod list_view;
+mod viewport;
+
+pub use controller::*;
+pub use grid_view::*;
+pub use list_view::*;
+pub use viewport::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollDirection {
+    Horizontal,
+    Vertical,
+    // Todo: All,
+}
+
+impl ScrollDirection {
+    /// Component of `v` along the scroll axis.
+    pub(crate) fn along(&self, v: Vec2) -> f64 {
+        match self {
+            ScrollDirection::Horizontal => v.x,
+            ScrollDirection::Vertical => v.y,
+        }
+    }
+
+    /// Vector of length `extent` pointing along the scroll axis.
+    pub(crate) fn vec(&self, extent: f64) -> Vec2 {
+        match self {
+            ScrollDirection::Horizontal => Vec2::new(extent, 0.),
+            ScrollDirection::Vertical => Vec2::new(0., extent),
+        }
+    }
+
+    pub(crate) fn extent(&self, size: Size) -> f64 {
+        match self {
+            ScrollDirection::Horizontal => size.width,
+            ScrollDirection::Vertical => size.height,
+        }
+    }
+
+    /// Constraints of the scrolled child, unbounded along the scroll axis.
+    pub(crate) fn child_constraints(&self, constraints: Constraints) -> Constraints {
+        match self {
+            ScrollDirection::Horizontal => Constraints {
+                min_width: 0.,
+                max_width: f64::INFINITY,
+                ..constraints
+            },
+            ScrollDirection::Vertical => Constraints {
+                min_height: 0.,
+                max_height: f64::INFINITY,
+                ..constraints
+            },
+        }
+    }
+}
+
+/// Older prototype of [`Viewport`] which keeps its scroll offset to itself.
+/// Prefer [`SingleChildScrollView`].
+#[derive(RenderWidget, Builder)]
+pub struct Scroll<W: Widget> {
+    pub child: W,
+    pub scroll_direction: ScrollDirection,
+}
+
+impl Scroll<()> {
+    pub fn builder() -> Scroll<()> {
+        Scroll {
+            child: (),
+            scroll_direction: ScrollDirection::Vertical,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct ScrollState {
+    scroll_offset: Cell<Vec2>,
+}
+
+impl<W: Widget> WidgetState for Scroll<W> {
+    type State = ScrollState;
+
+    fn create_state(&self) -> Self::State {
+        ScrollState {
+            scroll_offset: Cell::new(Vec2::new(0., 0.)),
+        }
+    }
+}
+
+impl<W: Widget> Scroll<W> {
+    /// Scroll offset at which the caret requested to be revealed (see
+    /// [`Caret::reveal`]) is visible, if it is a part of this scroll's content.
+    fn reveal_caret(&self, scroll_offset: Vec2, viewport: Rect, content_size: Size) -> Vec2 {
+        // Content bounds in the window coordinates.
+        let content = Rect::from_ltwh(
+            viewport.left - scroll_offset.x,
+            viewport.top - scroll_offset.y,
+            content_size.width,
+            content_size.height,
+        );
+
+        let delta = match caret_reveal_delta(self.scroll_direction, viewport, content) {
+            Some(delta) => delta,
+            None => return scroll_offset,
+        };
+
+        let mut scroll_offset = scroll_offset;
+
+        match self.scroll_direction {
+            ScrollDirection::Vertical => {
+                let max = (content_size.height - viewport.height()).max(0.);
+                scroll_offset.y = (scroll_offset.y - delta).clamp(0., max);
+            }
+            ScrollDirection::Horizontal => {
+                let max = (content_size.width - viewport.width()).max(0.);
+                scroll_offset.x = (scroll_offset.x - delta).clamp(0., max);
+            }
+        }
+
+        scroll_offset
+    }
+}
+
+/// Distance by which scrolled `content` has to be moved along `direction` to
+/// reveal the caret requested to be revealed (see [`Caret::reveal`]), if it's
+/// a part of that content. The request is taken if so.
+///
+/// Both `viewport` and `content` are in window coordinates.
+pub(crate) fn caret_reveal_delta(
+    direction: ScrollDirection,
+    viewport: Rect,
+    content: Rect,
+) -> Option<f64> {
+    let caret = Caret::reveal_request()?;
+
+    let contains = caret.left >= content.left
+        && caret.right <= content.right
+        && caret.top >= content.top
+        && caret.bottom <= content.bottom;
+
+    if !contains {
+        return None;
+    }
+
+    Caret::take_reveal_request();
+
+    // Part of the viewport which isn't covered by the soft keyboard.
+    let visible_bottom = viewport.bottom.min(Caret::visible_bottom());
+
+    Some(reveal_delta(direction, caret, viewport, visible_bottom))
+}
+
+/// Distance by which `caret` has to be moved along `direction` to be within
+/// `viewport`, above `visible_bottom`.
+fn reveal_delta(
+    direction: ScrollDirection,
+    caret: Rect,
+    viewport: Rect,
+    visible_bottom: f64,
+) -> f64 {
+    match direction {
+        ScrollDirection::Vertical if caret.bottom > visible_bottom => visible_bottom - caret.bottom,
+        ScrollDirection::Vertical if caret.top < viewport.top => viewport.top - caret.top,
+        ScrollDirection::Horizontal if caret.right > viewport.right => viewport.right - caret.right,
+        ScrollDirection::Horizontal if caret.left < viewport.left => viewport.left - caret.left,
+        _ => 0.,
+    }
+}
+
+impl<W: Widget> RenderWidget for Scroll<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let child_constraints = self.scroll_direction.child_constraints(constraints);
+
+        cx.child(0).layout(child_constraints);
+
+        constraints.biggest()
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let viewport = Rect::from_origin_size(*offset, cx.size());
+
+        cx.with_clip(canvas, viewport, |cx, canvas| {
+            let window_viewport = canvas
+                .current_transform()
+                .transform_rect_bbox(viewport.into());
+            let content_size = cx.child(0).size();
+            let scroll_offset = self.reveal_caret(
+                cx.widget_state().scroll_offset.get(),
+                window_viewport.into(),
+                content_size,
+            );
+            cx.widget_state().scroll_offset.set(scroll_offset);
+
+            canvas.transform(Affine::translate(-scroll_offset));
+
+            cx.child(0).paint(canvas, offset);
+        });
+
+        // Todo: Draw scroll bar.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn caret_is_moved_into_viewport() {
+        let viewport = Rect::from_ltwh(0., 100., 200., 100.);
+        let delta = |direction, x, y, bottom| {
+            reveal_delta(direction, Rect::from_ltwh(x, y, 2., 20.), viewport, bottom)
+        };
+
+        let vertical = ScrollDirection::Vertical;
+        assert_eq!(delta(vertical, 0., 150., 200.), 0.);
+        assert_eq!(delta(vertical, 0., 250., 200.), -70.);
+        assert_eq!(delta(vertical, 0., 60., 200.), 40.);
+
+        // Caret is kept above the soft keyboard.
+        assert_eq!(delta(vertical, 0., 150., 160.), -10.);
+
+        let horizontal = ScrollDirection::Horizontal;
+        assert_eq!(delta(horizontal, 250., 150., 200.), -52.);
+        assert_eq!(delta(horizontal, -10., 150., 200.), 10.);
+    }
+}
@@ -0,0 +1,514 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+use frui::{
+    app::interactions::{Interaction, Interactions},
+    prelude::*,
+    render::*,
+};
+
+use super::caret_reveal_delta;
+use crate::*;
+
+/// Duration of the animation started when a drag is released while moving.
+const FLING_DURATION: Duration = Duration::from_millis(600);
+
+/// Releases slower than that (in points per second) don't start a fling.
+const MIN_FLING_VELOCITY: f64 = 50.;
+
+/// Scrollable box containing a single widget, which is laid out without
+/// limits along `scroll_direction`.
+///
+/// Scrolled by dragging and by mouse wheel. Pass a `controller` to read or
+/// change the scroll offset from elsewhere.
+///
+/// The whole child is built and laid out, which suits content only slightly
+/// larger than the screen, like dialogs or settings pages. Long lists should
+/// use a [`ListView`] instead.
+#[derive(ViewWidget, Builder)]
+pub struct SingleChildScrollView<W: Widget, P: Directional<Output = EdgeInsets>> {
+    pub child: W,
+    pub controller: Option<ScrollController>,
+    pub scroll_direction: ScrollDirection,
+    /// Whether the scroll offset is measured from the end of the child, so
+    /// that it is initially scrolled to its end (e.g. to the latest message
+    /// of a chat).
+    pub reverse: bool,
+    /// Space around the child, which is scrolled along with it.
+    pub padding: P,
+}
+
+impl SingleChildScrollView<(), EdgeInsets> {
+    pub fn builder() -> Self {
+        SingleChildScrollView {
+            child: (),
+            controller: None,
+            scroll_direction: ScrollDirection::Vertical,
+            reverse: false,
+            padding: EdgeInsets::ZERO,
+        }
+    }
+}
+
+impl<W: Widget, P: Directional<Output = EdgeInsets>> WidgetState for SingleChildScrollView<W, P> {
+    // Controller used if none was passed.
+    type State = ScrollController;
+
+    fn create_state(&self) -> Self::State {
+        ScrollController::new()
+    }
+}
+
+impl<W: Widget, P: Directional<Output = EdgeInsets>> ViewWidget for SingleChildScrollView<W, P> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => cx.state().clone(),
+        };
+
+        Scrollable {
+            child: Padding {
+                child: &self.child,
+                padding: &self.padding,
+            },
+            controller,
+            scroll_direction: self.scroll_direction,
+            reverse: self.reverse,
+        }
+    }
+}
+
+/// Turns drags and mouse wheel into scroll offset of `controller`, and shows
+/// `child` scrolled by that offset in a [`Viewport`].
+///
+/// Drags compete with other gestures in the [`GestureArena`], so e.g. buttons
+/// inside of it can still be tapped. Mouse wheel scrolls the innermost
+/// scrollable under the pointer which can still scroll in that direction.
+#[derive(ViewWidget, Builder)]
+pub struct Scrollable<W: Widget> {
+    pub child: W,
+    /// Has to outlive rebuilds of this widget (e.g. be kept in the state of an
+    /// ancestor), otherwise the offset is reset every time it is rebuilt.
+    pub controller: ScrollController,
+    pub scroll_direction: ScrollDirection,
+    /// See [`Viewport::reverse`].
+    pub reverse: bool,
+}
+
+impl Scrollable<()> {
+    pub fn builder() -> Self {
+        Scrollable {
+            child: (),
+            controller: ScrollController::new(),
+            scroll_direction: ScrollDirection::Vertical,
+            reverse: false,
+        }
+    }
+}
+
+pub struct ScrollableState {
+    /// Reports scroll depth once the offset of the controller changes.
+    listener: ControllerListener<ScrollController>,
+    depth: Rc<ScrollDepth>,
+}
+
+impl<W: Widget> WidgetState for Scrollable<W> {
+    type State = ScrollableState;
+
+    fn create_state(&self) -> Self::State {
+        ScrollableState {
+            listener: ControllerListener::default(),
+            depth: Rc::default(),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().listener.unlisten();
+    }
+}
+
+impl<W: Widget> ViewWidget for Scrollable<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let (controller, direction) = (&self.controller, self.scroll_direction);
+
+        // Offset grows against the scroll direction if reversed.
+        let sign = if self.reverse { -1. } else { 1. };
+
+        let state = cx.state();
+        *state.depth.label.borrow_mut() = InteractionLabel::of(cx).label;
+        state.depth.reverse.set(self.reverse);
+        state.listener.listen(controller, {
+            let (controller, depth) = (controller.clone(), state.depth.clone());
+            move || depth.update(&controller)
+        });
+        drop(state);
+
+        GestureDetector::builder()
+            .on_pan_update(move |details: DragUpdateDetails| {
+                controller.scroll_by(-sign * direction.along(details.delta));
+            })
+            .on_pan_end(move |details: DragEndDetails| {
+                let velocity = sign * direction.along(details.velocity);
+
+                if velocity.abs() >= MIN_FLING_VELOCITY {
+                    // Initial speed of the (cubic ease-out) animation matches
+                    // the speed of the pointer.
+                    let distance = velocity * FLING_DURATION.as_secs_f64() / 3.;
+                    controller.animate_to(controller.offset() - distance, FLING_DURATION);
+                }
+            })
+            .child(Viewport {
+                child: &self.child,
+                controller: self.controller.clone(),
+                scroll_direction: self.scroll_direction,
+                reverse: self.reverse,
+            })
+    }
+}
+
+impl<W: Widget> HitTest for Scrollable<W> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        // Ancestors are hit tested first, so this is the start of dispatching
+        // of a new event.
+        WHEEL_CONSUMED.with(|c| c.set(false));
+
+        if cx.layout_box().contains(point) {
+            cx.hit_test_children(point);
+            return true;
+        }
+
+        false
+    }
+
+    fn handle_event(&self, _: &mut HitTestCx<Self>, event: &PointerEvent) {
+        let e = match event {
+            PointerEvent::PointerScroll(e) => e,
+            _ => return,
+        };
+
+        if WHEEL_CONSUMED.with(|c| c.get()) {
+            return;
+        }
+
+        let delta = match self.scroll_direction {
+            // Most mice only have a vertical wheel.
+            ScrollDirection::Horizontal if e.0.wheel_delta.x == 0. => e.0.wheel_delta.y,
+            direction => direction.along(e.0.wheel_delta),
+        };
+
+        let sign = if self.reverse { -1. } else { 1. };
+
+        let before = self.controller.offset();
+        self.controller.scroll_by(sign * delta);
+
+        // Otherwise an enclosing scrollable gets scrolled instead.
+        if self.controller.offset() != before {
+            WHEEL_CONSUMED.with(|c| c.set(true));
+        }
+    }
+}
+
+/// Paints the part of `child` visible at the offset of `controller`, clipping
+/// the rest. Reports [`ScrollExtents`] to `controller` on every layout.
+///
+/// Scrolls the caret of a text field within `child` into view once it's
+/// requested to be revealed, see [`Caret::reveal`].
+///
+/// Doesn't react to any input, see [`Scrollable`].
+#[derive(RenderWidget, Builder)]
+pub struct Viewport<W: Widget> {
+    pub child: W,
+    pub controller: ScrollController,
+    pub scroll_direction: ScrollDirection,
+    /// Whether offset 0 shows the end of the child instead of its start, with
+    /// the offset growing towards its start. Children smaller than the
+    /// viewport are aligned to its end.
+    pub reverse: bool,
+}
+
+impl Viewport<()> {
+    pub fn builder() -> Self {
+        Viewport {
+            child: (),
+            controller: ScrollController::new(),
+            scroll_direction: ScrollDirection::Vertical,
+            reverse: false,
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for Viewport<W> {
+    /// Repaints this viewport once the offset of its controller changes.
+    type State = ControllerListener<ScrollController>;
+
+    fn create_state(&self) -> Self::State {
+        ControllerListener::default()
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().unlisten();
+    }
+}
+
+impl<W: Widget> RenderWidget for Viewport<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        let updater = cx.state_updater();
+        cx.state()
+            .listen(&self.controller, move || updater.update(|_| {}));
+
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let direction = self.scroll_direction;
+
+        let child_size = cx.child(0).layout(direction.child_constraints(constraints));
+
+        // Shrink-wrap the child along the scroll axis only if there are no
+        // limits along it.
+        let mut size = constraints.constrain(child_size);
+        match direction {
+            ScrollDirection::Horizontal if constraints.has_bounded_width() => {
+                size.width = constraints.max_width
+            }
+            ScrollDirection::Vertical if constraints.has_bounded_height() => {
+                size.height = constraints.max_height
+            }
+            _ => {}
+        }
+
+        let viewport_extent = direction.extent(size);
+
+        self.controller.set_extents(ScrollExtents {
+            min_scroll_extent: 0.,
+            max_scroll_extent: (direction.extent(child_size) - viewport_extent).max(0.),
+            viewport_extent,
+        });
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let direction = self.scroll_direction;
+        let viewport = Rect::from_origin_size(*offset, cx.size());
+        let child_size = cx.child(0).size();
+
+        let shift = |scroll_offset| {
+            child_shift(
+                self.reverse,
+                scroll_offset,
+                direction.extent(viewport.size()),
+                direction.extent(child_size),
+            )
+        };
+
+        let mut scroll_offset = self.controller.offset();
+
+        // Caret of a text field within the child is revealed by scrolling it
+        // into view before the child is painted.
+        let transform = canvas.current_transform();
+        let moved = direction.vec(shift(scroll_offset));
+        let content = Rect::from_origin_size(*offset, child_size) + Offset::new(moved.x, moved.y);
+
+        if let Some(delta) = caret_reveal_delta(
+            direction,
+            transform.transform_rect_bbox(viewport.into()).into(),
+            transform.transform_rect_bbox(content.into()).into(),
+        ) {
+            // Offset grows against the scroll direction if reversed.
+            let sign = if self.reverse { 1. } else { -1. };
+            let max = self.controller.extents().max_scroll_extent;
+            let target = (scroll_offset + sign * delta).clamp(0., max);
+
+            if target != scroll_offset {
+                self.controller.jump_to(target);
+                scroll_offset = target;
+            }
+        }
+
+        let shift = shift(scroll_offset);
+
+        cx.with_clip(canvas, viewport, |cx, canvas| {
+            canvas.transform(Affine::translate(direction.vec(shift)));
+
+            cx.child(0).paint(canvas, offset);
+        });
+    }
+}
+
+/// Distance by which the child of a viewport is moved along the scroll axis
+/// when painted at `offset`.
+fn child_shift(reverse: bool, offset: f64, viewport_extent: f64, child_extent: f64) -> f64 {
+    match reverse {
+        true => viewport_extent - child_extent + offset,
+        false => -offset,
+    }
+}
+
+/// Deepest scroll position reported to [`Interactions`] observers.
+#[derive(Default)]
+struct ScrollDepth {
+    /// One of [`SCROLL_DEPTHS`], or 0 if none was reached yet.
+    reported: Cell<f64>,
+    label: RefCell<Option<String>>,
+    /// See [`Viewport::reverse`].
+    reverse: Cell<bool>,
+}
+
+/// Depths reported by [`Interaction::ScrollDepth`].
+const SCROLL_DEPTHS: [f64; 4] = [0.25, 0.5, 0.75, 1.];
+
+impl ScrollDepth {
+    fn update(&self, controller: &ScrollController) {
+        if !Interactions::is_observed() {
+            return;
+        }
+
+        let fraction = match depth_fraction(controller, self.reverse.get()) {
+            Some(fraction) => fraction,
+            None => return,
+        };
+
+        if let Some(depth) = next_depth(fraction, self.reported.get()) {
+            self.reported.set(depth);
+
+            Interactions::report(Interaction::ScrollDepth {
+                label: self.label.borrow().clone(),
+                depth,
+            });
+        }
+    }
+}
+
+/// Position of the viewport within the scroll range, from 0 at the start of
+/// the child to 1 at its end. `None` if the child can't be scrolled.
+fn depth_fraction(controller: &ScrollController, reverse: bool) -> Option<f64> {
+    let extents = controller.extents();
+    let range = extents.max_scroll_extent - extents.min_scroll_extent;
+
+    if range <= 0. {
+        return None;
+    }
+
+    let fraction = (controller.offset() - extents.min_scroll_extent) / range;
+
+    // Offset of reversed viewports grows towards the start of the child.
+    Some(match reverse {
+        true => 1. - fraction,
+        false => fraction,
+    })
+}
+
+/// Deepest of [`SCROLL_DEPTHS`] reached at `fraction` of the scroll range, if
+/// it's deeper than `reported`.
+fn next_depth(fraction: f64, reported: f64) -> Option<f64> {
+    SCROLL_DEPTHS
+        .into_iter()
+        .rev()
+        .find(|depth| fraction >= *depth)
+        .filter(|depth| *depth > reported)
+}
+
+thread_local! {
+    /// Whether the mouse wheel event being dispatched was already used by a
+    /// nested scrollable.
+    static WHEEL_CONSUMED: Cell<bool> = Cell::new(false);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tall box recording the scroll offset of `controller` once painted.
+    #[cfg(not(feature = "miri"))]
+    #[derive(RenderWidget)]
+    struct Probe {
+        controller: ScrollController,
+        painted_at: std::sync::Arc<std::sync::Mutex<Option<f64>>>,
+    }
+
+    #[cfg(not(feature = "miri"))]
+    impl RenderWidget for Probe {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![] as Vec<()>
+        }
+
+        fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+            constraints.constrain(Size::new(100., 1000.))
+        }
+
+        fn paint(&self, _: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {
+            *self.painted_at.lock().unwrap() = Some(self.controller.offset());
+        }
+    }
+
+    #[cfg(not(feature = "miri"))]
+    #[test]
+    fn caret_is_scrolled_into_view() {
+        let painted_at = std::sync::Arc::new(std::sync::Mutex::new(None));
+
+        let root = {
+            let painted_at = painted_at.clone();
+
+            move || {
+                // Caret of a focused field 500 points into the child.
+                Caret::report(Rect::from_ltwh(10., 500., 2., 20.));
+                Caret::reveal();
+
+                let controller = ScrollController::new();
+
+                Viewport::builder()
+                    .controller(controller.clone())
+                    .child(Probe {
+                        controller,
+                        painted_at,
+                    })
+            }
+        };
+
+        frui::app::offscreen::render(Size::new(100., 100.), 1., root)
+            .join()
+            .unwrap()
+            .unwrap();
+
+        // Bottom of the caret is aligned with the bottom of the viewport.
+        assert_eq!(*painted_at.lock().unwrap(), Some(420.));
+    }
+
+    #[test]
+    fn scroll_depths_are_reported_once() {
+        assert_eq!(next_depth(0.1, 0.), None);
+        assert_eq!(next_depth(0.6, 0.), Some(0.5));
+        assert_eq!(next_depth(0.7, 0.5), None);
+        assert_eq!(next_depth(1., 0.5), Some(1.));
+    }
+
+    #[test]
+    fn reversed_depth_is_measured_from_the_start() {
+        let controller = ScrollController::new();
+        controller.set_extents(ScrollExtents {
+            min_scroll_extent: 0.,
+            max_scroll_extent: 400.,
+            viewport_extent: 100.,
+        });
+        controller.jump_to(100.);
+
+        assert_eq!(depth_fraction(&controller, false), Some(0.25));
+        assert_eq!(depth_fraction(&controller, true), Some(0.75));
+    }
+
+    #[test]
+    fn reversed_child_is_aligned_to_the_end() {
+        // Child 500 points tall in a viewport 100 points tall.
+        assert_eq!(child_shift(false, 0., 100., 500.), 0.);
+        assert_eq!(child_shift(false, 50., 100., 500.), -50.);
+        assert_eq!(child_shift(true, 0., 100., 500.), -400.);
+        assert_eq!(child_shift(true, 50., 100., 500.), -350.);
+
+        // Smaller child stays at the end.
+        assert_eq!(child_shift(true, 0., 100., 40.), 60.);
+    }
+}
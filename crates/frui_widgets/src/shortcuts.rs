@@ -0,0 +1,218 @@
+//! Binding keys to intents, which are handled by actions.
+//!
+//! [`Shortcuts`] map key combinations pressed while focus is within them (or
+//! while nothing is focused) to intents, values describing what the user
+//! wants to do (e.g. to save the document). An intent is handled by the closest [`Actions`] ancestor of the
+//! focused widget with an action for it, so the same key can do different
+//! things depending on which part of the application is focused. While
+//! nothing is focused, only actions above the [`Shortcuts`] are considered.
+//!
+//! ```ignore
+//! struct Save;
+//!
+//! impl Intent for Save {}
+//!
+//! Actions::builder()
+//!     .action(|_: &Save| save_document())
+//!     .child(Shortcuts::builder().shortcut("Ctrl+S", Save).child(editor))
+//! ```
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    rc::Rc,
+};
+
+use druid_shell::{keyboard_types::KeyState, KeyEvent};
+use frui::{
+    app::{
+        commands::Shortcut,
+        focus::{FocusManager, FocusNode},
+    },
+    prelude::*,
+};
+
+use crate::*;
+
+/// Value describing what the user wants to do, bound to keys by
+/// [`Shortcuts`] and handled by [`Actions`].
+pub trait Intent: 'static {}
+
+#[derive(ViewWidget, Builder)]
+pub struct Shortcuts<W: Widget> {
+    /// Key combinations and intents they are bound to, see
+    /// [`shortcut`](Self::shortcut).
+    pub shortcuts: Vec<(Shortcut, Rc<dyn Any>)>,
+    pub child: W,
+}
+
+impl Shortcuts<()> {
+    pub fn builder() -> Self {
+        Shortcuts {
+            shortcuts: Vec::new(),
+            child: (),
+        }
+    }
+}
+
+impl<W: Widget> Shortcuts<W> {
+    /// Binds `shortcut` (e.g. `Ctrl+S`, see [`Shortcut::parse`]) to `intent`.
+    /// Panics if `shortcut` can't be parsed.
+    #[track_caller]
+    pub fn shortcut(mut self, shortcut: &str, intent: impl Intent) -> Self {
+        let shortcut = Shortcut::parse(shortcut).expect("invalid shortcut");
+        self.shortcuts.push((shortcut, Rc::new(intent)));
+        self
+    }
+}
+
+impl<W: Widget> WidgetState for Shortcuts<W> {
+    type State = FocusNode;
+
+    fn create_state(&self) -> Self::State {
+        FocusNode::new()
+    }
+}
+
+impl<W: Widget> ViewWidget for Shortcuts<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let node = cx.state().clone();
+
+        let on_key = {
+            let node = node.clone();
+
+            move |event: &KeyEvent| {
+                if event.state != KeyState::Down {
+                    return false;
+                }
+
+                let context = FocusManager::focused().unwrap_or_else(|| node.clone());
+
+                // Shortcuts without an action for their intent are left to
+                // ancestors.
+                self.shortcuts
+                    .iter()
+                    .filter(|(shortcut, _)| shortcut.matches(event))
+                    .any(|(_, intent)| invoke(&context, &**intent))
+            }
+        };
+
+        let mut focus = Focus::builder()
+            .can_request_focus(false)
+            .skip_traversal(true)
+            .on_key(on_key)
+            .child(&self.child);
+        focus.focus_node = Some(node);
+
+        focus
+    }
+}
+
+/// Handles intents of descendants bound to keys by [`Shortcuts`] or
+/// invoked with [`Actions::invoke`].
+#[derive(ViewWidget, Builder)]
+pub struct Actions<W: Widget> {
+    /// Actions and types of intents they handle, see
+    /// [`action`](Self::action).
+    pub actions: Vec<(TypeId, Rc<dyn Fn(&dyn Any)>)>,
+    pub child: W,
+}
+
+impl Actions<()> {
+    pub fn builder() -> Self {
+        Actions {
+            actions: Vec::new(),
+            child: (),
+        }
+    }
+
+    /// Invokes the action for `intent` of the closest [`Actions`] ancestor of
+    /// the focused widget, e.g. from a menu. Returns whether there was one.
+    pub fn invoke<I: Intent>(intent: &I) -> bool {
+        match FocusManager::focused() {
+            Some(focused) => invoke(&focused, intent),
+            None => false,
+        }
+    }
+}
+
+impl<W: Widget> Actions<W> {
+    /// Handles intents of type `I`, replacing actions of ancestors for them.
+    pub fn action<I: Intent>(mut self, action: impl Fn(&I) + 'static) -> Self {
+        let action = move |intent: &dyn Any| {
+            if let Some(intent) = intent.downcast_ref::<I>() {
+                action(intent);
+            }
+        };
+
+        self.actions.push((TypeId::of::<I>(), Rc::new(action)));
+        self
+    }
+}
+
+impl<W: Widget> WidgetState for Actions<W> {
+    type State = FocusNode;
+
+    /// Node which isn't focusable, only used to find the widget among
+    /// ancestors of the focused one.
+    fn create_state(&self) -> Self::State {
+        let node = FocusNode::new();
+        node.set_can_request_focus(false);
+        node.set_skip_traversal(true);
+        node
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        let node = cx.state().clone();
+        ACTIONS.with(|a| a.borrow_mut().push((node, self.actions.clone())));
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        let node = cx.state();
+        ACTIONS.with(|a| a.borrow_mut().retain(|(n, _)| !n.ptr_eq(&node)));
+    }
+}
+
+impl<W: Widget> ViewWidget for Actions<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        FocusAttach {
+            node: cx.state().clone(),
+            child: &self.child,
+        }
+    }
+}
+
+thread_local! {
+    /// Actions of mounted [`Actions`] widgets, by their nodes.
+    static ACTIONS: RefCell<Vec<(FocusNode, Vec<(TypeId, Rc<dyn Fn(&dyn Any)>)>)>> =
+        RefCell::new(Vec::new());
+}
+
+/// Invokes the action for `intent` of the closest [`Actions`] widget among
+/// `context` and its ancestors.
+fn invoke(context: &FocusNode, intent: &dyn Any) -> bool {
+    let type_id = Any::type_id(intent);
+
+    let action = ACTIONS.with(|a| {
+        let actions = a.borrow();
+
+        context.chain().iter().find_map(|node| {
+            actions
+                .iter()
+                .filter(|(n, _)| n.ptr_eq(node))
+                .flat_map(|(_, actions)| actions)
+                .find(|(id, _)| *id == type_id)
+                .map(|(_, action)| action.clone())
+        })
+    });
+
+    // Called outside of the borrow, since actions may mount or unmount
+    // widgets.
+    match action {
+        Some(action) => {
+            action(intent);
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,463 @@
+//! [`Slider`] for selecting a value from a range, either continuous or split
+//! into a number of divisions.
+
+use std::{cell::Cell, rc::Rc};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    kurbo::{Affine, Circle, RoundedRect},
+    piet::RenderContext,
+    Cursor, KeyEvent,
+};
+use frui::{prelude::*, render::*};
+
+use crate::*;
+
+/// Colors of a slider, resolved from the [`Theme`] unless set on the
+/// [`Slider`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliderColors {
+    /// Part of the track between the minimum and the thumb.
+    pub active_track: Color,
+    pub inactive_track: Color,
+    pub thumb: Color,
+    /// Hover, focus and drag highlight around the thumb.
+    pub overlay: Color,
+}
+
+/// Interaction states of a slider, in which it's painted differently.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SliderStates {
+    pub hovered: bool,
+    pub focused: bool,
+    pub dragged: bool,
+    pub disabled: bool,
+}
+
+/// Paints the track and the thumb of a [`Slider`]. Implement it to change the
+/// appearance of a slider without reimplementing its interaction.
+///
+/// ```ignore
+/// struct SquareThumb;
+///
+/// impl SliderPainter for SquareThumb {
+///     fn paint_thumb(&self, canvas: &mut Canvas, center: Point, colors: &SliderColors, _: SliderStates) {
+///         canvas.fill(kurbo::Rect::from_center_size(center, (16., 16.)), &colors.thumb);
+///     }
+/// }
+///
+/// Slider::builder().painter(SquareThumb)
+/// ```
+pub trait SliderPainter {
+    /// Paints the track spanning `track`, of which the part left of `thumb_x`
+    /// is active. `ticks` are positions of divisions of a discrete slider.
+    fn paint_track(
+        &self,
+        canvas: &mut Canvas,
+        track: Rect,
+        thumb_x: f64,
+        ticks: &[f64],
+        colors: &SliderColors,
+    ) {
+        let radius = track.height() / 2.;
+        let rounded = |x0: f64, x1: f64| RoundedRect::new(x0, track.top, x1, track.bottom, radius);
+
+        canvas.fill(rounded(track.left, track.right), &colors.inactive_track);
+        canvas.fill(rounded(track.left, thumb_x), &colors.active_track);
+
+        for x in ticks {
+            let color = match *x <= thumb_x {
+                true => &colors.inactive_track,
+                false => &colors.active_track,
+            };
+
+            canvas.fill(Circle::new((*x, track.center().y), radius / 2.), color);
+        }
+    }
+
+    fn paint_thumb(
+        &self,
+        canvas: &mut Canvas,
+        center: Point,
+        colors: &SliderColors,
+        states: SliderStates,
+    ) {
+        let opacity = match states {
+            SliderStates { disabled: true, .. } => 0.,
+            SliderStates { dragged: true, .. } | SliderStates { focused: true, .. } => 0.12,
+            SliderStates { hovered: true, .. } => 0.08,
+            _ => 0.,
+        };
+
+        canvas.fill(
+            Circle::new(center, 20.),
+            &colors.overlay.with_alpha(opacity),
+        );
+        canvas.fill(Circle::new(center, THUMB_RADIUS), &colors.thumb);
+    }
+}
+
+/// Painter of the default appearance of a [`Slider`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultSliderPainter;
+
+impl SliderPainter for DefaultSliderPainter {}
+
+impl<T: SliderPainter> SliderPainter for &T {
+    fn paint_track(
+        &self,
+        canvas: &mut Canvas,
+        track: Rect,
+        thumb_x: f64,
+        ticks: &[f64],
+        colors: &SliderColors,
+    ) {
+        T::paint_track(self, canvas, track, thumb_x, ticks, colors)
+    }
+
+    fn paint_thumb(
+        &self,
+        canvas: &mut Canvas,
+        center: Point,
+        colors: &SliderColors,
+        states: SliderStates,
+    ) {
+        T::paint_thumb(self, canvas, center, colors, states)
+    }
+}
+
+const THUMB_RADIUS: f64 = 10.;
+
+/// Space on both sides of the track, in which the thumb and its highlight
+/// are painted.
+const TRACK_INSET: f64 = 20.;
+
+/// Selects a value between `min` and `max` by dragging or tapping its track,
+/// or with arrow keys while focused.
+///
+/// In right-to-left [`Directionality`] the minimum is on the right, so the
+/// track is mirrored and the left arrow key increases the value.
+///
+/// If `divisions` is set, the value snaps to one of that many equal steps
+/// of the range. Otherwise arrow keys change it by a twentieth of the range.
+///
+/// ```ignore
+/// Slider::builder()
+///     .value(state.volume)
+///     .divisions(10)
+///     .on_changed(move |volume| cx.state_mut().volume = volume)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Slider<F: Fn(f64), P: SliderPainter> {
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    pub divisions: Option<usize>,
+    /// Called with the new value while the slider is dragged, tapped or
+    /// changed with keys. If `None`, the slider is disabled.
+    pub on_changed: Option<F>,
+    /// Defaults to the primary color.
+    pub active_color: Option<Color>,
+    /// Defaults to the active color with reduced opacity.
+    pub inactive_color: Option<Color>,
+    pub painter: P,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl Slider<fn(f64), DefaultSliderPainter> {
+    pub fn builder() -> Self {
+        Slider {
+            value: 0.,
+            min: 0.,
+            max: 1.,
+            divisions: None,
+            on_changed: None,
+            active_color: None,
+            inactive_color: None,
+            painter: DefaultSliderPainter,
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+pub struct SliderState {
+    hovered: bool,
+    focused: bool,
+    dragged: bool,
+    /// Left edge and width of the track, updated by layout.
+    track: Rc<Cell<(f64, f64)>>,
+}
+
+impl<F: Fn(f64), P: SliderPainter> WidgetState for Slider<F, P> {
+    type State = SliderState;
+
+    fn create_state(&self) -> Self::State {
+        SliderState {
+            hovered: false,
+            focused: false,
+            dragged: false,
+            track: Rc::default(),
+        }
+    }
+}
+
+impl<F: Fn(f64), P: SliderPainter> Slider<F, P> {
+    /// Position of the value in the range, from 0 to 1.
+    fn fraction(&self) -> f64 {
+        match self.max > self.min {
+            true => ((self.value - self.min) / (self.max - self.min)).clamp(0., 1.),
+            false => 0.,
+        }
+    }
+
+    /// Value at `fraction` of the range, snapped to the closest division.
+    fn value_at(&self, fraction: f64) -> f64 {
+        let fraction = match self.divisions {
+            Some(n) if n > 0 => (fraction * n as f64).round() / n as f64,
+            _ => fraction,
+        };
+
+        self.min + fraction.clamp(0., 1.) * (self.max - self.min)
+    }
+
+    fn change(&self, value: f64) {
+        if let Some(on_changed) = &self.on_changed {
+            if value != self.value {
+                on_changed(value);
+            }
+        }
+    }
+
+    /// Changes the value to the one under local position `x`.
+    fn change_to_x(&self, cx: BuildCx<Self>, x: f64, rtl: bool) {
+        let (left, width) = cx.state().track.get();
+
+        if width > 0. {
+            let fraction = (x - left) / width;
+
+            self.change(self.value_at(match rtl {
+                true => 1. - fraction,
+                false => fraction,
+            }));
+        }
+    }
+
+    fn handle_key(&self, event: &KeyEvent, rtl: bool) -> bool {
+        let step = match self.divisions {
+            Some(n) if n > 0 => 1. / n as f64,
+            _ => 0.05,
+        };
+
+        // Horizontal arrows move the thumb in their direction.
+        let (decrease, increase) = match rtl {
+            true => (Key::ArrowRight, Key::ArrowLeft),
+            false => (Key::ArrowLeft, Key::ArrowRight),
+        };
+
+        let fraction = match &event.key {
+            Key::ArrowDown => self.fraction() - step,
+            Key::ArrowUp => self.fraction() + step,
+            key if *key == decrease => self.fraction() - step,
+            key if *key == increase => self.fraction() + step,
+            Key::Home => 0.,
+            Key::End => 1.,
+            _ => return false,
+        };
+
+        if event.state == KeyState::Down {
+            self.change(self.value_at(fraction));
+        }
+
+        true
+    }
+}
+
+impl<F: Fn(f64), P: SliderPainter> ViewWidget for Slider<F, P> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let scheme = Theme::of(cx).color_scheme;
+        let disabled = self.on_changed.is_none();
+        let rtl = cx.depend_on::<TextDirection>().map(|d| *d) == Some(TextDirection::Rtl);
+
+        let active = match disabled {
+            true => scheme.on_surface.with_alpha(0.38),
+            false => self
+                .active_color
+                .clone()
+                .unwrap_or_else(|| scheme.primary.clone()),
+        };
+
+        let inactive = match (disabled, &self.inactive_color) {
+            (false, Some(color)) => color.clone(),
+            _ => active.clone().with_alpha(0.24),
+        };
+
+        let state = cx.state();
+
+        let surface = SliderSurface {
+            fraction: self.fraction(),
+            divisions: self.divisions.unwrap_or(0),
+            colors: SliderColors {
+                active_track: active.clone(),
+                inactive_track: inactive,
+                thumb: active.clone(),
+                overlay: active,
+            },
+            states: SliderStates {
+                hovered: state.hovered,
+                focused: state.focused,
+                dragged: state.dragged,
+                disabled,
+            },
+            painter: &self.painter,
+            track: state.track.clone(),
+            mirrored: rtl,
+        };
+
+        drop(state);
+
+        let mut region = PointerRegion::builder()
+            .on_enter(move |_| cx.state_mut().hovered = true)
+            .on_exit(move |_| cx.state_mut().hovered = false);
+
+        if !disabled {
+            region = region.cursor(Cursor::Pointer);
+        }
+
+        let updater = cx.state_updater();
+
+        let mut focus = Focus::builder()
+            .autofocus(self.autofocus)
+            .can_request_focus(!disabled)
+            .on_key(move |event: &KeyEvent| !disabled && self.handle_key(event, rtl))
+            .on_focus_change(move |focused| {
+                updater.update(move |state: &mut SliderState| state.focused = focused)
+            });
+        focus.focus_node = self.focus_node.clone();
+
+        InteractionLabel::builder().widget("Slider").child(
+            focus.child(
+                region.child(
+                    GestureDetector::builder()
+                        .on_tap_up(move |pos: Point| self.change_to_x(cx, pos.x, rtl))
+                        .on_pan_start(move |details: DragStartDetails| {
+                            cx.state_mut().dragged = true;
+                            self.change_to_x(cx, details.local_position.x, rtl);
+                        })
+                        .on_pan_update(move |details: DragUpdateDetails| {
+                            self.change_to_x(cx, details.local_position.x, rtl);
+                        })
+                        .on_pan_end(move |_| cx.state_mut().dragged = false)
+                        .child(surface),
+                ),
+            ),
+        )
+    }
+}
+
+#[derive(RenderWidget)]
+struct SliderSurface<P: SliderPainter> {
+    /// Position of the thumb, from 0 to 1.
+    fraction: f64,
+    /// Number of divisions, or 0 for a continuous slider.
+    divisions: usize,
+    colors: SliderColors,
+    states: SliderStates,
+    painter: P,
+    track: Rc<Cell<(f64, f64)>>,
+    /// Whether the track runs from right to left.
+    mirrored: bool,
+}
+
+impl<P: SliderPainter> RenderWidget for SliderSurface<P> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let width = match constraints.has_bounded_width() {
+            true => constraints.max_width,
+            false => 200.,
+        };
+
+        let size = constraints.constrain(Size::new(width, 40.));
+        self.track
+            .set((TRACK_INSET, (size.width - 2. * TRACK_INSET).max(0.)));
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let (left, width) = self.track.get();
+        let center_y = offset.y + size.height / 2.;
+
+        let track = Rect::from_ltrb(
+            offset.x + left,
+            center_y - 2.,
+            offset.x + left + width,
+            center_y + 2.,
+        );
+        let thumb_x = track.left + width * self.fraction;
+
+        let ticks = match self.divisions {
+            0 => vec![],
+            n => (0..=n)
+                .map(|i| track.left + width * i as f64 / n as f64)
+                .collect(),
+        };
+
+        let mirror = match self.mirrored {
+            true => MatchTextDirection::mirror_at(offset, size),
+            false => Affine::IDENTITY,
+        };
+
+        let r = canvas.with_save(|cv| {
+            cv.transform(mirror);
+
+            self.painter
+                .paint_track(cv, track, thumb_x, &ticks, &self.colors);
+            self.painter
+                .paint_thumb(cv, Point::new(thumb_x, center_y), &self.colors, self.states);
+
+            Ok(())
+        });
+
+        if let Err(e) = r {
+            log::error!("painting slider failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values_snap_to_divisions() {
+        let slider = Slider::builder().min(10.).max(20.).divisions(4);
+
+        assert_eq!(slider.value_at(0.3), 12.5);
+        assert_eq!(slider.value_at(1.2), 20.);
+        assert_eq!(slider.divisions(0).value_at(0.5), 15.);
+    }
+
+    #[test]
+    fn arrow_keys_follow_text_direction() {
+        let changed = Cell::new(None);
+        let slider = Slider::builder()
+            .value(0.5)
+            .divisions(10)
+            .on_changed(|value| changed.set(Some(value)));
+
+        let left = KeyEvent::for_test(Default::default(), Key::ArrowLeft);
+
+        slider.handle_key(&left, false);
+        assert_eq!(changed.get(), Some(0.4));
+
+        slider.handle_key(&left, true);
+        assert_eq!(changed.get(), Some(0.6));
+    }
+}
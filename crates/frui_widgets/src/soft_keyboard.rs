@@ -0,0 +1,172 @@
+//! Keeping the focused text field visible above the soft (on-screen) keyboard.
+//!
+//! Bottom view inset reported by [`MediaQuery`] is animated by
+//! [`SoftKeyboardPadding`], and while it changes, the enclosing [`Viewport`]
+//! is asked to scroll the caret of the focused text field into view.
+
+use std::{
+    cell::{Cell, OnceCell},
+    time::Duration,
+};
+
+use frui::{app::metrics::WindowMetrics, prelude::*, render::*};
+
+use crate::*;
+
+/// Caret of the focused text field.
+pub struct Caret;
+
+impl Caret {
+    /// Reports bounds of the caret of the focused text field, in window
+    /// coordinates. Text fields should call this every time they paint their
+    /// caret.
+    pub fn report(rect: Rect) {
+        CARET.with(|c| c.focused.set(Some(rect)));
+    }
+
+    /// Should be called when the focused text field loses focus.
+    pub fn clear() {
+        CARET.with(|c| {
+            c.focused.set(None);
+            c.reveal.set(None);
+        });
+    }
+
+    /// Bounds of the caret of the focused text field from the last paint.
+    pub fn focused() -> Option<Rect> {
+        CARET.with(|c| c.focused.get())
+    }
+
+    /// Requests the [`Viewport`] (or [`Scroll`]) containing the focused caret
+    /// to scroll it into view, above the soft keyboard. Request is fulfilled
+    /// on the next paint.
+    ///
+    /// Text fields request it every time their caret moves.
+    pub fn reveal() {
+        CARET.with(|c| c.reveal.set(c.focused.get()));
+    }
+
+    pub(crate) fn reveal_request() -> Option<Rect> {
+        CARET.with(|c| c.reveal.get())
+    }
+
+    pub(crate) fn take_reveal_request() {
+        CARET.with(|c| c.reveal.set(None));
+    }
+
+    /// Top of the soft keyboard in window coordinates.
+    pub(crate) fn visible_bottom() -> f64 {
+        let metrics = WindowMetrics::current();
+
+        match metrics.size.height {
+            height if height > 0. => height - metrics.view_insets.y1,
+            _ => f64::INFINITY,
+        }
+    }
+}
+
+#[derive(Default)]
+struct CaretState {
+    focused: Cell<Option<Rect>>,
+    reveal: Cell<Option<Rect>>,
+}
+
+thread_local! {
+    static CARET: CaretState = Default::default();
+}
+
+/// Pads `child` from the bottom by the height of the soft keyboard, animating
+/// the padding as the keyboard appears or disappears. The focused caret is
+/// kept in view (see [`Caret::reveal`]) for the duration of the animation.
+#[derive(ViewWidget, Builder)]
+pub struct SoftKeyboardPadding<W: Widget> {
+    pub child: W,
+    pub duration: Duration,
+}
+
+impl SoftKeyboardPadding<()> {
+    pub fn builder() -> Self {
+        SoftKeyboardPadding {
+            child: (),
+            duration: Duration::from_millis(250),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SoftKeyboardPaddingState {
+    inset: Cell<f64>,
+    /// Starting and target inset of the running animation.
+    animation: Cell<Option<(f64, f64)>>,
+    ticker: OnceCell<Ticker>,
+}
+
+impl SoftKeyboardPaddingState {
+    fn target(&self) -> f64 {
+        match self.animation.get() {
+            Some((_, to)) => to,
+            None => self.inset.get(),
+        }
+    }
+
+    fn tick(&self, elapsed: Duration, duration: Duration) {
+        let (from, to) = match self.animation.get() {
+            Some(animation) => animation,
+            None => return,
+        };
+
+        let t = match duration.is_zero() {
+            true => 1.,
+            false => (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.),
+        };
+
+        // Ease out, same as the platform keyboards.
+        let eased = 1. - (1. - t).powi(3);
+        self.inset.set(from + (to - from) * eased);
+
+        Caret::reveal();
+
+        if t >= 1. {
+            self.animation.set(None);
+
+            if let Some(ticker) = self.ticker.get() {
+                ticker.stop();
+            }
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for SoftKeyboardPadding<W> {
+    type State = SoftKeyboardPaddingState;
+
+    fn create_state(&self) -> Self::State {
+        SoftKeyboardPaddingState::default()
+    }
+}
+
+impl<W: Widget> ViewWidget for SoftKeyboardPadding<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let target = MediaQuery::of(cx).view_insets.bottom;
+        let state = cx.state();
+
+        if target != state.target() {
+            state.animation.set(Some((state.inset.get(), target)));
+
+            let ticker = state.ticker.get_or_init(|| {
+                let updater = cx.state_updater();
+                let duration = self.duration;
+
+                Ticker::new(move |elapsed| {
+                    updater.update(|state: &mut Self::State| state.tick(elapsed, duration))
+                })
+            });
+
+            // Retargeting restarts the animation from the current inset.
+            ticker.start();
+        }
+
+        Padding::builder()
+            .padding(EdgeInsets::from_ltrb(0., 0., 0., state.inset.get()))
+            .child(&self.child)
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use frui::render::*;
+
+/// Items spanning more cells than that aren't bucketed and are always
+/// returned from queries.
+const MAX_CELLS_PER_ITEM: i64 = 256;
+
+/// Bucketed grid of rectangles, used to find children under the pointer
+/// without testing every child of widgets with many children.
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    large: Vec<usize>,
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0., "cell_size must be positive");
+
+        SpatialIndex {
+            cell_size,
+            cells: HashMap::new(),
+            large: Vec::new(),
+        }
+    }
+
+    /// Index of `rects`, each identified by its position in the iterator.
+    pub fn from_rects(cell_size: f64, rects: impl IntoIterator<Item = Rect>) -> Self {
+        let mut index = SpatialIndex::new(cell_size);
+
+        for (n, rect) in rects.into_iter().enumerate() {
+            index.insert(n, rect);
+        }
+
+        index
+    }
+
+    fn cell(&self, x: f64, y: f64) -> (i64, i64) {
+        (
+            (x / self.cell_size).floor() as i64,
+            (y / self.cell_size).floor() as i64,
+        )
+    }
+
+    pub fn insert(&mut self, item: usize, rect: Rect) {
+        let (x0, y0) = self.cell(rect.left, rect.top);
+        let (x1, y1) = self.cell(rect.right, rect.bottom);
+
+        // Cells of huge or infinite rects saturate at the bounds of `i64`.
+        let columns = x1.saturating_sub(x0).saturating_add(1);
+        let rows = y1.saturating_sub(y0).saturating_add(1);
+
+        if columns.saturating_mul(rows) > MAX_CELLS_PER_ITEM {
+            self.large.push(item);
+            return;
+        }
+
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(item);
+            }
+        }
+    }
+
+    /// Items which rectangles may contain `point`, in ascending order.
+    pub fn query(&self, point: Point) -> Vec<usize> {
+        let cell = self.cell(point.x, point.y);
+        let mut items = self.large.clone();
+
+        if let Some(cell_items) = self.cells.get(&cell) {
+            items.extend_from_slice(cell_items);
+        }
+
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.large.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn query_spatial_index() {
+        let index = SpatialIndex::from_rects(
+            50.,
+            [
+                Rect::from_ltwh(0., 0., 40., 40.),
+                Rect::from_ltwh(30., 30., 40., 40.),
+                Rect::from_ltwh(-100., -100., 10000., 10000.),
+                Rect::from_ltwh(500., 500., 10., 10.),
+            ],
+        );
+
+        assert_eq!(index.query(Point::new(10., 10.)), [0, 1, 2]);
+        assert_eq!(index.query(Point::new(60., 60.)), [1, 2]);
+        assert_eq!(index.query(Point::new(505., 505.)), [2, 3]);
+        assert_eq!(index.query(Point::new(-50., 300.)), [2]);
+    }
+
+    #[test]
+    fn unbounded_rects_are_large() {
+        let index = SpatialIndex::from_rects(
+            1.,
+            [
+                Rect::from_ltrb(f64::NEG_INFINITY, 0., f64::INFINITY, 1.),
+                Rect::from_ltrb(-1e300, -1e300, 1e300, 1e300),
+                Rect::from_ltwh(0., 0., 1., 1.),
+            ],
+        );
+
+        assert_eq!(index.query(Point::new(0.5, 0.5)), [0, 1, 2]);
+        assert_eq!(index.query(Point::new(1e200, -1e200)), [0, 1]);
+    }
+}
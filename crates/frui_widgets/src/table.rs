@@ -0,0 +1,431 @@
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::*;
+
+/// How the width of a column of a [`Table`] is decided.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableColumnWidth {
+    Fixed(f64),
+    /// Share of the width left after sizing other columns, proportional to
+    /// the factor. Without limits on the width of the table, the column is as
+    /// wide as its widest cell.
+    Flex(f64),
+    /// As wide as the widest cell of the column, based on the maximum
+    /// intrinsic widths of cells.
+    Intrinsic,
+    /// Fraction of the maximum width of the table.
+    Fraction(f64),
+}
+
+impl Default for TableColumnWidth {
+    fn default() -> Self {
+        TableColumnWidth::Flex(1.)
+    }
+}
+
+/// Where a cell of a [`Table`] is placed vertically in its row, if it's
+/// shorter than the row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableCellVerticalAlignment {
+    Top,
+    Middle,
+    Bottom,
+    /// Stretched to the height of the row. Cells filling their rows don't
+    /// affect their height.
+    Fill,
+}
+
+impl Default for TableCellVerticalAlignment {
+    fn default() -> Self {
+        TableCellVerticalAlignment::Top
+    }
+}
+
+/// Row of a [`Table`], with a cell for every column. Rows with fewer cells
+/// leave the last columns empty.
+pub struct TableRow<WL: WidgetList> {
+    pub children: WL,
+    /// Painted behind the cells of the row, e.g. to highlight every other
+    /// row. Rows of a table can have different kinds of decorations.
+    pub decoration: Option<Box<dyn Decoration>>,
+}
+
+impl<WL: WidgetList> TableRow<WL> {
+    pub fn new(children: WL) -> Self {
+        TableRow {
+            children,
+            decoration: None,
+        }
+    }
+
+    pub fn decoration(mut self, decoration: impl Decoration + 'static) -> Self {
+        self.decoration = Some(Box::new(decoration));
+        self
+    }
+}
+
+/// Overrides the [`default_vertical_alignment`](Table::default_vertical_alignment)
+/// of the [`Table`] for its cell `child`.
+#[derive(RenderWidget, Builder)]
+pub struct TableCell<W: Widget> {
+    pub vertical_alignment: TableCellVerticalAlignment,
+    pub child: W,
+}
+
+impl TableCell<()> {
+    pub fn builder() -> Self {
+        TableCell {
+            vertical_alignment: TableCellVerticalAlignment::Top,
+            child: (),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableCellData {
+    vertical_alignment: TableCellVerticalAlignment,
+}
+
+impl<W: Widget> ParentData for TableCell<W> {
+    type Data = TableCellData;
+
+    fn create_data(&self) -> Self::Data {
+        TableCellData {
+            vertical_alignment: self.vertical_alignment,
+        }
+    }
+}
+
+impl<W: Widget> RenderWidget for TableCell<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(cx.child(0).layout(constraints))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+/// Lays out cells of its `rows` in columns, e.g. of a settings page or of
+/// displayed data.
+///
+/// ```ignore
+/// Table::builder()
+///     .column_widths(vec![TableColumnWidth::Intrinsic])
+///     .rows(vec![
+///         TableRow::new(wlist![Text::new("Name"), Text::new(name)]),
+///         TableRow::new(wlist![Text::new("Email"), Text::new(email)]),
+///     ])
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct Table<WL: WidgetList> {
+    pub rows: Vec<TableRow<WL>>,
+    /// Widths of the first columns. Other columns use `default_column_width`.
+    pub column_widths: Vec<TableColumnWidth>,
+    pub default_column_width: TableColumnWidth,
+    /// Alignment of cells which aren't wrapped in a [`TableCell`].
+    pub default_vertical_alignment: TableCellVerticalAlignment,
+    /// Whether the first column is on the left or on the right. If `None`,
+    /// the value of the closest [`Directionality`] ancestor is used.
+    pub text_direction: Option<TextDirection>,
+}
+
+impl Table<()> {
+    pub fn builder() -> Self {
+        Table {
+            rows: Vec::new(),
+            column_widths: Vec::new(),
+            default_column_width: TableColumnWidth::Flex(1.),
+            default_vertical_alignment: TableCellVerticalAlignment::Top,
+            text_direction: None,
+        }
+    }
+}
+
+impl<WL: WidgetList> Table<WL> {
+    fn column_width(&self, column: usize) -> TableColumnWidth {
+        match self.column_widths.get(column) {
+            Some(width) => *width,
+            None => self.default_column_width,
+        }
+    }
+
+    /// Cells of the table, split into its rows.
+    fn cells_by_row(&self, cx: &LayoutCx<Self>) -> Vec<Vec<LayoutCxOS>> {
+        let mut cells = cx.children();
+
+        self.rows
+            .iter()
+            .map(|row| cells.by_ref().take(row.children.len()).collect())
+            .collect()
+    }
+
+    /// Widths of columns within `max_width`, if cells need `intrinsic`
+    /// widths.
+    fn resolve_widths(
+        &self,
+        rows: &[Vec<LayoutCxOS>],
+        max_width: f64,
+        intrinsic: impl Fn(&LayoutCxOS) -> f64,
+    ) -> Vec<f64> {
+        let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let specs = (0..column_count)
+            .map(|column| self.column_width(column))
+            .collect::<Vec<_>>();
+
+        resolve_widths(&specs, max_width, |column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(&intrinsic)
+                .fold(0., f64::max)
+        })
+    }
+
+    /// Height of the table if its columns are sized within `width`.
+    fn intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        let rows = self.cells_by_row(cx);
+        let widths =
+            self.resolve_widths(&rows, width, |cell| cell.max_intrinsic_width(f64::INFINITY));
+
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| cell.max_intrinsic_height(*width))
+                    .fold(0., f64::max)
+            })
+            .sum()
+    }
+}
+
+/// Positions of rows and cells computed during layout.
+#[derive(Default)]
+pub struct TableGeometry {
+    /// Offsets of cells, ordered like the children of the table.
+    cells: Vec<Offset>,
+    /// Bounds of rows, relative to the table.
+    rows: Vec<Rect>,
+}
+
+impl<WL: WidgetList> RenderState for Table<WL> {
+    type State = TableGeometry;
+
+    fn create_state(&self) -> Self::State {
+        TableGeometry::default()
+    }
+}
+
+impl<WL: WidgetList> RenderWidget for Table<WL> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.rows
+            .iter()
+            .flat_map(|row| row.children.get())
+            .collect()
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let text_direction = Directionality::unwrap_or_default(self.text_direction, cx);
+
+        let rows = self.cells_by_row(cx);
+        let widths = self.resolve_widths(&rows, constraints.max_width, |cell| {
+            cell.max_intrinsic_width(f64::INFINITY)
+        });
+        let column_count = widths.len();
+
+        let width = widths.iter().sum::<f64>();
+
+        // Left edges of columns.
+        let mut lefts = Vec::with_capacity(column_count);
+        let mut left = 0.;
+
+        for column_width in &widths {
+            lefts.push(match text_direction {
+                TextDirection::Ltr => left,
+                TextDirection::Rtl => width - left - column_width,
+            });
+            left += column_width;
+        }
+
+        //
+        // Lay out rows.
+
+        let alignment_of = |cell: &LayoutCxOS| match cell.try_parent_data::<TableCellData>() {
+            Some(data) => data.vertical_alignment,
+            None => self.default_vertical_alignment,
+        };
+
+        let mut geometry = TableGeometry::default();
+        let mut top = 0.;
+
+        for row in &rows {
+            let mut height: f64 = 0.;
+
+            for (column, cell) in row.iter().enumerate() {
+                if alignment_of(cell) != TableCellVerticalAlignment::Fill {
+                    let size = cell.layout(Constraints::new_tight_for(Some(widths[column]), None));
+                    height = height.max(size.height);
+                }
+            }
+
+            for (column, cell) in row.iter().enumerate() {
+                let alignment = alignment_of(cell);
+
+                if alignment == TableCellVerticalAlignment::Fill {
+                    cell.layout(Constraints::new_tight(Size::new(widths[column], height)));
+                }
+
+                let free = height - cell.size().height;
+                let y = match alignment {
+                    TableCellVerticalAlignment::Top | TableCellVerticalAlignment::Fill => 0.,
+                    TableCellVerticalAlignment::Middle => free / 2.,
+                    TableCellVerticalAlignment::Bottom => free,
+                };
+
+                geometry.cells.push(Offset::new(lefts[column], top + y));
+            }
+
+            geometry.rows.push(Rect::from_ltwh(0., top, width, height));
+            top += height;
+        }
+
+        *cx.render_state_mut() = geometry;
+
+        constraints.constrain(Size::new(width, top))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let geometry = cx.render_state();
+
+        for (row, bounds) in self.rows.iter().zip(&geometry.rows) {
+            if let Some(decoration) = &row.decoration {
+                let rect = Rect::from_ltwh(
+                    offset.x + bounds.left,
+                    offset.y + bounds.top,
+                    bounds.width(),
+                    bounds.height(),
+                );
+
+                decoration.paint(canvas, rect, offset);
+            }
+        }
+
+        let cells = geometry.cells.clone();
+        drop(geometry);
+
+        for (mut cell, cell_offset) in cx.children().zip(cells) {
+            cell.paint(canvas, &(*offset + cell_offset));
+        }
+    }
+}
+
+impl<WL: WidgetList> Intrinsics for Table<WL> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        let rows = self.cells_by_row(cx);
+        let widths = self.resolve_widths(&rows, f64::INFINITY, |cell| {
+            cell.min_intrinsic_width(f64::INFINITY)
+        });
+
+        widths.iter().sum()
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        let rows = self.cells_by_row(cx);
+        let widths = self.resolve_widths(&rows, f64::INFINITY, |cell| {
+            cell.max_intrinsic_width(f64::INFINITY)
+        });
+
+        widths.iter().sum()
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_height(cx, width)
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.intrinsic_height(cx, width)
+    }
+}
+
+/// Widths of columns sized according to `specs`, within `max_width`.
+fn resolve_widths(
+    specs: &[TableColumnWidth],
+    max_width: f64,
+    intrinsic: impl Fn(usize) -> f64,
+) -> Vec<f64> {
+    let mut widths = vec![0.; specs.len()];
+    let mut flex_total = 0.;
+
+    for (column, spec) in specs.iter().enumerate() {
+        widths[column] = match *spec {
+            TableColumnWidth::Fixed(width) => width,
+            TableColumnWidth::Fraction(fraction) if max_width.is_finite() => fraction * max_width,
+            TableColumnWidth::Fraction(_) => 0.,
+            TableColumnWidth::Intrinsic => intrinsic(column),
+            TableColumnWidth::Flex(flex) => {
+                flex_total += flex;
+                continue;
+            }
+        };
+    }
+
+    let remaining = (max_width - widths.iter().sum::<f64>()).max(0.);
+
+    for (column, spec) in specs.iter().enumerate() {
+        if let TableColumnWidth::Flex(flex) = *spec {
+            widths[column] = match max_width.is_finite() {
+                true if flex_total > 0. => remaining * flex / flex_total,
+                true => 0.,
+                false => intrinsic(column),
+            };
+        }
+    }
+
+    widths
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolves_column_widths() {
+        let specs = [
+            TableColumnWidth::Fixed(50.),
+            TableColumnWidth::Intrinsic,
+            TableColumnWidth::Flex(1.),
+            TableColumnWidth::Flex(3.),
+            TableColumnWidth::Fraction(0.1),
+        ];
+
+        let widths = resolve_widths(&specs, 400., |column| 10. * column as f64);
+        assert_eq!(widths, [50., 10., 70., 210., 40.]);
+
+        // Flexible columns are as wide as their cells without limits.
+        let widths = resolve_widths(&specs, f64::INFINITY, |column| 10. * column as f64);
+        assert_eq!(widths, [50., 10., 20., 30., 0.]);
+    }
+
+    #[test]
+    fn rows_mix_decorations() {
+        let striped = BoxDecoration::builder().color(Color::SILVER);
+        let outlined =
+            BoxDecoration::builder().border(BoxBorder::all(Color::BLACK, 1., BorderStyle::Solid));
+
+        let rows = vec![
+            TableRow::new(((), ())).decoration(striped),
+            TableRow::new(((), ())).decoration(outlined),
+            TableRow::new(((), ())),
+        ];
+
+        assert_eq!(
+            rows[1].decoration.as_ref().unwrap().padding(),
+            EdgeInsets::all(1.)
+        );
+        assert!(rows.iter().all(|row| row.children.len() == 2));
+    }
+}
@@ -0,0 +1,59 @@
+use frui::{app::test_ids::TestIds, prelude::*, render::*};
+
+pub trait TestIdExt: Widget + Sized {
+    /// Wraps this widget in a [`TestId`].
+    fn test_id(self, id: impl Into<String>) -> TestId<Self> {
+        TestId {
+            id: id.into(),
+            child: self,
+        }
+    }
+}
+
+impl<T: Widget> TestIdExt for T {}
+
+/// Makes `child` locatable by UI tests and automation tools through
+/// [`TestIds::find`]. Identifiers should be unique within the application.
+#[derive(RenderWidget, Builder)]
+pub struct TestId<W: Widget> {
+    pub id: String,
+    pub child: W,
+}
+
+impl TestId<()> {
+    pub fn builder() -> Self {
+        TestId {
+            id: String::new(),
+            child: (),
+        }
+    }
+}
+
+impl<W: Widget> WidgetState for TestId<W> {
+    type State = ();
+
+    fn create_state(&self) -> Self::State {}
+
+    fn unmount(&self, _: BuildCx<Self>) {
+        TestIds::unregister(&self.id);
+    }
+}
+
+impl<W: Widget> RenderWidget for TestId<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        // Bounds of the widget as painted, e.g. scaled by a `Transform`.
+        let rect = Rect::from_origin_size(Point::from(*offset), cx.size());
+        let bounds = canvas.current_transform().transform_rect_bbox(rect.into());
+        TestIds::register(&self.id, bounds.into());
+
+        cx.child(0).paint(canvas, offset)
+    }
+}
@@ -0,0 +1,300 @@
+//! Context menu with text editing actions, opened on right-click or long-press.
+
+use std::{ops::Range, rc::Rc};
+
+use druid_shell::{keyboard_types::Key, MouseButton};
+use frui::{
+    app::{
+        clipboard::Clipboard,
+        feedback::{Feedback, FeedbackKind},
+    },
+    prelude::*,
+};
+
+use crate::*;
+
+/// Custom item of the [`TextContextMenu`].
+#[derive(Clone)]
+pub struct ContextMenuItem {
+    pub label: String,
+    pub enabled: bool,
+    action: Rc<dyn Fn()>,
+}
+
+impl ContextMenuItem {
+    pub fn new(label: impl Into<String>, action: impl Fn() + 'static) -> Self {
+        ContextMenuItem {
+            label: label.into(),
+            enabled: true,
+            action: Rc::new(action),
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Shows cut / copy / paste / select all menu when `child` is right-clicked or
+/// long-pressed. Menu actions are performed on the system [`Clipboard`].
+///
+/// Custom `items` are listed below the default ones. If the menu is opened
+/// with a `misspelled` word, its replacements are listed above them.
+///
+/// Menu is shown in the closest [`Overlay`] ancestor, so it can extend past
+/// the bounds of `child`.
+#[derive(ViewWidget, Builder)]
+pub struct TextContextMenu<W: Widget, R: Fn(String), S: Fn(), M: Fn(Range<usize>, String)> {
+    pub child: W,
+    /// Currently selected text.
+    pub selection: String,
+    /// Whether selected text can be replaced (cut / paste).
+    pub editable: bool,
+    /// Whether text can be pasted, e.g. `false` for obscured fields which
+    /// don't [allow pasting](ObscureText::allow_paste).
+    pub pastable: bool,
+    /// Replaces the selected text with the given text.
+    pub on_replace_selection: R,
+    pub on_select_all: S,
+    /// Misspelled word at the caret.
+    pub misspelled: Option<SpellCheckSuggestion>,
+    /// Replaces the given byte range of the text with the given text.
+    pub on_replace_range: M,
+    pub items: Vec<ContextMenuItem>,
+    pub color: Color,
+}
+
+impl TextContextMenu<(), fn(String), fn(), fn(Range<usize>, String)> {
+    pub fn builder() -> Self {
+        TextContextMenu {
+            child: (),
+            selection: String::new(),
+            editable: true,
+            pastable: true,
+            on_replace_selection: |_| {},
+            on_select_all: || {},
+            misspelled: None,
+            on_replace_range: |_, _| {},
+            items: Vec::new(),
+            color: Color::rgb8(48, 48, 48),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TextContextMenuState {
+    /// Position of the open menu, relative to this widget.
+    position: Option<Offset>,
+    long_press: Option<Timer>,
+    /// Link of `child` and the menu.
+    link: LayerLink,
+}
+
+impl<W: Widget, R: Fn(String), S: Fn(), M: Fn(Range<usize>, String)> WidgetState
+    for TextContextMenu<W, R, S, M>
+{
+    type State = TextContextMenuState;
+
+    fn create_state(&self) -> Self::State {
+        TextContextMenuState::default()
+    }
+}
+
+impl<W: Widget, R: Fn(String), S: Fn(), M: Fn(Range<usize>, String)> TextContextMenu<W, R, S, M> {
+    fn default_items(&self) -> Vec<(&'static str, bool, Box<dyn Fn() + '_>)> {
+        let has_selection = !self.selection.is_empty();
+
+        vec![
+            (
+                "Cut",
+                self.editable && has_selection,
+                Box::new(|| {
+                    Clipboard::put_string(&self.selection);
+                    (self.on_replace_selection)(String::new());
+                }),
+            ),
+            (
+                "Copy",
+                has_selection,
+                Box::new(|| Clipboard::put_string(&self.selection)),
+            ),
+            (
+                "Paste",
+                self.editable && self.pastable && Clipboard::has_string(),
+                Box::new(|| {
+                    if !self.editable || !self.pastable {
+                        return;
+                    }
+
+                    if let Some(text) = Clipboard::get_string() {
+                        (self.on_replace_selection)(text);
+                    }
+                }),
+            ),
+            ("Select All", true, Box::new(|| (self.on_select_all)())),
+        ]
+    }
+}
+
+impl<W: Widget, R: Fn(String), S: Fn(), M: Fn(Range<usize>, String)> ViewWidget
+    for TextContextMenu<W, R, S, M>
+{
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let close = move || {
+            let mut state = cx.state_mut();
+            state.position = None;
+        };
+
+        let (position, link) = {
+            let state = cx.state();
+            (state.position, state.link.clone())
+        };
+
+        let menu = match position {
+            Some(position) => {
+                let mut entries = Vec::new();
+
+                if let Some(misspelled) = &self.misspelled {
+                    entries.extend(misspelled.replacements.iter().map(|replacement| {
+                        MenuEntry::new(replacement, self.editable, move || {
+                            close();
+                            (self.on_replace_range)(misspelled.range.clone(), replacement.clone());
+                        })
+                        .boxed()
+                    }));
+
+                    if misspelled.replacements.is_empty() {
+                        entries.push(MenuEntry::new("No Replacements Found", false, || {}).boxed());
+                    }
+                }
+
+                entries.extend(
+                    self.default_items()
+                        .into_iter()
+                        .map(|(label, enabled, action)| {
+                            MenuEntry::new(label, enabled, move || {
+                                close();
+                                action();
+                            })
+                            .boxed()
+                        }),
+                );
+
+                entries.extend(self.items.iter().map(|item| {
+                    MenuEntry::new(item.label.as_str(), item.enabled, move || {
+                        close();
+                        (item.action)();
+                    })
+                    .boxed()
+                }));
+
+                Follower::builder()
+                    .link(link.clone())
+                    .target_anchor(Alignment::TOP_LEFT)
+                    .offset(position)
+                    .flip(false)
+                    .child(
+                        // Entries are as wide as the widest of them, rather
+                        // than as wide as the overlay.
+                        IntrinsicWidth::builder().child(
+                            DecoratedBox::builder()
+                                .position(DecorationPosition::Background)
+                                .decoration(
+                                    BoxDecoration::builder()
+                                        .color(self.color.clone())
+                                        .border_radius(BorderRadius::circular(4.)),
+                                )
+                                .child(
+                                    Column::builder()
+                                        .cross_axis_alignment(CrossAxisAlignment::Stretch)
+                                        .children(entries),
+                                ),
+                        ),
+                    )
+                    .boxed()
+            }
+            None => ().boxed(),
+        };
+
+        let child = PointerListener::builder()
+            .on_pointer_down(move |e| {
+                if e.0.button == MouseButton::Right {
+                    cx.state_mut().position = Some(Offset::new(e.0.pos.x, e.0.pos.y));
+                    return;
+                }
+
+                let position = Offset::new(e.0.pos.x, e.0.pos.y);
+                let timer = cx.timer_once(LONG_PRESS_TIMEOUT, move |state: &mut Self::State| {
+                    state.position = Some(position);
+                    state.long_press = None;
+
+                    Feedback::perform(FeedbackKind::LongPress);
+                });
+
+                let mut state = cx.state_mut();
+                state.position = None;
+
+                if let Some(previous) = state.long_press.replace(timer) {
+                    previous.cancel();
+                }
+            })
+            .on_pointer_up(move |_| {
+                if let Some(timer) = cx.state_mut().long_press.take() {
+                    timer.cancel();
+                }
+            })
+            .child(&self.child);
+
+        KeyboardEventDetector {
+            on_event: move |e| {
+                if e.key == Key::Escape && cx.state().position.is_some() {
+                    close();
+                }
+            },
+            child: OverlayPortal::builder()
+                .visible(position.is_some())
+                .child(FollowerTarget::builder().link(link).child(child))
+                .overlay_child(menu),
+        }
+    }
+}
+
+#[derive(ViewWidget)]
+struct MenuEntry<F: Fn()> {
+    label: String,
+    enabled: bool,
+    on_tap: F,
+}
+
+impl<F: Fn()> MenuEntry<F> {
+    fn new(label: &str, enabled: bool, on_tap: F) -> Self {
+        Self {
+            label: label.to_owned(),
+            enabled,
+            on_tap,
+        }
+    }
+}
+
+impl<F: Fn()> ViewWidget for MenuEntry<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let color = match self.enabled {
+            true => Color::WHITE,
+            false => Color::grey(0.5),
+        };
+
+        PointerListener::builder()
+            .on_pointer_up(|_| {
+                if self.enabled {
+                    Feedback::perform(FeedbackKind::Click);
+                    (self.on_tap)()
+                }
+            })
+            .child(
+                Padding::builder()
+                    .padding(EdgeInsets::symmetric(6., 12.))
+                    .child(Text::new(self.label.as_str()).size(14.).color(color)),
+            )
+    }
+}
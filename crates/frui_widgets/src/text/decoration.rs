@@ -0,0 +1,347 @@
+use std::ops::{BitOr, Range};
+
+use druid_shell::{
+    kurbo::{BezPath, Line},
+    piet::{LineCap, PietTextLayout, StrokeStyle, TextLayout},
+};
+use frui::prelude::*;
+use frui::render::*;
+
+/// Which lines are drawn by a [`TextDecoration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextDecorationLine {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+}
+
+impl TextDecorationLine {
+    pub const NONE: TextDecorationLine = TextDecorationLine {
+        underline: false,
+        overline: false,
+        line_through: false,
+    };
+
+    pub const UNDERLINE: TextDecorationLine = TextDecorationLine {
+        underline: true,
+        ..Self::NONE
+    };
+
+    pub const OVERLINE: TextDecorationLine = TextDecorationLine {
+        overline: true,
+        ..Self::NONE
+    };
+
+    pub const LINE_THROUGH: TextDecorationLine = TextDecorationLine {
+        line_through: true,
+        ..Self::NONE
+    };
+
+    pub fn contains(&self, other: TextDecorationLine) -> bool {
+        (!other.underline || self.underline)
+            && (!other.overline || self.overline)
+            && (!other.line_through || self.line_through)
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+impl BitOr for TextDecorationLine {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        TextDecorationLine {
+            underline: self.underline || rhs.underline,
+            overline: self.overline || rhs.overline,
+            line_through: self.line_through || rhs.line_through,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDecorationStyle {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    /// Sinusoidal line, commonly used to mark spelling or lint errors.
+    Wavy,
+}
+
+impl Default for TextDecorationStyle {
+    fn default() -> Self {
+        TextDecorationStyle::Solid
+    }
+}
+
+/// Lines drawn over, under or through text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDecoration {
+    pub line: TextDecorationLine,
+    pub style: TextDecorationStyle,
+    /// Defaults to the color of the decorated text.
+    pub color: Option<Color>,
+    /// Thickness of the line in logical pixels. Defaults to a value derived
+    /// from the font size.
+    pub thickness: Option<f64>,
+}
+
+impl TextDecoration {
+    pub fn new(line: TextDecorationLine) -> Self {
+        TextDecoration {
+            line,
+            style: TextDecorationStyle::Solid,
+            color: None,
+            thickness: None,
+        }
+    }
+
+    pub fn underline() -> Self {
+        Self::new(TextDecorationLine::UNDERLINE)
+    }
+
+    pub fn overline() -> Self {
+        Self::new(TextDecorationLine::OVERLINE)
+    }
+
+    pub fn line_through() -> Self {
+        Self::new(TextDecorationLine::LINE_THROUGH)
+    }
+
+    /// Wavy underline of the given color, e.g. for spell-check annotations.
+    pub fn wavy_underline(color: Color) -> Self {
+        Self::underline()
+            .style(TextDecorationStyle::Wavy)
+            .color(color)
+    }
+
+    pub fn style(mut self, style: TextDecorationStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn thickness(mut self, thickness: f64) -> Self {
+        self.thickness = Some(thickness);
+        self
+    }
+}
+
+/// A [`TextDecoration`] applied to a byte range of the text. Characters only
+/// partially covered by the range are decorated whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDecorationSpan {
+    pub range: Range<usize>,
+    pub decoration: TextDecoration,
+}
+
+#[cfg(not(feature = "miri"))]
+pub(crate) fn paint_decorations(
+    canvas: &mut Canvas,
+    layout: &PietTextLayout,
+    offset: &Offset,
+    spans: &[TextDecorationSpan],
+    font_size: f64,
+    text_color: &Color,
+) {
+    let text = layout.text();
+
+    for span in spans {
+        let decoration = &span.decoration;
+
+        if decoration.line.is_none() {
+            continue;
+        }
+
+        let range = snap_to_chars(text, span.range.clone());
+
+        let thickness = decoration
+            .thickness
+            .unwrap_or_else(|| (font_size / 16.).max(1.));
+        let color = decoration.color.as_ref().unwrap_or(text_color);
+
+        for n in 0..layout.line_count() {
+            let metric = layout.line_metric(n).unwrap();
+
+            // Don't decorate trailing whitespace at the end of a line.
+            let line_end = metric.end_offset - metric.trailing_whitespace;
+
+            let start = range.start.max(metric.start_offset);
+            let end = range.end.min(line_end);
+
+            if start >= end {
+                continue;
+            }
+
+            let (x0, x1) = match horizontal_extent(layout, start..end) {
+                Some(extent) => extent,
+                None => continue,
+            };
+
+            let baseline = metric.y_offset + metric.baseline;
+            let line = decoration.line;
+
+            if line.underline {
+                let y = baseline + font_size * 0.1 + thickness / 2.;
+                let line = DecorationLine::new(x0, x1, y, 1.);
+                line.paint(canvas, offset, decoration.style, thickness, color);
+            }
+
+            if line.overline {
+                let y = metric.y_offset + thickness / 2.;
+                let line = DecorationLine::new(x0, x1, y, -1.);
+                line.paint(canvas, offset, decoration.style, thickness, color);
+            }
+
+            if line.line_through {
+                let y = baseline - font_size * 0.3;
+                let line = DecorationLine::new(x0, x1, y, 1.);
+                line.paint(canvas, offset, decoration.style, thickness, color);
+            }
+        }
+    }
+}
+
+/// Widens `range` to the closest char boundaries of `text`, and limits it to
+/// the length of `text`.
+#[cfg(not(feature = "miri"))]
+fn snap_to_chars(text: &str, range: Range<usize>) -> Range<usize> {
+    let end = range.end.min(text.len());
+    let start = range.start.min(end);
+
+    let start = (0..=start)
+        .rev()
+        .find(|i| text.is_char_boundary(*i))
+        .unwrap_or(0);
+    let end = (end..=text.len())
+        .find(|i| text.is_char_boundary(*i))
+        .unwrap_or(text.len());
+
+    start..end
+}
+
+/// Horizontal span covered by the given range of a single line.
+#[cfg(not(feature = "miri"))]
+fn horizontal_extent(layout: &PietTextLayout, range: Range<usize>) -> Option<(f64, f64)> {
+    layout
+        .rects_for_range(range)
+        .into_iter()
+        .fold(None, |extent, rect| match extent {
+            None => Some((rect.x0, rect.x1)),
+            Some((x0, x1)) => Some((rect.x0.min(x0), rect.x1.max(x1))),
+        })
+}
+
+#[cfg(not(feature = "miri"))]
+struct DecorationLine {
+    x0: f64,
+    x1: f64,
+    y: f64,
+    /// Direction in which the second line of [`TextDecorationStyle::Double`]
+    /// is placed, away from the glyphs.
+    y_direction: f64,
+}
+
+#[cfg(not(feature = "miri"))]
+impl DecorationLine {
+    fn new(x0: f64, x1: f64, y: f64, y_direction: f64) -> Self {
+        DecorationLine {
+            x0,
+            x1,
+            y,
+            y_direction,
+        }
+    }
+
+    fn paint(
+        &self,
+        canvas: &mut Canvas,
+        offset: &Offset,
+        style: TextDecorationStyle,
+        thickness: f64,
+        color: &Color,
+    ) {
+        let x0 = offset.x + self.x0;
+        let x1 = offset.x + self.x1;
+        let y = offset.y + self.y;
+
+        match style {
+            TextDecorationStyle::Solid => {
+                canvas.stroke(Line::new((x0, y), (x1, y)), color, thickness);
+            }
+            TextDecorationStyle::Double => {
+                let y2 = y + self.y_direction * thickness * 2.;
+
+                canvas.stroke(Line::new((x0, y), (x1, y)), color, thickness);
+                canvas.stroke(Line::new((x0, y2), (x1, y2)), color, thickness);
+            }
+            TextDecorationStyle::Dotted => {
+                let mut stroke = StrokeStyle::new().line_cap(LineCap::Round);
+                stroke.set_dash_pattern(vec![0., thickness * 2.]);
+
+                let line = Line::new((x0, y), (x1, y));
+                canvas.stroke_styled(line, color, thickness, &stroke);
+            }
+            TextDecorationStyle::Dashed => {
+                let mut stroke = StrokeStyle::new();
+                stroke.set_dash_pattern(vec![thickness * 4., thickness * 2.]);
+
+                let line = Line::new((x0, y), (x1, y));
+                canvas.stroke_styled(line, color, thickness, &stroke);
+            }
+            TextDecorationStyle::Wavy => {
+                let amplitude = thickness * 1.5;
+                let half_wavelength = (thickness * 3.).max(2.);
+
+                let mut path = BezPath::new();
+                let mut x = x0;
+                let mut sign = 1.;
+
+                path.move_to((x, y));
+
+                while x < x1 {
+                    let control = (x + half_wavelength / 2., y + sign * amplitude * 2.);
+                    path.quad_to(control, (x + half_wavelength, y));
+
+                    x += half_wavelength;
+                    sign = -sign;
+                }
+
+                // Waves don't generally end exactly at `x1`, so cut off the
+                // overflowing part of the last one.
+                let clip = DruidRect::new(x0, y - amplitude * 2., x1, y + amplitude * 2.);
+
+                canvas
+                    .with_save(|canvas| {
+                        canvas.clip(clip);
+                        canvas.stroke(&path, color, thickness);
+                        Ok(())
+                    })
+                    .unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "miri")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ranges_are_snapped_to_char_boundaries() {
+        // "é" and "ö" are two bytes long.
+        let text = "héllö";
+
+        assert_eq!(snap_to_chars(text, 0..3), 0..3);
+        assert_eq!(snap_to_chars(text, 2..5), 1..5);
+        assert_eq!(snap_to_chars(text, 1..6), 1..7);
+        assert_eq!(snap_to_chars(text, 4..usize::MAX), 4..7);
+        assert_eq!(snap_to_chars(text, 9..12), 7..7);
+    }
+}
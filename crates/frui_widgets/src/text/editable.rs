@@ -0,0 +1,1329 @@
+//! Text edited by the user. Editing is done by the platform, which calls the
+//! [`InputHandler`] of the focused field, so that typed text can be composed
+//! with input methods and platform key bindings are respected.
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    kurbo::{Affine, Point, Rect, Vec2},
+    piet::{HitTestPoint, PietTextLayout, TextLayout},
+    KeyEvent, MouseButton,
+};
+use frui::{
+    app::{
+        clipboard::Clipboard,
+        text_input::{
+            Affinity, Direction, InputHandler, Movement, Selection, TextAction,
+            TextInputConnection, TextInputEvent, VerticalMovement,
+        },
+        window::{Cursor, Window},
+    },
+    prelude::*,
+    render::*,
+};
+
+use crate::*;
+
+use super::editing::{next_char, next_word, prev_char, prev_word, word_at};
+#[cfg(not(feature = "miri"))]
+use super::rich_text::shape_styled;
+
+/// Time for which the blinking caret is shown, and then hidden.
+const CURSOR_BLINK: Duration = Duration::from_millis(530);
+
+const CURSOR_WIDTH: f64 = 2.;
+
+/// Text which can be edited by the user, with a caret and a selection which
+/// can be changed with the pointer and the keyboard.
+///
+/// Edited value is kept by a [`TextEditingController`], which can be passed
+/// to read or change it from elsewhere. No decoration is painted, see
+/// [`TextField`] for a field with a border and a placeholder.
+///
+/// ```ignore
+/// EditableText::builder()
+///     .controller(controller.clone())
+///     .on_submitted(|text: &str| send_message(text))
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct EditableText<C: Fn(&str), S: Fn(&str)> {
+    /// If `None`, the value is kept by the widget itself.
+    pub controller: Option<TextEditingController>,
+    /// Properties which aren't set are taken from the closest
+    /// [`DefaultTextStyle`] ancestor.
+    pub style: TextStyle,
+    pub text_align: Option<TextAlign>,
+    /// If `None`, direction is detected from the first strong character of
+    /// the text, falling back to the closest [`Directionality`] ancestor.
+    pub text_direction: Option<TextDirection>,
+    /// Number of lines the field is high, lines which don't fit are scrolled.
+    /// Single-line fields (the default) don't wrap their text and submit it
+    /// on enter. If `None`, the field grows with its text, see
+    /// [`multiline`](Self::multiline).
+    pub max_lines: Option<usize>,
+    pub obscure: Option<ObscureText>,
+    /// Whether obscured text is shown, e.g. after it was revealed with an
+    /// [`ObscureToggle`].
+    pub obscure_visible: bool,
+    /// Whether the field is focused once it's inserted into the tree.
+    pub autofocus: bool,
+    /// Node which can be passed to focus the field from elsewhere. If `None`,
+    /// the field has its own node.
+    pub focus_node: Option<FocusNode>,
+    /// If `None`, the primary color of the [`Theme`] is used.
+    pub cursor_color: Option<Color>,
+    /// If `None`, a translucent primary color of the [`Theme`] is used.
+    pub selection_color: Option<Color>,
+    /// Whether words reported as misspelled by the registered
+    /// [`SpellCheckService`] are underlined. Obscured text isn't checked.
+    pub spell_check: bool,
+    /// Whether draggable handles and a cut / copy / paste toolbar are shown
+    /// for selections made with the pointer, as on touch screens. A
+    /// [`Magnifier`] is shown while a handle is dragged.
+    ///
+    /// Controls are shown in the closest [`Overlay`] ancestor.
+    pub selection_controls: bool,
+    /// Called with the text after every edit made by the user.
+    pub on_changed: C,
+    /// Called with the text when enter is pressed in a single-line field.
+    pub on_submitted: S,
+}
+
+impl EditableText<fn(&str), fn(&str)> {
+    pub fn builder() -> Self {
+        EditableText {
+            controller: None,
+            style: TextStyle::default(),
+            text_align: None,
+            text_direction: None,
+            max_lines: Some(1),
+            obscure: None,
+            obscure_visible: false,
+            autofocus: false,
+            focus_node: None,
+            cursor_color: None,
+            selection_color: None,
+            spell_check: false,
+            selection_controls: false,
+            on_changed: |_| {},
+            on_submitted: |_| {},
+        }
+    }
+}
+
+impl<C: Fn(&str), S: Fn(&str)> EditableText<C, S> {
+    /// Lets the field grow with its text, which is wrapped. Enter inserts a
+    /// new line.
+    pub fn multiline(mut self) -> Self {
+        self.max_lines = None;
+        self
+    }
+
+    fn focus_node(&self, state: &EditableTextState) -> FocusNode {
+        match &self.focus_node {
+            Some(node) => node.clone(),
+            None => state.focus_fallback.clone(),
+        }
+    }
+
+    /// Handles at the ends of the selection, and a toolbar above it unless a
+    /// handle is being dragged. They follow the field in an [`Overlay`].
+    fn selection_controls<'w>(
+        &'w self,
+        shared: &Rc<RefCell<EditSession>>,
+        session: &EditSession,
+        link: &LayerLink,
+        color: &Color,
+    ) -> Vec<Box<dyn Widget + 'w>> {
+        let scroll = session.viewport.origin().to_vec2();
+        let range = session.value.selection_range();
+
+        // Other end of the selection stays in place while a handle is dragged.
+        let ends = match range.is_empty() {
+            true => vec![(SelectionHandleType::Collapsed, range.start, None)],
+            false => vec![
+                (SelectionHandleType::Left, range.start, Some(range.end)),
+                (SelectionHandleType::Right, range.end, Some(range.start)),
+            ],
+        };
+
+        let mut controls: Vec<Box<dyn Widget + 'w>> = Vec::new();
+
+        for (handle_type, offset, fixed) in ends {
+            let caret = match session.caret_rect(offset) {
+                Some(caret) => caret - scroll,
+                None => continue,
+            };
+
+            // Handle is moved by the distance it was dragged from the caret.
+            let grab = caret.center() + scroll;
+
+            let handle = SelectionHandle::builder()
+                .handle_type(handle_type)
+                .color(color.clone())
+                .on_drag({
+                    let session = shared.clone();
+
+                    move |delta: Offset| {
+                        let mut session = session.borrow_mut();
+                        let offset = session.offset_at(grab + Vec2::new(delta.x, delta.y));
+
+                        session.dragging_handle = true;
+                        session.select(match fixed {
+                            Some(fixed) => Selection::new(fixed, offset),
+                            None => Selection::caret(offset),
+                        });
+                    }
+                })
+                .on_drag_end({
+                    let session = shared.clone();
+
+                    move || {
+                        let mut session = session.borrow_mut();
+                        session.dragging_handle = false;
+                        session.rebuild();
+                    }
+                });
+
+            let anchor = handle.anchor();
+
+            controls
+                .push(Self::follow(link, caret.x0 - anchor.x, caret.y1 - anchor.y, handle).boxed());
+        }
+
+        let start = session.caret_rect(range.start);
+
+        if let (false, Some(start)) = (session.dragging_handle, start) {
+            let can_copy = !range.is_empty() && session.can_copy();
+
+            let toolbar = TextSelectionToolbar {
+                on_cut: can_copy.then(|| {
+                    let session = shared.clone();
+                    move || session.borrow_mut().cut()
+                }),
+                on_copy: can_copy.then(|| {
+                    let session = shared.clone();
+
+                    move || {
+                        let mut session = session.borrow_mut();
+                        session.copy();
+                        session.show_controls = false;
+                        session.rebuild();
+                    }
+                }),
+                on_paste: Some({
+                    let session = shared.clone();
+                    move || session.borrow_mut().paste()
+                }),
+                color: TextSelectionToolbar::builder().color,
+            };
+
+            let mut toolbar = Self::follow(link, start.x0 - scroll.x, start.y0 - scroll.y, toolbar);
+            toolbar.follower_anchor = Alignment::BOTTOM_LEFT;
+            toolbar.keep_in_bounds = true;
+
+            controls.push(toolbar.boxed());
+        }
+
+        controls
+    }
+
+    /// Places the top left corner of `child` at `x`, `y` of the field.
+    fn follow<W: Widget>(link: &LayerLink, x: f64, y: f64, child: W) -> Follower<W> {
+        Follower::builder()
+            .link(link.clone())
+            .target_anchor(Alignment::TOP_LEFT)
+            .offset(Offset::new(x, y))
+            .flip(false)
+            .keep_in_bounds(false)
+            .child(child)
+    }
+
+    /// Clipboard shortcuts, which aren't handled by the platform. Returns
+    /// whether `event` was one of them.
+    fn handle_shortcut(cx: BuildCx<Self>, event: &KeyEvent) -> bool {
+        if event.state != KeyState::Down || (!event.mods.ctrl() && !event.mods.meta()) {
+            return false;
+        }
+
+        let key = match &event.key {
+            Key::Character(c) => c.to_lowercase(),
+            _ => return false,
+        };
+
+        // Edits notify the controller, which rebuilds this widget, so the
+        // state can't be borrowed while they are made.
+        let session = {
+            let state = cx.state();
+            let connection = state.connection.borrow();
+
+            match &*connection {
+                Some(connection) if connection.is_focused() => state.session.clone(),
+                _ => return false,
+            }
+        };
+
+        let mut session = session.borrow_mut();
+        let len = session.value.text.len();
+
+        match key.as_str() {
+            "a" => session.select(Selection::new(0, len)),
+            "c" => session.copy(),
+            "x" => session.cut(),
+            "v" => session.paste(),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+pub struct EditableTextState {
+    session: Rc<RefCell<EditSession>>,
+    /// Created once the field is focused for the first time.
+    connection: RefCell<Option<TextInputConnection>>,
+    listener: ControllerListener<TextEditingController>,
+    /// Controller used if none was passed.
+    fallback: TextEditingController,
+    focus_listener: ControllerListener<FocusNode>,
+    /// Focus node used if none was passed.
+    focus_fallback: FocusNode,
+    /// Value from the last build.
+    value: RefCell<TextEditingValue>,
+    autofocused: Cell<bool>,
+    was_focused: Cell<bool>,
+    cursor_visible: Cell<bool>,
+    blink: Cell<Option<Timer>>,
+    /// Obscures the revealed character of obscured text.
+    reveal: Cell<Option<Timer>>,
+    /// Link of the field and its selection controls.
+    link: LayerLink,
+}
+
+impl Drop for EditableTextState {
+    fn drop(&mut self) {
+        if self.was_focused.get() {
+            Caret::clear();
+        }
+    }
+}
+
+impl<C: Fn(&str), S: Fn(&str)> WidgetState for EditableText<C, S> {
+    type State = EditableTextState;
+
+    fn create_state(&self) -> Self::State {
+        let fallback = TextEditingController::new();
+        let controller = self.controller.clone().unwrap_or_else(|| fallback.clone());
+
+        EditableTextState {
+            session: Rc::new(RefCell::new(EditSession::new(controller))),
+            connection: RefCell::new(None),
+            listener: ControllerListener::default(),
+            fallback,
+            focus_listener: ControllerListener::default(),
+            focus_fallback: FocusNode::new(),
+            value: RefCell::new(TextEditingValue::default()),
+            autofocused: Cell::new(false),
+            was_focused: Cell::new(false),
+            cursor_visible: Cell::new(false),
+            blink: Cell::new(None),
+            reveal: Cell::new(None),
+            link: LayerLink::new(),
+        }
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        if self.autofocus && !cx.state().autofocused.replace(true) {
+            // Focusing rebuilds the field, so the state can't be borrowed.
+            let node = self.focus_node(&cx.state());
+            node.request_focus();
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        let state = cx.state();
+        state.listener.unlisten();
+        state.focus_listener.unlisten();
+    }
+}
+
+impl<C: Fn(&str), S: Fn(&str)> ViewWidget for EditableText<C, S> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.fallback.clone(),
+        };
+
+        let (edited, submitted) = {
+            let mut session = state.session.borrow_mut();
+            session.controller = controller.clone();
+            session.updater = Some(cx.state_updater());
+            session.single_line = self.max_lines == Some(1);
+            session.obscure = self.obscure.clone();
+            session.obscure_state.set_visible(self.obscure_visible);
+
+            let edited = std::mem::take(&mut session.edited);
+            (edited, std::mem::take(&mut session.submitted))
+        };
+
+        if edited || submitted {
+            // Callbacks may change the controller (e.g. clear the field once
+            // it's submitted), which mustn't rebuild the widget while it is
+            // being built.
+            state.listener.unlisten();
+
+            let text = controller.text();
+
+            if edited {
+                (self.on_changed)(&text);
+            }
+
+            if submitted {
+                (self.on_submitted)(&text);
+            }
+        }
+
+        let updater = cx.state_updater();
+        state
+            .listener
+            .listen(&controller, move || updater.update(|_| {}));
+
+        let value = controller.value();
+        let event = state.session.borrow_mut().sync(&value);
+
+        let node = self.focus_node(&state);
+
+        let updater = cx.state_updater();
+        state
+            .focus_listener
+            .listen(&node, move || updater.update(|_| {}));
+
+        let focused = {
+            let mut connection = state.connection.borrow_mut();
+
+            // Text input is routed to the field while its node is focused.
+            if node.is_focused() {
+                let connection = connection
+                    .get_or_insert_with(|| TextInputConnection::new(state.session.clone()));
+
+                if !connection.is_focused() {
+                    let node = node.clone();
+                    connection.focus(move || node.unfocus());
+                }
+            } else if let Some(connection) = &*connection {
+                connection.blur();
+            }
+
+            if let (Some(connection), Some(event)) = (&*connection, event) {
+                connection.notify(event);
+            }
+
+            connection.as_ref().map_or(false, |c| c.is_focused())
+        };
+
+        let changed = *state.value.borrow() != value;
+        let was_focused = state.was_focused.replace(focused);
+
+        if focused && (changed || !was_focused) {
+            state.session.borrow_mut().reveal_caret = true;
+        }
+
+        // Caret is shown for a while after every change.
+        if focused && (changed || !was_focused) {
+            if let Some(timer) = state.blink.take() {
+                timer.cancel();
+            }
+
+            state.cursor_visible.set(true);
+            state.blink.set(Some(cx.timer_periodic(
+                CURSOR_BLINK,
+                |state: &mut EditableTextState| {
+                    state.cursor_visible.set(!state.cursor_visible.get())
+                },
+            )));
+        } else if !focused && was_focused {
+            if let Some(timer) = state.blink.take() {
+                timer.cancel();
+            }
+
+            Caret::clear();
+        }
+
+        let display_text = {
+            let session = state.session.borrow();
+
+            if changed {
+                if let Some(deadline) = session.reveal_deadline() {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+
+                    if let Some(timer) =
+                        state.reveal.replace(Some(cx.timer_once(remaining, |_| {})))
+                    {
+                        timer.cancel();
+                    }
+                }
+            }
+
+            session.display_text(&value.text)
+        };
+
+        let misspelled = match self.spell_check && self.obscure.is_none() {
+            true => SpellCheck::decorations(&SpellCheck::check(&value.text)),
+            false => Vec::new(),
+        };
+
+        *state.value.borrow_mut() = value.clone();
+
+        let primary = Theme::of(cx).color_scheme.primary;
+        let cursor_color = self.cursor_color.clone().unwrap_or(primary.clone());
+
+        let (controls, focal_point) = {
+            let mut session = state.session.borrow_mut();
+
+            if !focused {
+                session.show_controls = false;
+            }
+
+            match self.selection_controls && session.show_controls {
+                true => (
+                    self.selection_controls(&state.session, &session, &state.link, &cursor_color),
+                    session.handle_focal_point(),
+                ),
+                false => (Vec::new(), None),
+            }
+        };
+
+        let mut magnifier = Magnifier::builder().child(RawEditableText {
+            session: state.session.clone(),
+            text: display_text,
+            value,
+            misspelled,
+            style: self.style.clone(),
+            text_align: self.text_align,
+            text_direction: self.text_direction,
+            max_lines: self.max_lines,
+            focused,
+            show_cursor: focused && state.cursor_visible.get(),
+            cursor_color: cursor_color.clone(),
+            selection_color: self
+                .selection_color
+                .clone()
+                .unwrap_or(primary.with_alpha(0.35)),
+            on_focus: {
+                let node = node.clone();
+                move || node.request_focus()
+            },
+        });
+        magnifier.focal_point = focal_point;
+
+        Focus::builder()
+            .focus_node(node)
+            .on_key(move |event: &KeyEvent| Self::handle_shortcut(cx, event))
+            .child(
+                OverlayPortal::builder()
+                    .visible(!controls.is_empty())
+                    .child(
+                        FollowerTarget::builder()
+                            .link(state.link.clone())
+                            .child(magnifier),
+                    )
+                    .overlay_child(
+                        Stack::builder()
+                            .fit(StackFit::Expand)
+                            .clip(false)
+                            .children(controls),
+                    ),
+            )
+    }
+}
+
+/// Editing state shared by the widget and the platform, which edits the text
+/// through the [`InputHandler`] implementation.
+struct EditSession {
+    controller: TextEditingController,
+    /// Rebuilds the widget, e.g. once the text was submitted.
+    updater: Option<StateUpdater<EditableTextState>>,
+    /// Value known to the platform. Values of the controller which differ from
+    /// it were set programmatically, which the platform must be notified of.
+    value: TextEditingValue,
+    single_line: bool,
+    direction: TextDirection,
+    obscure: Option<ObscureText>,
+    obscure_state: ObscureState,
+    /// Layout of the displayed text, from the last layout of the field.
+    layout: Option<PietTextLayout>,
+    font_size: f64,
+    text_color: Color,
+    /// Visible part of the layout, which is scrolled to keep the caret
+    /// visible.
+    viewport: Rect,
+    /// Transformation from coordinates of the layout to window coordinates.
+    transform: Affine,
+    /// Whether the text was edited or submitted by the user since the last
+    /// build of the widget.
+    edited: bool,
+    submitted: bool,
+    /// Whether the selection is being extended by dragging the pointer.
+    dragging: bool,
+    /// Whether selection controls are shown, i.e. the selection was last
+    /// changed with the pointer.
+    show_controls: bool,
+    /// Whether a selection handle is being dragged.
+    dragging_handle: bool,
+    /// Whether the caret moved since it was last painted, so that scroll
+    /// views containing the field should scroll it into view.
+    reveal_caret: bool,
+}
+
+impl EditSession {
+    fn new(controller: TextEditingController) -> Self {
+        EditSession {
+            controller,
+            updater: None,
+            value: TextEditingValue::default(),
+            single_line: true,
+            direction: TextDirection::Ltr,
+            obscure: None,
+            obscure_state: ObscureState::default(),
+            layout: None,
+            font_size: 0.,
+            text_color: Color::BLACK,
+            viewport: Rect::ZERO,
+            transform: Affine::IDENTITY,
+            edited: false,
+            submitted: false,
+            dragging: false,
+            show_controls: false,
+            dragging_handle: false,
+            reveal_caret: false,
+        }
+    }
+
+    fn rebuild(&self) {
+        if let Some(updater) = &self.updater {
+            updater.update(|_| {});
+        }
+    }
+
+    /// Center of the caret at the dragged end of the selection, magnified
+    /// while the handle is dragged.
+    fn handle_focal_point(&self) -> Option<Offset> {
+        if !self.dragging_handle {
+            return None;
+        }
+
+        let center = self.caret_rect(self.value.selection.active)?.center()
+            - self.viewport.origin().to_vec2();
+
+        Some(Offset::new(center.x, center.y))
+    }
+
+    /// Updates the value known to the platform, returning the event it should
+    /// be notified with if the value changed.
+    fn sync(&mut self, value: &TextEditingValue) -> Option<TextInputEvent> {
+        if self.value == *value {
+            return None;
+        }
+
+        let event = if self.value.text == value.text && self.value.composing == value.composing {
+            TextInputEvent::SelectionChanged
+        } else {
+            self.obscure_state.on_edit();
+            TextInputEvent::Reset
+        };
+
+        self.value = value.clone();
+        Some(event)
+    }
+
+    fn display_text(&self, text: &str) -> String {
+        match &self.obscure {
+            Some(config) => self.obscure_state.display_text(config, text),
+            None => text.to_owned(),
+        }
+    }
+
+    fn reveal_deadline(&self) -> Option<Instant> {
+        self.obscure_state.reveal_deadline(self.obscure.as_ref()?)
+    }
+
+    /// Converts an offset in the text to an offset in the displayed text.
+    fn to_display(&self, offset: usize) -> usize {
+        match &self.obscure {
+            Some(config) => self
+                .obscure_state
+                .display_offset(config, &self.value.text, offset),
+            None => offset,
+        }
+    }
+
+    /// Converts an offset in the displayed text to an offset in the text.
+    fn from_display(&self, offset: usize) -> usize {
+        let config = match &self.obscure {
+            Some(config) => config,
+            None => return offset,
+        };
+
+        let text = &self.value.text;
+
+        text.char_indices()
+            .map(|(i, _)| i)
+            .chain([text.len()])
+            .find(|i| self.obscure_state.display_offset(config, text, *i) >= offset)
+            .unwrap_or(text.len())
+    }
+
+    fn commit(&mut self, value: TextEditingValue) {
+        self.value = value.clone();
+        self.controller.set_value(value);
+    }
+
+    fn select(&mut self, selection: Selection) {
+        let mut value = self.value.clone();
+        value.selection = selection;
+        self.commit(value);
+    }
+
+    /// Replaces `range` of the text with `text` typed, pasted or deleted by
+    /// the user.
+    fn edit(&mut self, range: Range<usize>, text: &str) {
+        let text = match self.single_line {
+            true => text.replace(&['\r', '\n'][..], ""),
+            false => text.to_owned(),
+        };
+
+        let mut value = self.value.clone();
+        let range = value.clamp(range.start)..value.clamp(range.end);
+
+        if self.obscure.is_some() {
+            match range.is_empty() && text.chars().count() == 1 {
+                true => self.obscure_state.on_char_typed(range.start),
+                false => self.obscure_state.on_edit(),
+            }
+        }
+
+        value.replace_range(range, &text);
+
+        self.edited = true;
+        self.show_controls = false;
+        self.commit(value);
+    }
+
+    /// Replaces the selection with `transform` applied to it, keeping the
+    /// transformed text selected.
+    fn transform_selection(&mut self, transform: impl Fn(&str) -> String) {
+        let range = self.value.selection_range();
+        let text = transform(&self.value.text[range.clone()]);
+
+        self.edit(range.clone(), &text);
+        self.select(Selection::new(range.start, range.start + text.len()));
+    }
+
+    fn can_copy(&self) -> bool {
+        self.obscure.is_none() || self.obscure_state.can_copy()
+    }
+
+    fn copy(&self) {
+        let selected = self.value.selected_text();
+
+        if self.can_copy() && !selected.is_empty() {
+            Clipboard::put_string(selected);
+        }
+    }
+
+    fn cut(&mut self) {
+        if self.can_copy() && !self.value.selected_text().is_empty() {
+            self.copy();
+            self.edit(self.value.selection_range(), "");
+        }
+    }
+
+    fn paste(&mut self) {
+        let allowed = match &self.obscure {
+            Some(config) => self.obscure_state.can_paste(config),
+            None => true,
+        };
+
+        if let (true, Some(text)) = (allowed, Clipboard::get_string()) {
+            self.edit(self.value.selection_range(), &text);
+        }
+    }
+
+    /// Offset of the text closest to `point` in coordinates of the layout.
+    fn offset_at(&self, point: Point) -> usize {
+        match &self.layout {
+            Some(layout) => self.from_display(layout.hit_test_point(point).idx),
+            None => 0,
+        }
+    }
+
+    /// Bounds of the caret at `offset` in coordinates of the layout.
+    fn caret_rect(&self, offset: usize) -> Option<Rect> {
+        let layout = self.layout.as_ref()?;
+        let position = layout.hit_test_text_position(self.to_display(offset));
+        let metric = layout.line_metric(position.line)?;
+
+        Some(Rect::new(
+            position.point.x,
+            metric.y_offset,
+            position.point.x + CURSOR_WIDTH,
+            metric.y_offset + metric.height,
+        ))
+    }
+
+    /// Range of the visual line containing `offset`, without its trailing
+    /// whitespace.
+    fn line_at(&self, offset: usize) -> Range<usize> {
+        let layout = match &self.layout {
+            Some(layout) => layout,
+            None => return paragraph_at(&self.value.text, offset),
+        };
+
+        let display = self.to_display(offset);
+        let count = layout.line_count();
+
+        let metric = (0..count)
+            .filter_map(|n| layout.line_metric(n))
+            .find(|metric| display < metric.end_offset)
+            .or_else(|| layout.line_metric(count.saturating_sub(1)));
+
+        match metric {
+            Some(metric) => {
+                let end = metric.end_offset - metric.trailing_whitespace;
+                self.from_display(metric.start_offset)..self.from_display(end)
+            }
+            None => 0..0,
+        }
+    }
+
+    /// Whether `direction` points towards the end of the text.
+    fn is_forward(&self, direction: Direction) -> bool {
+        match direction {
+            Direction::Left => self.direction == TextDirection::Rtl,
+            Direction::Right => self.direction == TextDirection::Ltr,
+            _ => matches!(direction, Direction::Downstream),
+        }
+    }
+
+    /// Offset the active end of the selection is moved to by `movement`.
+    fn target(&self, movement: Movement) -> usize {
+        let text = &self.value.text;
+        let offset = self.value.clamp(self.value.selection.active);
+
+        match movement {
+            Movement::Grapheme(d) if self.is_forward(d) => next_char(text, offset),
+            Movement::Grapheme(_) => prev_char(text, offset),
+            Movement::Word(d) if self.is_forward(d) => next_word(text, offset),
+            Movement::Word(_) => prev_word(text, offset),
+            Movement::Line(d) if self.is_forward(d) => self.line_at(offset).end,
+            Movement::Line(_) => self.line_at(offset).start,
+            Movement::ParagraphStart => paragraph_at(text, offset).start,
+            Movement::ParagraphEnd => paragraph_at(text, offset).end,
+            Movement::Vertical(movement) => self.vertical_target(offset, movement),
+            _ => offset,
+        }
+    }
+
+    fn vertical_target(&self, offset: usize, movement: VerticalMovement) -> usize {
+        let len = self.value.text.len();
+
+        let layout = match &self.layout {
+            Some(layout) => layout,
+            None => return offset,
+        };
+
+        let position = layout.hit_test_text_position(self.to_display(offset));
+        let line = position.line as isize;
+
+        let line_height = layout.line_metric(position.line).map_or(1., |m| m.height);
+        let page = ((self.viewport.height() / line_height.max(1.)) as isize).max(1);
+
+        let target = match movement {
+            VerticalMovement::LineUp => line - 1,
+            VerticalMovement::LineDown => line + 1,
+            VerticalMovement::PageUp => line - page,
+            VerticalMovement::PageDown => line + page,
+            VerticalMovement::DocumentStart => return 0,
+            VerticalMovement::DocumentEnd => return len,
+            _ => return offset,
+        };
+
+        if target < 0 {
+            return 0;
+        }
+
+        match layout.line_metric(target as usize) {
+            Some(metric) => {
+                // Caret keeps its horizontal position over lines shorter than
+                // the one where the vertical movement started.
+                let x = self.value.selection.h_pos.unwrap_or(position.point.x);
+                self.offset_at(Point::new(x, metric.y_offset + metric.height / 2.))
+            }
+            None => len,
+        }
+    }
+
+    /// Horizontal position to be kept by the selection after `movement`.
+    fn h_pos(&self, movement: Movement) -> Option<f64> {
+        match movement {
+            Movement::Vertical(_) => self.value.selection.h_pos.or_else(|| {
+                let caret = self.caret_rect(self.value.selection.active)?;
+                Some(caret.x0)
+            }),
+            _ => None,
+        }
+    }
+
+    /// Scrolls the viewport of the given `size` to keep the caret visible.
+    fn scroll_to_caret(&mut self, size: Size) {
+        let content = match &self.layout {
+            Some(layout) => layout.size(),
+            None => return,
+        };
+
+        let mut origin = self.viewport.origin();
+
+        if let Some(caret) = self.caret_rect(self.value.selection.active) {
+            origin.x = origin.x.min(caret.x0).max(caret.x1 - size.width);
+            origin.y = origin.y.min(caret.y0).max(caret.y1 - size.height);
+        }
+
+        origin.x = origin
+            .x
+            .min(content.width + CURSOR_WIDTH - size.width)
+            .max(0.);
+        origin.y = origin.y.min(content.height - size.height).max(0.);
+
+        self.viewport = Rect::from_origin_size(origin, (size.width, size.height));
+    }
+}
+
+impl InputHandler for EditSession {
+    fn selection(&self) -> Selection {
+        self.value.selection
+    }
+
+    fn set_selection(&mut self, selection: Selection) {
+        let mut clamped = Selection::new(
+            self.value.clamp(selection.anchor),
+            self.value.clamp(selection.active),
+        );
+        clamped.h_pos = selection.h_pos;
+
+        self.select(clamped);
+    }
+
+    fn composition_range(&self) -> Option<Range<usize>> {
+        self.value.composing.clone()
+    }
+
+    fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+        let mut value = self.value.clone();
+        value.composing = range;
+        self.commit(value);
+    }
+
+    fn is_char_boundary(&self, i: usize) -> bool {
+        self.value.text.is_char_boundary(i)
+    }
+
+    fn len(&self) -> usize {
+        self.value.text.len()
+    }
+
+    fn slice(&self, range: Range<usize>) -> Cow<str> {
+        Cow::Borrowed(&self.value.text[range])
+    }
+
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        self.edit(range, text);
+    }
+
+    fn hit_test_point(&self, point: Point) -> HitTestPoint {
+        let point = self.transform.inverse() * point;
+
+        match &self.layout {
+            Some(layout) => {
+                let mut hit = layout.hit_test_point(point);
+                hit.idx = self.from_display(hit.idx);
+                hit
+            }
+            None => HitTestPoint::default(),
+        }
+    }
+
+    fn line_range(&self, index: usize, _: Affinity) -> Range<usize> {
+        self.line_at(index)
+    }
+
+    fn bounding_box(&self) -> Option<Rect> {
+        Some(self.transform.transform_rect_bbox(self.viewport))
+    }
+
+    fn slice_bounding_box(&self, range: Range<usize>) -> Option<Rect> {
+        let layout = self.layout.as_ref()?;
+
+        let rect = match range.is_empty() {
+            true => self.caret_rect(range.start)?,
+            false => layout
+                .rects_for_range(self.to_display(range.start)..self.to_display(range.end))
+                .into_iter()
+                .reduce(|a, b| a.union(b))?,
+        };
+
+        Some(self.transform.transform_rect_bbox(rect))
+    }
+
+    fn handle_action(&mut self, action: TextAction) {
+        let selection = self.value.selection;
+        let range = self.value.selection_range();
+
+        match action {
+            TextAction::Move(movement) => {
+                let offset = match movement {
+                    // Collapses the selection to its start or end.
+                    Movement::Grapheme(d) if !selection.is_caret() => match self.is_forward(d) {
+                        true => range.end,
+                        false => range.start,
+                    },
+                    _ => self.target(movement),
+                };
+
+                let mut selection = Selection::caret(offset);
+                selection.h_pos = self.h_pos(movement);
+                self.select(selection);
+            }
+            TextAction::MoveSelecting(movement) => {
+                let mut selection = Selection::new(selection.anchor, self.target(movement));
+                selection.h_pos = self.h_pos(movement);
+                self.select(selection);
+            }
+            TextAction::SelectAll => self.select(Selection::new(0, self.value.text.len())),
+            TextAction::SelectWord => {
+                let word = word_at(&self.value.text, range.start);
+                self.select(Selection::new(word.start, word.end));
+            }
+            TextAction::SelectLine => {
+                let line = self.line_at(range.start);
+                self.select(Selection::new(line.start, line.end));
+            }
+            TextAction::SelectParagraph => {
+                let paragraph = paragraph_at(&self.value.text, range.start);
+                self.select(Selection::new(paragraph.start, paragraph.end));
+            }
+            TextAction::Delete(movement) => {
+                let range = match selection.is_caret() {
+                    true => {
+                        let target = self.target(movement);
+                        target.min(range.start)..target.max(range.end)
+                    }
+                    false => range,
+                };
+
+                self.edit(range, "");
+            }
+            TextAction::DecomposingBackspace => {
+                self.handle_action(TextAction::Delete(Movement::Grapheme(Direction::Upstream)))
+            }
+            TextAction::UppercaseSelection => self.transform_selection(str::to_uppercase),
+            TextAction::LowercaseSelection => self.transform_selection(str::to_lowercase),
+            TextAction::TitlecaseSelection => self.transform_selection(titlecase),
+            TextAction::InsertNewLine { .. } if self.single_line => {
+                self.submitted = true;
+
+                self.rebuild();
+            }
+            TextAction::InsertNewLine { .. } => self.edit(range, "\n"),
+            TextAction::InsertSingleQuoteIgnoringSmartQuotes => self.edit(range, "'"),
+            TextAction::InsertDoubleQuoteIgnoringSmartQuotes => self.edit(range, "\""),
+            // Tabs aren't inserted, they are left for moving focus.
+            _ => {}
+        }
+    }
+}
+
+/// Range of the paragraph (text between new lines) containing `offset`.
+fn paragraph_at(text: &str, offset: usize) -> Range<usize> {
+    let start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+
+    start..end
+}
+
+fn titlecase(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word_start = true;
+
+    for c in text.chars() {
+        match word_start {
+            true => result.extend(c.to_uppercase()),
+            false => result.extend(c.to_lowercase()),
+        }
+
+        word_start = c.is_whitespace();
+    }
+
+    result
+}
+
+/// Text of [`EditableText`] with its caret and selection. Changes of the
+/// value are passed with it, so that the text is laid out again.
+#[derive(RenderWidget)]
+struct RawEditableText<F: Fn()> {
+    session: Rc<RefCell<EditSession>>,
+    /// Displayed (possibly obscured) text.
+    text: String,
+    value: TextEditingValue,
+    /// Underlines of misspelled words.
+    misspelled: Vec<TextDecorationSpan>,
+    style: TextStyle,
+    text_align: Option<TextAlign>,
+    text_direction: Option<TextDirection>,
+    max_lines: Option<usize>,
+    focused: bool,
+    show_cursor: bool,
+    cursor_color: Color,
+    selection_color: Color,
+    on_focus: F,
+}
+
+#[cfg(not(feature = "miri"))]
+impl<F: Fn()> RenderWidget for RawEditableText<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let default = DefaultTextStyle::of(cx);
+        let mut style = default.style.merge(&self.style).resolve();
+        style.size *= MediaQuery::of_layout(cx).text_scale_factor;
+
+        let direction = self
+            .text_direction
+            .or(TextDirection::detect(&self.text))
+            .unwrap_or_else(|| Directionality::of_or_default(cx));
+        let text_align = self.text_align.unwrap_or(default.text_align);
+
+        let mut styled = StyledText::new(style.clone());
+        styled.push(&self.text, style.clone());
+
+        // Room is left for the caret at the end of lines.
+        let max_width = constraints.biggest().width;
+        let wrap_width = max_width - CURSOR_WIDTH;
+
+        let layout = match self.max_lines {
+            // Single line isn't wrapped, but scrolled once it doesn't fit.
+            Some(1) => {
+                let layout = shape_styled(&styled, direction, text_align, f64::INFINITY);
+
+                match layout.size().width <= wrap_width && wrap_width.is_finite() {
+                    true => shape_styled(&styled, direction, text_align, wrap_width),
+                    false => layout,
+                }
+            }
+            _ => shape_styled(&styled, direction, text_align, wrap_width),
+        };
+
+        let text_size = layout.size();
+        let line_height = layout.line_metric(0).map_or(style.size, |m| m.height);
+
+        let width = match max_width.is_finite() {
+            true => max_width,
+            false => text_size.width + CURSOR_WIDTH,
+        };
+        let height = match self.max_lines {
+            Some(n) => line_height * n as f64,
+            None => text_size.height,
+        };
+
+        let size = constraints.constrain(Size::new(width, height));
+
+        let mut session = self.session.borrow_mut();
+        session.direction = direction;
+        session.layout = Some(layout);
+        session.font_size = style.size;
+        session.text_color = style.color;
+        session.scroll_to_caret(size);
+
+        size
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let bounds = Rect::from_origin_size((offset.x, offset.y), (size.width, size.height));
+
+        let mut session = self.session.borrow_mut();
+
+        let origin = Vec2::new(offset.x, offset.y) - session.viewport.origin().to_vec2();
+        session.transform = cx.window_transform(canvas) * Affine::translate(origin);
+        let reveal = std::mem::take(&mut session.reveal_caret);
+
+        let session = &*session;
+        let layout = match &session.layout {
+            Some(layout) => layout,
+            None => return,
+        };
+
+        let range = self.value.selection_range();
+        let range = session.to_display(range.start)..session.to_display(range.end);
+        let caret = session.caret_rect(self.value.selection.active);
+
+        cx.with_clip(canvas, bounds, |_, canvas| {
+            if self.focused && !range.is_empty() {
+                for rect in layout.rects_for_range(range) {
+                    canvas.fill(rect + origin, &self.selection_color);
+                }
+            }
+
+            RenderContext::draw_text(canvas, layout, origin.to_point());
+
+            let mut spans = self.misspelled.clone();
+
+            if let Some(composing) = &self.value.composing {
+                spans.push(TextDecorationSpan {
+                    range: session.to_display(composing.start)..session.to_display(composing.end),
+                    decoration: TextDecoration::underline(),
+                });
+            }
+
+            super::decoration::paint_decorations(
+                canvas,
+                layout,
+                &Offset::new(origin.x, origin.y),
+                &spans,
+                session.font_size,
+                &session.text_color,
+            );
+
+            if let (true, Some(caret)) = (self.show_cursor && range.is_empty(), caret) {
+                canvas.fill(caret + origin, &self.cursor_color);
+            }
+        });
+
+        if let (true, Some(caret)) = (self.focused, caret) {
+            Caret::report(session.transform.transform_rect_bbox(caret));
+
+            // Viewports containing the field scroll to the caret before
+            // their child is painted, which is in the next frame.
+            if reveal {
+                Caret::reveal();
+                Window::request_paint();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "miri")]
+impl<F: Fn()> RenderWidget for RawEditableText<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.smallest()
+    }
+
+    fn paint(&self, _: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {}
+}
+
+impl<F: Fn()> HitTest for RawEditableText<F> {
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(e) if e.0.button == MouseButton::Left => {
+                // Focusing rebuilds the field, so it's done before the session
+                // is borrowed.
+                (self.on_focus)();
+
+                let mut session = self.session.borrow_mut();
+                let offset = session.offset_at(e.0.pos + session.viewport.origin().to_vec2());
+
+                let selection = match e.0.count {
+                    2 => {
+                        let word = word_at(&session.value.text, offset);
+                        Selection::new(word.start, word.end)
+                    }
+                    n if n > 2 => {
+                        let paragraph = paragraph_at(&session.value.text, offset);
+                        Selection::new(paragraph.start, paragraph.end)
+                    }
+                    _ if e.0.mods.shift() => Selection::new(session.value.selection.anchor, offset),
+                    _ => Selection::caret(offset),
+                };
+
+                session.dragging = true;
+                session.show_controls = true;
+                session.select(selection);
+
+                // Keep selecting even if the pointer leaves the field.
+                cx.capture_pointer();
+            }
+            PointerEvent::PointerMove(e) => {
+                Window::set_cursor(&Cursor::IBeam);
+
+                let mut session = self.session.borrow_mut();
+
+                if session.dragging {
+                    let offset = session.offset_at(e.0.pos + session.viewport.origin().to_vec2());
+                    let anchor = session.value.selection.anchor;
+                    session.select(Selection::new(anchor, offset));
+                }
+            }
+            PointerEvent::PointerUp(_) => {
+                self.session.borrow_mut().dragging = false;
+                cx.release_pointer();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn edit_session_actions() {
+        let controller = TextEditingController::with_text("hello world");
+        let mut session = EditSession::new(controller.clone());
+        session.sync(&controller.value());
+
+        session.handle_action(TextAction::Move(Movement::Word(Direction::Left)));
+        assert_eq!(controller.selection(), Selection::caret(6));
+
+        session.handle_action(TextAction::MoveSelecting(Movement::ParagraphStart));
+        assert_eq!(controller.selection(), Selection::new(6, 0));
+
+        session.handle_action(TextAction::TitlecaseSelection);
+        assert_eq!(controller.text(), "Hello world");
+        assert_eq!(controller.selection(), Selection::new(0, 6));
+
+        session.handle_action(TextAction::Move(Movement::Grapheme(Direction::Right)));
+        session.handle_action(TextAction::Delete(Movement::Word(Direction::Downstream)));
+        assert_eq!(controller.text(), "Hello ");
+        assert!(session.edited);
+
+        // Single-line fields submit on enter instead of inserting a new line.
+        session.replace_range(6..6, "a\nb");
+        session.handle_action(TextAction::InsertNewLine {
+            ignore_hotkey: false,
+            newline_type: '\n',
+        });
+        assert_eq!(controller.text(), "Hello ab");
+        assert!(session.submitted);
+
+        // Changes made through the controller reset the platform's state.
+        controller.set_text("x");
+        let event = session.sync(&controller.value());
+        assert!(matches!(event, Some(TextInputEvent::Reset)));
+        assert!(session.sync(&controller.value()).is_none());
+    }
+}
@@ -0,0 +1,412 @@
+//! Value of editable text and the controller sharing it between a text field
+//! and the rest of the application.
+
+use std::{
+    cell::{Cell, RefCell},
+    ops::Range,
+    rc::Rc,
+};
+
+use frui::app::{
+    leaks::{LeakDetector, Tracked},
+    text_input::Selection,
+};
+
+use crate::Listenable;
+
+/// Text of a text field, together with its selection and the range being
+/// composed by an input method.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextEditingValue {
+    pub text: String,
+    /// Byte offsets of the selection. Collapsed selection is the caret.
+    pub selection: Selection,
+    /// Range of text being composed by an input method (e.g. characters of
+    /// a word typed phonetically), which is underlined until it is committed.
+    pub composing: Option<Range<usize>>,
+}
+
+impl TextEditingValue {
+    /// `text` with the caret at its end.
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+
+        TextEditingValue {
+            selection: Selection::caret(text.len()),
+            text,
+            composing: None,
+        }
+    }
+
+    /// Selected range, limited to the text.
+    pub fn selection_range(&self) -> Range<usize> {
+        let start = self.clamp(self.selection.min());
+        let end = self.clamp(self.selection.max());
+
+        start..end
+    }
+
+    pub fn selected_text(&self) -> &str {
+        &self.text[self.selection_range()]
+    }
+
+    /// Replaces `range` of the text with `text`. Ends of the selection after
+    /// the range move with the text after it, the ones inside of it move to
+    /// the end of the inserted text. Composition is cancelled.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let range = self.clamp(range.start)..self.clamp(range.end);
+        self.text.replace_range(range.clone(), text);
+
+        let shift = |offset: usize| match offset {
+            o if o < range.start => o,
+            o if o >= range.end => o - range.len() + text.len(),
+            _ => range.start + text.len(),
+        };
+
+        self.selection = Selection::new(shift(self.selection.anchor), shift(self.selection.active));
+        self.composing = None;
+    }
+
+    /// Replaces the selection with `text`, placing the caret after it.
+    pub fn replace_selection(&mut self, text: &str) {
+        self.replace_range(self.selection_range(), text);
+    }
+
+    /// Nearest character boundary at or before `offset`.
+    pub(crate) fn clamp(&self, offset: usize) -> usize {
+        let mut offset = offset.min(self.text.len());
+
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        offset
+    }
+}
+
+/// Offset of the character before `offset`.
+pub(crate) fn prev_char(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// Offset after the character at `offset`.
+pub(crate) fn next_char(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .chars()
+        .next()
+        .map_or(offset, |c| offset + c.len_utf8())
+}
+
+#[derive(PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    /// Punctuation and symbols, each of which is a word of its own.
+    Other(char),
+}
+
+fn class(c: char) -> CharClass {
+    match c {
+        c if c.is_whitespace() => CharClass::Space,
+        c if c.is_alphanumeric() || c == '_' => CharClass::Word,
+        c => CharClass::Other(c),
+    }
+}
+
+/// Start of the word before `offset`, skipping whitespace.
+pub(crate) fn prev_word(text: &str, offset: usize) -> usize {
+    let mut chars = text[..offset].char_indices().rev().peekable();
+
+    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+    let (mut start, c) = match chars.next() {
+        Some(first) => first,
+        None => return 0,
+    };
+
+    if class(c) == CharClass::Word {
+        while let Some((i, _)) = chars.next_if(|(_, c)| class(*c) == CharClass::Word) {
+            start = i;
+        }
+    }
+
+    start
+}
+
+/// End of the word after `offset`, skipping whitespace.
+pub(crate) fn next_word(text: &str, offset: usize) -> usize {
+    let mut chars = text[offset..]
+        .char_indices()
+        .map(|(i, c)| (offset + i, c))
+        .peekable();
+
+    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+    let (start, c) = match chars.next() {
+        Some(first) => first,
+        None => return text.len(),
+    };
+
+    let mut end = start + c.len_utf8();
+
+    if class(c) == CharClass::Word {
+        while let Some((i, c)) = chars.next_if(|(_, c)| class(*c) == CharClass::Word) {
+            end = i + c.len_utf8();
+        }
+    }
+
+    end
+}
+
+/// Range of the word (or of the whitespace) containing the character at
+/// `offset`.
+pub(crate) fn word_at(text: &str, offset: usize) -> Range<usize> {
+    let c = match text[offset..].chars().next() {
+        Some(c) => c,
+        None => match text[..offset].chars().next_back() {
+            Some(c) => return offset - c.len_utf8()..offset,
+            None => return offset..offset,
+        },
+    };
+
+    if let CharClass::Other(_) = class(c) {
+        return offset..offset + c.len_utf8();
+    }
+
+    let same = |other: char| class(other) == class(c);
+
+    let start = text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| same(*c))
+        .last()
+        .map_or(offset, |(i, _)| i);
+
+    let end = text[offset..]
+        .char_indices()
+        .find(|(_, c)| !same(*c))
+        .map_or(text.len(), |(i, _)| offset + i);
+
+    start..end
+}
+
+/// [`TextEditingValue`] shared by a text field and other widgets, which can
+/// read it, change it (e.g. clear the field after submitting it) and listen
+/// to its changes.
+///
+/// Clones of a controller refer to the same value.
+#[derive(Clone)]
+pub struct TextEditingController {
+    inner: Rc<ControllerInner>,
+}
+
+#[derive(Default)]
+struct ControllerInner {
+    value: RefCell<TextEditingValue>,
+    listeners: RefCell<Vec<(TextEditingListenerKey, Rc<dyn Fn()>, Tracked)>>,
+    next_key: Cell<usize>,
+    _tracked: Tracked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEditingListenerKey(usize);
+
+impl Default for TextEditingController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextEditingController {
+    pub fn new() -> Self {
+        let inner = ControllerInner {
+            _tracked: LeakDetector::track("TextEditingController"),
+            ..Default::default()
+        };
+
+        TextEditingController {
+            inner: Rc::new(inner),
+        }
+    }
+
+    /// Controller of `text` with the caret at its end.
+    pub fn with_text(text: impl Into<String>) -> Self {
+        let controller = Self::new();
+        *controller.inner.value.borrow_mut() = TextEditingValue::new(text);
+        controller
+    }
+
+    pub fn value(&self) -> TextEditingValue {
+        self.inner.value.borrow().clone()
+    }
+
+    /// Replaces the value, notifying listeners if it changed.
+    pub fn set_value(&self, value: TextEditingValue) {
+        if *self.inner.value.borrow() == value {
+            return;
+        }
+
+        *self.inner.value.borrow_mut() = value;
+        self.notify();
+    }
+
+    pub fn text(&self) -> String {
+        self.inner.value.borrow().text.clone()
+    }
+
+    /// Replaces the text, placing the caret at its end.
+    pub fn set_text(&self, text: impl Into<String>) {
+        self.set_value(TextEditingValue::new(text));
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.inner.value.borrow().selection
+    }
+
+    pub fn set_selection(&self, selection: Selection) {
+        let mut value = self.value();
+        value.selection = selection;
+        self.set_value(value);
+    }
+
+    pub fn clear(&self) {
+        self.set_text("");
+    }
+
+    /// Calls `callback` every time the value changes.
+    pub fn listen(&self, callback: impl Fn() + 'static) -> TextEditingListenerKey {
+        let key = TextEditingListenerKey(self.inner.next_key.get());
+        self.inner.next_key.set(key.0 + 1);
+
+        let mut listeners = self.inner.listeners.borrow_mut();
+        listeners.push((key, Rc::new(callback), LeakDetector::track("Listener")));
+
+        key
+    }
+
+    pub fn unlisten(&self, key: TextEditingListenerKey) {
+        let mut listeners = self.inner.listeners.borrow_mut();
+        listeners.retain(|(k, ..)| *k != key);
+    }
+
+    /// Whether both controllers refer to the same value.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn notify(&self) {
+        // Listeners may add or remove listeners.
+        let listeners: Vec<_> = self
+            .inner
+            .listeners
+            .borrow()
+            .iter()
+            .map(|l| l.1.clone())
+            .collect();
+
+        for listener in listeners {
+            listener();
+        }
+    }
+}
+
+impl Listenable for TextEditingController {
+    type Key = TextEditingListenerKey;
+
+    fn listen(&self, callback: impl Fn() + 'static) -> Self::Key {
+        TextEditingController::listen(self, callback)
+    }
+
+    fn unlisten(&self, key: Self::Key) {
+        TextEditingController::unlisten(self, key)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        TextEditingController::ptr_eq(self, other)
+    }
+}
+
+impl PartialEq for TextEditingController {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl std::fmt::Debug for TextEditingController {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextEditingController")
+            .field("value", &*self.inner.value.borrow())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn replace_range_moves_selection() {
+        let mut value = TextEditingValue::new("hello world");
+        value.selection = Selection::new(6, 11);
+
+        value.replace_range(0..5, "hi");
+        assert_eq!(value.text, "hi world");
+        assert_eq!(value.selection_range(), 3..8);
+
+        value.replace_selection("there");
+        assert_eq!(value.text, "hi there");
+        assert_eq!(value.selection, Selection::caret(8));
+
+        // Typing at the caret moves it after the typed text.
+        value.replace_range(8..8, "!");
+        assert_eq!(value.selection, Selection::caret(9));
+    }
+
+    #[test]
+    fn word_movement() {
+        let text = "let x = foo_bar(1);  ";
+
+        assert_eq!(next_word(text, 0), 3);
+        assert_eq!(next_word(text, 3), 5);
+        assert_eq!(next_word(text, 8), 15);
+        assert_eq!(next_word(text, 15), 16);
+        assert_eq!(next_word(text, 19), text.len());
+
+        assert_eq!(prev_word(text, 15), 8);
+        assert_eq!(prev_word(text, 8), 6);
+        assert_eq!(prev_word(text, 2), 0);
+
+        assert_eq!(word_at(text, 10), 8..15);
+        assert_eq!(word_at(text, 15), 15..16);
+        assert_eq!(word_at(text, text.len()), text.len() - 1..text.len());
+
+        assert_eq!(prev_char("aé", 3), 1);
+        assert_eq!(next_char("aé", 1), 3);
+    }
+
+    #[test]
+    fn controller_notifies_changes() {
+        let controller = TextEditingController::with_text("abc");
+        let changes = Rc::new(Cell::new(0));
+
+        let key = controller.listen({
+            let changes = changes.clone();
+            move || changes.set(changes.get() + 1)
+        });
+
+        controller.set_selection(Selection::caret(3));
+        assert_eq!(changes.get(), 0);
+
+        controller.set_selection(Selection::new(0, 3));
+        controller.clear();
+        assert_eq!(changes.get(), 2);
+        assert_eq!(controller.value(), TextEditingValue::new(""));
+
+        controller.unlisten(key);
+        controller.set_text("d");
+        assert_eq!(changes.get(), 2);
+    }
+}
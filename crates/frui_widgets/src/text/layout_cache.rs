@@ -0,0 +1,319 @@
+//! Cache of shaped text layouts, shared by all [`Text`](super::Text) widgets.
+//!
+//! Shaping text is by far the most expensive part of laying out text-heavy
+//! UIs, and the same paragraphs are usually laid out again every frame with
+//! the same style and width.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+use druid_shell::piet::{Color, FontFamily, FontWeight, PietTextLayout, TextAlignment};
+use frui::{
+    app::diagnostics::{CacheUsage, MemoryDiagnostics},
+    render::TEXT_FACTORY,
+};
+
+use super::{StyledText, TextOverflow};
+
+const DEFAULT_BUDGET: usize = 1024;
+
+/// Everything that affects the shape of a text layout.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct TextLayoutKey {
+    text: String,
+    family: String,
+    size: u64,
+    weight: u16,
+    color: u32,
+    alignment: u8,
+    max_width: u64,
+    /// Styles of ranges of rich text, overriding the base style above.
+    runs: Vec<RunKey>,
+    max_lines: Option<usize>,
+    overflow: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RunKey {
+    start: usize,
+    end: usize,
+    family: String,
+    size: u64,
+    weight: u16,
+    color: u32,
+    italic: bool,
+}
+
+impl TextLayoutKey {
+    pub(crate) fn new(
+        text: &str,
+        family: &FontFamily,
+        size: f64,
+        weight: FontWeight,
+        color: &Color,
+        alignment: TextAlignment,
+        max_width: f64,
+    ) -> Self {
+        TextLayoutKey {
+            text: text.to_owned(),
+            family: family.name().to_owned(),
+            size: size.to_bits(),
+            weight: weight.to_raw(),
+            color: color.as_rgba_u32(),
+            alignment: alignment_key(alignment),
+            max_width: max_width.to_bits(),
+            runs: Vec::new(),
+            max_lines: None,
+            overflow: 0,
+        }
+    }
+
+    /// Key of rich text cut off after `max_lines` lines.
+    pub(crate) fn styled(
+        styled: &StyledText,
+        alignment: TextAlignment,
+        max_width: f64,
+        max_lines: Option<usize>,
+        overflow: TextOverflow,
+    ) -> Self {
+        let runs = styled
+            .runs
+            .iter()
+            .map(|(range, style)| RunKey {
+                start: range.start,
+                end: range.end,
+                family: style.family.name().to_owned(),
+                size: style.size.to_bits(),
+                weight: style.weight.to_raw(),
+                color: style.color.as_rgba_u32(),
+                italic: style.italic,
+            })
+            .collect();
+
+        let base = &styled.base;
+
+        TextLayoutKey {
+            text: styled.text.clone(),
+            family: base.family.name().to_owned(),
+            size: base.size.to_bits(),
+            weight: base.weight.to_raw(),
+            color: base.color.as_rgba_u32(),
+            alignment: alignment_key(alignment),
+            max_width: max_width.to_bits(),
+            runs,
+            max_lines,
+            overflow: match overflow {
+                TextOverflow::Clip => 0,
+                TextOverflow::Ellipsis => 1,
+                TextOverflow::Fade => 2,
+                TextOverflow::Visible => 3,
+            },
+        }
+    }
+}
+
+fn alignment_key(alignment: TextAlignment) -> u8 {
+    match alignment {
+        TextAlignment::Start => 0,
+        TextAlignment::End => 1,
+        TextAlignment::Center => 2,
+        TextAlignment::Justified => 3,
+    }
+}
+
+/// Least recently used text layouts, up to a budget of entries.
+pub struct TextLayoutCache {
+    entries: Lru<TextLayoutKey, PietTextLayout>,
+    budget: usize,
+    /// Approximate number of bytes retained by cached layouts.
+    bytes: usize,
+}
+
+impl TextLayoutCache {
+    /// Sets the maximal number of cached layouts. Zero disables caching.
+    pub fn set_budget(budget: usize) {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            cache.budget = budget;
+
+            while cache.entries.len() > budget {
+                cache.evict();
+            }
+
+            cache.report_usage();
+        });
+    }
+
+    pub fn len() -> usize {
+        CACHE.with(|c| c.borrow().entries.len())
+    }
+
+    /// Drops all cached layouts. Should be called when available fonts
+    /// change, which [`load_font`](Self::load_font) does automatically.
+    pub fn clear() {
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+            cache.entries.clear();
+            cache.bytes = 0;
+            cache.report_usage();
+        });
+    }
+
+    /// Loads font from `data`, invalidating cached layouts which might have
+    /// used a fallback font instead.
+    pub fn load_font(data: &[u8]) -> Result<FontFamily, druid_shell::piet::Error> {
+        let family = TEXT_FACTORY.with(|f| f.load_font(data))?;
+        Self::clear();
+        Ok(family)
+    }
+
+    /// Returns cached layout of `key`, or caches the layout returned by
+    /// `build`.
+    pub(crate) fn get_or_insert(
+        key: TextLayoutKey,
+        build: impl FnOnce() -> PietTextLayout,
+    ) -> PietTextLayout {
+        let cached = CACHE.with(|c| c.borrow_mut().entries.get(&key).cloned());
+
+        if let Some(layout) = cached {
+            return layout;
+        }
+
+        // Built outside of the borrow, since building uses `TEXT_FACTORY`.
+        let layout = build();
+
+        CACHE.with(|c| {
+            let mut cache = c.borrow_mut();
+
+            if cache.budget == 0 {
+                return;
+            }
+
+            if cache.entries.len() >= cache.budget {
+                cache.evict();
+            }
+
+            cache.bytes += entry_bytes(&key);
+            cache.entries.insert(key, layout.clone());
+            cache.report_usage();
+        });
+
+        layout
+    }
+
+    fn report_usage(&self) {
+        MemoryDiagnostics::report_cache(
+            "text layouts",
+            CacheUsage {
+                entries: self.entries.len(),
+                bytes: self.bytes,
+            },
+        );
+    }
+
+    fn evict(&mut self) {
+        if let Some((key, _)) = self.entries.pop_oldest() {
+            self.bytes -= entry_bytes(&key);
+        }
+    }
+}
+
+/// Shaped layouts live in the platform text backend, assume their size is
+/// proportional to the length of the text.
+fn entry_bytes(key: &TextLayoutKey) -> usize {
+    std::mem::size_of::<(TextLayoutKey, PietTextLayout)>() + 2 * key.text.len()
+}
+
+/// Map which keeps track of the order in which its entries were used, so
+/// that the least recently used one can be removed in logarithmic time.
+struct Lru<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    /// Keys by the time they were last used.
+    order: BTreeMap<u64, K>,
+    /// Incremented on every access.
+    clock: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+    fn new() -> Self {
+        Lru {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the value of `key`, marking it as the most recently used.
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+
+        let (value, last_used) = self.entries.get_mut(key)?;
+        let key = self.order.remove(last_used).unwrap();
+        *last_used = self.clock;
+        self.order.insert(self.clock, key);
+
+        Some(value)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+
+        if let Some((_, last_used)) = self.entries.insert(key.clone(), (value, self.clock)) {
+            self.order.remove(&last_used);
+        }
+
+        self.order.insert(self.clock, key);
+    }
+
+    fn pop_oldest(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.order.pop_first()?;
+        let (value, _) = self.entries.remove(&key).unwrap();
+
+        Some((key, value))
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+thread_local! {
+    static CACHE: RefCell<TextLayoutCache> = RefCell::new(TextLayoutCache {
+        entries: Lru::new(),
+        budget: DEFAULT_BUDGET,
+        bytes: 0,
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_entries_are_evicted_first() {
+        let mut lru = Lru::new();
+        lru.insert("a", 1);
+        lru.insert("b", 2);
+        lru.insert("c", 3);
+
+        assert_eq!(lru.get(&"a"), Some(&1));
+        assert_eq!(lru.get(&"d"), None);
+
+        // Replacing a value counts as using it.
+        lru.insert("b", 4);
+
+        assert_eq!(lru.pop_oldest(), Some(("c", 3)));
+        assert_eq!(lru.pop_oldest(), Some(("a", 1)));
+        assert_eq!(lru.pop_oldest(), Some(("b", 4)));
+        assert_eq!(lru.pop_oldest(), None);
+        assert_eq!(lru.len(), 0);
+    }
+}
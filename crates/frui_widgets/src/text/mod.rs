@@ -0,0 +1,296 @@
+use std::ops::Range;
+
+use frui::prelude::*;
+use frui::render::*;
+
+use druid_shell::piet::FontFamily;
+
+use crate::{Directionality, MediaQuery};
+
+pub use context_menu::*;
+pub use decoration::*;
+pub use editable::*;
+pub use editing::*;
+pub use layout_cache::*;
+pub use obscure::*;
+#[cfg(not(feature = "miri"))]
+pub use paragraph::*;
+pub use rich_text::*;
+pub use selection::*;
+pub use span::*;
+pub use spell_check::*;
+pub use style::*;
+pub use text_field::*;
+
+pub mod context_menu;
+pub mod decoration;
+pub mod editable;
+pub mod editing;
+pub mod layout_cache;
+pub mod obscure;
+#[cfg(not(feature = "miri"))]
+pub mod paragraph;
+pub mod rich_text;
+pub mod selection;
+pub mod span;
+pub mod spell_check;
+pub mod style;
+pub mod text_field;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TextDirection {
+    Rtl,
+    Ltr,
+}
+
+impl Default for TextDirection {
+    fn default() -> Self {
+        TextDirection::Ltr
+    }
+}
+
+impl TextDirection {
+    /// Direction of the first strong (directional) character of `text`, or
+    /// `None` if there is none, e.g. in text consisting of numbers only.
+    pub fn detect(text: &str) -> Option<Self> {
+        text.chars().find_map(|c| match c {
+            '\u{200E}' => Some(TextDirection::Ltr),
+            '\u{200F}' => Some(TextDirection::Rtl),
+            c if is_rtl(c) => Some(TextDirection::Rtl),
+            c if c.is_alphabetic() => Some(TextDirection::Ltr),
+            _ => None,
+        })
+    }
+}
+
+/// Whether `c` belongs to one of the right-to-left scripts (Hebrew, Arabic,
+/// Syriac, Thaana, N'Ko and others).
+fn is_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{0590}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        | '\u{10800}'..='\u{10FFF}'
+        | '\u{1E800}'..='\u{1EFFF}'
+    ) && c.is_alphabetic()
+}
+
+pub trait Directional {
+    type Output;
+
+    fn resolve(&self, text_direction: &TextDirection) -> Self::Output;
+}
+
+impl<T: Directional> Directional for &T {
+    type Output = T::Output;
+
+    fn resolve(&self, text_direction: &TextDirection) -> Self::Output {
+        T::resolve(self, text_direction)
+    }
+}
+
+/// Displays a string of a single style. Properties which aren't set are
+/// taken from the closest [`DefaultTextStyle`] ancestor.
+///
+/// For text consisting of multiple styles, see [`RichText`].
+#[derive(RenderWidget, Builder)]
+pub struct Text<S: AsRef<str>> {
+    text: S,
+    size: Option<f64>,
+    color: Option<Color>,
+    weight: Option<FontWeight>,
+    family: Option<FontFamily>,
+    /// If `None`, direction is detected from the first strong character of
+    /// the text, falling back to the closest [`Directionality`] ancestor.
+    text_direction: Option<TextDirection>,
+    text_align: Option<TextAlign>,
+    max_lines: Option<usize>,
+    overflow: Option<TextOverflow>,
+    decorations: Vec<TextDecorationSpan>,
+}
+
+impl<S: AsRef<str>> Text<S> {
+    pub fn new(string: S) -> Self {
+        Self {
+            text: string,
+            size: None,
+            color: None,
+            weight: None,
+            family: None,
+            text_direction: None,
+            text_align: None,
+            max_lines: None,
+            overflow: None,
+            decorations: Vec::new(),
+        }
+    }
+
+    /// Decorate the whole text.
+    pub fn decoration(self, decoration: TextDecoration) -> Self {
+        self.decorate(0..usize::MAX, decoration)
+    }
+
+    /// Decorate the given byte range of the text. Decorations of overlapping
+    /// ranges are all painted, in the order they were added.
+    pub fn decorate(mut self, range: Range<usize>, decoration: TextDecoration) -> Self {
+        self.decorations
+            .push(TextDecorationSpan { range, decoration });
+        self
+    }
+
+    /// Underline words reported as misspelled by the registered
+    /// [`SpellCheckService`].
+    pub fn spell_check(mut self) -> Self {
+        let suggestions = SpellCheck::check(self.text.as_ref());
+        self.decorations
+            .extend(SpellCheck::decorations(&suggestions));
+        self
+    }
+
+    fn style(&self) -> TextStyle {
+        TextStyle {
+            color: self.color.clone(),
+            size: self.size,
+            weight: self.weight,
+            family: self.family.clone(),
+            italic: None,
+            decoration: None,
+        }
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> RenderState for Text<S> {
+    type State = TextLayoutState;
+
+    fn create_state(&self) -> Self::State {
+        TextLayoutState::new()
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> RenderWidget for Text<S> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let state = self.layout_text(cx, constraints.biggest().width);
+
+        let text_size = state.size();
+        *cx.render_state_mut() = state;
+
+        constraints.constrain(text_size)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.render_state().paint(canvas, offset);
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> Baseline for Text<S> {
+    fn distance_to_baseline(&self, cx: &LayoutCx<Self>) -> Option<f64> {
+        Some(cx.render_state().first_baseline())
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> Intrinsics for Text<S> {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        // Text wraps at every opportunity when laid out without space, so
+        // it's as wide as its widest word.
+        self.layout_text(cx, 0.).size().width
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        self.layout_text(cx, f64::INFINITY).size().width
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.layout_text(cx, width).size().height
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.layout_text(cx, width).size().height
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl<S: AsRef<str>> Text<S> {
+    fn layout_text(&self, cx: &LayoutCx<Self>, max_width: f64) -> TextLayoutState {
+        let default = DefaultTextStyle::of(cx);
+        let mut style = default.style.merge(&self.style()).resolve();
+        style.size *= MediaQuery::of_layout(cx).text_scale_factor;
+
+        let mut styled = StyledText::new(style.clone());
+        styled.push(self.text.as_ref(), style.clone());
+
+        let direction = self
+            .text_direction
+            .or(TextDirection::detect(self.text.as_ref()))
+            .unwrap_or_else(|| Directionality::of_or_default(cx));
+
+        let mut state = TextLayoutState::layout(
+            &styled,
+            direction,
+            self.text_align.unwrap_or(default.text_align),
+            max_width,
+            self.max_lines.or(default.max_lines),
+            self.overflow.unwrap_or(default.overflow),
+        );
+
+        state.decorate(&self.decorations, style.size, &style.color);
+        state
+    }
+}
+
+#[cfg(feature = "miri")]
+pub struct TextRenderState([u8; 30]);
+
+#[cfg(feature = "miri")]
+impl<S: AsRef<str>> RenderState for Text<S> {
+    type State = TextRenderState;
+
+    fn create_state(&self) -> Self::State {
+        TextRenderState([1; 30])
+    }
+}
+
+#[cfg(feature = "miri")]
+impl<S: AsRef<str>> RenderWidget for Text<S> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let _: &mut TextRenderState = &mut cx.render_state_mut();
+
+        Size {
+            width: constraints.smallest().width,
+            height: constraints.smallest().height,
+        }
+    }
+
+    fn paint(&self, _: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect_text_direction() {
+        assert_eq!(TextDirection::detect("Hello"), Some(TextDirection::Ltr));
+        assert_eq!(TextDirection::detect("123 שלום"), Some(TextDirection::Rtl));
+        assert_eq!(
+            TextDirection::detect("(مرحبا) hi"),
+            Some(TextDirection::Rtl)
+        );
+        assert_eq!(
+            TextDirection::detect("\u{200F}42 hi"),
+            Some(TextDirection::Rtl)
+        );
+        assert_eq!(TextDirection::detect("3.14 !?"), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::time::{Duration, Instant};
+
+use druid_shell::kurbo::{BezPath, Circle, Line};
+use frui::prelude::*;
+use frui::render::*;
+
+/// Configuration of a text field which hides its content, e.g. a password
+/// field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObscureText {
+    /// Character displayed in place of each character of the text.
+    pub character: char,
+    /// How long the most recently typed character stays visible. Set to
+    /// `None` to never reveal typed characters.
+    pub reveal_last_for: Option<Duration>,
+    /// Whether text can be pasted into the field.
+    pub allow_paste: bool,
+}
+
+impl Default for ObscureText {
+    fn default() -> Self {
+        ObscureText {
+            character: '•',
+            reveal_last_for: Some(Duration::from_millis(1000)),
+            allow_paste: true,
+        }
+    }
+}
+
+/// Per-field state of obscured text: whether the user revealed the text with
+/// a toggle and which character was typed last.
+#[derive(Debug, Clone, Default)]
+pub struct ObscureState {
+    visible: bool,
+    /// Byte offset of the last typed character and when it was typed.
+    last_typed: Option<(usize, Instant)>,
+}
+
+impl ObscureState {
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle_visibility(&mut self) {
+        self.visible = !self.visible;
+        self.last_typed = None;
+    }
+
+    /// Toggles visibility, unless it already is `visible`.
+    pub fn set_visible(&mut self, visible: bool) {
+        if self.visible != visible {
+            self.toggle_visibility();
+        }
+    }
+
+    /// Called after a single character was typed at the given byte offset.
+    pub fn on_char_typed(&mut self, offset: usize) {
+        self.last_typed = Some((offset, Instant::now()));
+    }
+
+    /// Called after any other edit (deletion, paste, etc.), which hides the
+    /// revealed character.
+    pub fn on_edit(&mut self) {
+        self.last_typed = None;
+    }
+
+    /// Copying or cutting obscured text is not allowed.
+    pub fn can_copy(&self) -> bool {
+        self.visible
+    }
+
+    pub fn can_paste(&self, config: &ObscureText) -> bool {
+        self.visible || config.allow_paste
+    }
+
+    /// Instant at which the revealed character gets obscured again. Field
+    /// should repaint at that point.
+    pub fn reveal_deadline(&self, config: &ObscureText) -> Option<Instant> {
+        let (_, typed_at) = self.last_typed?;
+        Some(typed_at + config.reveal_last_for?)
+    }
+
+    fn revealed_offset(&self, config: &ObscureText) -> Option<usize> {
+        match self.reveal_deadline(config) {
+            Some(deadline) if Instant::now() < deadline => self.last_typed.map(|(o, _)| o),
+            _ => None,
+        }
+    }
+
+    /// Text which should be displayed in place of `text`.
+    pub fn display_text(&self, config: &ObscureText, text: &str) -> String {
+        if self.visible {
+            return text.to_owned();
+        }
+
+        let revealed = self.revealed_offset(config);
+
+        text.char_indices()
+            .map(|(offset, c)| {
+                if Some(offset) == revealed {
+                    c
+                } else {
+                    config.character
+                }
+            })
+            .collect()
+    }
+
+    /// Converts a byte offset in the original text to a byte offset in the
+    /// text returned by [`display_text`](Self::display_text).
+    pub fn display_offset(&self, config: &ObscureText, text: &str, offset: usize) -> usize {
+        if self.visible {
+            return offset;
+        }
+
+        let revealed = self.revealed_offset(config);
+
+        text.char_indices()
+            .take_while(|(o, _)| *o < offset)
+            .map(|(o, c)| {
+                if Some(o) == revealed {
+                    c.len_utf8()
+                } else {
+                    config.character.len_utf8()
+                }
+            })
+            .sum()
+    }
+}
+
+/// Button showing an eye icon, which toggles visibility of obscured text.
+#[derive(RenderWidget, Builder)]
+pub struct ObscureToggle<F: Fn()> {
+    /// Whether the text is currently obscured. The icon is crossed out if so.
+    pub obscured: bool,
+    pub color: Color,
+    pub size: f64,
+    pub on_toggle: F,
+}
+
+impl ObscureToggle<fn()> {
+    pub fn builder() -> Self {
+        Self {
+            obscured: true,
+            color: Color::grey8(120),
+            size: 24.,
+            on_toggle: || {},
+        }
+    }
+}
+
+impl<F: Fn()> RenderWidget for ObscureToggle<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(Size::new(self.size, self.size))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let s = self.size;
+        let (x, y) = (
+            offset.x + (size.width - s) / 2.,
+            offset.y + (size.height - s) / 2.,
+        );
+        let stroke_width = (s / 12.).max(1.);
+
+        // Eye outline.
+        let mut eye = BezPath::new();
+        eye.move_to((x + s * 0.08, y + s * 0.5));
+        eye.quad_to((x + s * 0.5, y + s * 0.05), (x + s * 0.92, y + s * 0.5));
+        eye.quad_to((x + s * 0.5, y + s * 0.95), (x + s * 0.08, y + s * 0.5));
+        eye.close_path();
+
+        canvas.stroke(eye, &self.color, stroke_width);
+        canvas.fill(
+            Circle::new((x + s * 0.5, y + s * 0.5), s * 0.14),
+            &self.color,
+        );
+
+        if self.obscured {
+            let slash = Line::new((x + s * 0.15, y + s * 0.15), (x + s * 0.85, y + s * 0.85));
+            canvas.stroke(slash, &self.color, stroke_width);
+        }
+    }
+}
+
+impl<F: Fn()> HitTest for ObscureToggle<F> {
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        if let PointerEvent::PointerUp(e) = event {
+            if cx.layout_box().contains(e.0.pos) {
+                (self.on_toggle)();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config(reveal_last_for: Option<Duration>) -> ObscureText {
+        ObscureText {
+            character: '*',
+            reveal_last_for,
+            allow_paste: false,
+        }
+    }
+
+    #[test]
+    fn text_is_masked() {
+        let config = config(Some(Duration::from_secs(3600)));
+        let mut state = ObscureState::default();
+
+        assert_eq!(state.display_text(&config, "pä55"), "****");
+        // 'ä' takes two bytes, but is displayed as a single byte.
+        assert_eq!(state.display_offset(&config, "pä55", 3), 2);
+
+        // Last typed character stays visible for a while.
+        state.on_char_typed(1);
+        assert_eq!(state.display_text(&config, "pä55"), "*ä**");
+        assert_eq!(state.display_offset(&config, "pä55", 3), 3);
+
+        state.on_edit();
+        assert_eq!(state.display_text(&config, "pä55"), "****");
+
+        // Typed characters are never shown without `reveal_last_for`.
+        let config = ObscureText {
+            reveal_last_for: None,
+            ..config
+        };
+        state.on_char_typed(1);
+        assert_eq!(state.display_text(&config, "pä55"), "****");
+    }
+
+    #[test]
+    fn visibility_is_toggled() {
+        let config = config(Some(Duration::from_secs(3600)));
+        let mut state = ObscureState::default();
+        assert!(!state.can_copy());
+        assert!(!state.can_paste(&config));
+
+        state.toggle_visibility();
+        assert!(state.is_visible());
+        assert_eq!(state.display_text(&config, "pass"), "pass");
+        assert_eq!(state.display_offset(&config, "pass", 2), 2);
+        assert!(state.can_copy());
+        assert!(state.can_paste(&config));
+
+        // Setting the current visibility doesn't toggle it.
+        state.set_visible(true);
+        assert!(state.is_visible());
+
+        // Characters typed while visible aren't revealed once obscured again.
+        state.on_char_typed(0);
+        state.set_visible(false);
+        assert_eq!(state.display_text(&config, "pass"), "****");
+    }
+}
@@ -0,0 +1,155 @@
+//! Text drawing for custom painters.
+//!
+//! Paragraphs are laid out the same way as by the [`Text`](super::Text) widget
+//! (including direction detection, alignment and the [`TextLayoutCache`]), so
+//! text drawn by a painter looks the same as text next to it drawn by widgets.
+//!
+//! ```ignore
+//! let label = Paragraph::builder("Revenue").size(12.).max_width(80.).build();
+//!
+//! canvas.draw_paragraph(&label, (x, y - label.size().height));
+//! ```
+
+use std::rc::Rc;
+
+use druid_shell::piet::{FontFamily, FontWeight, PietTextLayout, TextLayout};
+use frui::render::*;
+
+use super::{StyledText, TextAlign, TextDirection, TextLayoutState, TextOverflow, TextStyle};
+
+/// Shaped text, ready to be drawn. Cloning a paragraph is cheap, so it can be
+/// kept (e.g. in render state) and drawn on every frame.
+#[derive(Clone)]
+pub struct Paragraph {
+    state: Rc<TextLayoutState>,
+}
+
+impl Paragraph {
+    pub fn builder(text: impl Into<String>) -> ParagraphBuilder {
+        ParagraphBuilder {
+            text: text.into(),
+            style: TextStyle::default(),
+            text_direction: None,
+            text_align: TextAlign::Start,
+            max_width: f64::INFINITY,
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.state.size()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.layout().line_count()
+    }
+
+    /// Distance from the top of the paragraph to the baseline of its first
+    /// line.
+    pub fn first_baseline(&self) -> f64 {
+        self.state.first_baseline()
+    }
+
+    /// Underlying layout, for querying hit test or caret positions. It's
+    /// drawn at [`layout_origin`](Self::layout_origin).
+    pub fn layout(&self) -> &PietTextLayout {
+        self.state.text_layout()
+    }
+
+    /// Position of the underlying layout of the paragraph drawn at `pos`.
+    /// Lines which aren't aligned to the left are laid out within
+    /// `max_width`, of which the paragraph only takes the part covered by
+    /// text.
+    pub fn layout_origin(&self, pos: impl Into<Point>) -> Point {
+        let pos = pos.into();
+        self.state.layout_origin(&Offset::new(pos.x, pos.y))
+    }
+}
+
+pub struct ParagraphBuilder {
+    text: String,
+    style: TextStyle,
+    text_direction: Option<TextDirection>,
+    text_align: TextAlign,
+    max_width: f64,
+}
+
+impl ParagraphBuilder {
+    pub fn size(mut self, size: f64) -> Self {
+        self.style.size = Some(size);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.style.weight = Some(weight);
+        self
+    }
+
+    pub fn family(mut self, family: FontFamily) -> Self {
+        self.style.family = Some(family);
+        self
+    }
+
+    /// Properties of `style` which are set override the ones set before.
+    pub fn style(mut self, style: &TextStyle) -> Self {
+        self.style = self.style.merge(style);
+        self
+    }
+
+    /// If not set, direction is detected from the first strong character of
+    /// the text, falling back to left-to-right.
+    pub fn text_direction(mut self, text_direction: TextDirection) -> Self {
+        self.text_direction = Some(text_direction);
+        self
+    }
+
+    pub fn text_align(mut self, text_align: TextAlign) -> Self {
+        self.text_align = text_align;
+        self
+    }
+
+    /// Width at which lines are wrapped.
+    pub fn max_width(mut self, max_width: f64) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    pub fn build(self) -> Paragraph {
+        let style = self.style.resolve();
+
+        let mut styled = StyledText::new(style.clone());
+        styled.push(&self.text, style);
+
+        let direction = self.text_direction.or(TextDirection::detect(&self.text));
+
+        let state = TextLayoutState::layout(
+            &styled,
+            direction.unwrap_or_default(),
+            self.text_align,
+            self.max_width,
+            None,
+            TextOverflow::Visible,
+        );
+
+        Paragraph {
+            state: Rc::new(state),
+        }
+    }
+}
+
+/// Drawing of [`Paragraph`]s on a [`Canvas`].
+pub trait CanvasTextExt {
+    /// Draws `paragraph` with its top left corner at `pos`.
+    fn draw_paragraph(&mut self, paragraph: &Paragraph, pos: impl Into<Point>);
+}
+
+impl CanvasTextExt for Canvas<'_> {
+    fn draw_paragraph(&mut self, paragraph: &Paragraph, pos: impl Into<Point>) {
+        let pos = pos.into();
+        paragraph.state.paint(self, &Offset::new(pos.x, pos.y));
+    }
+}
@@ -0,0 +1,451 @@
+use std::ops::Range;
+
+use druid_shell::piet::{
+    kurbo::Point, FontStyle, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout,
+    TextLayoutBuilder,
+};
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::{Directionality, MediaQuery};
+
+use super::{
+    decoration, DefaultTextStyle, StyledText, TextAlign, TextDecorationSpan, TextDirection,
+    TextLayoutCache, TextLayoutKey, TextOverflow, TextSpan,
+};
+
+/// Displays a tree of [`TextSpan`]s, each of which can have its own style.
+///
+/// Properties which aren't set are taken from the closest
+/// [`DefaultTextStyle`] ancestor.
+///
+/// ```ignore
+/// RichText::new(
+///     TextSpan::new("Deleted ")
+///         .child(TextSpan::new("3 files").style(TextStyle::new().weight(FontWeight::BOLD))),
+/// )
+/// .max_lines(1)
+/// .overflow(TextOverflow::Ellipsis)
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct RichText {
+    text: TextSpan,
+    /// If `None`, direction is detected from the first strong character of
+    /// the text, falling back to the closest [`Directionality`] ancestor.
+    text_direction: Option<TextDirection>,
+    text_align: Option<TextAlign>,
+    max_lines: Option<usize>,
+    overflow: Option<TextOverflow>,
+}
+
+impl RichText {
+    pub fn new(text: TextSpan) -> Self {
+        RichText {
+            text,
+            text_direction: None,
+            text_align: None,
+            max_lines: None,
+            overflow: None,
+        }
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl RenderState for RichText {
+    type State = TextLayoutState;
+
+    fn create_state(&self) -> Self::State {
+        TextLayoutState::new()
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl RenderWidget for RichText {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        let state = self.layout_text(cx, constraints.biggest().width);
+
+        let text_size = state.size();
+        *cx.render_state_mut() = state;
+
+        constraints.constrain(text_size)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.render_state().paint(canvas, offset);
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl Baseline for RichText {
+    fn distance_to_baseline(&self, cx: &LayoutCx<Self>) -> Option<f64> {
+        Some(cx.render_state().first_baseline())
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl Intrinsics for RichText {
+    fn min_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        // See `Text`.
+        self.layout_text(cx, 0.).size().width
+    }
+
+    fn max_intrinsic_width(&self, cx: &LayoutCx<Self>, _: f64) -> f64 {
+        self.layout_text(cx, f64::INFINITY).size().width
+    }
+
+    fn min_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.layout_text(cx, width).size().height
+    }
+
+    fn max_intrinsic_height(&self, cx: &LayoutCx<Self>, width: f64) -> f64 {
+        self.layout_text(cx, width).size().height
+    }
+}
+
+#[cfg(not(feature = "miri"))]
+impl RichText {
+    fn layout_text(&self, cx: &LayoutCx<Self>, max_width: f64) -> TextLayoutState {
+        let default = DefaultTextStyle::of(cx);
+        let mut styled = self.text.flatten(&default.style);
+        styled.scale(MediaQuery::of_layout(cx).text_scale_factor);
+
+        let direction = self
+            .text_direction
+            .or(TextDirection::detect(&styled.text))
+            .unwrap_or_else(|| Directionality::of_or_default(cx));
+
+        TextLayoutState::layout(
+            &styled,
+            direction,
+            self.text_align.unwrap_or(default.text_align),
+            max_width,
+            self.max_lines.or(default.max_lines),
+            self.overflow.unwrap_or(default.overflow),
+        )
+    }
+}
+
+#[cfg(feature = "miri")]
+impl RenderState for RichText {
+    type State = super::TextRenderState;
+
+    fn create_state(&self) -> Self::State {
+        super::TextRenderState([1; 30])
+    }
+}
+
+#[cfg(feature = "miri")]
+impl RenderWidget for RichText {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.smallest()
+    }
+
+    fn paint(&self, _: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {}
+}
+
+/// Laid out text of [`Text`](super::Text) and [`RichText`] widgets.
+#[doc(hidden)]
+#[cfg(not(feature = "miri"))]
+pub struct TextLayoutState {
+    layout: PietTextLayout,
+    size: Size,
+    /// Horizontal position of the layout relative to the widget. Lines which
+    /// aren't aligned to the left are laid out within the maximal width, of
+    /// which the widget only takes the part covered by text.
+    x: f64,
+    /// Decorated ranges of the text, with the font size and color of the
+    /// text they decorate.
+    decorations: Vec<(TextDecorationSpan, f64, Color)>,
+}
+
+#[cfg(not(feature = "miri"))]
+impl TextLayoutState {
+    pub(crate) fn new() -> Self {
+        TextLayoutState {
+            layout: TEXT_FACTORY.with(|f| f.get().new_text_layout("").build().unwrap()),
+            size: Size::ZERO,
+            x: 0.,
+            decorations: Vec::new(),
+        }
+    }
+
+    /// Lays out `styled` text, reusing a cached layout if possible.
+    pub(crate) fn layout(
+        styled: &StyledText,
+        direction: TextDirection,
+        text_align: TextAlign,
+        max_width: f64,
+        max_lines: Option<usize>,
+        overflow: TextOverflow,
+    ) -> Self {
+        let max_lines = max_lines.map(|n| n.max(1));
+
+        let detected = TextDirection::detect(&styled.text).unwrap_or(TextDirection::Ltr);
+        let alignment = text_align.to_piet(direction, detected);
+
+        let key = TextLayoutKey::styled(styled, alignment, max_width, max_lines, overflow);
+
+        let layout = TextLayoutCache::get_or_insert(key, || {
+            let layout = build_layout(styled, alignment, max_width, &[]);
+
+            match max_lines {
+                Some(n) if layout.line_count() > n => {
+                    cut_off(styled, &layout, alignment, max_width, n, overflow)
+                }
+                _ => layout,
+            }
+        });
+
+        let mut size: Size = layout.size().into();
+
+        // Overflowing lines of visible overflow don't take space.
+        if let Some(n) = max_lines.filter(|n| layout.line_count() > *n) {
+            let metric = layout.line_metric(n - 1).unwrap();
+            size.height = metric.y_offset + metric.height;
+        }
+
+        let left_aligned = match alignment {
+            TextAlignment::Start => detected == TextDirection::Ltr,
+            TextAlignment::End => detected == TextDirection::Rtl,
+            _ => false,
+        };
+
+        let mut x = 0.;
+
+        if !left_aligned && max_width.is_finite() {
+            if alignment == TextAlignment::Justified && layout.line_count() > 1 {
+                size.width = max_width;
+            } else if let Some((left, right)) = horizontal_extent(&layout) {
+                size.width = right - left;
+                x = -left;
+            }
+        }
+
+        let decorations = styled
+            .runs
+            .iter()
+            .filter_map(|(range, style)| {
+                let decoration = style.decoration.clone()?;
+                let span = TextDecorationSpan {
+                    range: range.clone(),
+                    decoration,
+                };
+
+                Some((span, style.size, style.color.clone()))
+            })
+            .collect();
+
+        TextLayoutState {
+            layout,
+            size,
+            x,
+            decorations,
+        }
+    }
+
+    pub(crate) fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Distance from the top of the text to the baseline of its first line.
+    pub(crate) fn first_baseline(&self) -> f64 {
+        self.layout
+            .line_metric(0)
+            .map_or(0., |metric| metric.baseline)
+    }
+
+    /// Adds decorations of the whole text, painted after the ones of spans.
+    pub(crate) fn decorate(&mut self, spans: &[TextDecorationSpan], size: f64, color: &Color) {
+        self.decorations
+            .extend(spans.iter().map(|span| (span.clone(), size, color.clone())));
+    }
+
+    pub(crate) fn text_layout(&self) -> &PietTextLayout {
+        &self.layout
+    }
+
+    /// Position of the layout of text painted at `offset`.
+    pub(crate) fn layout_origin(&self, offset: &Offset) -> Point {
+        Point::new(offset.x + self.x, offset.y)
+    }
+
+    pub(crate) fn paint(&self, canvas: &mut Canvas, offset: &Offset) {
+        let origin = self.layout_origin(offset);
+        let offset = Offset::new(origin.x, origin.y);
+
+        RenderContext::draw_text(canvas, &self.layout, origin);
+
+        for (span, size, color) in &self.decorations {
+            let spans = std::slice::from_ref(span);
+            decoration::paint_decorations(canvas, &self.layout, &offset, spans, *size, color);
+        }
+    }
+}
+
+/// Lays out all lines of `styled` text, reusing a cached layout if possible.
+/// Used by widgets which need the layout itself, e.g. to hit test its text.
+#[cfg(not(feature = "miri"))]
+pub(crate) fn shape_styled(
+    styled: &StyledText,
+    direction: TextDirection,
+    text_align: TextAlign,
+    max_width: f64,
+) -> PietTextLayout {
+    let detected = TextDirection::detect(&styled.text).unwrap_or(TextDirection::Ltr);
+    let alignment = text_align.to_piet(direction, detected);
+
+    let key = TextLayoutKey::styled(styled, alignment, max_width, None, TextOverflow::Visible);
+    TextLayoutCache::get_or_insert(key, || build_layout(styled, alignment, max_width, &[]))
+}
+
+#[cfg(not(feature = "miri"))]
+fn build_layout(
+    styled: &StyledText,
+    alignment: TextAlignment,
+    max_width: f64,
+    colors: &[(Range<usize>, Color)],
+) -> PietTextLayout {
+    let base = &styled.base;
+
+    TEXT_FACTORY.with(|f| {
+        let mut builder = f
+            .get()
+            .new_text_layout(styled.text.clone())
+            .alignment(alignment)
+            .font(base.family.clone(), base.size)
+            .text_color(base.color.clone())
+            .max_width(max_width);
+
+        for (range, style) in &styled.runs {
+            let font_style = match style.italic {
+                true => FontStyle::Italic,
+                false => FontStyle::Regular,
+            };
+
+            builder = builder
+                .range_attribute(range.clone(), style.family.clone())
+                .range_attribute(range.clone(), TextAttribute::FontSize(style.size))
+                .range_attribute(range.clone(), style.weight)
+                .range_attribute(range.clone(), style.color.clone())
+                .range_attribute(range.clone(), font_style);
+        }
+
+        // Added last, so that they take precedence over colors of runs.
+        for (range, color) in colors {
+            builder = builder.range_attribute(range.clone(), color.clone());
+        }
+
+        builder.build().unwrap()
+    })
+}
+
+/// Lays out text of `layout` (which has more than `max_lines` lines) cut off
+/// after `max_lines` lines.
+#[cfg(not(feature = "miri"))]
+fn cut_off(
+    styled: &StyledText,
+    layout: &PietTextLayout,
+    alignment: TextAlignment,
+    max_width: f64,
+    max_lines: usize,
+    overflow: TextOverflow,
+) -> PietTextLayout {
+    let last = layout.line_metric(max_lines - 1).unwrap();
+
+    match overflow {
+        TextOverflow::Visible => layout.clone(),
+        TextOverflow::Clip => {
+            build_layout(&styled.truncate(last.end_offset), alignment, max_width, &[])
+        }
+        TextOverflow::Ellipsis => {
+            let with_ellipsis = |end: usize| {
+                let mut text = styled.truncate(end);
+                let style = match text.runs.last() {
+                    Some((_, style)) => style.clone(),
+                    None => text.base.clone(),
+                };
+
+                text.push("…", style);
+                build_layout(&text, alignment, max_width, &[])
+            };
+
+            let ends = styled.text[last.start_offset..last.end_offset]
+                .char_indices()
+                .map(|(i, _)| last.start_offset + i)
+                .chain([last.end_offset])
+                .collect::<Vec<_>>();
+
+            // Find the longest part of the last line followed by an ellipsis
+            // which doesn't wrap onto the next line.
+            let (mut low, mut high) = (0, ends.len() - 1);
+            let mut fitting = with_ellipsis(ends[0]);
+
+            while low < high {
+                let middle = (low + high + 1) / 2;
+                let layout = with_ellipsis(ends[middle]);
+
+                if layout.line_count() <= max_lines {
+                    low = middle;
+                    fitting = layout;
+                } else {
+                    high = middle - 1;
+                }
+            }
+
+            fitting
+        }
+        TextOverflow::Fade => {
+            let text = styled.truncate(last.end_offset);
+            let layout = build_layout(&text, alignment, max_width, &[]);
+
+            let start = match layout.line_metric(max_lines - 1) {
+                Some(metric) => metric.start_offset,
+                None => return layout,
+            };
+
+            let end_x = layout.hit_test_text_position(text.text.len()).point.x;
+
+            let colors = text.text[start..]
+                .char_indices()
+                .filter_map(|(i, c)| {
+                    let i = start + i;
+                    let style = text.style_at(i)?;
+
+                    // Characters fade out over the width of about two
+                    // characters before the end of the line.
+                    let x = layout.hit_test_text_position(i).point.x;
+                    let factor = ((end_x - x).abs() / (2. * style.size)).clamp(0., 1.);
+
+                    let (r, g, b, a) = style.color.as_rgba();
+                    Some((i..i + c.len_utf8(), Color::rgba(r, g, b, a * factor)))
+                })
+                .collect::<Vec<_>>();
+
+            build_layout(&text, alignment, max_width, &colors)
+        }
+    }
+}
+
+/// Horizontal span covered by lines of `layout`, without their trailing
+/// whitespace.
+#[cfg(not(feature = "miri"))]
+fn horizontal_extent(layout: &PietTextLayout) -> Option<(f64, f64)> {
+    (0..layout.line_count())
+        .filter_map(|n| layout.line_metric(n))
+        .flat_map(|metric| {
+            let end = metric.end_offset - metric.trailing_whitespace;
+            layout.rects_for_range(metric.start_offset..end)
+        })
+        .fold(None, |extent, rect| match extent {
+            None => Some((rect.x0, rect.x1)),
+            Some((x0, x1)) => Some((rect.x0.min(x0), rect.x1.max(x1))),
+        })
+}
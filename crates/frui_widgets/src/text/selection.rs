@@ -0,0 +1,314 @@
+//! Widgets used for touch text selection: draggable selection handles, the
+//! magnifier loupe and the floating cut / copy / paste toolbar. They are shown
+//! by [`EditableText`] with [`selection_controls`](EditableText::selection_controls)
+//! set.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+use druid_shell::kurbo::{Arc, BezPath};
+use frui::app::feedback::{Feedback, FeedbackKind};
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionHandleType {
+    /// Handle marking the start of the selection. Its anchor is at the top
+    /// right corner.
+    Left,
+    /// Handle marking the end of the selection. Its anchor is at the top left
+    /// corner.
+    Right,
+    /// Handle of a collapsed selection (caret). Its anchor is at the top
+    /// center.
+    Collapsed,
+}
+
+/// Draggable handle displayed at an end of a text selection.
+///
+/// `on_drag` is called with the distance between the pointer and the point at
+/// which the handle was grabbed. The handle is expected to be moved by that
+/// distance, so that it stays under the pointer. `on_drag_end` is called once
+/// the handle is released.
+#[derive(RenderWidget, Builder)]
+pub struct SelectionHandle<F: Fn(Offset), E: Fn()> {
+    pub handle_type: SelectionHandleType,
+    pub color: Color,
+    pub radius: f64,
+    pub on_drag: F,
+    pub on_drag_end: E,
+}
+
+impl SelectionHandle<fn(Offset), fn()> {
+    pub fn builder() -> Self {
+        Self {
+            handle_type: SelectionHandleType::Collapsed,
+            color: Color::rgb8(33, 150, 243),
+            radius: 11.,
+            on_drag: |_| {},
+            on_drag_end: || {},
+        }
+    }
+}
+
+impl<F: Fn(Offset), E: Fn()> SelectionHandle<F, E> {
+    /// Point of the handle which touches the selection, relative to the
+    /// top left corner of the handle.
+    pub fn anchor(&self) -> Offset {
+        let r = self.radius;
+
+        match self.handle_type {
+            SelectionHandleType::Left => Offset::new(2. * r, 0.),
+            SelectionHandleType::Right => Offset::new(0., 0.),
+            SelectionHandleType::Collapsed => Offset::new(r, 0.),
+        }
+    }
+
+    fn handle_size(&self) -> Size {
+        let r = self.radius;
+
+        match self.handle_type {
+            SelectionHandleType::Left | SelectionHandleType::Right => Size::new(2. * r, 2. * r),
+            SelectionHandleType::Collapsed => Size::new(2. * r, r + r * SQRT_2),
+        }
+    }
+
+    fn handle_path(&self) -> BezPath {
+        let r = self.radius;
+
+        // Circle with its top right quadrant squared off.
+        let mut path = BezPath::new();
+        path.move_to((r, 0.));
+        path.line_to((2. * r, 0.));
+        path.line_to((2. * r, r));
+        path.extend(
+            Arc {
+                center: (r, r).into(),
+                radii: (r, r).into(),
+                start_angle: 0.,
+                sweep_angle: 3. * FRAC_PI_2,
+                x_rotation: 0.,
+            }
+            .append_iter(0.1),
+        );
+        path.close_path();
+
+        match self.handle_type {
+            SelectionHandleType::Left => path,
+            SelectionHandleType::Right => Affine::new([-1., 0., 0., 1., 2. * r, 0.]) * path,
+            SelectionHandleType::Collapsed => {
+                // Rotate the squared corner so that it points up.
+                Affine::translate((r, r * SQRT_2))
+                    * Affine::rotate(-FRAC_PI_4)
+                    * Affine::translate((-r, -r))
+                    * path
+            }
+        }
+    }
+}
+
+impl<F: Fn(Offset), E: Fn()> RenderState for SelectionHandle<F, E> {
+    /// Point at which the handle was grabbed.
+    type State = Option<Point>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl<F: Fn(Offset), E: Fn()> RenderWidget for SelectionHandle<F, E> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(self.handle_size())
+    }
+
+    fn paint(&self, _: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let path = Affine::translate((offset.x, offset.y)) * self.handle_path();
+
+        canvas.fill(path, &self.color);
+    }
+}
+
+impl<F: Fn(Offset), E: Fn()> HitTest for SelectionHandle<F, E> {
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(e) => {
+                *cx.render_state_mut() = Some(e.0.pos);
+
+                // Fast drags would leave the handle behind otherwise.
+                cx.capture_pointer();
+            }
+            PointerEvent::PointerMove(e) => {
+                let grab_point = *cx.render_state();
+
+                if let Some(grab_point) = grab_point {
+                    let pos = e.0.pos;
+                    (self.on_drag)(Offset::new(pos.x - grab_point.x, pos.y - grab_point.y));
+                }
+            }
+            PointerEvent::PointerUp(_) => {
+                cx.release_pointer();
+
+                if cx.render_state_mut().take().is_some() {
+                    (self.on_drag_end)();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Paints its child and a magnified copy of the area around `focal_point`
+/// above it, like the loupe shown while dragging a selection handle.
+#[derive(RenderWidget, Builder)]
+pub struct Magnifier<W: Widget> {
+    pub child: W,
+    /// Point in the child's coordinate space which gets magnified. Loupe is
+    /// not displayed if it is `None`.
+    pub focal_point: Option<Offset>,
+    pub scale: f64,
+    pub size: Size,
+    pub border_radius: f64,
+    /// Distance between the focal point and the center of the loupe.
+    pub vertical_offset: f64,
+    /// Color filling the loupe behind the magnified content.
+    pub background: Color,
+    pub border_color: Color,
+}
+
+impl Magnifier<()> {
+    pub fn builder() -> Self {
+        Self {
+            child: (),
+            focal_point: None,
+            scale: 1.25,
+            size: Size::new(77., 37.),
+            border_radius: 36.,
+            vertical_offset: 50.,
+            background: Color::WHITE,
+            border_color: Color::grey8(200),
+        }
+    }
+}
+
+impl<W: Widget> RenderWidget for Magnifier<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset);
+
+        let focal_point = match self.focal_point {
+            Some(focal_point) => *offset + focal_point,
+            None => return,
+        };
+
+        let center = Point::new(focal_point.x, focal_point.y - self.vertical_offset);
+        let loupe = DruidRect::from_center_size(center, self.size)
+            .to_rounded_rect(self.border_radius.min(self.size.height / 2.));
+
+        let magnify = Affine::translate(center.to_vec2())
+            * Affine::scale(self.scale)
+            * Affine::translate((-focal_point.x, -focal_point.y));
+
+        canvas
+            .with_save(|canvas| {
+                canvas.clip(loupe);
+                canvas.fill(loupe, &self.background);
+                canvas.transform(magnify);
+
+                // Child is painted at the same offset, so that its local offset
+                // used during hit testing stays unchanged.
+                cx.child(0).paint(canvas, offset);
+
+                Ok(())
+            })
+            .unwrap();
+
+        canvas.stroke(loupe, &self.border_color, 1.);
+    }
+}
+
+/// Floating toolbar with text editing actions. Only actions which have a
+/// callback set are displayed.
+#[derive(ViewWidget, Builder)]
+pub struct TextSelectionToolbar<CUT: Fn(), COPY: Fn(), PASTE: Fn()> {
+    pub on_cut: Option<CUT>,
+    pub on_copy: Option<COPY>,
+    pub on_paste: Option<PASTE>,
+    pub color: Color,
+}
+
+impl TextSelectionToolbar<fn(), fn(), fn()> {
+    pub fn builder() -> Self {
+        Self {
+            on_cut: None,
+            on_copy: None,
+            on_paste: None,
+            color: Color::rgb8(48, 48, 48),
+        }
+    }
+}
+
+impl<CUT: Fn(), COPY: Fn(), PASTE: Fn()> ViewWidget for TextSelectionToolbar<CUT, COPY, PASTE> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let mut actions: Vec<Box<dyn Widget + 'w>> = Vec::new();
+
+        if let Some(on_cut) = &self.on_cut {
+            actions.push(ToolbarButton::new("Cut", on_cut).boxed());
+        }
+
+        if let Some(on_copy) = &self.on_copy {
+            actions.push(ToolbarButton::new("Copy", on_copy).boxed());
+        }
+
+        if let Some(on_paste) = &self.on_paste {
+            actions.push(ToolbarButton::new("Paste", on_paste).boxed());
+        }
+
+        DecoratedBox::builder()
+            .position(DecorationPosition::Background)
+            .decoration(
+                BoxDecoration::builder()
+                    .color(self.color.clone())
+                    .border_radius(BorderRadius::circular(8.)),
+            )
+            .child(Row::builder().children(actions))
+    }
+}
+
+#[derive(ViewWidget)]
+struct ToolbarButton<F: Fn()> {
+    label: &'static str,
+    on_tap: F,
+}
+
+impl<F: Fn()> ToolbarButton<F> {
+    fn new(label: &'static str, on_tap: F) -> Self {
+        Self { label, on_tap }
+    }
+}
+
+impl<F: Fn()> ViewWidget for ToolbarButton<F> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        PointerListener::builder()
+            .on_pointer_up(|_| {
+                Feedback::perform(FeedbackKind::Click);
+                (self.on_tap)()
+            })
+            .child(
+                Padding::builder()
+                    .padding(EdgeInsets::symmetric(10., 12.))
+                    .child(Text::new(self.label).size(14.)),
+            )
+    }
+}
@@ -0,0 +1,202 @@
+use std::ops::Range;
+
+use super::{ResolvedTextStyle, TextStyle};
+
+/// Piece of styled text, followed by its children. Children inherit the
+/// style of their parent, overriding the properties they set themselves.
+///
+/// ```ignore
+/// RichText::new(
+///     TextSpan::new("Terms were ")
+///         .style(TextStyle::new().size(14.))
+///         .child(TextSpan::new("updated").style(TextStyle::new().weight(FontWeight::BOLD)))
+///         .child(TextSpan::new(" yesterday.")),
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSpan {
+    pub text: String,
+    pub style: TextStyle,
+    pub children: Vec<TextSpan>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<String>) -> Self {
+        TextSpan {
+            text: text.into(),
+            style: TextStyle::default(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn style(mut self, style: TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn child(mut self, child: TextSpan) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = TextSpan>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Text of this span and all of its descendants, without styles.
+    pub fn to_plain_text(&self) -> String {
+        let mut text = String::new();
+        self.visit(&TextStyle::default(), &mut |span, _| {
+            text.push_str(&span.text)
+        });
+        text
+    }
+
+    /// Resolves styles of all spans, starting with `base` style.
+    pub(crate) fn flatten(&self, base: &TextStyle) -> StyledText {
+        let mut styled = StyledText::new(base.merge(&self.style).resolve());
+        self.visit(base, &mut |span, style| {
+            styled.push(&span.text, style.resolve())
+        });
+        styled
+    }
+
+    /// Calls `f` with spans in order of their text, and their merged styles.
+    fn visit(&self, parent: &TextStyle, f: &mut impl FnMut(&TextSpan, &TextStyle)) {
+        let style = parent.merge(&self.style);
+        f(self, &style);
+
+        for child in &self.children {
+            child.visit(&style, f);
+        }
+    }
+}
+
+/// Text with the style of each of its byte ranges resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StyledText {
+    pub text: String,
+    /// Style of the root span, which determines e.g. the height of empty
+    /// text.
+    pub base: ResolvedTextStyle,
+    /// Consecutive ranges covering all of the text, neighbouring ranges have
+    /// different styles.
+    pub runs: Vec<(Range<usize>, ResolvedTextStyle)>,
+}
+
+impl StyledText {
+    pub fn new(base: ResolvedTextStyle) -> Self {
+        StyledText {
+            text: String::new(),
+            base,
+            runs: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, text: &str, style: ResolvedTextStyle) {
+        if text.is_empty() {
+            return;
+        }
+
+        let start = self.text.len();
+        self.text.push_str(text);
+
+        match self.runs.last_mut() {
+            Some((range, last)) if *last == style => range.end = self.text.len(),
+            _ => self.runs.push((start..self.text.len(), style)),
+        }
+    }
+
+    /// Scales font sizes of all of the text by `factor`, e.g. the text scale
+    /// factor preferred by the user.
+    pub fn scale(&mut self, factor: f64) {
+        self.base.size *= factor;
+
+        for (_, style) in &mut self.runs {
+            style.size *= factor;
+        }
+    }
+
+    /// Style of the character starting at byte `index`.
+    pub fn style_at(&self, index: usize) -> Option<&ResolvedTextStyle> {
+        self.runs
+            .iter()
+            .find(|(range, _)| range.contains(&index))
+            .map(|(_, style)| style)
+    }
+
+    /// Text up to byte `end`, without trailing whitespace.
+    pub fn truncate(&self, end: usize) -> StyledText {
+        let mut truncated = StyledText::new(self.base.clone());
+
+        for (range, style) in &self.runs {
+            let end = range.end.min(end);
+            if range.start < end {
+                truncated.push(&self.text[range.start..end], style.clone());
+            }
+        }
+
+        let trimmed = truncated.text.trim_end().len();
+        truncated.text.truncate(trimmed);
+        truncated.runs.retain_mut(|(range, _)| {
+            range.end = range.end.min(trimmed);
+            range.start < range.end
+        });
+
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flatten_merges_styles() {
+        let bold = TextStyle::new().weight(druid_shell::piet::FontWeight::BOLD);
+
+        let span = TextSpan::new("a")
+            .style(TextStyle::new().size(10.))
+            .child(TextSpan::new("b").style(bold.clone()))
+            .child(TextSpan::new("c").style(TextStyle::new().size(10.)))
+            .child(TextSpan::new("").child(TextSpan::new("d").style(bold)));
+
+        let styled = span.flatten(&TextStyle::new().size(20.).italic(true));
+
+        assert_eq!(styled.text, "abcd");
+        assert_eq!(span.to_plain_text(), "abcd");
+
+        // "a" and "c" share their style, but aren't neighbours.
+        let ranges = styled
+            .runs
+            .iter()
+            .map(|(r, _)| r.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![0..1, 1..2, 2..3, 3..4]);
+
+        let d = styled.style_at(3).unwrap();
+        assert_eq!((d.size, d.italic), (10., true));
+        assert_eq!(d.weight, druid_shell::piet::FontWeight::BOLD);
+        assert_eq!(styled.base.size, 10.);
+
+        let mut text = StyledText::new(styled.base.clone());
+        text.push("ab  ", styled.base.clone());
+
+        let truncated = text.truncate(3);
+        assert_eq!(truncated.text, "ab");
+        assert_eq!(truncated.runs[0].0, 0..2);
+    }
+
+    #[test]
+    fn scale_font_sizes() {
+        let span = TextSpan::new("a").child(TextSpan::new("b").style(TextStyle::new().size(20.)));
+
+        let mut styled = span.flatten(&TextStyle::new().size(10.));
+        styled.scale(1.5);
+
+        assert_eq!(styled.base.size, 15.);
+        assert_eq!(styled.style_at(0).unwrap().size, 15.);
+        assert_eq!(styled.style_at(1).unwrap().size, 30.);
+    }
+}
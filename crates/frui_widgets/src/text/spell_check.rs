@@ -0,0 +1,140 @@
+use std::{cell::RefCell, ops::Range, rc::Rc};
+
+use frui::{app::services::Services, prelude::*};
+
+use super::{TextDecoration, TextDecorationSpan};
+
+/// Misspelled word found by a [`SpellCheckService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellCheckSuggestion {
+    /// Byte range of the misspelled word.
+    pub range: Range<usize>,
+    /// Possible replacements, most likely first.
+    pub replacements: Vec<String>,
+}
+
+impl SpellCheckSuggestion {
+    /// Replaces the misspelled word in `text` with `replacement`.
+    pub fn apply(&self, text: &mut String, replacement: &str) {
+        text.replace_range(self.range.clone(), replacement);
+    }
+}
+
+/// Backend used by text widgets to find misspelled words, e.g. hunspell or
+/// a spell checker provided by the operating system.
+pub trait SpellCheckService {
+    /// Returns misspelled words of `text`, ordered by their position.
+    fn check(&self, text: &str) -> Vec<SpellCheckSuggestion>;
+}
+
+pub struct SpellCheck;
+
+impl SpellCheck {
+    /// Sets the service used for spell checking by all text widgets.
+    pub fn set_service(service: impl SpellCheckService + 'static) {
+        Services::register::<Box<dyn SpellCheckService>>(Box::new(service));
+        LAST_CHECK.with(|c| c.borrow_mut().take());
+    }
+
+    pub fn service() -> Option<Rc<Box<dyn SpellCheckService>>> {
+        Services::get::<Box<dyn SpellCheckService>>()
+    }
+
+    /// Checks `text` using the registered service. If no service was
+    /// registered, no words are reported as misspelled.
+    ///
+    /// Result of the last check is reused, since the same text is usually
+    /// checked by a field and its context menu.
+    pub fn check(text: &str) -> Vec<SpellCheckSuggestion> {
+        let service = match Self::service() {
+            Some(service) => service,
+            None => return Vec::new(),
+        };
+
+        let cached = LAST_CHECK.with(|c| match &*c.borrow() {
+            Some((checked, suggestions)) if checked == text => Some(suggestions.clone()),
+            _ => None,
+        });
+
+        cached.unwrap_or_else(|| {
+            let suggestions = service.check(text);
+            LAST_CHECK.with(|c| *c.borrow_mut() = Some((text.to_owned(), suggestions.clone())));
+            suggestions
+        })
+    }
+
+    /// Returns the suggestion for the misspelled word at the given byte
+    /// offset, used to offer replacements e.g. in a context menu.
+    pub fn suggestion_at(
+        suggestions: &[SpellCheckSuggestion],
+        offset: usize,
+    ) -> Option<&SpellCheckSuggestion> {
+        suggestions
+            .iter()
+            .find(|s| s.range.start <= offset && offset <= s.range.end)
+    }
+
+    pub fn misspelled_decoration() -> TextDecoration {
+        TextDecoration::wavy_underline(Color::rgb8(244, 67, 54))
+    }
+
+    pub fn decorations(suggestions: &[SpellCheckSuggestion]) -> Vec<TextDecorationSpan> {
+        suggestions
+            .iter()
+            .map(|s| TextDecorationSpan {
+                range: s.range.clone(),
+                decoration: Self::misspelled_decoration(),
+            })
+            .collect()
+    }
+}
+
+thread_local! {
+    /// Text checked last, with its misspelled words.
+    static LAST_CHECK: RefCell<Option<(String, Vec<SpellCheckSuggestion>)>> = Default::default();
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    thread_local! {
+        static CHECKS: Cell<usize> = Cell::new(0);
+    }
+
+    /// Reports every "teh" as misspelled.
+    struct Teh;
+
+    impl SpellCheckService for Teh {
+        fn check(&self, text: &str) -> Vec<SpellCheckSuggestion> {
+            CHECKS.with(|c| c.set(c.get() + 1));
+
+            text.match_indices("teh")
+                .map(|(start, word)| SpellCheckSuggestion {
+                    range: start..start + word.len(),
+                    replacements: vec!["the".into()],
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn check_with_service() {
+        assert!(SpellCheck::check("teh end").is_empty());
+
+        SpellCheck::set_service(Teh);
+
+        let suggestions = SpellCheck::check("at teh end");
+        assert_eq!(SpellCheck::check("at teh end"), suggestions);
+        assert_eq!(CHECKS.with(|c| c.get()), 1);
+
+        let suggestion = SpellCheck::suggestion_at(&suggestions, 4).unwrap();
+        assert!(SpellCheck::suggestion_at(&suggestions, 7).is_none());
+
+        let mut text = "at teh end".to_owned();
+        suggestion.apply(&mut text, &suggestion.replacements[0]);
+        assert_eq!(text, "at the end");
+    }
+}
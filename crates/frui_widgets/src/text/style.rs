@@ -0,0 +1,228 @@
+use std::ops::Deref;
+
+use druid_shell::piet::{FontFamily, FontWeight, TextAlignment};
+use frui::prelude::*;
+use frui::render::*;
+
+use crate::Theme;
+
+use super::{TextDecoration, TextDirection};
+
+/// Style of text. Unset properties are inherited from the enclosing
+/// [`TextSpan`](super::TextSpan) or [`DefaultTextStyle`].
+///
+/// ```ignore
+/// TextStyle::new().size(20.).weight(FontWeight::BOLD)
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextStyle {
+    pub color: Option<Color>,
+    pub size: Option<f64>,
+    pub weight: Option<FontWeight>,
+    pub family: Option<FontFamily>,
+    pub italic: Option<bool>,
+    pub decoration: Option<TextDecoration>,
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn weight(mut self, weight: FontWeight) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    pub fn family(mut self, family: FontFamily) -> Self {
+        self.family = Some(family);
+        self
+    }
+
+    pub fn italic(mut self, italic: bool) -> Self {
+        self.italic = Some(italic);
+        self
+    }
+
+    pub fn decoration(mut self, decoration: TextDecoration) -> Self {
+        self.decoration = Some(decoration);
+        self
+    }
+
+    /// This style with properties set in `other` replaced by them.
+    pub fn merge(&self, other: &TextStyle) -> TextStyle {
+        TextStyle {
+            color: other.color.clone().or_else(|| self.color.clone()),
+            size: other.size.or(self.size),
+            weight: other.weight.or(self.weight),
+            family: other.family.clone().or_else(|| self.family.clone()),
+            italic: other.italic.or(self.italic),
+            decoration: other.decoration.clone().or_else(|| self.decoration.clone()),
+        }
+    }
+
+    /// Fills properties which weren't set anywhere with the defaults.
+    pub(crate) fn resolve(&self) -> ResolvedTextStyle {
+        ResolvedTextStyle {
+            color: self.color.clone().unwrap_or(Color::WHITE),
+            size: self.size.unwrap_or(16.),
+            weight: self.weight.unwrap_or_default(),
+            // Layout of `FontFamily::SYSTEM_UI` is incredibly slow. Other fonts
+            // seem to render just fine. This issue is related to Piet.
+            //
+            // For now, the default will be `FontFamily::MONOSPACE`.
+            family: self.family.clone().unwrap_or(FontFamily::MONOSPACE),
+            italic: self.italic.unwrap_or(false),
+            decoration: self.decoration.clone(),
+        }
+    }
+}
+
+/// [`TextStyle`] with all properties set.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ResolvedTextStyle {
+    pub color: Color,
+    pub size: f64,
+    pub weight: FontWeight,
+    pub family: FontFamily,
+    pub italic: bool,
+    pub decoration: Option<TextDecoration>,
+}
+
+/// Horizontal alignment of lines of text within the width of the widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    Left,
+    Right,
+    Center,
+    /// Stretches lines (except the last one) to the full width.
+    Justify,
+    /// Left for left-to-right text, right otherwise.
+    #[default]
+    Start,
+    /// Right for left-to-right text, left otherwise.
+    End,
+}
+
+impl TextAlign {
+    /// Alignment of text laid out in `direction` by the text layout, which
+    /// aligns relative to `detected` direction of the first strong character
+    /// of the text instead.
+    pub(crate) fn to_piet(
+        self,
+        direction: TextDirection,
+        detected: TextDirection,
+    ) -> TextAlignment {
+        let left = match self {
+            TextAlign::Center => return TextAlignment::Center,
+            TextAlign::Justify => return TextAlignment::Justified,
+            TextAlign::Left => true,
+            TextAlign::Right => false,
+            TextAlign::Start => direction == TextDirection::Ltr,
+            TextAlign::End => direction == TextDirection::Rtl,
+        };
+
+        match left == (detected == TextDirection::Ltr) {
+            true => TextAlignment::Start,
+            false => TextAlignment::End,
+        }
+    }
+}
+
+/// How text exceeding its maximal number of lines is cut off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextOverflow {
+    /// Lines after the last one are left out.
+    #[default]
+    Clip,
+    /// End of the last line is replaced with an ellipsis ("…").
+    Ellipsis,
+    /// End of the last line fades out.
+    Fade,
+    /// All lines are painted, overflowing the widget.
+    Visible,
+}
+
+/// Style, alignment and overflow of [`Text`](super::Text) and
+/// [`RichText`](super::RichText) descendants which don't set their own.
+///
+/// Unlike styles of nested text spans, nested default text styles aren't
+/// merged: the closest one replaces all others. Properties it doesn't set are
+/// taken from the body style of the [`Theme`](crate::Theme).
+///
+/// ```ignore
+/// DefaultTextStyle::builder()
+///     .style(TextStyle::new().size(14.).color(Color::grey(0.8)))
+///     .max_lines(2)
+///     .overflow(TextOverflow::Ellipsis)
+///     .child(Card)
+/// ```
+#[derive(InheritedWidget, Builder)]
+pub struct DefaultTextStyle<W: Widget> {
+    pub child: W,
+    pub style: TextStyle,
+    pub text_align: TextAlign,
+    pub max_lines: Option<usize>,
+    pub overflow: TextOverflow,
+}
+
+impl DefaultTextStyle<()> {
+    pub fn builder() -> Self {
+        DefaultTextStyle {
+            child: (),
+            style: TextStyle::default(),
+            text_align: TextAlign::default(),
+            max_lines: None,
+            overflow: TextOverflow::default(),
+        }
+    }
+
+    /// Data of the closest [`DefaultTextStyle`] ancestor, or the defaults if
+    /// there is none, on top of the body style of the theme.
+    pub fn of<T>(cx: &LayoutCx<T>) -> DefaultTextStyleData {
+        let state = cx.depend_on_inherited_widget::<Self>();
+        let mut data: DefaultTextStyleData = state
+            .map(|s| s.as_ref().deref().clone())
+            .unwrap_or_default();
+
+        data.style = Theme::of_layout(cx).text_theme.body.merge(&data.style);
+        data
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DefaultTextStyleData {
+    pub style: TextStyle,
+    pub text_align: TextAlign,
+    pub max_lines: Option<usize>,
+    pub overflow: TextOverflow,
+}
+
+impl<W: Widget> WidgetState for DefaultTextStyle<W> {
+    type State = DefaultTextStyleData;
+
+    fn create_state(&self) -> Self::State {
+        DefaultTextStyleData {
+            style: self.style.clone(),
+            text_align: self.text_align,
+            max_lines: self.max_lines,
+            overflow: self.overflow,
+        }
+    }
+}
+
+impl<W: Widget> InheritedWidget for DefaultTextStyle<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
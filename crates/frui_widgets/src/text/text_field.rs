@@ -0,0 +1,252 @@
+use std::ops::Range;
+
+use frui::{app::text_input::Selection, prelude::*};
+
+use crate::*;
+
+/// [`EditableText`] with a border, a placeholder shown while the field is
+/// empty and a [`TextContextMenu`] with clipboard actions. With
+/// [`spell_check`](Self::spell_check) on, the menu also offers replacements
+/// of the misspelled word at the caret. Obscured fields show an
+/// [`ObscureToggle`] after the text, which reveals it.
+///
+/// Context menu is shown in the closest [`Overlay`] ancestor.
+///
+/// ```ignore
+/// TextField::builder()
+///     .placeholder("Search")
+///     .on_changed(|query: &str| search(query))
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct TextField<C: Fn(&str), S: Fn(&str)> {
+    /// If `None`, the value is kept by the widget itself.
+    pub controller: Option<TextEditingController>,
+    pub placeholder: String,
+    pub style: TextStyle,
+    /// See [`EditableText::max_lines`].
+    pub max_lines: Option<usize>,
+    pub obscure: Option<ObscureText>,
+    /// Whether obscured fields show an [`ObscureToggle`].
+    pub obscure_toggle: bool,
+    pub autofocus: bool,
+    /// See [`EditableText::focus_node`].
+    pub focus_node: Option<FocusNode>,
+    /// If `None`, [`ThemeData::input_padding`] is used.
+    pub padding: Option<EdgeInsets>,
+    /// Background color. If `None`, the surface color of the [`Theme`] is
+    /// used.
+    pub color: Option<Color>,
+    /// If `None`, the outline color of the [`Theme`] is used.
+    pub border_color: Option<Color>,
+    /// If `None`, a faded text color of the [`Theme`] is used.
+    pub placeholder_color: Option<Color>,
+    /// See [`EditableText::spell_check`].
+    pub spell_check: bool,
+    /// See [`EditableText::selection_controls`].
+    pub selection_controls: bool,
+    pub on_changed: C,
+    pub on_submitted: S,
+}
+
+impl TextField<fn(&str), fn(&str)> {
+    pub fn builder() -> Self {
+        TextField {
+            controller: None,
+            placeholder: String::new(),
+            style: TextStyle::default(),
+            max_lines: Some(1),
+            obscure: None,
+            obscure_toggle: true,
+            autofocus: false,
+            focus_node: None,
+            padding: None,
+            color: None,
+            border_color: None,
+            placeholder_color: None,
+            spell_check: false,
+            selection_controls: false,
+            on_changed: |_| {},
+            on_submitted: |_| {},
+        }
+    }
+}
+
+impl<C: Fn(&str), S: Fn(&str)> TextField<C, S> {
+    /// See [`EditableText::multiline`].
+    pub fn multiline(mut self) -> Self {
+        self.max_lines = None;
+        self
+    }
+}
+
+pub struct TextFieldState {
+    /// Controller used if none was passed.
+    fallback: TextEditingController,
+    listener: ControllerListener<TextEditingController>,
+    /// Whether obscured text was revealed with the toggle.
+    obscure: ObscureState,
+}
+
+impl<C: Fn(&str), S: Fn(&str)> WidgetState for TextField<C, S> {
+    type State = TextFieldState;
+
+    fn create_state(&self) -> Self::State {
+        TextFieldState {
+            fallback: TextEditingController::new(),
+            listener: ControllerListener::default(),
+            obscure: ObscureState::default(),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().listener.unlisten();
+    }
+}
+
+impl<C: Fn(&str), S: Fn(&str)> ViewWidget for TextField<C, S> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        let controller = match &self.controller {
+            Some(controller) => controller.clone(),
+            None => state.fallback.clone(),
+        };
+
+        // Rebuilt to show the placeholder and to update the selection of the
+        // context menu.
+        let updater = cx.state_updater();
+        state
+            .listener
+            .listen(&controller, move || updater.update(|_| {}));
+
+        let value = controller.value();
+        let obscure = state.obscure.clone();
+        let theme = Theme::of(cx);
+        let scheme = &theme.color_scheme;
+
+        let placeholder_color = self
+            .placeholder_color
+            .clone()
+            .unwrap_or(scheme.on_surface.with_alpha(0.6));
+
+        let placeholder = match value.text.is_empty() {
+            true => RichText::new(
+                TextSpan::new(self.placeholder.as_str())
+                    .style(self.style.clone().color(placeholder_color.clone())),
+            )
+            .max_lines(1)
+            .overflow(TextOverflow::Ellipsis)
+            .boxed(),
+            false => ().boxed(),
+        };
+
+        let mut field = EditableText::builder()
+            .controller(controller.clone())
+            .style(
+                TextStyle::new()
+                    .color(scheme.on_surface.clone())
+                    .merge(&self.style),
+            )
+            .autofocus(self.autofocus)
+            .on_changed(|text: &str| (self.on_changed)(text))
+            .on_submitted(|text: &str| (self.on_submitted)(text));
+        field.max_lines = self.max_lines;
+        field.obscure = self.obscure.clone();
+        field.obscure_visible = obscure.is_visible();
+        field.focus_node = self.focus_node.clone();
+        field.spell_check = self.spell_check;
+        field.selection_controls = self.selection_controls;
+
+        let misspelled = match self.spell_check && self.obscure.is_none() {
+            true => {
+                let suggestions = SpellCheck::check(&value.text);
+                SpellCheck::suggestion_at(&suggestions, value.selection.active).cloned()
+            }
+            false => None,
+        };
+
+        let edit = {
+            let controller = controller.clone();
+
+            move |text: String| {
+                let mut value = controller.value();
+                value.replace_selection(&text);
+                controller.set_value(value);
+
+                (self.on_changed)(&controller.text());
+            }
+        };
+
+        let replace_range = {
+            let controller = controller.clone();
+
+            move |range: Range<usize>, text: String| {
+                let mut value = controller.value();
+                value.replace_range(range, &text);
+                controller.set_value(value);
+
+                (self.on_changed)(&controller.text());
+            }
+        };
+
+        let select_all = move || {
+            let len = controller.value().text.len();
+            controller.set_selection(Selection::new(0, len));
+        };
+
+        // Fields which can't be copied from or pasted into are handled the
+        // same as by `EditableText` itself.
+        let (can_copy, can_paste) = match &self.obscure {
+            Some(config) => (obscure.can_copy(), obscure.can_paste(config)),
+            None => (true, true),
+        };
+
+        let input = Stack::builder().children((placeholder, field));
+
+        let input = match (&self.obscure, self.obscure_toggle) {
+            (Some(_), true) => Row::builder()
+                .cross_axis_alignment(CrossAxisAlignment::Center)
+                .children((
+                    Expanded::new(input),
+                    ObscureToggle::builder()
+                        .obscured(!obscure.is_visible())
+                        .color(placeholder_color)
+                        .on_toggle(move || cx.state_mut().obscure.toggle_visibility()),
+                ))
+                .boxed(),
+            _ => input.boxed(),
+        };
+
+        let mut menu = TextContextMenu::builder()
+            .selection(match can_copy {
+                true => value.selected_text().to_owned(),
+                false => String::new(),
+            })
+            .pastable(can_paste)
+            .on_replace_selection(edit)
+            .on_replace_range(replace_range)
+            .on_select_all(select_all)
+            .child(
+                DecoratedBox::builder()
+                    .position(DecorationPosition::Background)
+                    .decoration(
+                        BoxDecoration::builder()
+                            .color(self.color.clone().unwrap_or(scheme.surface.clone()))
+                            .border_radius(BorderRadius::circular(4.))
+                            .border(BoxBorder::all(
+                                self.border_color.clone().unwrap_or(scheme.outline.clone()),
+                                1.,
+                                BorderStyle::Solid,
+                            )),
+                    )
+                    .child(
+                        Padding::builder()
+                            .padding(self.padding.unwrap_or(theme.input_padding))
+                            .child(input),
+                    ),
+            );
+        menu.misspelled = misspelled;
+
+        menu
+    }
+}
@@ -0,0 +1,243 @@
+//! Colors, text styles and spacing shared by built-in widgets.
+//!
+//! Widgets which have a color or style property usually leave it unset by
+//! default and take it from the closest [`Theme`] instead. An application
+//! picks between [`ThemeData::light`] and [`ThemeData::dark`] (or its own
+//! data) at the root, and can wrap any subtree in another [`Theme`] to
+//! override it.
+
+use druid_shell::piet::{FontFamily, FontWeight};
+use frui::prelude::*;
+
+pub use frui::app::metrics::Brightness;
+
+use crate::{EdgeInsets, IconThemeData, TextStyle};
+
+/// Colors of a [`ThemeData`]. Every `on_*` color is used for text and icons
+/// painted over the color of the same name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorScheme {
+    pub brightness: Brightness,
+    /// Color of prominent controls, e.g. buttons, cursors and selections.
+    pub primary: Color,
+    pub on_primary: Color,
+    /// Color of less prominent controls, e.g. toggles and sliders.
+    pub secondary: Color,
+    pub on_secondary: Color,
+    /// Color of cards, fields and menus.
+    pub surface: Color,
+    pub on_surface: Color,
+    /// Color behind all other content.
+    pub background: Color,
+    pub on_background: Color,
+    pub error: Color,
+    pub on_error: Color,
+    /// Color of borders and dividers.
+    pub outline: Color,
+}
+
+impl ColorScheme {
+    pub fn light() -> Self {
+        ColorScheme {
+            brightness: Brightness::Light,
+            primary: Color::rgb8(33, 150, 243),
+            on_primary: Color::WHITE,
+            secondary: Color::rgb8(0, 150, 136),
+            on_secondary: Color::WHITE,
+            surface: Color::WHITE,
+            on_surface: Color::grey8(0x1f),
+            background: Color::grey8(0xfa),
+            on_background: Color::grey8(0x1f),
+            error: Color::rgb8(176, 0, 32),
+            on_error: Color::WHITE,
+            outline: Color::grey8(180),
+        }
+    }
+
+    pub fn dark() -> Self {
+        ColorScheme {
+            brightness: Brightness::Dark,
+            primary: Color::rgb8(100, 181, 246),
+            on_primary: Color::grey8(0x1f),
+            secondary: Color::rgb8(77, 182, 172),
+            on_secondary: Color::grey8(0x1f),
+            surface: Color::rgb8(0x2b, 0x2e, 0x30),
+            on_surface: Color::WHITE,
+            background: Color::rgb8(0x20, 0x23, 0x24),
+            on_background: Color::WHITE,
+            error: Color::rgb8(207, 102, 121),
+            on_error: Color::grey8(0x1f),
+            outline: Color::grey8(100),
+        }
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Text styles of a [`ThemeData`], from the largest to the smallest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextTheme {
+    /// Large, short text, e.g. numbers on a dashboard.
+    pub display: TextStyle,
+    /// Headings of pages, dialogs and sections.
+    pub title: TextStyle,
+    /// Style of [`Text`](crate::Text) which doesn't set its own.
+    pub body: TextStyle,
+    /// Text of buttons, tabs and other controls.
+    pub label: TextStyle,
+}
+
+impl TextTheme {
+    /// Text theme with all styles painted in `color`.
+    pub fn new(color: Color) -> Self {
+        // Layout of `FontFamily::SYSTEM_UI` is slow, see `TextStyle::resolve`.
+        let base = TextStyle::new().color(color).family(FontFamily::MONOSPACE);
+
+        TextTheme {
+            display: base.clone().size(36.),
+            title: base.clone().size(22.).weight(FontWeight::BOLD),
+            body: base.clone().size(16.),
+            label: base.size(14.).weight(FontWeight::MEDIUM),
+        }
+    }
+}
+
+impl Default for TextTheme {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
+/// Appearance of built-in widgets, provided to them by [`Theme`].
+///
+/// ```ignore
+/// let mut theme = ThemeData::light();
+/// theme.color_scheme.primary = Color::rgb8(103, 58, 183);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeData {
+    pub color_scheme: ColorScheme,
+    pub text_theme: TextTheme,
+    /// Size and color of icons, see [`IconTheme`](crate::IconTheme).
+    pub icon_theme: IconThemeData,
+    /// Padding of the content of buttons.
+    pub button_padding: EdgeInsets,
+    /// Padding of the content of text fields.
+    pub input_padding: EdgeInsets,
+    pub divider_color: Color,
+}
+
+impl ThemeData {
+    pub fn light() -> Self {
+        Self::from_color_scheme(ColorScheme::light())
+    }
+
+    pub fn dark() -> Self {
+        Self::from_color_scheme(ColorScheme::dark())
+    }
+
+    /// Theme with defaults derived from `color_scheme`.
+    pub fn from_color_scheme(color_scheme: ColorScheme) -> Self {
+        let divider_color = match color_scheme.brightness {
+            Brightness::Light => Color::rgba8(0, 0, 0, 0x1f),
+            Brightness::Dark => Color::rgba8(0xff, 0xff, 0xff, 0x1f),
+        };
+
+        ThemeData {
+            text_theme: TextTheme::new(color_scheme.on_background.clone()),
+            icon_theme: IconThemeData {
+                color: Some(color_scheme.on_background.clone()),
+                size: Some(24.),
+            },
+            button_padding: EdgeInsets::symmetric(8., 16.),
+            input_padding: EdgeInsets::symmetric(8., 10.),
+            divider_color,
+            color_scheme,
+        }
+    }
+
+    pub fn brightness(&self) -> Brightness {
+        self.color_scheme.brightness
+    }
+}
+
+impl Default for ThemeData {
+    /// The dark theme, which matches the default window background.
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Provides [`ThemeData`] to its descendants. A nested [`Theme`] replaces the
+/// data of the enclosing one for its subtree.
+///
+/// ```ignore
+/// Theme::builder()
+///     .data(match dark_mode {
+///         true => ThemeData::dark(),
+///         false => ThemeData::light(),
+///     })
+///     .child(App)
+/// ```
+#[derive(InheritedWidget, Builder)]
+pub struct Theme<W: Widget> {
+    pub data: ThemeData,
+    pub child: W,
+}
+
+impl Theme<()> {
+    pub fn builder() -> Self {
+        Theme {
+            data: ThemeData::default(),
+            child: (),
+        }
+    }
+
+    /// Data of the closest [`Theme`] ancestor, or [`ThemeData::default`] if
+    /// there is none.
+    pub fn of<T>(cx: BuildCx<T>) -> ThemeData {
+        cx.depend_on::<ThemeData>()
+            .map_or_else(ThemeData::default, |data| (*data).clone())
+    }
+
+    /// See [`Theme::of`].
+    pub fn of_layout<T>(cx: &LayoutCx<T>) -> ThemeData {
+        cx.depend_on::<ThemeData>()
+            .map_or_else(ThemeData::default, |data| (*data).clone())
+    }
+}
+
+impl<W: Widget> InheritedWidget for Theme<W> {
+    fn build<'w>(&'w self) -> Self::Widget<'w> {
+        &self.child
+    }
+}
+
+impl<W: Widget> InheritedValue for Theme<W> {
+    type Value = ThemeData;
+
+    fn value(&self) -> &Self::Value {
+        &self.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_contrasts_with_background() {
+        for theme in [ThemeData::light(), ThemeData::dark()] {
+            let scheme = &theme.color_scheme;
+            assert_eq!(
+                theme.text_theme.body.color.as_ref(),
+                Some(&scheme.on_background)
+            );
+            assert_ne!(scheme.background, scheme.on_background);
+        }
+    }
+}
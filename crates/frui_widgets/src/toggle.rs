@@ -0,0 +1,661 @@
+//! Toggle controls: [`Checkbox`], [`Switch`] and [`Radio`].
+//!
+//! Like buttons, they are drawn from the [`Theme`], highlight themselves
+//! while hovered or focused and can be toggled with Space or Enter while
+//! focused. A toggle without `on_changed` is disabled. Toggles don't keep
+//! their value themselves: `on_changed` should store the new value and
+//! rebuild the toggle with it, after which it animates to that value.
+
+use std::{cell::Cell, time::Duration};
+
+use druid_shell::{
+    keyboard_types::{Key, KeyState},
+    kurbo::{BezPath, Circle, Line, RoundedRect},
+    piet::RenderContext,
+    Cursor, KeyEvent,
+};
+use frui::{
+    app::feedback::{Feedback, FeedbackKind},
+    prelude::*,
+    render::*,
+};
+
+use crate::*;
+
+/// Value of a [`Checkbox`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CheckboxValue {
+    #[default]
+    Unchecked,
+    Checked,
+    /// Neither checked nor unchecked, e.g. for a checkbox selecting a group
+    /// of items of which only some are selected.
+    Indeterminate,
+}
+
+impl CheckboxValue {
+    /// Value a checkbox changes to when toggled. Only tri-state checkboxes
+    /// become indeterminate.
+    pub fn next(self, tristate: bool) -> Self {
+        match self {
+            CheckboxValue::Unchecked => CheckboxValue::Checked,
+            CheckboxValue::Checked if tristate => CheckboxValue::Indeterminate,
+            CheckboxValue::Checked | CheckboxValue::Indeterminate => CheckboxValue::Unchecked,
+        }
+    }
+
+    pub fn is_checked(self) -> bool {
+        self == CheckboxValue::Checked
+    }
+}
+
+impl From<bool> for CheckboxValue {
+    fn from(checked: bool) -> Self {
+        match checked {
+            true => CheckboxValue::Checked,
+            false => CheckboxValue::Unchecked,
+        }
+    }
+}
+
+/// `None` is [`CheckboxValue::Indeterminate`].
+impl From<Option<bool>> for CheckboxValue {
+    fn from(value: Option<bool>) -> Self {
+        value.map_or(CheckboxValue::Indeterminate, CheckboxValue::from)
+    }
+}
+
+/// ```ignore
+/// Checkbox::builder()
+///     .value(state.accepted.into())
+///     .on_changed(move |value: CheckboxValue| {
+///         cx.state_mut().accepted = value.is_checked();
+///     })
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Checkbox<F: Fn(CheckboxValue)> {
+    pub value: CheckboxValue,
+    /// Whether toggling a checked checkbox makes it indeterminate (and only
+    /// then unchecked).
+    pub tristate: bool,
+    /// Called with the value the checkbox was toggled to. If `None`, the
+    /// checkbox is disabled.
+    pub on_changed: Option<F>,
+    /// Fill color of the checked box. Defaults to the primary color.
+    pub active_color: Option<Color>,
+    /// Color of the check mark.
+    pub check_color: Option<Color>,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl Checkbox<fn(CheckboxValue)> {
+    pub fn builder() -> Self {
+        Checkbox {
+            value: CheckboxValue::Unchecked,
+            tristate: false,
+            on_changed: None,
+            active_color: None,
+            check_color: None,
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+impl<F: Fn(CheckboxValue)> ViewWidget for Checkbox<F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let scheme = Theme::of(cx).color_scheme;
+        let active = self
+            .active_color
+            .clone()
+            .unwrap_or_else(|| scheme.primary.clone());
+        let check = self
+            .check_color
+            .clone()
+            .unwrap_or_else(|| scheme.on_primary.clone());
+
+        let shape = ToggleShape::Checkbox(match self.value {
+            CheckboxValue::Indeterminate => CheckMark::Dash,
+            _ => CheckMark::Check,
+        });
+
+        RawToggle {
+            shape,
+            on: self.value != CheckboxValue::Unchecked,
+            on_toggle: self
+                .on_changed
+                .as_ref()
+                .map(|f| move || f(self.value.next(self.tristate))),
+            track: (scheme.on_surface.with_alpha(0.54), active),
+            thumb: (check.clone(), check),
+            autofocus: self.autofocus,
+            focus_node: self.focus_node.clone(),
+        }
+    }
+}
+
+/// ```ignore
+/// Switch::builder()
+///     .value(state.notifications)
+///     .on_changed(move |on: bool| cx.state_mut().notifications = on)
+/// ```
+#[derive(ViewWidget, Builder)]
+pub struct Switch<F: Fn(bool)> {
+    pub value: bool,
+    /// Called with the value the switch was toggled to. If `None`, the switch
+    /// is disabled.
+    pub on_changed: Option<F>,
+    /// Color of the switch while on. Defaults to the secondary color of the
+    /// theme (or green for [`SwitchStyle::Cupertino`]).
+    pub active_color: Option<Color>,
+    /// Defaults to the style of the [`Platform`].
+    pub style: Option<SwitchStyle>,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl Switch<fn(bool)> {
+    pub fn builder() -> Self {
+        Switch {
+            value: false,
+            on_changed: None,
+            active_color: None,
+            style: None,
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+impl<F: Fn(bool)> ViewWidget for Switch<F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let scheme = Theme::of(cx).color_scheme;
+        let style = self
+            .style
+            .unwrap_or_else(|| Platform::of(cx).switch_style());
+
+        let (track, thumb) = match style {
+            SwitchStyle::Material => {
+                let active = self
+                    .active_color
+                    .clone()
+                    .unwrap_or_else(|| scheme.secondary.clone());
+                let thumb_off = match scheme.brightness {
+                    Brightness::Light => Color::grey8(0xfa),
+                    Brightness::Dark => Color::grey8(0xbd),
+                };
+
+                (
+                    (scheme.on_surface.with_alpha(0.38), active.with_alpha(0.5)),
+                    (thumb_off, active),
+                )
+            }
+            SwitchStyle::Cupertino => {
+                let active = self
+                    .active_color
+                    .clone()
+                    .unwrap_or_else(|| Color::rgb8(52, 199, 89));
+
+                (
+                    (scheme.on_surface.with_alpha(0.16), active),
+                    (Color::WHITE, Color::WHITE),
+                )
+            }
+        };
+
+        RawToggle {
+            shape: ToggleShape::Switch(style),
+            on: self.value,
+            on_toggle: self.on_changed.as_ref().map(|f| move || f(!self.value)),
+            track,
+            thumb,
+            autofocus: self.autofocus,
+            focus_node: self.focus_node.clone(),
+        }
+    }
+}
+
+/// One of a group of radio buttons, of which at most one is selected: the one
+/// whose `value` equals `group_value`.
+///
+/// ```ignore
+/// Column::builder().children((
+///     Radio::new(Plan::Free, state.plan).on_changed(move |plan| cx.state_mut().plan = plan),
+///     Radio::new(Plan::Pro, state.plan).on_changed(move |plan| cx.state_mut().plan = plan),
+/// ))
+/// ```
+#[derive(ViewWidget)]
+pub struct Radio<T: PartialEq + Clone, F: Fn(T)> {
+    pub value: T,
+    /// Value of the selected radio button of the group, if any.
+    pub group_value: Option<T>,
+    /// Called with `value` once this radio button is selected. If `None`, the
+    /// radio button is disabled.
+    pub on_changed: Option<F>,
+    /// Color of the selected radio button. Defaults to the primary color.
+    pub active_color: Option<Color>,
+    pub autofocus: bool,
+    /// See [`Focus::focus_node`].
+    pub focus_node: Option<FocusNode>,
+}
+
+impl<T: PartialEq + Clone> Radio<T, fn(T)> {
+    pub fn new(value: T, group_value: impl Into<Option<T>>) -> Self {
+        Radio {
+            value,
+            group_value: group_value.into(),
+            on_changed: None,
+            active_color: None,
+            autofocus: false,
+            focus_node: None,
+        }
+    }
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> Radio<T, F> {
+    pub fn on_changed<F2: Fn(T)>(self, on_changed: F2) -> Radio<T, F2> {
+        Radio {
+            value: self.value,
+            group_value: self.group_value,
+            on_changed: Some(on_changed),
+            active_color: self.active_color,
+            autofocus: self.autofocus,
+            focus_node: self.focus_node,
+        }
+    }
+
+    pub fn active_color(mut self, color: Color) -> Self {
+        self.active_color = Some(color);
+        self
+    }
+
+    pub fn autofocus(mut self, autofocus: bool) -> Self {
+        self.autofocus = autofocus;
+        self
+    }
+
+    pub fn focus_node(mut self, focus_node: FocusNode) -> Self {
+        self.focus_node = Some(focus_node);
+        self
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.group_value.as_ref() == Some(&self.value)
+    }
+}
+
+impl<T: PartialEq + Clone, F: Fn(T)> ViewWidget for Radio<T, F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let scheme = Theme::of(cx).color_scheme;
+        let active = self
+            .active_color
+            .clone()
+            .unwrap_or_else(|| scheme.primary.clone());
+
+        RawToggle {
+            shape: ToggleShape::Radio,
+            on: self.is_selected(),
+            on_toggle: self.on_changed.as_ref().map(|f| {
+                move || {
+                    // Selecting the selected radio button changes nothing.
+                    if !self.is_selected() {
+                        f(self.value.clone());
+                    }
+                }
+            }),
+            track: (scheme.on_surface.with_alpha(0.54), active.clone()),
+            thumb: (active.clone(), active),
+            autofocus: self.autofocus,
+            focus_node: self.focus_node.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CheckMark {
+    Check,
+    Dash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToggleShape {
+    Checkbox(CheckMark),
+    Switch(SwitchStyle),
+    Radio,
+}
+
+impl ToggleShape {
+    /// Name of the public widget, under which its taps are reported.
+    fn name(&self) -> &'static str {
+        match self {
+            ToggleShape::Checkbox(_) => "Checkbox",
+            ToggleShape::Switch(_) => "Switch",
+            ToggleShape::Radio => "Radio",
+        }
+    }
+
+    fn size(&self) -> Size {
+        match self {
+            ToggleShape::Switch(SwitchStyle::Material) => Size::new(60., 40.),
+            ToggleShape::Switch(SwitchStyle::Cupertino) => Size::new(51., 31.),
+            _ => Size::new(40., 40.),
+        }
+    }
+}
+
+const TOGGLE_DURATION: Duration = Duration::from_millis(150);
+
+/// Toggle of any kind, drawn by [`ToggleSurface`].
+#[derive(ViewWidget)]
+struct RawToggle<F: Fn()> {
+    shape: ToggleShape,
+    on: bool,
+    on_toggle: Option<F>,
+    /// Colors of the box, ring or track while off and while on.
+    track: (Color, Color),
+    /// Colors of the check mark, dot or thumb while off and while on.
+    thumb: (Color, Color),
+    autofocus: bool,
+    focus_node: Option<FocusNode>,
+}
+
+pub struct ToggleState {
+    hovered: bool,
+    focused: bool,
+    /// Position of the toggle, from 0 (off) to 1 (on).
+    position: ImplicitAnimation<f64>,
+    /// Shape the toggle had when it was last on, which is kept while it
+    /// animates to off (e.g. so that an indeterminate checkbox keeps its
+    /// dash).
+    on_shape: Cell<Option<ToggleShape>>,
+}
+
+impl<F: Fn()> WidgetState for RawToggle<F> {
+    type State = ToggleState;
+
+    fn create_state(&self) -> Self::State {
+        ToggleState {
+            hovered: false,
+            focused: false,
+            position: ImplicitAnimation::default(),
+            on_shape: Cell::new(None),
+        }
+    }
+
+    fn unmount(&self, cx: BuildCx<Self>) {
+        cx.state().position.dispose();
+    }
+}
+
+impl<F: Fn()> RawToggle<F> {
+    fn toggle(&self) {
+        if let Some(on_toggle) = &self.on_toggle {
+            Feedback::perform(FeedbackKind::Click);
+            on_toggle();
+        }
+    }
+
+    fn handle_key(&self, event: &KeyEvent) -> bool {
+        let activates = match &event.key {
+            Key::Enter => true,
+            Key::Character(c) => c == " ",
+            _ => false,
+        };
+
+        if activates && event.state == KeyState::Down && !event.repeat {
+            self.toggle();
+        }
+
+        activates
+    }
+}
+
+impl<F: Fn()> ViewWidget for RawToggle<F> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let scheme = Theme::of(cx).color_scheme;
+        let disabled = self.on_toggle.is_none();
+
+        let state = cx.state();
+        let updater = cx.state_updater();
+
+        let position = state.position.animate(
+            &if self.on { 1. } else { 0. },
+            TOGGLE_DURATION,
+            Curve::EaseInOut,
+            updater.clone(),
+        );
+
+        if self.on {
+            state.on_shape.set(Some(self.shape));
+        }
+
+        let shape = match self.on {
+            true => self.shape,
+            false => state.on_shape.get().unwrap_or(self.shape),
+        };
+
+        let (track, thumb) = match disabled {
+            true => {
+                let color = scheme.on_surface.with_alpha(0.38);
+                let thumb = match self.shape {
+                    ToggleShape::Checkbox(_) => scheme.surface.clone(),
+                    ToggleShape::Switch(_) => self.thumb.0.clone(),
+                    ToggleShape::Radio => color.clone(),
+                };
+
+                ((color.clone(), color), (thumb.clone(), thumb))
+            }
+            false => (self.track.clone(), self.thumb.clone()),
+        };
+
+        let overlay_opacity = if disabled {
+            0.
+        } else if state.focused {
+            0.12
+        } else if state.hovered {
+            0.08
+        } else {
+            0.
+        };
+
+        let overlay = match self.on {
+            true => self.track.1.clone(),
+            false => scheme.on_surface.clone(),
+        };
+
+        let surface = ToggleSurface {
+            shape,
+            position,
+            track,
+            thumb,
+            overlay: overlay.with_alpha(overlay_opacity),
+            focused: state.focused && !disabled,
+        };
+
+        drop(state);
+
+        let mut region = PointerRegion::builder()
+            .on_enter(move |_| cx.state_mut().hovered = true)
+            .on_exit(move |_| cx.state_mut().hovered = false);
+
+        if !disabled {
+            region = region.cursor(Cursor::Pointer);
+        }
+
+        let mut focus = Focus::builder()
+            .autofocus(self.autofocus)
+            .can_request_focus(!disabled)
+            .on_key(move |event: &KeyEvent| self.handle_key(event))
+            .on_focus_change(move |focused| {
+                updater.update(move |state: &mut ToggleState| state.focused = focused)
+            });
+        focus.focus_node = self.focus_node.clone();
+
+        InteractionLabel::builder().widget(self.shape.name()).child(
+            focus.child(
+                region.child(
+                    GestureDetector::builder()
+                        .on_tap(move || self.toggle())
+                        .keyboard_activation(false)
+                        .child(surface),
+                ),
+            ),
+        )
+    }
+}
+
+/// Paints a toggle in its `position`, from 0 (off) to 1 (on).
+#[derive(RenderWidget)]
+struct ToggleSurface {
+    shape: ToggleShape,
+    position: f64,
+    track: (Color, Color),
+    thumb: (Color, Color),
+    /// Hover and focus highlight.
+    overlay: Color,
+    focused: bool,
+}
+
+impl RenderWidget for ToggleSurface {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(self.shape.size())
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let center = Point::new(offset.x + size.width / 2., offset.y + size.height / 2.);
+
+        let t = self.position;
+        let track = self.track.0.lerp(&self.track.1, t);
+        let thumb = self.thumb.0.lerp(&self.thumb.1, t);
+
+        match self.shape {
+            ToggleShape::Checkbox(mark) => {
+                canvas.fill(Circle::new(center, 20.), &self.overlay);
+
+                let (x0, y0) = (center.x - 9., center.y - 9.);
+                let outline = RoundedRect::new(x0 + 1., y0 + 1., x0 + 17., y0 + 17., 1.);
+                canvas.fill(outline, &self.track.1.with_alpha(t));
+                canvas.stroke(outline, &track, 2.);
+
+                if t > 0. {
+                    let p = |x: f64, y: f64| Point::new(x0 + x * 18., y0 + y * 18.);
+
+                    let path = match mark {
+                        CheckMark::Check => {
+                            partial_polyline(&[p(0.25, 0.5), p(0.42, 0.67), p(0.77, 0.32)], t)
+                        }
+                        CheckMark::Dash => partial_polyline(&[p(0.25, 0.5), p(0.75, 0.5)], t),
+                    };
+
+                    canvas.stroke(path, &thumb, 2.);
+                }
+            }
+            ToggleShape::Radio => {
+                canvas.fill(Circle::new(center, 20.), &self.overlay);
+                canvas.stroke(Circle::new(center, 9.), &track, 2.);
+
+                if t > 0. {
+                    canvas.fill(Circle::new(center, 5. * t), &thumb);
+                }
+            }
+            ToggleShape::Switch(SwitchStyle::Material) => {
+                let track_rect = RoundedRect::new(
+                    center.x - 17.,
+                    center.y - 7.,
+                    center.x + 17.,
+                    center.y + 7.,
+                    7.,
+                );
+                let thumb_center = Point::new(center.x - 10. + 20. * t, center.y);
+
+                canvas.fill(track_rect, &track);
+                canvas.fill(Circle::new(thumb_center, 20.), &self.overlay);
+                canvas.fill(Circle::new(thumb_center, 10.), &thumb);
+            }
+            ToggleShape::Switch(SwitchStyle::Cupertino) => {
+                let radius = size.height / 2.;
+                let track_rect = RoundedRect::new(
+                    offset.x,
+                    offset.y,
+                    offset.x + size.width,
+                    offset.y + size.height,
+                    radius,
+                );
+
+                let left = offset.x + radius;
+                let right = offset.x + size.width - radius;
+                let thumb_center = Point::new(left.lerp(&right, t), center.y);
+
+                canvas.fill(track_rect, &track);
+                canvas.fill(track_rect, &self.overlay);
+                canvas.fill(Circle::new(thumb_center, radius - 2.), &thumb);
+
+                if self.focused {
+                    canvas.stroke(track_rect, &self.track.1.with_alpha(0.5), 2.);
+                }
+            }
+        }
+    }
+}
+
+/// Path along `points`, cut off after `t` (from 0 to 1) of its length.
+fn partial_polyline(points: &[Point], t: f64) -> BezPath {
+    let segments = points.windows(2).map(|p| Line::new(p[0], p[1]));
+    let mut remaining = segments.clone().map(length).sum::<f64>() * t;
+
+    let mut path = BezPath::new();
+    path.move_to(points[0]);
+
+    for segment in segments {
+        let length = length(segment);
+
+        if remaining >= length {
+            path.line_to(segment.p1);
+            remaining -= length;
+        } else {
+            path.line_to(segment.p0.lerp(segment.p1, remaining / length));
+            break;
+        }
+    }
+
+    path
+}
+
+fn length(line: Line) -> f64 {
+    line.p0.distance(line.p1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn checkbox_value_cycles() {
+        let mut value = CheckboxValue::Unchecked;
+        let mut seen = vec![];
+
+        for _ in 0..3 {
+            value = value.next(true);
+            seen.push(value);
+        }
+
+        assert_eq!(
+            seen,
+            [
+                CheckboxValue::Checked,
+                CheckboxValue::Indeterminate,
+                CheckboxValue::Unchecked
+            ]
+        );
+
+        assert_eq!(CheckboxValue::Checked.next(false), CheckboxValue::Unchecked);
+    }
+}
@@ -1,12 +1,80 @@
 use frui::prelude::*;
 use frui::render::*;
 
-#[derive(RenderWidget)]
-pub struct Transform<W: Widget>(pub Affine, pub W);
+use crate::{Alignment, Directionality, TextDirection};
+
+/// Paints `child` transformed by `transform`, without affecting its layout.
+///
+/// The transformation is applied around `alignment` of the child, e.g. its
+/// center for rotations. Pointer events are transformed back, so that they
+/// hit the child where it is painted rather than where it was laid out.
+///
+/// ```ignore
+/// Transform::rotate(std::f64::consts::FRAC_PI_4).child(Arrow)
+///
+/// Transform::builder()
+///     .transform(Affine::scale_non_uniform(-1., 1.))
+///     .alignment(Alignment::CENTER)
+///     .child(Icon)
+/// ```
+#[derive(RenderWidget, Builder)]
+pub struct Transform<W: Widget> {
+    pub child: W,
+    pub transform: Affine,
+    /// Point of the child which the transformation is applied around, its
+    /// top left corner if `None`.
+    pub alignment: Option<Alignment>,
+    /// Whether pointer events hit the child where it is painted. Otherwise
+    /// they hit it where it was laid out.
+    pub transform_hit_tests: bool,
+}
+
+impl Transform<()> {
+    pub fn builder() -> Self {
+        Transform {
+            child: (),
+            transform: Affine::IDENTITY,
+            alignment: None,
+            transform_hit_tests: true,
+        }
+    }
+
+    /// Rotates by `angle` (in radians, clockwise) around the center.
+    pub fn rotate(angle: f64) -> Self {
+        Self::builder()
+            .transform(Affine::rotate(angle))
+            .alignment(Alignment::CENTER)
+    }
+
+    /// Scales uniformly around the center.
+    pub fn scale(scale: f64) -> Self {
+        Self::builder()
+            .transform(Affine::scale(scale))
+            .alignment(Alignment::CENTER)
+    }
+
+    pub fn translate(offset: Offset) -> Self {
+        Self::builder().transform(Affine::translate((offset.x, offset.y)))
+    }
+}
+
+impl<W: Widget> Transform<W> {
+    /// Transformation in local coordinates of a child of the given size.
+    fn effective_transform(&self, size: Size) -> Affine {
+        let origin = match self.alignment {
+            Some(alignment) => alignment.along(size),
+            None => return self.transform,
+        };
+
+        Affine::translate((origin.x, origin.y))
+            * self.transform
+            * Affine::translate((-origin.x, -origin.y))
+    }
+}
 
 impl<W: Widget> RenderWidget for Transform<W> {
     fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
-        vec![&self.1]
+        vec![&self.child]
     }
 
     fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
@@ -14,8 +82,19 @@ impl<W: Widget> RenderWidget for Transform<W> {
     }
 
     fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let transform = self.effective_transform(cx.size());
+
+        // Child squashed into a line or a point isn't visible.
+        if transform.determinant() == 0. {
+            return;
+        }
+
         let r = canvas.with_save(|cv| {
-            cv.transform(self.0);
+            cv.transform(
+                Affine::translate((offset.x, offset.y))
+                    * transform
+                    * Affine::translate((-offset.x, -offset.y)),
+            );
             cx.child(0).paint(cv, offset);
 
             Ok(())
@@ -27,14 +106,142 @@ impl<W: Widget> RenderWidget for Transform<W> {
 
 impl<W: Widget> HitTest for Transform<W> {
     fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
-        if cx.layout_box().contains(point) {
-            for mut child in cx.children() {
-                if child.hit_test_with_transform(point, self.0.inverse()) {
-                    return true;
-                }
+        let transform = match self.transform_hit_tests {
+            true => self.effective_transform(cx.layout_box()),
+            false => Affine::IDENTITY,
+        };
+
+        if transform.determinant() == 0. {
+            return false;
+        }
+
+        // Transformed child may be hit outside of the layout box of this
+        // widget, so it isn't checked.
+        for mut child in cx.children() {
+            if child.hit_test_with_transform(point, transform.inverse()) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// Mirrors `child` horizontally if the text direction is right-to-left. Used
+/// for directional icons and images, like arrows pointing "forward".
+#[derive(RenderWidget, Builder)]
+pub struct MatchTextDirection<W: Widget> {
+    pub child: W,
+    /// Uses [`Directionality`] of the context if `None`.
+    pub text_direction: Option<TextDirection>,
+}
+
+impl MatchTextDirection<()> {
+    pub fn builder() -> Self {
+        MatchTextDirection {
+            child: (),
+            text_direction: None,
+        }
+    }
+}
+
+impl<W: Widget> MatchTextDirection<W> {
+    /// Mirrors across the vertical center line of a box of the given width.
+    fn mirror(width: f64) -> Affine {
+        Affine::new([-1., 0., 0., 1., width, 0.])
+    }
+}
+
+impl MatchTextDirection<()> {
+    /// Whether widgets matching the text direction are mirrored, given
+    /// `text_direction` which overrides [`Directionality`] of the context.
+    pub(crate) fn is_mirrored<T>(cx: &LayoutCx<T>, text_direction: Option<TextDirection>) -> bool {
+        Directionality::unwrap_or_default(text_direction, cx) == TextDirection::Rtl
+    }
+
+    /// Transformation mirroring a box of `size` painted at `offset`.
+    pub(crate) fn mirror_at(offset: &Offset, size: Size) -> Affine {
+        Affine::translate((offset.x, offset.y))
+            * Self::mirror(size.width)
+            * Affine::translate((-offset.x, -offset.y))
+    }
+}
+
+impl<W: Widget> RenderState for MatchTextDirection<W> {
+    /// Whether child is mirrored.
+    type State = bool;
+
+    fn create_state(&self) -> Self::State {
+        false
+    }
+}
+
+impl<W: Widget> RenderWidget for MatchTextDirection<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        *cx.render_state_mut() = MatchTextDirection::is_mirrored(cx, self.text_direction);
+
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        if !*cx.render_state() {
+            return cx.child(0).paint(canvas, offset);
+        }
+
+        let mirror = MatchTextDirection::mirror_at(offset, cx.size());
+
+        let r = canvas.with_save(|cv| {
+            cv.transform(mirror);
+            cx.child(0).paint(cv, offset);
+
+            Ok(())
+        });
+
+        if let Err(e) = r {
+            log::error!("painting mirrored child failed: {}", e);
+        }
+    }
+}
+
+impl<W: Widget> HitTest for MatchTextDirection<W> {
+    fn hit_test<'a>(&'a self, cx: &'a mut HitTestCx<Self>, point: Point) -> bool {
+        if !cx.layout_box().contains(point) {
+            return false;
+        }
+
+        let mirror = match *cx.render_state() {
+            true => Self::mirror(cx.layout_box().width),
+            false => Affine::IDENTITY,
+        };
+
+        for mut child in cx.children() {
+            if child.hit_test_with_transform(point, mirror) {
+                return true;
             }
         }
 
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotates_around_alignment() {
+        let size = Size::new(100., 50.);
+        let transform = Transform::rotate(std::f64::consts::FRAC_PI_2).effective_transform(size);
+
+        let center = transform * Point::new(50., 25.);
+        assert!((center - Point::new(50., 25.)).hypot() < 1e-9);
+
+        // Top left corner swings around the center.
+        let top_left = transform * Point::new(0., 0.);
+        assert!((top_left - Point::new(75., -25.)).hypot() < 1e-9);
+    }
+}
@@ -52,6 +52,11 @@ pub use frui::prelude::Widget;
 /// [`wlist`]: (crate::wlist)
 pub trait WidgetList {
     fn get(&self) -> Vec<&dyn Widget>;
+
+    /// Number of widgets in the list, without collecting them.
+    fn len(&self) -> usize {
+        self.get().len()
+    }
 }
 
 /// See [`WidgetList`] documentation for usage.
@@ -68,16 +73,28 @@ impl<W: Widget> WidgetList for [W] {
     fn get(&self) -> Vec<&dyn Widget> {
         self.iter().map(|e| e as &dyn Widget).collect()
     }
+
+    fn len(&self) -> usize {
+        <[W]>::len(self)
+    }
 }
 
 impl<W: Widget> WidgetList for Vec<W> {
     fn get(&self) -> Vec<&dyn Widget> {
         self.iter().map(|e| e as &dyn Widget).collect()
     }
+
+    fn len(&self) -> usize {
+        <[W]>::len(self)
+    }
 }
 
 impl<W: Widget, const N: usize> WidgetList for [W; N] {
     fn get(&self) -> Vec<&dyn Widget> {
         self.iter().map(|e| e as &dyn Widget).collect()
     }
+
+    fn len(&self) -> usize {
+        <[W]>::len(self)
+    }
 }
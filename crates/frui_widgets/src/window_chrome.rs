@@ -0,0 +1,312 @@
+//! Widgets for drawing custom window chrome in windows created without
+//! platform decorations (see [`WindowOptions::decorations`]).
+//!
+//! [`WindowOptions::decorations`]: frui::app::window::WindowOptions::decorations
+
+use druid_shell::{kurbo::Line, MouseButton};
+use frui::{
+    app::window::{Cursor, Window, WindowState},
+    prelude::*,
+    render::*,
+};
+
+use crate::*;
+
+/// Moves the window when `child` is dragged with the primary button. Double
+/// click toggles maximization of the window.
+#[derive(ViewWidget, Builder)]
+pub struct WindowDragArea<W: Widget> {
+    pub child: W,
+}
+
+impl WindowDragArea<()> {
+    pub fn builder() -> Self {
+        WindowDragArea { child: () }
+    }
+}
+
+impl<W: Widget> ViewWidget for WindowDragArea<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        PointerListener::builder()
+            .on_pointer_down(|e| {
+                if e.0.button != MouseButton::Left {
+                    return;
+                }
+
+                if e.0.count == 2 {
+                    Window::toggle_maximized();
+                } else {
+                    Window::begin_drag();
+                }
+            })
+            .child(&self.child)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionButtonKind {
+    Minimize,
+    Maximize,
+    Close,
+}
+
+/// Title bar button which minimizes, maximizes (or restores) or closes the
+/// window.
+#[derive(RenderWidget, Builder)]
+pub struct CaptionButton {
+    pub kind: CaptionButtonKind,
+    pub color: Color,
+    /// Size of the glyph. The button itself is three times wider and twice as
+    /// high.
+    pub size: f64,
+}
+
+impl CaptionButton {
+    pub fn builder() -> Self {
+        CaptionButton {
+            kind: CaptionButtonKind::Close,
+            color: Color::grey8(200),
+            size: 10.,
+        }
+    }
+
+    pub fn minimize() -> Self {
+        Self::builder().kind(CaptionButtonKind::Minimize)
+    }
+
+    pub fn maximize() -> Self {
+        Self::builder().kind(CaptionButtonKind::Maximize)
+    }
+
+    pub fn close() -> Self {
+        Self::builder().kind(CaptionButtonKind::Close)
+    }
+}
+
+impl RenderWidget for CaptionButton {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(Size::new(self.size * 3., self.size * 2.))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.size();
+        let s = self.size;
+        let (x, y) = (
+            offset.x + (size.width - s) / 2.,
+            offset.y + (size.height - s) / 2.,
+        );
+
+        match self.kind {
+            CaptionButtonKind::Minimize => {
+                let line = Line::new((x, y + s * 0.5), (x + s, y + s * 0.5));
+                canvas.stroke(line, &self.color, 1.);
+            }
+            CaptionButtonKind::Maximize => {
+                let maximized = Window::state() == Some(WindowState::Maximized);
+
+                if maximized {
+                    // Two overlapping windows, same as the platform restore
+                    // button.
+                    let back = DruidRect::new(x + s * 0.25, y, x + s, y + s * 0.75);
+                    canvas.stroke(back, &self.color, 1.);
+                }
+
+                let front = match maximized {
+                    true => DruidRect::new(x, y + s * 0.25, x + s * 0.75, y + s),
+                    false => DruidRect::new(x, y, x + s, y + s),
+                };
+
+                canvas.stroke(front, &self.color, 1.);
+            }
+            CaptionButtonKind::Close => {
+                canvas.stroke(Line::new((x, y), (x + s, y + s)), &self.color, 1.);
+                canvas.stroke(Line::new((x + s, y), (x, y + s)), &self.color, 1.);
+            }
+        }
+    }
+}
+
+impl HitTest for CaptionButton {
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        if let PointerEvent::PointerUp(e) = event {
+            if e.0.button == MouseButton::Left && cx.layout_box().contains(e.0.pos) {
+                match self.kind {
+                    CaptionButtonKind::Minimize => Window::minimize(),
+                    CaptionButtonKind::Maximize => Window::toggle_maximized(),
+                    CaptionButtonKind::Close => Window::request_close(),
+                }
+            }
+        }
+    }
+}
+
+/// Makes edges of a frameless window resizable. Place it at the root of the
+/// widget tree, so that `border` wide regions along its edges match the edges
+/// of the window.
+#[derive(RenderWidget, Builder)]
+pub struct WindowResizeArea<W: Widget> {
+    pub child: W,
+    /// Width of the resize regions.
+    pub border: f64,
+    pub min_size: Size,
+}
+
+impl WindowResizeArea<()> {
+    pub fn builder() -> Self {
+        WindowResizeArea {
+            child: (),
+            border: 6.,
+            min_size: Size::new(100., 50.),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Edges {
+    left: bool,
+    top: bool,
+    right: bool,
+    bottom: bool,
+}
+
+impl Edges {
+    fn any(&self) -> bool {
+        self.left || self.top || self.right || self.bottom
+    }
+
+    fn cursor(&self) -> Cursor {
+        match (self.left || self.right, self.top || self.bottom) {
+            (false, true) => Cursor::ResizeUpDown,
+            _ => Cursor::ResizeLeftRight,
+        }
+    }
+}
+
+/// Resize in progress.
+#[derive(Debug, Clone, Copy)]
+pub struct ResizeDrag {
+    edges: Edges,
+    /// Pointer position in screen coordinates.
+    start_pointer: Point,
+    start_position: Point,
+    start_size: Size,
+}
+
+impl<W: Widget> RenderState for WindowResizeArea<W> {
+    type State = Option<ResizeDrag>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl<W: Widget> WindowResizeArea<W> {
+    fn edges_at(&self, pos: Point, size: Size) -> Edges {
+        Edges {
+            left: pos.x < self.border,
+            top: pos.y < self.border,
+            right: pos.x > size.width - self.border,
+            bottom: pos.y > size.height - self.border,
+        }
+    }
+
+    fn resize(&self, drag: &ResizeDrag, pointer: Point) {
+        let (dx, dy) = (
+            pointer.x - drag.start_pointer.x,
+            pointer.y - drag.start_pointer.y,
+        );
+
+        let mut position = drag.start_position;
+        let mut width = drag.start_size.width;
+        let mut height = drag.start_size.height;
+
+        if drag.edges.left {
+            let dx = dx.min(width - self.min_size.width);
+            position.x += dx;
+            width -= dx;
+        } else if drag.edges.right {
+            width = (width + dx).max(self.min_size.width);
+        }
+
+        if drag.edges.top {
+            let dy = dy.min(height - self.min_size.height);
+            position.y += dy;
+            height -= dy;
+        } else if drag.edges.bottom {
+            height = (height + dy).max(self.min_size.height);
+        }
+
+        if drag.edges.left || drag.edges.top {
+            Window::set_position(position);
+        }
+
+        Window::set_size(Size::new(width, height));
+    }
+}
+
+impl<W: Widget> RenderWidget for WindowResizeArea<W> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![&self.child]
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        cx.child(0).layout(constraints)
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        cx.child(0).paint(canvas, offset)
+    }
+}
+
+impl<W: Widget> HitTest for WindowResizeArea<W> {
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        // Pointer position relative to the screen.
+        let screen_pos = |pos: Point| {
+            let window = Window::position().unwrap_or_default();
+            Point::new(window.x + pos.x, window.y + pos.y)
+        };
+
+        match event {
+            PointerEvent::PointerDown(e) if e.0.button == MouseButton::Left => {
+                let edges = self.edges_at(e.0.pos, cx.layout_box());
+
+                if let (true, Some(position), Some(size)) =
+                    (edges.any(), Window::position(), Window::size())
+                {
+                    *cx.render_state_mut() = Some(ResizeDrag {
+                        edges,
+                        start_pointer: screen_pos(e.0.pos),
+                        start_position: position,
+                        start_size: size,
+                    });
+
+                    // Keep resizing even if the pointer leaves this widget.
+                    cx.capture_pointer();
+                }
+            }
+            PointerEvent::PointerMove(e) => {
+                let drag = *cx.render_state();
+
+                match drag {
+                    Some(drag) => {
+                        Window::set_cursor(&drag.edges.cursor());
+                        self.resize(&drag, screen_pos(e.0.pos));
+                    }
+                    None => {
+                        let edges = self.edges_at(e.0.pos, cx.layout_box());
+
+                        if edges.any() {
+                            Window::set_cursor(&edges.cursor());
+                        }
+                    }
+                }
+            }
+            PointerEvent::PointerUp(_) => *cx.render_state_mut() = None,
+            _ => {}
+        }
+    }
+}
@@ -109,6 +109,7 @@ mod test {
     use frui::render::*;
 
     static COUNT: std::sync::Mutex<isize> = std::sync::Mutex::new(0);
+    static PRESSED: std::sync::Mutex<isize> = std::sync::Mutex::new(0);
 
     #[derive(ViewWidget)]
     pub struct OnlyButtons;
@@ -160,6 +161,53 @@ mod test {
         assert_eq!(*COUNT.lock().unwrap(), 0);
     }
 
+    #[derive(ViewWidget)]
+    pub struct ThemedButtons;
+
+    impl WidgetState for ThemedButtons {
+        type State = isize;
+
+        fn create_state(&self) -> Self::State {
+            0
+        }
+    }
+
+    impl ViewWidget for ThemedButtons {
+        fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            *PRESSED.lock().unwrap() = *cx.state();
+
+            UnconstrainedBox {
+                child: Row::builder().space_between(10.0).children((
+                    frui::prelude::Button::builder()
+                        .on_pressed(|| *cx.state_mut() += 1)
+                        .child(()),
+                    // Disabled, since it has no `on_pressed`.
+                    frui::prelude::Button::builder().child(()),
+                )),
+            }
+        }
+    }
+
+    #[test]
+    pub fn themed_button_is_pressed_unless_disabled() {
+        let mut runner = MiriRunner::new(ThemedButtons);
+
+        runner.size(frui::druid_shell::kurbo::Size {
+            width: 500.,
+            height: 400.,
+        });
+
+        // Buttons are 64 pixels wide by default.
+        for x in [20.0, 100.0] {
+            runner.mouse_move(&mdef(Point::new(x, 20.0)));
+            runner.mouse_down(&mdef(Point::new(x, 20.0)));
+            runner.mouse_up(&mdef(Point::new(x, 20.0)));
+            runner.update(false);
+        }
+
+        assert_eq!(*PRESSED.lock().unwrap(), 1);
+    }
+
     fn click_plus(runner: &mut MiriRunner) {
         let (x, y) = (20.0, 20.0);
 
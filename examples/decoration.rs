@@ -48,3 +48,49 @@ fn main() {
             )),
     });
 }
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::{runner::miri::MiriRunner, test_ids::TestIds},
+        render::*,
+    };
+
+    #[derive(ViewWidget)]
+    struct BorderedBox;
+
+    impl ViewWidget for BorderedBox {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            let border = BoxBorder::symmetric(
+                BorderSide::solid(Color::BLACK, 4.),
+                BorderSide::solid(Color::BLACK, 1.),
+            );
+
+            Align::builder().alignment(Alignment::TOP_LEFT).child(
+                Container::builder()
+                    .decoration(BoxDecoration::builder().border(border))
+                    .child(
+                        Container::builder()
+                            .width(40.)
+                            .height(20.)
+                            .child(())
+                            .test_id("child"),
+                    )
+                    .test_id("container"),
+            )
+        }
+    }
+
+    #[test]
+    pub fn container_insets_child_by_border_widths() {
+        let mut runner = MiriRunner::new(BorderedBox);
+        runner.update(true);
+
+        let container = TestIds::find("container").unwrap();
+        let child = TestIds::find("child").unwrap();
+
+        assert_eq!(container, Rect::from_ltwh(0., 0., 48., 22.));
+        assert_eq!(child, Rect::from_ltwh(4., 1., 40., 20.));
+    }
+}
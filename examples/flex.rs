@@ -74,3 +74,129 @@ fn main() {
         child: App,
     });
 }
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+        render::*,
+    };
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+    };
+
+    thread_local! {
+        static SIZES: RefCell<HashMap<usize, Size>> = Default::default();
+        static PAINTED: RefCell<HashSet<usize>> = Default::default();
+    }
+
+    /// Records its size and whether it was painted.
+    #[derive(RenderWidget)]
+    struct Probe {
+        id: usize,
+        width: f64,
+    }
+
+    impl RenderWidget for Probe {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![] as Vec<()>
+        }
+
+        fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+            let size = constraints.constrain(Size::new(self.width, 20.));
+            SIZES.with(|s| s.borrow_mut().insert(self.id, size));
+            size
+        }
+
+        fn paint(&self, _: &mut PaintCx<Self>, _: &mut Canvas, _: &Offset) {
+            PAINTED.with(|p| p.borrow_mut().insert(self.id));
+        }
+    }
+
+    fn width(id: usize) -> Option<f64> {
+        SIZES.with(|s| s.borrow().get(&id).map(|s| s.width))
+    }
+
+    #[derive(ViewWidget)]
+    struct FlexFactors;
+
+    impl WidgetState for FlexFactors {
+        type State = usize;
+
+        fn create_state(&self) -> Self::State {
+            1
+        }
+    }
+
+    impl ViewWidget for FlexFactors {
+        fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            KeyboardEventDetector {
+                on_event: |_| *cx.state_mut() = 3,
+                child: UnconstrainedBox {
+                    child: SizedBox::from_size(
+                        Row::builder().children((
+                            Flexible {
+                                flex: *cx.state(),
+                                fit: FlexFit::Tight,
+                                child: Probe { id: 0, width: 0. },
+                            },
+                            Expanded::new(Probe { id: 1, width: 0. }),
+                        )),
+                        Size::new(400., 20.),
+                    ),
+                },
+            }
+        }
+    }
+
+    #[test]
+    pub fn changed_flex_factor_is_laid_out() {
+        let mut runner = MiriRunner::new(FlexFactors);
+        runner.update(true);
+        assert_eq!((width(0), width(1)), (Some(200.), Some(200.)));
+
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+        runner.update(true);
+
+        // Parent data of the updated `Flexible` was replaced.
+        assert_eq!((width(0), width(1)), (Some(300.), Some(100.)));
+    }
+
+    #[derive(ViewWidget)]
+    struct Overflowing;
+
+    impl ViewWidget for Overflowing {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            UnconstrainedBox {
+                child: SizedBox::from_size(
+                    Row::builder().children((
+                        Probe { id: 10, width: 80. },
+                        Probe { id: 11, width: 80. },
+                        Probe { id: 12, width: 80. },
+                    )),
+                    Size::new(100., 20.),
+                ),
+            }
+        }
+    }
+
+    #[test]
+    pub fn overflowing_children_are_clipped() {
+        let mut runner = MiriRunner::new(Overflowing);
+        runner.update(true);
+
+        // Children keep their sizes, but the last one lies entirely past the
+        // clipped edge of the row.
+        assert_eq!(width(12), Some(80.));
+
+        let painted = PAINTED.with(|p| p.borrow().clone());
+        assert!(painted.contains(&10) && painted.contains(&11));
+        assert!(!painted.contains(&12));
+    }
+}
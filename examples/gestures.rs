@@ -0,0 +1,131 @@
+//! This example shows how nested [`GestureDetector`]s compete for a tap. Only
+//! the inner detector recognizes taps on the inner box, while taps on the
+//! rest of the outer box are recognized by the outer detector.
+//!
+//! The inner detector is labeled, so it can also be focused with Tab and
+//! tapped with Enter or Space.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Taps {
+    inner: usize,
+    outer: usize,
+}
+
+impl WidgetState for App {
+    type State = Taps;
+
+    fn create_state(&self) -> Self::State {
+        Taps::default()
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        #[cfg(all(test, feature = "miri"))]
+        test::record(*cx.state());
+
+        Stack::builder().children((
+            Text::new(format!("{:#?}", cx.state())),
+            Center::child(
+                GestureDetector::builder()
+                    .on_tap(|| cx.state_mut().outer += 1)
+                    .child(
+                        Container::builder()
+                            .width(200.)
+                            .height(200.)
+                            .color(Color::SILVER)
+                            .child(Center::child(
+                                GestureDetector::builder()
+                                    .on_tap(|| cx.state_mut().inner += 1)
+                                    .child(
+                                        Container::builder()
+                                            .width(100.)
+                                            .height(100.)
+                                            .color(Color::GRAY)
+                                            .child(()),
+                                    )
+                                    .interaction_label("Inner box"),
+                            )),
+                    ),
+            ),
+        ))
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{
+            keyboard_types::Key, KeyEvent, Modifiers, MouseButton, MouseButtons, MouseEvent,
+        },
+        render::*,
+    };
+
+    thread_local! {
+        static TAPS: std::cell::Cell<Taps> = Default::default();
+    }
+
+    pub fn record(taps: Taps) {
+        TAPS.with(|t| t.set(taps));
+    }
+
+    fn taps() -> Taps {
+        TAPS.with(|t| t.get())
+    }
+
+    #[test]
+    pub fn nested_detectors_recognize_one_tap() {
+        let mut runner = MiriRunner::new(App);
+
+        // Center of the window, which is 500x400 by default.
+        click(&mut runner, Point::new(250., 200.));
+        assert_eq!(taps(), Taps { inner: 1, outer: 0 });
+
+        // Inside of the outer box, outside of the inner one.
+        click(&mut runner, Point::new(170., 120.));
+        assert_eq!(taps(), Taps { inner: 1, outer: 1 });
+    }
+
+    #[test]
+    pub fn labeled_detector_is_activated_by_keyboard() {
+        let mut runner = MiriRunner::new(App);
+
+        for key in [Key::Tab, Key::Enter] {
+            runner.key_down(KeyEvent::for_test(Modifiers::default(), key));
+            runner.update(true);
+        }
+
+        assert_eq!(taps(), Taps { inner: 1, outer: 0 });
+    }
+
+    fn click(runner: &mut MiriRunner, pos: Point) {
+        let mut event = MouseEvent {
+            pos,
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+
+        runner.mouse_move(&event);
+        runner.mouse_down(&event);
+        event.buttons = MouseButtons::new();
+        runner.mouse_up(&event);
+        runner.update(true);
+    }
+}
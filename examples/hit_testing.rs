@@ -0,0 +1,164 @@
+//! This example shows how pointer events find the widget they hit. Layers
+//! painted by [`Layers`] overlap, so the layer painted last (on top) is hit.
+//! Layers are painted through transformations of the canvas, which hit
+//! testing follows, so they are hit where they are painted.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+use frui::render::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    /// Name of the last layer hit by the pointer.
+    type State = Option<&'static str>;
+
+    fn create_state(&self) -> Self::State {
+        None
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        #[cfg(all(test, feature = "miri"))]
+        test::record(*cx.state());
+
+        let layer = move |name: &'static str, color: Color| {
+            PointerListener::builder()
+                .on_pointer_down(move |_| *cx.state_mut() = Some(name))
+                .child(
+                    Container::builder()
+                        .width(100.)
+                        .height(100.)
+                        .color(color)
+                        .child(()),
+                )
+        };
+
+        Layers {
+            children: (
+                Text::new(format!("Hit: {}", cx.state().unwrap_or("nothing"))),
+                layer("bottom", Color::GRAY),
+                layer("top", Color::SILVER),
+                layer("scaled", Color::AQUA),
+            ),
+            transforms: vec![
+                Affine::translate((0., 300.)),
+                Affine::IDENTITY,
+                Affine::translate((50., 50.)),
+                Affine::translate((300., 0.)) * Affine::scale(2.),
+            ],
+        }
+    }
+}
+
+/// Paints each of `children` at the top left corner, transformed by the
+/// respective transformation. It doesn't implement [`HitTest`], so children
+/// are hit tested by the default implementation.
+#[derive(RenderWidget)]
+struct Layers<WL: WidgetList> {
+    children: WL,
+    transforms: Vec<Affine>,
+}
+
+impl<WL: WidgetList> RenderWidget for Layers<WL> {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        self.children.get()
+    }
+
+    fn layout(&self, cx: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        for child in cx.children() {
+            child.layout(constraints.loosen());
+        }
+
+        constraints.biggest()
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        for (mut child, transform) in cx.children().zip(&self.transforms) {
+            let r = canvas.with_save(|cv| {
+                cv.transform(
+                    Affine::translate((offset.x, offset.y))
+                        * *transform
+                        * Affine::translate((-offset.x, -offset.y)),
+                );
+                child.paint(cv, offset);
+
+                Ok(())
+            });
+
+            r.unwrap();
+        }
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{Modifiers, MouseButton, MouseButtons, MouseEvent},
+    };
+    use std::cell::Cell;
+
+    thread_local! {
+        static HIT: Cell<Option<&'static str>> = Cell::new(None);
+    }
+
+    pub fn record(hit: Option<&'static str>) {
+        HIT.with(|h| h.set(hit));
+    }
+
+    fn hit(runner: &mut MiriRunner, pos: Point) -> Option<&'static str> {
+        record(None);
+
+        let mut event = MouseEvent {
+            pos,
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+
+        runner.mouse_move(&event);
+        runner.mouse_down(&event);
+        event.buttons = MouseButtons::new();
+        runner.mouse_up(&event);
+        runner.update(true);
+
+        HIT.with(|h| h.get())
+    }
+
+    #[test]
+    pub fn topmost_of_overlapping_siblings_is_hit() {
+        let mut runner = MiriRunner::new(App);
+
+        // Only the bottom layer is painted there.
+        assert_eq!(hit(&mut runner, Point::new(25., 25.)), Some("bottom"));
+
+        // Both layers are painted there.
+        assert_eq!(hit(&mut runner, Point::new(75., 75.)), Some("top"));
+        assert_eq!(hit(&mut runner, Point::new(140., 140.)), Some("top"));
+    }
+
+    #[test]
+    pub fn transformed_children_are_hit_where_painted() {
+        let mut runner = MiriRunner::new(App);
+
+        // Scaled layer is painted from (300, 0) to (500, 200), while it was
+        // laid out from (0, 0) to (100, 100).
+        assert_eq!(hit(&mut runner, Point::new(450., 150.)), Some("scaled"));
+        assert_eq!(hit(&mut runner, Point::new(250., 150.)), None);
+
+        // Translated layer isn't hit where it was laid out.
+        assert_eq!(hit(&mut runner, Point::new(10., 10.)), Some("bottom"));
+    }
+}
@@ -0,0 +1,145 @@
+//! This example shows implicitly animated widgets. Pressing any key moves the
+//! box to the opposite corner while resizing and recoloring it, all of which
+//! is animated by [`AnimatedAlign`] and [`AnimatedContainer`].
+
+#![feature(type_alias_impl_trait)]
+
+use std::time::Duration;
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App<W: Widget> {
+    duration: Duration,
+    child: W,
+}
+
+impl<W: Widget> WidgetState for App<W> {
+    /// Whether the box was moved to the bottom right corner.
+    type State = bool;
+
+    fn create_state(&self) -> Self::State {
+        false
+    }
+}
+
+impl<W: Widget> ViewWidget for App<W> {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let moved = *cx.state();
+
+        let (alignment, width, color) = match moved {
+            false => (Alignment::TOP_LEFT, 100., Color::rgb8(66, 133, 244)),
+            true => (Alignment::BOTTOM_RIGHT, 200., Color::rgb8(219, 68, 55)),
+        };
+
+        KeyboardEventDetector {
+            on_event: |_| {
+                let mut moved = cx.state_mut();
+                *moved = !*moved;
+            },
+            child: AnimatedAlign::builder()
+                .alignment(alignment)
+                .duration(self.duration)
+                .curve(Curve::EaseInOut)
+                .child(
+                    AnimatedContainer::builder()
+                        .width(width)
+                        .height(100.)
+                        .color(color)
+                        .duration(self.duration)
+                        .curve(Curve::EaseInOut)
+                        .child(&self.child),
+                ),
+        }
+    }
+}
+
+#[allow(unused)]
+fn main() {
+    run_app(App {
+        duration: Duration::from_millis(500),
+        child: (),
+    });
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+        render::*,
+    };
+    use std::cell::Cell;
+
+    thread_local! {
+        static PAINTED: Cell<Option<(Offset, Size)>> = Cell::new(None);
+    }
+
+    /// Records where it was last painted and its size.
+    #[derive(RenderWidget)]
+    struct Probe;
+
+    impl RenderWidget for Probe {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+            vec![] as Vec<()>
+        }
+
+        fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+            constraints.biggest()
+        }
+
+        fn paint(&self, cx: &mut PaintCx<Self>, _: &mut Canvas, offset: &Offset) {
+            PAINTED.with(|p| p.set(Some((*offset, cx.size()))));
+        }
+    }
+
+    fn painted() -> (Offset, Size) {
+        PAINTED.with(|p| p.get()).expect("probe wasn't painted")
+    }
+
+    fn press_key(runner: &mut MiriRunner) {
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+    }
+
+    #[test]
+    pub fn changes_are_animated() {
+        let mut runner = MiriRunner::new(App {
+            duration: Duration::from_secs(3600),
+            child: Probe,
+        });
+        runner.update(true);
+        assert_eq!(painted(), (Offset::new(0., 0.), Size::new(100., 100.)));
+
+        press_key(&mut runner);
+        runner.update(true);
+        runner.update(true);
+
+        // Neither the position nor the size jump to the new values.
+        let (offset, size) = painted();
+        assert!(offset.x < 300. && offset.y < 300.);
+        assert!(size.width >= 100. && size.width < 200.);
+    }
+
+    #[test]
+    pub fn animations_reach_new_values() {
+        let mut runner = MiriRunner::new(App {
+            duration: Duration::from_millis(1),
+            child: Probe,
+        });
+
+        press_key(&mut runner);
+        runner.update(true);
+
+        std::thread::sleep(Duration::from_millis(20));
+        for _ in 0..3 {
+            runner.update(true);
+        }
+
+        // Window of the runner is 500 by 400.
+        assert_eq!(painted(), (Offset::new(300., 300.), Size::new(200., 100.)));
+    }
+}
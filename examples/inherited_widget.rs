@@ -136,17 +136,105 @@ mod test {
         app::runner::miri::MiriRunner,
         druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
     };
+    use std::{cell::RefCell, collections::HashMap};
+
+    thread_local! {
+        static BUILDS: RefCell<HashMap<&'static str, usize>> = Default::default();
+    }
+
+    fn record_build(label: &'static str) {
+        BUILDS.with(|b| *b.borrow_mut().entry(label).or_default() += 1);
+    }
+
+    fn builds(label: &str) -> usize {
+        BUILDS.with(|b| b.borrow().get(label).copied().unwrap_or(0))
+    }
+
+    fn press_space(runner: &mut MiriRunner) {
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+        runner.update(true);
+    }
 
     #[test]
     pub fn inherited_widget() {
         let mut runner = MiriRunner::new(InheritedSwitch { child: App });
 
         for _ in 0..4 {
-            runner.key_down(KeyEvent::for_test(
-                Modifiers::default(),
-                Key::Character(" ".into()),
-            ));
-            runner.update(true);
+            press_space(&mut runner);
+        }
+    }
+
+    /// Switches the provided text direction on key press. Its child is the
+    /// same configuration on every rebuild, so it isn't rebuilt along with
+    /// this widget.
+    #[derive(ViewWidget)]
+    struct DirectionSwitch<W: Widget> {
+        child: W,
+    }
+
+    impl<W: Widget> WidgetState for DirectionSwitch<W> {
+        type State = TextDirection;
+
+        fn create_state(&self) -> Self::State {
+            TextDirection::Ltr
+        }
+    }
+
+    impl<W: Widget> ViewWidget for DirectionSwitch<W> {
+        fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            KeyboardEventDetector {
+                on_event: |_| {
+                    let mut direction = cx.state_mut();
+
+                    *direction = match *direction {
+                        TextDirection::Ltr => TextDirection::Rtl,
+                        TextDirection::Rtl => TextDirection::Ltr,
+                    };
+                },
+                child: Directionality {
+                    direction: *cx.state(),
+                    child: &self.child,
+                },
+            }
+        }
+    }
+
+    #[derive(ViewWidget)]
+    struct Dependent;
+
+    impl ViewWidget for Dependent {
+        fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            record_build("dependent");
+
+            Text::new(format!("{:?}", cx.depend_on::<TextDirection>().map(|d| *d)))
+        }
+    }
+
+    #[derive(ViewWidget)]
+    struct Independent;
+
+    impl ViewWidget for Independent {
+        fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+            record_build("independent");
+
+            Text::new("independent")
         }
     }
+
+    #[test]
+    pub fn inherited_value_change_rebuilds_only_dependents() {
+        let mut runner = MiriRunner::new(DirectionSwitch {
+            child: Column::builder().children((Dependent, Independent)),
+        });
+        assert_eq!((builds("dependent"), builds("independent")), (1, 1));
+
+        press_space(&mut runner);
+        assert_eq!((builds("dependent"), builds("independent")), (2, 1));
+
+        press_space(&mut runner);
+        assert_eq!((builds("dependent"), builds("independent")), (3, 1));
+    }
 }
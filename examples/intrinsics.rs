@@ -0,0 +1,69 @@
+//! This example shows how [`IntrinsicWidth`] sizes its child to the width the
+//! child asks for: the widest child of a [`Stack`] which isn't positioned,
+//! one run of a [`Wrap`], and the widest cells of every column of a
+//! [`Table`].
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let fill = |width, height| {
+            Container::builder()
+                .width(width)
+                .height(height)
+                .color(Color::SILVER)
+        };
+
+        Column::builder().space_between(10.).children((
+            IntrinsicWidth::builder()
+                .child(Stack::builder().children((
+                    fill(120., 20.),
+                    // Positioned children don't size the stack.
+                    Positioned::builder().left(0.).child(fill(300., 10.)),
+                )))
+                .test_id("stack"),
+            IntrinsicWidth::builder()
+                .child(
+                    Wrap::builder()
+                        .spacing(10.)
+                        .children((fill(40., 10.), fill(50., 20.))),
+                )
+                .test_id("wrap"),
+            IntrinsicWidth::builder()
+                .child(Table::builder().rows(vec![
+                    TableRow::new((fill(30., 10.), fill(60., 10.))),
+                    TableRow::new((fill(50., 10.), fill(10., 10.))),
+                ]))
+                .test_id("table"),
+        ))
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::app::{runner::miri::MiriRunner, test_ids::TestIds};
+
+    #[test]
+    pub fn sizes_children_to_their_intrinsic_width() {
+        let mut runner = MiriRunner::new(App);
+        runner.update(true);
+
+        let size = |id| TestIds::find(id).unwrap().size();
+
+        assert_eq!(size("stack").width, 120.);
+        assert_eq!(size("wrap").width, 100.);
+        // All children of the wrap fit into one run.
+        assert_eq!(size("wrap").height, 20.);
+        assert_eq!(size("table").width, 110.);
+    }
+}
@@ -0,0 +1,115 @@
+//! This example shows how [`ValueKey`]s keep the state of items of a list
+//! when items are inserted before them. Pressing any key inserts a new item
+//! at the top of the list, and every item keeps the number it was created
+//! with.
+
+#![feature(type_alias_impl_trait)]
+
+use std::cell::Cell;
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    /// Identifiers of items, from the top.
+    type State = Vec<u32>;
+
+    fn create_state(&self) -> Self::State {
+        vec![1, 2]
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let items = cx
+            .state()
+            .iter()
+            .map(|&id| LocalKey::new(ValueKey(id), Item { id }))
+            .collect::<Vec<_>>();
+
+        KeyboardEventDetector {
+            on_event: |_| {
+                let mut items = cx.state_mut();
+                let id = items.iter().max().map_or(1, |id| id + 1);
+                items.insert(0, id);
+            },
+            child: Center::child(Column::builder().children(items)),
+        }
+    }
+}
+
+thread_local! {
+    /// Number of item states created so far.
+    static CREATED: Cell<usize> = Cell::new(0);
+}
+
+#[derive(ViewWidget)]
+struct Item {
+    id: u32,
+}
+
+impl WidgetState for Item {
+    /// Order in which the state was created.
+    type State = usize;
+
+    fn create_state(&self) -> Self::State {
+        CREATED.with(|c| {
+            c.set(c.get() + 1);
+            c.get()
+        })
+    }
+}
+
+impl ViewWidget for Item {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        #[cfg(all(test, feature = "miri"))]
+        test::record(self.id, *cx.state());
+
+        Text::new(format!("Item {} (created #{})", self.id, cx.state()))
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+    };
+    use std::{cell::RefCell, collections::HashMap};
+
+    thread_local! {
+        static STATES: RefCell<HashMap<u32, usize>> = Default::default();
+    }
+
+    pub fn record(id: u32, created: usize) {
+        STATES.with(|s| s.borrow_mut().insert(id, created));
+    }
+
+    fn created(id: u32) -> Option<usize> {
+        STATES.with(|s| s.borrow().get(&id).copied())
+    }
+
+    #[test]
+    pub fn inserting_at_front_keeps_state_of_later_items() {
+        let mut runner = MiriRunner::new(App);
+        assert_eq!((created(1), created(2)), (Some(1), Some(2)));
+
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+        runner.update(true);
+
+        // Only the new item created a state.
+        assert_eq!(created(3), Some(3));
+        assert_eq!((created(1), created(2)), (Some(1), Some(2)));
+        assert_eq!(CREATED.with(|c| c.get()), 3);
+    }
+}
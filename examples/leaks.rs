@@ -0,0 +1,124 @@
+//! This example shows how [`LeakDetector`](frui::app::leaks::LeakDetector)
+//! reports listeners which outlive the widget that added them.
+//!
+//! Both [`OffsetListener`]s listen to the same [`ScrollController`], which is
+//! owned by the [`App`]. Pressing any key removes them from the tree. Only one
+//! of them removes its listener when it's unmounted, so the listener of the
+//! other one is logged as a leak.
+
+#![feature(type_alias_impl_trait)]
+
+use std::cell::Cell;
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+struct AppState {
+    controller: ScrollController,
+    show_listeners: bool,
+}
+
+impl WidgetState for App {
+    type State = AppState;
+
+    fn create_state(&self) -> Self::State {
+        AppState {
+            controller: ScrollController::new(),
+            show_listeners: true,
+        }
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let state = cx.state();
+
+        let child = match state.show_listeners {
+            true => Center::child(Column::builder().children((
+                OffsetListener {
+                    controller: state.controller.clone(),
+                    unlisten_on_unmount: true,
+                },
+                OffsetListener {
+                    controller: state.controller.clone(),
+                    unlisten_on_unmount: false,
+                },
+            )))
+            .boxed(),
+            false => Center::child(Text::new("Removed listeners, see the log")).boxed(),
+        };
+
+        KeyboardEventDetector {
+            on_event: |_| cx.state_mut().show_listeners = false,
+            child,
+        }
+    }
+}
+
+#[derive(ViewWidget)]
+struct OffsetListener {
+    controller: ScrollController,
+    unlisten_on_unmount: bool,
+}
+
+impl WidgetState for OffsetListener {
+    type State = Cell<Option<ScrollListenerKey>>;
+
+    fn create_state(&self) -> Self::State {
+        Cell::new(None)
+    }
+
+    fn mount<'a>(&'a self, cx: BuildCx<'a, Self>) {
+        let key = self.controller.listen(|| log::info!("scrolled"));
+        cx.state().set(Some(key));
+    }
+
+    fn unmount<'a>(&'a self, cx: BuildCx<'a, Self>) {
+        if let Some(key) = cx.state().take() {
+            if self.unlisten_on_unmount {
+                self.controller.unlisten(key);
+            }
+        }
+    }
+}
+
+impl ViewWidget for OffsetListener {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        match self.unlisten_on_unmount {
+            true => Text::new("Removes its listener"),
+            false => Text::new("Leaks its listener"),
+        }
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::{leaks::LeakDetector, runner::miri::MiriRunner},
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+    };
+
+    #[test]
+    pub fn reports_listener_outliving_its_widget() {
+        let mut runner = MiriRunner::new(App);
+        assert!(LeakDetector::take_leaks().is_empty());
+
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+        runner.update(true);
+
+        let leaks = LeakDetector::take_leaks();
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].kind, "Listener");
+        assert_eq!(leaks[0].owner, "OffsetListener");
+    }
+}
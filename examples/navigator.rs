@@ -0,0 +1,277 @@
+//! This example shows a [`Navigator`] with a page which opens a dialog. The
+//! dialog is a modal route shown above a barrier, which closes the dialog once
+//! tapped. Every change of the routes is printed by a [`NavigatorObserver`].
+//!
+//! Pressing Escape pops the topmost route, unless a [`WillPopScope`] in it
+//! vetoes. The editor page asks whether to discard its changes that way.
+
+#![feature(type_alias_impl_trait)]
+
+use std::time::Duration;
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        Navigator::new(Route::page(|| Home).named("/")).observer(PrintRoutes)
+    }
+}
+
+#[derive(ViewWidget)]
+struct Home;
+
+impl ViewWidget for Home {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let navigator = Navigator::of(cx).unwrap();
+
+        #[cfg(all(test, feature = "miri"))]
+        test::record_navigator(&navigator);
+
+        let editor = navigator.clone();
+
+        Center::child(
+            Column::builder().children((
+                PointerListener::builder()
+                    .on_pointer_up(move |_| navigator.push(dialog()))
+                    .child(Text::new("Open dialog").test_id("open")),
+                PointerListener::builder()
+                    .on_pointer_up(move |_| editor.push(Route::page(|| Editor).named("/editor")))
+                    .child(Text::new("Open editor").test_id("editor")),
+            )),
+        )
+    }
+}
+
+fn dialog() -> Route {
+    Route::modal(|| Dialog)
+        .named("/dialog")
+        .barrier_color(Color::BLACK.with_alpha(0.3))
+        .barrier_duration(Duration::from_millis(300))
+}
+
+#[derive(ViewWidget)]
+struct Dialog;
+
+impl ViewWidget for Dialog {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        ColoredBox::builder().color(Color::WHITE).child(
+            Padding::builder()
+                .padding(EdgeInsets::all(24.))
+                .child(Text::new("Tap outside of the dialog to close it.").color(Color::BLACK)),
+        )
+    }
+}
+
+#[derive(ViewWidget)]
+struct Editor;
+
+impl ViewWidget for Editor {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let navigator = Navigator::of(cx).unwrap();
+
+        WillPopScope {
+            on_will_pop: move || {
+                navigator.push(Route::modal(|| DiscardDialog).named("/discard"));
+                false
+            },
+            child: Center::child(Text::new("Press Escape to leave the editor.")),
+        }
+    }
+}
+
+#[derive(ViewWidget)]
+struct DiscardDialog;
+
+impl ViewWidget for DiscardDialog {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let navigator = Navigator::of(cx).unwrap();
+
+        ColoredBox::builder().color(Color::WHITE).child(
+            PointerListener::builder()
+                .on_pointer_up(move |_| {
+                    // Pops the dialog and then the editor, which isn't asked
+                    // again when popped directly.
+                    navigator.pop();
+                    navigator.pop();
+                })
+                .child(
+                    Padding::builder()
+                        .padding(EdgeInsets::all(24.))
+                        .child(Text::new("Discard changes").color(Color::BLACK))
+                        .test_id("discard"),
+                ),
+        )
+    }
+}
+
+struct PrintRoutes;
+
+impl NavigatorObserver for PrintRoutes {
+    fn did_push(&self, route: &Route, previous: Option<&Route>) {
+        let previous = previous.map_or("nothing", name);
+        report(format!("push {} above {}", name(route), previous));
+    }
+
+    fn did_pop(&self, route: &Route, previous: Option<&Route>) {
+        let previous = previous.map_or("nothing", name);
+        report(format!("pop {} to {}", name(route), previous));
+    }
+
+    fn did_replace(&self, new: &Route, old: &Route) {
+        report(format!("replace {} with {}", name(old), name(new)));
+    }
+}
+
+fn name(route: &Route) -> &str {
+    route.name().unwrap_or("unnamed route")
+}
+
+fn report(message: String) {
+    #[cfg(all(test, feature = "miri"))]
+    test::record(message.clone());
+
+    println!("{}", message);
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{
+            keyboard_types::Key, KeyEvent, Modifiers, MouseButton, MouseButtons, MouseEvent,
+        },
+    };
+    use std::cell::RefCell;
+
+    thread_local! {
+        static LOG: RefCell<Vec<String>> = Default::default();
+        static NAVIGATOR: RefCell<Option<NavigatorHandle>> = Default::default();
+    }
+
+    pub fn record(message: String) {
+        LOG.with(|log| log.borrow_mut().push(message));
+    }
+
+    pub fn record_navigator(navigator: &NavigatorHandle) {
+        NAVIGATOR.with(|n| *n.borrow_mut() = Some(navigator.clone()));
+    }
+
+    fn log() -> Vec<String> {
+        LOG.with(|log| log.take())
+    }
+
+    fn navigator() -> NavigatorHandle {
+        NAVIGATOR.with(|n| n.borrow().clone().unwrap())
+    }
+
+    fn tap(runner: &mut MiriRunner, pos: Point) {
+        let mut event = MouseEvent {
+            pos,
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+
+        runner.mouse_move(&event);
+        runner.mouse_down(&event);
+        event.buttons = MouseButtons::new();
+        runner.mouse_up(&event);
+        runner.update(true);
+    }
+
+    fn escape(runner: &mut MiriRunner) {
+        runner.key_down(KeyEvent::for_test(Modifiers::default(), Key::Escape));
+        runner.update(true);
+    }
+
+    #[test]
+    pub fn observers_are_notified_about_route_changes() {
+        let mut runner = MiriRunner::new(App);
+        assert_eq!(log(), ["push / above nothing"]);
+
+        assert!(runner.tap("open"));
+        runner.update(true);
+        assert_eq!(log(), ["push /dialog above /"]);
+
+        // Barrier is tapped outside of the centered dialog.
+        tap(&mut runner, Point::new(10., 10.));
+        assert_eq!(log(), ["pop /dialog to /"]);
+
+        navigator().replace(Route::page(|| Home).named("/home"));
+        runner.update(true);
+        assert_eq!(log(), ["replace / with /home"]);
+        assert!(!navigator().can_pop());
+    }
+
+    #[test]
+    pub fn non_dismissible_barrier_keeps_route() {
+        let mut runner = MiriRunner::new(App);
+        log();
+
+        navigator().push(dialog().barrier_dismissible(false));
+        runner.update(true);
+
+        tap(&mut runner, Point::new(10., 10.));
+        assert_eq!(navigator().current().name(), Some("/dialog"));
+
+        assert!(navigator().pop());
+        runner.update(true);
+        assert_eq!(log(), ["push /dialog above /", "pop /dialog to /"]);
+    }
+
+    #[test]
+    pub fn escape_pops_topmost_route() {
+        let mut runner = MiriRunner::new(App);
+        log();
+
+        assert!(runner.tap("open"));
+        escape(&mut runner);
+        assert_eq!(log(), ["push /dialog above /", "pop /dialog to /"]);
+
+        // Initial route isn't popped.
+        escape(&mut runner);
+        assert!(log().is_empty());
+        assert!(!BackDispatcher::dispatch());
+    }
+
+    #[test]
+    pub fn will_pop_scope_vetoes_pop() {
+        let mut runner = MiriRunner::new(App);
+        log();
+
+        assert!(runner.tap("editor"));
+        escape(&mut runner);
+        assert_eq!(
+            log(),
+            ["push /editor above /", "push /discard above /editor"]
+        );
+
+        // Dialog has no scope of its own, so it's popped.
+        assert!(BackDispatcher::dispatch());
+        runner.update(true);
+        assert_eq!(log(), ["pop /discard to /editor"]);
+
+        escape(&mut runner);
+        assert!(runner.tap("discard"));
+        runner.update(true);
+        assert_eq!(
+            log(),
+            [
+                "push /discard above /editor",
+                "pop /discard to /editor",
+                "pop /editor to /"
+            ]
+        );
+    }
+}
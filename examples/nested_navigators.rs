@@ -0,0 +1,151 @@
+//! This example shows tabs with their own [`Navigator`]s, nested in the
+//! navigator of the application. Routes of tabs are kept in their
+//! [`NavigatorKey`]s, so tabs keep their history when switching between them.
+//! Dialogs are pushed to the navigator of the application, above all tabs.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        Navigator::new(Route::page(|| Tabs)).key(NavigatorKey::named("app"))
+    }
+}
+
+#[derive(ViewWidget)]
+struct Tabs;
+
+impl WidgetState for Tabs {
+    /// Name of the selected tab.
+    type State = &'static str;
+
+    fn create_state(&self) -> Self::State {
+        "feed"
+    }
+}
+
+impl ViewWidget for Tabs {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let tab = *cx.state();
+
+        let tab_button = move |name: &'static str| {
+            PointerListener::builder()
+                .on_pointer_up(move |_| *cx.state_mut() = name)
+                .child(
+                    Padding::builder()
+                        .padding(EdgeInsets::all(8.))
+                        .child(Text::new(name).test_id(name)),
+                )
+        };
+
+        Column::builder().children((
+            Row::builder().children((tab_button("feed"), tab_button("inbox"))),
+            Expanded::new(Navigator::new(page(tab, 0)).key(NavigatorKey::named(tab))),
+        ))
+    }
+}
+
+fn page(tab: &'static str, depth: usize) -> Route {
+    Route::page(move || Page { tab, depth }).named(format!("/{}/{}", tab, depth))
+}
+
+#[derive(ViewWidget)]
+struct Page {
+    tab: &'static str,
+    depth: usize,
+}
+
+impl ViewWidget for Page {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        #[cfg(all(test, feature = "miri"))]
+        test::record(self.tab, self.depth);
+
+        let navigator = Navigator::of(cx).unwrap();
+        let root = navigator.root();
+
+        Column::builder().children((
+            Text::new(format!("Page {} of {}", self.depth, self.tab)),
+            PointerListener::builder()
+                .on_pointer_up(move |_| navigator.push(page(self.tab, self.depth + 1)))
+                .child(Text::new("Open next page").test_id("next")),
+            PointerListener::builder()
+                .on_pointer_up(move |_| root.push(Route::modal(|| Text::new("Above all tabs"))))
+                .child(Text::new("Open dialog").test_id("dialog")),
+        ))
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::app::runner::miri::MiriRunner;
+    use std::cell::Cell;
+
+    thread_local! {
+        static LAST_BUILT: Cell<Option<(&'static str, usize)>> = Cell::new(None);
+    }
+
+    pub fn record(tab: &'static str, depth: usize) {
+        LAST_BUILT.with(|p| p.set(Some((tab, depth))));
+    }
+
+    fn last_built() -> Option<(&'static str, usize)> {
+        LAST_BUILT.with(|p| p.get())
+    }
+
+    fn current(key: &str) -> String {
+        let navigator = NavigatorKey::named(key).current().unwrap();
+        navigator.current().name().unwrap().to_owned()
+    }
+
+    fn tap(runner: &mut MiriRunner, test_id: &str) {
+        assert!(runner.tap(test_id));
+        runner.update(true);
+    }
+
+    #[test]
+    pub fn tabs_keep_their_history() {
+        let mut runner = MiriRunner::new(App);
+
+        tap(&mut runner, "next");
+        assert_eq!(current("feed"), "/feed/1");
+
+        tap(&mut runner, "inbox");
+        assert_eq!(current("inbox"), "/inbox/0");
+        assert_eq!(last_built(), Some(("inbox", 0)));
+
+        tap(&mut runner, "feed");
+        assert_eq!(current("feed"), "/feed/1");
+        assert_eq!(last_built(), Some(("feed", 1)));
+
+        // Routes can be pushed to a tab which isn't shown.
+        let inbox = NavigatorKey::named("inbox").current().unwrap();
+        inbox.push(page("inbox", 1));
+        runner.update(true);
+
+        tap(&mut runner, "inbox");
+        assert_eq!(last_built(), Some(("inbox", 1)));
+    }
+
+    #[test]
+    pub fn nested_navigator_reaches_outer_navigator() {
+        let mut runner = MiriRunner::new(App);
+
+        let app = NavigatorKey::named("app").current().unwrap();
+        let feed = NavigatorKey::named("feed").current().unwrap();
+        assert!(feed.parent() == Some(app.clone()));
+        assert!(feed.root() == app);
+
+        tap(&mut runner, "dialog");
+        assert!(app.can_pop());
+        assert!(!feed.can_pop());
+    }
+}
@@ -0,0 +1,148 @@
+//! This example shows how a widget can lock the pointer to change a value by
+//! dragging. While the bar is dragged, the cursor is hidden and held in place,
+//! so the value can be changed further than the edges of the screen allow.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::{
+    druid_shell::{kurbo::Rect as DruidRect, MouseButton},
+    prelude::*,
+    render::*,
+};
+
+#[derive(ViewWidget)]
+struct App;
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        Center::child(DragValue { width: 200. })
+    }
+}
+
+/// Bar filled according to its value, which is changed by dragging the bar
+/// horizontally.
+#[derive(RenderWidget)]
+struct DragValue {
+    width: f64,
+}
+
+struct DragState {
+    value: f64,
+    /// Position of the last pointer event of the drag in progress.
+    last: Option<Point>,
+}
+
+impl RenderState for DragValue {
+    type State = DragState;
+
+    fn create_state(&self) -> Self::State {
+        DragState {
+            value: 0.5,
+            last: None,
+        }
+    }
+}
+
+impl RenderWidget for DragValue {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Vec<Self::Widget<'w>> {
+        vec![] as Vec<()>
+    }
+
+    fn layout(&self, _: &LayoutCx<Self>, constraints: Constraints) -> Size {
+        constraints.constrain(Size::new(self.width, 20.))
+    }
+
+    fn paint(&self, cx: &mut PaintCx<Self>, canvas: &mut Canvas, offset: &Offset) {
+        let size = cx.layout_box();
+        let filled = size.width * cx.render_state().value;
+
+        let bar = DruidRect::from_origin_size(*offset, (size.width, size.height));
+        canvas.fill(bar, &Color::GRAY);
+        canvas.fill(bar.with_size((filled, size.height)), &Color::SILVER);
+    }
+}
+
+impl HitTest for DragValue {
+    fn handle_event(&self, cx: &mut HitTestCx<Self>, event: &PointerEvent) {
+        match event {
+            PointerEvent::PointerDown(e) if e.0.button == MouseButton::Left => {
+                cx.render_state_mut().last = Some(e.0.pos);
+                cx.lock_pointer();
+            }
+            PointerEvent::PointerMove(e) if cx.has_pointer_lock() => {
+                let mut state = cx.render_state_mut();
+
+                // Dragging by the whole width changes the value from 0 to 1.
+                if let Some(last) = state.last.replace(e.0.pos) {
+                    state.value = (state.value + (e.0.pos.x - last.x) / self.width).clamp(0., 1.);
+                }
+
+                #[cfg(all(test, feature = "miri"))]
+                test::record(state.value);
+            }
+            PointerEvent::PointerUp(_) => cx.render_state_mut().last = None,
+            _ => {}
+        }
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::{
+            runner::miri::{MiriRunner, POINTER},
+            window::Window,
+        },
+        druid_shell::{kurbo::Vec2, Modifiers, MouseButtons, MouseEvent},
+    };
+    use std::cell::Cell;
+
+    thread_local! {
+        static VALUE: Cell<f64> = Cell::new(0.5);
+    }
+
+    pub fn record(value: f64) {
+        VALUE.with(|v| v.set(value));
+    }
+
+    #[test]
+    pub fn locked_pointer_is_held_in_place() {
+        let mut runner = MiriRunner::new(App);
+        runner.update(true);
+
+        // Center of the window, which is 500x400 by default.
+        let center = Point::new(250., 200.);
+        let mut event = MouseEvent {
+            pos: center,
+            buttons: MouseButtons::new().with(MouseButton::Left),
+            mods: Modifiers::empty(),
+            count: 1,
+            focus: false,
+            button: MouseButton::Left,
+            wheel_delta: Vec2::ZERO,
+        };
+
+        runner.mouse_down(&event);
+        assert!(!Window::is_cursor_visible());
+
+        // Pointer is moved back to the center after each move, so every
+        // event moves it from the center.
+        for _ in 0..3 {
+            event.pos = Point::new(center.x + 20., center.y);
+            runner.mouse_move(&event);
+            assert_eq!(*POINTER.lock().unwrap(), Some(center));
+        }
+
+        // Positions reported to the widget kept moving right.
+        assert!((VALUE.with(|v| v.get()) - (0.5 + 60. / 200.)).abs() < 1e-9);
+
+        event.buttons = MouseButtons::new();
+        runner.mouse_up(&event);
+        assert!(Window::is_cursor_visible());
+    }
+}
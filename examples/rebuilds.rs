@@ -0,0 +1,90 @@
+//! This example shows that a state change rebuilds only the widget whose
+//! state changed. Tapping one of the counters rebuilds it, but not the other
+//! one, which [`Instrumentation::on_frame`] reports in the log.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::{app::instrumentation::Instrumentation, prelude::*};
+
+#[derive(ViewWidget)]
+struct App;
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, _: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        Center::child(
+            Row::builder()
+                .space_between(20.)
+                .children((Counter { label: "left" }, Counter { label: "right" })),
+        )
+    }
+}
+
+#[derive(ViewWidget)]
+struct Counter {
+    label: &'static str,
+}
+
+impl WidgetState for Counter {
+    type State = usize;
+
+    fn create_state(&self) -> Self::State {
+        0
+    }
+}
+
+impl ViewWidget for Counter {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        #[cfg(all(test, feature = "miri"))]
+        test::record_build(self.label);
+
+        GestureDetector::builder()
+            .on_tap(|| *cx.state_mut() += 1)
+            .child(Text::new(format!("{}: {}", self.label, cx.state())))
+            .test_id(self.label)
+    }
+}
+
+fn main() {
+    Instrumentation::on_frame(|summary| log::info!("{}", summary));
+
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::app::runner::miri::MiriRunner;
+    use std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        rc::Rc,
+    };
+
+    thread_local! {
+        static BUILDS: RefCell<HashMap<&'static str, usize>> = Default::default();
+    }
+
+    pub fn record_build(label: &'static str) {
+        BUILDS.with(|b| *b.borrow_mut().entry(label).or_default() += 1);
+    }
+
+    fn builds(label: &str) -> usize {
+        BUILDS.with(|b| b.borrow().get(label).copied().unwrap_or(0))
+    }
+
+    #[test]
+    pub fn state_change_doesnt_rebuild_siblings() {
+        let mut runner = MiriRunner::new(App);
+        assert_eq!((builds("left"), builds("right")), (1, 1));
+
+        let dirty = Rc::new(Cell::new(0));
+        let dirty_ = dirty.clone();
+        Instrumentation::on_frame(move |s| dirty_.set(dirty_.get() + s.dirty));
+
+        assert!(runner.tap("left"));
+        runner.update(true);
+
+        assert_eq!((builds("left"), builds("right")), (2, 1));
+        assert_eq!(dirty.get(), 1);
+    }
+}
@@ -0,0 +1,66 @@
+//! This example shows how to restore widget state on the next launch. Press
+//! any key to increment the counter, then close and reopen the application.
+//!
+//! Restoration data is saved whenever the window is minimized or loses focus,
+//! so the counter is restored even if the application is killed.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::{
+    app::restoration::{Restorable, Restoration},
+    prelude::*,
+};
+
+#[derive(ViewWidget)]
+struct Counter;
+
+impl WidgetState for Counter {
+    type State = Restorable<u64>;
+
+    fn create_state(&self) -> Self::State {
+        Restorable::new("counter", 0)
+    }
+}
+
+impl ViewWidget for Counter {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        KeyboardEventDetector {
+            on_event: |_| cx.state_mut().update(|count| *count += 1),
+            child: Center::child(Text::new(cx.state().get().to_string()).size(100.)),
+        }
+    }
+}
+
+#[allow(unused)]
+fn main() {
+    Restoration::enable(std::env::temp_dir().join("frui_restoration_example.txt"));
+    run_app(Counter);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+    };
+
+    #[test]
+    pub fn state_is_saved_once_window_loses_focus() {
+        let path = std::env::temp_dir().join(format!("frui-restoration-{}", std::process::id()));
+        Restoration::enable(&path);
+
+        let mut runner = MiriRunner::new(Counter);
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+        runner.update(true);
+        runner.lost_focus();
+
+        let saved = std::fs::read_to_string(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(saved.unwrap(), "counter=1\n");
+    }
+}
@@ -0,0 +1,103 @@
+//! This example lays out the same widgets in left-to-right and right-to-left
+//! text direction, which can be switched by pressing space.
+//!
+//! Everything below the title should be mirrored when switching direction:
+//! the order of the [`Row`] children, directional alignments and paddings,
+//! the [`Stack`] alignment and the arrow wrapped in [`MatchTextDirection`].
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+mod misc;
+use misc::flex_children as list;
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    type State = TextDirection;
+
+    fn create_state(&self) -> Self::State {
+        TextDirection::Rtl
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let direction = *cx.state();
+
+        KeyboardEventDetector {
+            on_event: |_| {
+                let mut direction = cx.state_mut();
+
+                *direction = match *direction {
+                    TextDirection::Ltr => TextDirection::Rtl,
+                    TextDirection::Rtl => TextDirection::Ltr,
+                };
+            },
+            // Changing the key mounts a new `Directionality`.
+            child: LocalKey::new(
+                direction == TextDirection::Rtl,
+                Directionality {
+                    direction,
+                    child: Column::builder()
+                        .space_between(20.0)
+                        .cross_axis_alignment(CrossAxisAlignment::Stretch)
+                        .children((
+                            Text::new(format!("{:?} (press space to switch)", direction))
+                                .size(20.0),
+                            Row::builder()
+                                .space_between(10.0)
+                                .children(list::inflexible()),
+                            Padding::builder()
+                                .padding(EdgeInsetsDirectional::from_steb(80., 0., 0., 0.))
+                                .child(Text::new("Indented from the start").size(20.0)),
+                            Align::builder()
+                                .alignment(AlignmentDirectional::CENTER_END)
+                                .child(
+                                    MatchTextDirection::builder().child(Text::new("→").size(40.0)),
+                                ),
+                            Container::builder()
+                                .height(100.)
+                                .color(Color::SILVER)
+                                .child(
+                                    Stack::builder()
+                                        .alignment(AlignmentDirectional::CENTER_START)
+                                        .children((Container::builder()
+                                            .width(50.)
+                                            .height(50.)
+                                            .color(Color::TEAL),)),
+                                ),
+                        )),
+                },
+            ),
+        }
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+    };
+
+    #[test]
+    pub fn run_example_under_miri() {
+        let mut runner = MiriRunner::new(App);
+
+        for _ in 0..4 {
+            runner.key_down(KeyEvent::for_test(
+                Modifiers::default(),
+                Key::Character(" ".into()),
+            ));
+            runner.update(true);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! This example shows how to bind keys to intents with [`Shortcuts`] and
+//! handle those intents with [`Actions`]. Pressing Ctrl+S saves (counts) the
+//! document, whether the focusable text below is focused or not.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::prelude::*;
+
+struct Save;
+
+impl Intent for Save {}
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    /// Number of saves.
+    type State = usize;
+
+    fn create_state(&self) -> Self::State {
+        0
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        #[cfg(all(test, feature = "miri"))]
+        test::record(*cx.state());
+
+        let updater = cx.state_updater();
+
+        Actions::builder()
+            .action(move |_: &Save| updater.update(|saves: &mut usize| *saves += 1))
+            .child(
+                Shortcuts::builder()
+                    .shortcut("Ctrl+S", Save)
+                    .child(Column::builder().children((
+                        Text::new(format!("Saved {} times", cx.state())),
+                        Focus::builder().child(Text::new("Focus me with Tab")),
+                    ))),
+            )
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+    };
+
+    thread_local! {
+        static SAVES: std::cell::Cell<usize> = Default::default();
+    }
+
+    pub fn record(saves: usize) {
+        SAVES.with(|s| s.set(saves));
+    }
+
+    fn press(runner: &mut MiriRunner, mods: Modifiers, key: Key) {
+        runner.key_down(KeyEvent::for_test(mods, key));
+        runner.update(true);
+    }
+
+    #[test]
+    pub fn shortcut_reaches_actions() {
+        let mut runner = MiriRunner::new(App);
+
+        // Nothing is focused.
+        press(&mut runner, Modifiers::CONTROL, Key::Character("s".into()));
+        assert_eq!(SAVES.with(|s| s.get()), 1);
+
+        // Focus is within the shortcuts.
+        press(&mut runner, Modifiers::empty(), Key::Tab);
+        press(&mut runner, Modifiers::CONTROL, Key::Character("s".into()));
+        assert_eq!(SAVES.with(|s| s.get()), 2);
+    }
+}
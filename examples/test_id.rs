@@ -0,0 +1,89 @@
+//! This example shows how [`TestId`]s locate widgets. Bounds of each
+//! identified widget are listed in the log after every key press, which also
+//! hides the scaled box.
+
+#![feature(type_alias_impl_trait)]
+
+use frui::{app::test_ids::TestIds, prelude::*};
+
+#[derive(ViewWidget)]
+struct App;
+
+impl WidgetState for App {
+    /// Whether the scaled box is shown.
+    type State = bool;
+
+    fn create_state(&self) -> Self::State {
+        true
+    }
+}
+
+impl ViewWidget for App {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let square = |color| Container::builder().width(50.).height(50.).color(color);
+
+        let scaled = match *cx.state() {
+            true => Transform::scale(2.)
+                .child(square(Color::TEAL).test_id("scaled"))
+                .boxed(),
+            false => ().boxed(),
+        };
+
+        KeyboardEventDetector {
+            on_event: |_| {
+                for (id, rect) in TestIds::all() {
+                    log::info!("{}: {:?}", id, rect);
+                }
+
+                let mut shown = cx.state_mut();
+                *shown = !*shown;
+            },
+            child: Center::child(
+                Row::builder()
+                    .space_between(100.)
+                    .children((square(Color::SILVER).test_id("plain"), scaled)),
+            ),
+        }
+    }
+}
+
+fn main() {
+    run_app(App);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+    use frui::{
+        app::runner::miri::MiriRunner,
+        druid_shell::{keyboard_types::Key, KeyEvent, Modifiers},
+    };
+
+    #[test]
+    pub fn bounds_include_transform() {
+        let mut runner = MiriRunner::new(App);
+        runner.update(true);
+
+        assert_eq!(TestIds::find("plain").unwrap().size(), Size::new(50., 50.));
+        assert_eq!(
+            TestIds::find("scaled").unwrap().size(),
+            Size::new(100., 100.)
+        );
+    }
+
+    #[test]
+    pub fn unmounted_widgets_are_unregistered() {
+        let mut runner = MiriRunner::new(App);
+        runner.update(true);
+        assert!(TestIds::find("scaled").is_some());
+
+        runner.key_down(KeyEvent::for_test(
+            Modifiers::default(),
+            Key::Character(" ".into()),
+        ));
+        runner.update(true);
+
+        assert!(TestIds::find("scaled").is_none());
+        assert!(TestIds::find("plain").is_some());
+    }
+}
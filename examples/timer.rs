@@ -0,0 +1,93 @@
+//! This example shows how to use timers owned by a widget. The timer is
+//! cancelled automatically once [`Stopwatch`] is removed from the tree.
+
+#![feature(type_alias_impl_trait)]
+
+use std::time::Duration;
+
+use frui::prelude::*;
+
+#[derive(ViewWidget)]
+pub struct Stopwatch;
+
+impl WidgetState for Stopwatch {
+    /// Elapsed tenths of a second.
+    type State = u64;
+
+    fn create_state(&self) -> Self::State {
+        0
+    }
+
+    fn mount(&self, cx: BuildCx<Self>) {
+        cx.timer_periodic(Duration::from_millis(100), |elapsed| *elapsed += 1);
+    }
+}
+
+impl ViewWidget for Stopwatch {
+    fn build<'w>(&'w self, cx: BuildCx<'w, Self>) -> Self::Widget<'w> {
+        let elapsed = *cx.state();
+        let text = format!("{}.{}s", elapsed / 10, elapsed % 10);
+
+        #[cfg(all(test, feature = "miri"))]
+        test::record_text(&text);
+
+        Center::child(Text::new(text).size(100.).weight(FontWeight::BOLD))
+    }
+}
+
+#[allow(unused)]
+fn main() {
+    run_app(Stopwatch);
+}
+
+#[cfg(all(test, feature = "miri"))]
+mod test {
+    use super::*;
+
+    use frui::app::{runner::miri::MiriRunner, timer::Timer};
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    thread_local! {
+        static TEXT: RefCell<String> = Default::default();
+    }
+
+    pub fn record_text(text: &str) {
+        TEXT.with(|t| *t.borrow_mut() = text.to_owned());
+    }
+
+    fn text() -> String {
+        TEXT.with(|t| t.borrow().clone())
+    }
+
+    #[test]
+    pub fn stopwatch_ticks_every_tenth_of_second() {
+        let mut runner = MiriRunner::new(Stopwatch);
+        assert_eq!(text(), "0.0s");
+
+        runner.advance_time(Duration::from_millis(50));
+        assert_eq!(text(), "0.0s");
+
+        runner.advance_time(Duration::from_millis(50));
+        assert_eq!(text(), "0.1s");
+
+        for _ in 0..10 {
+            runner.advance_time(Duration::from_millis(100));
+        }
+        assert_eq!(text(), "1.1s");
+    }
+
+    #[test]
+    pub fn timers_created_before_window_connects_fire() {
+        let fired = Rc::new(Cell::new(false));
+        let fired_ = fired.clone();
+        Timer::once(Duration::from_millis(10), move || fired_.set(true));
+
+        let mut runner = MiriRunner::new(Stopwatch);
+        runner.advance_time(Duration::from_millis(10));
+
+        assert!(fired.get());
+    }
+}